@@ -1,42 +1,238 @@
 // Common types and utilities for runtime
 
+use crate::error_display::{format_caught, read_local_source};
 use crate::module_builder::ModuleBuilder;
-use rquickjs::CaughtError;
+use deno_terminal::colors;
+use rquickjs::function::{Opt, This};
+use rquickjs::{
+    AsyncRuntime, CatchResultExt, CaughtError, Ctx, Exception, Function, Promise, Value,
+};
 use std::error::Error;
+use std::sync::OnceLock;
+use utils::{add_internal_function, quickjs_version};
+
+static SEED: OnceLock<u64> = OnceLock::new();
+
+/// Seed `Math.random`, `crypto.getRandomValues` and `crypto.randomUUID`
+/// (called from main.rs for --seed)
+pub fn set_seed(seed: u64) {
+    let _ = SEED.set(seed);
+}
 
 #[derive(Debug, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
 pub struct BytecodeBundle {
+    /// mdeno version that compiled the bundle
+    pub version: String,
+    /// `QuickJS` version that produced the bytecode
+    pub quickjs_version: String,
     pub entry_point: String,
     pub modules: std::collections::HashMap<String, Vec<u8>>,
 }
 
+impl BytecodeBundle {
+    /// Check that the bundle can be run by this runtime
+    ///
+    /// # Errors
+    /// Returns a message asking to recompile if the bundle was produced by an
+    /// incompatible mdeno or `QuickJS` version
+    pub fn check_compatibility(&self) -> Result<(), String> {
+        let runtime_version = env!("CARGO_PKG_VERSION");
+        if !is_semver_compatible(&self.version, runtime_version) {
+            return Err(format!(
+                "This binary was compiled with mdeno {} but you are running {runtime_version}. Please recompile.",
+                self.version
+            ));
+        }
+        let runtime_quickjs = quickjs_version();
+        if self.quickjs_version != runtime_quickjs {
+            return Err(format!(
+                "This binary was compiled with QuickJS {} but you are running QuickJS {runtime_quickjs}. Please recompile.",
+                self.quickjs_version
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Semver compatibility: the leftmost non-zero component must match,
+/// so 1.2.0 runs 1.4.1 bundles but 0.2.1 does not run 0.3.0 bundles
+fn is_semver_compatible(a: &str, b: &str) -> bool {
+    fn parse(version: &str) -> Option<(u64, u64)> {
+        let mut parts = version.split(['.', '-', '+']);
+        Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+    }
+    match (parse(a), parse(b)) {
+        (Some((a_major, a_minor)), Some((b_major, b_minor))) => {
+            a_major == b_major && (a_major != 0 || a_minor == b_minor)
+        }
+        _ => a == b,
+    }
+}
+
 pub(crate) fn setup_extensions(ctx: &rquickjs::Ctx) -> Result<(), Box<dyn Error>> {
     // Build module configuration using default (feature-based)
     let builder = ModuleBuilder::default();
     let (global_attachment, _module_registry) = builder.build();
     global_attachment.attach(ctx)?;
 
+    if let Some(seed) = SEED.get() {
+        setup_seeded_random(ctx, *seed)?;
+    }
+
+    setup_uncaught_reporter(ctx, true)?;
+
     Ok(())
 }
 
-#[allow(clippy::print_stderr)] // Intentional: error handler prints to stderr
-pub(crate) fn handle_error(caught: CaughtError) {
-    match caught {
-        CaughtError::Exception(exception) => {
-            if let Some(message) = exception.message() {
-                eprintln!("Error: {message}");
+/// Install `reportUncaught`, which is called with errors thrown by timer
+/// callbacks and event listeners, and with promise rejections nothing
+/// handled. An uncaught error is first dispatched as an "error" event, and
+/// ends the process unless a listener prevents that or `exit` is false, as
+/// in the REPL.
+pub(crate) fn setup_uncaught_reporter(ctx: &Ctx<'_>, exit: bool) -> rquickjs::Result<()> {
+    add_internal_function!(
+        ctx,
+        "reportUncaught",
+        move |error: Value<'_>, in_promise: Opt<bool>| {
+            let in_promise = in_promise.0.unwrap_or(false);
+            // Rejections have had their "unhandledrejection" event already
+            if !in_promise && dispatch_uncaught(error.ctx(), &error) {
+                return;
+            }
+            let caught = caught_value(error);
+            if in_promise {
+                report_rejection(&caught, &read_local_source);
             } else {
-                eprintln!("Error: Exception (no message)");
+                report_error(&caught, &read_local_source);
             }
-            if let Some(stack) = exception.stack() {
-                eprintln!("{stack}");
+            if exit {
+                std::process::exit(1);
             }
         }
-        CaughtError::Value(value) => {
-            eprintln!("Error: {value:?}");
-        }
-        CaughtError::Error(error) => {
-            eprintln!("Error: {error:?}");
-        }
+    );
+    Ok(())
+}
+
+/// Dispatch an "error" event on globalThis for an uncaught error, returning
+/// true if a listener called `preventDefault()`
+fn dispatch_uncaught<'js>(ctx: &Ctx<'js>, error: &Value<'js>) -> bool {
+    let dispatch: rquickjs::Result<Option<Function>> =
+        ctx.eval("globalThis[Symbol.for('mdeno.internal')].dispatchUncaught");
+    match dispatch {
+        Ok(Some(dispatch)) => dispatch
+            .call::<_, bool>((error.clone(),))
+            .catch(ctx)
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Have `web_events` track promises rejected without a handler. Once pending
+/// jobs have run, those still unhandled get an "unhandledrejection" event,
+/// and are reported as uncaught unless a listener prevents that.
+pub(crate) async fn track_rejections(runtime: &AsyncRuntime) {
+    runtime
+        .set_host_promise_rejection_tracker(Some(Box::new(|ctx, promise, reason, is_handled| {
+            let track: rquickjs::Result<Option<Function>> =
+                ctx.eval("globalThis[Symbol.for('mdeno.internal')].trackRejection");
+            let Ok(Some(track)) = track else {
+                return;
+            };
+            if track
+                .call::<_, ()>((promise, reason, is_handled))
+                .catch(&ctx)
+                .is_err()
+                || is_handled
+            {
+                return;
+            }
+            // Spawned tasks only run once the job queue is empty
+            let ctx_clone = ctx.clone();
+            ctx.spawn(async move {
+                let process: rquickjs::Result<Function> =
+                    ctx_clone.eval("globalThis[Symbol.for('mdeno.internal')].processRejections");
+                if let Err(caught) = process
+                    .and_then(|process| process.call::<_, ()>(()))
+                    .catch(&ctx_clone)
+                {
+                    handle_error(caught);
+                }
+            });
+        })))
+        .await;
+}
+
+/// Replace `Math.random` with a generator seeded by --seed
+fn setup_seeded_random(ctx: &rquickjs::Ctx, seed: u64) -> Result<(), Box<dyn Error>> {
+    let rng = web_crypto::set_seeded_rng(seed);
+    add_internal_function!(ctx, "seededRandom", move || web_crypto::seeded_random(&rng));
+    ctx.eval::<(), _>(
+        "{ const seededRandom = globalThis[Symbol.for('mdeno.internal')].seededRandom; \
+         Math.random = function random() { return seededRandom(); }; }",
+    )?;
+    Ok(())
+}
+
+pub(crate) fn handle_error(caught: CaughtError) {
+    report_error(&caught, &read_local_source);
+}
+
+/// Print an uncaught error; `source` looks up module code for syntax errors
+pub(crate) fn report_error(caught: &CaughtError, source: &dyn Fn(&str) -> Option<String>) {
+    utils::eprint_line!(
+        "{}: Uncaught {}",
+        colors::red_bold("error"),
+        format_caught(caught, source)
+    );
+}
+
+/// Print a promise rejection that nothing handled
+fn report_rejection(caught: &CaughtError, source: &dyn Fn(&str) -> Option<String>) {
+    utils::eprint_line!(
+        "{}: Uncaught (in promise) {}",
+        colors::red_bold("error"),
+        format_caught(caught, source)
+    );
+}
+
+/// Wrap a thrown value, keeping the stack of `Error` objects
+fn caught_value(value: Value<'_>) -> CaughtError<'_> {
+    match value.as_object().cloned().and_then(Exception::from_object) {
+        Some(exception) => CaughtError::Exception(exception),
+        None => CaughtError::Value(value),
+    }
+}
+
+/// Report the error a module's evaluation rejects with and exit, since a
+/// module that throws at the top level settles its promise instead. An
+/// "error" listener can prevent that.
+pub(crate) fn exit_on_rejection<'js>(
+    ctx: &Ctx<'js>,
+    promise: &Promise<'js>,
+    source: impl Fn(&str) -> Option<String> + 'static,
+) -> rquickjs::Result<()> {
+    let on_rejected = Function::new(
+        ctx.clone(),
+        move |ctx: Ctx<'js>, reason: Value<'js>| -> rquickjs::Result<()> {
+            if dispatch_uncaught(&ctx, &reason) {
+                return Ok(());
+            }
+            report_error(&caught_value(reason), &source);
+            std::process::exit(1);
+        },
+    )?;
+    promise.catch()?.call((This(promise.clone()), on_rejected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semver_compatibility() {
+        assert!(is_semver_compatible("1.2.0", "1.4.1"));
+        assert!(is_semver_compatible("0.3.0", "0.3.2"));
+        assert!(!is_semver_compatible("0.3.0", "0.2.1"));
+        assert!(!is_semver_compatible("1.0.0", "2.0.0"));
     }
 }
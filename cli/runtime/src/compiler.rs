@@ -1,11 +1,21 @@
 // Compiler functions for bytecode generation
 
 use crate::common::BytecodeBundle;
-use crate::module_builder::{self, ModuleBuilder};
-use rquickjs::{AsyncContext, AsyncRuntime, CatchResultExt, Module, async_with};
+use crate::error_display::format_caught;
+use crate::module_builder::{self, ModuleBuilder, SourceMapResolver};
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+    ExportAllDeclaration, ExportNamedDeclaration, Expression, ImportDeclaration, ImportExpression,
+};
+use oxc_ast_visit::{Visit, walk};
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+use rquickjs::loader::Resolver;
+use rquickjs::{AsyncContext, AsyncRuntime, CatchResultExt, Ctx, Module, async_with};
 use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
+use utils::quickjs_version;
 
 /// # Errors
 /// Returns an error if compilation fails
@@ -66,30 +76,8 @@ pub fn compile_modules(
             let bc = async_with!(ctx => |ctx| {
                 let module = Module::declare(ctx.clone(), path.clone(), source.clone())
                     .catch(&ctx)
-                    .map_err(|e| {
-                        let mut error_msg = format!("Failed to declare module {path}: ");
-                        match e {
-                            rquickjs::CaughtError::Exception(ex) => {
-                                if let Some(msg) = ex.message() {
-                                    error_msg.push_str(&msg);
-                                } else {
-                                    error_msg.push_str("Unknown exception");
-                                }
-                                if let Some(stack) = ex.stack() {
-                                    error_msg.push_str("\nStack: ");
-                                    error_msg.push_str(&stack);
-                                }
-                            }
-                            rquickjs::CaughtError::Error(err) => {
-                                use std::fmt::Write;
-                                let _ = write!(error_msg, "{err:?}");
-                            }
-                            rquickjs::CaughtError::Value(_) => {
-                                use std::fmt::Write;
-                                let _ = write!(error_msg, "{e:?}");
-                            }
-                        }
-                        error_msg
+                    .map_err(|caught| {
+                        format_caught(&caught, &|file: &str| (file == path).then(|| source.clone()))
                     })?;
                 let bc = module
                     .write(rquickjs::module::WriteOptions::default())
@@ -102,8 +90,17 @@ pub fn compile_modules(
             bytecode_map.insert(path.clone(), bc);
         }
 
+        // Imports the bundler missed would only fail once the binary runs
+        let mut resolver = SourceMapResolver::new(registry.clone(), modules.clone());
+        async_with!(ctx => |ctx| {
+            verify_imports(&ctx, &mut resolver, &modules)
+        })
+        .await?;
+
         // Create bundle with entry point
         let bundle = BytecodeBundle {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            quickjs_version: quickjs_version(),
             entry_point,
             modules: bytecode_map,
         };
@@ -116,3 +113,89 @@ pub fn compile_modules(
         Ok(serialized)
     })
 }
+
+/// Check that every import in `modules` resolves to a built-in, another
+/// module of the map, or a file that can be loaded from disk at runtime
+/// (dynamic imports aren't bundled)
+fn verify_imports(
+    ctx: &Ctx<'_>,
+    resolver: &mut SourceMapResolver,
+    modules: &HashMap<String, String>,
+) -> Result<(), Box<dyn Error>> {
+    let mut missing = Vec::new();
+    for (path, source) in modules {
+        for specifier in import_specifiers(source) {
+            if resolver.resolve(ctx, path, &specifier).is_err()
+                && module_builder::resolve_local_file(path, &specifier).is_none()
+            {
+                missing.push(format!("  \"{specifier}\" imported from {path}"));
+            }
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+    missing.sort();
+    Err(format!("Module not found:\n{}", missing.join("\n")).into())
+}
+
+/// Specifiers of static imports, re-exports and dynamic imports of string literals
+fn import_specifiers(source: &str) -> Vec<String> {
+    let allocator = Allocator::default();
+    let parser_ret = Parser::new(&allocator, source, SourceType::mjs()).parse();
+    let mut collector = ImportCollector::default();
+    collector.visit_program(&parser_ret.program);
+    collector.specifiers
+}
+
+#[derive(Default)]
+struct ImportCollector {
+    specifiers: Vec<String>,
+}
+
+impl<'a> Visit<'a> for ImportCollector {
+    fn visit_import_declaration(&mut self, decl: &ImportDeclaration<'a>) {
+        self.specifiers.push(decl.source.value.to_string());
+    }
+
+    fn visit_export_named_declaration(&mut self, decl: &ExportNamedDeclaration<'a>) {
+        if let Some(source) = &decl.source {
+            self.specifiers.push(source.value.to_string());
+        }
+        walk::walk_export_named_declaration(self, decl);
+    }
+
+    fn visit_export_all_declaration(&mut self, decl: &ExportAllDeclaration<'a>) {
+        self.specifiers.push(decl.source.value.to_string());
+    }
+
+    fn visit_import_expression(&mut self, expr: &ImportExpression<'a>) {
+        // Computed specifiers can't be checked ahead of time
+        if let Expression::StringLiteral(literal) = &expr.source {
+            self.specifiers.push(literal.value.to_string());
+        }
+        walk::walk_import_expression(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_specifiers() {
+        let source = r#"
+            import { a } from "./a.js";
+            import "./side-effect.js";
+            export * from "./b.js";
+            export { c } from "./c.js";
+            export const d = 1;
+            const e = await import("./e.js");
+            const f = await import(`./${name}.js`);
+        "#;
+        assert_eq!(
+            import_specifiers(source),
+            vec!["./a.js", "./side-effect.js", "./b.js", "./c.js", "./e.js"]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// Formatting of uncaught errors: the call stack of runtime errors, and the
+// offending source line of syntax errors
+
+use deno_terminal::colors;
+use mdeno_path_util::{SourceMapConsumer, from_file_url};
+use rquickjs::{CaughtError, Coerced, Exception, FromJs};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// Source maps of transpiled modules by module URL, as JSON. They're only
+/// parsed when an error needs them.
+static SOURCE_MAPS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Register the source maps of modules so stack traces point at their
+/// original source
+pub fn register_source_maps(maps: impl IntoIterator<Item = (String, String)>) {
+    SOURCE_MAPS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .extend(maps);
+}
+
+/// Map a stack frame position in transpiled code back to the original source.
+/// `QuickJS` positions are one-based while source maps are zero-based.
+fn original_location(location: &Location) -> Option<(String, usize, usize)> {
+    let map = SOURCE_MAPS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(location.file)?
+        .clone();
+    let consumer = SourceMapConsumer::new(&map).ok()?;
+    let (file, line, column) = consumer.original_position_for(
+        u32::try_from(location.line.checked_sub(1)?).ok()?,
+        u32::try_from(location.column.checked_sub(1)?).ok()?,
+    )?;
+    Some((file, line as usize + 1, column as usize + 1))
+}
+
+/// A position in a module, as printed in `QuickJS` stack frames
+#[derive(Debug, PartialEq, Eq)]
+struct Location<'a> {
+    file: &'a str,
+    line: usize,
+    column: usize,
+}
+
+/// Split a stack frame such as `at foo (file:///a.js:3:14)` or
+/// `at file:///a.js:3:14` into the function name and its location
+fn parse_frame(frame: &str) -> Option<(Option<&str>, Location<'_>)> {
+    let frame = frame.trim().strip_prefix("at ")?;
+    let (function, position) = match frame.strip_suffix(')') {
+        Some(rest) => {
+            let (function, position) = rest.split_once(" (")?;
+            (Some(function), position)
+        }
+        None => (None, frame),
+    };
+    let (rest, column) = position.rsplit_once(':')?;
+    let (file, line) = rest.rsplit_once(':')?;
+    let location = Location {
+        file,
+        line: line.parse().ok()?,
+        column: column.parse().ok()?,
+    };
+    Some((function, location))
+}
+
+/// Read the source of a local module for highlighting
+pub(crate) fn read_local_source(file: &str) -> Option<String> {
+    let path = if file.starts_with("file://") {
+        from_file_url(file).ok()?
+    } else {
+        file.into()
+    };
+    std::fs::read_to_string(path).ok()
+}
+
+/// Format an exception for the terminal. `source` looks up the code of a
+/// module so syntax errors can point at the offending line.
+pub(crate) fn format_exception(
+    exception: &Exception,
+    source: &dyn Fn(&str) -> Option<String>,
+) -> String {
+    let name = exception
+        .get::<_, Option<String>>("name")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "Error".to_string());
+    let message = exception.message().unwrap_or_default();
+    let stack = exception.stack().unwrap_or_default();
+
+    let mut output = if message.is_empty() {
+        name.clone()
+    } else {
+        format!("{name}: {message}")
+    };
+    let mut first = None;
+    for frame in stack.lines().filter(|line| !line.trim().is_empty()) {
+        let Some((function, location)) = parse_frame(frame) else {
+            // Frames without a position, such as native functions
+            let _ = write!(output, "\n    {}", frame.trim());
+            continue;
+        };
+        let position = colors::cyan(match original_location(&location) {
+            Some((file, line, column)) => format!("{file}:{line}:{column}"),
+            None => format!("{}:{}:{}", location.file, location.line, location.column),
+        });
+        match function {
+            Some(function) => {
+                let _ = write!(output, "\n    at {function} ({position})");
+            }
+            None => {
+                let _ = write!(output, "\n    at {position}");
+            }
+        }
+        first.get_or_insert(location);
+    }
+
+    if name == "SyntaxError"
+        && let Some(location) = first
+        && let Some(code) = source(location.file)
+        && let Some(snippet) = highlight(&code, &location, &message)
+    {
+        let _ = write!(output, "\n\n{snippet}");
+    }
+    output
+}
+
+/// Format any uncaught value the way `format_exception` formats exceptions
+pub(crate) fn format_caught(
+    caught: &CaughtError,
+    source: &dyn Fn(&str) -> Option<String>,
+) -> String {
+    match caught {
+        CaughtError::Exception(exception) => format_exception(exception, source),
+        CaughtError::Value(value) => Coerced::<String>::from_js(value.ctx(), value.clone())
+            .map_or_else(|_| format!("{value:?}"), |Coerced(text)| text),
+        CaughtError::Error(error) => format!("{error:?}"),
+    }
+}
+
+/// The source line at `location` with a red caret under the error
+///
+/// `QuickJS` reports syntax errors at the start of the line, so when the
+/// message quotes the unexpected token the caret points at that instead.
+fn highlight(code: &str, location: &Location, message: &str) -> Option<String> {
+    let line = code.lines().nth(location.line.checked_sub(1)?)?;
+    let token = message
+        .split_once('\'')
+        .and_then(|(_, rest)| rest.split_once('\''))
+        .map(|(token, _)| token)
+        .filter(|token| !token.is_empty());
+    let column = token
+        .filter(|_| location.column <= 1)
+        .and_then(|token| line.find(token))
+        .map_or(location.column.saturating_sub(1), |offset| {
+            line[..offset].chars().count()
+        });
+    let width = token.map_or(1, |token| token.chars().count());
+
+    let number = location.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let padding: String = line
+        .chars()
+        .take(column)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    Some(format!(
+        "{number} | {line}\n{gutter} | {padding}{}",
+        colors::red_bold("^".repeat(width))
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frame() {
+        assert_eq!(
+            parse_frame("    at foo (file:///tmp/a.js:3:14)"),
+            Some((
+                Some("foo"),
+                Location {
+                    file: "file:///tmp/a.js",
+                    line: 3,
+                    column: 14
+                }
+            ))
+        );
+        assert_eq!(
+            parse_frame("    at ./$mdeno$eval.js:1:1"),
+            Some((
+                None,
+                Location {
+                    file: "./$mdeno$eval.js",
+                    line: 1,
+                    column: 1
+                }
+            ))
+        );
+        assert_eq!(parse_frame("    at forEach (native)"), None);
+    }
+
+    #[test]
+    fn test_original_location() {
+        register_source_maps(HashMap::from([(
+            "file:///tmp/mapped.ts".to_string(),
+            r#"{"version":3,"sources":["file:///tmp/mapped.ts"],"mappings":";AAEA,MAAM"}"#
+                .to_string(),
+        )]));
+        let location = Location {
+            file: "file:///tmp/mapped.ts",
+            line: 2,
+            column: 9,
+        };
+        assert_eq!(
+            original_location(&location),
+            Some(("file:///tmp/mapped.ts".to_string(), 3, 7))
+        );
+        let unmapped = Location {
+            file: "file:///tmp/other.js",
+            ..location
+        };
+        assert_eq!(original_location(&unmapped), None);
+    }
+
+    #[test]
+    fn test_highlight_points_at_quoted_token() {
+        colors::set_use_color(false);
+        let location = Location {
+            file: "a.js",
+            line: 2,
+            column: 1,
+        };
+        let snippet = highlight(
+            "const a = 1;\nconst b = ;\n",
+            &location,
+            "unexpected token in expression: ';'",
+        );
+        assert_eq!(snippet.as_deref(), Some("2 | const b = ;\n  |           ^"));
+    }
+
+    #[test]
+    fn test_highlight_uses_column() {
+        colors::set_use_color(false);
+        let location = Location {
+            file: "a.js",
+            line: 1,
+            column: 5,
+        };
+        let snippet = highlight("foo bar", &location, "expecting ';'");
+        assert_eq!(snippet.as_deref(), Some("1 | foo bar\n  |     ^"));
+    }
+}
@@ -2,16 +2,29 @@
 #![allow(clippy::exit)] // Executor needs to exit process on errors
 #![allow(clippy::print_stderr)] // Executor prints errors to stderr
 
-use crate::common::{BytecodeBundle, handle_error, setup_extensions};
+use crate::common::{
+    BytecodeBundle, exit_on_rejection, handle_error, report_error, setup_extensions,
+    track_rejections,
+};
+use crate::error_display::read_local_source;
 use crate::module_builder;
-use rquickjs::{AsyncContext, AsyncRuntime, CatchResultExt, Module, async_with};
+use rquickjs::{
+    AsyncContext, AsyncRuntime, CatchResultExt, Ctx, Function, Module, Object, Value, async_with,
+};
 use std::error::Error;
 use std::sync::Arc;
 
 /// Execute an async block and drive all futures with `runtime.idle()`
 /// This is a helper to ensure consistent behavior across all execution paths.
 /// The closure should contain the `async_with`! block.
-pub async fn execute_with_idle<F, Fut>(runtime: &AsyncRuntime, f: F) -> Result<(), Box<dyn Error>>
+///
+/// Once the module has settled, the "load" event is dispatched on globalThis,
+/// and the exit hooks ("unload" / "beforeExit" listeners) run before returning.
+pub async fn execute_with_idle<F, Fut>(
+    runtime: &AsyncRuntime,
+    context: &AsyncContext,
+    f: F,
+) -> Result<(), Box<dyn Error>>
 where
     F: FnOnce() -> Fut,
     Fut: std::future::Future<Output = Result<(), Box<dyn Error>>>,
@@ -23,9 +36,63 @@ where
     // This is critical for compio integration - don't use promise.finish()
     runtime.idle().await;
 
+    // Top-level module (including top-level await) has finished
+    dispatch_load_event(context).await?;
+    runtime.idle().await;
+
+    // The runtime is about to exit
+    run_exit_hooks(context).await?;
+    runtime.idle().await;
+
     Ok(())
 }
 
+/// Dispatch the "load" event on globalThis
+async fn dispatch_load_event(context: &AsyncContext) -> Result<(), Box<dyn Error>> {
+    async_with!(context => |ctx| {
+        let globals = ctx.globals();
+        let symbol_ctor: Function = globals.get("Symbol")?;
+        let symbol_for: Function = symbol_ctor.get("for")?;
+        let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
+        let internal: Object = globals.get(internal_symbol)?;
+        if let Ok(dispatch_load) = internal.get::<_, Function>("dispatchLoad") {
+            dispatch_load.call::<_, ()>(()).catch(&ctx).map_err(|caught| {
+                handle_error(caught);
+                std::process::exit(1);
+            }).unwrap_or_default();
+        }
+        Ok::<_, Box<dyn Error>>(())
+    })
+    .await
+}
+
+/// Call all "unload" and "beforeExit" handlers
+async fn run_exit_hooks(context: &AsyncContext) -> Result<(), Box<dyn Error>> {
+    async_with!(context => |ctx| {
+        deno_os::run_exit_hooks(&ctx).catch(&ctx).map_err(|caught| {
+            handle_error(caught);
+            std::process::exit(1);
+        }).unwrap_or_default();
+        Ok::<_, Box<dyn Error>>(())
+    })
+    .await
+}
+
+/// Mark `module` as the entry point: `Deno.mainModule` is `url` and the
+/// module's `import.meta.main` is true
+fn set_main_module<'js, T>(
+    ctx: &Ctx<'js>,
+    module: &Module<'js, T>,
+    url: &str,
+) -> rquickjs::Result<()> {
+    let symbol_ctor: Function = ctx.globals().get("Symbol")?;
+    let symbol_for: Function = symbol_ctor.get("for")?;
+    let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
+    let internal: Object = ctx.globals().get(internal_symbol)?;
+    internal.set("mainModule", url)?;
+    module.meta()?.set("main", true)
+}
+
 /// Common runtime setup for all execution modes
 /// Returns (runtime, context, `module_registry`)
 pub async fn setup_runtime_with_loader() -> Result<
@@ -39,6 +106,7 @@ pub async fn setup_runtime_with_loader() -> Result<
     use module_builder::ModuleBuilder;
 
     let runtime = AsyncRuntime::new()?;
+    track_rejections(&runtime).await;
 
     // Build module configuration
     let (_global_attachment, module_registry) = ModuleBuilder::default().build();
@@ -96,19 +164,34 @@ pub fn run_js_code_with_path(js_code: &str, file_path: &str) -> Result<(), Box<d
     compio_runtime.block_on(async {
         let (runtime, context, _registry) = setup_runtime_with_loader().await?;
 
-        execute_with_idle(&runtime, || async {
+        execute_with_idle(&runtime, &context, || async {
             async_with!(context => |ctx| {
                 setup_extensions(&ctx)?;
 
                 // Evaluate and get the module, but don't call finish()
                 // execute_with_idle will drive all futures via runtime.idle()
-                let _module = Module::evaluate(ctx.clone(), file_path, js_code)
+                // Evaluated code isn't on disk, so syntax errors highlight it
+                // from memory
+                let code = js_code.to_string();
+                let path = file_path.to_string();
+                let source = move |file: &str| (file == path).then(|| code.clone());
+                let module = Module::declare(ctx.clone(), file_path, js_code)
                     .catch(&ctx)
                     .map_err(|caught| {
-                        handle_error(caught);
+                        report_error(&caught, &source);
                         std::process::exit(1);
                     })
                     .unwrap();
+                set_main_module(&ctx, &module, file_path)?;
+                let (_module, promise) = module
+                    .eval()
+                    .catch(&ctx)
+                    .map_err(|caught| {
+                        report_error(&caught, &source);
+                        std::process::exit(1);
+                    })
+                    .unwrap();
+                exit_on_rejection(&ctx, &promise, source)?;
 
                 Ok::<_, Box<dyn Error>>(())
             })
@@ -123,9 +206,9 @@ pub fn run_js_code_with_path(js_code: &str, file_path: &str) -> Result<(), Box<d
     })
 }
 
-async fn cleanup_test_context_sync(context: &AsyncContext) -> Result<(), Box<dyn Error>> {
-    use rquickjs::{Function, Object, Value};
-
+pub(crate) async fn cleanup_test_context_sync(
+    context: &AsyncContext,
+) -> Result<(), Box<dyn Error>> {
     async_with!(context => |ctx| {
         // Try to get test context - it's OK if it doesn't exist
         let globals = ctx.globals();
@@ -153,9 +236,12 @@ async fn cleanup_test_context_sync(context: &AsyncContext) -> Result<(), Box<dyn
 pub fn run_bytecode(bytecode: &[u8]) -> Result<(), Box<dyn Error>> {
     // Try to deserialize as bytecode bundle first
     if let Ok(bundle) = rkyv::from_bytes::<BytecodeBundle, rkyv::rancor::Error>(bytecode) {
+        if let Err(message) = bundle.check_compatibility() {
+            eprintln!("Error: {message}");
+            std::process::exit(1);
+        }
         return run_bytecode_bundle(bundle);
     }
-    // Fall back to single module bytecode
 
     // Fall back to single module bytecode
     run_bytecode_with_loader(bytecode, true)
@@ -173,6 +259,7 @@ pub fn run_bytecode_bundle(bundle: BytecodeBundle) -> Result<(), Box<dyn Error>>
     let compio_runtime = compio_runtime::Runtime::new()?;
     compio_runtime.block_on(async {
         let runtime = AsyncRuntime::new()?;
+        track_rejections(&runtime).await;
 
         // Set up custom loader for bytecode map
         let (_global_attachment, module_registry) = ModuleBuilder::default().build();
@@ -188,7 +275,7 @@ pub fn run_bytecode_bundle(bundle: BytecodeBundle) -> Result<(), Box<dyn Error>>
 
         let context = AsyncContext::full(&runtime).await?;
 
-        execute_with_idle(&runtime, || async {
+        execute_with_idle(&runtime, &context, || async {
             async_with!(context => |ctx| {
                 setup_extensions(&ctx)?;
 
@@ -224,8 +311,10 @@ pub fn run_bytecode_bundle(bundle: BytecodeBundle) -> Result<(), Box<dyn Error>>
                         })?
                 };
 
+                set_main_module(&ctx, &module, &bundle.entry_point)?;
+
                 // Evaluate the module - execute_with_idle will drive all futures
-                let (_module, _promise) = module
+                let (_module, promise) = module
                     .eval()
                     .catch(&ctx)
                     .map_err(|caught| {
@@ -233,6 +322,7 @@ pub fn run_bytecode_bundle(bundle: BytecodeBundle) -> Result<(), Box<dyn Error>>
                         std::process::exit(1);
                     })
                     .unwrap();
+                exit_on_rejection(&ctx, &promise, read_local_source)?;
 
                 Ok::<_, Box<dyn Error>>(())
             })
@@ -262,6 +352,7 @@ pub fn run_bytecode_with_loader(
     let compio_runtime = compio_runtime::Runtime::new()?;
     compio_runtime.block_on(async {
         let runtime = AsyncRuntime::new()?;
+        track_rejections(&runtime).await;
 
         if enable_loader {
             let (_global_attachment, module_registry) = ModuleBuilder::default().build();
@@ -277,7 +368,7 @@ pub fn run_bytecode_with_loader(
 
         let context = AsyncContext::full(&runtime).await?;
 
-        execute_with_idle(&runtime, || async {
+        execute_with_idle(&runtime, &context, || async {
             async_with!(context => |ctx| {
                 setup_extensions(&ctx)?;
 
@@ -285,7 +376,7 @@ pub fn run_bytecode_with_loader(
                 let module = unsafe { Module::load(ctx.clone(), bytecode)? };
 
                 // Evaluate the module - execute_with_idle will drive all futures
-                let (_module, _promise) = module
+                let (_module, promise) = module
                     .eval()
                     .catch(&ctx)
                     .map_err(|caught| {
@@ -293,6 +384,7 @@ pub fn run_bytecode_with_loader(
                         std::process::exit(1);
                     })
                     .unwrap();
+                exit_on_rejection(&ctx, &promise, read_local_source)?;
 
                 Ok::<_, Box<dyn Error>>(())
             })
@@ -4,14 +4,17 @@
 
 mod common;
 mod compiler;
+mod error_display;
 mod executor;
+mod repl;
 mod test;
 
 pub mod module_builder;
 mod path_utils;
 
 // Re-export public types
-pub use common::BytecodeBundle;
+pub use common::{BytecodeBundle, set_seed};
+pub use error_display::register_source_maps;
 
 // Re-export compiler functions
 pub use compiler::{compile_js, compile_modules};
@@ -21,7 +24,13 @@ pub use executor::{
     run_bytecode, run_bytecode_bundle, run_bytecode_with_loader, run_js_code_with_path,
 };
 
+// Re-export REPL session
+pub use repl::ReplSession;
+
 // Re-export test functions
+pub use deno_test::{
+    set_bench_mode, set_only_mode, set_retries, set_timeout as set_test_timeout, take_bench_results,
+};
 pub use test::{run_test_bytecode, run_test_js_code};
 
 use std::error::Error;
@@ -31,6 +40,24 @@ pub fn set_script_args(args: Vec<String>) {
     deno_os::set_script_args(args);
 }
 
+/// Set the URL for globalThis.location (--location)
+///
+/// # Errors
+/// Returns an error if the URL is not an absolute http(s) URL
+pub fn set_location(href: &str) -> Result<(), String> {
+    deno_common::set_location(href)
+}
+
+/// Deny a permission for the given resources (--deny-read, --deny-write)
+pub fn deny_permissions(name: &str, resources: &[String]) {
+    deno_permissions::deny(name, resources);
+}
+
+/// Set the mdeno release reported by Deno.version
+pub fn set_mdeno_version(version: &'static str) {
+    deno_os::set_mdeno_version(version);
+}
+
 /// Evaluate JavaScript code directly (for eval command)
 ///
 /// # Errors
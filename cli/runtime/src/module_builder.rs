@@ -1,8 +1,8 @@
-use crate::path_utils::to_file_url;
+use crate::path_utils::{from_file_url, to_file_url};
 use rquickjs::loader::{Loader, Resolver};
 use rquickjs::{Ctx, Error, Module, Result};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use utils::ModuleDef;
 
@@ -28,7 +28,6 @@ impl ModuleBuilder {
     }
 
     #[must_use]
-    #[allow(dead_code)]
     pub fn with_module<M: ModuleDef>(mut self) -> Self {
         self.module_sources.insert(M::name(), M::source);
         self
@@ -55,9 +54,17 @@ impl Default for ModuleBuilder {
 
         builder = builder.with_global(web_console::init);
         builder = builder.with_global(web_crypto::init);
+        builder = builder.with_global(web_blob::init);
         builder = builder.with_global(web_url::init);
         builder = builder.with_global(web_encoding::init);
+        builder = builder.with_global(web_events::init);
+        builder = builder.with_global(web_abort::init);
+        builder = builder.with_global(web_streams::init);
+        builder = builder.with_global(web_compression::init);
         builder = builder.with_global(web_fetch::init);
+        builder = builder.with_global(web_wasm::init);
+        builder = builder.with_global(web_timers::init);
+        builder = builder.with_global(web_performance::init);
 
         // Initialize navigator after other modules
         builder = builder.with_global(web_navigator::init);
@@ -65,12 +72,17 @@ impl Default for ModuleBuilder {
         // Initialize file system and OS modules
         builder = builder.with_global(deno_fs::init);
         builder = builder.with_global(deno_os::init);
+        builder = builder.with_global(deno_net::init);
+        builder = builder.with_global(deno_permissions::init);
+        builder = builder.with_global(deno_kv::init);
+        builder = builder.with_global(deno_command::init);
 
-        // Initialize Deno namespace (depends on deno_fs and deno_os)
+        // Initialize Deno namespace (depends on deno_fs, deno_os, deno_net, deno_permissions and deno_kv)
         builder = builder.with_global(deno_ns::init);
 
         // Initialize test runner (after deno_ns so it can add to the Deno object)
         builder = builder.with_global(deno_test::init);
+        builder = builder.with_module::<deno_test::AssertionsModule>();
 
         builder
     }
@@ -122,6 +134,11 @@ impl Resolver for NodeResolver {
             return Ok(name.to_string());
         }
 
+        // Blob URLs from URL.createObjectURL() are loaded as is
+        if name.starts_with("blob:") {
+            return Ok(name.to_string());
+        }
+
         // JSR imports are not supported at runtime - they should be resolved at compile time
         if name.starts_with("jsr:") {
             return Err(Error::new_resolving(
@@ -130,31 +147,48 @@ impl Resolver for NodeResolver {
             ));
         }
 
-        // Handle relative paths (./xxx or ../xxx)
-        if name.starts_with("./") || name.starts_with("../") {
-            let base_path = Path::new(base);
-            let base_dir = if base_path.is_file() {
-                base_path.parent().unwrap_or(Path::new("."))
-            } else {
-                base_path
-            };
-
-            let resolved = base_dir.join(name);
-
-            // Try with the exact path
-            if let Some(path) = try_resolve_file(&resolved) {
-                return Ok(path);
-            }
+        // Handle relative paths (./xxx or ../xxx), including dynamic imports
+        if let Some(path) = resolve_local_file(base, name) {
+            return Ok(path);
         }
 
         Err(Error::new_resolving(name, "Module not found"))
     }
 }
 
+/// Resolve a relative specifier against the module importing it to an
+/// absolute path on disk, for modules that are only loaded at runtime such
+/// as dynamic imports outside the bundle
+pub(crate) fn resolve_local_file(base: &str, name: &str) -> Option<String> {
+    if !(name.starts_with("./") || name.starts_with("../")) {
+        return None;
+    }
+    let base_path = from_file_url(base).unwrap_or_else(|| PathBuf::from(base));
+    // Bases that aren't directories are modules, or a name like "<eval>"
+    // whose empty parent stands for the working directory
+    let base_dir = if base_path.is_dir() {
+        base_path.as_path()
+    } else {
+        base_path.parent().unwrap_or(Path::new(""))
+    };
+
+    let resolved = base_dir.join(name);
+    let resolved = if resolved.is_absolute() {
+        resolved
+    } else {
+        std::env::current_dir().ok()?.join(resolved)
+    };
+    try_resolve_file(&resolved)
+}
+
 fn try_resolve_file(path: &Path) -> Option<String> {
-    // Try exact path only
-    if path.exists() && path.is_file() {
-        return path.to_str().map(std::string::ToString::to_string);
+    // Try exact path only, normalized so nested imports resolve from it
+    if path.is_file() {
+        return path
+            .canonicalize()
+            .ok()?
+            .to_str()
+            .map(std::string::ToString::to_string);
     }
 
     None
@@ -200,6 +234,11 @@ impl Resolver for BytecodeMapResolver {
             return Ok(name.to_string());
         }
 
+        // Blob URLs from URL.createObjectURL() are loaded as is
+        if name.starts_with("blob:") {
+            return Ok(name.to_string());
+        }
+
         // JSR imports are not supported at runtime - they should be resolved at compile time
         if name.starts_with("jsr:") {
             return Err(Error::new_resolving(
@@ -224,12 +263,12 @@ impl Resolver for BytecodeMapResolver {
                     }
                 }
             } else {
-                // Regular file path resolution
-                let base_path = Path::new(base);
+                // Regular file path resolution; bundled modules are keyed by file:// URL
+                let base_path = from_file_url(base).unwrap_or_else(|| PathBuf::from(base));
                 let base_dir = if base_path.is_file() {
                     base_path.parent().unwrap_or(Path::new("."))
                 } else {
-                    base_path
+                    &base_path
                 };
 
                 let resolved = base_dir.join(name);
@@ -247,6 +286,11 @@ impl Resolver for BytecodeMapResolver {
                         return Ok(canonical_str);
                     }
                 }
+
+                // Dynamic imports aren't bundled, so load those from disk
+                if let Some(path) = resolve_local_file(base, name) {
+                    return Ok(path);
+                }
             }
         }
 
@@ -283,28 +327,43 @@ impl Loader for BytecodeMapLoader {
             return unsafe { Module::load(ctx.clone(), bytecode) };
         }
 
-        // Load from file system (JS files only - for external modules)
-        let path = Path::new(name);
-        if path.exists() && path.is_file() {
-            // TypeScript files are not supported at runtime - they must be compiled first
-            if Path::new(name)
-                .extension()
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("ts"))
-            {
-                return Err(Error::new_loading_message(
-                    name,
-                    "TypeScript files must be compiled first",
-                ));
-            }
-
-            let source = std::fs::read_to_string(path)
-                .map_err(|e| Error::new_loading_message(name, e.to_string()))?;
-
-            return Module::declare(ctx.clone(), name, source);
+        if name.starts_with("blob:") {
+            return load_blob_module(ctx, name);
         }
 
-        Err(Error::new_loading(name))
+        // Modules outside the bundle, such as dynamic imports
+        load_local_file(ctx, name)
+    }
+}
+
+/// Declare a module from a file on disk. TypeScript is stripped of its
+/// types here, as the bundler does for modules it includes.
+fn load_local_file<'js>(ctx: &Ctx<'js>, name: &str) -> Result<Module<'js>> {
+    let path = Path::new(name);
+    if !path.is_file() {
+        return Err(Error::new_loading(name));
     }
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| Error::new_loading_message(name, e.to_string()))?;
+    let source = if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ts"))
+    {
+        utils::transpile_ts(&source, name)
+            .map_err(|e| Error::new_loading_message(name, e.to_string()))?
+    } else {
+        source
+    };
+    Module::declare(ctx.clone(), name, source)
+}
+
+/// Declare a module whose source is a blob registered with
+/// `URL.createObjectURL()`
+fn load_blob_module<'js>(ctx: &Ctx<'js>, name: &str) -> Result<Module<'js>> {
+    let blob = web_blob::resolve_object_url(name)
+        .ok_or_else(|| Error::new_loading_message(name, "Blob URL not found"))?;
+    let source = String::from_utf8_lossy(&blob.data()).into_owned();
+    Module::declare(ctx.clone(), name, source)
 }
 
 impl Loader for NodeLoader {
@@ -314,27 +373,12 @@ impl Loader for NodeLoader {
             return Module::declare(ctx.clone(), name, source);
         }
 
-        // Load from file system (JS files only)
-        let path = Path::new(name);
-        if path.exists() && path.is_file() {
-            // TypeScript files are not supported at runtime - they must be compiled first
-            if path
-                .extension()
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("ts"))
-            {
-                return Err(Error::new_loading_message(
-                    name,
-                    "TypeScript files must be compiled first",
-                ));
-            }
-
-            let source = std::fs::read_to_string(path)
-                .map_err(|e| Error::new_loading_message(name, e.to_string()))?;
-
-            return Module::declare(ctx.clone(), name, source);
+        if name.starts_with("blob:") {
+            return load_blob_module(ctx, name);
         }
 
-        Err(Error::new_loading(name))
+        // Modules outside the bundle, such as dynamic imports
+        load_local_file(ctx, name)
     }
 }
 
@@ -392,12 +436,12 @@ impl Resolver for SourceMapResolver {
                     }
                 }
             } else {
-                // Regular file path resolution
-                let base_path = Path::new(base);
+                // Regular file path resolution; bundled modules are keyed by file:// URL
+                let base_path = from_file_url(base).unwrap_or_else(|| PathBuf::from(base));
                 let base_dir = if base_path.is_file() {
                     base_path.parent().unwrap_or(Path::new("."))
                 } else {
-                    base_path
+                    &base_path
                 };
 
                 let resolved = base_dir.join(name);
@@ -450,26 +494,7 @@ impl Loader for SourceMapLoader {
             return Module::declare(ctx.clone(), name, source.as_str());
         }
 
-        // Load from file system (JS files only - for external modules)
-        let path = Path::new(name);
-        if path.exists() && path.is_file() {
-            // TypeScript files are not supported at runtime - they must be compiled first
-            if path
-                .extension()
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("ts"))
-            {
-                return Err(Error::new_loading_message(
-                    name,
-                    "TypeScript files must be compiled first",
-                ));
-            }
-
-            let source = std::fs::read_to_string(path)
-                .map_err(|e| Error::new_loading_message(name, e.to_string()))?;
-
-            return Module::declare(ctx.clone(), name, source);
-        }
-
-        Err(Error::new_loading(name))
+        // Modules outside the bundle, such as dynamic imports
+        load_local_file(ctx, name)
     }
 }
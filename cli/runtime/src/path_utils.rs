@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Convert a file path to a file:// URL
 pub fn to_file_url(path: &Path) -> String {
@@ -14,6 +14,16 @@ pub fn to_file_url(path: &Path) -> String {
     }
 }
 
+/// Convert a file:// URL produced by `to_file_url` back to a path
+pub fn from_file_url(url: &str) -> Option<PathBuf> {
+    let path = if cfg!(windows) {
+        url.strip_prefix("file:///")?
+    } else {
+        url.strip_prefix("file://")?
+    };
+    Some(PathBuf::from(path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,5 +43,6 @@ mod tests {
         let path = PathBuf::from("/home/user/file.js");
         let url = to_file_url(&path);
         assert_eq!(url, "file:///home/user/file.js");
+        assert_eq!(from_file_url(&url), Some(path));
     }
 }
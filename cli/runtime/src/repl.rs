@@ -0,0 +1,202 @@
+// REPL session with a context that persists across evaluations
+
+use crate::common::{setup_extensions, setup_uncaught_reporter};
+use crate::executor::{cleanup_test_context_sync, setup_runtime_with_loader};
+use oxc_allocator::Allocator;
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+use rquickjs::context::EvalOptions;
+use rquickjs::{
+    AsyncContext, AsyncRuntime, CatchResultExt, CaughtError, Function, Value, async_with,
+};
+use std::error::Error;
+
+pub struct ReplSession {
+    compio_runtime: compio_runtime::Runtime,
+    runtime: AsyncRuntime,
+    context: AsyncContext,
+}
+
+impl ReplSession {
+    /// Create a session with all runtime extensions installed
+    ///
+    /// # Errors
+    /// Returns an error if the runtime or context cannot be created
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let compio_runtime = compio_runtime::Runtime::new()?;
+        let (runtime, context) = compio_runtime.block_on(async {
+            let (runtime, context, _registry) = setup_runtime_with_loader().await?;
+            async_with!(context => |ctx| {
+                setup_extensions(&ctx)?;
+                // A throwing timer callback shouldn't end the session
+                setup_uncaught_reporter(&ctx, false)?;
+                Ok::<_, Box<dyn Error>>(())
+            })
+            .await?;
+            Ok::<_, Box<dyn Error>>((runtime, context))
+        })?;
+
+        Ok(Self {
+            compio_runtime,
+            runtime,
+            context,
+        })
+    }
+
+    /// Evaluate `code` as a global script and return the inspected result.
+    /// Exceptions are returned as "Uncaught ..." strings rather than `Err`.
+    ///
+    /// # Errors
+    /// Returns an error if the runtime itself fails
+    pub fn eval(&self, code: &str) -> Result<String, Box<dyn Error>> {
+        self.compio_runtime.block_on(async {
+            let output = async_with!(self.context => |ctx| {
+                let mut options = EvalOptions::default();
+                options.global = true;
+                options.strict = false;
+                options.filename = Some("$mdeno$repl.js".to_string());
+
+                let output = match ctx.eval_with_options::<Value, _>(code, options).catch(&ctx) {
+                    Ok(value) => inspect(&ctx, value)?,
+                    Err(caught) => format_uncaught(&ctx, caught)?,
+                };
+                Ok::<_, Box<dyn Error>>(output)
+            })
+            .await?;
+
+            // Let promises and timers started by this input make progress
+            self.runtime.idle().await;
+
+            Ok(output)
+        })
+    }
+
+    /// Whether `code` stops partway through a statement, as with an unclosed
+    /// bracket or a trailing operator, so the REPL should read another line
+    /// before evaluating it
+    pub fn is_incomplete(code: &str) -> bool {
+        let allocator = Allocator::default();
+        let parsed = Parser::new(&allocator, code, SourceType::cjs()).parse();
+        let Some(label) = parsed
+            .errors
+            .first()
+            .and_then(|error| error.labels.as_ref()?.first().cloned())
+        else {
+            return false;
+        };
+        // The parser ran out of input, or a template literal or comment
+        // runs to its end. Other strings can't continue on the next line.
+        let rest = code.get(label.offset()..).unwrap_or_default();
+        rest.trim().is_empty()
+            || (label.offset() + label.len() == code.len()
+                && (rest.starts_with('`') || rest.starts_with("/*")))
+    }
+}
+
+impl Drop for ReplSession {
+    fn drop(&mut self) {
+        // Release persistent values before the runtime is freed
+        let _ = self.compio_runtime.block_on(async {
+            async_with!(self.context => |ctx| {
+                if let Err(caught) = deno_os::run_exit_hooks(&ctx).catch(&ctx) {
+                    crate::common::handle_error(caught);
+                }
+                Ok::<_, Box<dyn Error>>(())
+            })
+            .await?;
+            cleanup_test_context_sync(&self.context).await
+        });
+    }
+}
+
+/// Format a value with `Deno.inspect`
+fn inspect<'js>(ctx: &rquickjs::Ctx<'js>, value: Value<'js>) -> Result<String, Box<dyn Error>> {
+    let deno: rquickjs::Object = ctx.globals().get("Deno")?;
+    let inspect: Function = deno.get("inspect")?;
+    Ok(inspect.call((value,))?)
+}
+
+fn format_uncaught<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    caught: CaughtError<'js>,
+) -> Result<String, Box<dyn Error>> {
+    Ok(match caught {
+        CaughtError::Exception(exception) => {
+            let message = exception.message().unwrap_or_default();
+            let name: Option<String> = exception.get("name").ok();
+            let mut output = format!("Uncaught {}: {message}", name.as_deref().unwrap_or("Error"));
+            if let Some(stack) = exception.stack() {
+                output.push('\n');
+                output.push_str(stack.trim_end());
+            }
+            output
+        }
+        CaughtError::Value(value) => format!("Uncaught {}", inspect(ctx, value)?),
+        CaughtError::Error(error) => format!("Uncaught {error}"),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval_expression() {
+        let session = ReplSession::new().unwrap();
+        assert_eq!(session.eval("1 + 2").unwrap(), "3");
+        assert_eq!(
+            session.eval("({ a: [1, 'b'] })").unwrap(),
+            "{ a: [ 1, \"b\" ] }"
+        );
+        assert_eq!(session.eval("undefined").unwrap(), "undefined");
+    }
+
+    #[test]
+    fn test_state_persists_across_inputs() {
+        let session = ReplSession::new().unwrap();
+        session.eval("let x = 40").unwrap();
+        session
+            .eval("function add(a, b) { return a + b; }")
+            .unwrap();
+        assert_eq!(session.eval("add(x, 2)").unwrap(), "42");
+        session.eval("x = 1").unwrap();
+        assert_eq!(session.eval("x").unwrap(), "1");
+    }
+
+    #[test]
+    fn test_incomplete_input_continues() {
+        let mut input = String::new();
+        for line in ["function add(a, b) {", "  return a +", "    b;"] {
+            input.push_str(line);
+            input.push('\n');
+            assert!(ReplSession::is_incomplete(&input), "{input}");
+        }
+        input.push_str("}\n");
+        assert!(!ReplSession::is_incomplete(&input));
+
+        let session = ReplSession::new().unwrap();
+        session.eval(&input).unwrap();
+        assert_eq!(session.eval("add(1, 2)").unwrap(), "3");
+
+        assert!(ReplSession::is_incomplete("[1,\n"));
+        assert!(ReplSession::is_incomplete("`first line\n"));
+        assert!(ReplSession::is_incomplete("/* comment\n"));
+        // Errors that more input can't fix are evaluated and reported
+        assert!(!ReplSession::is_incomplete("'unterminated\n"));
+        assert!(!ReplSession::is_incomplete("1 + )\n"));
+        assert!(!ReplSession::is_incomplete("}\n"));
+    }
+
+    #[test]
+    fn test_error_keeps_session() {
+        let session = ReplSession::new().unwrap();
+        session.eval("const kept = 'still here'").unwrap();
+        let output = session.eval("throw new TypeError('boom')").unwrap();
+        assert!(output.starts_with("Uncaught TypeError: boom"), "{output}");
+        let output = session.eval("missing.property").unwrap();
+        assert!(output.starts_with("Uncaught ReferenceError"), "{output}");
+        assert_eq!(session.eval("throw 1").unwrap(), "Uncaught 1");
+        assert_eq!(session.eval("kept").unwrap(), "\"still here\"");
+    }
+}
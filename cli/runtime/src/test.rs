@@ -1,6 +1,6 @@
 // Test execution functions for Deno.test()
 
-use crate::common::{BytecodeBundle, handle_error, setup_extensions};
+use crate::common::{BytecodeBundle, handle_error, setup_extensions, track_rejections};
 use crate::executor::{execute_pending_jobs_loop, setup_runtime_with_loader};
 use crate::module_builder;
 use deno_test::TestContext;
@@ -20,16 +20,25 @@ fn get_test_context(ctx: &rquickjs::Ctx<'_>) -> Result<TestContext, Box<dyn Erro
     Ok(internal.get("testContext")?)
 }
 
+/// Run a plain JavaScript test file, returning its (passed, failed, skipped) counts
+///
 /// # Errors
 /// Returns an error if test execution fails
 ///
 /// # Panics
 /// Panics if test context access fails
 #[allow(clippy::unwrap_used)] // Test infrastructure: object creation should not fail
-pub fn run_test_js_code(js_code: &str, file_path: &str) -> Result<(usize, usize), Box<dyn Error>> {
+pub fn run_test_js_code(
+    js_code: &str,
+    file_path: &str,
+) -> Result<(usize, usize, usize), Box<dyn Error>> {
     let compio_runtime = compio_runtime::Runtime::new()?;
     compio_runtime.block_on(async {
         let (runtime, context, _registry) = setup_runtime_with_loader().await?;
+        // Stop tests that run past their timeout
+        runtime
+            .set_interrupt_handler(Some(Box::new(deno_test::deadline_passed)))
+            .await;
 
         async_with!(context => |ctx| {
             setup_extensions(&ctx)?;
@@ -49,12 +58,13 @@ pub fn run_test_js_code(js_code: &str, file_path: &str) -> Result<(usize, usize)
         })
         .await?;
 
-        // Execute all pending jobs (promises, microtasks)
-        // idle() should integrate with compio through standard Future polling
+        // Execute all pending jobs (promises, microtasks). idle() also polls
+        // the futures spawned by async ops, and since it runs inside compio's
+        // block_on those futures are driven by the compio reactor.
         runtime.idle().await;
 
         // Call globalThis[Symbol.for('mdeno.internal')].test.runTests after module execution completes
-        let (mut passed, mut failed) = async_with!(context => |ctx| {
+        let (mut passed, mut failed, skipped) = async_with!(context => |ctx| {
             // Get runTests function using Rust API
             let globals = ctx.globals();
             let symbol_ctor: Function = globals.get("Symbol")?;
@@ -84,56 +94,69 @@ pub fn run_test_js_code(js_code: &str, file_path: &str) -> Result<(usize, usize)
             });
             let passed: usize = obj.get("passed").unwrap_or(0);
             let failed: usize = obj.get("failed").unwrap_or(0);
+            let skipped: usize = obj.get("skipped").unwrap_or(0);
 
-            Ok::<_, Box<dyn Error>>((passed, failed))
-        })
-        .await?;
-
-        // Drive all pending promises (including async tests)
-        runtime.idle().await;
-
-        // Resolve pending async tests after promises are driven
-        let (async_passed, async_failed) = async_with!(context => |ctx| {
-            let globals = ctx.globals();
-            let symbol_ctor: Function = globals.get("Symbol")?;
-            let symbol_for: Function = symbol_ctor.get("for")?;
-            let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
-
-            let internal: Object = globals.get(internal_symbol)?;
-            let test_obj: Object = internal.get("test")?;
-            let resolve_pending_fn: Function = test_obj.get("resolvePending")?;
-
-            let result: Value = resolve_pending_fn.call(()).catch(&ctx).map_err(|caught| {
-                handle_error(caught);
-            }).unwrap_or_else(|()| {
-                let obj = Object::new(ctx.clone()).unwrap();
-                obj.set("passed", 0).unwrap();
-                obj.set("failed", 0).unwrap();
-                obj.into_value()
-            });
-
-            let obj: Object = result.into_object().unwrap_or_else(|| {
-                let obj = Object::new(ctx.clone()).unwrap();
-                obj.set("passed", 0).unwrap();
-                obj.set("failed", 0).unwrap();
-                obj
-            });
-            let async_passed: usize = obj.get("passed").unwrap_or(0);
-            let async_failed: usize = obj.get("failed").unwrap_or(0);
-
-            Ok::<_, Box<dyn Error>>((async_passed, async_failed))
+            Ok::<_, Box<dyn Error>>((passed, failed, skipped))
         })
         .await?;
 
-        // Add async test results
-        passed += async_passed;
-        failed += async_failed;
+        // Drive all pending promises (including async tests). Failed async
+        // tests with retries left run again, so this may take several rounds.
+        loop {
+            runtime.idle().await;
+
+            // Resolve pending async tests after promises are driven
+            let (async_passed, async_failed, still_pending) = async_with!(context => |ctx| {
+                let globals = ctx.globals();
+                let symbol_ctor: Function = globals.get("Symbol")?;
+                let symbol_for: Function = symbol_ctor.get("for")?;
+                let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
+
+                let internal: Object = globals.get(internal_symbol)?;
+                let test_obj: Object = internal.get("test")?;
+                let resolve_pending_fn: Function = test_obj.get("resolvePending")?;
+
+                let result: Value = resolve_pending_fn.call(()).catch(&ctx).map_err(|caught| {
+                    handle_error(caught);
+                }).unwrap_or_else(|()| {
+                    let obj = Object::new(ctx.clone()).unwrap();
+                    obj.set("passed", 0).unwrap();
+                    obj.set("failed", 0).unwrap();
+                    obj.into_value()
+                });
+
+                let obj: Object = result.into_object().unwrap_or_else(|| {
+                    let obj = Object::new(ctx.clone()).unwrap();
+                    obj.set("passed", 0).unwrap();
+                    obj.set("failed", 0).unwrap();
+                    obj
+                });
+                let async_passed: usize = obj.get("passed").unwrap_or(0);
+                let async_failed: usize = obj.get("failed").unwrap_or(0);
+                let still_pending: usize = obj.get("pending").unwrap_or(0);
+
+                Ok::<_, Box<dyn Error>>((async_passed, async_failed, still_pending))
+            })
+            .await?;
+
+            // Add async test results
+            passed += async_passed;
+            failed += async_failed;
+            if still_pending == 0 {
+                break;
+            }
+        }
 
         // Execute pending jobs from runTests
         execute_pending_jobs_loop(&runtime, &context).await?;
 
         // Clean up TestContext to avoid GC assertion
         async_with!(context => |ctx| {
+            // Run exit hooks so their persistent functions are released too
+            if let Err(caught) = deno_os::run_exit_hooks(&ctx).catch(&ctx) {
+                handle_error(caught);
+            }
+
             // First cleanup the persistent objects
             {
                 let test_context = get_test_context(&ctx)?;
@@ -155,7 +178,7 @@ pub fn run_test_js_code(js_code: &str, file_path: &str) -> Result<(usize, usize)
         // Drop context explicitly before runtime
         drop(context);
 
-        Ok((passed, failed))
+        Ok((passed, failed, skipped))
     })
 }
 
@@ -169,7 +192,7 @@ pub fn run_test_js_code(js_code: &str, file_path: &str) -> Result<(usize, usize)
 pub fn run_test_bytecode(
     bytecode: &[u8],
     file_path: &str,
-) -> Result<(usize, usize), Box<dyn Error>> {
+) -> Result<(usize, usize, usize), Box<dyn Error>> {
     // Try to deserialize as bytecode bundle first
     match rkyv::from_bytes::<BytecodeBundle, rkyv::rancor::Error>(bytecode) {
         Ok(bundle) => run_test_bytecode_bundle(bundle, file_path),
@@ -184,12 +207,17 @@ pub fn run_test_bytecode(
 fn run_test_bytecode_bundle(
     bundle: BytecodeBundle,
     file_path: &str,
-) -> Result<(usize, usize), Box<dyn Error>> {
+) -> Result<(usize, usize, usize), Box<dyn Error>> {
     use module_builder::ModuleBuilder;
 
     let compio_runtime = compio_runtime::Runtime::new()?;
     compio_runtime.block_on(async {
         let runtime = AsyncRuntime::new()?;
+        track_rejections(&runtime).await;
+        // Stop tests that run past their timeout
+        runtime
+            .set_interrupt_handler(Some(Box::new(deno_test::deadline_passed)))
+            .await;
 
         // Set up custom loader for bytecode map
         let (_global_attachment, module_registry) = ModuleBuilder::default().build();
@@ -256,12 +284,13 @@ fn run_test_bytecode_bundle(
         })
         .await?;
 
-        // Execute all pending jobs (promises, microtasks)
-        // idle() should integrate with compio through standard Future polling
+        // Execute all pending jobs (promises, microtasks). idle() also polls
+        // the futures spawned by async ops, and since it runs inside compio's
+        // block_on those futures are driven by the compio reactor.
         runtime.idle().await;
 
         // Call globalThis[Symbol.for('mdeno.internal')].test.runTests after module execution completes
-        let (mut passed, mut failed) = async_with!(context => |ctx| {
+        let (mut passed, mut failed, skipped) = async_with!(context => |ctx| {
             // Get runTests function using Rust API
             let globals = ctx.globals();
             let symbol_ctor: Function = globals.get("Symbol")?;
@@ -291,56 +320,69 @@ fn run_test_bytecode_bundle(
             });
             let passed: usize = obj.get("passed").unwrap_or(0);
             let failed: usize = obj.get("failed").unwrap_or(0);
+            let skipped: usize = obj.get("skipped").unwrap_or(0);
 
-            Ok::<_, Box<dyn Error>>((passed, failed))
-        })
-        .await?;
-
-        // Drive all pending promises (including async tests)
-        runtime.idle().await;
-
-        // Resolve pending async tests after promises are driven
-        let (async_passed, async_failed) = async_with!(context => |ctx| {
-            let globals = ctx.globals();
-            let symbol_ctor: Function = globals.get("Symbol")?;
-            let symbol_for: Function = symbol_ctor.get("for")?;
-            let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
-
-            let internal: Object = globals.get(internal_symbol)?;
-            let test_obj: Object = internal.get("test")?;
-            let resolve_pending_fn: Function = test_obj.get("resolvePending")?;
-
-            let result: Value = resolve_pending_fn.call(()).catch(&ctx).map_err(|caught| {
-                handle_error(caught);
-            }).unwrap_or_else(|()| {
-                let obj = Object::new(ctx.clone()).unwrap();
-                obj.set("passed", 0).unwrap();
-                obj.set("failed", 0).unwrap();
-                obj.into_value()
-            });
-
-            let obj: Object = result.into_object().unwrap_or_else(|| {
-                let obj = Object::new(ctx.clone()).unwrap();
-                obj.set("passed", 0).unwrap();
-                obj.set("failed", 0).unwrap();
-                obj
-            });
-            let async_passed: usize = obj.get("passed").unwrap_or(0);
-            let async_failed: usize = obj.get("failed").unwrap_or(0);
-
-            Ok::<_, Box<dyn Error>>((async_passed, async_failed))
+            Ok::<_, Box<dyn Error>>((passed, failed, skipped))
         })
         .await?;
 
-        // Add async test results
-        passed += async_passed;
-        failed += async_failed;
+        // Drive all pending promises (including async tests). Failed async
+        // tests with retries left run again, so this may take several rounds.
+        loop {
+            runtime.idle().await;
+
+            // Resolve pending async tests after promises are driven
+            let (async_passed, async_failed, still_pending) = async_with!(context => |ctx| {
+                let globals = ctx.globals();
+                let symbol_ctor: Function = globals.get("Symbol")?;
+                let symbol_for: Function = symbol_ctor.get("for")?;
+                let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
+
+                let internal: Object = globals.get(internal_symbol)?;
+                let test_obj: Object = internal.get("test")?;
+                let resolve_pending_fn: Function = test_obj.get("resolvePending")?;
+
+                let result: Value = resolve_pending_fn.call(()).catch(&ctx).map_err(|caught| {
+                    handle_error(caught);
+                }).unwrap_or_else(|()| {
+                    let obj = Object::new(ctx.clone()).unwrap();
+                    obj.set("passed", 0).unwrap();
+                    obj.set("failed", 0).unwrap();
+                    obj.into_value()
+                });
+
+                let obj: Object = result.into_object().unwrap_or_else(|| {
+                    let obj = Object::new(ctx.clone()).unwrap();
+                    obj.set("passed", 0).unwrap();
+                    obj.set("failed", 0).unwrap();
+                    obj
+                });
+                let async_passed: usize = obj.get("passed").unwrap_or(0);
+                let async_failed: usize = obj.get("failed").unwrap_or(0);
+                let still_pending: usize = obj.get("pending").unwrap_or(0);
+
+                Ok::<_, Box<dyn Error>>((async_passed, async_failed, still_pending))
+            })
+            .await?;
+
+            // Add async test results
+            passed += async_passed;
+            failed += async_failed;
+            if still_pending == 0 {
+                break;
+            }
+        }
 
         // Execute pending jobs from runTests
         execute_pending_jobs_loop(&runtime, &context).await?;
 
         // Clean up TestContext to avoid GC assertion
         async_with!(context => |ctx| {
+            // Run exit hooks so their persistent functions are released too
+            if let Err(caught) = deno_os::run_exit_hooks(&ctx).catch(&ctx) {
+                handle_error(caught);
+            }
+
             // First cleanup the persistent objects
             {
                 let test_context = get_test_context(&ctx)?;
@@ -362,6 +404,6 @@ fn run_test_bytecode_bundle(
         // Drop context explicitly before runtime
         drop(context);
 
-        Ok((passed, failed))
+        Ok((passed, failed, skipped))
     })
 }
@@ -1,20 +1,48 @@
-use crate::jsr::JsrResolver;
-use crate::strip_types::transform;
+use crate::import_glob;
+use crate::jsr::{JsrError, JsrResolver};
+use crate::strip_types::{self, transform_with_source_map};
+use crate::tree_shake;
 use mdeno_path_util::to_file_url;
 use oxc_allocator::Allocator;
 use oxc_ast::ast::Statement;
 use oxc_parser::Parser;
-use oxc_span::SourceType;
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// A JSR import that couldn't be resolved, keeping the `JsrError` as its source
+#[derive(Debug)]
+pub struct JsrImportError {
+    pub specifier: String,
+    pub source: JsrError,
+}
+
+impl fmt::Display for JsrImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to resolve JSR import {}", self.specifier)
+    }
+}
+
+impl Error for JsrImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
 
 pub struct ModuleBundler {
     modules: HashMap<String, String>, // path -> source
     visited: HashSet<String>,
+    // (importer key, specifier) -> imported key, for local imports
+    resolved_imports: HashMap<(String, String), String>,
+    // key -> file the module was read from, the cache file for JSR modules
+    local_paths: HashMap<String, PathBuf>,
+    // key -> (transpiled code, source map), for local TypeScript modules
+    source_maps: HashMap<String, (String, String)>,
     jsr_resolver: JsrResolver,
     unstable: bool,
+    tree_shake: bool,
 }
 
 impl ModuleBundler {
@@ -22,11 +50,29 @@ impl ModuleBundler {
         Self {
             modules: HashMap::new(),
             visited: HashSet::new(),
+            resolved_imports: HashMap::new(),
+            local_paths: HashMap::new(),
+            source_maps: HashMap::new(),
             jsr_resolver: JsrResolver::new(),
             unstable,
+            tree_shake: true,
         }
     }
 
+    /// Enable or disable integrity verification of JSR downloads
+    #[must_use]
+    pub fn with_check_integrity(mut self, check_integrity: bool) -> Self {
+        self.jsr_resolver = self.jsr_resolver.with_check_integrity(check_integrity);
+        self
+    }
+
+    /// Enable or disable removal of unused exports from the bundle
+    #[must_use]
+    pub fn with_tree_shake(mut self, tree_shake: bool) -> Self {
+        self.tree_shake = tree_shake;
+        self
+    }
+
     pub fn is_unstable(&self) -> bool {
         self.unstable
     }
@@ -37,9 +83,86 @@ impl ModuleBundler {
         // entry_path should already be an absolute canonical path
         self.process_module(entry_path)?;
 
+        if !self.tree_shake {
+            return Ok(self.modules.clone());
+        }
+
+        let entry_key = to_file_url(Path::new(entry_path));
+        Ok(tree_shake::shake(
+            &self.modules,
+            &entry_key,
+            |base, specifier| self.resolve_bundled(base, specifier),
+        ))
+    }
+
+    /// Collect a JSR module and its dependencies, without a local entry point
+    ///
+    /// # Errors
+    /// Returns an error if the module can't be resolved or downloaded
+    pub fn bundle_jsr(
+        &mut self,
+        specifier: &str,
+    ) -> Result<HashMap<String, String>, Box<dyn Error>> {
+        self.process_jsr(specifier)?;
         Ok(self.modules.clone())
     }
 
+    /// Imports of a collected module, each with the key of the collected
+    /// module it resolves to (`None` for built-ins and anything not collected)
+    pub fn dependencies(&self, key: &str) -> Vec<(String, Option<String>)> {
+        let Some(source) = self.modules.get(key) else {
+            return Vec::new();
+        };
+        Self::extract_imports(source, key)
+            .into_iter()
+            .map(|specifier| {
+                let resolved = self.resolve_bundled(key, &specifier);
+                (specifier, resolved)
+            })
+            .collect()
+    }
+
+    /// Source maps of the bundled modules, keyed like `modules`. Modules
+    /// changed after transpiling (by tree shaking or glob expansion) are left
+    /// out, since their maps no longer match.
+    pub fn source_maps(&self, modules: &HashMap<String, String>) -> HashMap<String, String> {
+        self.source_maps
+            .iter()
+            .filter(|(key, (code, _))| modules.get(*key) == Some(code))
+            .map(|(key, (_, map))| (key.clone(), map.clone()))
+            .collect()
+    }
+
+    /// The file a collected module was read from, the cache file for JSR modules
+    pub fn local_path(&self, key: &str) -> Option<&Path> {
+        self.local_paths.get(key).map(PathBuf::as_path)
+    }
+
+    /// Resolve an import between bundled modules the way the module loader will
+    fn resolve_bundled(&self, base: &str, specifier: &str) -> Option<String> {
+        if let Some(key) = self
+            .resolved_imports
+            .get(&(base.to_string(), specifier.to_string()))
+        {
+            return Some(key.clone());
+        }
+
+        let key = if specifier.starts_with("jsr:") {
+            specifier.to_string()
+        } else if base.starts_with("jsr:")
+            && (specifier.starts_with("./") || specifier.starts_with("../"))
+        {
+            // Relative import within the same JSR package
+            let base_prefix = &base[..base.rfind('/')?];
+            let relative_path = specifier.trim_start_matches("./").trim_end_matches(".js");
+            format!("{base_prefix}/{relative_path}")
+        } else {
+            return None;
+        };
+
+        self.modules.contains_key(&key).then_some(key)
+    }
+
     fn process_module(&mut self, module_path: &str) -> Result<(), Box<dyn Error>> {
         // Convert file path to file:// URL for map key
         let file_url = to_file_url(Path::new(module_path));
@@ -64,16 +187,28 @@ impl ModuleBundler {
             .extension()
             .is_some_and(|ext| ext.eq_ignore_ascii_case("ts"))
         {
-            transform(&source, module_path)?
+            let (code, source_map) = transform_with_source_map(&source, module_path, map_key)?;
+            if let Some(source_map) = source_map {
+                self.source_maps
+                    .insert(map_key.to_string(), (code.clone(), source_map));
+            }
+            code
         } else {
             source
         };
 
+        // Expand import.meta.glob() calls into the files they match
+        let expanded = import_glob::expand(&js_source, module_path)?;
+        let js_source = expanded.source;
+
         // Parse to extract imports
-        let imports = Self::extract_imports(&js_source, module_path);
+        let mut imports = Self::extract_imports(&js_source, module_path);
+        imports.extend(expanded.imports);
 
         // Store this module with the specified key
         self.modules.insert(map_key.to_string(), js_source);
+        self.local_paths
+            .insert(map_key.to_string(), PathBuf::from(module_path));
 
         // Process dependencies
         for import_path in imports {
@@ -84,6 +219,10 @@ impl ModuleBundler {
 
                 // Try to resolve file
                 if let Ok(canonical) = resolved.canonicalize() {
+                    self.resolved_imports.insert(
+                        (map_key.to_string(), import_path.clone()),
+                        to_file_url(&canonical),
+                    );
                     let canonical_str = canonical.display().to_string();
                     self.process_module(&canonical_str)?;
                 }
@@ -93,26 +232,36 @@ impl ModuleBundler {
                         format!("JSR imports require --unstable flag: {import_path}").into(),
                     );
                 }
-                // Resolve JSR imports - returns HashMap<jsr_specifier, cache_path>
-                let resolved_modules = self
-                    .jsr_resolver
-                    .resolve(&import_path)
-                    .map_err(|e| format!("Failed to resolve JSR import {import_path}: {e}"))?;
-
-                // Add all resolved JSR modules to the bundle
-                for (jsr_spec, cache_path) in resolved_modules {
-                    if !self.visited.contains(&jsr_spec) {
-                        let source = std::fs::read_to_string(&cache_path).map_err(|e| {
-                            format!(
-                                "Failed to read cached JSR file {}: {}",
-                                cache_path.display(),
-                                e
-                            )
-                        })?;
-                        self.modules.insert(jsr_spec.clone(), source.clone());
-                        self.visited.insert(jsr_spec.clone());
-                    }
-                }
+                self.process_jsr(&import_path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_jsr(&mut self, import_path: &str) -> Result<(), Box<dyn Error>> {
+        // Resolve JSR imports - returns HashMap<jsr_specifier, cache_path>
+        let resolved_modules =
+            self.jsr_resolver
+                .resolve(import_path)
+                .map_err(|source| JsrImportError {
+                    specifier: import_path.to_string(),
+                    source,
+                })?;
+
+        // Add all resolved JSR modules to the bundle
+        for (jsr_spec, cache_path) in resolved_modules {
+            if !self.visited.contains(&jsr_spec) {
+                let source = std::fs::read_to_string(&cache_path).map_err(|e| {
+                    format!(
+                        "Failed to read cached JSR file {}: {}",
+                        cache_path.display(),
+                        e
+                    )
+                })?;
+                self.modules.insert(jsr_spec.clone(), source.clone());
+                self.visited.insert(jsr_spec.clone());
+                self.local_paths.insert(jsr_spec, cache_path);
             }
         }
 
@@ -121,7 +270,7 @@ impl ModuleBundler {
 
     fn extract_imports(source: &str, filename: &str) -> Vec<String> {
         let allocator = Allocator::default();
-        let source_type = SourceType::from_path(Path::new(filename)).unwrap_or_default();
+        let source_type = strip_types::source_type(filename);
 
         let parser_ret = Parser::new(&allocator, source, source_type).parse();
         if !parser_ret.errors.is_empty() {
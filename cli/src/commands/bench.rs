@@ -0,0 +1,321 @@
+use crate::commands::run;
+use crate::commands::test::{find_files, matches_pattern};
+use deno_terminal::colors;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+/// Slowdowns beyond this percentage are highlighted unless --threshold is given
+pub const DEFAULT_THRESHOLD: f64 = 5.0;
+
+/// Where `--json` writes results unless --output is given
+const DEFAULT_OUTPUT: &str = "bench.json";
+
+/// Output and comparison options of `mdeno bench`
+pub struct BenchOptions {
+    pub json: bool,
+    pub output: Option<String>,
+    pub baseline: Option<String>,
+    pub threshold: f64,
+}
+
+/// A measured benchmark, as reported by `Deno.bench` and stored by `--json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BenchResult {
+    name: String,
+    warmup_iterations: u64,
+    total_iterations: u64,
+    avg_ns: f64,
+    min_ns: f64,
+    max_ns: f64,
+    p75_ns: f64,
+    p99_ns: f64,
+    /// Iterations per second
+    throughput: f64,
+}
+
+/// What `Deno.bench` reports for each benchmark
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Reported {
+    Failed { name: String, error: String },
+    Measured(BenchResult),
+}
+
+/// Run bench files, print their results and optionally store or compare them
+pub fn execute(
+    pattern: Option<String>,
+    unstable: bool,
+    check_integrity: bool,
+    tree_shake: bool,
+    options: &BenchOptions,
+) -> Result<(), Box<dyn Error>> {
+    let bench_dir = pattern.unwrap_or_else(|| ".".to_string());
+    let bench_files = find_files(Path::new(&bench_dir), is_bench_file)?;
+
+    if bench_files.is_empty() {
+        eprintln!("No bench files found");
+        return Ok(());
+    }
+
+    // Read the baseline up front so a bad path fails before anything runs
+    let baseline = options.baseline.as_deref().map(read_baseline).transpose()?;
+
+    let start_time = Instant::now();
+    mdeno_runtime::set_bench_mode(true);
+
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for bench_file in &bench_files {
+        println!(
+            "{}",
+            colors::gray(&format!("benchmark file: {}", bench_file.display()))
+        );
+        run::execute(
+            &bench_file.to_string_lossy(),
+            unstable,
+            check_integrity,
+            tree_shake,
+        )?;
+
+        let mut measured = Vec::new();
+        for reported in mdeno_runtime::take_bench_results() {
+            match serde_json::from_str(&reported)? {
+                Reported::Measured(result) => measured.push(result),
+                Reported::Failed { name, error } => {
+                    failed += 1;
+                    println!("{name} ... {} {error}", colors::red("error:"));
+                }
+            }
+        }
+        print_results(&measured);
+        println!();
+        results.extend(measured);
+    }
+
+    if let Some(baseline) = &baseline {
+        print_comparison(baseline, &results, options.threshold);
+        println!();
+    }
+
+    if options.json || options.output.is_some() {
+        let output = options.output.as_deref().unwrap_or(DEFAULT_OUTPUT);
+        fs::write(output, serde_json::to_string_pretty(&results)?)
+            .map_err(|e| format!("Failed to write {output}: {e}"))?;
+        println!(
+            "{}",
+            colors::gray(&format!("Wrote {} results to {output}", results.len()))
+        );
+    }
+
+    let elapsed_ms = start_time.elapsed().as_millis();
+    let status = if failed > 0 {
+        colors::red("FAILED")
+    } else {
+        colors::green("ok")
+    };
+    println!(
+        "{} | {} measured | {} failed {}",
+        status,
+        results.len(),
+        failed,
+        colors::gray(&format!("({elapsed_ms}ms)"))
+    );
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Bench files follow the test file pattern with "bench": `{*_,*.,}bench.{js,ts}`
+fn is_bench_file(path: &Path) -> bool {
+    matches_pattern(path, "bench")
+}
+
+/// Results stored by an earlier `--json` run, by benchmark name
+fn read_baseline(path: &str) -> Result<HashMap<String, BenchResult>, Box<dyn Error>> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read baseline {path}: {e}"))?;
+    let results: Vec<BenchResult> =
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid baseline {path}: {e}"))?;
+    Ok(results
+        .into_iter()
+        .map(|result| (result.name.clone(), result))
+        .collect())
+}
+
+fn print_results(results: &[BenchResult]) {
+    if results.is_empty() {
+        return;
+    }
+    let rows: Vec<[String; 6]> = results
+        .iter()
+        .map(|result| {
+            [
+                result.name.clone(),
+                format_ns(result.avg_ns),
+                format!("{:.0}", result.throughput),
+                format!(
+                    "({} … {})",
+                    format_ns(result.min_ns),
+                    format_ns(result.max_ns)
+                ),
+                format_ns(result.p75_ns),
+                format_ns(result.p99_ns),
+            ]
+        })
+        .collect();
+    let header = [
+        "benchmark",
+        "time/iter (avg)",
+        "iter/s",
+        "(min … max)",
+        "p75",
+        "p99",
+    ]
+    .map(String::from);
+    print_table(&header, &rows, |_, cell| cell.to_string());
+}
+
+/// Print the average time of each benchmark next to its baseline
+fn print_comparison(
+    baseline: &HashMap<String, BenchResult>,
+    results: &[BenchResult],
+    threshold: f64,
+) {
+    println!("{}", colors::bold("comparison with baseline"));
+    let rows: Vec<[String; 4]> = results
+        .iter()
+        .map(|result| {
+            let before = baseline.get(&result.name);
+            let change = before
+                .and_then(|before| change_percent(before.avg_ns, result.avg_ns))
+                .map_or_else(|| "new".to_string(), |change| format!("{change:+.1}%"));
+            [
+                result.name.clone(),
+                before.map_or_else(|| "-".to_string(), |before| format_ns(before.avg_ns)),
+                format_ns(result.avg_ns),
+                change,
+            ]
+        })
+        .collect();
+    let header = ["benchmark", "baseline", "current", "change"].map(String::from);
+    print_table(&header, &rows, |column, cell| {
+        if column != 3 {
+            return cell.to_string();
+        }
+        match cell.trim_end().trim_end_matches('%').parse::<f64>() {
+            Ok(change) if change > threshold => colors::red(cell).to_string(),
+            Ok(change) if change < -threshold => colors::green(cell).to_string(),
+            Ok(_) => cell.to_string(),
+            Err(_) => colors::gray(cell).to_string(),
+        }
+    });
+}
+
+/// Print rows with padded columns; `style` colors a padded cell by column
+fn print_table<const N: usize>(
+    header: &[String; N],
+    rows: &[[String; N]],
+    style: impl Fn(usize, &str) -> String,
+) {
+    let widths: Vec<usize> = (0..N)
+        .map(|column| {
+            rows.iter()
+                .chain(std::iter::once(header))
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let pad = |column: usize, cell: &str| {
+        let padding = widths[column] - cell.chars().count();
+        format!("{cell}{}", " ".repeat(padding))
+    };
+
+    let header_line: Vec<String> = (0..N).map(|column| pad(column, &header[column])).collect();
+    println!("{}", colors::gray(header_line.join("  ").trim_end()));
+    for row in rows {
+        let line: Vec<String> = (0..N)
+            .map(|column| style(column, &pad(column, &row[column])))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+/// Percentage change from `baseline` to `current`; positive means slower
+fn change_percent(baseline: f64, current: f64) -> Option<f64> {
+    (baseline > 0.0).then(|| (current - baseline) / baseline * 100.0)
+}
+
+/// Format a duration in nanoseconds with a readable unit
+fn format_ns(ns: f64) -> String {
+    if ns < 1e3 {
+        format!("{ns:.1} ns")
+    } else if ns < 1e6 {
+        format!("{:.1} µs", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.1} ms", ns / 1e6)
+    } else {
+        format!("{:.2} s", ns / 1e9)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_bench_file() {
+        assert!(is_bench_file(Path::new("src/parse_bench.ts")));
+        assert!(is_bench_file(Path::new("parse.bench.js")));
+        assert!(is_bench_file(Path::new("bench.ts")));
+        assert!(!is_bench_file(Path::new("parse_test.ts")));
+        assert!(!is_bench_file(Path::new("benchmark.ts")));
+    }
+
+    #[test]
+    fn test_format_ns() {
+        assert_eq!(format_ns(12.34), "12.3 ns");
+        assert_eq!(format_ns(1_500.0), "1.5 µs");
+        assert_eq!(format_ns(2_400_000.0), "2.4 ms");
+        assert_eq!(format_ns(3e9), "3.00 s");
+    }
+
+    #[test]
+    fn test_change_percent() {
+        assert_eq!(
+            format!("{:+.1}%", change_percent(100.0, 105.2).unwrap()),
+            "+5.2%"
+        );
+        assert_eq!(
+            format!("{:+.1}%", change_percent(100.0, 96.9).unwrap()),
+            "-3.1%"
+        );
+        assert_eq!(change_percent(0.0, 10.0), None);
+    }
+
+    #[test]
+    fn test_reported() {
+        let failed = r#"{"name":"a","error":"boom"}"#;
+        assert!(matches!(
+            serde_json::from_str(failed).unwrap(),
+            Reported::Failed { name, error } if name == "a" && error == "boom"
+        ));
+
+        let measured = r#"{"name":"b","warmupIterations":5,"totalIterations":10,"avgNs":2.0,
+            "minNs":1.0,"maxNs":3.0,"p75Ns":2.5,"p99Ns":3.0,"throughput":5e8}"#;
+        assert!(matches!(
+            serde_json::from_str(measured).unwrap(),
+            Reported::Measured(result)
+                if result.total_iterations == 10 && (result.p75_ns - 2.5).abs() < f64::EPSILON
+        ));
+    }
+}
@@ -5,7 +5,12 @@ use std::error::Error;
 use std::fs;
 use utils::SECTION_NAME;
 
-pub fn execute(file_path: &str, unstable: bool) -> Result<(), Box<dyn Error>> {
+pub fn execute(
+    file_path: &str,
+    unstable: bool,
+    check_integrity: bool,
+    tree_shake: bool,
+) -> Result<(), Box<dyn Error>> {
     // Convert file path to absolute canonical path
     let file_path_buf = std::path::Path::new(file_path);
     let absolute_file_path = if file_path_buf.is_absolute() {
@@ -29,7 +34,9 @@ pub fn execute(file_path: &str, unstable: bool) -> Result<(), Box<dyn Error>> {
     let entry_file_url = to_file_url(&canonical_file_path);
 
     // Use bundler to collect all modules
-    let mut bundler = bundler::ModuleBundler::new(unstable);
+    let mut bundler = bundler::ModuleBundler::new(unstable)
+        .with_check_integrity(check_integrity)
+        .with_tree_shake(tree_shake);
     let modules = match bundler.bundle(&canonical_file_path_str) {
         Ok(modules) => modules,
         Err(e) => {
@@ -0,0 +1,504 @@
+use crate::jsr::JsrResolver;
+use deno_terminal::colors;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+    Class, Declaration, ExportDefaultDeclarationKind, FormalParameters, Function, ModuleExportName,
+    Program, Statement,
+};
+use oxc_parser::Parser;
+use oxc_span::{GetSpan, SourceType, Span};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocNode {
+    pub name: String,
+    pub kind: &'static str,
+    pub signature: String,
+    pub description: String,
+    pub params: Vec<ParamDoc>,
+    pub returns: Option<String>,
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamDoc {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_annotation: Option<String>,
+    pub doc: Option<String>,
+}
+
+/// Parsed contents of a `/** ... */` comment
+#[derive(Debug, Default)]
+struct JsDoc {
+    description: String,
+    params: HashMap<String, String>,
+    returns: Option<String>,
+    examples: Vec<String>,
+}
+
+pub fn execute(target: &str, json: bool) -> Result<(), Box<dyn Error>> {
+    let mut documenter = if target.starts_with("jsr:") {
+        // Original TypeScript sources carry the type information
+        let (entry, sources) = JsrResolver::new().fetch_sources(target)?;
+        Documenter {
+            entry,
+            sources,
+            from_disk: false,
+            documented: HashMap::new(),
+        }
+    } else {
+        let path = fs::canonicalize(target).map_err(|e| format!("Failed to read {target}: {e}"))?;
+        Documenter {
+            entry: path.display().to_string(),
+            sources: HashMap::new(),
+            from_disk: true,
+            documented: HashMap::new(),
+        }
+    };
+
+    let entry = documenter.entry.clone();
+    let nodes = documenter.document_module(&entry, &mut HashSet::new())?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&nodes)?);
+    } else {
+        print_nodes(&nodes);
+    }
+
+    Ok(())
+}
+
+struct Documenter {
+    entry: String,
+    sources: HashMap<String, String>,
+    from_disk: bool,
+    /// Exports of modules already documented, reused by later re-exports
+    documented: HashMap<String, Vec<DocNode>>,
+}
+
+impl Documenter {
+    fn load(&mut self, path: &str) -> Result<String, String> {
+        if let Some(source) = self.sources.get(path) {
+            return Ok(source.clone());
+        }
+        if !self.from_disk {
+            return Err(format!("Module not found: {path}"));
+        }
+        let source = fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+        self.sources.insert(path.to_string(), source.clone());
+        Ok(source)
+    }
+
+    /// Collect documentation for every export of a module, following re-exports
+    fn document_module(
+        &mut self,
+        path: &str,
+        visiting: &mut HashSet<String>,
+    ) -> Result<Vec<DocNode>, String> {
+        if let Some(nodes) = self.documented.get(path) {
+            return Ok(nodes.clone());
+        }
+        // Cyclic re-exports contribute nothing the second time around
+        if !visiting.insert(path.to_string()) {
+            return Ok(Vec::new());
+        }
+
+        let source = self.load(path)?;
+        let allocator = Allocator::default();
+        let source_type = SourceType::from_path(path).unwrap_or_else(|_| SourceType::ts());
+        let parser_ret = Parser::new(&allocator, &source, source_type).parse();
+        if let Some(error) = parser_ret.errors.first() {
+            return Err(format!("Failed to parse {path}: {error}"));
+        }
+        let program = &parser_ret.program;
+
+        // Non-exported declarations, for `export { foo }` lists
+        let mut locals: HashMap<String, DocNode> = HashMap::new();
+        let mut nodes = Vec::new();
+
+        for stmt in &program.body {
+            let jsdoc = leading_jsdoc(program, &source, stmt.span());
+            match stmt {
+                Statement::ExportNamedDeclaration(export) => {
+                    if let Some(declaration) = &export.declaration {
+                        nodes.extend(document_declaration(declaration, &source, &jsdoc));
+                    } else if let Some(module) = &export.source {
+                        let target = self.resolve(path, module.value.as_str());
+                        let exported = self.document_module(&target, visiting)?;
+                        for specifier in &export.specifiers {
+                            let local = export_name(&specifier.local);
+                            if let Some(node) = exported.iter().find(|node| node.name == local) {
+                                let mut node = node.clone();
+                                node.name = export_name(&specifier.exported);
+                                nodes.push(node);
+                            }
+                        }
+                    } else {
+                        for specifier in &export.specifiers {
+                            if let Some(node) = locals.get(&export_name(&specifier.local)) {
+                                let mut node = node.clone();
+                                node.name = export_name(&specifier.exported);
+                                nodes.push(node);
+                            }
+                        }
+                    }
+                }
+                Statement::ExportAllDeclaration(export) => {
+                    let target = self.resolve(path, export.source.value.as_str());
+                    let exported = self.document_module(&target, visiting)?;
+                    match &export.exported {
+                        // export * as ns from "./mod.ts"
+                        Some(name) => nodes.push(DocNode {
+                            name: export_name(name),
+                            kind: "namespace",
+                            signature: format!("namespace {}", export_name(name)),
+                            description: jsdoc.description.clone(),
+                            params: Vec::new(),
+                            returns: None,
+                            examples: jsdoc.examples.clone(),
+                        }),
+                        None => nodes.extend(exported),
+                    }
+                }
+                Statement::ExportDefaultDeclaration(export) => {
+                    let node = match &export.declaration {
+                        ExportDefaultDeclarationKind::FunctionDeclaration(function) => {
+                            Some(document_function("default", function, &source, &jsdoc))
+                        }
+                        ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                            Some(document_class("default", class, &source, &jsdoc))
+                        }
+                        _ => None,
+                    };
+                    nodes.extend(node);
+                }
+                _ => {
+                    if let Some(declaration) = stmt.as_declaration() {
+                        for node in document_declaration(declaration, &source, &jsdoc) {
+                            locals.insert(node.name.clone(), node);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.documented.insert(path.to_string(), nodes.clone());
+        Ok(nodes)
+    }
+
+    /// Resolve a relative import against the importing module
+    fn resolve(&self, from: &str, specifier: &str) -> String {
+        let base = Path::new(from).parent().unwrap_or(Path::new(""));
+        let mut resolved = normalize(&base.join(specifier));
+        if self.from_disk {
+            return resolved.display().to_string();
+        }
+        // JSR packages import sibling modules with .js extensions
+        if resolved.extension().is_some_and(|ext| ext == "js") {
+            resolved.set_extension("ts");
+        }
+        resolved
+            .iter()
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::IdentifierName(ident) => ident.name.to_string(),
+        ModuleExportName::IdentifierReference(ident) => ident.name.to_string(),
+        ModuleExportName::StringLiteral(literal) => literal.value.to_string(),
+    }
+}
+
+fn document_declaration(declaration: &Declaration, source: &str, jsdoc: &JsDoc) -> Vec<DocNode> {
+    match declaration {
+        Declaration::FunctionDeclaration(function) => {
+            let name = function
+                .id
+                .as_ref()
+                .map_or_else(|| "default".to_string(), |id| id.name.to_string());
+            vec![document_function(&name, function, source, jsdoc)]
+        }
+        Declaration::ClassDeclaration(class) => {
+            let name = class
+                .id
+                .as_ref()
+                .map_or_else(|| "default".to_string(), |id| id.name.to_string());
+            vec![document_class(&name, class, source, jsdoc)]
+        }
+        Declaration::VariableDeclaration(variable) => variable
+            .declarations
+            .iter()
+            .filter_map(|declarator| {
+                let name = declarator.id.get_identifier_name()?.to_string();
+                let type_annotation = declarator
+                    .type_annotation
+                    .as_ref()
+                    .map(|annotation| slice(source, annotation.type_annotation.span()));
+                let signature = match &type_annotation {
+                    Some(ty) => format!("{} {name}: {ty}", variable.kind.as_str()),
+                    None => format!("{} {name}", variable.kind.as_str()),
+                };
+                Some(node(&name, variable.kind.as_str(), signature, jsdoc))
+            })
+            .collect(),
+        Declaration::TSTypeAliasDeclaration(alias) => {
+            let signature = slice(source, alias.span);
+            vec![node(&alias.id.name, "type", signature, jsdoc)]
+        }
+        Declaration::TSInterfaceDeclaration(interface) => {
+            let signature = format!("interface {}", interface.id.name);
+            vec![node(&interface.id.name, "interface", signature, jsdoc)]
+        }
+        Declaration::TSEnumDeclaration(declaration) => {
+            let signature = format!("enum {}", declaration.id.name);
+            vec![node(&declaration.id.name, "enum", signature, jsdoc)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn node(name: &str, kind: &'static str, signature: String, jsdoc: &JsDoc) -> DocNode {
+    DocNode {
+        name: name.to_string(),
+        kind,
+        signature,
+        description: jsdoc.description.clone(),
+        params: Vec::new(),
+        returns: jsdoc.returns.clone(),
+        examples: jsdoc.examples.clone(),
+    }
+}
+
+fn document_function(name: &str, function: &Function, source: &str, jsdoc: &JsDoc) -> DocNode {
+    // Everything from the name up to the body is the signature
+    let end = function
+        .body
+        .as_ref()
+        .map_or(function.span.end, |body| body.span.start);
+    let start = function
+        .id
+        .as_ref()
+        .map_or(function.params.span.start, |id| id.span.start);
+    let mut signature = String::from("function ");
+    if function.id.is_none() {
+        signature.push_str(name);
+    }
+    signature.push_str(slice(source, Span::new(start, end)).trim_end());
+
+    let mut doc = node(name, "function", signature, jsdoc);
+    doc.params = document_params(&function.params, source, jsdoc);
+    doc
+}
+
+fn document_class(name: &str, class: &Class, source: &str, jsdoc: &JsDoc) -> DocNode {
+    let signature = slice(source, Span::new(class.span.start, class.body.span.start))
+        .trim_start_matches("export ")
+        .trim_start_matches("default ")
+        .trim_end()
+        .to_string();
+    node(name, "class", signature, jsdoc)
+}
+
+fn document_params(params: &FormalParameters, source: &str, jsdoc: &JsDoc) -> Vec<ParamDoc> {
+    let mut docs: Vec<ParamDoc> = params
+        .items
+        .iter()
+        .map(|param| {
+            let name = param.pattern.get_identifier_name().map_or_else(
+                || slice(source, param.pattern.span()),
+                |name| name.to_string(),
+            );
+            ParamDoc {
+                doc: jsdoc.params.get(&name).cloned(),
+                type_annotation: param
+                    .type_annotation
+                    .as_ref()
+                    .map(|annotation| slice(source, annotation.type_annotation.span())),
+                name,
+            }
+        })
+        .collect();
+
+    if let Some(rest) = &params.rest {
+        let name = rest
+            .rest
+            .argument
+            .get_identifier_name()
+            .map_or_else(|| "rest".to_string(), |name| name.to_string());
+        docs.push(ParamDoc {
+            doc: jsdoc.params.get(&name).cloned(),
+            type_annotation: rest
+                .type_annotation
+                .as_ref()
+                .map(|annotation| slice(source, annotation.type_annotation.span())),
+            name: format!("...{name}"),
+        });
+    }
+
+    docs
+}
+
+fn slice(source: &str, span: Span) -> String {
+    source
+        .get(span.start as usize..span.end as usize)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Find the doc comment directly attached to a statement
+fn leading_jsdoc(program: &Program, source: &str, span: Span) -> JsDoc {
+    program
+        .comments
+        .iter()
+        .rev()
+        .find(|comment| comment.is_jsdoc() && comment.attached_to == span.start)
+        .map(|comment| parse_jsdoc(&slice(source, comment.content_span())))
+        .unwrap_or_default()
+}
+
+fn parse_jsdoc(content: &str) -> JsDoc {
+    // Strip the leading `*` decoration from each line
+    let lines: Vec<&str> = content
+        .lines()
+        .map(|line| {
+            let line = line.trim_start();
+            line.strip_prefix("* ")
+                .or_else(|| line.strip_prefix('*'))
+                .unwrap_or(line)
+        })
+        .collect();
+
+    let mut jsdoc = JsDoc::default();
+    let mut description = Vec::new();
+    // The tag currently being collected, with its lines
+    let mut current: Option<(String, Vec<String>)> = None;
+
+    for line in lines {
+        if let Some(tag_line) = line.trim_start().strip_prefix('@') {
+            if let Some((tag, body)) = current.take() {
+                apply_tag(&mut jsdoc, &tag, &body);
+            }
+            let (tag, rest) = tag_line
+                .split_once(char::is_whitespace)
+                .unwrap_or((tag_line, ""));
+            current = Some((tag.to_string(), vec![rest.to_string()]));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push(line.to_string());
+        } else {
+            description.push(line.trim());
+        }
+    }
+    if let Some((tag, body)) = current.take() {
+        apply_tag(&mut jsdoc, &tag, &body);
+    }
+
+    jsdoc.description = description.join("\n").trim().to_string();
+    jsdoc
+}
+
+fn apply_tag(jsdoc: &mut JsDoc, tag: &str, body: &[String]) {
+    match tag {
+        "param" => {
+            let text = body.join(" ");
+            let mut text = text.trim();
+            // Skip an optional `{type}`
+            if text.starts_with('{')
+                && let Some(end) = text.find('}')
+            {
+                text = text[end + 1..].trim_start();
+            }
+            let (name, doc) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+            let name = name.trim_matches(|c| c == '[' || c == ']');
+            let name = name.split('=').next().unwrap_or(name);
+            let doc = doc.trim().trim_start_matches('-').trim();
+            jsdoc.params.insert(name.to_string(), doc.to_string());
+        }
+        "returns" | "return" => {
+            jsdoc.returns = Some(body.join(" ").trim().to_string());
+        }
+        "example" => {
+            jsdoc.examples.push(body.join("\n").trim().to_string());
+        }
+        _ => {}
+    }
+}
+
+fn print_nodes(nodes: &[DocNode]) {
+    if nodes.is_empty() {
+        println!("No documentation found");
+        return;
+    }
+
+    for node in nodes {
+        println!("{}", colors::cyan(&node.signature));
+        if !node.description.is_empty() {
+            for line in node.description.lines() {
+                println!("  {line}");
+            }
+        }
+
+        let documented: Vec<&ParamDoc> = node.params.iter().filter(|p| p.doc.is_some()).collect();
+        if !documented.is_empty() {
+            println!();
+            for param in documented {
+                println!(
+                    "  {} {} {}",
+                    colors::magenta("@param"),
+                    param.name,
+                    param.doc.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        if let Some(returns) = &node.returns {
+            println!("  {} {returns}", colors::magenta("@returns"));
+        }
+        for example in &node.examples {
+            println!();
+            println!("  {}", colors::magenta("@example"));
+            for line in example.lines() {
+                println!("    {line}");
+            }
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jsdoc_tags() {
+        let jsdoc = parse_jsdoc(
+            "*\n * Add two numbers.\n *\n * @param a - First number\n * @param {number} b Second\n * @returns The sum\n * @example\n * ```ts\n * add(1, 2);\n * ```\n ",
+        );
+        assert_eq!(jsdoc.description, "Add two numbers.");
+        assert_eq!(jsdoc.params["a"], "First number");
+        assert_eq!(jsdoc.params["b"], "Second");
+        assert_eq!(jsdoc.returns.as_deref(), Some("The sum"));
+        assert_eq!(jsdoc.examples, vec!["```ts\nadd(1, 2);\n```"]);
+    }
+}
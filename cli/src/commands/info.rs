@@ -0,0 +1,261 @@
+use crate::bundler::ModuleBundler;
+use crate::error_fmt::format_error_chain;
+use deno_terminal::colors;
+use mdeno_path_util::to_file_url;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// What `mdeno info --json` prints
+#[derive(Debug, Serialize)]
+struct Info {
+    roots: Vec<String>,
+    modules: Vec<ModuleInfo>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModuleInfo {
+    specifier: String,
+    /// Size in bytes of the file the module was read from
+    size: u64,
+    media_type: &'static str,
+    /// The file the module was read from, the cache file for JSR modules
+    local: Option<String>,
+    /// Resolved version of a JSR module
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Serialize)]
+struct Dependency {
+    specifier: String,
+    /// The module the specifier resolves to, unless it's a built-in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved: Option<String>,
+}
+
+/// Print the dependency tree of a module
+pub fn execute(target: &str, check_integrity: bool, json: bool) -> Result<(), Box<dyn Error>> {
+    // Inspecting a module graph runs nothing, so JSR imports don't need --unstable
+    let mut bundler = ModuleBundler::new(true)
+        .with_check_integrity(check_integrity)
+        .with_tree_shake(false);
+
+    let root = if target.starts_with("jsr:") {
+        if let Err(e) = bundler.bundle_jsr(target) {
+            let error_chain = format_error_chain(e.as_ref());
+            return Err(format!("Import '{target}' failed.{error_chain}").into());
+        }
+        target.to_string()
+    } else {
+        let path =
+            fs::canonicalize(target).map_err(|e| format!("Module not found \"{target}\": {e}"))?;
+        let root = to_file_url(&path);
+        if let Err(e) = bundler.bundle(&path.display().to_string()) {
+            let error_chain = format_error_chain(e.as_ref());
+            return Err(format!("Import '{root}' failed.{error_chain}").into());
+        }
+        root
+    };
+
+    let modules = collect_modules(&bundler, &root);
+    if json {
+        let info = Info {
+            roots: vec![root],
+            modules,
+        };
+        println!("{}", serde_json::to_string_pretty(&info)?);
+    } else {
+        print_info(&modules, &root);
+    }
+
+    Ok(())
+}
+
+/// Every module reachable from `root`, in breadth-first order
+fn collect_modules(bundler: &ModuleBundler, root: &str) -> Vec<ModuleInfo> {
+    let mut modules = Vec::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([root.to_string()]);
+
+    while let Some(specifier) = queue.pop_front() {
+        if !seen.insert(specifier.clone()) {
+            continue;
+        }
+        let dependencies = bundler
+            .dependencies(&specifier)
+            .into_iter()
+            .map(|(specifier, resolved)| {
+                queue.extend(resolved.clone());
+                Dependency {
+                    specifier,
+                    resolved,
+                }
+            })
+            .collect();
+        let local = bundler.local_path(&specifier);
+        modules.push(ModuleInfo {
+            size: local
+                .and_then(|path| fs::metadata(path).ok())
+                .map_or(0, |metadata| metadata.len()),
+            media_type: media_type(local.unwrap_or(Path::new(&specifier))),
+            local: local.map(|path| path.display().to_string()),
+            version: jsr_version(&specifier),
+            dependencies,
+            specifier,
+        });
+    }
+
+    modules
+}
+
+fn print_info(modules: &[ModuleInfo], root: &str) {
+    let by_specifier: HashMap<&str, &ModuleInfo> = modules
+        .iter()
+        .map(|module| (module.specifier.as_str(), module))
+        .collect();
+    let Some(root) = by_specifier.get(root) else {
+        return;
+    };
+
+    println!(
+        "{} {}",
+        colors::bold("local:"),
+        root.local.as_deref().unwrap_or(&root.specifier)
+    );
+    println!("{} {}", colors::bold("type:"), root.media_type);
+    println!(
+        "{} {}",
+        colors::bold("size:"),
+        format_size(modules.iter().map(|module| module.size).sum())
+    );
+    println!(
+        "{} {} unique",
+        colors::bold("dependencies:"),
+        modules.len() - 1
+    );
+
+    let mut printed = HashSet::from([root.specifier.as_str()]);
+    print_dependencies(root, &by_specifier, "  ", &mut printed);
+}
+
+/// Print the imports of `module` as a tree; modules shown before are marked
+/// with `*` rather than expanded again
+fn print_dependencies<'a>(
+    module: &ModuleInfo,
+    by_specifier: &HashMap<&str, &'a ModuleInfo>,
+    prefix: &str,
+    printed: &mut HashSet<&'a str>,
+) {
+    let count = module.dependencies.len();
+    for (index, dependency) in module.dependencies.iter().enumerate() {
+        let last = index + 1 == count;
+        let branch = if last { "└─" } else { "├─" };
+        let child = dependency
+            .resolved
+            .as_deref()
+            .and_then(|resolved| by_specifier.get(resolved));
+
+        let Some(child) = child else {
+            // Built-in modules such as node:process
+            println!("{prefix}{branch} {}", dependency.specifier);
+            continue;
+        };
+        // Relative imports inside a JSR package read better by their full specifier
+        let name = if child.specifier.starts_with("jsr:") {
+            &child.specifier
+        } else {
+            &dependency.specifier
+        };
+        let expand = printed.insert(child.specifier.as_str());
+        let repeated = if expand { "" } else { " *" };
+        println!(
+            "{prefix}{branch} {name} {}",
+            colors::gray(&format!("{}{repeated}", details(child)))
+        );
+        if expand {
+            let indent = if last { "   " } else { "│  " };
+            print_dependencies(child, by_specifier, &format!("{prefix}{indent}"), printed);
+        }
+    }
+}
+
+/// Media type, size and, for JSR modules, version and cache file
+fn details(module: &ModuleInfo) -> String {
+    let mut parts = vec![module.media_type.to_string(), format_size(module.size)];
+    if let Some(version) = &module.version {
+        parts.push(format!("version {version}"));
+        parts.extend(module.local.clone());
+    }
+    format!("({})", parts.join(", "))
+}
+
+/// How the module is loaded, from its file extension
+fn media_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("ts" | "mts" | "cts") => "TypeScript",
+        Some("tsx") => "TSX",
+        Some("jsx") => "JSX",
+        _ => "JavaScript",
+    }
+}
+
+/// The version of a `jsr:@scope/name@version/path` specifier
+fn jsr_version(specifier: &str) -> Option<String> {
+    let package = specifier.strip_prefix("jsr:@")?.split('/').nth(1)?;
+    let (_, version) = package.split_once('@')?;
+    Some(version.to_string())
+}
+
+#[allow(clippy::cast_precision_loss)] // Sizes are only shown with two decimals
+fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let size = bytes as f64;
+    if size < KB {
+        format!("{bytes}B")
+    } else if size < KB * KB {
+        format!("{:.2}KB", size / KB)
+    } else {
+        format!("{:.2}MB", size / (KB * KB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsr_version() {
+        assert_eq!(
+            jsr_version("jsr:@std/assert@1.0.0/assert_equals").as_deref(),
+            Some("1.0.0")
+        );
+        assert_eq!(
+            jsr_version("jsr:@std/assert@1.0.0").as_deref(),
+            Some("1.0.0")
+        );
+        assert_eq!(jsr_version("file:///script.ts"), None);
+    }
+
+    #[test]
+    fn test_media_type() {
+        assert_eq!(media_type(Path::new("/a/script.ts")), "TypeScript");
+        assert_eq!(media_type(Path::new("/a/app.tsx")), "TSX");
+        assert_eq!(media_type(Path::new("/a/mod.js")), "JavaScript");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(312), "312B");
+        assert_eq!(format_size(1536), "1.50KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.00MB");
+    }
+}
@@ -1,4 +1,11 @@
+pub mod bench;
 pub mod compile;
+pub mod doc;
 pub mod eval;
+pub mod info;
+pub mod repl;
 pub mod run;
+pub mod task;
 pub mod test;
+pub mod upgrade;
+pub mod versions;
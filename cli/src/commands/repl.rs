@@ -0,0 +1,48 @@
+use mdeno_runtime::ReplSession;
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+pub fn execute() -> Result<(), Box<dyn Error>> {
+    let session = ReplSession::new()?;
+
+    println!("mdeno {}", env!("CARGO_PKG_VERSION"));
+    println!("exit using ctrl+d, ctrl+c, or close()");
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    let mut lines = stdin.lock().lines();
+
+    // Lines read so far of an input that isn't complete yet
+    let mut input = String::new();
+    loop {
+        print!("{}", if input.is_empty() { "> " } else { "... " });
+        stdout.flush()?;
+
+        let Some(line) = lines.next() else {
+            // EOF (ctrl+d)
+            println!();
+            break;
+        };
+        let line = line?;
+
+        if input.is_empty() {
+            let code = line.trim();
+            if code.is_empty() {
+                continue;
+            }
+            if code == "close()" {
+                break;
+            }
+        }
+
+        input.push_str(&line);
+        input.push('\n');
+        if ReplSession::is_incomplete(&input) {
+            continue;
+        }
+        println!("{}", session.eval(input.trim_end())?);
+        input.clear();
+    }
+
+    Ok(())
+}
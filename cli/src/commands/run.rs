@@ -4,7 +4,12 @@ use mdeno_path_util::to_file_url;
 use std::error::Error;
 use std::fs;
 
-pub fn execute(file_path: &str, unstable: bool) -> Result<(), Box<dyn Error>> {
+pub fn execute(
+    file_path: &str,
+    unstable: bool,
+    check_integrity: bool,
+    tree_shake: bool,
+) -> Result<(), Box<dyn Error>> {
     // Convert file path to absolute canonical path
     let file_path_buf = std::path::Path::new(file_path);
     let absolute_file_path = if file_path_buf.is_absolute() {
@@ -28,7 +33,9 @@ pub fn execute(file_path: &str, unstable: bool) -> Result<(), Box<dyn Error>> {
     let entry_file_url = to_file_url(&canonical_file_path);
 
     // Use bundler to collect all modules
-    let mut bundler = bundler::ModuleBundler::new(unstable);
+    let mut bundler = bundler::ModuleBundler::new(unstable)
+        .with_check_integrity(check_integrity)
+        .with_tree_shake(tree_shake);
     let modules = match bundler.bundle(&canonical_file_path_str) {
         Ok(modules) => modules,
         Err(e) => {
@@ -37,6 +44,8 @@ pub fn execute(file_path: &str, unstable: bool) -> Result<(), Box<dyn Error>> {
         }
     };
 
+    mdeno_runtime::register_source_maps(bundler.source_maps(&modules));
+
     // Run mode: compile to bytecode and execute
     let bytecode = mdeno_runtime::compile_modules(modules, entry_file_url)?;
     mdeno_runtime::run_bytecode(&bytecode)?;
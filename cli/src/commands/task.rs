@@ -0,0 +1,639 @@
+use deno_terminal::colors;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const CONFIG_FILE_NAME: &str = "deno.json";
+
+#[derive(Debug)]
+struct TaskDefinition {
+    command: String,
+    description: Option<String>,
+    dependencies: Vec<String>,
+    env: HashMap<String, String>,
+}
+
+/// How a command segment is joined to the previous one
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    And,
+    Or,
+}
+
+pub fn execute(
+    name: Option<&str>,
+    args: &[String],
+    env_file: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let cwd = std::env::current_dir()?;
+    let config_path = find_config(&cwd)
+        .ok_or_else(|| format!("No {CONFIG_FILE_NAME} found in {}", cwd.display()))?;
+    let tasks = load_tasks(&config_path)?;
+
+    let Some(name) = name else {
+        print_tasks(&tasks);
+        return Ok(());
+    };
+
+    // Variables from --env-file apply to every task, below each task's own env
+    let env = match env_file {
+        Some(path) => {
+            let content =
+                fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+            parse_env_file(&content)
+                .map_err(|e| format!("Failed to parse {path}: {e}"))?
+                .into_iter()
+                .collect()
+        }
+        None => HashMap::new(),
+    };
+
+    // Tasks run from the directory containing the config file
+    let task_dir = config_path.parent().unwrap_or(&cwd);
+    let code = run_task(name, args, &tasks, task_dir, &env, &mut Vec::new())?;
+    if code != 0 {
+        std::process::exit(code);
+    }
+
+    Ok(())
+}
+
+/// Find deno.json in `start` or any of its ancestors
+fn find_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+fn load_tasks(config_path: &Path) -> Result<Vec<(String, TaskDefinition)>, Box<dyn Error>> {
+    let content = fs::read_to_string(config_path)?;
+    let config: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {e}", config_path.display()))?;
+
+    let Some(tasks) = config.get("tasks").and_then(Value::as_object) else {
+        return Ok(Vec::new());
+    };
+
+    tasks
+        .iter()
+        .map(|(name, value)| Ok((name.clone(), parse_task(name, value)?)))
+        .collect()
+}
+
+fn parse_task(name: &str, value: &Value) -> Result<TaskDefinition, Box<dyn Error>> {
+    // "name": "command" shorthand
+    if let Some(command) = value.as_str() {
+        return Ok(TaskDefinition {
+            command: command.to_string(),
+            description: None,
+            dependencies: Vec::new(),
+            env: HashMap::new(),
+        });
+    }
+
+    let object = value
+        .as_object()
+        .ok_or_else(|| format!("Task \"{name}\" must be a string or an object"))?;
+
+    let command = object
+        .get("command")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let description = object
+        .get("description")
+        .and_then(Value::as_str)
+        .map(ToString::to_string);
+    let dependencies = object
+        .get("dependencies")
+        .and_then(Value::as_array)
+        .map(|deps| {
+            deps.iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let env = object
+        .get("env")
+        .and_then(Value::as_object)
+        .map(|env| {
+            env.iter()
+                .map(|(key, value)| {
+                    let value = value
+                        .as_str()
+                        .map_or_else(|| value.to_string(), ToString::to_string);
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(TaskDefinition {
+        command,
+        description,
+        dependencies,
+        env,
+    })
+}
+
+fn print_tasks(tasks: &[(String, TaskDefinition)]) {
+    if tasks.is_empty() {
+        eprintln!("No tasks found in configuration file");
+        return;
+    }
+
+    println!("{}", colors::green("Available tasks:"));
+    for (name, task) in tasks {
+        println!("- {}", colors::cyan(name));
+        if let Some(description) = &task.description {
+            println!("    {description}");
+        }
+        if !task.command.is_empty() {
+            println!("    {}", colors::gray(&task.command));
+        }
+    }
+}
+
+/// Run a task (and its dependencies), returning the exit code
+fn run_task(
+    name: &str,
+    args: &[String],
+    tasks: &[(String, TaskDefinition)],
+    task_dir: &Path,
+    inherited_env: &HashMap<String, String>,
+    stack: &mut Vec<String>,
+) -> Result<i32, Box<dyn Error>> {
+    let task = tasks
+        .iter()
+        .find(|(task_name, _)| task_name == name)
+        .map(|(_, task)| task)
+        .ok_or_else(|| format!("Task not found: {name}"))?;
+
+    if stack.iter().any(|running| running == name) {
+        return Err(format!("Task cycle detected: {} -> {name}", stack.join(" -> ")).into());
+    }
+    stack.push(name.to_string());
+
+    let mut env = inherited_env.clone();
+    env.extend(task.env.clone());
+
+    for dependency in &task.dependencies {
+        let code = run_task(dependency, &[], tasks, task_dir, &env, stack)?;
+        if code != 0 {
+            stack.pop();
+            return Ok(code);
+        }
+    }
+
+    let mut code = 0;
+    if !task.command.is_empty() {
+        // The shell expands variables from `env` itself; cmd.exe has no `$VAR`
+        // syntax, so those are expanded here
+        let command = if cfg!(windows) {
+            append_args(&expand_vars(&task.command, &env), args)
+        } else {
+            append_args(&task.command, args)
+        };
+        eprintln!("{} {} {command}", colors::green("Task"), colors::cyan(name));
+
+        let mut status = None;
+        for (operator, segment) in split_command(&command) {
+            let should_run = match (operator, status) {
+                (_, None) | (Some(Operator::And), Some(0)) => true,
+                (Some(Operator::Or), Some(last)) => last != 0,
+                _ => false,
+            };
+            if should_run {
+                status = Some(run_segment(&segment, tasks, task_dir, &env, stack)?);
+            }
+        }
+        code = status.unwrap_or(0);
+    }
+
+    stack.pop();
+    Ok(code)
+}
+
+/// Run one command segment, either as a nested task or through the shell
+fn run_segment(
+    segment: &str,
+    tasks: &[(String, TaskDefinition)],
+    task_dir: &Path,
+    env: &HashMap<String, String>,
+    stack: &mut Vec<String>,
+) -> Result<i32, Box<dyn Error>> {
+    if let Some((name, args)) = parse_task_reference(segment) {
+        return run_task(&name, &args, tasks, task_dir, env, stack);
+    }
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd.exe");
+        command.arg("/C").arg(segment);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(segment);
+        command
+    };
+
+    let status = command.current_dir(task_dir).envs(env).status()?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Recognize `mdeno task <name>` and `mdeno task:<name>` references to other tasks
+fn parse_task_reference(segment: &str) -> Option<(String, Vec<String>)> {
+    let rest = segment.trim().strip_prefix("mdeno ")?.trim_start();
+    let rest = rest
+        .strip_prefix("task:")
+        .or_else(|| rest.strip_prefix("task "))?;
+
+    let mut words = rest.split_whitespace().map(ToString::to_string);
+    let name = words.next()?;
+    let args = words.filter(|word| word != "--").collect();
+    Some((name, args))
+}
+
+/// Replace `$VAR`, `${VAR}` and `${VAR:-default}` with values from `env`, then
+/// the process environment. Unset variables expand to an empty string, the
+/// default being used when one is unset or empty. Like a shell, text in
+/// single quotes and `\$` are left alone. Values are quoted so that they
+/// can't add operators or arguments to the command.
+fn expand_vars(command: &str, env: &HashMap<String, String>) -> String {
+    let lookup = |name: &str| {
+        env.get(name)
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+            .unwrap_or_default()
+    };
+
+    let push_value = |expanded: &mut String, value: &str, in_double_quotes: bool| {
+        if in_double_quotes {
+            expanded.push_str(&value.replace('"', "\\\""));
+        } else if !value.is_empty() {
+            expanded.push_str(&quote_arg(value));
+        }
+    };
+
+    let mut expanded = String::new();
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+    let mut rest = command;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '\\' if !in_single_quotes => {
+                expanded.push(c);
+                if let Some(escaped) = rest.chars().next() {
+                    expanded.push(escaped);
+                    rest = &rest[escaped.len_utf8()..];
+                }
+            }
+            '\'' if !in_double_quotes => {
+                in_single_quotes = !in_single_quotes;
+                expanded.push(c);
+            }
+            '"' if !in_single_quotes => {
+                in_double_quotes = !in_double_quotes;
+                expanded.push(c);
+            }
+            '$' if !in_single_quotes => {
+                if let Some(braced) = rest.strip_prefix('{')
+                    && let Some(end) = braced.find('}')
+                {
+                    let (name, default) = match braced[..end].split_once(":-") {
+                        Some((name, default)) => (name, Some(default)),
+                        None => (&braced[..end], None),
+                    };
+                    let value = lookup(name);
+                    match default {
+                        Some(default) if value.is_empty() => {
+                            expanded.push_str(&expand_vars(default, env));
+                        }
+                        _ => push_value(&mut expanded, &value, in_double_quotes),
+                    }
+                    rest = &braced[end + 1..];
+                    continue;
+                }
+                let len = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(rest.len());
+                if len == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+                    // `$1`, `$?` and a lone `$` are left for the shell
+                    expanded.push(c);
+                } else {
+                    push_value(&mut expanded, &lookup(&rest[..len]), in_double_quotes);
+                    rest = &rest[len..];
+                }
+            }
+            _ => expanded.push(c),
+        }
+    }
+    expanded
+}
+
+/// Parse a `.env` file of `KEY=VALUE` lines. Blank lines and `#` comments
+/// are skipped and an `export ` prefix is allowed. Double-quoted values
+/// understand `\n`, `\t`, `\"` and `\\`, single-quoted values are taken
+/// as is, and both may span lines. An unquoted value ends at a ` #` comment
+/// and continues on the next line when it ends with a backslash.
+fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+    let mut lines = content.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("expected KEY=VALUE on line {}", index + 1))?;
+        let key = key.trim();
+        if key.is_empty()
+            || key.starts_with(|c: char| c.is_ascii_digit())
+            || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!(
+                "invalid variable name on line {}: {key}",
+                index + 1
+            ));
+        }
+
+        let value = value.trim_start();
+        let value = if let Some(quote @ ('"' | '\'')) = value.chars().next() {
+            let mut raw = value[1..].to_string();
+            loop {
+                if let Some(end) = closing_quote(&raw, quote) {
+                    raw.truncate(end);
+                    break;
+                }
+                let (_, next) = lines
+                    .next()
+                    .ok_or_else(|| format!("unterminated quoted value on line {}", index + 1))?;
+                raw.push('\n');
+                raw.push_str(next);
+            }
+            if quote == '"' { unescape(&raw) } else { raw }
+        } else {
+            let mut value = strip_comment(value).to_string();
+            while let Some(continued) = value.strip_suffix('\\') {
+                value = continued.to_string();
+                let Some((_, next)) = lines.next() else {
+                    break;
+                };
+                value.push('\n');
+                value.push_str(strip_comment(next.trim()));
+            }
+            value
+        };
+        vars.push((key.to_string(), value));
+    }
+    Ok(vars)
+}
+
+/// Byte offset of the quote closing a value, skipping escaped double quotes
+fn closing_quote(raw: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (offset, c) in raw.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quote == '"' => escaped = true,
+            _ if c == quote => return Some(offset),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape(raw: &str) -> String {
+    let mut value = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some(escaped @ ('"' | '\\')) => value.push(escaped),
+            Some(other) => {
+                value.push('\\');
+                value.push(other);
+            }
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
+/// An unquoted value up to a `#` comment that follows whitespace
+fn strip_comment(value: &str) -> &str {
+    let end = value
+        .char_indices()
+        .find(|&(offset, c)| {
+            c == '#' && (offset == 0 || value[..offset].ends_with(char::is_whitespace))
+        })
+        .map_or(value.len(), |(offset, _)| offset);
+    value[..end].trim_end()
+}
+
+fn append_args(command: &str, args: &[String]) -> String {
+    if args.is_empty() {
+        return command.to_string();
+    }
+    let quoted: Vec<String> = args.iter().map(|arg| quote_arg(arg)).collect();
+    format!("{command} {}", quoted.join(" "))
+}
+
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c))
+    {
+        arg.to_string()
+    } else if cfg!(windows) {
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Split a command line on top-level `&&` and `||`, ignoring quoted text
+fn split_command(command: &str) -> Vec<(Option<Operator>, String)> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut operator = None;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, '&' | '|') if chars.peek() == Some(&c) => {
+                chars.next();
+                segments.push((operator, current.trim().to_string()));
+                current.clear();
+                operator = Some(if c == '&' {
+                    Operator::And
+                } else {
+                    Operator::Or
+                });
+            }
+            _ => current.push(c),
+        }
+    }
+    segments.push((operator, current.trim().to_string()));
+
+    segments
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command_operators() {
+        let segments = split_command("a && b || c");
+        assert_eq!(
+            segments,
+            vec![
+                (None, "a".to_string()),
+                (Some(Operator::And), "b".to_string()),
+                (Some(Operator::Or), "c".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_command_keeps_quoted_and_pipes() {
+        let segments = split_command("echo 'a && b' | cat");
+        assert_eq!(segments, vec![(None, "echo 'a && b' | cat".to_string())]);
+    }
+
+    #[test]
+    fn test_expand_vars() {
+        let env = HashMap::from([
+            ("MDENO_TASK_TARGET".to_string(), "web".to_string()),
+            ("MDENO_TASK_EMPTY".to_string(), String::new()),
+        ]);
+        assert_eq!(
+            expand_vars(
+                "TARGET=$MDENO_TASK_TARGET build ${MDENO_TASK_TARGET}.js",
+                &env
+            ),
+            "TARGET=web build web.js"
+        );
+        assert_eq!(
+            expand_vars(
+                "${MDENO_TASK_EMPTY:-a} ${MDENO_TASK_UNSET:-$MDENO_TASK_TARGET} [$MDENO_TASK_UNSET]",
+                &env
+            ),
+            "a web []"
+        );
+        assert_eq!(
+            expand_vars(
+                r#"'$MDENO_TASK_TARGET' "$MDENO_TASK_TARGET" \$HOME $1 $"#,
+                &env
+            ),
+            r#"'$MDENO_TASK_TARGET' "web" \$HOME $1 $"#
+        );
+
+        let env = HashMap::from([("MDENO_TASK_VALUE".to_string(), "a && b".to_string())]);
+        assert_eq!(
+            expand_vars("echo $MDENO_TASK_VALUE", &env),
+            format!("echo {}", quote_arg("a && b"))
+        );
+        assert_eq!(
+            expand_vars(r#"echo "$MDENO_TASK_VALUE""#, &env),
+            r#"echo "a && b""#
+        );
+    }
+
+    #[test]
+    fn test_env_values_are_not_parsed_as_commands() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tasks = vec![(
+            "show".to_string(),
+            parse_task(
+                "show",
+                &serde_json::json!("echo $MDENO_TASK_VALUE > out.txt"),
+            )
+            .unwrap(),
+        )];
+        let env = HashMap::from([(
+            "MDENO_TASK_VALUE".to_string(),
+            "a && echo injected > injected.txt".to_string(),
+        )]);
+
+        let code = run_task("show", &[], &tasks, dir.path(), &env, &mut Vec::new()).unwrap();
+        assert_eq!(code, 0);
+        assert!(!dir.path().join("injected.txt").exists());
+        let out = fs::read_to_string(dir.path().join("out.txt")).unwrap();
+        assert!(out.contains("a && echo injected"), "{out}");
+    }
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = r#"
+# comment
+export A=1
+B = two words # trailing comment
+C="line\nbreak \"quoted\""
+D='single $X # kept'
+E="spans
+lines"
+F=first \
+second
+URL=http://x/#anchor
+"#;
+        let vars = parse_env_file(content).unwrap();
+        let get = |key: &str| {
+            vars.iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(get("A"), Some("1"));
+        assert_eq!(get("B"), Some("two words"));
+        assert_eq!(get("C"), Some("line\nbreak \"quoted\""));
+        assert_eq!(get("D"), Some("single $X # kept"));
+        assert_eq!(get("E"), Some("spans\nlines"));
+        assert_eq!(get("F"), Some("first \nsecond"));
+        assert_eq!(get("URL"), Some("http://x/#anchor"));
+
+        assert!(parse_env_file("NOT A VAR").is_err());
+        assert!(parse_env_file("1X=1").is_err());
+        assert!(parse_env_file("X=\"open").is_err());
+    }
+
+    #[test]
+    fn test_parse_task_reference() {
+        assert_eq!(
+            parse_task_reference("mdeno task:build"),
+            Some(("build".to_string(), Vec::new()))
+        );
+        assert_eq!(
+            parse_task_reference("mdeno task test -- --filter x"),
+            Some((
+                "test".to_string(),
+                vec!["--filter".to_string(), "x".to_string()]
+            ))
+        );
+        assert_eq!(parse_task_reference("mdeno run main.ts"), None);
+    }
+}
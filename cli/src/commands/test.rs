@@ -1,16 +1,37 @@
 use deno_terminal::colors;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Expression, ObjectProperty};
+use oxc_ast_visit::{Visit, walk};
+use oxc_parser::Parser;
+use oxc_span::SourceType;
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 use std::time::Instant;
+use utils::output;
 
-pub fn execute(pattern: Option<String>, unstable: bool) -> Result<(), Box<dyn Error>> {
+/// Stack size for test worker threads, matching the main thread's default on Linux
+const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Run test files, `jobs` at a time if set, otherwise one after another
+pub fn execute(
+    pattern: Option<String>,
+    unstable: bool,
+    check_integrity: bool,
+    tree_shake: bool,
+    jobs: Option<usize>,
+    retries: u32,
+    timeout_ms: u64,
+) -> Result<(), Box<dyn Error>> {
     // Determine test directory
     let test_dir = pattern.unwrap_or_else(|| ".".to_string());
     let test_path = Path::new(&test_dir);
 
     // Find test files
-    let test_files = find_test_files(test_path)?;
+    let test_files = find_files(test_path, is_test_file)?;
 
     if test_files.is_empty() {
         eprintln!("No test files found");
@@ -20,22 +41,27 @@ pub fn execute(pattern: Option<String>, unstable: bool) -> Result<(), Box<dyn Er
     // Start timing
     let start_time = Instant::now();
 
-    // Run each test file
-    let mut total_passed = 0;
-    let mut total_failed = 0;
+    // First pass: `only: true` in any file restricts every file to its only tests
+    mdeno_runtime::set_only_mode(test_files.iter().any(|test_file| uses_only(test_file)));
+    mdeno_runtime::set_retries(retries);
+    mdeno_runtime::set_test_timeout(timeout_ms);
 
-    for test_file in &test_files {
-        match run_test_file(test_file, unstable) {
-            Ok((passed, failed)) => {
-                total_passed += passed;
-                total_failed += failed;
-            }
+    let run =
+        |test_file: &Path| match run_test_file(test_file, unstable, check_integrity, tree_shake) {
+            Ok(counts) => counts,
             Err(e) => {
-                eprintln!("Error running test file {}: {}", test_file.display(), e);
-                total_failed += 1;
+                utils::eprint_line!("Error running test file {}: {}", test_file.display(), e);
+                (0, 1, 0)
             }
-        }
-    }
+        };
+
+    let (total_passed, total_failed, total_skipped) = match jobs {
+        Some(jobs) => run_parallel(&test_files, jobs, run)?,
+        None => test_files
+            .iter()
+            .map(|test_file| run(test_file))
+            .fold((0, 0, 0), add_counts),
+    };
 
     // Calculate elapsed time
     let elapsed = start_time.elapsed();
@@ -49,10 +75,11 @@ pub fn execute(pattern: Option<String>, unstable: bool) -> Result<(), Box<dyn Er
         colors::green("ok")
     };
     println!(
-        "{} | {} passed | {} failed {}",
+        "{} | {} passed | {} failed | {} skipped {}",
         status,
         total_passed,
         total_failed,
+        total_skipped,
         colors::gray(&format!("({elapsed_ms}ms)"))
     );
     println!();
@@ -64,17 +91,104 @@ pub fn execute(pattern: Option<String>, unstable: bool) -> Result<(), Box<dyn Er
     Ok(())
 }
 
-fn find_test_files(path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+/// Passed, failed and skipped test counts
+type Counts = (usize, usize, usize);
+
+fn add_counts(total: Counts, counts: Counts) -> Counts {
+    (total.0 + counts.0, total.1 + counts.1, total.2 + counts.2)
+}
+
+/// Whether a test file contains an `only: true` option
+fn uses_only(path: &Path) -> bool {
+    #[derive(Default)]
+    struct OnlyFinder {
+        found: bool,
+    }
+
+    impl<'a> Visit<'a> for OnlyFinder {
+        fn visit_object_property(&mut self, property: &ObjectProperty<'a>) {
+            if property
+                .key
+                .static_name()
+                .is_some_and(|name| name == "only")
+                && matches!(&property.value, Expression::BooleanLiteral(value) if value.value)
+            {
+                self.found = true;
+            }
+            walk::walk_object_property(self, property);
+        }
+    }
+
+    let Ok(source) = fs::read_to_string(path) else {
+        return false;
+    };
+    if !source.contains("only") {
+        return false;
+    }
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(path).unwrap_or_default();
+    let parser_ret = Parser::new(&allocator, &source, source_type).parse();
+    let mut finder = OnlyFinder::default();
+    finder.visit_program(&parser_ret.program);
+    finder.found
+}
+
+/// Default `--jobs` value: one test file per CPU
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+}
+
+/// Run test files on `jobs` worker threads, each with its own runtime.
+/// Output is buffered per file and printed in one piece when the file finishes.
+fn run_parallel(
+    test_files: &[PathBuf],
+    jobs: usize,
+    run: impl Fn(&Path) -> Counts + Sync,
+) -> Result<Counts, Box<dyn Error>> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, test_files.len()) {
+            let sender = sender.clone();
+            let (next, run) = (&next, &run);
+            thread::Builder::new()
+                .stack_size(WORKER_STACK_SIZE)
+                .spawn_scoped(scope, move || {
+                    while let Some(test_file) = test_files.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        if sender.send(output::capture(|| run(test_file))).is_err() {
+                            break;
+                        }
+                    }
+                })?;
+        }
+        drop(sender);
+
+        let mut totals = (0, 0, 0);
+        for (counts, captured) in receiver {
+            captured.print();
+            totals = add_counts(totals, counts);
+        }
+        Ok(totals)
+    })
+}
+
+/// Find the files under `path` that `matches` accepts, such as test files
+pub(crate) fn find_files(
+    path: &Path,
+    matches: fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let mut test_files = Vec::new();
 
     if path.is_file() {
         // Single file
-        if is_test_file(path) {
+        if matches(path) {
             test_files.push(path.to_path_buf());
         }
     } else if path.is_dir() {
         // Directory - recursively find test files
-        find_test_files_recursive(path, &mut test_files)?;
+        find_files_recursive(path, matches, &mut test_files)?;
     }
 
     // Sort for consistent ordering
@@ -83,8 +197,9 @@ fn find_test_files(path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     Ok(test_files)
 }
 
-fn find_test_files_recursive(
+fn find_files_recursive(
     dir: &Path,
+    matches: fn(&Path) -> bool,
     test_files: &mut Vec<PathBuf>,
 ) -> Result<(), Box<dyn Error>> {
     for entry in fs::read_dir(dir)? {
@@ -99,8 +214,8 @@ fn find_test_files_recursive(
                     continue;
                 }
             }
-            find_test_files_recursive(&path, test_files)?;
-        } else if is_test_file(&path) {
+            find_files_recursive(&path, matches, test_files)?;
+        } else if matches(&path) {
             test_files.push(path);
         }
     }
@@ -109,6 +224,12 @@ fn find_test_files_recursive(
 }
 
 fn is_test_file(path: &Path) -> bool {
+    matches_pattern(path, "test")
+}
+
+/// Whether the file name follows `{*_*,*.,}<kind>.{js,ts}`, as test and
+/// bench files do
+pub(crate) fn matches_pattern(path: &Path, kind: &str) -> bool {
     if let Some(filename) = path.file_name() {
         let filename = filename.to_string_lossy();
 
@@ -127,15 +248,15 @@ fn is_test_file(path: &Path) -> bool {
 
         for ext in &test_extensions {
             // Check for *_test.ext pattern
-            if filename.ends_with(&format!("_test{ext}")) {
+            if filename.ends_with(&format!("_{kind}{ext}")) {
                 return true;
             }
             // Check for *.test.ext pattern
-            if filename.ends_with(&format!(".test{ext}")) {
+            if filename.ends_with(&format!(".{kind}{ext}")) {
                 return true;
             }
             // Check for test.ext pattern (exact match)
-            if filename == format!("test{ext}") {
+            if filename == format!("{kind}{ext}") {
                 return true;
             }
         }
@@ -146,7 +267,12 @@ fn is_test_file(path: &Path) -> bool {
     }
 }
 
-fn run_test_file(path: &Path, unstable: bool) -> Result<(usize, usize), Box<dyn Error>> {
+fn run_test_file(
+    path: &Path,
+    unstable: bool,
+    check_integrity: bool,
+    tree_shake: bool,
+) -> Result<Counts, Box<dyn Error>> {
     use crate::bundler::ModuleBundler;
     use mdeno_path_util::to_file_url;
 
@@ -166,16 +292,44 @@ fn run_test_file(path: &Path, unstable: bool) -> Result<(usize, usize), Box<dyn
         let canonical_str = canonical_path.display().to_string();
         let entry_file_url = to_file_url(&canonical_path);
 
-        let mut bundler = ModuleBundler::new(unstable);
+        let mut bundler = ModuleBundler::new(unstable)
+            .with_check_integrity(check_integrity)
+            .with_tree_shake(tree_shake);
         let modules = bundler.bundle(&canonical_str)?;
 
+        mdeno_runtime::register_source_maps(bundler.source_maps(&modules));
+
         // Compile and run with bytecode for tests
         let bytecode = mdeno_runtime::compile_modules(modules.clone(), entry_file_url.clone())?;
-        let (passed, failed) = mdeno_runtime::run_test_bytecode(&bytecode, &file_path_str)?;
-        Ok((passed, failed))
+        mdeno_runtime::run_test_bytecode(&bytecode, &file_path_str)
     } else {
         // Plain JavaScript without imports - use simple execution
-        let (passed, failed) = mdeno_runtime::run_test_js_code(&file_contents, &file_path_str)?;
-        Ok((passed, failed))
+        mdeno_runtime::run_test_js_code(&file_contents, &file_path_str)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uses_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let only = dir.path().join("only_test.ts");
+        fs::write(
+            &only,
+            "Deno.test({ name: 'a', only: true, fn() {} });\nDeno.test('b', () => {});\n",
+        )
+        .unwrap();
+        let comment = dir.path().join("comment_test.ts");
+        fs::write(
+            &comment,
+            "// only: true\nDeno.test({ name: 'a', only: false, fn() {} });\n",
+        )
+        .unwrap();
+
+        assert!(uses_only(&only));
+        assert!(!uses_only(&comment));
     }
 }
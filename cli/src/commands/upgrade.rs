@@ -0,0 +1,295 @@
+use deno_terminal::colors;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const RELEASES_URL: &str = "https://api.github.com/repos/ryuapp/mdeno/releases";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+    /// "sha256:<hex>", provided by GitHub for each uploaded asset
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+pub fn execute(version: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let compio_runtime = compio::runtime::Runtime::new()?;
+    compio_runtime.block_on(upgrade(version))
+}
+
+async fn upgrade(version: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let client = cyper::Client::new();
+
+    let release_url = match version {
+        Some(tag) => format!("{RELEASES_URL}/tags/{}", normalize_tag(tag)),
+        None => format!("{RELEASES_URL}/latest"),
+    };
+    eprintln!("{} {release_url}", colors::green("Looking up"));
+    let release: Release = serde_json::from_slice(&fetch(&client, &release_url).await?)
+        .map_err(|e| format!("Failed to parse release metadata: {e}"))?;
+
+    let current = normalize_tag(env!("CARGO_PKG_VERSION"));
+    if version.is_none() && release.tag_name == current {
+        eprintln!("Local mdeno version {current} is the most recent release");
+        return Ok(());
+    }
+
+    let asset_name = asset_name();
+    let asset = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == asset_name)
+        .ok_or_else(|| {
+            format!(
+                "Release {} has no binary for this platform ({asset_name})",
+                release.tag_name
+            )
+        })?;
+    let expected_checksum = expected_checksum(&client, &release, asset).await?;
+
+    let current_exe = std::env::current_exe()?;
+    let temp_path = temp_path(&current_exe);
+    download(&client, asset, &temp_path).await?;
+
+    let result = verify_file(&temp_path, &expected_checksum)
+        .and_then(|()| set_executable(&temp_path))
+        .and_then(|()| replace_executable(&temp_path, &current_exe));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    eprintln!(
+        "{} mdeno {} -> {}",
+        colors::green("Upgraded"),
+        current,
+        release.tag_name
+    );
+    Ok(())
+}
+
+/// Release tags are prefixed with "v"
+fn normalize_tag(tag: &str) -> String {
+    if tag.starts_with('v') {
+        tag.to_string()
+    } else {
+        format!("v{tag}")
+    }
+}
+
+/// Target triple of the running binary
+fn target_triple() -> String {
+    let arch = if cfg!(target_arch = "aarch64") {
+        "aarch64"
+    } else {
+        "x86_64"
+    };
+    let os = if cfg!(target_os = "windows") {
+        "pc-windows-msvc"
+    } else if cfg!(target_os = "macos") {
+        "apple-darwin"
+    } else if cfg!(target_env = "musl") {
+        "unknown-linux-musl"
+    } else {
+        "unknown-linux-gnu"
+    };
+    format!("{arch}-{os}")
+}
+
+fn asset_name() -> String {
+    let extension = if cfg!(windows) { ".exe" } else { "" };
+    format!("mdeno-{}{extension}", target_triple())
+}
+
+/// Find the SHA-256 of an asset, from the GitHub digest or a `<name>.sha256` asset
+async fn expected_checksum(
+    client: &cyper::Client,
+    release: &Release,
+    asset: &ReleaseAsset,
+) -> Result<String, Box<dyn Error>> {
+    if let Some(digest) = asset
+        .digest
+        .as_deref()
+        .and_then(|d| d.strip_prefix("sha256:"))
+    {
+        return Ok(digest.to_lowercase());
+    }
+
+    let checksum_name = format!("{}.sha256", asset.name);
+    let checksum_asset = release
+        .assets
+        .iter()
+        .find(|candidate| candidate.name == checksum_name)
+        .ok_or_else(|| {
+            format!(
+                "Release {} has no checksum for {}",
+                release.tag_name, asset.name
+            )
+        })?;
+    let content = fetch(client, &checksum_asset.browser_download_url).await?;
+
+    // "<hex>  <file name>" as written by sha256sum
+    String::from_utf8_lossy(&content)
+        .split_whitespace()
+        .next()
+        .map(str::to_lowercase)
+        .ok_or_else(|| format!("Empty checksum file {checksum_name}").into())
+}
+
+async fn fetch(client: &cyper::Client, url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let response = client
+        .get(url)?
+        .header("User-Agent", concat!("mdeno/", env!("CARGO_PKG_VERSION")))?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch {url}: {}", response.status()).into());
+    }
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Stream an asset to `path`, printing progress to stderr
+async fn download(
+    client: &cyper::Client,
+    asset: &ReleaseAsset,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let url = &asset.browser_download_url;
+    let response = client
+        .get(url)?
+        .header("User-Agent", concat!("mdeno/", env!("CARGO_PKG_VERSION")))?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download {url}: {}", response.status()).into());
+    }
+
+    let total = response.content_length().unwrap_or(asset.size);
+    let mut file = fs::File::create(path)?;
+    let mut received = 0u64;
+    let mut stream = response.bytes_stream();
+    let mut stderr = std::io::stderr();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Failed to download {url}: {e}"))?;
+        file.write_all(&chunk)?;
+        received += chunk.len() as u64;
+        let _ = write!(
+            stderr,
+            "\r{} {} {}",
+            colors::green("Downloading"),
+            asset.name,
+            format_progress(received, total)
+        );
+        let _ = stderr.flush();
+    }
+    eprintln!();
+    file.sync_all()?;
+
+    Ok(())
+}
+
+fn format_progress(received: u64, total: u64) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    if total == 0 {
+        return format!("{:.1} MiB", received as f64 / MIB);
+    }
+    format!(
+        "{:.1}/{:.1} MiB ({}%)",
+        received as f64 / MIB,
+        total as f64 / MIB,
+        received * 100 / total
+    )
+}
+
+fn verify_file(path: &Path, expected: &str) -> Result<(), Box<dyn Error>> {
+    let actual = format!("{:x}", Sha256::digest(fs::read(path)?));
+    if actual != expected {
+        return Err(format!("Checksum mismatch: expected {expected}, got {actual}").into());
+    }
+    Ok(())
+}
+
+/// Download next to the current executable so the final rename stays on one file system
+fn temp_path(current_exe: &Path) -> PathBuf {
+    let file_name = current_exe
+        .file_name()
+        .map_or_else(|| "mdeno".into(), |name| name.to_string_lossy());
+    current_exe.with_file_name(format!(".{file_name}.upgrade"))
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), Box<dyn Error>> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)] // Matches the unix signature
+fn set_executable(_path: &Path) -> Result<(), Box<dyn Error>> {
+    Ok(())
+}
+
+/// Swap the new binary into place
+fn replace_executable(new_exe: &Path, current_exe: &Path) -> Result<(), Box<dyn Error>> {
+    if cfg!(windows) {
+        // A running executable can't be overwritten on Windows, but it can be
+        // renamed. Move it aside and remove it on a best-effort basis.
+        let old_exe = current_exe.with_extension("old.exe");
+        let _ = fs::remove_file(&old_exe);
+        fs::rename(current_exe, &old_exe)?;
+        if let Err(e) = fs::rename(new_exe, current_exe) {
+            let _ = fs::rename(&old_exe, current_exe);
+            return Err(e.into());
+        }
+        let _ = fs::remove_file(&old_exe);
+    } else {
+        fs::rename(new_exe, current_exe)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag("0.2.0"), "v0.2.0");
+        assert_eq!(normalize_tag("v0.2.0"), "v0.2.0");
+    }
+
+    #[test]
+    fn test_release_parsing() {
+        let release: Release = serde_json::from_str(
+            r#"{
+                "tag_name": "v0.2.0",
+                "assets": [{
+                    "name": "mdeno-x86_64-unknown-linux-gnu",
+                    "browser_download_url": "https://example.com/mdeno",
+                    "size": 10,
+                    "digest": "sha256:abc"
+                }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(release.tag_name, "v0.2.0");
+        assert_eq!(release.assets[0].digest.as_deref(), Some("sha256:abc"));
+    }
+}
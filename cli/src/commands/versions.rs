@@ -0,0 +1,27 @@
+use crate::jsr::JsrResolver;
+use deno_terminal::colors;
+use std::error::Error;
+
+/// List the published versions of a JSR package, newest first
+pub fn execute(specifier: &str) -> Result<(), Box<dyn Error>> {
+    let parsed = JsrResolver::parse_specifier(specifier)
+        .map_err(|e| format!("Invalid JSR specifier \"{specifier}\": {e}"))?;
+    let versions = JsrResolver::new().list_versions(&parsed.scope, &parsed.package)?;
+
+    if versions.is_empty() {
+        eprintln!("No versions of {} are published", parsed.package);
+        return Ok(());
+    }
+    for version in versions {
+        let mut line = version.version;
+        if version.latest {
+            line = format!("{line} {}", colors::green("(latest)"));
+        }
+        if version.yanked {
+            line = format!("{line} {}", colors::gray("(yanked)"));
+        }
+        println!("{line}");
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
 
+use crate::jsr::JsrError;
 use std::error::Error;
 use std::fmt::Write;
 
@@ -12,10 +13,13 @@ use std::fmt::Write;
 /// displays the first error message without a number, and only numbers the source chain
 /// starting from 0. Our implementation numbers all errors including the first one.
 /// Deno's version also handles multi-line error messages with additional indentation.
+///
+/// If a JSR failure is part of the chain, a hint on how to recover follows it.
 #[allow(clippy::unwrap_used)] // Writing to String never fails
-pub fn format_error_chain(error: &dyn Error) -> String {
+pub fn format_error_chain(error: &(dyn Error + 'static)) -> String {
     let mut message = String::new();
     let mut display_count = 0;
+    let mut hint = jsr_hint(error);
 
     // Start with the error itself
     let current_message = error.to_string();
@@ -27,6 +31,7 @@ pub fn format_error_chain(error: &dyn Error) -> String {
     let mut maybe_source = error.source();
     while let Some(source) = maybe_source {
         let current_message = source.to_string();
+        hint = hint.or_else(|| jsr_hint(source));
         maybe_source = source.source();
 
         if current_message != past_message {
@@ -42,5 +47,78 @@ pub fn format_error_chain(error: &dyn Error) -> String {
         }
     }
 
+    if let Some(hint) = hint {
+        write!(&mut message, "\n\n    hint: {hint}").unwrap();
+    }
+
     message
 }
+
+/// Suggest a next step for JSR failures the user can do something about
+fn jsr_hint(error: &(dyn Error + 'static)) -> Option<&'static str> {
+    match error.downcast_ref::<JsrError>()? {
+        JsrError::NetworkError(_) | JsrError::HttpStatus { .. } => {
+            Some("Check your internet connection and that https://jsr.io is reachable.")
+        }
+        JsrError::ChecksumMismatch { .. } => Some(
+            "The file differs from the one the package was published with. \
+             Run again with `--reload` to download the package afresh.",
+        ),
+        JsrError::NotInManifest(_) => Some(
+            "The cached package metadata may be out of date. \
+             Run again with `--reload` to download it afresh.",
+        ),
+        JsrError::UnsupportedChecksum(_) => Some(
+            "The package was published with a checksum this version of mdeno \
+             can't verify. Try a newer mdeno.",
+        ),
+        JsrError::PackageNotFound(_) => {
+            Some("Check the package name and version with `mdeno versions jsr:@scope/package`.")
+        }
+        JsrError::ExportNotFound(_) => {
+            Some("Check the export name against the package's page on https://jsr.io.")
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundler::JsrImportError;
+
+    #[test]
+    fn test_jsr_hint_follows_chain() {
+        let error = JsrImportError {
+            specifier: "jsr:@std/nope@1.0.0".to_string(),
+            source: JsrError::PackageNotFound("@std/nope@1.0.0".to_string()),
+        };
+        let message = format_error_chain(&error);
+        assert_eq!(
+            message,
+            "\n    0: Failed to resolve JSR import jsr:@std/nope@1.0.0\
+             \n    1: @std/nope@1.0.0 was not found on JSR\
+             \n\n    hint: Check the package name and version with \
+             `mdeno versions jsr:@scope/package`."
+        );
+    }
+
+    #[test]
+    fn test_integrity_hints_suggest_reload() {
+        let mismatch = JsrError::ChecksumMismatch {
+            expected: "aa".to_string(),
+            actual: "bb".to_string(),
+        };
+        assert!(jsr_hint(&mismatch).is_some_and(|hint| hint.contains("--reload")));
+        let unlisted = JsrError::NotInManifest("/mod.ts".to_string());
+        assert!(jsr_hint(&unlisted).is_some_and(|hint| hint.contains("--reload")));
+        let checksum = JsrError::UnsupportedChecksum("md5-abc".to_string());
+        assert!(jsr_hint(&checksum).is_some());
+    }
+
+    #[test]
+    fn test_no_hint_for_other_errors() {
+        let error = std::io::Error::other("boom");
+        assert_eq!(format_error_chain(&error), "\n    0: boom");
+    }
+}
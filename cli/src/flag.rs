@@ -1,19 +1,81 @@
-use bpaf::{Args, OptionParser, Parser, construct, long, positional};
+use crate::jsr::Reload;
+use bpaf::{Args, OptionParser, Parser, any, construct, long, positional, short};
 
 #[derive(Debug, Clone)]
 pub struct CliArgs {
     pub command: Command,
     pub script_args: Vec<String>,
     pub unstable: bool,
+    pub no_check_integrity: bool,
+    pub no_tree_shake: bool,
+    pub seed: Option<u64>,
+    /// URL for `globalThis.location` (--location)
+    pub location: Option<String>,
+    pub deny: Deny,
+    /// JSR packages to download again (--reload)
+    pub reload: Reload,
+}
+
+/// Paths denied with --deny-read / --deny-write
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deny {
+    pub read: Vec<String>,
+    pub write: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Command {
-    Run { file_path: String },
-    Compile { file_path: String },
-    Eval { code: String },
-    Test { pattern: Option<String> },
-    Help { command: Option<String> },
+    Run {
+        file_path: String,
+    },
+    Compile {
+        file_path: String,
+    },
+    Eval {
+        code: String,
+    },
+    Test {
+        pattern: Option<String>,
+        parallel: bool,
+        jobs: Option<usize>,
+        /// Times to retry a failed test (--retries)
+        retries: Option<u32>,
+        /// Milliseconds a test may run before it fails (--timeout)
+        timeout: Option<u64>,
+    },
+    Bench {
+        pattern: Option<String>,
+        /// Write results to a JSON file (--json)
+        json: bool,
+        output: Option<String>,
+        /// Results of an earlier --json run to compare against
+        baseline: Option<String>,
+        /// Percentage slowdown to highlight as a regression
+        threshold: Option<f64>,
+    },
+    Task {
+        name: Option<String>,
+        /// `.env` file loaded before running the task (--env-file)
+        env_file: Option<String>,
+    },
+    Repl,
+    Doc {
+        target: String,
+        json: bool,
+    },
+    Info {
+        target: String,
+        json: bool,
+    },
+    Upgrade {
+        version: Option<String>,
+    },
+    Versions {
+        specifier: String,
+    },
+    Help {
+        command: Option<String>,
+    },
 }
 
 /// Parse command line arguments
@@ -54,59 +116,441 @@ fn unstable_flag() -> impl Parser<bool> {
     long("unstable").help("Enable unstable features").switch()
 }
 
+fn no_check_integrity_flag() -> impl Parser<bool> {
+    long("no-check-integrity")
+        .help("Skip checksum verification of JSR downloads")
+        .switch()
+}
+
+fn no_tree_shake_flag() -> impl Parser<bool> {
+    long("no-tree-shake")
+        .help("Keep unused exports of imported modules in the bundle")
+        .switch()
+}
+
+fn seed_flag() -> impl Parser<Option<u64>> {
+    long("seed")
+        .help("Set the random number generator seed")
+        .argument::<u64>("NUMBER")
+        .optional()
+}
+
+fn location_flag() -> impl Parser<Option<String>> {
+    long("location")
+        .help("Value of globalThis.location used by some web APIs")
+        .argument::<String>("HREF")
+        .optional()
+}
+
+fn deny_flags() -> impl Parser<Deny> {
+    // Each flag takes a comma-separated list and may be repeated
+    let paths = |values: Vec<String>| -> Vec<String> {
+        values
+            .iter()
+            .flat_map(|value| value.split(','))
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let read = long("deny-read")
+        .help("Deny file system read access to the given paths")
+        .argument::<String>("PATHS")
+        .many()
+        .map(paths);
+    let write = long("deny-write")
+        .help("Deny file system write access to the given paths")
+        .argument::<String>("PATHS")
+        .many()
+        .map(paths);
+    construct!(read, write).map(|(read, write)| Deny { read, write })
+}
+
+fn reload_flag() -> impl Parser<Reload> {
+    // A bare --reload takes no value, so it can't be confused with the file
+    // that follows it; --reload=<packages> takes a comma-separated list
+    any::<String, _, _>("-r, --reload[=PACKAGES]", |arg| match arg.as_str() {
+        "-r" | "--reload" => Some(Reload::All),
+        _ => arg.strip_prefix("--reload=").map(|packages| {
+            Reload::Packages(
+                packages
+                    .split(',')
+                    .filter(|package| !package.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            )
+        }),
+    })
+    .help("Download cached JSR modules again, or only the given jsr: packages")
+    .anywhere()
+    .optional()
+    .map(Option::unwrap_or_default)
+}
+
 fn cli_parser() -> OptionParser<CliArgs> {
     // Run command: mdeno run <file> [-- args...]
     let run_file = positional::<String>("FILE").help("File to run");
     let run_args = positional::<String>("ARGS")
         .help("Arguments to pass to the script (use -- to separate)")
         .many();
-    let run = construct!(unstable_flag(), run_file, run_args)
-        .map(|(unstable, file_path, script_args)| CliArgs {
+    let run = construct!(
+        unstable_flag(),
+        no_check_integrity_flag(),
+        no_tree_shake_flag(),
+        seed_flag(),
+        location_flag(),
+        deny_flags(),
+        reload_flag(),
+        run_file,
+        run_args
+    )
+    .map(
+        |(
+            unstable,
+            no_check_integrity,
+            no_tree_shake,
+            seed,
+            location,
+            deny,
+            reload,
+            file_path,
+            script_args,
+        )| CliArgs {
             command: Command::Run { file_path },
             script_args,
             unstable,
-        })
-        .to_options()
-        .command("run")
-        .help("Run a JavaScript or TypeScript file");
+            no_check_integrity,
+            no_tree_shake,
+            seed,
+            location,
+            deny,
+            reload,
+        },
+    )
+    .to_options()
+    .command("run")
+    .help("Run a JavaScript or TypeScript file");
 
     // Compile command: mdeno compile <file>
     let compile_file = positional::<String>("FILE").help("File to compile");
-    let compile = construct!(unstable_flag(), compile_file)
-        .map(|(unstable, file_path)| CliArgs {
+    let compile = construct!(
+        unstable_flag(),
+        no_check_integrity_flag(),
+        no_tree_shake_flag(),
+        reload_flag(),
+        compile_file
+    )
+    .map(
+        |(unstable, no_check_integrity, no_tree_shake, reload, file_path)| CliArgs {
             command: Command::Compile { file_path },
             script_args: Vec::new(),
             unstable,
-        })
-        .to_options()
-        .command("compile")
-        .help("Compile the script into a self contained executable");
+            no_check_integrity,
+            no_tree_shake,
+            seed: None,
+            location: None,
+            deny: Deny::default(),
+            reload,
+        },
+    )
+    .to_options()
+    .command("compile")
+    .help("Compile the script into a self contained executable");
 
     // Eval command: mdeno eval <code>
     let eval_code = positional::<String>("CODE").help("Code to evaluate");
-    let eval = construct!(unstable_flag(), eval_code)
-        .map(|(unstable, code)| CliArgs {
-            command: Command::Eval { code },
+    let eval = construct!(
+        unstable_flag(),
+        seed_flag(),
+        location_flag(),
+        deny_flags(),
+        eval_code
+    )
+    .map(|(unstable, seed, location, deny, code)| CliArgs {
+        command: Command::Eval { code },
+        script_args: Vec::new(),
+        unstable,
+        no_check_integrity: false,
+        no_tree_shake: false,
+        seed,
+        location,
+        deny,
+        reload: Reload::default(),
+    })
+    .to_options()
+    .command("eval")
+    .help("Evaluate a script from the command line");
+
+    // Test command: mdeno test [--parallel] [--jobs=N] [--retries=N] [--timeout=MS] [pattern]
+    let test_parallel = short('p')
+        .long("parallel")
+        .help("Run test files in parallel")
+        .switch();
+    let test_jobs = long("jobs")
+        .help("Number of test files to run at once (implies --parallel, defaults to the CPU count)")
+        .argument::<usize>("N")
+        .optional();
+    let test_retries = long("retries")
+        .help("Retry failed tests up to N times, unless a test sets its own retry option")
+        .argument::<u32>("N")
+        .optional();
+    let test_timeout = long("timeout")
+        .help("Fail tests that run longer than MS milliseconds, unless a test sets its own timeout option")
+        .argument::<u64>("MS")
+        .optional();
+    let test_pattern = positional::<String>("PATTERN")
+        .help("Test file pattern (optional)")
+        .optional();
+    let test = construct!(
+        unstable_flag(),
+        no_check_integrity_flag(),
+        no_tree_shake_flag(),
+        seed_flag(),
+        location_flag(),
+        deny_flags(),
+        reload_flag(),
+        test_parallel,
+        test_jobs,
+        test_retries,
+        test_timeout,
+        test_pattern
+    )
+    .map(
+        |(
+            unstable,
+            no_check_integrity,
+            no_tree_shake,
+            seed,
+            location,
+            deny,
+            reload,
+            parallel,
+            jobs,
+            retries,
+            timeout,
+            pattern,
+        )| {
+            CliArgs {
+                command: Command::Test {
+                    pattern,
+                    parallel,
+                    jobs,
+                    retries,
+                    timeout,
+                },
+                script_args: Vec::new(),
+                unstable,
+                no_check_integrity,
+                no_tree_shake,
+                seed,
+                location,
+                deny,
+                reload,
+            }
+        },
+    )
+    .to_options()
+    .command("test")
+    .help("Run tests");
+
+    // Bench command: mdeno bench [--json] [--output=FILE] [--baseline=FILE] [--threshold=PERCENT] [pattern]
+    let bench_json = long("json")
+        .help("Write results as JSON to bench.json, or the --output file")
+        .switch();
+    let bench_output = long("output")
+        .help("File to write JSON results to (implies --json)")
+        .argument::<String>("FILE")
+        .optional();
+    let bench_baseline = long("baseline")
+        .help("Compare against the JSON results of an earlier run")
+        .argument::<String>("FILE")
+        .optional();
+    let bench_threshold = long("threshold")
+        .help("Slowdown in percent to highlight as a regression (default: 5)")
+        .argument::<f64>("PERCENT")
+        .optional();
+    let bench_pattern = positional::<String>("PATTERN")
+        .help("Bench file pattern (optional)")
+        .optional();
+    let bench = construct!(
+        unstable_flag(),
+        no_check_integrity_flag(),
+        no_tree_shake_flag(),
+        seed_flag(),
+        location_flag(),
+        deny_flags(),
+        reload_flag(),
+        bench_json,
+        bench_output,
+        bench_baseline,
+        bench_threshold,
+        bench_pattern
+    )
+    .map(
+        |(
+            unstable,
+            no_check_integrity,
+            no_tree_shake,
+            seed,
+            location,
+            deny,
+            reload,
+            json,
+            output,
+            baseline,
+            threshold,
+            pattern,
+        )| CliArgs {
+            command: Command::Bench {
+                pattern,
+                json,
+                output,
+                baseline,
+                threshold,
+            },
             script_args: Vec::new(),
             unstable,
+            no_check_integrity,
+            no_tree_shake,
+            seed,
+            location,
+            deny,
+            reload,
+        },
+    )
+    .to_options()
+    .command("bench")
+    .help("Run benchmarks");
+
+    // Task command: mdeno task [name] [-- args...]
+    let env_file = long("env-file")
+        .help("Load environment variables from a .env file before running the task")
+        .argument::<String>("FILE")
+        .optional();
+    let task_name = positional::<String>("TASK")
+        .help("Task to run (lists available tasks if omitted)")
+        .optional();
+    let task_args = positional::<String>("ARGS")
+        .help("Arguments to pass to the task (use -- to separate)")
+        .many();
+    let task = construct!(env_file, task_name, task_args)
+        .map(|(env_file, name, script_args)| CliArgs {
+            command: Command::Task { name, env_file },
+            script_args,
+            unstable: false,
+            no_check_integrity: false,
+            no_tree_shake: false,
+            seed: None,
+            location: None,
+            deny: Deny::default(),
+            reload: Reload::default(),
         })
         .to_options()
-        .command("eval")
-        .help("Evaluate a script from the command line");
+        .command("task")
+        .help("Run a task defined in the configuration file");
 
-    // Test command: mdeno test [pattern]
-    let test_pattern = positional::<String>("PATTERN")
-        .help("Test file pattern (optional)")
-        .optional();
-    let test = construct!(unstable_flag(), test_pattern)
-        .map(|(unstable, pattern)| CliArgs {
-            command: Command::Test { pattern },
+    // Repl command: mdeno repl
+    let repl = construct!(unstable_flag(), seed_flag(), location_flag(), deny_flags())
+        .map(|(unstable, seed, location, deny)| CliArgs {
+            command: Command::Repl,
             script_args: Vec::new(),
             unstable,
+            no_check_integrity: false,
+            no_tree_shake: false,
+            seed,
+            location,
+            deny,
+            reload: Reload::default(),
+        })
+        .to_options()
+        .command("repl")
+        .help("Start an interactive Read-Eval-Print Loop");
+
+    // Doc command: mdeno doc [--json] <file>
+    let doc_json = long("json")
+        .help("Output documentation in JSON format")
+        .switch();
+    let doc_target = positional::<String>("FILE").help("Module or jsr: specifier to document");
+    let doc = construct!(reload_flag(), doc_json, doc_target)
+        .map(|(reload, json, target)| CliArgs {
+            command: Command::Doc { target, json },
+            script_args: Vec::new(),
+            unstable: false,
+            no_check_integrity: false,
+            no_tree_shake: false,
+            seed: None,
+            location: None,
+            deny: Deny::default(),
+            reload,
         })
         .to_options()
-        .command("test")
-        .help("Run tests");
+        .command("doc")
+        .help("Show documentation for a module");
+
+    // Info command: mdeno info [--json] <file>
+    let info_json = long("json")
+        .help("Output the module graph in JSON format")
+        .switch();
+    let info_target = positional::<String>("FILE").help("Module or jsr: specifier to inspect");
+    let info = construct!(
+        no_check_integrity_flag(),
+        reload_flag(),
+        info_json,
+        info_target
+    )
+    .map(|(no_check_integrity, reload, json, target)| CliArgs {
+        command: Command::Info { target, json },
+        script_args: Vec::new(),
+        unstable: false,
+        no_check_integrity,
+        no_tree_shake: false,
+        seed: None,
+        location: None,
+        deny: Deny::default(),
+        reload,
+    })
+    .to_options()
+    .command("info")
+    .help("Show the dependency tree of a module");
+
+    // Upgrade command: mdeno upgrade [--version <tag>]
+    let upgrade_version = long("version")
+        .help("Release to install instead of the latest (e.g. v0.1.0)")
+        .argument::<String>("TAG")
+        .optional();
+    let upgrade = construct!(upgrade_version)
+        .map(|version| CliArgs {
+            command: Command::Upgrade { version },
+            script_args: Vec::new(),
+            unstable: false,
+            no_check_integrity: false,
+            no_tree_shake: false,
+            seed: None,
+            location: None,
+            deny: Deny::default(),
+            reload: Reload::default(),
+        })
+        .to_options()
+        .command("upgrade")
+        .help("Upgrade mdeno to the latest release");
+
+    // Versions command: mdeno versions <jsr:@scope/package>
+    let versions_specifier =
+        positional::<String>("PACKAGE").help("jsr: package to list versions of");
+    let versions = construct!(versions_specifier)
+        .map(|specifier| CliArgs {
+            command: Command::Versions { specifier },
+            script_args: Vec::new(),
+            unstable: false,
+            no_check_integrity: false,
+            no_tree_shake: false,
+            seed: None,
+            location: None,
+            deny: Deny::default(),
+            reload: Reload::default(),
+        })
+        .to_options()
+        .command("versions")
+        .help("List the published versions of a JSR package");
 
     // Help command: mdeno help [command]
     let help_command = positional::<String>("COMMAND")
@@ -117,15 +561,23 @@ fn cli_parser() -> OptionParser<CliArgs> {
             command: Command::Help { command },
             script_args: Vec::new(),
             unstable: false,
+            no_check_integrity: false,
+            no_tree_shake: false,
+            seed: None,
+            location: None,
+            deny: Deny::default(),
+            reload: Reload::default(),
         })
         .to_options()
         .command("help")
         .help("Show help information")
         .hide();
 
-    construct!([run, compile, eval, test, help])
-        .to_options()
-        .version(env!("CARGO_PKG_VERSION"))
-        .descr("A minimal JavaScript runtime for CLI tools")
-        .usage("mdeno [OPTIONS] [COMMAND]")
+    construct!([
+        run, compile, eval, test, bench, task, repl, doc, info, upgrade, versions, help
+    ])
+    .to_options()
+    .version(env!("CARGO_PKG_VERSION"))
+    .descr("A minimal JavaScript runtime for CLI tools")
+    .usage("mdeno [OPTIONS] [COMMAND]")
 }
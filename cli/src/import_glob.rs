@@ -0,0 +1,325 @@
+// import.meta.glob() expansion for the bundler
+//
+// `import.meta.glob("./routes/*.ts")` is replaced with an object literal
+// mapping each matching file to a lazy `() => import(...)`, or with the module
+// namespace itself when called with `{ eager: true }`.
+
+use crate::strip_types;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Argument, CallExpression, Expression, ObjectPropertyKind};
+use oxc_ast_visit::{Visit, walk};
+use oxc_parser::Parser;
+use oxc_span::{GetSpan, Span};
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Result of expanding the `import.meta.glob` calls of a module
+pub struct Expanded {
+    pub source: String,
+    /// Relative specifiers of every matched file, to be bundled
+    pub imports: Vec<String>,
+}
+
+struct GlobCall {
+    span: Span,
+    pattern: Result<String, Span>,
+    eager: bool,
+}
+
+#[derive(Default)]
+struct GlobCallCollector {
+    calls: Vec<GlobCall>,
+}
+
+impl<'a> Visit<'a> for GlobCallCollector {
+    fn visit_call_expression(&mut self, call: &CallExpression<'a>) {
+        if is_import_meta_glob(&call.callee) {
+            let pattern = match call.arguments.first() {
+                Some(Argument::StringLiteral(literal)) => Ok(literal.value.to_string()),
+                Some(argument) => Err(argument.span()),
+                None => Err(call.span),
+            };
+            self.calls.push(GlobCall {
+                span: call.span,
+                pattern,
+                eager: call.arguments.get(1).is_some_and(is_eager_option),
+            });
+            return;
+        }
+        walk::walk_call_expression(self, call);
+    }
+}
+
+fn is_import_meta_glob(callee: &Expression) -> bool {
+    let Expression::StaticMemberExpression(member) = callee else {
+        return false;
+    };
+    let Expression::MetaProperty(meta) = &member.object else {
+        return false;
+    };
+    meta.meta.name == "import" && meta.property.name == "meta" && member.property.name == "glob"
+}
+
+/// `{ eager: true }`
+fn is_eager_option(argument: &Argument) -> bool {
+    let Argument::ObjectExpression(object) = argument else {
+        return false;
+    };
+    object.properties.iter().any(|property| {
+        let ObjectPropertyKind::ObjectProperty(property) = property else {
+            return false;
+        };
+        property
+            .key
+            .static_name()
+            .is_some_and(|name| name == "eager")
+            && matches!(&property.value, Expression::BooleanLiteral(value) if value.value)
+    })
+}
+
+/// Replace `import.meta.glob` calls in `source`, matching patterns against
+/// files relative to `module_path`
+///
+/// # Errors
+/// Returns an error if a pattern is not a relative string literal or a
+/// directory cannot be read
+pub fn expand(source: &str, module_path: &str) -> Result<Expanded, Box<dyn Error>> {
+    let unchanged = || Expanded {
+        source: source.to_string(),
+        imports: Vec::new(),
+    };
+    if !source.contains("import.meta.glob") {
+        return Ok(unchanged());
+    }
+
+    let allocator = Allocator::default();
+    let source_type = strip_types::source_type(module_path);
+    let parser_ret = Parser::new(&allocator, source, source_type).parse();
+    if !parser_ret.errors.is_empty() {
+        return Ok(unchanged());
+    }
+    let mut collector = GlobCallCollector::default();
+    collector.visit_program(&parser_ret.program);
+    if collector.calls.is_empty() {
+        return Ok(unchanged());
+    }
+
+    let module_path = Path::new(module_path);
+    let base_dir = module_path.parent().unwrap_or(Path::new("."));
+    let mut imports = Vec::new();
+    let mut eager_imports = String::new();
+    let mut output = source.to_string();
+
+    // Splice from the end so earlier spans stay valid
+    collector
+        .calls
+        .sort_by_key(|call| std::cmp::Reverse(call.span.start));
+    for call in &collector.calls {
+        let pattern = call.pattern.as_ref().map_err(|span| {
+            format!(
+                "import.meta.glob() expects a string literal pattern, found `{}` in {}",
+                span.source_text(source),
+                module_path.display()
+            )
+        })?;
+        let files = glob_files(base_dir, pattern)?;
+
+        let mut entries = Vec::new();
+        for file in files {
+            if base_dir.join(&file).canonicalize().ok() == module_path.canonicalize().ok() {
+                continue;
+            }
+            let specifier = serde_json::to_string(&file)?;
+            let value = if call.eager {
+                let binding = format!("__mdeno_glob_{}", imports.len());
+                let _ = writeln!(eager_imports, "import * as {binding} from {specifier};");
+                binding
+            } else {
+                format!("() => import({specifier})")
+            };
+            entries.push(format!("{specifier}: {value}"));
+            imports.push(file);
+        }
+
+        let object = format!("{{ {} }}", entries.join(", "));
+        output.replace_range(call.span.start as usize..call.span.end as usize, &object);
+    }
+
+    Ok(Expanded {
+        source: eager_imports + &output,
+        imports,
+    })
+}
+
+/// Files under `base_dir` matching a relative glob pattern, as sorted
+/// specifiers in the pattern's own form (e.g. "./routes/home.ts")
+fn glob_files(base_dir: &Path, pattern: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    if !pattern.starts_with("./") && !pattern.starts_with("../") {
+        return Err(format!("import.meta.glob() patterns must be relative: {pattern}").into());
+    }
+
+    let mut files = Vec::new();
+    for pattern in expand_braces(pattern) {
+        let segments: Vec<&str> = pattern.split('/').collect();
+        // Leading segments without wildcards name the directory to walk
+        let literal = segments
+            .iter()
+            .take_while(|segment| !segment.contains(['*', '?']))
+            .count()
+            .min(segments.len() - 1);
+        let (prefix, glob) = segments.split_at(literal);
+
+        let root: PathBuf = prefix.iter().collect();
+        let mut found = Vec::new();
+        walk_files(&base_dir.join(&root), &mut Vec::new(), &mut found)?;
+        for relative in found {
+            let relative: Vec<&str> = relative.iter().map(String::as_str).collect();
+            if match_segments(glob, &relative) {
+                files.push([prefix, relative.as_slice()].concat().join("/"));
+            }
+        }
+    }
+
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Collect the paths of all files below `dir`, relative to where the walk began
+fn walk_files(
+    dir: &Path,
+    current: &mut Vec<String>,
+    found: &mut Vec<Vec<String>>,
+) -> Result<(), Box<dyn Error>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name == "node_modules" {
+            continue;
+        }
+        current.push(name);
+        if entry.file_type()?.is_dir() {
+            walk_files(&entry.path(), current, found)?;
+        } else {
+            found.push(current.clone());
+        }
+        current.pop();
+    }
+    Ok(())
+}
+
+/// Expand `{a,b}` alternatives into separate patterns
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_string()];
+    };
+    let Some(close) = pattern[open..].find('}').map(|i| open + i) else {
+        return vec![pattern.to_string()];
+    };
+    pattern[open + 1..close]
+        .split(',')
+        .flat_map(|alternative| {
+            expand_braces(&format!(
+                "{}{alternative}{}",
+                &pattern[..open],
+                &pattern[close + 1..]
+            ))
+        })
+        .collect()
+}
+
+/// Match path segments, where `**` matches any number of segments
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path)| {
+            match_wildcard(segment, name) && match_segments(rest, path)
+        }),
+    }
+}
+
+/// Match a single segment with `*` and `?` wildcards
+fn match_wildcard(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_segments() {
+        let pattern = ["routes", "**", "*.ts"];
+        assert!(match_segments(&pattern, &["routes", "home.ts"]));
+        assert!(match_segments(&pattern, &["routes", "a", "b", "page.ts"]));
+        assert!(!match_segments(&pattern, &["routes", "home.js"]));
+        assert!(match_wildcard("*_test.?s", "url_test.ts"));
+        assert_eq!(expand_braces("./*.{js,ts}"), vec!["./*.js", "./*.ts"]);
+    }
+
+    #[test]
+    fn test_expand_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("routes/blog")).unwrap();
+        fs::write(dir.path().join("routes/home.ts"), "").unwrap();
+        fs::write(dir.path().join("routes/blog/post.ts"), "").unwrap();
+        fs::write(dir.path().join("routes/notes.md"), "").unwrap();
+        let main = dir.path().join("main.ts");
+
+        let lazy = expand(
+            r#"const routes = import.meta.glob("./routes/**/*.ts");"#,
+            &main.display().to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            lazy.imports,
+            vec!["./routes/blog/post.ts", "./routes/home.ts"]
+        );
+        assert!(
+            lazy.source
+                .contains(r#""./routes/home.ts": () => import("./routes/home.ts")"#)
+        );
+
+        let eager = expand(
+            r#"const routes = import.meta.glob("./routes/*.ts", { eager: true });"#,
+            &main.display().to_string(),
+        )
+        .unwrap();
+        assert!(
+            eager
+                .source
+                .starts_with("import * as __mdeno_glob_0 from \"./routes/home.ts\";\n")
+        );
+        assert!(
+            eager
+                .source
+                .contains(r#""./routes/home.ts": __mdeno_glob_0"#)
+        );
+    }
+}
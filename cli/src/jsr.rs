@@ -1,22 +1,253 @@
 use crate::strip_types::transform;
 use oxc_allocator::Allocator;
-use oxc_ast::ast::Statement;
+use oxc_ast::ast::{
+    ExportAllDeclaration, ExportNamedDeclaration, Expression, ImportDeclaration, ImportExpression,
+    Statement, StringLiteral,
+};
+use oxc_ast_visit::{VisitMut, walk_mut};
+use oxc_codegen::Codegen;
 use oxc_parser::Parser;
-use oxc_span::SourceType;
+use oxc_span::{Atom, SourceType};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const JSR_URL: &str = "https://jsr.io";
 
+/// How long a cached package `meta.json` is used before fetching it again
+const PACKAGE_METADATA_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// What `--reload` asked to download again instead of reading from the cache
+static FORCE_RELOAD: OnceLock<Reload> = OnceLock::new();
+
+/// Make every resolver created from now on ignore the cache for `reload`
+pub fn set_force_reload(reload: Reload) {
+    let _ = FORCE_RELOAD.set(reload);
+}
+
+/// Cached JSR files to download again (--reload)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Reload {
+    #[default]
+    None,
+    All,
+    /// Only these packages, as `jsr:@scope/name` with an optional `@version`
+    Packages(Vec<String>),
+}
+
+impl Reload {
+    /// Whether files of `package` (at `version`, if known) must be downloaded again
+    fn includes(&self, package: &str, version: Option<&str>) -> bool {
+        match self {
+            Self::None => false,
+            Self::All => true,
+            Self::Packages(specifiers) => specifiers.iter().any(|specifier| {
+                let specifier = specifier.strip_prefix("jsr:").unwrap_or(specifier);
+                JsrResolver::parse_specifier(&format!("jsr:{specifier}")).is_ok_and(|parsed| {
+                    format!("{}/{}", parsed.scope, parsed.package) == package
+                        && parsed
+                            .version
+                            .as_deref()
+                            .is_none_or(|wanted| version.is_none_or(|version| version == wanted))
+                })
+            }),
+        }
+    }
+}
+
+/// The HTTP validator of a cached file, kept in a `.etag` file next to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Validator {
+    ETag(String),
+    LastModified(String),
+}
+
+impl Validator {
+    fn sidecar_path(cache_path: &Path) -> PathBuf {
+        let mut path = cache_path.as_os_str().to_owned();
+        path.push(".etag");
+        PathBuf::from(path)
+    }
+
+    fn read(cache_path: &Path) -> Option<Self> {
+        let sidecar = fs::read_to_string(Self::sidecar_path(cache_path)).ok()?;
+        let (name, value) = sidecar.trim().split_once(": ")?;
+        match name {
+            "ETag" => Some(Self::ETag(value.to_string())),
+            "Last-Modified" => Some(Self::LastModified(value.to_string())),
+            _ => None,
+        }
+    }
+
+    fn write(validator: Option<&Self>, cache_path: &Path) -> std::io::Result<()> {
+        let sidecar = Self::sidecar_path(cache_path);
+        match validator {
+            Some(Self::ETag(etag)) => fs::write(sidecar, format!("ETag: {etag}\n")),
+            Some(Self::LastModified(date)) => {
+                fs::write(sidecar, format!("Last-Modified: {date}\n"))
+            }
+            // Don't revalidate against the validator of an older download
+            None => match fs::remove_file(sidecar) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// The conditional request header asking for the file only if it changed
+    fn condition(&self) -> (&'static str, &str) {
+        match self {
+            Self::ETag(etag) => ("If-None-Match", etag),
+            Self::LastModified(date) => ("If-Modified-Since", date),
+        }
+    }
+}
+
+/// Response to a (possibly conditional) download
+enum Fetched {
+    NotFound,
+    /// The server answered 304 to a conditional request
+    NotModified,
+    Body {
+        bytes: Vec<u8>,
+        validator: Option<Validator>,
+    },
+}
+
+/// Failure while resolving or downloading a JSR package
+#[derive(Debug)]
+pub enum JsrError {
+    /// The request to jsr.io couldn't be made or answered
+    NetworkError(cyper::Error),
+    /// jsr.io answered with a status other than success or 404
+    HttpStatus {
+        url: String,
+        status: u16,
+    },
+    /// Package metadata isn't valid JSON
+    ParseError(serde_json::Error),
+    IoError(std::io::Error),
+    /// A downloaded file doesn't match the checksum in the package manifest
+    ChecksumMismatch {
+        expected: String,
+        actual: String,
+    },
+    /// A downloaded file isn't listed in the package manifest, so it can't
+    /// be verified
+    NotInManifest(String),
+    /// The manifest uses a checksum algorithm other than sha256
+    UnsupportedChecksum(String),
+    /// `@scope/package@version` (or one of its files) doesn't exist
+    PackageNotFound(String),
+    /// The package has no export with this name
+    ExportNotFound(String),
+    InvalidSpecifier(String),
+    /// TypeScript in a package file couldn't be stripped
+    StripTypesError(String),
+}
+
+impl fmt::Display for JsrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NetworkError(e) => write!(f, "Failed to reach JSR: {e}"),
+            Self::HttpStatus { url, status } => write!(f, "Failed to fetch {url}: HTTP {status}"),
+            Self::ParseError(e) => write!(f, "Failed to parse JSR metadata: {e}"),
+            Self::IoError(e) => write!(f, "{e}"),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Integrity check failed: expected sha256:{expected}, got sha256:{actual}"
+            ),
+            Self::NotInManifest(file) => write!(
+                f,
+                "Integrity check failed: {file} is not listed in the package manifest"
+            ),
+            Self::UnsupportedChecksum(checksum) => {
+                write!(f, "Unsupported checksum format: {checksum}")
+            }
+            Self::PackageNotFound(package) => write!(f, "{package} was not found on JSR"),
+            Self::ExportNotFound(export) => write!(f, "Export '{export}' not found in package"),
+            Self::InvalidSpecifier(message) => write!(f, "{message}"),
+            Self::StripTypesError(message) => write!(f, "Failed to strip TypeScript: {message}"),
+        }
+    }
+}
+
+impl Error for JsrError {
+    // Display already includes the wrapped error, so continue the chain below it
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NetworkError(e) => e.source(),
+            Self::ParseError(e) => e.source(),
+            Self::IoError(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<cyper::Error> for JsrError {
+    fn from(e: cyper::Error) -> Self {
+        Self::NetworkError(e)
+    }
+}
+
+impl From<serde_json::Error> for JsrError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::ParseError(e)
+    }
+}
+
+impl From<std::io::Error> for JsrError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IoError(e)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct JsrVersionMetadata {
     pub exports: HashMap<String, String>,
+    /// File checksums keyed by absolute package path (e.g. "/mod.ts")
+    #[serde(default)]
+    pub manifest: HashMap<String, JsrManifestEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JsrManifestEntry {
+    pub checksum: String,
+}
+
+/// A package's `meta.json`, listing every published version
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JsrPackageMetadata {
+    #[serde(default)]
+    pub latest: Option<String>,
+    pub versions: HashMap<String, JsrPackageVersion>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct JsrPackageVersion {
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+/// A published version of a package, as listed by `list_versions`
+#[derive(Debug, PartialEq, Eq)]
+pub struct JsrVersionInfo {
+    pub version: String,
+    pub yanked: bool,
+    pub latest: bool,
 }
 
 pub struct JsrResolver {
     cache_dir: PathBuf,
+    check_integrity: bool,
+    reload: Reload,
 }
 
 #[derive(Debug)]
@@ -46,20 +277,77 @@ impl JsrResolver {
             PathBuf::from(home).join(".mdeno").join("jsr")
         };
 
-        Self { cache_dir }
+        Self {
+            cache_dir,
+            check_integrity: true,
+            reload: FORCE_RELOAD.get().cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Enable or disable checksum verification of downloaded files
+    #[must_use]
+    pub fn with_check_integrity(mut self, check_integrity: bool) -> Self {
+        self.check_integrity = check_integrity;
+        self
+    }
+
+    /// Ignore the cache for the files selected by `reload`
+    #[must_use]
+    pub fn with_reload(mut self, reload: Reload) -> Self {
+        self.reload = reload;
+        self
+    }
+
+    /// Verify `content` against a JSR manifest checksum (`sha256:<hex>`)
+    ///
+    /// # Errors
+    /// Returns an error if the checksum format is unsupported or doesn't match
+    pub fn verify_checksum(content: &[u8], checksum: &str) -> Result<(), JsrError> {
+        let expected = checksum
+            .strip_prefix("sha256:")
+            .ok_or_else(|| JsrError::UnsupportedChecksum(checksum.to_string()))?;
+        let actual = format!("{:x}", Sha256::digest(content));
+        if actual.eq_ignore_ascii_case(expected) {
+            Ok(())
+        } else {
+            Err(JsrError::ChecksumMismatch {
+                expected: expected.to_string(),
+                actual,
+            })
+        }
+    }
+
+    /// Verify the content of a package file against its entry in the package
+    /// manifest. A file the manifest doesn't list fails the check.
+    ///
+    /// # Errors
+    /// Returns an error if the file isn't in the manifest or its checksum
+    /// doesn't match
+    pub fn verify_manifest_file(
+        manifest: &HashMap<String, JsrManifestEntry>,
+        file_path: &str,
+        content: &[u8],
+    ) -> Result<(), JsrError> {
+        let manifest_key = format!("/{}", file_path.trim_start_matches("./"));
+        let entry = manifest
+            .get(&manifest_key)
+            .ok_or(JsrError::NotInManifest(manifest_key))?;
+        Self::verify_checksum(content, &entry.checksum)
     }
 
     /// # Errors
     /// Returns an error if the specifier is invalid
-    pub fn parse_specifier(specifier: &str) -> Result<ParsedSpecifier, String> {
+    pub fn parse_specifier(specifier: &str) -> Result<ParsedSpecifier, JsrError> {
         // Parse jsr:@scope/package[@version]/path
         let without_prefix = specifier
             .strip_prefix("jsr:")
-            .ok_or("Not a JSR specifier")?;
+            .ok_or_else(|| JsrError::InvalidSpecifier("Not a JSR specifier".to_string()))?;
 
         let parts: Vec<&str> = without_prefix.splitn(2, '/').collect();
         if parts.len() != 2 {
-            return Err("Invalid JSR specifier format".to_string());
+            return Err(JsrError::InvalidSpecifier(
+                "Invalid JSR specifier format".to_string(),
+            ));
         }
 
         let scope = parts[0]; // @scope
@@ -94,32 +382,19 @@ impl JsrResolver {
     ///
     /// # Errors
     /// Returns an error if resolution fails
-    pub fn resolve(&self, specifier: &str) -> Result<HashMap<String, PathBuf>, String> {
+    pub fn resolve(&self, specifier: &str) -> Result<HashMap<String, PathBuf>, JsrError> {
         let parsed = Self::parse_specifier(specifier)?;
         let full_package = format!("{}/{}", parsed.scope, parsed.package);
 
         // Version must be specified
-        let resolved_version = parsed
-            .version
-            .ok_or("Version must be specified in JSR import")?;
+        let resolved_version = parsed.version.ok_or_else(|| {
+            JsrError::InvalidSpecifier("Version must be specified in JSR import".to_string())
+        })?;
 
         // Determine file path from exports
-        let exports = self.fetch_exports(&full_package, &resolved_version)?;
+        let metadata = self.fetch_metadata(&full_package, &resolved_version)?;
         let has_file_path = parsed.file_path.is_some();
-        let export_key = if let Some(path) = parsed.file_path {
-            // Export name provided (e.g., "assert_equals" from jsr:@std/assert@1.0.0/assert_equals)
-            format!("./{path}")
-        } else {
-            // No export name, use default export
-            ".".to_string()
-        };
-
-        let file = exports
-            .get(&export_key)
-            .map(std::string::String::as_str)
-            .ok_or_else(|| format!("Export '{export_key}' not found in package"))?
-            .trim_start_matches("./")
-            .to_string();
+        let file = Self::export_file(&metadata.exports, parsed.file_path.as_deref())?;
 
         // Download and cache the file and all its dependencies
         let mut module_map = HashMap::new();
@@ -127,6 +402,7 @@ impl JsrResolver {
             &full_package,
             &resolved_version,
             &file,
+            &metadata.manifest,
             &mut module_map,
             &mut HashSet::new(),
         )?;
@@ -158,9 +434,10 @@ impl JsrResolver {
         package: &str,
         version: &str,
         file_path: &str,
+        manifest: &HashMap<String, JsrManifestEntry>,
         module_map: &mut HashMap<String, PathBuf>,
         visited: &mut HashSet<String>,
-    ) -> Result<(), String> {
+    ) -> Result<(), JsrError> {
         // Check if already visited
         let visit_key = format!("{package}/{version}/{file_path}");
         if visited.contains(&visit_key) {
@@ -171,24 +448,20 @@ impl JsrResolver {
         // Construct JSR specifier for this file
         let file_without_ext = file_path.trim_start_matches("./").trim_end_matches(".ts");
         let mut package_parts = package.split('/');
-        let scope = package_parts
-            .next()
-            .ok_or_else(|| "Invalid package format".to_string())?;
-        let package_name = package_parts
-            .next()
-            .ok_or_else(|| "Invalid package format".to_string())?;
+        let invalid = || JsrError::InvalidSpecifier(format!("Invalid package format: {package}"));
+        let scope = package_parts.next().ok_or_else(invalid)?;
+        let package_name = package_parts.next().ok_or_else(invalid)?;
         let jsr_specifier = format!("jsr:{scope}/{package_name}@{version}/{file_without_ext}");
 
         // Download the file
-        let cache_path = self.fetch_file_impl(package, version, file_path)?;
+        let cache_path = self.fetch_file_impl(package, version, file_path, manifest)?;
         module_map.insert(jsr_specifier, cache_path.clone());
 
         // Read the cached file to extract dependencies
-        let content = fs::read_to_string(&cache_path)
-            .map_err(|e| format!("Failed to read cached file: {e}"))?;
+        let content = fs::read_to_string(&cache_path)?;
 
         // Extract relative imports
-        let imports = Self::extract_relative_imports(&content);
+        let imports = Self::extract_relative_imports(&content, SourceType::mjs());
         for import_path in imports {
             // Convert .js back to .ts for fetching
             let import_path_ts = if Path::new(&import_path)
@@ -208,13 +481,22 @@ impl JsrResolver {
             let resolved = base_dir.join(&import_path_ts);
             let normalized = resolved
                 .to_str()
-                .ok_or("Failed to normalize path")?
+                .ok_or_else(|| {
+                    JsrError::InvalidSpecifier(format!("Invalid import path: {import_path}"))
+                })?
                 .replace('\\', "/")
                 .trim_start_matches("./")
                 .to_string();
 
             // Recursively fetch dependencies
-            self.fetch_file_with_deps(package, version, &normalized, module_map, visited)?;
+            self.fetch_file_with_deps(
+                package,
+                version,
+                &normalized,
+                manifest,
+                module_map,
+                visited,
+            )?;
         }
 
         Ok(())
@@ -225,7 +507,8 @@ impl JsrResolver {
         package: &str,
         version: &str,
         file_path: &str,
-    ) -> Result<PathBuf, String> {
+        manifest: &HashMap<String, JsrManifestEntry>,
+    ) -> Result<PathBuf, JsrError> {
         // Determine cache file path (.ts files are cached as .js)
         let cache_file_path = if Path::new(file_path)
             .extension()
@@ -245,31 +528,36 @@ impl JsrResolver {
             .join(version)
             .join(&cache_file_path);
 
-        // Check cache first
-        if cache_path.exists() {
-            return Ok(cache_path);
-        }
-
-        // Download from JSR using cyper
-        let file_url = format!("{JSR_URL}/{package}/{version}/{file_path}");
-
-        let compio_runtime = compio::runtime::Runtime::new()
-            .map_err(|e| format!("Failed to create runtime: {e}"))?;
+        // A cached file is revalidated with a conditional request, unless
+        // --reload asks for it to be downloaded again unconditionally
+        let cached = if cache_path.exists() && !self.reload.includes(package, Some(version)) {
+            match Validator::read(&cache_path) {
+                Some(validator) => Some(validator),
+                // Nothing to revalidate against
+                None => return Ok(cache_path),
+            }
+        } else {
+            None
+        };
 
-        let mut content = compio_runtime.block_on(async {
-            let client = cyper::Client::new();
-            let response = client
-                .get(&file_url)
-                .map_err(|e| format!("Failed to create request: {e}"))?
-                .send()
-                .await
-                .map_err(|e| format!("Failed to fetch JSR file: {e}"))?;
-
-            response
-                .text()
-                .await
-                .map_err(|e| format!("Failed to read JSR file: {e}"))
-        })?;
+        let (mut content, validator) =
+            match self.download_file(package, version, file_path, manifest, cached.as_ref()) {
+                Ok(Some(download)) => download,
+                Ok(None) => return Ok(cache_path),
+                // Offline or failing upstream: the cached copy is still usable.
+                // Anything else, like an integrity failure, is reported.
+                Err(JsrError::NetworkError(_) | JsrError::HttpStatus { status: 500.., .. })
+                    if cached.is_some() =>
+                {
+                    return Ok(cache_path);
+                }
+                Err(e) => {
+                    // Never leave a corrupted file behind, so the next run re-downloads it
+                    let _ = fs::remove_file(&cache_path);
+                    let _ = Validator::write(None, &cache_path);
+                    return Err(e);
+                }
+            };
 
         // Strip TypeScript if .ts file
         if Path::new(file_path)
@@ -277,7 +565,7 @@ impl JsrResolver {
             .is_some_and(|ext| ext.eq_ignore_ascii_case("ts"))
         {
             content = transform(&content, file_path)
-                .map_err(|e| format!("Failed to strip TypeScript: {e}"))?;
+                .map_err(|e| JsrError::StripTypesError(format!("{e} ({file_path})")))?;
         }
 
         // Rewrite .ts imports to .js
@@ -285,63 +573,261 @@ impl JsrResolver {
 
         // Create cache directory
         if let Some(parent) = cache_path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create cache directory: {e}"))?;
+            fs::create_dir_all(parent)?;
         }
 
         // Write to cache
-        fs::write(&cache_path, content).map_err(|e| format!("Failed to write cache: {e}"))?;
+        fs::write(&cache_path, content)?;
+        Validator::write(validator.as_ref(), &cache_path)?;
 
         Ok(cache_path)
     }
 
-    #[allow(clippy::unused_self)] // Method uses cache_dir from self
-    fn fetch_exports(
+    /// Download a package file, verifying it against the manifest checksum.
+    /// Given the validator of a cached copy, returns `None` if the file hasn't
+    /// changed since.
+    fn download_file(
         &self,
         package: &str,
         version: &str,
-    ) -> Result<HashMap<String, String>, String> {
-        let meta_url = format!("{JSR_URL}/{package}/{version}_meta.json");
+        file_path: &str,
+        manifest: &HashMap<String, JsrManifestEntry>,
+        cached: Option<&Validator>,
+    ) -> Result<Option<(String, Option<Validator>)>, JsrError> {
+        let file_url = format!("{JSR_URL}/{package}/{version}/{file_path}");
+        let (raw_content, validator) = match Self::fetch(&file_url, cached)? {
+            Fetched::Body { bytes, validator } => (bytes, validator),
+            Fetched::NotModified => return Ok(None),
+            Fetched::NotFound => {
+                return Err(JsrError::PackageNotFound(format!(
+                    "{package}@{version}/{file_path}"
+                )));
+            }
+        };
 
-        let compio_runtime = compio::runtime::Runtime::new()
-            .map_err(|e| format!("Failed to create runtime: {e}"))?;
+        // Verify the raw content against the package manifest
+        if self.check_integrity {
+            Self::verify_manifest_file(manifest, file_path, &raw_content)?;
+        }
 
-        let body = compio_runtime.block_on(async {
-            let client = cyper::Client::new();
-            let response = client
-                .get(&meta_url)
-                .map_err(|e| format!("Failed to create request: {e}"))?
-                .send()
-                .await
-                .map_err(|e| format!("Failed to fetch JSR version metadata: {e}"))?;
-
-            response
-                .text()
-                .await
-                .map_err(|e| format!("Failed to read JSR version metadata: {e}"))
+        let content = String::from_utf8(raw_content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(Some((content, validator)))
+    }
+
+    /// Download the original (un-stripped) sources of a JSR export and its
+    /// relative dependencies. Returns the entry file path and a map of
+    /// package file paths to sources.
+    ///
+    /// # Errors
+    /// Returns an error if resolution or downloading fails
+    pub fn fetch_sources(
+        &self,
+        specifier: &str,
+    ) -> Result<(String, HashMap<String, String>), JsrError> {
+        let parsed = Self::parse_specifier(specifier)?;
+        let full_package = format!("{}/{}", parsed.scope, parsed.package);
+        let version = parsed.version.ok_or_else(|| {
+            JsrError::InvalidSpecifier("Version must be specified in JSR import".to_string())
         })?;
 
-        let metadata: JsrVersionMetadata = serde_json::from_str(&body)
-            .map_err(|e| format!("Failed to parse JSR version metadata: {e}"))?;
+        let metadata = self.fetch_metadata(&full_package, &version)?;
+        let entry = Self::export_file(&metadata.exports, parsed.file_path.as_deref())?;
+
+        let mut sources = HashMap::new();
+        let mut pending = vec![entry.clone()];
+        while let Some(file_path) = pending.pop() {
+            if sources.contains_key(&file_path) {
+                continue;
+            }
+            // Without a cached validator the download is unconditional
+            let (content, _) = self
+                .download_file(
+                    &full_package,
+                    &version,
+                    &file_path,
+                    &metadata.manifest,
+                    None,
+                )?
+                .unwrap_or_default();
+
+            for import_path in Self::extract_relative_imports(&content, SourceType::ts()) {
+                let import_path = Path::new(&import_path);
+                let import_path = if import_path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("js"))
+                {
+                    import_path.with_extension("ts")
+                } else {
+                    import_path.to_path_buf()
+                };
+                let base_dir = Path::new(&file_path).parent().unwrap_or(Path::new(""));
+                pending.push(normalize_package_path(&base_dir.join(import_path)));
+            }
+
+            sources.insert(file_path, content);
+        }
+
+        Ok((entry, sources))
+    }
+
+    /// Every published version of `@scope/package`, newest first
+    ///
+    /// # Errors
+    /// Returns an error if the package metadata can't be fetched or parsed
+    pub fn list_versions(
+        &self,
+        scope: &str,
+        package: &str,
+    ) -> Result<Vec<JsrVersionInfo>, JsrError> {
+        let scope = scope.trim_start_matches('@');
+        let metadata = self.fetch_package_metadata(&format!("@{scope}/{package}"))?;
+
+        let mut versions: Vec<JsrVersionInfo> = metadata
+            .versions
+            .into_iter()
+            .map(|(version, info)| JsrVersionInfo {
+                latest: metadata.latest.as_ref() == Some(&version),
+                yanked: info.yanked,
+                version,
+            })
+            .collect();
+        versions.sort_by(|a, b| compare_versions(&b.version, &a.version));
+        Ok(versions)
+    }
+
+    /// Read a package's `meta.json`, from the cache while it's fresh
+    fn fetch_package_metadata(&self, package: &str) -> Result<JsrPackageMetadata, JsrError> {
+        let cache_path = self.cache_dir.join(package).join("meta.json");
+        let timestamp_path = self.cache_dir.join(package).join("meta.json.timestamp");
+
+        let cached = fs::read_to_string(&cache_path).ok();
+        let fetched_at = fs::read_to_string(&timestamp_path)
+            .ok()
+            .and_then(|timestamp| timestamp.trim().parse::<u64>().ok())
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        let fresh = fetched_at
+            .and_then(|fetched_at| SystemTime::now().duration_since(fetched_at).ok())
+            .is_some_and(|age| age < PACKAGE_METADATA_TTL);
+
+        let body = match cached {
+            Some(body) if fresh && !self.reload.includes(package, None) => body,
+            cached => match Self::fetch_bytes(&format!("{JSR_URL}/{package}/meta.json")) {
+                Ok(Some(body)) => {
+                    if let Some(parent) = cache_path.parent() {
+                        let _ = fs::create_dir_all(parent);
+                    }
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs();
+                    let _ = fs::write(&cache_path, &body);
+                    let _ = fs::write(&timestamp_path, now.to_string());
+                    String::from_utf8_lossy(&body).into_owned()
+                }
+                Ok(None) => return Err(JsrError::PackageNotFound(package.to_string())),
+                // A stale list is still better than none when offline
+                Err(e) => cached.ok_or(e)?,
+            },
+        };
+
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Download `url`, returning `None` if JSR answers 404
+    fn fetch_bytes(url: &str) -> Result<Option<Vec<u8>>, JsrError> {
+        match Self::fetch(url, None)? {
+            Fetched::Body { bytes, .. } => Ok(Some(bytes)),
+            Fetched::NotFound | Fetched::NotModified => Ok(None),
+        }
+    }
+
+    /// Download `url`, only if it changed since `validator` was received
+    fn fetch(url: &str, validator: Option<&Validator>) -> Result<Fetched, JsrError> {
+        let compio_runtime = compio::runtime::Runtime::new()?;
+
+        compio_runtime.block_on(async {
+            let client = cyper::Client::new();
+            let mut request = client.get(url)?;
+            if let Some(validator) = validator {
+                let (name, value) = validator.condition();
+                request = request.header(name, value)?;
+            }
+            let response = request.send().await?;
+            let status = response.status();
+            match status.as_u16() {
+                404 => return Ok(Fetched::NotFound),
+                304 if validator.is_some() => return Ok(Fetched::NotModified),
+                _ if !status.is_success() => {
+                    return Err(JsrError::HttpStatus {
+                        url: url.to_string(),
+                        status: status.as_u16(),
+                    });
+                }
+                _ => {}
+            }
+
+            let header = |name: &str| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string)
+            };
+            let validator = header("etag")
+                .map(Validator::ETag)
+                .or_else(|| header("last-modified").map(Validator::LastModified));
+            Ok(Fetched::Body {
+                bytes: response.bytes().await?.to_vec(),
+                validator,
+            })
+        })
+    }
+
+    /// Look up the file behind an export name (`None` for the default "." export)
+    fn export_file(
+        exports: &HashMap<String, String>,
+        export_name: Option<&str>,
+    ) -> Result<String, JsrError> {
+        let export_key = export_name.map_or_else(|| ".".to_string(), |path| format!("./{path}"));
+        Ok(exports
+            .get(&export_key)
+            .ok_or_else(|| JsrError::ExportNotFound(export_key.clone()))?
+            .trim_start_matches("./")
+            .to_string())
+    }
 
-        Ok(metadata.exports)
+    #[allow(clippy::unused_self)] // Method uses cache_dir from self
+    fn fetch_metadata(&self, package: &str, version: &str) -> Result<JsrVersionMetadata, JsrError> {
+        let meta_url = format!("{JSR_URL}/{package}/{version}_meta.json");
+        let body = Self::fetch_bytes(&meta_url)?
+            .ok_or_else(|| JsrError::PackageNotFound(format!("{package}@{version}")))?;
+        Ok(serde_json::from_slice(&body)?)
     }
 
+    /// Point relative `.ts` imports and re-exports at the `.js` files they
+    /// are cached as, leaving every other string untouched
     fn rewrite_ts_imports(content: &str) -> String {
-        // Simple regex-based rewrite of .ts imports to .js
-        // This handles: import ... from "./foo.ts" and export ... from "./foo.ts"
-        content
-            .replace(r#"from "./\"#, "FROM_PLACEHOLDER_")
-            .replace(r"from '../\", "FROM_PARENT_PLACEHOLDER_")
-            .replace(r#".ts""#, r#".js""#)
-            .replace(r".ts'", r".js'")
-            .replace("FROM_PLACEHOLDER_", r#"from "./"#)
-            .replace("FROM_PARENT_PLACEHOLDER_", r"from '../")
-    }
-
-    fn extract_relative_imports(source: &str) -> Vec<String> {
         let allocator = Allocator::default();
-        let source_type = SourceType::mjs();
+        let parser_ret = Parser::new(&allocator, content, SourceType::mjs()).parse();
+        if !parser_ret.errors.is_empty() {
+            return content.to_string();
+        }
+        let mut program = parser_ret.program;
+
+        let mut rewriter = TsImportRewriter {
+            allocator: &allocator,
+            changed: false,
+        };
+        rewriter.visit_program(&mut program);
+        if !rewriter.changed {
+            return content.to_string();
+        }
+        Codegen::new().build(&program).code
+    }
+
+    fn extract_relative_imports(source: &str, source_type: SourceType) -> Vec<String> {
+        let allocator = Allocator::default();
 
         let parser_ret = Parser::new(&allocator, source, source_type).parse();
         if !parser_ret.errors.is_empty() {
@@ -380,3 +866,203 @@ impl JsrResolver {
         imports
     }
 }
+
+/// Rewrites `./foo.ts` specifiers of imports, re-exports and literal dynamic
+/// imports to `./foo.js`
+struct TsImportRewriter<'a> {
+    allocator: &'a Allocator,
+    changed: bool,
+}
+
+impl<'a> TsImportRewriter<'a> {
+    fn rewrite(&mut self, source: &mut StringLiteral<'a>) {
+        let specifier = source.value.as_str();
+        if !(specifier.starts_with("./") || specifier.starts_with("../")) {
+            return;
+        }
+        if let Some(stem) = specifier.strip_suffix(".ts") {
+            let rewritten = self.allocator.alloc_str(&format!("{stem}.js"));
+            source.value = Atom::from(rewritten);
+            // Codegen prints `raw` when present, which still has the old extension
+            source.raw = None;
+            self.changed = true;
+        }
+    }
+}
+
+impl<'a> VisitMut<'a> for TsImportRewriter<'a> {
+    fn visit_import_declaration(&mut self, decl: &mut ImportDeclaration<'a>) {
+        self.rewrite(&mut decl.source);
+    }
+
+    fn visit_export_named_declaration(&mut self, decl: &mut ExportNamedDeclaration<'a>) {
+        if let Some(source) = &mut decl.source {
+            self.rewrite(source);
+        }
+        walk_mut::walk_export_named_declaration(self, decl);
+    }
+
+    fn visit_export_all_declaration(&mut self, decl: &mut ExportAllDeclaration<'a>) {
+        self.rewrite(&mut decl.source);
+    }
+
+    fn visit_import_expression(&mut self, expr: &mut ImportExpression<'a>) {
+        if let Expression::StringLiteral(source) = &mut expr.source {
+            self.rewrite(source);
+        }
+        walk_mut::walk_import_expression(self, expr);
+    }
+}
+
+/// Order two semver versions; pre-releases come before their release
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn parse(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.split('+').next().unwrap_or(version);
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let numbers = core.split('.').map(|n| n.parse().unwrap_or(0)).collect();
+        (numbers, pre)
+    }
+
+    let (a_core, a_pre) = parse(a);
+    let (b_core, b_pre) = parse(b);
+    a_core.cmp(&b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a_pre), Some(b_pre)) => {
+            let a_ids = a_pre.split('.');
+            let b_ids = b_pre.split('.');
+            for (a_id, b_id) in a_ids.clone().zip(b_ids.clone()) {
+                let ordering = match (a_id.parse::<u64>(), b_id.parse::<u64>()) {
+                    (Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num),
+                    // Numeric identifiers sort before alphanumeric ones
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => a_id.cmp(b_id),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a_ids.count().cmp(&b_ids.count())
+        }
+    })
+}
+
+/// Resolve `.` and `..` components of a path inside a package, using `/` separators
+fn normalize_package_path(path: &Path) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::ParentDir => {
+                parts.pop();
+            }
+            std::path::Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            _ => {}
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        let mut versions = vec![
+            "0.9.2",
+            "1.0.0",
+            "1.0.0-rc.1",
+            "1.0.0-rc.10",
+            "1.0.0-beta",
+            "0.10.0",
+            "1.0.0-rc.2",
+        ];
+        versions.sort_by(|a, b| compare_versions(b, a));
+        assert_eq!(
+            versions,
+            [
+                "1.0.0",
+                "1.0.0-rc.10",
+                "1.0.0-rc.2",
+                "1.0.0-rc.1",
+                "1.0.0-beta",
+                "0.10.0",
+                "0.9.2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reload_includes() {
+        let reload = Reload::Packages(vec![
+            "jsr:@std/path".to_string(),
+            "@std/fs@1.0.0".to_string(),
+        ]);
+        assert!(reload.includes("@std/path", Some("1.0.8")));
+        assert!(reload.includes("@std/fs", Some("1.0.0")));
+        assert!(reload.includes("@std/fs", None));
+        assert!(!reload.includes("@std/fs", Some("1.0.1")));
+        assert!(!reload.includes("@std/assert", Some("1.0.0")));
+        assert!(Reload::All.includes("@std/assert", None));
+        assert!(!Reload::None.includes("@std/assert", None));
+    }
+
+    #[test]
+    fn test_validator_sidecar() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("mod.js");
+        assert_eq!(Validator::read(&cache_path), None);
+
+        let etag = Validator::ETag("\"abc\"".to_string());
+        Validator::write(Some(&etag), &cache_path).unwrap();
+        assert!(temp_dir.path().join("mod.js.etag").exists());
+        assert_eq!(Validator::read(&cache_path), Some(etag));
+
+        let date = Validator::LastModified("Wed, 21 Oct 2015 07:28:00 GMT".to_string());
+        Validator::write(Some(&date), &cache_path).unwrap();
+        assert_eq!(Validator::read(&cache_path), Some(date));
+
+        Validator::write(None, &cache_path).unwrap();
+        assert_eq!(Validator::read(&cache_path), None);
+        Validator::write(None, &cache_path).unwrap();
+    }
+
+    #[test]
+    fn test_package_metadata() {
+        let json = r#"{"scope":"std","name":"assert","latest":"1.0.1",
+            "versions":{"1.0.1":{},"1.0.0":{"yanked":true}}}"#;
+        let metadata: JsrPackageMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(metadata.latest.as_deref(), Some("1.0.1"));
+        assert!(metadata.versions["1.0.0"].yanked);
+        assert!(!metadata.versions["1.0.1"].yanked);
+    }
+
+    #[test]
+    fn test_rewrite_ts_imports() {
+        let source = r#"import { a } from "./a.ts";
+export * from "../b.ts";
+export { c } from "./c.ts";
+export * as d from "./d.ts";
+const lazy = () => import("./e.ts");
+const message = 'see "./notes.ts"';
+import "jsr:@std/fmt/colors.ts";
+"#;
+        let output = JsrResolver::rewrite_ts_imports(source);
+
+        for specifier in ["./a.js", "../b.js", "./c.js", "./d.js", "./e.js"] {
+            assert!(output.contains(specifier), "{specifier} in {output}");
+        }
+        // Strings that aren't specifiers and non-relative imports are kept
+        assert!(output.contains("./notes.ts") && !output.contains("./notes.js"));
+        assert!(output.contains("jsr:@std/fmt/colors.ts"));
+
+        let imports = JsrResolver::extract_relative_imports(&output, SourceType::mjs());
+        assert_eq!(imports, ["./a.js", "../b.js", "./c.js", "./d.js"]);
+    }
+}
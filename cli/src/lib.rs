@@ -2,5 +2,7 @@
 
 pub mod bundler;
 pub mod flag;
+mod import_glob;
 pub mod jsr;
 mod strip_types;
+mod tree_shake;
@@ -6,8 +6,10 @@ pub mod bundler;
 mod commands;
 mod error_fmt;
 mod flag;
+mod import_glob;
 pub mod jsr;
 mod strip_types;
+mod tree_shake;
 
 fn main() {
     if let Err(e) = run() {
@@ -17,6 +19,8 @@ fn main() {
 }
 
 fn run() -> Result<(), Box<dyn Error>> {
+    mdeno_runtime::set_mdeno_version(env!("CARGO_PKG_VERSION"));
+
     // Check if this executable has embedded bytecode
     if let Some(bytecode) = extract_embedded_bytecode() {
         // Standalone binary: args are retrieved directly in deno_os module
@@ -27,20 +31,91 @@ fn run() -> Result<(), Box<dyn Error>> {
     let cli_args = flag::parse_args();
 
     // Set script arguments for Deno.args
-    mdeno_runtime::set_script_args(cli_args.script_args);
+    mdeno_runtime::set_script_args(cli_args.script_args.clone());
+    if let Some(seed) = cli_args.seed {
+        mdeno_runtime::set_seed(seed);
+    }
+    if let Some(location) = &cli_args.location {
+        mdeno_runtime::set_location(location)?;
+    }
+    mdeno_runtime::deny_permissions("read", &cli_args.deny.read);
+    mdeno_runtime::deny_permissions("write", &cli_args.deny.write);
+    jsr::set_force_reload(cli_args.reload.clone());
 
     match cli_args.command {
         flag::Command::Eval { code } => {
             commands::eval::execute(&code)?;
         }
         flag::Command::Run { file_path } => {
-            commands::run::execute(&file_path, cli_args.unstable)?;
+            commands::run::execute(
+                &file_path,
+                cli_args.unstable,
+                !cli_args.no_check_integrity,
+                !cli_args.no_tree_shake,
+            )?;
         }
         flag::Command::Compile { file_path } => {
-            commands::compile::execute(&file_path, cli_args.unstable)?;
+            commands::compile::execute(
+                &file_path,
+                cli_args.unstable,
+                !cli_args.no_check_integrity,
+                !cli_args.no_tree_shake,
+            )?;
+        }
+        flag::Command::Test {
+            pattern,
+            parallel,
+            jobs,
+            retries,
+            timeout,
+        } => {
+            commands::test::execute(
+                pattern,
+                cli_args.unstable,
+                !cli_args.no_check_integrity,
+                !cli_args.no_tree_shake,
+                jobs.or_else(|| parallel.then(commands::test::default_jobs)),
+                retries.unwrap_or(0),
+                timeout.unwrap_or(0),
+            )?;
+        }
+        flag::Command::Bench {
+            pattern,
+            json,
+            output,
+            baseline,
+            threshold,
+        } => {
+            commands::bench::execute(
+                pattern,
+                cli_args.unstable,
+                !cli_args.no_check_integrity,
+                !cli_args.no_tree_shake,
+                &commands::bench::BenchOptions {
+                    json,
+                    output,
+                    baseline,
+                    threshold: threshold.unwrap_or(commands::bench::DEFAULT_THRESHOLD),
+                },
+            )?;
+        }
+        flag::Command::Task { name, env_file } => {
+            commands::task::execute(name.as_deref(), &cli_args.script_args, env_file.as_deref())?;
+        }
+        flag::Command::Repl => {
+            commands::repl::execute()?;
+        }
+        flag::Command::Doc { target, json } => {
+            commands::doc::execute(&target, json)?;
+        }
+        flag::Command::Info { target, json } => {
+            commands::info::execute(&target, !cli_args.no_check_integrity, json)?;
+        }
+        flag::Command::Upgrade { version } => {
+            commands::upgrade::execute(version.as_deref())?;
         }
-        flag::Command::Test { pattern } => {
-            commands::test::execute(pattern, cli_args.unstable)?;
+        flag::Command::Versions { specifier } => {
+            commands::versions::execute(&specifier)?;
         }
         flag::Command::Help { command } => {
             // Show help using bpaf directly (no process spawn)
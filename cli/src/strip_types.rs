@@ -5,12 +5,38 @@ use oxc_semantic::SemanticBuilder;
 use oxc_span::SourceType;
 use oxc_transformer::{TransformOptions, Transformer};
 use std::error::Error;
+use std::path::Path;
+
+/// Source type for a file mdeno loads. Every file is evaluated as an ES
+/// module, so it's parsed as one: content-based detection misses modules
+/// whose only module syntax is a top-level `for await`.
+pub fn source_type(filename: &str) -> SourceType {
+    SourceType::from_path(Path::new(filename))
+        .unwrap_or_default()
+        .with_module(true)
+}
 
 pub fn transform(source: &str, filename: &str) -> Result<String, Box<dyn Error>> {
+    transform_impl(source, filename, None).map(|(code, _)| code)
+}
+
+/// Like `transform`, also returning a JSON source map whose `sources` entry
+/// is `source_name`
+pub fn transform_with_source_map(
+    source: &str,
+    filename: &str,
+    source_name: &str,
+) -> Result<(String, Option<String>), Box<dyn Error>> {
+    transform_impl(source, filename, Some(Path::new(source_name)))
+}
+
+fn transform_impl(
+    source: &str,
+    filename: &str,
+    source_map_path: Option<&Path>,
+) -> Result<(String, Option<String>), Box<dyn Error>> {
     let allocator = Allocator::default();
-    let source_type = SourceType::from_path(std::path::Path::new(filename))
-        .unwrap_or_default()
-        .with_typescript(true);
+    let source_type = source_type(filename).with_typescript(true);
 
     // Parse the source code
     let parser_ret = Parser::new(&allocator, source, source_type).parse();
@@ -27,24 +53,25 @@ pub fn transform(source: &str, filename: &str) -> Result<String, Box<dyn Error>>
 
     // Configure and run the transformer
     let transform_options = TransformOptions::default();
-    let transformer_ret = Transformer::new(
-        &allocator,
-        std::path::Path::new(filename),
-        &transform_options,
-    )
-    .build_with_scoping(scoping, &mut program);
+    let transformer_ret = Transformer::new(&allocator, Path::new(filename), &transform_options)
+        .build_with_scoping(scoping, &mut program);
 
     if !transformer_ret.errors.is_empty() {
         return Err(format!("Transform error: {:?}", transformer_ret.errors[0]).into());
     }
 
     // Generate code from the transformed AST
-    let code = Codegen::new()
-        .with_options(CodegenOptions::default())
-        .build(&program)
-        .code;
-
-    Ok(code)
+    let generated = Codegen::new()
+        .with_options(CodegenOptions {
+            source_map_path: source_map_path.map(Path::to_path_buf),
+            ..CodegenOptions::default()
+        })
+        .build(&program);
+
+    Ok((
+        generated.code,
+        generated.map.map(|map| map.to_json_string()),
+    ))
 }
 
 #[cfg(test)]
@@ -105,6 +132,30 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("Parse error"));
     }
 
+    #[test]
+    fn test_top_level_for_await() {
+        let input = "for await (const line of Deno.stdin.readLines()) console.log(line);";
+        let output = transform(input, "test.ts").unwrap();
+
+        assert!(output.contains("for await"));
+    }
+
+    #[test]
+    fn test_source_map() {
+        let input = "interface A {}\nconst x: number = 1;\nthrow new Error(`${x}`);";
+        let (output, map) = transform_with_source_map(input, "test.ts", "file:///test.ts").unwrap();
+        let consumer = mdeno_path_util::SourceMapConsumer::new(&map.unwrap()).unwrap();
+        let line = output
+            .lines()
+            .position(|line| line.starts_with("throw"))
+            .unwrap();
+
+        assert_eq!(
+            consumer.original_position_for(line as u32, 0),
+            Some(("file:///test.ts".to_string(), 2, 0))
+        );
+    }
+
     #[test]
     fn test_preserve_javascript() {
         let input = "const x = 42; console.log(x);";
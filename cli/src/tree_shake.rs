@@ -0,0 +1,723 @@
+// Tree shaking for bundled modules
+//
+// Runs in two passes over the bundle: the first walks every import and
+// re-export to record which names of each module are actually used, the
+// second re-emits each module without the exports nobody asked for. Modules
+// that end up unreferenced are dropped from the bundle entirely, unless a
+// re-export still reaches them and they have side effects.
+
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+    Class, Declaration, ExportDefaultDeclarationKind, Expression, ImportDeclarationSpecifier,
+    ImportExpression, Program, Statement,
+};
+use oxc_ast_visit::{Visit, walk};
+use oxc_codegen::Codegen;
+use oxc_parser::Parser;
+use oxc_semantic::{Scoping, SemanticBuilder};
+use oxc_span::SourceType;
+use std::collections::{HashMap, HashSet};
+
+/// Which exports of a module the rest of the bundle depends on
+#[derive(Debug, Clone, PartialEq)]
+enum Usage {
+    /// Every export (entry modules, namespace imports)
+    All,
+    /// Only these exports; an empty set still keeps the module for its side effects
+    Names(HashSet<String>),
+}
+
+impl Usage {
+    fn includes(&self, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Names(names) => names.contains(name),
+        }
+    }
+
+    /// Merge `other` into `self`, returning whether anything was added
+    fn extend(&mut self, other: &Usage) -> bool {
+        match (&mut *self, other) {
+            (Self::All, _) => false,
+            (_, Self::All) => {
+                *self = Self::All;
+                true
+            }
+            (Self::Names(names), Self::Names(other)) => {
+                let before = names.len();
+                names.extend(other.iter().cloned());
+                names.len() != before
+            }
+        }
+    }
+}
+
+/// The import and export structure of a single module
+#[derive(Debug, Default)]
+struct ModuleInfo {
+    /// Names exported by this module itself (declarations and `export { x }`)
+    local_exports: HashSet<String>,
+    /// `import ... from "specifier"` and `import("specifier")` with the names
+    /// they pull in
+    imports: Vec<(String, Usage)>,
+    /// `export { imported as exported } from "specifier"`
+    reexports: Vec<ReExport>,
+    /// `export * from "specifier"`
+    star_exports: Vec<String>,
+    /// Whether the module body does anything besides declaring things
+    side_effects: bool,
+}
+
+#[derive(Debug)]
+struct ReExport {
+    specifier: String,
+    imported: String,
+    exported: String,
+}
+
+/// Remove exports that no module imports.
+///
+/// `resolve` maps an import specifier, relative to the key of the importing
+/// module, to the key of the imported module in `modules`. Specifiers outside
+/// the bundle (built-in modules) resolve to `None`. If a module cannot be
+/// analyzed, or a bundled import cannot be resolved, the bundle is returned
+/// unchanged.
+pub fn shake<F>(
+    modules: &HashMap<String, String>,
+    entry: &str,
+    resolve: F,
+) -> HashMap<String, String>
+where
+    F: Fn(&str, &str) -> Option<String>,
+{
+    let mut infos = HashMap::new();
+    for (key, source) in modules {
+        let Some(info) = analyze(source, key) else {
+            return modules.clone();
+        };
+        for specifier in info.dependencies() {
+            if is_bundled_specifier(specifier) && resolve(key, specifier).is_none() {
+                return modules.clone();
+            }
+        }
+        infos.insert(key.clone(), info);
+    }
+
+    let usage = collect_usage(&infos, entry, &resolve);
+
+    let mut shaken = HashMap::new();
+    for (key, used) in &usage {
+        let Some(source) = modules.get(key) else {
+            continue;
+        };
+        let source = match used {
+            Usage::All => source.clone(),
+            Usage::Names(_) => remove_unused_exports(source, key, used, &|specifier| {
+                resolve(key, specifier).is_some_and(|target| {
+                    provides_any(&infos, &target, used, &resolve)
+                        || has_side_effects(&infos, &target, &resolve, &mut HashSet::new())
+                })
+            }),
+        };
+        shaken.insert(key.clone(), source);
+    }
+    shaken
+}
+
+impl ModuleInfo {
+    fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.imports
+            .iter()
+            .map(|(specifier, _)| specifier.as_str())
+            .chain(
+                self.reexports
+                    .iter()
+                    .map(|reexport| reexport.specifier.as_str()),
+            )
+            .chain(self.star_exports.iter().map(String::as_str))
+    }
+}
+
+/// Whether a specifier refers to a module that is part of the bundle
+fn is_bundled_specifier(specifier: &str) -> bool {
+    specifier.starts_with("./") || specifier.starts_with("../") || specifier.starts_with("jsr:")
+}
+
+fn source_type(key: &str) -> SourceType {
+    // Modules are already stripped of types; keys may be jsr: specifiers
+    // without an extension
+    SourceType::mjs().with_jsx(
+        std::path::Path::new(key)
+            .extension()
+            .is_some_and(|ext| ext == "jsx"),
+    )
+}
+
+fn analyze(source: &str, key: &str) -> Option<ModuleInfo> {
+    let allocator = Allocator::default();
+    let parser_ret = Parser::new(&allocator, source, source_type(key)).parse();
+    if !parser_ret.errors.is_empty() {
+        return None;
+    }
+
+    let mut info = ModuleInfo::default();
+    for stmt in &parser_ret.program.body {
+        match stmt {
+            Statement::ImportDeclaration(import) => {
+                let mut names = HashSet::new();
+                let mut usage = None;
+                for specifier in import.specifiers.iter().flatten() {
+                    match specifier {
+                        ImportDeclarationSpecifier::ImportSpecifier(specifier) => {
+                            names.insert(specifier.imported.name().to_string());
+                        }
+                        ImportDeclarationSpecifier::ImportDefaultSpecifier(_) => {
+                            names.insert("default".to_string());
+                        }
+                        ImportDeclarationSpecifier::ImportNamespaceSpecifier(_) => {
+                            usage = Some(Usage::All);
+                        }
+                    }
+                }
+                let usage = usage.unwrap_or(Usage::Names(names));
+                info.imports.push((import.source.value.to_string(), usage));
+            }
+            Statement::ExportNamedDeclaration(export) => {
+                if let Some(source) = &export.source {
+                    // `export {} from` only runs the module
+                    if export.specifiers.is_empty() {
+                        let usage = Usage::Names(HashSet::new());
+                        info.imports.push((source.value.to_string(), usage));
+                    }
+                    for specifier in &export.specifiers {
+                        info.reexports.push(ReExport {
+                            specifier: source.value.to_string(),
+                            imported: specifier.local.name().to_string(),
+                            exported: specifier.exported.name().to_string(),
+                        });
+                    }
+                } else {
+                    if let Some(declaration) = &export.declaration {
+                        info.local_exports.extend(declared_names(declaration));
+                    }
+                    for specifier in &export.specifiers {
+                        info.local_exports
+                            .insert(specifier.exported.name().to_string());
+                    }
+                }
+            }
+            Statement::ExportAllDeclaration(export) => {
+                let specifier = export.source.value.to_string();
+                if let Some(exported) = &export.exported {
+                    // export * as ns: the whole target is reachable through `ns`
+                    info.local_exports.insert(exported.name().to_string());
+                    info.imports.push((specifier, Usage::All));
+                } else {
+                    info.star_exports.push(specifier);
+                }
+            }
+            Statement::ExportDefaultDeclaration(_) => {
+                info.local_exports.insert("default".to_string());
+            }
+            _ => {}
+        }
+        info.side_effects |= has_side_effect_statement(stmt);
+    }
+
+    // A dynamic import can use any export of its target. Without a literal
+    // specifier the target is unknown, so the module can't be analyzed.
+    let mut dynamic = DynamicImports::default();
+    dynamic.visit_program(&parser_ret.program);
+    if dynamic.non_literal {
+        return None;
+    }
+    info.imports.extend(
+        dynamic
+            .specifiers
+            .into_iter()
+            .map(|specifier| (specifier, Usage::All)),
+    );
+    Some(info)
+}
+
+#[derive(Default)]
+struct DynamicImports {
+    specifiers: Vec<String>,
+    non_literal: bool,
+}
+
+impl<'a> Visit<'a> for DynamicImports {
+    fn visit_import_expression(&mut self, it: &ImportExpression<'a>) {
+        if let Expression::StringLiteral(literal) = &it.source {
+            self.specifiers.push(literal.value.to_string());
+        } else {
+            self.non_literal = true;
+        }
+        walk::walk_import_expression(self, it);
+    }
+}
+
+fn declared_names(declaration: &Declaration) -> Vec<String> {
+    match declaration {
+        Declaration::FunctionDeclaration(function) => {
+            function.id.iter().map(|id| id.name.to_string()).collect()
+        }
+        Declaration::ClassDeclaration(class) => {
+            class.id.iter().map(|id| id.name.to_string()).collect()
+        }
+        Declaration::VariableDeclaration(variable) => variable
+            .declarations
+            .iter()
+            .flat_map(|declarator| declarator.id.get_binding_identifiers())
+            .map(|id| id.name.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Propagate usage from the entry module until nothing changes
+fn collect_usage<F>(
+    infos: &HashMap<String, ModuleInfo>,
+    entry: &str,
+    resolve: &F,
+) -> HashMap<String, Usage>
+where
+    F: Fn(&str, &str) -> Option<String>,
+{
+    let mut usage = HashMap::from([(entry.to_string(), Usage::All)]);
+
+    loop {
+        let mut changed = false;
+        let current: Vec<(String, Usage)> = usage
+            .iter()
+            .map(|(key, used)| (key.clone(), used.clone()))
+            .collect();
+
+        for (key, used) in current {
+            let Some(info) = infos.get(&key) else {
+                continue;
+            };
+
+            // Re-exported modules that contribute nothing still run for
+            // their side effects
+            let side_effects_only = |specifier: &str| {
+                resolve(&key, specifier).is_some_and(|target| {
+                    has_side_effects(infos, &target, resolve, &mut HashSet::new())
+                })
+            };
+
+            let mut requests: Vec<(String, Usage)> = info.imports.clone();
+            for reexport in &info.reexports {
+                if used.includes(&reexport.exported) {
+                    let names = HashSet::from([reexport.imported.clone()]);
+                    requests.push((reexport.specifier.clone(), Usage::Names(names)));
+                } else if side_effects_only(&reexport.specifier) {
+                    requests.push((reexport.specifier.clone(), Usage::Names(HashSet::new())));
+                }
+            }
+            for specifier in &info.star_exports {
+                let forwarded = match &used {
+                    Usage::All => Usage::All,
+                    Usage::Names(names) => Usage::Names(
+                        names
+                            .iter()
+                            .filter(|name| !info.local_exports.contains(*name))
+                            .cloned()
+                            .collect(),
+                    ),
+                };
+                // Skip targets that provide none of the requested names
+                if resolve(&key, specifier)
+                    .is_some_and(|target| provides_any(infos, &target, &forwarded, resolve))
+                {
+                    requests.push((specifier.clone(), forwarded));
+                } else if side_effects_only(specifier) {
+                    requests.push((specifier.clone(), Usage::Names(HashSet::new())));
+                }
+            }
+
+            for (specifier, requested) in requests {
+                let Some(target) = resolve(&key, &specifier) else {
+                    continue;
+                };
+                if let Some(existing) = usage.get_mut(&target) {
+                    changed |= existing.extend(&requested);
+                } else {
+                    usage.insert(target, requested);
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            return usage;
+        }
+    }
+}
+
+/// Whether a module exports at least one of the used names
+fn provides_any<F>(
+    infos: &HashMap<String, ModuleInfo>,
+    key: &str,
+    used: &Usage,
+    resolve: &F,
+) -> bool
+where
+    F: Fn(&str, &str) -> Option<String>,
+{
+    match used {
+        Usage::All => true,
+        Usage::Names(names) => {
+            let exports = export_names(infos, key, resolve, &mut HashSet::new());
+            names.iter().any(|name| exports.contains(name))
+        }
+    }
+}
+
+/// Whether loading a module, or any module it depends on, has side effects
+fn has_side_effects<F>(
+    infos: &HashMap<String, ModuleInfo>,
+    key: &str,
+    resolve: &F,
+    visited: &mut HashSet<String>,
+) -> bool
+where
+    F: Fn(&str, &str) -> Option<String>,
+{
+    if !visited.insert(key.to_string()) {
+        return false;
+    }
+    let Some(info) = infos.get(key) else {
+        return true;
+    };
+    info.side_effects
+        || info.dependencies().any(|specifier| {
+            // Built-in modules load without side effects
+            resolve(key, specifier)
+                .is_some_and(|target| has_side_effects(infos, &target, resolve, visited))
+        })
+}
+
+/// All names a module exports, including those from `export *`
+fn export_names<F>(
+    infos: &HashMap<String, ModuleInfo>,
+    key: &str,
+    resolve: &F,
+    visited: &mut HashSet<String>,
+) -> HashSet<String>
+where
+    F: Fn(&str, &str) -> Option<String>,
+{
+    let mut names = HashSet::new();
+    if !visited.insert(key.to_string()) {
+        return names;
+    }
+    let Some(info) = infos.get(key) else {
+        return names;
+    };
+
+    names.extend(info.local_exports.iter().cloned());
+    names.extend(
+        info.reexports
+            .iter()
+            .map(|reexport| reexport.exported.clone()),
+    );
+    for specifier in &info.star_exports {
+        if let Some(target) = resolve(key, specifier) {
+            // `export *` never forwards the default export
+            names.extend(
+                export_names(infos, &target, resolve, visited)
+                    .into_iter()
+                    .filter(|name| name != "default"),
+            );
+        }
+    }
+    names
+}
+
+/// Re-emit a module without the exports outside of `used`.
+///
+/// `keep_reexport` decides whether a re-export statement still contributes
+/// used names or runs a module with side effects. Removing one export can
+/// leave another export unreferenced, so this repeats until the module stops
+/// changing.
+fn remove_unused_exports(
+    source: &str,
+    key: &str,
+    used: &Usage,
+    keep_reexport: &dyn Fn(&str) -> bool,
+) -> String {
+    let mut current = source.to_string();
+    loop {
+        let allocator = Allocator::default();
+        let parser_ret = Parser::new(&allocator, &current, source_type(key)).parse();
+        if !parser_ret.errors.is_empty() {
+            return current;
+        }
+        let mut program = parser_ret.program;
+        let scoping = SemanticBuilder::new()
+            .build(&program)
+            .semantic
+            .into_scoping();
+
+        if !shake_program(&mut program, &scoping, used, keep_reexport) {
+            return current;
+        }
+        current = Codegen::new().build(&program).code;
+    }
+}
+
+/// Remove unused export statements and specifiers, returning whether
+/// anything was removed
+fn shake_program(
+    program: &mut Program,
+    scoping: &Scoping,
+    used: &Usage,
+    keep_reexport: &dyn Fn(&str) -> bool,
+) -> bool {
+    let before = program.body.len();
+    let mut removed_specifiers = false;
+
+    program.body.retain_mut(|stmt| match stmt {
+        Statement::ExportNamedDeclaration(export) => {
+            if let Some(declaration) = &export.declaration {
+                return declared_names(declaration)
+                    .iter()
+                    .any(|name| used.includes(name))
+                    || !is_removable_declaration(declaration, scoping);
+            }
+            if export.specifiers.is_empty() {
+                return true;
+            }
+            let count = export.specifiers.len();
+            export
+                .specifiers
+                .retain(|specifier| used.includes(&specifier.exported.name()));
+            removed_specifiers |= export.specifiers.len() != count;
+            // An emptied `export {} from` still runs the module
+            !export.specifiers.is_empty()
+                || export
+                    .source
+                    .as_ref()
+                    .is_some_and(|source| keep_reexport(source.value.as_str()))
+        }
+        Statement::ExportAllDeclaration(export) => {
+            export.exported.is_some() || keep_reexport(export.source.value.as_str())
+        }
+        Statement::ExportDefaultDeclaration(export) => {
+            if used.includes("default") {
+                return true;
+            }
+            match &export.declaration {
+                ExportDefaultDeclarationKind::FunctionDeclaration(function) => function
+                    .id
+                    .as_ref()
+                    .is_some_and(|id| is_referenced(id.symbol_id(), scoping)),
+                ExportDefaultDeclarationKind::ClassDeclaration(class) => class
+                    .id
+                    .as_ref()
+                    .is_some_and(|id| is_referenced(id.symbol_id(), scoping)),
+                // Expressions may have side effects
+                _ => true,
+            }
+        }
+        _ => true,
+    });
+
+    removed_specifiers || program.body.len() != before
+}
+
+fn is_referenced(symbol_id: oxc_semantic::SymbolId, scoping: &Scoping) -> bool {
+    !scoping.get_resolved_reference_ids(symbol_id).is_empty()
+}
+
+/// Whether an exported declaration can be dropped without changing behavior:
+/// it must be unreferenced within the module and free of side effects
+fn is_removable_declaration(declaration: &Declaration, scoping: &Scoping) -> bool {
+    if !is_pure_declaration(declaration) {
+        return false;
+    }
+    match declaration {
+        Declaration::FunctionDeclaration(function) => function
+            .id
+            .as_ref()
+            .is_some_and(|id| !is_referenced(id.symbol_id(), scoping)),
+        Declaration::ClassDeclaration(class) => class
+            .id
+            .as_ref()
+            .is_some_and(|id| !is_referenced(id.symbol_id(), scoping)),
+        Declaration::VariableDeclaration(variable) => {
+            variable.declarations.iter().all(|declarator| {
+                declarator
+                    .id
+                    .get_binding_identifier()
+                    .is_some_and(|id| !is_referenced(id.symbol_id(), scoping))
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Whether a top-level statement runs code when the module is loaded
+fn has_side_effect_statement(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::ImportDeclaration(_)
+        | Statement::ExportAllDeclaration(_)
+        | Statement::EmptyStatement(_) => false,
+        Statement::ExportNamedDeclaration(export) => export
+            .declaration
+            .as_ref()
+            .is_some_and(|declaration| !is_pure_declaration(declaration)),
+        Statement::ExportDefaultDeclaration(export) => match &export.declaration {
+            ExportDefaultDeclarationKind::FunctionDeclaration(_) => false,
+            ExportDefaultDeclarationKind::ClassDeclaration(class) => !is_pure_class(class),
+            kind => kind
+                .as_expression()
+                .is_none_or(|expression| !is_pure_expression(expression)),
+        },
+        _ => stmt
+            .as_declaration()
+            .is_none_or(|declaration| !is_pure_declaration(declaration)),
+    }
+}
+
+/// Whether evaluating a declaration cannot have side effects
+fn is_pure_declaration(declaration: &Declaration) -> bool {
+    match declaration {
+        Declaration::FunctionDeclaration(_) => true,
+        Declaration::ClassDeclaration(class) => is_pure_class(class),
+        Declaration::VariableDeclaration(variable) => {
+            variable.declarations.iter().all(|declarator| {
+                // Destructuring can run getters
+                declarator.id.get_binding_identifier().is_some()
+                    && declarator.init.as_ref().is_none_or(is_pure_expression)
+            })
+        }
+        _ => false,
+    }
+}
+
+fn is_pure_class(class: &Class) -> bool {
+    // Static members and computed keys run code when the class is defined
+    class
+        .body
+        .body
+        .iter()
+        .all(|element| !element.r#static() && !element.computed())
+        && class
+            .super_class
+            .as_ref()
+            .is_none_or(|super_class| matches!(super_class, Expression::Identifier(_)))
+}
+
+/// Expressions whose evaluation cannot have side effects
+fn is_pure_expression(expression: &Expression) -> bool {
+    match expression {
+        Expression::FunctionExpression(_)
+        | Expression::ArrowFunctionExpression(_)
+        | Expression::BooleanLiteral(_)
+        | Expression::NullLiteral(_)
+        | Expression::NumericLiteral(_)
+        | Expression::BigIntLiteral(_)
+        | Expression::StringLiteral(_) => true,
+        Expression::TemplateLiteral(template) => template.expressions.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[allow(clippy::unnecessary_wraps)] // Signature required by shake()
+    fn resolve(_base: &str, specifier: &str) -> Option<String> {
+        Some(specifier.trim_start_matches("./").to_string())
+    }
+
+    fn bundle(modules: &[(&str, &str)]) -> HashMap<String, String> {
+        modules
+            .iter()
+            .map(|(key, source)| ((*key).to_string(), (*source).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_removes_unused_exports() {
+        let modules = bundle(&[
+            ("main.js", r#"import { used } from "./utils.js"; used();"#),
+            (
+                "utils.js",
+                "function helper() {}\nexport function used() { helper(); }\nexport function unused() {}\nexport const value = 1;",
+            ),
+        ]);
+
+        let shaken = shake(&modules, "main.js", resolve);
+        let utils = &shaken["utils.js"];
+        assert!(utils.contains("function used"));
+        assert!(utils.contains("function helper"));
+        assert!(!utils.contains("unused"));
+        assert!(!utils.contains("value"));
+        assert_eq!(shaken["main.js"], modules["main.js"]);
+    }
+
+    #[test]
+    fn test_follows_reexports() {
+        let modules = bundle(&[
+            ("main.js", r#"import { a } from "./mod.js"; a();"#),
+            (
+                "mod.js",
+                r#"export * from "./a.js"; export * from "./b.js"; export { c } from "./c.js"; export * from "./d.js"; export { e } from "./e.js";"#,
+            ),
+            ("a.js", "export function a() {}"),
+            ("b.js", "export function b() {}\nconsole.log(\"b loaded\");"),
+            ("c.js", "export function c() {}\nexport const name = \"c\";"),
+            ("d.js", r#"export { c as d } from "./c.js";"#),
+            ("e.js", "export const e = 1;\nglobalThis.loaded = true;"),
+        ]);
+
+        let shaken = shake(&modules, "main.js", resolve);
+        assert!(shaken["a.js"].contains("function a"));
+        assert!(!shaken["mod.js"].contains("c.js"));
+        assert!(!shaken.contains_key("c.js"));
+        assert!(!shaken.contains_key("d.js"));
+
+        // b.js contributes no names but still has to run
+        assert!(shaken["mod.js"].contains("b.js"));
+        assert!(shaken["b.js"].contains("console.log"));
+        assert!(!shaken["b.js"].contains("function b"));
+        assert!(shaken["mod.js"].contains("e.js"));
+        assert!(shaken["e.js"].contains("globalThis.loaded"));
+    }
+
+    #[test]
+    fn test_keeps_dynamically_imported_modules() {
+        let modules = bundle(&[
+            ("main.js", r#"const load = () => import("./lazy.js");"#),
+            ("lazy.js", "export const page = 1;"),
+        ]);
+
+        let shaken = shake(&modules, "main.js", resolve);
+        assert!(shaken["lazy.js"].contains("page"));
+    }
+
+    #[test]
+    fn test_keeps_referenced_and_side_effecting_exports() {
+        let modules = bundle(&[
+            (
+                "main.js",
+                r#"import * as all from "./all.js"; import "./effects.js";"#,
+            ),
+            ("all.js", "export function a() {}"),
+            (
+                "effects.js",
+                "export const started = Date.now();\nexport function run() {}\nrun();",
+            ),
+        ]);
+
+        let shaken = shake(&modules, "main.js", resolve);
+        assert!(shaken["all.js"].contains("function a"));
+        assert!(shaken["effects.js"].contains("Date.now()"));
+        assert!(shaken["effects.js"].contains("function run"));
+    }
+}
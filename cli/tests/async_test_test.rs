@@ -0,0 +1,82 @@
+// Integration tests for async Deno.test functions that wait on I/O
+// These make sure the runner keeps compio's reactor running until every test settles
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_async_tests_complete_io() {
+    let (_temp_dir, test_file) = common::write_script(
+        "io_test.ts",
+        r#"Deno.test("fetch", async () => {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 0 });
+  const served = (async () => {
+    const conn = await listener.accept();
+    await conn.read(new Uint8Array(4096));
+    await conn.write(new TextEncoder().encode(
+      "HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+    ));
+    conn.close();
+  })();
+  const r = await fetch(`http://127.0.0.1:${listener.addr.port}/get`);
+  if (r.status !== 200) throw new Error(`status ${r.status}`);
+  if (await r.text() !== "ok") throw new Error("body");
+  await served;
+  listener.close();
+});
+
+Deno.test("tcp round trip", async () => {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 0 });
+  const echoed = (async () => {
+    const conn = await listener.accept();
+    const buf = new Uint8Array(16);
+    const n = await conn.read(buf);
+    await conn.write(buf.subarray(0, n ?? 0));
+    conn.close();
+  })();
+  const conn = await Deno.connect({ hostname: "127.0.0.1", port: listener.addr.port });
+  await conn.write(new TextEncoder().encode("hello"));
+  const buf = new Uint8Array(16);
+  const n = await conn.read(buf);
+  conn.close();
+  await echoed;
+  listener.close();
+  if (new TextDecoder().decode(buf.subarray(0, n ?? 0)) !== "hello") {
+    throw new Error("echo");
+  }
+});
+"#,
+    );
+
+    let mut child = common::mdeno()
+        .arg("test")
+        .arg(&test_file)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let start = Instant::now();
+    while child.try_wait().unwrap().is_none() {
+        if start.elapsed() > Duration::from_secs(30) {
+            child.kill().unwrap();
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(
+        start.elapsed() <= Duration::from_secs(30),
+        "async tests did not finish within 30 seconds"
+    );
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("2 passed | 0 failed"), "stdout: {stdout}");
+    assert!(!stderr.contains("Uncaught"), "stderr: {stderr}");
+}
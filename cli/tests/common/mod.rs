@@ -0,0 +1,38 @@
+// Helpers shared by the integration tests, which run the mdeno binary on
+// scripts written to a temporary directory
+
+#![allow(dead_code)] // Each test crate uses a different subset
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+/// A command for the mdeno binary, with colors turned off
+pub fn mdeno() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_mdeno"));
+    command.env("NO_COLOR", "1");
+    command
+}
+
+/// Write `source` to a file called `name` in a new temporary directory,
+/// which is removed when the returned `TempDir` is dropped
+pub fn write_script(name: &str, source: &str) -> (TempDir, PathBuf) {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join(name);
+    fs::write(&path, source).unwrap();
+    (temp_dir, path)
+}
+
+/// Run `source` as a module with `mdeno run`
+pub fn run(source: &str) -> Output {
+    let (_temp_dir, path) = write_script("main.ts", source);
+    mdeno().arg("run").arg(&path).output().unwrap()
+}
+
+/// Run `source` as a test file with `mdeno test`
+pub fn run_tests(source: &str) -> Output {
+    let (_temp_dir, path) = write_script("main_test.ts", source);
+    mdeno().arg("test").arg(&path).output().unwrap()
+}
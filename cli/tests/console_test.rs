@@ -0,0 +1,24 @@
+// Integration tests for console methods, checked against what reaches
+// stdout and stderr. How each method formats its output is covered by
+// modules/web_console/console_test.ts.
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+#[test]
+fn test_warn_and_error_write_to_stderr() {
+    let output = common::run(
+        "console.log(\"out\");\n\
+         console.info(\"info\");\n\
+         console.warn(\"warn\");\n\
+         console.error(\"error\");\n",
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "out\ninfo\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "warn\nerror\n");
+}
@@ -0,0 +1,48 @@
+// Integration tests for how uncaught errors are printed
+// These run the mdeno binary with colors disabled
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use std::path::Path;
+
+fn run(script: &Path) -> (bool, String) {
+    let output = common::mdeno().arg("run").arg(script).output().unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn test_syntax_error_highlights_source_line() {
+    let (_temp_dir, script) = common::write_script("syntax.js", "const a = 1;\nconst b = ;\n");
+
+    let (success, stderr) = run(&script);
+    assert!(!success);
+    assert!(
+        stderr.contains("SyntaxError: unexpected token in expression: ';'"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("2 | const b = ;\n  |           ^"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn test_runtime_error_prints_call_stack() {
+    let (_temp_dir, script) = common::write_script(
+        "throws.js",
+        "function inner() {\n  throw new TypeError(\"boom\");\n}\nfunction outer() {\n  inner();\n}\nouter();\n",
+    );
+
+    let (success, stderr) = run(&script);
+    assert!(!success, "a top-level throw should fail the process");
+    let lines: Vec<&str> = stderr.lines().collect();
+    assert_eq!(lines[0], "error: Uncaught TypeError: boom");
+    assert!(lines[1].starts_with("    at inner (file://"), "{stderr}");
+    assert!(lines[1].ends_with("throws.js:2:13)"), "{stderr}");
+    assert!(lines[2].starts_with("    at outer (file://"), "{stderr}");
+}
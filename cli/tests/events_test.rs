@@ -0,0 +1,92 @@
+// Integration tests for the "unhandledrejection" and "error" events on
+// globalThis, which decide whether the process exits
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use common::run;
+
+#[test]
+fn test_unhandled_rejection_exits() {
+    let output = run("Promise.reject(new Error(\"boom\"));\n\
+         setTimeout(() => console.log(\"unreachable\"), 10);\n");
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Uncaught (in promise) Error: boom"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_unhandled_rejection_can_be_prevented() {
+    let output = run("addEventListener(\"unhandledrejection\", (event) => {\n\
+           console.log(\"reason:\", event.reason, event.promise instanceof Promise);\n\
+           event.preventDefault();\n\
+         });\n\
+         Promise.reject(\"boom\");\n\
+         setTimeout(() => console.log(\"still running\"), 10);\n");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "reason: boom true\nstill running\n"
+    );
+}
+
+#[test]
+fn test_rejection_handled_later_in_the_same_tick() {
+    let output = run(
+        "addEventListener(\"unhandledrejection\", () => console.log(\"unhandled\"));\n\
+         const promise = Promise.reject(new Error(\"late\"));\n\
+         await Promise.resolve();\n\
+         promise.catch((error) => console.log(\"caught\", error.message));\n",
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "caught late\n");
+}
+
+#[test]
+fn test_error_event_for_uncaught_errors() {
+    let output = run("addEventListener(\"error\", (event) => {\n\
+           console.log(\"error:\", event.message, event.error instanceof Error);\n\
+           event.preventDefault();\n\
+         });\n\
+         setTimeout(() => { throw new Error(\"timer\"); }, 0);\n\
+         setTimeout(() => console.log(\"still running\"), 10);\n");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "error: timer true\nstill running\n"
+    );
+}
+
+#[test]
+fn test_load_listener_runs_after_module() {
+    let output = run(
+        "addEventListener(\"load\", (event) => console.log(event.type, event.target === globalThis));\n\
+         console.log(\"module\");\n",
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "module\nload true\n"
+    );
+}
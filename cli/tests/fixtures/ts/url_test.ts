@@ -212,3 +212,29 @@ Deno.test("URL.parse() with invalid URL", () => {
     throw new Error("Expected URL.parse to return null for invalid URL");
   }
 });
+
+Deno.test("URL[Symbol.toPrimitive]", () => {
+  const url = new URL("https://example.com/path");
+  if (`${url}` !== "https://example.com/path") {
+    throw new Error(`Expected href in template literal, got "${url}"`);
+  }
+  // deno-lint-ignore eqeqeq
+  if (url != "https://example.com/path") {
+    throw new Error("Expected loose equality with href");
+  }
+});
+
+Deno.test("URL.format() from components", () => {
+  // @ts-ignore: mdeno extension
+  const href = URL.format({
+    protocol: "https",
+    hostname: "example.com",
+    port: 8080,
+    pathname: "a/b",
+    query: { q: "x y" },
+    hash: "top",
+  });
+  if (href !== "https://example.com:8080/a/b?q=x+y#top") {
+    throw new Error(`Expected formatted URL, got "${href}"`);
+  }
+});
@@ -0,0 +1,13 @@
+// Integration tests for errors thrown by Deno file system APIs that nothing
+// catches. Caught errors are covered by modules/deno_fs/fs_test.ts.
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+#[test]
+fn test_uncaught_fs_error_fails_the_script() {
+    let output = common::run("Deno.readDirSync(\"/no-such-path\");\n");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("NotFound"));
+}
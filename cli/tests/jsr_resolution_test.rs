@@ -2,7 +2,8 @@
 
 #![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
 
-use mdeno::jsr::JsrResolver;
+use mdeno::jsr::{JsrError, JsrManifestEntry, JsrResolver, JsrVersionMetadata};
+use std::collections::HashMap;
 
 #[test]
 fn test_parse_jsr_specifier_with_version_and_path() {
@@ -56,14 +57,17 @@ fn test_parse_jsr_specifier_without_version_with_path() {
 fn test_parse_invalid_jsr_specifier_no_prefix() {
     let result = JsrResolver::parse_specifier("@std/assert@1.0.0");
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "Not a JSR specifier");
+    assert_eq!(result.unwrap_err().to_string(), "Not a JSR specifier");
 }
 
 #[test]
 fn test_parse_invalid_jsr_specifier_no_scope() {
     let result = JsrResolver::parse_specifier("jsr:assert");
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "Invalid JSR specifier format");
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Invalid JSR specifier format"
+    );
 }
 
 #[test]
@@ -73,7 +77,7 @@ fn test_version_required_error() {
 
     assert!(result.is_err());
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().to_string(),
         "Version must be specified in JSR import"
     );
 }
@@ -85,7 +89,64 @@ fn test_version_required_with_path_error() {
 
     assert!(result.is_err());
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().to_string(),
         "Version must be specified in JSR import"
     );
 }
+
+#[test]
+fn test_verify_checksum() {
+    // sha256("hello")
+    let checksum = "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    assert!(JsrResolver::verify_checksum(b"hello", checksum).is_ok());
+
+    let result = JsrResolver::verify_checksum(b"hello!", checksum);
+    let error = result.unwrap_err();
+    assert!(error.to_string().starts_with("Integrity check failed"));
+    assert!(matches!(
+        error,
+        JsrError::ChecksumMismatch { ref expected, ref actual }
+            if expected == &checksum["sha256:".len()..] && actual != expected
+    ));
+}
+
+#[test]
+fn test_verify_checksum_unsupported_algorithm() {
+    let result = JsrResolver::verify_checksum(b"hello", "md5:5d41402abc4b2a76b9719d911017c592");
+    assert!(matches!(result, Err(JsrError::UnsupportedChecksum(_))));
+}
+
+#[test]
+fn test_verify_manifest_file() {
+    let manifest = HashMap::from([(
+        "/mod.ts".to_string(),
+        JsrManifestEntry {
+            // sha256("hello")
+            checksum: "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                .to_string(),
+        },
+    )]);
+    assert!(JsrResolver::verify_manifest_file(&manifest, "./mod.ts", b"hello").is_ok());
+    assert!(matches!(
+        JsrResolver::verify_manifest_file(&manifest, "./mod.ts", b"hello!"),
+        Err(JsrError::ChecksumMismatch { .. })
+    ));
+
+    // A file the manifest doesn't list can't be trusted
+    let error = JsrResolver::verify_manifest_file(&manifest, "./extra.ts", b"hello").unwrap_err();
+    assert!(matches!(error, JsrError::NotInManifest(ref file) if file == "/extra.ts"));
+    assert_eq!(
+        error.to_string(),
+        "Integrity check failed: /extra.ts is not listed in the package manifest"
+    );
+}
+
+#[test]
+fn test_parse_version_metadata_manifest() {
+    let body = r#"{
+        "exports": { ".": "./mod.ts" },
+        "manifest": { "/mod.ts": { "size": 5, "checksum": "sha256:abc" } }
+    }"#;
+    let metadata: JsrVersionMetadata = serde_json::from_str(body).unwrap();
+    assert_eq!(metadata.manifest["/mod.ts"].checksum, "sha256:abc");
+}
@@ -0,0 +1,62 @@
+// Integration tests for Deno.Kv databases shared between processes
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use std::fs;
+use std::process::Stdio;
+use tempfile::TempDir;
+
+#[test]
+fn test_atomic_commits_across_processes() {
+    let temp_dir = TempDir::new().unwrap();
+    let database = temp_dir.path().join("kv.sqlite3");
+    let script = temp_dir.path().join("increment.ts");
+    fs::write(
+        &script,
+        format!(
+            "const kv = await Deno.openKv({database:?});\n\
+             for (let i = 0; i < 100; i++) {{\n\
+               for (;;) {{\n\
+                 const entry = await kv.get([\"n\"]);\n\
+                 const value = (entry.value ?? 0) + 1;\n\
+                 if ((await kv.atomic().check(entry).set([\"n\"], value).commit()).ok) break;\n\
+               }}\n\
+             }}\n\
+             kv.close();\n",
+            database = database.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    // Each failed check retries, so only errors can lose increments
+    let children: Vec<_> = (0..3)
+        .map(|_| {
+            common::mdeno()
+                .arg("run")
+                .arg(&script)
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for child in children {
+        let output = child.wait_with_output().unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{stderr}");
+    }
+
+    let check = temp_dir.path().join("check.ts");
+    fs::write(
+        &check,
+        format!(
+            "const kv = await Deno.openKv({:?});\n\
+             console.log((await kv.get([\"n\"])).value);\n",
+            database.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    let output = common::mdeno().arg("run").arg(&check).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "300");
+}
@@ -0,0 +1,30 @@
+// Integration tests for Deno.mainModule and import.meta.main
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use std::fs;
+
+#[test]
+fn test_main_module_is_the_entry_point() {
+    let (temp_dir, script) = common::write_script(
+        "main.ts",
+        "import { depIsMain } from \"./dep.ts\";\n\
+         console.log(Deno.mainModule);\n\
+         console.log(import.meta.main, depIsMain);\n",
+    );
+    fs::write(
+        temp_dir.path().join("dep.ts"),
+        "export const depIsMain = Boolean(import.meta.main);\n",
+    )
+    .unwrap();
+
+    let output = common::mdeno().arg("run").arg(&script).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("file://"), "{stdout}");
+    assert!(lines[0].ends_with("/main.ts"), "{stdout}");
+    assert_eq!(lines[1], "true false");
+}
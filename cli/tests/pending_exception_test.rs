@@ -0,0 +1,51 @@
+// Integration tests for native functions that check the type of an argument.
+// A failed check must not leave an exception pending, or the test runner
+// reports it as uncaught once the file is done.
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+fn assert_nothing_pending(test: &str) {
+    let output = common::run_tests(test);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stdout}{stderr}");
+    assert!(stdout.contains("1 passed"), "{stdout}");
+    assert!(
+        !stdout.contains("Uncaught") && !stderr.contains("Uncaught"),
+        "{stdout}{stderr}"
+    );
+}
+
+#[test]
+fn test_url_format_components() {
+    assert_nothing_pending(
+        "Deno.test(\"format\", () => {\n\
+           const href = URL.format({ protocol: \"http\", host: \"example.com\" });\n\
+           if (href !== \"http://example.com\") throw new Error(href);\n\
+         });\n",
+    );
+}
+
+#[test]
+fn test_blob_typed_array_part() {
+    assert_nothing_pending(
+        "Deno.test(\"blob\", async () => {\n\
+           const blob = new Blob([new Uint8Array([1, 2]), new DataView(new ArrayBuffer(1))]);\n\
+           const bytes = new Uint8Array(await blob.arrayBuffer());\n\
+           if (bytes.join() !== \"1,2,0\") throw new Error(bytes.join());\n\
+         });\n",
+    );
+}
+
+#[test]
+fn test_response_body() {
+    assert_nothing_pending(
+        "Deno.test(\"response\", async () => {\n\
+           const text = await new Response(\"hello\").text();\n\
+           const bytes = await new Response(new Uint16Array([0x6968])).text();\n\
+           if (text !== \"hello\" || bytes !== \"hi\") throw new Error(text + bytes);\n\
+         });\n",
+    );
+}
@@ -0,0 +1,48 @@
+// Integration tests for the --deny-read / --deny-write flags
+// These run the mdeno binary against files in a temporary directory
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use std::fs;
+
+#[test]
+fn test_deny_read_wins_over_default_grant() {
+    let (temp_dir, script) = common::write_script(
+        "main.ts",
+        r#"const dir = Deno.args[0];
+console.log(Deno.readTextFileSync(`${dir}/other`));
+console.log(Deno.permissions.querySync({ name: "read", path: `${dir}/secret` }).state);
+try {
+  Deno.readTextFileSync(`${dir}/secret/key`);
+} catch (error) {
+  console.log(error.name);
+}
+"#,
+    );
+    let secret = temp_dir.path().join("secret");
+    fs::create_dir(&secret).unwrap();
+    fs::write(secret.join("key"), "secret").unwrap();
+    fs::write(temp_dir.path().join("other"), "other").unwrap();
+
+    let output = common::mdeno()
+        .arg("run")
+        .arg(format!("--deny-read={}", secret.display()))
+        .arg(&script)
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .collect::<Vec<_>>(),
+        ["other", "denied", "PermissionDenied"]
+    );
+}
@@ -0,0 +1,60 @@
+// Integration tests for reading stdin from a module's top level
+// These run the mdeno binary with a pipe for stdin
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use std::io::Write;
+use std::process::Stdio;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_top_level_for_await_drains_stdin() {
+    let (_temp_dir, script) = common::write_script(
+        "lines.ts",
+        r"let count: number = 0;
+for await (const line of Deno.stdin.readLines()) {
+  count++;
+  console.log(`line: ${line}`);
+}
+console.log(`done: ${count}`);
+",
+    );
+
+    let mut child = common::mdeno()
+        .arg("run")
+        .arg(&script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Write lines with pauses, so the module has to wait on stdin between
+    // them instead of finding everything buffered
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = thread::spawn(move || {
+        for line in ["first", "second", "third"] {
+            writeln!(stdin, "{line}").unwrap();
+            stdin.flush().unwrap();
+            thread::sleep(Duration::from_millis(100));
+        }
+        // Dropping stdin closes the pipe and ends the loop
+    });
+
+    let output = child.wait_with_output().unwrap();
+    writer.join().unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        ["line: first", "line: second", "line: third", "done: 3"]
+    );
+}
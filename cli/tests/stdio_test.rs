@@ -0,0 +1,121 @@
+// Integration tests for Deno.stdin, Deno.stdout and Deno.stderr
+// These run the mdeno binary with its standard streams redirected
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Output, Stdio};
+use tempfile::TempDir;
+
+/// Run `source` as a script with `stdin` and `stdout`
+fn run(dir: &Path, source: &str, stdin: Stdio, stdout: Stdio) -> Output {
+    let script = dir.join("script.ts");
+    fs::write(&script, source).unwrap();
+    common::mdeno()
+        .arg("run")
+        .arg(&script)
+        .stdin(stdin)
+        .stdout(stdout)
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_stdin_readable_pipes_to_stdout_writable() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.bin");
+    // Binary data in several chunks, with no trailing newline
+    let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(&input, &data).unwrap();
+
+    let output = run(
+        temp_dir.path(),
+        "await Deno.stdin.readable.pipeTo(Deno.stdout.writable);\n",
+        File::open(&input).unwrap().into(),
+        Stdio::piped(),
+    );
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(output.stdout, data);
+}
+
+#[test]
+fn test_stdin_reads_then_reports_eof() {
+    let (_temp_dir, script) = common::write_script(
+        "script.ts",
+        r"const buffer = new Uint8Array(3);
+const first = Deno.stdin.readSync(buffer);
+const text = new TextDecoder().decode(buffer.subarray(0, first ?? 0));
+const rest = await Deno.stdin.read(new Uint8Array(16));
+const eof = await Deno.stdin.read(new Uint8Array(16));
+console.log(first, text, rest, eof);
+",
+    );
+
+    let mut child = common::mdeno()
+        .arg("run")
+        .arg(&script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"abcde").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3 abc 2 null\n");
+}
+
+#[test]
+fn test_stdout_writes_stay_in_order_with_console_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("out.txt");
+    // A file rather than a pipe, so every write has to land after the last
+    let output = run(
+        temp_dir.path(),
+        r#"const encoder = new TextEncoder();
+console.log("one");
+Deno.stdout.writeSync(encoder.encode("two "));
+await Deno.stdout.write(encoder.encode("three\n"));
+console.log("four");
+Deno.stderr.writeSync(encoder.encode("to stderr"));
+"#,
+        Stdio::null(),
+        File::create(&path).unwrap().into(),
+    );
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo three\nfour\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "to stderr");
+}
+
+#[test]
+fn test_closed_stdout_throws_bad_resource() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = run(
+        temp_dir.path(),
+        r"Deno.stdout.close();
+try {
+  Deno.stdout.writeSync(new Uint8Array([0x41]));
+} catch (error) {
+  console.log(error instanceof Deno.errors.BadResource);
+}
+",
+        Stdio::null(),
+        Stdio::piped(),
+    );
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "true\n");
+}
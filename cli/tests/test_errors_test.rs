@@ -0,0 +1,42 @@
+// Integration tests for how `mdeno test` reports what a failing test threw
+// Thrown values that are not Error instances should still show their name and message
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+#[test]
+fn test_failures_show_name_and_message() {
+    let output = common::run_tests(
+        r#"Deno.test({ name: "denied", permissions: "none" }, () => {
+  Deno.readTextFileSync("/nonexistent");
+});
+
+Deno.test("plain object", () => {
+  throw { name: "Custom", message: "not an error" };
+});
+
+Deno.test("string", () => {
+  throw "just a string";
+});
+
+Deno.test("type error", () => {
+  null.foo;
+});
+"#,
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains("error: PermissionDenied: Requires read access"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("error: Custom: not an error"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("error: just a string"), "stdout: {stdout}");
+    assert!(stdout.contains("error: TypeError: "), "stdout: {stdout}");
+    assert!(!stdout.contains("Object(0x"), "stdout: {stdout}");
+}
@@ -0,0 +1,48 @@
+// Integration tests for `mdeno test --timeout`
+// These run the mdeno binary on a test file that never finishes
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+#[test]
+fn test_timeout_flag_fails_hanging_tests() {
+    let (_temp_dir, test_file) = common::write_script(
+        "hang_test.ts",
+        r#"Deno.test("hangs", async () => {
+  await new Promise(() => {});
+});
+
+Deno.test({
+  name: "has its own timeout",
+  timeout: 20,
+  fn() {
+    while (true) {
+      // spin until interrupted
+    }
+  },
+});
+
+Deno.test("passes", () => {});
+"#,
+    );
+
+    let output = common::mdeno()
+        .arg("test")
+        .arg("--timeout=100")
+        .arg(&test_file)
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains("Test exceeded timeout of 100ms"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Test exceeded timeout of 20ms"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("1 passed | 2 failed"), "stdout: {stdout}");
+}
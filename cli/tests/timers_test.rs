@@ -0,0 +1,33 @@
+// Integration tests for setTimeout and setInterval
+// These check how pending timers and throwing callbacks end the process
+
+#![allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_cleared_timers_do_not_keep_the_process_alive() {
+    let start = Instant::now();
+    let output = common::run(
+        "const id = setTimeout(() => console.log(\"never\"), 60_000);\n\
+         clearTimeout(id);\n\
+         setTimeout(() => console.log(\"done\"), 10);\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "done\n");
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
+
+#[test]
+fn test_throwing_timer_callback_exits_with_error() {
+    let output = common::run(
+        "setTimeout(() => { throw new Error(\"boom\"); }, 1);\n\
+         setTimeout(() => console.log(\"after\"), 50);\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("error: Uncaught Error: boom"), "{stderr}");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("after"));
+}
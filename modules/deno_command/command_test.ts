@@ -0,0 +1,192 @@
+// Deno.Command E2E tests, driving sh so they only run on Unix
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+const ignore = Deno.build.os === "windows";
+
+function sh(script: string, options: Deno.CommandOptions = {}): Deno.Command {
+  return new Deno.Command("sh", { ...options, args: ["-c", script] });
+}
+
+Deno.test({
+  name: "Command output collects stdout, stderr and the exit code",
+  ignore,
+  async fn() {
+    const output = await sh("echo out; echo err >&2; exit 3").output();
+    if (output.success || output.code !== 3 || output.signal !== null) {
+      throw new Error(`status: ${output.success} ${output.code} ${output.signal}`);
+    }
+    if (decoder.decode(output.stdout) !== "out\n") throw new Error("stdout");
+    if (decoder.decode(output.stderr) !== "err\n") throw new Error("stderr");
+  },
+});
+
+Deno.test({
+  name: "Command outputSync passes cwd and env",
+  ignore,
+  fn() {
+    const cwd = Deno.makeTempDirSync();
+    try {
+      const output = sh('echo "$GREETING" > greeting.txt', {
+        cwd,
+        env: { GREETING: "hello" },
+      }).outputSync();
+      if (!output.success) throw new Error(`code: ${output.code}`);
+      const text = Deno.readTextFileSync(`${cwd}/greeting.txt`);
+      if (text !== "hello\n") throw new Error(`Unexpected output: ${text}`);
+    } finally {
+      Deno.removeSync(cwd, { recursive: true });
+    }
+  },
+});
+
+Deno.test({
+  name: "Command output of a stream that isn't piped throws",
+  ignore,
+  fn() {
+    const output = sh("true", { stdout: "null" }).outputSync();
+    try {
+      output.stdout;
+      throw new Error("should throw");
+    } catch (error) {
+      if (!(error instanceof TypeError)) throw error;
+    }
+  },
+});
+
+Deno.test({
+  name: "Command rejects invalid stdio and piped stdin for output",
+  ignore,
+  async fn() {
+    for (const run of [
+      () => sh("true", { stdin: "piped" }).output(),
+      // @ts-ignore: testing invalid input
+      () => sh("true", { stdout: "bogus" }).output(),
+    ]) {
+      try {
+        await run();
+        throw new Error("should reject");
+      } catch (error) {
+        if (!(error instanceof TypeError)) throw error;
+      }
+    }
+  },
+});
+
+Deno.test("Command of a missing program throws NotFound", async () => {
+  try {
+    await new Deno.Command("mdeno-no-such-program").output();
+    throw new Error("should reject");
+  } catch (error) {
+    if (!(error instanceof Deno.errors.NotFound)) throw error;
+  }
+});
+
+Deno.test({
+  name: "Command spawn pipes stdin through to stdout",
+  ignore,
+  async fn() {
+    const child = new Deno.Command("cat", {
+      stdin: "piped",
+      stdout: "piped",
+    }).spawn();
+    if (!(child.pid > 0)) throw new Error(`pid: ${child.pid}`);
+    const writer = child.stdin.getWriter();
+    await writer.write(encoder.encode("round trip"));
+    await writer.close();
+
+    const chunks: string[] = [];
+    for await (const chunk of child.stdout) chunks.push(decoder.decode(chunk));
+    const status = await child.status;
+    if (chunks.join("") !== "round trip") throw new Error(`stdout: ${chunks}`);
+    if (!status.success) throw new Error(`code: ${status.code}`);
+  },
+});
+
+Deno.test({
+  name: "Command pipes one child's stdout into another's stdin",
+  ignore,
+  async fn() {
+    const producer = sh("printf 'apple\\nbanana\\ncherry\\n'", {
+      stdout: "piped",
+    }).spawn();
+    const filter = new Deno.Command("grep", {
+      args: ["an"],
+      stdin: "piped",
+      stdout: "piped",
+    }).spawn();
+    if (!(producer.stdout instanceof ReadableStream)) {
+      throw new Error("stdout is not a ReadableStream");
+    }
+    if (!(filter.stdin instanceof WritableStream)) {
+      throw new Error("stdin is not a WritableStream");
+    }
+    const [, output] = await Promise.all([
+      producer.stdout.pipeTo(filter.stdin),
+      filter.output(),
+    ]);
+    if (decoder.decode(output.stdout) !== "banana\n") {
+      throw new Error(`stdout: ${decoder.decode(output.stdout)}`);
+    }
+    if (!(await producer.status).success) throw new Error("producer failed");
+  },
+});
+
+Deno.test({
+  name: "ChildProcess kill defaults to SIGTERM",
+  ignore,
+  async fn() {
+    const child = new Deno.Command("sleep", { args: ["30"] }).spawn();
+    child.kill();
+    const status = await child.status;
+    if (status.signal !== "SIGTERM" || status.code !== 143) {
+      throw new Error(`status: ${JSON.stringify(status)}`);
+    }
+  },
+});
+
+Deno.test({
+  name: "ChildProcess kill reports the signal",
+  ignore,
+  async fn() {
+    const child = new Deno.Command("sleep", { args: ["30"] }).spawn();
+    child.kill("SIGKILL");
+    const status = await child.status;
+    if (status.success || status.signal !== "SIGKILL" || status.code !== 137) {
+      throw new Error(`status: ${JSON.stringify(status)}`);
+    }
+    try {
+      child.kill();
+      throw new Error("should throw");
+    } catch (error) {
+      if (!(error instanceof TypeError)) throw error;
+    }
+  },
+});
+
+Deno.test({
+  name: "ChildProcess output collects piped streams",
+  ignore,
+  async fn() {
+    const child = sh("echo spawned; exit 1", { stdout: "piped" }).spawn();
+    const output = await child.output();
+    if (output.code !== 1) throw new Error(`code: ${output.code}`);
+    if (decoder.decode(output.stdout) !== "spawned\n") {
+      throw new Error("stdout");
+    }
+  },
+});
+
+Deno.test({
+  name: "Command requires run permission",
+  permissions: { run: false },
+  // Synchronous, so the scope can't leak into async tests still running
+  fn() {
+    try {
+      new Deno.Command("true").outputSync();
+      throw new Error("should throw");
+    } catch (error) {
+      if (!(error instanceof Deno.errors.PermissionDenied)) throw error;
+    }
+  },
+});
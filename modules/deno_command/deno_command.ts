@@ -0,0 +1,307 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+// Register subprocess APIs under __mdeno__.command
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+// @ts-ignore: mdeno internal API
+const fs = globalThis.__mdeno__.fs;
+
+// Size of each read from a child's stdout or stderr
+const READ_CHUNK_SIZE = 64 * 1024;
+
+type Stdio = "inherit" | "piped" | "null";
+
+interface CommandOptions {
+  args?: string[];
+  cwd?: string | URL;
+  clearEnv?: boolean;
+  env?: Record<string, string>;
+  uid?: number;
+  gid?: number;
+  stdin?: Stdio;
+  stdout?: Stdio;
+  stderr?: Stdio;
+  windowsRawArguments?: boolean;
+}
+
+interface CommandStatus {
+  success: boolean;
+  code: number;
+  signal: string | null;
+}
+
+interface CommandOutput extends CommandStatus {
+  readonly stdout: Uint8Array;
+  readonly stderr: Uint8Array;
+}
+
+interface RawOutput extends CommandStatus {
+  stdout: Uint8Array | null;
+  stderr: Uint8Array | null;
+}
+
+interface SpawnInfo {
+  rid: number;
+  pid: number;
+  stdin: boolean;
+  stdout: boolean;
+  stderr: boolean;
+}
+
+// Options for the internal functions, with the method's stdio defaults
+function resolveOptions(
+  command: string,
+  options: CommandOptions,
+  defaultStdio: { stdin: Stdio; stdout: Stdio; stderr: Stdio },
+) {
+  return {
+    cmd: command,
+    args: options.args?.map(String) ?? [],
+    cwd: options.cwd === undefined ? undefined : fs.pathFromURL(options.cwd),
+    clearEnv: options.clearEnv ?? false,
+    env: options.env ?? {},
+    uid: options.uid,
+    gid: options.gid,
+    stdin: options.stdin ?? defaultStdio.stdin,
+    stdout: options.stdout ?? defaultStdio.stdout,
+    stderr: options.stderr ?? defaultStdio.stderr,
+    windowsRawArguments: options.windowsRawArguments ?? false,
+  };
+}
+
+function commandOutput(output: RawOutput): CommandOutput {
+  const { success, code, signal } = output;
+  return {
+    success,
+    code,
+    signal,
+    get stdout(): Uint8Array {
+      if (output.stdout === null) {
+        throw new TypeError("stdout is not piped");
+      }
+      return output.stdout;
+    },
+    get stderr(): Uint8Array {
+      if (output.stderr === null) {
+        throw new TypeError("stderr is not piped");
+      }
+      return output.stderr;
+    },
+  };
+}
+
+function concat(chunks: Uint8Array[]): Uint8Array {
+  let length = 0;
+  for (const chunk of chunks) length += chunk.length;
+  const result = new Uint8Array(length);
+  let offset = 0;
+  for (const chunk of chunks) {
+    result.set(chunk, offset);
+    offset += chunk.length;
+  }
+  return result;
+}
+
+// A child's stdout or stderr, read in chunks as they arrive
+function readablePipe(
+  rid: number,
+  pipe: "stdout" | "stderr",
+): ReadableStream<Uint8Array> {
+  return new ReadableStream({
+    async pull(controller) {
+      const chunk = await __internal.command.read(rid, pipe, READ_CHUNK_SIZE);
+      if (chunk === null) {
+        controller.close();
+      } else {
+        controller.enqueue(chunk);
+      }
+    },
+    cancel(): Promise<void> {
+      return __internal.command.closePipe(rid, pipe);
+    },
+  });
+}
+
+// A child's stdin. Closing or aborting it closes the pipe, so the child
+// reads EOF.
+function writablePipe(rid: number): WritableStream<Uint8Array> {
+  return new WritableStream({
+    async write(chunk) {
+      if (!(chunk instanceof Uint8Array)) {
+        throw new TypeError("Chunk must be a Uint8Array");
+      }
+      await __internal.command.write(rid, chunk);
+    },
+    close(): Promise<void> {
+      return __internal.command.closePipe(rid, "stdin");
+    },
+    abort(): Promise<void> {
+      return __internal.command.closePipe(rid, "stdin");
+    },
+  });
+}
+
+// https://docs.deno.com/api/deno/~/Deno.ChildProcess
+class ChildProcess {
+  #rid: number;
+  #pid: number;
+  #status: Promise<CommandStatus>;
+  #exited = false;
+  #stdin: WritableStream<Uint8Array> | null;
+  #stdout: ReadableStream<Uint8Array> | null;
+  #stderr: ReadableStream<Uint8Array> | null;
+
+  constructor(info: SpawnInfo) {
+    this.#rid = info.rid;
+    this.#pid = info.pid;
+    this.#stdin = info.stdin ? writablePipe(info.rid) : null;
+    this.#stdout = info.stdout ? readablePipe(info.rid, "stdout") : null;
+    this.#stderr = info.stderr ? readablePipe(info.rid, "stderr") : null;
+    this.#status = __internal.command.wait(info.rid).then(
+      (status: CommandStatus) => {
+        this.#exited = true;
+        return status;
+      },
+    );
+  }
+
+  get pid(): number {
+    return this.#pid;
+  }
+
+  get status(): Promise<CommandStatus> {
+    return this.#status;
+  }
+
+  get stdin(): WritableStream<Uint8Array> {
+    if (this.#stdin === null) {
+      throw new TypeError("stdin is not piped");
+    }
+    return this.#stdin;
+  }
+
+  get stdout(): ReadableStream<Uint8Array> {
+    if (this.#stdout === null) {
+      throw new TypeError("stdout is not piped");
+    }
+    return this.#stdout;
+  }
+
+  get stderr(): ReadableStream<Uint8Array> {
+    if (this.#stderr === null) {
+      throw new TypeError("stderr is not piped");
+    }
+    return this.#stderr;
+  }
+
+  // Wait for the child to exit, collecting whatever is left of its output
+  async output(): Promise<CommandOutput> {
+    for (const stream of [this.#stdout, this.#stderr]) {
+      if (stream?.locked) {
+        throw new TypeError(
+          "Can't collect output because a stream is already in use",
+        );
+      }
+    }
+    const collect = async (stream: ReadableStream<Uint8Array> | null) => {
+      if (stream === null) return null;
+      const chunks: Uint8Array[] = [];
+      for await (const chunk of stream) chunks.push(chunk);
+      return concat(chunks);
+    };
+    const [stdout, stderr, status] = await Promise.all([
+      collect(this.#stdout),
+      collect(this.#stderr),
+      this.#status,
+    ]);
+    return commandOutput({ ...status, stdout, stderr });
+  }
+
+  kill(signal: string = "SIGTERM"): void {
+    if (this.#exited) {
+      throw new TypeError("Child process has already terminated");
+    }
+    __internal.command.kill(this.#rid, signal);
+  }
+
+  ref(): void {
+    // The status promise always keeps the runtime waiting for the child
+  }
+
+  unref(): void {
+    // Not supported: the runtime waits for every child to exit
+  }
+
+  async [Symbol.asyncDispose](): Promise<void> {
+    if (!this.#exited) {
+      try {
+        this.kill();
+      } catch {
+        // Exited in the meantime
+      }
+    }
+    await this.#status;
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.Command
+class Command {
+  #command: string;
+  #options: CommandOptions;
+
+  constructor(command: string | URL, options: CommandOptions = {}) {
+    this.#command = fs.pathFromURL(command);
+    this.#options = options;
+  }
+
+  // Run to completion, collecting stdout and stderr
+  output(): Promise<CommandOutput> {
+    if (this.#options.stdin === "piped") {
+      return Promise.reject(
+        new TypeError(
+          "Piped stdin is not supported for this function, use 'Deno.Command.spawn()' instead",
+        ),
+      );
+    }
+    let options;
+    try {
+      options = this.#outputOptions();
+    } catch (error) {
+      return Promise.reject(error);
+    }
+    return __internal.command.output(options).then(commandOutput);
+  }
+
+  outputSync(): CommandOutput {
+    if (this.#options.stdin === "piped") {
+      throw new TypeError(
+        "Piped stdin is not supported for this function, use 'Deno.Command.spawn()' instead",
+      );
+    }
+    return commandOutput(
+      __internal.command.outputSync(this.#outputOptions()),
+    );
+  }
+
+  spawn(): ChildProcess {
+    const options = resolveOptions(this.#command, this.#options, {
+      stdin: "inherit",
+      stdout: "inherit",
+      stderr: "inherit",
+    });
+    return new ChildProcess(__internal.command.spawn(options));
+  }
+
+  #outputOptions() {
+    return resolveOptions(this.#command, this.#options, {
+      stdin: "null",
+      stdout: "piped",
+      stderr: "piped",
+    });
+  }
+}
+
+// @ts-ignore: mdeno internal API
+Object.assign(globalThis.__mdeno__.command, {
+  Command,
+  ChildProcess,
+});
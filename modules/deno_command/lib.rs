@@ -0,0 +1,50 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+mod process;
+
+use rquickjs::function::Async;
+use rquickjs::{Ctx, Module};
+use utils::add_internal_function;
+use utils_macros::include_ts;
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    setup_internal(ctx)?;
+
+    // Register subprocess APIs under __mdeno__.command as a module
+    let js_source = include_ts!("deno_command.ts");
+    let module = Module::evaluate(ctx.clone(), "deno_command", js_source)?;
+    module.finish::<()>()?;
+
+    Ok(())
+}
+
+fn setup_internal(ctx: &Ctx) -> rquickjs::Result<()> {
+    ctx.eval::<(), _>("globalThis[Symbol.for('mdeno.internal')].command = {};")?;
+
+    // spawn(options: CommandOptions): SpawnInfo
+    add_internal_function!(ctx, "command.spawn", process::command_spawn);
+
+    // output(options: CommandOptions): Promise<CommandOutput>
+    add_internal_function!(ctx, "command.output", Async(process::command_output));
+
+    // outputSync(options: CommandOptions): CommandOutput
+    add_internal_function!(ctx, "command.outputSync", process::command_output_sync);
+
+    // wait(rid: number): Promise<CommandStatus>
+    add_internal_function!(ctx, "command.wait", Async(process::command_wait));
+
+    // read(rid: number, pipe: "stdout" | "stderr", len: number): Promise<Uint8Array | null>
+    add_internal_function!(ctx, "command.read", Async(process::command_read));
+
+    // write(rid: number, data: Uint8Array): Promise<number>
+    add_internal_function!(ctx, "command.write", Async(process::command_write));
+
+    // closePipe(rid: number, pipe: "stdin" | "stdout" | "stderr"): Promise<void>
+    add_internal_function!(ctx, "command.closePipe", Async(process::command_close_pipe));
+
+    // kill(rid: number, signal: string): void
+    add_internal_function!(ctx, "command.kill", process::command_kill);
+
+    Ok(())
+}
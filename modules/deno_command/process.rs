@@ -0,0 +1,543 @@
+// Child processes for Deno.Command, addressed by resource ID
+use compio::BufResult;
+use compio::io::{AsyncRead, AsyncWriteExt};
+use compio::process::{Child, ChildStderr, ChildStdin, ChildStdout};
+use futures_util::lock::Mutex;
+use rquickjs::{Exception, FromJs, IntoJs, Object, TypedArray};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::process::{ExitStatus, Stdio};
+use std::rc::Rc;
+use utils::{DenoError, DenoResult, JsResult};
+
+/// Where a child's stdin, stdout or stderr goes
+#[derive(Debug, Clone, Copy)]
+enum StdioOption {
+    /// Share the parent's handle
+    Inherit,
+    /// Connect a pipe the parent reads or writes
+    Piped,
+    /// Redirect to the null device
+    Null,
+}
+
+impl StdioOption {
+    fn to_stdio(self) -> Stdio {
+        match self {
+            StdioOption::Inherit => Stdio::inherit(),
+            StdioOption::Piped => Stdio::piped(),
+            StdioOption::Null => Stdio::null(),
+        }
+    }
+}
+
+impl<'js> FromJs<'js> for StdioOption {
+    fn from_js(ctx: &rquickjs::Ctx<'js>, value: rquickjs::Value<'js>) -> rquickjs::Result<Self> {
+        match String::from_js(ctx, value)?.as_str() {
+            "inherit" => Ok(StdioOption::Inherit),
+            "piped" => Ok(StdioOption::Piped),
+            "null" => Ok(StdioOption::Null),
+            other => Err(Exception::throw_type(
+                ctx,
+                &format!("Invalid stdio option: '{other}'"),
+            )),
+        }
+    }
+}
+
+/// `Deno.CommandOptions`, with the defaults of the calling method already
+/// filled in by the TypeScript side
+#[derive(Debug, Clone)]
+pub(crate) struct CommandOptions {
+    cmd: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    clear_env: bool,
+    env: HashMap<String, String>,
+    stdin: StdioOption,
+    stdout: StdioOption,
+    stderr: StdioOption,
+    /// User and group to run the command as, which only Unix supports
+    #[cfg_attr(not(unix), allow(dead_code))]
+    uid: Option<u32>,
+    #[cfg_attr(not(unix), allow(dead_code))]
+    gid: Option<u32>,
+    /// Pass `args` to the command line unquoted, which only Windows does
+    #[cfg_attr(not(windows), allow(dead_code))]
+    windows_raw_arguments: bool,
+}
+
+impl<'js> FromJs<'js> for CommandOptions {
+    fn from_js(ctx: &rquickjs::Ctx<'js>, value: rquickjs::Value<'js>) -> rquickjs::Result<Self> {
+        let obj = Object::from_js(ctx, value)?;
+        Ok(Self {
+            cmd: obj.get("cmd")?,
+            args: obj
+                .get::<_, Option<Vec<String>>>("args")?
+                .unwrap_or_default(),
+            cwd: obj.get("cwd")?,
+            clear_env: obj.get::<_, Option<bool>>("clearEnv")?.unwrap_or(false),
+            env: obj
+                .get::<_, Option<HashMap<String, String>>>("env")?
+                .unwrap_or_default(),
+            stdin: obj.get("stdin")?,
+            stdout: obj.get("stdout")?,
+            stderr: obj.get("stderr")?,
+            uid: obj.get("uid")?,
+            gid: obj.get("gid")?,
+            windows_raw_arguments: obj
+                .get::<_, Option<bool>>("windowsRawArguments")?
+                .unwrap_or(false),
+        })
+    }
+}
+
+/// Apply everything but stdio from `$options` to `$command`, which may be a
+/// `std::process::Command` or a `compio::process::Command`
+macro_rules! configure {
+    ($command:expr, $options:expr) => {{
+        let command = $command;
+        let options = $options;
+        #[cfg(windows)]
+        if options.windows_raw_arguments {
+            for arg in &options.args {
+                command.raw_arg(arg);
+            }
+        } else {
+            command.args(&options.args);
+        }
+        #[cfg(not(windows))]
+        command.args(&options.args);
+        if let Some(cwd) = &options.cwd {
+            command.current_dir(cwd);
+        }
+        if options.clear_env {
+            command.env_clear();
+        }
+        command.envs(&options.env);
+        // Only Unix can run a child as another user
+        #[cfg(unix)]
+        {
+            if let Some(uid) = options.uid {
+                command.uid(uid);
+            }
+            if let Some(gid) = options.gid {
+                command.gid(gid);
+            }
+        }
+    }};
+}
+
+/// Check the run permission for `options.cmd`
+fn check(options: &CommandOptions) -> DenoResult<()> {
+    deno_permissions::check("run", &options.cmd)
+}
+
+/// Name the command in spawn errors, which otherwise only say what went wrong
+fn spawn_error(cmd: &str, error: &std::io::Error) -> DenoError {
+    std::io::Error::new(error.kind(), format!("Failed to spawn '{cmd}': {error}")).into()
+}
+
+/// `value`, or `null` rather than the `undefined` an `Option` converts to
+fn nullable<'js, T: IntoJs<'js>>(
+    ctx: &rquickjs::Ctx<'js>,
+    value: Option<T>,
+) -> rquickjs::Result<rquickjs::Value<'js>> {
+    match value {
+        Some(value) => value.into_js(ctx),
+        None => Ok(rquickjs::Value::new_null(ctx.clone())),
+    }
+}
+
+/// Bytes handed to JavaScript as a `Uint8Array`, or `null` for none
+pub(crate) struct Bytes(Option<Vec<u8>>);
+
+impl<'js> IntoJs<'js> for Bytes {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let array = self
+            .0
+            .map(|bytes| TypedArray::<u8>::new(ctx.clone(), bytes))
+            .transpose()?;
+        nullable(ctx, array)
+    }
+}
+
+/// `Deno.CommandStatus`: `{ success, code, signal }`
+pub(crate) struct CommandStatus(ExitStatus);
+
+impl CommandStatus {
+    fn set_fields<'js>(&self, ctx: &rquickjs::Ctx<'js>, obj: &Object<'js>) -> rquickjs::Result<()> {
+        let signal = exit_signal(self.0);
+        // Like a shell, report death by signal N as exit code 128 + N
+        let code = self
+            .0
+            .code()
+            .or_else(|| signal.map(|(number, _)| 128 + number))
+            .unwrap_or(1);
+        obj.set("success", self.0.success())?;
+        obj.set("code", code)?;
+        obj.set("signal", nullable(ctx, signal.map(|(_, name)| name))?)?;
+        Ok(())
+    }
+}
+
+impl<'js> IntoJs<'js> for CommandStatus {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let obj = Object::new(ctx.clone())?;
+        self.set_fields(ctx, &obj)?;
+        Ok(obj.into_value())
+    }
+}
+
+/// The status plus collected output, `None` for streams that weren't piped
+pub(crate) struct CommandOutput {
+    status: CommandStatus,
+    stdout: Option<Vec<u8>>,
+    stderr: Option<Vec<u8>>,
+}
+
+impl CommandOutput {
+    fn new(output: std::process::Output, options: &CommandOptions) -> Self {
+        let piped = |option: StdioOption| matches!(option, StdioOption::Piped);
+        Self {
+            status: CommandStatus(output.status),
+            stdout: piped(options.stdout).then_some(output.stdout),
+            stderr: piped(options.stderr).then_some(output.stderr),
+        }
+    }
+}
+
+impl<'js> IntoJs<'js> for CommandOutput {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let obj = Object::new(ctx.clone())?;
+        self.status.set_fields(ctx, &obj)?;
+        obj.set("stdout", Bytes(self.stdout))?;
+        obj.set("stderr", Bytes(self.stderr))?;
+        Ok(obj.into_value())
+    }
+}
+
+/// Resource ID, process ID and piped streams of a spawned child
+pub(crate) struct SpawnInfo {
+    rid: u32,
+    pid: u32,
+    stdin: bool,
+    stdout: bool,
+    stderr: bool,
+}
+
+impl<'js> IntoJs<'js> for SpawnInfo {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let obj = Object::new(ctx.clone())?;
+        obj.set("rid", self.rid)?;
+        obj.set("pid", self.pid)?;
+        obj.set("stdin", self.stdin)?;
+        obj.set("stdout", self.stdout)?;
+        obj.set("stderr", self.stderr)?;
+        Ok(obj.into_value())
+    }
+}
+
+/// A spawned child. Each pipe is `None` once closed or read to the end.
+struct ChildResource {
+    pid: u32,
+    /// Taken by the single `wait`
+    child: RefCell<Option<Child>>,
+    exited: Cell<bool>,
+    stdin: Mutex<Option<ChildStdin>>,
+    stdout: Mutex<Option<ChildStdout>>,
+    stderr: Mutex<Option<ChildStderr>>,
+}
+
+thread_local! {
+    // Pipes are registered with this thread's driver, so children live in a
+    // thread-local table like sockets do
+    static RESOURCES: RefCell<HashMap<u32, Rc<ChildResource>>> = RefCell::new(HashMap::new());
+    static NEXT_RID: Cell<u32> = const { Cell::new(0) };
+}
+
+fn resource(rid: u32) -> DenoResult<Rc<ChildResource>> {
+    RESOURCES
+        .with_borrow(|resources| resources.get(&rid).cloned())
+        .ok_or_else(|| DenoError::BadResource("Bad resource ID".to_string()))
+}
+
+/// Forget a child once it has exited and every pipe is closed
+async fn release_if_done(rid: u32, child: &ChildResource) {
+    if child.exited.get()
+        && child.stdin.lock().await.is_none()
+        && child.stdout.lock().await.is_none()
+        && child.stderr.lock().await.is_none()
+    {
+        RESOURCES.with_borrow_mut(|resources| resources.remove(&rid));
+    }
+}
+
+fn spawn(options: &CommandOptions) -> DenoResult<SpawnInfo> {
+    check(options)?;
+    let mut command = compio::process::Command::new(&options.cmd);
+    configure!(&mut command, options);
+    // Stdio values always convert, so these can't fail
+    let _ = command.stdin(options.stdin.to_stdio());
+    let _ = command.stdout(options.stdout.to_stdio());
+    let _ = command.stderr(options.stderr.to_stdio());
+    let mut child = command.spawn().map_err(|e| spawn_error(&options.cmd, &e))?;
+
+    let info = SpawnInfo {
+        rid: NEXT_RID.get(),
+        pid: child.id(),
+        stdin: child.stdin.is_some(),
+        stdout: child.stdout.is_some(),
+        stderr: child.stderr.is_some(),
+    };
+    NEXT_RID.set(info.rid + 1);
+    let resource = ChildResource {
+        pid: info.pid,
+        stdin: Mutex::new(child.stdin.take()),
+        stdout: Mutex::new(child.stdout.take()),
+        stderr: Mutex::new(child.stderr.take()),
+        child: RefCell::new(Some(child)),
+        exited: Cell::new(false),
+    };
+    RESOURCES.with_borrow_mut(|resources| resources.insert(info.rid, Rc::new(resource)));
+    Ok(info)
+}
+
+pub(crate) fn command_spawn(options: CommandOptions) -> JsResult<SpawnInfo> {
+    spawn(&options).into()
+}
+
+async fn output(options: CommandOptions) -> DenoResult<CommandOutput> {
+    check(&options)?;
+    let mut command = compio::process::Command::new(&options.cmd);
+    configure!(&mut command, &options);
+    let _ = command.stdin(options.stdin.to_stdio());
+    let _ = command.stdout(options.stdout.to_stdio());
+    let _ = command.stderr(options.stderr.to_stdio());
+    let child = command.spawn().map_err(|e| spawn_error(&options.cmd, &e))?;
+    let output = child.wait_with_output().await?;
+    Ok(CommandOutput::new(output, &options))
+}
+
+pub(crate) async fn command_output(options: CommandOptions) -> JsResult<CommandOutput> {
+    output(options).await.into()
+}
+
+fn output_sync(options: &CommandOptions) -> DenoResult<CommandOutput> {
+    #[cfg(unix)]
+    use std::os::unix::process::CommandExt;
+    #[cfg(windows)]
+    use std::os::windows::process::CommandExt;
+
+    check(options)?;
+    let mut command = std::process::Command::new(&options.cmd);
+    configure!(&mut command, options);
+    command
+        .stdin(options.stdin.to_stdio())
+        .stdout(options.stdout.to_stdio())
+        .stderr(options.stderr.to_stdio());
+    let output = command
+        .output()
+        .map_err(|e| spawn_error(&options.cmd, &e))?;
+    Ok(CommandOutput::new(output, options))
+}
+
+pub(crate) fn command_output_sync(options: CommandOptions) -> JsResult<CommandOutput> {
+    output_sync(&options).into()
+}
+
+async fn wait(rid: u32) -> DenoResult<CommandStatus> {
+    let resource = resource(rid)?;
+    let child = resource
+        .child
+        .borrow_mut()
+        .take()
+        .ok_or_else(|| DenoError::Busy("Child process is already being awaited".to_string()))?;
+    let status = child.wait().await;
+    resource.exited.set(true);
+    release_if_done(rid, &resource).await;
+    Ok(CommandStatus(status?))
+}
+
+pub(crate) async fn command_wait(rid: u32) -> JsResult<CommandStatus> {
+    wait(rid).await.into()
+}
+
+/// Read up to `len` bytes from `pipe`, closing it at the end of the stream
+async fn read_pipe<R: AsyncRead>(
+    pipe: &Mutex<Option<R>>,
+    len: usize,
+) -> DenoResult<Option<Vec<u8>>> {
+    let mut pipe = pipe.lock().await;
+    let Some(reader) = pipe.as_mut() else {
+        return Ok(None);
+    };
+    let BufResult(result, buf) = reader.read(Vec::with_capacity(len)).await;
+    if result? == 0 {
+        *pipe = None;
+        return Ok(None);
+    }
+    Ok(Some(buf))
+}
+
+async fn read(rid: u32, name: &str, len: usize) -> DenoResult<Bytes> {
+    let resource = resource(rid)?;
+    let chunk = match name {
+        "stdout" => read_pipe(&resource.stdout, len).await?,
+        "stderr" => read_pipe(&resource.stderr, len).await?,
+        _ => return Err(DenoError::Other(format!("Cannot read from {name}"))),
+    };
+    if chunk.is_none() {
+        release_if_done(rid, &resource).await;
+    }
+    Ok(Bytes(chunk))
+}
+
+pub(crate) async fn command_read(rid: u32, name: String, len: usize) -> JsResult<Bytes> {
+    read(rid, &name, len).await.into()
+}
+
+async fn write(rid: u32, data: Vec<u8>) -> DenoResult<usize> {
+    let resource = resource(rid)?;
+    let mut stdin = resource.stdin.lock().await;
+    let Some(writer) = stdin.as_mut() else {
+        return Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe).into());
+    };
+    let len = data.len();
+    writer.write_all(data).await.0?;
+    Ok(len)
+}
+
+pub(crate) async fn command_write(rid: u32, data: TypedArray<'_, u8>) -> JsResult<usize> {
+    let Some(data) = data.as_bytes().map(<[u8]>::to_vec) else {
+        return JsResult::from(Err::<usize, _>(DenoError::Other(
+            "Buffer is detached".to_string(),
+        )));
+    };
+    write(rid, data).await.into()
+}
+
+/// Close one pipe: stdin so the child reads EOF, or an output it won't read
+async fn close_pipe(rid: u32, name: &str) -> DenoResult<()> {
+    let resource = resource(rid)?;
+    match name {
+        "stdin" => *resource.stdin.lock().await = None,
+        "stdout" => *resource.stdout.lock().await = None,
+        "stderr" => *resource.stderr.lock().await = None,
+        _ => return Err(DenoError::Other(format!("Cannot close {name}"))),
+    }
+    release_if_done(rid, &resource).await;
+    Ok(())
+}
+
+pub(crate) async fn command_close_pipe(rid: u32, name: String) -> JsResult<()> {
+    close_pipe(rid, &name).await.into()
+}
+
+fn kill(rid: u32, signal: &str) -> DenoResult<()> {
+    let resource = resource(rid)?;
+    if resource.exited.get() {
+        return Err(DenoError::Other(
+            "Child process has already terminated".to_string(),
+        ));
+    }
+    send_signal(resource.pid, signal)
+}
+
+pub(crate) fn command_kill(rid: u32, signal: String) -> JsResult<()> {
+    kill(rid, &signal).into()
+}
+
+#[cfg(unix)]
+const SIGNALS: &[(&str, i32)] = &[
+    ("SIGHUP", libc::SIGHUP),
+    ("SIGINT", libc::SIGINT),
+    ("SIGQUIT", libc::SIGQUIT),
+    ("SIGILL", libc::SIGILL),
+    ("SIGTRAP", libc::SIGTRAP),
+    ("SIGABRT", libc::SIGABRT),
+    ("SIGBUS", libc::SIGBUS),
+    ("SIGFPE", libc::SIGFPE),
+    ("SIGKILL", libc::SIGKILL),
+    ("SIGUSR1", libc::SIGUSR1),
+    ("SIGSEGV", libc::SIGSEGV),
+    ("SIGUSR2", libc::SIGUSR2),
+    ("SIGPIPE", libc::SIGPIPE),
+    ("SIGALRM", libc::SIGALRM),
+    ("SIGTERM", libc::SIGTERM),
+    ("SIGCHLD", libc::SIGCHLD),
+    ("SIGCONT", libc::SIGCONT),
+    ("SIGSTOP", libc::SIGSTOP),
+    ("SIGTSTP", libc::SIGTSTP),
+    ("SIGTTIN", libc::SIGTTIN),
+    ("SIGTTOU", libc::SIGTTOU),
+    ("SIGURG", libc::SIGURG),
+    ("SIGXCPU", libc::SIGXCPU),
+    ("SIGXFSZ", libc::SIGXFSZ),
+    ("SIGVTALRM", libc::SIGVTALRM),
+    ("SIGPROF", libc::SIGPROF),
+    ("SIGWINCH", libc::SIGWINCH),
+    ("SIGIO", libc::SIGIO),
+    ("SIGSYS", libc::SIGSYS),
+];
+
+/// Number and name of the signal that killed the process, if one did
+#[cfg(unix)]
+fn exit_signal(status: ExitStatus) -> Option<(i32, &'static str)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let number = status.signal()?;
+    let name = SIGNALS
+        .iter()
+        .find_map(|&(name, n)| (n == number).then_some(name))?;
+    Some((number, name))
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: ExitStatus) -> Option<(i32, &'static str)> {
+    None
+}
+
+#[cfg(unix)]
+fn send_signal(pid: u32, signal: &str) -> DenoResult<()> {
+    let Some(&(_, number)) = SIGNALS.iter().find(|(name, _)| *name == signal) else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Unknown signal: {signal}"),
+        )
+        .into());
+    };
+    let pid = libc::pid_t::try_from(pid).map_err(|e| DenoError::Other(e.to_string()))?;
+    // SAFETY: kill takes plain integers. The child is not reaped until wait
+    // returns, which marks it exited, so the pid still belongs to it.
+    if unsafe { libc::kill(pid, number) } == -1 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn send_signal(pid: u32, signal: &str) -> DenoResult<()> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_TERMINATE, TerminateProcess};
+
+    // Windows has no signals; the ones that end a process terminate it
+    if !matches!(signal, "SIGKILL" | "SIGTERM") {
+        return Err(DenoError::NotSupported(format!(
+            "Unsupported signal on Windows: {signal}"
+        )));
+    }
+    // SAFETY: the handle is checked before use and closed afterwards
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let terminated = TerminateProcess(handle, 1);
+        CloseHandle(handle);
+        if terminated == 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+    }
+    Ok(())
+}
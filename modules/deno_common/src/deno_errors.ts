@@ -168,9 +168,16 @@ class NotADirectory extends Error {
   }
 }
 
+class NotCapable extends Error {
+  constructor(msg: string) {
+    super(msg);
+    this.name = "NotCapable";
+  }
+}
+
 // Export all error classes
 // @ts-ignore: mdeno internal API
-globalThis.__mdeno__.errors = {
+globalThis.__mdeno__.errors = Object.freeze({
   NotFound,
   PermissionDenied,
   ConnectionRefused,
@@ -195,4 +202,5 @@ globalThis.__mdeno__.errors = {
   IsADirectory,
   NetworkUnreachable,
   NotADirectory,
-};
+  NotCapable,
+});
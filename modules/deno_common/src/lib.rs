@@ -1,6 +1,27 @@
-use rquickjs::{Ctx, Module};
+use rquickjs::{Ctx, Module, Object, qjs};
+use std::sync::OnceLock;
+use utils::add_internal_function;
 use utils_macros::include_ts;
 
+/// URL given with --location, exposed as `globalThis.location`
+static LOCATION: OnceLock<String> = OnceLock::new();
+
+/// Set the URL for `globalThis.location`
+///
+/// # Errors
+/// Returns an error if `href` is not an absolute http(s) URL
+pub fn set_location(href: &str) -> Result<(), String> {
+    let url = ars::Url::parse(href, None).map_err(|_| format!("Invalid --location URL: {href}"))?;
+    if !matches!(url.protocol(), "http:" | "https:") {
+        return Err(format!(
+            "--location must be an http or https URL, got {}",
+            url.protocol()
+        ));
+    }
+    let _ = LOCATION.set(url.href().to_string());
+    Ok(())
+}
+
 /// # Errors
 /// Returns an error if module initialization fails
 pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
@@ -14,14 +35,99 @@ pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
         globalThis.__mdeno__ ||= {};
         globalThis.__mdeno__.fs ||= {};
         globalThis.__mdeno__.os ||= {};
+        globalThis.__mdeno__.net ||= {};
+        globalThis.__mdeno__.permissions ||= {};
+        globalThis.__mdeno__.kv ||= {};
+        globalThis.__mdeno__.command ||= {};
         globalThis.__mdeno__.errors ||= {};
         "#,
     )?;
 
+    // Deno.core - QuickJS engine diagnostics
+    ctx.eval::<(), _>("globalThis[Symbol.for('mdeno.internal')].core = {};")?;
+    add_internal_function!(ctx, "core.heapStats", heap_stats);
+    add_internal_function!(ctx, "core.gc", |ctx: Ctx<'_>| ctx.run_gc());
+    add_internal_function!(ctx, "core.eventLoopHasMoreWork", |ctx: Ctx<'_>| -> bool {
+        // SAFETY: the context is alive for the duration of the call
+        unsafe { qjs::JS_IsJobPending(qjs::JS_GetRuntime(ctx.as_raw().as_ptr())) }
+    });
+
     // Load error classes
     let js_source = include_ts!("src/deno_errors.ts");
     let errors_module = Module::evaluate(ctx.clone(), "deno_errors", js_source)?;
     errors_module.finish::<()>()?;
 
+    let js_source = include_ts!("src/structured_clone.ts");
+    let clone_module = Module::evaluate(ctx.clone(), "structured_clone", js_source)?;
+    clone_module.finish::<()>()?;
+
+    // globalThis.location stays undefined unless --location was given
+    if let Some(href) = LOCATION.get() {
+        init_location(ctx, href)?;
+    }
+
     Ok(())
 }
+
+fn init_location(ctx: &Ctx<'_>, href: &str) -> rquickjs::Result<()> {
+    let url = ars::Url::parse(href, None).map_err(|_| rquickjs::Error::Unknown)?;
+    let location = Object::new(ctx.clone())?;
+    location.set("href", url.href())?;
+    location.set("origin", url.origin())?;
+    location.set("protocol", url.protocol())?;
+    location.set("host", url.host())?;
+    location.set("hostname", url.hostname())?;
+    location.set("port", url.port())?;
+    location.set("pathname", url.pathname())?;
+    location.set("search", url.search())?;
+    location.set("hash", url.hash())?;
+    let internal: Object = ctx.eval("globalThis[Symbol.for('mdeno.internal')]")?;
+    internal.set("location", location)?;
+
+    let js_source = include_ts!("src/location.ts");
+    let module = Module::evaluate(ctx.clone(), "location", js_source)?;
+    module.finish::<()>()
+}
+
+/// `JS_ComputeMemoryUsage` for the runtime owning `ctx`, with camelCase keys
+fn heap_stats(ctx: Ctx<'_>) -> rquickjs::Result<Object<'_>> {
+    // SAFETY: JSMemoryUsage is plain integers, filled in by QuickJS for a live runtime
+    let usage = unsafe {
+        let mut usage = std::mem::zeroed::<qjs::JSMemoryUsage>();
+        qjs::JS_ComputeMemoryUsage(qjs::JS_GetRuntime(ctx.as_raw().as_ptr()), &raw mut usage);
+        usage
+    };
+
+    let stats = Object::new(ctx)?;
+    for (name, value) in [
+        ("mallocSize", usage.malloc_size),
+        ("mallocLimit", usage.malloc_limit),
+        ("memoryUsedSize", usage.memory_used_size),
+        ("mallocCount", usage.malloc_count),
+        ("memoryUsedCount", usage.memory_used_count),
+        ("atomCount", usage.atom_count),
+        ("atomSize", usage.atom_size),
+        ("strCount", usage.str_count),
+        ("strSize", usage.str_size),
+        ("objCount", usage.obj_count),
+        ("objSize", usage.obj_size),
+        ("propCount", usage.prop_count),
+        ("propSize", usage.prop_size),
+        ("shapeCount", usage.shape_count),
+        ("shapeSize", usage.shape_size),
+        ("jsFuncCount", usage.js_func_count),
+        ("jsFuncSize", usage.js_func_size),
+        ("jsFuncCodeSize", usage.js_func_code_size),
+        ("jsFuncPc2lineCount", usage.js_func_pc2line_count),
+        ("jsFuncPc2lineSize", usage.js_func_pc2line_size),
+        ("cFuncCount", usage.c_func_count),
+        ("arrayCount", usage.array_count),
+        ("fastArrayCount", usage.fast_array_count),
+        ("fastArrayElements", usage.fast_array_elements),
+        ("binaryObjectCount", usage.binary_object_count),
+        ("binaryObjectSize", usage.binary_object_size),
+    ] {
+        stats.set(name, value as f64)?;
+    }
+    Ok(stats)
+}
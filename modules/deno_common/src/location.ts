@@ -0,0 +1,60 @@
+// Read-only globalThis.location, set from --location
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+const url = __internal.location;
+
+const PROPERTIES = [
+  "href",
+  "origin",
+  "protocol",
+  "host",
+  "hostname",
+  "port",
+  "pathname",
+  "search",
+  "hash",
+] as const;
+
+// https://developer.mozilla.org/en-US/docs/Web/API/Location
+class Location {
+  toString(): string {
+    return url.href;
+  }
+
+  assign(): never {
+    throw new TypeError("Cannot call location.assign() outside of a browser");
+  }
+
+  replace(): never {
+    throw new TypeError("Cannot call location.replace() outside of a browser");
+  }
+
+  reload(): never {
+    throw new TypeError("Cannot call location.reload() outside of a browser");
+  }
+
+  get [Symbol.toStringTag](): string {
+    return "Location";
+  }
+}
+
+for (const property of PROPERTIES) {
+  Object.defineProperty(Location.prototype, property, {
+    get(): string {
+      return url[property];
+    },
+    set(): never {
+      throw new TypeError(
+        `Cannot set property ${property} of #<Location> which has only a getter`,
+      );
+    },
+    enumerable: true,
+    configurable: true,
+  });
+}
+
+Object.defineProperty(globalThis, "location", {
+  value: Object.freeze(new Location()),
+  enumerable: true,
+  configurable: true,
+});
@@ -0,0 +1,190 @@
+// globalThis.structuredClone, following the HTML structured clone algorithm
+// for the types QuickJS has
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+// https://html.spec.whatwg.org/multipage/structured-data.html#serializable-objects
+const ERROR_TYPES: Record<string, ErrorConstructor> = {
+  Error,
+  EvalError,
+  RangeError,
+  ReferenceError,
+  SyntaxError,
+  TypeError,
+  URIError,
+};
+
+// Objects that hold state a copy can't have
+const UNCLONEABLE: (abstract new (...args: never[]) => unknown)[] = [
+  Promise,
+  WeakMap,
+  WeakSet,
+  WeakRef,
+  FinalizationRegistry,
+];
+
+type View = ArrayBufferView & {
+  constructor: new (
+    buffer: ArrayBufferLike,
+    byteOffset: number,
+    length: number,
+  ) => ArrayBufferView;
+  length?: number;
+};
+
+function dataCloneError(message: string): DOMException {
+  return new DOMException(message, "DataCloneError");
+}
+
+// Objects already copied, and the objects whose properties are being copied
+interface Memory {
+  copies: Map<object, unknown>;
+  ancestors: Set<object>;
+}
+
+// Copy `value`. An object reached twice is copied once and stays shared,
+// unless it contains itself.
+function clone(value: unknown, memory: Memory): unknown {
+  if (typeof value === "function") {
+    throw dataCloneError(`${value.name || "anonymous"} could not be cloned`);
+  }
+  if (typeof value === "symbol") {
+    throw dataCloneError(`${String(value)} could not be cloned`);
+  }
+  if (value === null || typeof value !== "object") {
+    return value;
+  }
+  if (memory.ancestors.has(value)) {
+    throw dataCloneError("Circular references could not be cloned");
+  }
+  if (memory.copies.has(value)) {
+    return memory.copies.get(value);
+  }
+
+  let copy: unknown;
+  if (value instanceof Date) {
+    copy = new Date(value.getTime());
+  } else if (value instanceof RegExp) {
+    copy = new RegExp(value.source, value.flags);
+  } else if (
+    value instanceof Boolean || value instanceof Number ||
+    value instanceof String || value instanceof BigInt
+  ) {
+    copy = Object(value.valueOf());
+  } else if (value instanceof ArrayBuffer) {
+    copy = value.slice(0);
+  } else if (
+    typeof SharedArrayBuffer !== "undefined" &&
+    value instanceof SharedArrayBuffer
+  ) {
+    // Copied rather than shared, as there is only ever one thread
+    copy = value.slice(0);
+  } else if (ArrayBuffer.isView(value)) {
+    const view = value as View;
+    // Views of the same buffer keep sharing its copy
+    const buffer = clone(view.buffer, memory) as ArrayBufferLike;
+    copy = view instanceof DataView
+      ? new DataView(buffer, view.byteOffset, view.byteLength)
+      : new view.constructor(buffer, view.byteOffset, view.length ?? 0);
+  } else if (UNCLONEABLE.some((type) => value instanceof type)) {
+    throw dataCloneError(
+      `${value.constructor.name} object could not be cloned`,
+    );
+  } else {
+    memory.ancestors.add(value);
+    try {
+      copy = cloneContents(value, memory);
+    } finally {
+      memory.ancestors.delete(value);
+    }
+  }
+  memory.copies.set(value, copy);
+  return copy;
+}
+
+// Copy an object that holds other values
+function cloneContents(value: object, memory: Memory): unknown {
+  if (value instanceof Error) {
+    return cloneError(value, memory);
+  }
+  if (value instanceof Map) {
+    const map = new Map();
+    for (const [key, item] of value) {
+      map.set(clone(key, memory), clone(item, memory));
+    }
+    return map;
+  }
+  if (value instanceof Set) {
+    const set = new Set();
+    for (const item of value) {
+      set.add(clone(item, memory));
+    }
+    return set;
+  }
+  if (Array.isArray(value)) {
+    return copyProperties(value, new Array(value.length), memory);
+  }
+  // Other objects, including class instances, become plain objects
+  return copyProperties(value, {}, memory);
+}
+
+// Copy the own enumerable string-keyed properties of `from`
+function copyProperties<T extends object>(
+  from: object,
+  to: T,
+  memory: Memory,
+): T {
+  for (const key of Object.keys(from)) {
+    (to as Record<string, unknown>)[key] = clone(
+      (from as Record<string, unknown>)[key],
+      memory,
+    );
+  }
+  return to;
+}
+
+// Errors keep their type when it is a built-in one, with the message, stack
+// and cause
+function cloneError(error: Error, memory: Memory): Error {
+  const name = String(error.name);
+  const type = Object.hasOwn(ERROR_TYPES, name) ? ERROR_TYPES[name] : Error;
+  const copy = new type();
+  if (Object.hasOwn(error, "message")) {
+    copy.message = String(error.message);
+  }
+  if (typeof error.stack === "string") {
+    Object.defineProperty(copy, "stack", {
+      value: error.stack,
+      writable: true,
+      enumerable: false,
+      configurable: true,
+    });
+  }
+  if (Object.hasOwn(error, "cause")) {
+    copy.cause = clone(error.cause, memory);
+  }
+  return copy;
+}
+
+// https://html.spec.whatwg.org/multipage/structured-data.html#dom-structuredclone
+function structuredClone<T>(
+  value: T,
+  options?: StructuredSerializeOptions,
+): T {
+  if (arguments.length === 0) {
+    throw new TypeError("structuredClone requires at least 1 argument");
+  }
+  const transfer = options?.transfer;
+  if (transfer !== undefined && transfer !== null && [...transfer].length > 0) {
+    throw dataCloneError("transfer not supported");
+  }
+  return clone(value, { copies: new Map(), ancestors: new Set() }) as T;
+}
+
+Object.defineProperty(globalThis, "structuredClone", {
+  value: structuredClone,
+  writable: true,
+  enumerable: false,
+  configurable: true,
+});
+__internal.structuredClone = structuredClone;
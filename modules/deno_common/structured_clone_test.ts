@@ -0,0 +1,132 @@
+function assertCloneError(fn: () => unknown, message?: string) {
+  try {
+    fn();
+  } catch (error) {
+    if (!(error instanceof DOMException) || error.name !== "DataCloneError") {
+      throw error;
+    }
+    if (message !== undefined && error.message !== message) {
+      throw new Error(`message: ${error.message}`);
+    }
+    return;
+  }
+  throw new Error("should throw");
+}
+
+Deno.test("structuredClone copies plain values", () => {
+  const source = {
+    nothing: undefined,
+    empty: null,
+    big: 12345678901234567890n,
+    list: [1, "two", [3]],
+    nested: { date: new Date(0), pattern: /a+b/gi },
+  };
+  const copy = structuredClone(source);
+  if (copy === source || copy.list === source.list) {
+    throw new Error("should be a copy");
+  }
+  if (!("nothing" in copy) || copy.nothing !== undefined || copy.empty !== null) {
+    throw new Error("undefined and null should be kept");
+  }
+  if (copy.big !== 12345678901234567890n) throw new Error(`big: ${copy.big}`);
+  if (JSON.stringify(copy.list) !== '[1,"two",[3]]') {
+    throw new Error(`list: ${JSON.stringify(copy.list)}`);
+  }
+  const { date, pattern } = copy.nested;
+  if (!(date instanceof Date) || date === source.nested.date || date.getTime() !== 0) {
+    throw new Error(`date: ${date}`);
+  }
+  if (!(pattern instanceof RegExp) || `${pattern}` !== "/a+b/gi") {
+    throw new Error(`pattern: ${pattern}`);
+  }
+  if (structuredClone("text") !== "text" || structuredClone(undefined) !== undefined) {
+    throw new Error("primitives should be returned as is");
+  }
+});
+
+Deno.test("structuredClone copies maps, sets and errors", () => {
+  const key = { id: 1 };
+  const map = new Map<unknown, unknown>([[key, new Set([1, 2])]]);
+  const copy = structuredClone(map);
+  const [[copiedKey, copiedSet]] = [...copy];
+  if (copiedKey === key || (copiedKey as typeof key).id !== 1) {
+    throw new Error("map keys should be copied");
+  }
+  if (!(copiedSet instanceof Set) || [...copiedSet].join() !== "1,2") {
+    throw new Error("set should be copied");
+  }
+
+  const error = new RangeError("out of range", { cause: { code: 42 } });
+  const errorCopy = structuredClone(error);
+  if (!(errorCopy instanceof RangeError) || errorCopy === error) {
+    throw new Error("error type should be kept");
+  }
+  if (errorCopy.message !== "out of range" || errorCopy.stack !== error.stack) {
+    throw new Error(`error: ${errorCopy.message}`);
+  }
+  if ((errorCopy.cause as { code: number }).code !== 42) {
+    throw new Error("cause should be copied");
+  }
+});
+
+Deno.test("structuredClone copies buffers and views", () => {
+  const buffer = new ArrayBuffer(8);
+  const bytes = new Uint8Array(buffer);
+  bytes.set([1, 2, 3, 4, 5, 6, 7, 8]);
+  const words = new Uint16Array(buffer, 2, 2);
+  const copy = structuredClone({ buffer, bytes, words, view: new DataView(buffer, 4) });
+
+  bytes[0] = 99;
+  if (!(copy.buffer instanceof ArrayBuffer) || new Uint8Array(copy.buffer)[0] !== 1) {
+    throw new Error("buffer bytes should be copied");
+  }
+  if (copy.bytes.buffer !== copy.buffer || copy.words.buffer !== copy.buffer) {
+    throw new Error("views should share the copied buffer");
+  }
+  if (!(copy.words instanceof Uint16Array) || copy.words.byteOffset !== 2) {
+    throw new Error("view type and offset should be kept");
+  }
+  if (copy.words.length !== 2 || copy.view.getUint8(0) !== 5) {
+    throw new Error("view contents should be kept");
+  }
+});
+
+Deno.test("structuredClone keeps shared references shared", () => {
+  const shared = { value: 1 };
+  const copy = structuredClone({ a: shared, b: shared });
+  if (copy.a !== copy.b || copy.a === shared) {
+    throw new Error("shared object should be copied once");
+  }
+});
+
+Deno.test("structuredClone rejects values that can't be cloned", () => {
+  const cyclic: Record<string, unknown> = {};
+  cyclic.self = cyclic;
+  assertCloneError(() => structuredClone(cyclic));
+  const list: unknown[] = [];
+  list.push(new Map([["list", list]]));
+  assertCloneError(() => structuredClone(list));
+
+  assertCloneError(() => structuredClone(() => {}));
+  assertCloneError(() => structuredClone({ key: Symbol("key") }));
+  assertCloneError(() => structuredClone(Promise.resolve()));
+  assertCloneError(() => structuredClone(new WeakMap()));
+});
+
+Deno.test("structuredClone doesn't support transfer yet", () => {
+  const buffer = new ArrayBuffer(4);
+  assertCloneError(
+    () => structuredClone(buffer, { transfer: [buffer] }),
+    "transfer not supported",
+  );
+  if (structuredClone(buffer, { transfer: [] }).byteLength !== 4) {
+    throw new Error("an empty transfer list should be accepted");
+  }
+  try {
+    // @ts-expect-error: the value is required
+    structuredClone();
+    throw new Error("should throw");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+});
@@ -42,25 +42,367 @@ function pathFromURL(pathOrUrl: string | URL): string {
   return String(pathOrUrl);
 }
 
+// https://docs.deno.com/api/deno/~/Deno.SeekMode
+const SeekMode = Object.freeze({
+  Start: 0,
+  Current: 1,
+  End: 2,
+  0: "Start",
+  1: "Current",
+  2: "End",
+});
+
+type Whence = number | "start" | "current" | "end";
+
+function seekModeFromWhence(whence: Whence): number {
+  switch (whence) {
+    case "start":
+      return SeekMode.Start;
+    case "current":
+      return SeekMode.Current;
+    case "end":
+      return SeekMode.End;
+    default:
+      return whence;
+  }
+}
+
+// Seconds since the Unix epoch, or null to leave the time unchanged
+function toSeconds(time: number | Date | null): number | null {
+  return time instanceof Date ? time.getTime() / 1000 : time;
+}
+
+// Size of each read made when iterating over an FsFile
+const READ_CHUNK_SIZE = 64 * 1024;
+
+// https://docs.deno.com/api/deno/~/Deno.FsFile
+class FsFile {
+  #rid: number;
+
+  constructor(rid: number) {
+    this.#rid = rid;
+  }
+
+  get rid(): number {
+    return this.#rid;
+  }
+
+  readSync(buffer: Uint8Array): number | null {
+    const data = __internal.fs.fileReadSync(this.#rid, buffer.byteLength);
+    if (data == null) return null;
+    buffer.set(data);
+    return data.length;
+  }
+
+  async read(buffer: Uint8Array): Promise<number | null> {
+    const data = await __internal.fs.fileRead(this.#rid, buffer.byteLength);
+    if (data == null) return null;
+    buffer.set(data);
+    return data.length;
+  }
+
+  writeSync(data: Uint8Array): number {
+    return __internal.fs.fileWriteSync(this.#rid, data);
+  }
+
+  write(data: Uint8Array): Promise<number> {
+    return __internal.fs.fileWrite(this.#rid, data);
+  }
+
+  seekSync(offset: number | bigint, whence: Whence): number {
+    return __internal.fs.fileSeekSync(
+      this.#rid,
+      Number(offset),
+      seekModeFromWhence(whence),
+    );
+  }
+
+  seek(offset: number | bigint, whence: Whence): Promise<number> {
+    return __internal.fs.fileSeek(
+      this.#rid,
+      Number(offset),
+      seekModeFromWhence(whence),
+    );
+  }
+
+  truncateSync(len?: number): void {
+    __internal.fs.fileTruncateSync(this.#rid, len);
+  }
+
+  truncate(len?: number): Promise<void> {
+    return __internal.fs.fileTruncate(this.#rid, len);
+  }
+
+  syncSync(): void {
+    __internal.fs.fileSyncSync(this.#rid);
+  }
+
+  sync(): Promise<void> {
+    return __internal.fs.fileSync(this.#rid);
+  }
+
+  syncDataSync(): void {
+    __internal.fs.fileSyncDataSync(this.#rid);
+  }
+
+  syncData(): Promise<void> {
+    return __internal.fs.fileSyncData(this.#rid);
+  }
+
+  flushSync(): void {
+    __internal.fs.fileFlushSync(this.#rid);
+  }
+
+  statSync(): unknown {
+    return __internal.fs.fileStatSync(this.#rid);
+  }
+
+  stat(): Promise<unknown> {
+    return __internal.fs.fileStat(this.#rid);
+  }
+
+  utimeSync(atime: number | Date | null, mtime: number | Date | null): void {
+    __internal.fs.fileUtimeSync(this.#rid, toSeconds(atime), toSeconds(mtime));
+  }
+
+  utime(
+    atime: number | Date | null,
+    mtime: number | Date | null,
+  ): Promise<void> {
+    return __internal.fs.fileUtime(
+      this.#rid,
+      toSeconds(atime),
+      toSeconds(mtime),
+    );
+  }
+
+  closeSync(): void {
+    __internal.fs.fileClose(this.#rid);
+  }
+
+  close(): void {
+    __internal.fs.fileClose(this.#rid);
+  }
+
+  // Yield the rest of the file in chunks, copied so each stays valid
+  async *[Symbol.asyncIterator](): AsyncGenerator<Uint8Array> {
+    const buffer = new Uint8Array(READ_CHUNK_SIZE);
+    while (true) {
+      const n = await this.read(buffer);
+      if (n === null) return;
+      yield buffer.slice(0, n);
+    }
+  }
+
+  [Symbol.dispose](): void {
+    try {
+      __internal.fs.fileClose(this.#rid);
+    } catch {
+      // Already closed
+    }
+  }
+}
+
+interface FsEvent {
+  kind: "any" | "access" | "create" | "modify" | "remove";
+  paths: string[];
+}
+
+// https://docs.deno.com/api/deno/~/Deno.FsWatcher
+class FsWatcher {
+  #rid: number;
+  #closed = false;
+  // Settles pending next() calls once the watcher is closed
+  #closedSignal: Promise<null>;
+  #resolveClosed!: (value: null) => void;
+
+  constructor(rid: number) {
+    this.#rid = rid;
+    this.#closedSignal = new Promise((resolve) => {
+      this.#resolveClosed = resolve;
+    });
+  }
+
+  get rid(): number {
+    return this.#rid;
+  }
+
+  async next(): Promise<IteratorResult<FsEvent>> {
+    if (this.#closed) return { value: undefined, done: true };
+    const event = await Promise.race([
+      __internal.fs.watchNext(this.#rid),
+      this.#closedSignal,
+    ]);
+    if (event === null) return { value: undefined, done: true };
+    return { value: event, done: false };
+  }
+
+  // Called when a for await loop exits early
+  return(value?: unknown): Promise<IteratorResult<FsEvent>> {
+    this.close();
+    return Promise.resolve({ value, done: true });
+  }
+
+  close(): void {
+    if (this.#closed) return;
+    this.#closed = true;
+    __internal.fs.watchClose(this.#rid);
+    this.#resolveClosed(null);
+  }
+
+  [Symbol.asyncIterator](): FsWatcher {
+    return this;
+  }
+
+  [Symbol.dispose](): void {
+    this.close();
+  }
+}
+
 // @ts-ignore: mdeno internal API
 Object.assign(globalThis.__mdeno__.fs, {
+  FsFile,
+  FsWatcher,
+  SeekMode,
+  // Shared with modules that take paths, such as deno_command
+  pathFromURL,
+
+  // https://docs.deno.com/api/deno/~/Deno.openSync
+  openSync(path: string | URL, options?: unknown): FsFile {
+    path = pathFromURL(path);
+    return new FsFile(__internal.fs.openSync(path, options));
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.seekSync
+  seekSync(rid: number, offset: number | bigint, whence: Whence): number {
+    return __internal.fs.fileSeekSync(
+      rid,
+      Number(offset),
+      seekModeFromWhence(whence),
+    );
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.seek
+  seek(
+    rid: number,
+    offset: number | bigint,
+    whence: Whence,
+  ): Promise<number> {
+    return __internal.fs.fileSeek(
+      rid,
+      Number(offset),
+      seekModeFromWhence(whence),
+    );
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.fstatSync
+  fstatSync(rid: number): unknown {
+    return __internal.fs.fileStatSync(rid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.fstat
+  fstat(rid: number): Promise<unknown> {
+    return __internal.fs.fileStat(rid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.fsyncSync
+  fsyncSync(rid: number): void {
+    __internal.fs.fileSyncSync(rid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.fsync
+  fsync(rid: number): Promise<void> {
+    return __internal.fs.fileSync(rid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.fdatasyncSync
+  fdatasyncSync(rid: number): void {
+    __internal.fs.fileSyncDataSync(rid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.fdatasync
+  fdatasync(rid: number): Promise<void> {
+    return __internal.fs.fileSyncData(rid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.futimeSync
+  futimeSync(
+    rid: number,
+    atime: number | Date | null,
+    mtime: number | Date | null,
+  ): void {
+    __internal.fs.fileUtimeSync(rid, toSeconds(atime), toSeconds(mtime));
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.futime
+  futime(
+    rid: number,
+    atime: number | Date | null,
+    mtime: number | Date | null,
+  ): Promise<void> {
+    return __internal.fs.fileUtime(rid, toSeconds(atime), toSeconds(mtime));
+  },
+
+  // Close a file by resource ID
+  close(rid: number): void {
+    __internal.fs.fileClose(rid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.open
+  async open(path: string | URL, options?: unknown): Promise<FsFile> {
+    path = pathFromURL(path);
+    return new FsFile(await __internal.fs.open(path, options));
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.watchFs
+  watchFs(
+    paths: string | URL | (string | URL)[],
+    options: { recursive?: boolean } = {},
+  ): FsWatcher {
+    const list = (Array.isArray(paths) ? paths : [paths]).map(pathFromURL);
+    if (list.length === 0) {
+      throw new TypeError("At least one path must be watched");
+    }
+    return new FsWatcher(
+      __internal.fs.watch(list, options.recursive ?? true),
+    );
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.cwd
   cwd(): string {
     return __internal.fs.cwd();
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.chdir
+  chdir(directory: string | URL): void {
+    directory = pathFromURL(directory);
+    return __internal.fs.chdir(directory);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.readFileSync
   readFileSync(path: string | URL): Uint8Array {
     path = pathFromURL(path);
     return __internal.fs.readFileSync(path);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.readFile
+  readFile(path: string | URL): Promise<Uint8Array> {
+    path = pathFromURL(path);
+    return __internal.fs.readFile(path);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.readTextFileSync
   readTextFileSync(path: string | URL): string {
     path = pathFromURL(path);
     return __internal.fs.readTextFileSync(path);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.readTextFile
+  readTextFile(path: string | URL): Promise<string> {
+    path = pathFromURL(path);
+    return __internal.fs.readTextFile(path);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.writeFileSync
   writeFileSync(
     path: string | URL,
@@ -74,30 +416,71 @@ Object.assign(globalThis.__mdeno__.fs, {
     return __internal.fs.writeFileSync(path, data, options);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.writeFile
+  writeFile(
+    path: string | URL,
+    data: Uint8Array | string,
+    options?: unknown,
+  ): Promise<void> {
+    path = pathFromURL(path);
+    if (typeof data === "string") {
+      data = new TextEncoder().encode(data);
+    }
+    return __internal.fs.writeFile(path, data, options);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.writeTextFileSync
   writeTextFileSync(path: string | URL, text: string, options?: unknown): void {
     path = pathFromURL(path);
     return __internal.fs.writeTextFileSync(path, String(text), options);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.writeTextFile
+  writeTextFile(
+    path: string | URL,
+    text: string,
+    options?: unknown,
+  ): Promise<void> {
+    path = pathFromURL(path);
+    return __internal.fs.writeTextFile(path, String(text), options);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.statSync
   statSync(path: string | URL): unknown {
     path = pathFromURL(path);
     return __internal.fs.statSync(path);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.stat
+  stat(path: string | URL): Promise<unknown> {
+    path = pathFromURL(path);
+    return __internal.fs.stat(path);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.mkdirSync
   mkdirSync(path: string | URL, options?: unknown): void {
     path = pathFromURL(path);
     return __internal.fs.mkdirSync(path, options);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.mkdir
+  mkdir(path: string | URL, options?: unknown): Promise<void> {
+    path = pathFromURL(path);
+    return __internal.fs.mkdir(path, options);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.removeSync
   removeSync(path: string | URL, options?: unknown): void {
     path = pathFromURL(path);
     return __internal.fs.removeSync(path, options);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.remove
+  remove(path: string | URL, options?: unknown): Promise<void> {
+    path = pathFromURL(path);
+    return __internal.fs.remove(path, options);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.copyFileSync
   copyFileSync(fromPath: string | URL, toPath: string | URL): void {
     fromPath = pathFromURL(fromPath);
@@ -105,16 +488,36 @@ Object.assign(globalThis.__mdeno__.fs, {
     return __internal.fs.copyFileSync(fromPath, toPath);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.copyFile
+  copyFile(fromPath: string | URL, toPath: string | URL): Promise<void> {
+    fromPath = pathFromURL(fromPath);
+    toPath = pathFromURL(toPath);
+    return __internal.fs.copyFile(fromPath, toPath);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.lstatSync
   lstatSync(path: string | URL): unknown {
     path = pathFromURL(path);
     return __internal.fs.lstatSync(path);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.lstat
+  lstat(path: string | URL): Promise<unknown> {
+    path = pathFromURL(path);
+    return __internal.fs.lstat(path);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.readDirSync
-  readDirSync(path: string | URL): unknown {
+  readDirSync(path: string | URL): IterableIterator<unknown> {
     path = pathFromURL(path);
-    return __internal.fs.readDirSync(path);
+    return __internal.fs.readDirSync(path).values();
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.readDir
+  async *readDir(path: string | URL): AsyncIterableIterator<unknown> {
+    path = pathFromURL(path);
+    // The directory is listed off the main thread, then yielded entry by entry
+    yield* await __internal.fs.readDir(path);
   },
 
   // https://docs.deno.com/api/deno/~/Deno.renameSync
@@ -124,25 +527,148 @@ Object.assign(globalThis.__mdeno__.fs, {
     return __internal.fs.renameSync(oldpath, newpath);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.rename
+  rename(oldpath: string | URL, newpath: string | URL): Promise<void> {
+    oldpath = pathFromURL(oldpath);
+    newpath = pathFromURL(newpath);
+    return __internal.fs.rename(oldpath, newpath);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.linkSync
+  linkSync(oldpath: string, newpath: string): void {
+    return __internal.fs.linkSync(oldpath, newpath);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.link
+  link(oldpath: string, newpath: string): Promise<void> {
+    return __internal.fs.link(oldpath, newpath);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.symlinkSync
+  symlinkSync(
+    oldpath: string | URL,
+    newpath: string | URL,
+    options?: { type: "file" | "dir" | "junction" },
+  ): void {
+    oldpath = pathFromURL(oldpath);
+    newpath = pathFromURL(newpath);
+    return __internal.fs.symlinkSync(oldpath, newpath, options?.type);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.symlink
+  symlink(
+    oldpath: string | URL,
+    newpath: string | URL,
+    options?: { type: "file" | "dir" | "junction" },
+  ): Promise<void> {
+    oldpath = pathFromURL(oldpath);
+    newpath = pathFromURL(newpath);
+    return __internal.fs.symlink(oldpath, newpath, options?.type);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.readLinkSync
+  readLinkSync(path: string | URL): string {
+    path = pathFromURL(path);
+    return __internal.fs.readLinkSync(path);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.readLink
+  readLink(path: string | URL): Promise<string> {
+    path = pathFromURL(path);
+    return __internal.fs.readLink(path);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.realPathSync
   realPathSync(path: string | URL): string {
     path = pathFromURL(path);
     return __internal.fs.realPathSync(path);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.realPath
+  realPath(path: string | URL): Promise<string> {
+    path = pathFromURL(path);
+    return __internal.fs.realPath(path);
+  },
+
+  // Like realPathSync, but returns the OS-native path instead of a file:// URL
+  canonicalizeSync(path: string | URL): string {
+    path = pathFromURL(path);
+    return __internal.fs.canonicalizeSync(path);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.truncateSync
   truncateSync(path: string | URL, len?: number): void {
     path = pathFromURL(path);
     return __internal.fs.truncateSync(path, len);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.truncate
+  truncate(path: string | URL, len?: number): Promise<void> {
+    path = pathFromURL(path);
+    return __internal.fs.truncate(path, len);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.chmodSync
+  chmodSync(path: string | URL, mode: number): void {
+    path = pathFromURL(path);
+    return __internal.fs.chmodSync(path, mode);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.chmod
+  chmod(path: string | URL, mode: number): Promise<void> {
+    path = pathFromURL(path);
+    return __internal.fs.chmod(path, mode);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.chownSync
+  chownSync(path: string | URL, uid: number | null, gid: number | null): void {
+    path = pathFromURL(path);
+    return __internal.fs.chownSync(path, uid, gid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.chown
+  chown(path: string | URL, uid: number | null, gid: number | null): Promise<void> {
+    path = pathFromURL(path);
+    return __internal.fs.chown(path, uid, gid);
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.utimeSync
+  utimeSync(
+    path: string | URL,
+    atime: number | Date | null,
+    mtime: number | Date | null,
+  ): void {
+    path = pathFromURL(path);
+    __internal.fs.utimeSync(path, toSeconds(atime), toSeconds(mtime));
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.utime
+  utime(
+    path: string | URL,
+    atime: number | Date | null,
+    mtime: number | Date | null,
+  ): Promise<void> {
+    path = pathFromURL(path);
+    return __internal.fs.utime(path, toSeconds(atime), toSeconds(mtime));
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.makeTempDirSync
   makeTempDirSync(options?: unknown): string {
     return __internal.fs.makeTempDirSync(options);
   },
 
+  // https://docs.deno.com/api/deno/~/Deno.makeTempDir
+  makeTempDir(options?: unknown): Promise<string> {
+    return __internal.fs.makeTempDir(options);
+  },
+
   // https://docs.deno.com/api/deno/~/Deno.makeTempFileSync
   makeTempFileSync(options?: unknown): string {
     return __internal.fs.makeTempFileSync(options);
   },
+
+  // https://docs.deno.com/api/deno/~/Deno.makeTempFile
+  makeTempFile(options?: unknown): Promise<string> {
+    return __internal.fs.makeTempFile(options);
+  },
 });
@@ -0,0 +1,262 @@
+// Open files for Deno.open / Deno.FsFile, addressed by resource ID
+use crate::{FileInfo, build_file_info, check_read, check_write, file_time, run_blocking};
+use rquickjs::TypedArray;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use utils::{DenoError, DenoResult, JsResult};
+
+/// Open files indexed by resource ID. IDs aren't reused, so a stale ID can't
+/// reach a file opened after it was closed. Each file has its own lock, so
+/// I/O on one file doesn't block every other file.
+static RESOURCES: LazyLock<Mutex<HashMap<u32, Arc<Mutex<File>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+// Start past stdin/stdout/stderr, which Deno reserves as rids 0-2
+static NEXT_RID: AtomicU32 = AtomicU32::new(3);
+
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)] // Mirrors Deno.OpenOptions
+pub struct OpenOptions {
+    pub read: bool,
+    pub write: bool,
+    pub append: bool,
+    pub truncate: bool,
+    pub create: bool,
+    pub create_new: bool,
+    /// Permissions for a created file, ignored on Windows
+    #[cfg_attr(not(unix), allow(dead_code))]
+    pub mode: Option<u32>,
+}
+
+impl Default for OpenOptions {
+    /// Read-only, as with `Deno.open(path)`
+    fn default() -> Self {
+        Self {
+            read: true,
+            write: false,
+            append: false,
+            truncate: false,
+            create: false,
+            create_new: false,
+            mode: None,
+        }
+    }
+}
+
+impl<'js> rquickjs::FromJs<'js> for OpenOptions {
+    fn from_js(ctx: &rquickjs::Ctx<'js>, value: rquickjs::Value<'js>) -> rquickjs::Result<Self> {
+        let obj = rquickjs::Object::from_js(ctx, value)?;
+        let flag = |name: &str| obj.get::<_, Option<bool>>(name).ok().flatten();
+        let write = flag("write");
+        let append = flag("append");
+        Ok(Self {
+            // Like Deno, only default to reading when no access mode is given
+            read: flag("read").unwrap_or(write.is_none() && append.is_none()),
+            write: write.unwrap_or(false),
+            append: append.unwrap_or(false),
+            truncate: flag("truncate").unwrap_or(false),
+            create: flag("create").unwrap_or(false),
+            create_new: flag("createNew").unwrap_or(false),
+            mode: obj.get("mode").ok().flatten(),
+        })
+    }
+}
+
+fn open(path: &str, options: &OpenOptions) -> DenoResult<u32> {
+    if options.read {
+        check_read(path)?;
+    }
+    if options.write || options.append {
+        check_write(path)?;
+    }
+    let mut open_options = fs::OpenOptions::new();
+    open_options
+        .read(options.read)
+        .write(options.write)
+        .append(options.append)
+        .truncate(options.truncate)
+        .create(options.create)
+        .create_new(options.create_new);
+    #[cfg(unix)]
+    if let Some(mode) = options.mode {
+        use std::os::unix::fs::OpenOptionsExt;
+        open_options.mode(mode & 0o777);
+    }
+    let file = open_options.open(path)?;
+    Ok(insert(file))
+}
+
+fn insert(file: File) -> u32 {
+    let rid = NEXT_RID.fetch_add(1, Ordering::Relaxed);
+    resources().insert(rid, Arc::new(Mutex::new(file)));
+    rid
+}
+
+fn resources() -> std::sync::MutexGuard<'static, HashMap<u32, Arc<Mutex<File>>>> {
+    RESOURCES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Run `f` on the open file behind `rid`, without holding the table lock
+fn with_file<T>(rid: u32, f: impl FnOnce(&mut File) -> DenoResult<T>) -> DenoResult<T> {
+    let file = resources()
+        .get(&rid)
+        .cloned()
+        .ok_or_else(|| DenoError::BadResource("Bad resource ID".to_string()))?;
+    let mut file = file
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    f(&mut file)
+}
+
+fn read(rid: u32, len: usize) -> DenoResult<Option<Vec<u8>>> {
+    with_file(rid, |file| {
+        let mut buf = vec![0; len];
+        let read = file.read(&mut buf)?;
+        if read == 0 && len > 0 {
+            return Ok(None);
+        }
+        buf.truncate(read);
+        Ok(Some(buf))
+    })
+}
+
+fn write(rid: u32, data: &[u8]) -> DenoResult<usize> {
+    with_file(rid, |file| Ok(file.write(data)?))
+}
+
+fn seek(rid: u32, offset: i64, whence: u8) -> DenoResult<u64> {
+    let position = match whence {
+        0 => SeekFrom::Start(
+            u64::try_from(offset)
+                .map_err(|_| DenoError::Other("Negative seek offset".to_string()))?,
+        ),
+        1 => SeekFrom::Current(offset),
+        2 => SeekFrom::End(offset),
+        _ => return Err(DenoError::Other(format!("Invalid seek mode: {whence}"))),
+    };
+    with_file(rid, |file| Ok(file.seek(position)?))
+}
+
+pub(crate) fn fs_open_sync(path: String, options: Option<OpenOptions>) -> JsResult<u32> {
+    open(&path, &options.unwrap_or_default()).into()
+}
+
+pub(crate) async fn fs_open(path: String, options: Option<OpenOptions>) -> JsResult<u32> {
+    run_blocking(move || open(&path, &options.unwrap_or_default()))
+        .await
+        .into()
+}
+
+pub(crate) fn fs_file_read_sync(rid: u32, len: usize) -> JsResult<Option<Vec<u8>>> {
+    read(rid, len).into()
+}
+
+pub(crate) async fn fs_file_read(rid: u32, len: usize) -> JsResult<Option<Vec<u8>>> {
+    run_blocking(move || read(rid, len)).await.into()
+}
+
+pub(crate) fn fs_file_write_sync(rid: u32, data: TypedArray<'_, u8>) -> JsResult<usize> {
+    let result: DenoResult<usize> = match data.as_bytes() {
+        Some(bytes) => write(rid, bytes),
+        None => Err(DenoError::Other("Buffer is detached".to_string())),
+    };
+    result.into()
+}
+
+pub(crate) async fn fs_file_write(rid: u32, data: TypedArray<'_, u8>) -> JsResult<usize> {
+    let Some(data) = data.as_bytes().map(<[u8]>::to_vec) else {
+        return JsResult::from(Err::<usize, _>(DenoError::Other(
+            "Buffer is detached".to_string(),
+        )));
+    };
+    run_blocking(move || write(rid, &data)).await.into()
+}
+
+pub(crate) fn fs_file_seek_sync(rid: u32, offset: i64, whence: u8) -> JsResult<u64> {
+    seek(rid, offset, whence).into()
+}
+
+pub(crate) async fn fs_file_seek(rid: u32, offset: i64, whence: u8) -> JsResult<u64> {
+    run_blocking(move || seek(rid, offset, whence)).await.into()
+}
+
+fn truncate(rid: u32, len: Option<u64>) -> DenoResult<()> {
+    with_file(rid, |file| Ok(file.set_len(len.unwrap_or(0))?))
+}
+
+pub(crate) fn fs_file_truncate_sync(rid: u32, len: Option<u64>) -> JsResult<()> {
+    truncate(rid, len).into()
+}
+
+pub(crate) async fn fs_file_truncate(rid: u32, len: Option<u64>) -> JsResult<()> {
+    run_blocking(move || truncate(rid, len)).await.into()
+}
+
+pub(crate) fn fs_file_sync_sync(rid: u32) -> JsResult<()> {
+    with_file(rid, |file| Ok(file.sync_all()?)).into()
+}
+
+pub(crate) async fn fs_file_sync(rid: u32) -> JsResult<()> {
+    run_blocking(move || with_file(rid, |file| Ok(file.sync_all()?)))
+        .await
+        .into()
+}
+
+pub(crate) fn fs_file_sync_data_sync(rid: u32) -> JsResult<()> {
+    with_file(rid, |file| Ok(file.sync_data()?)).into()
+}
+
+pub(crate) async fn fs_file_sync_data(rid: u32) -> JsResult<()> {
+    run_blocking(move || with_file(rid, |file| Ok(file.sync_data()?)))
+        .await
+        .into()
+}
+
+pub(crate) fn fs_file_flush_sync(rid: u32) -> JsResult<()> {
+    with_file(rid, |file| Ok(file.flush()?)).into()
+}
+
+fn stat(rid: u32) -> DenoResult<FileInfo> {
+    with_file(rid, |file| Ok(build_file_info(&file.metadata()?)))
+}
+
+pub(crate) fn fs_file_stat_sync(rid: u32) -> JsResult<FileInfo> {
+    stat(rid).into()
+}
+
+pub(crate) async fn fs_file_stat(rid: u32) -> JsResult<FileInfo> {
+    run_blocking(move || stat(rid)).await.into()
+}
+
+fn utime(rid: u32, atime: Option<f64>, mtime: Option<f64>) -> DenoResult<()> {
+    with_file(rid, |file| {
+        filetime::set_file_handle_times(file, atime.map(file_time), mtime.map(file_time))?;
+        Ok(())
+    })
+}
+
+pub(crate) fn fs_file_utime_sync(rid: u32, atime: Option<f64>, mtime: Option<f64>) -> JsResult<()> {
+    utime(rid, atime, mtime).into()
+}
+
+pub(crate) async fn fs_file_utime(
+    rid: u32,
+    atime: Option<f64>,
+    mtime: Option<f64>,
+) -> JsResult<()> {
+    run_blocking(move || utime(rid, atime, mtime)).await.into()
+}
+
+pub(crate) fn fs_file_close(rid: u32) -> JsResult<()> {
+    let closed = resources().remove(&rid);
+    let result: DenoResult<()> = match closed {
+        // The file closes once the last pending operation drops its handle
+        Some(_) => Ok(()),
+        None => Err(DenoError::BadResource("Bad resource ID".to_string())),
+    };
+    result.into()
+}
@@ -0,0 +1,411 @@
+Deno.test("Deno.truncateSync", () => {
+  const path = Deno.makeTempFileSync();
+  Deno.writeTextFileSync(path, "hello world");
+  Deno.truncateSync(path, 5);
+  if (Deno.readTextFileSync(path) !== "hello") throw new Error("len");
+  Deno.truncateSync(path);
+  if (Deno.statSync(path).size !== 0) throw new Error("default len");
+  Deno.removeSync(path);
+});
+
+Deno.test("Deno.truncate", async () => {
+  const path = Deno.makeTempFileSync();
+  Deno.writeTextFileSync(path, "hello world");
+  await Deno.truncate(path, 5);
+  if (Deno.readTextFileSync(path) !== "hello") throw new Error("len");
+  Deno.removeSync(path);
+
+  let error;
+  try {
+    await Deno.truncate(path);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.NotFound)) throw new Error("NotFound");
+});
+
+Deno.test("Deno.chmod and Deno.chown", async () => {
+  const dir = Deno.makeTempDirSync();
+  const file = `${dir}/file.txt`;
+  Deno.writeTextFileSync(file, "");
+
+  if (Deno.build.os !== "windows") {
+    Deno.chmodSync(file, 0o600);
+    if ((Deno.statSync(file).mode! & 0o777) !== 0o600) throw new Error("chmodSync");
+    await Deno.chmod(file, 0o644);
+    if (((await Deno.stat(file)).mode! & 0o777) !== 0o644) throw new Error("chmod");
+
+    // null leaves the owner and group as they are
+    Deno.chownSync(file, null, null);
+    await Deno.chown(file, null, null);
+  }
+
+  let error;
+  try {
+    await Deno.chmod(`${dir}/missing`, 0o644);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.NotFound)) throw new Error("NotFound");
+  if (Deno.build.os !== "windows") {
+    error = undefined;
+    try {
+      Deno.chownSync(`${dir}/missing`, null, null);
+    } catch (e) {
+      error = e;
+    }
+    if (!(error instanceof Deno.errors.NotFound)) throw new Error("chown NotFound");
+  }
+  Deno.removeSync(dir, { recursive: true });
+});
+
+Deno.test("Deno.realPathSync returns a file URL", () => {
+  const path = Deno.makeTempFileSync();
+  const url = Deno.realPathSync(path);
+  if (!url.startsWith("file://")) throw new Error(url);
+  if (new URL(url).protocol !== "file:") throw new Error(url);
+  Deno.removeSync(path);
+});
+
+Deno.test("Deno.realPath", async () => {
+  const path = Deno.makeTempFileSync();
+  if (await Deno.realPath(path) !== Deno.realPathSync(path)) {
+    throw new Error("realPath and realPathSync differ");
+  }
+  Deno.removeSync(path);
+});
+
+Deno.test("Deno.openSync reads, writes and seeks", () => {
+  const path = Deno.makeTempFileSync();
+  const file = Deno.openSync(path, { read: true, write: true });
+  if (typeof file.rid !== "number") throw new Error("rid");
+  file.writeSync(new TextEncoder().encode("hello world"));
+  if (file.seekSync(6, Deno.SeekMode.Start) !== 6) throw new Error("seek");
+  const buf = new Uint8Array(16);
+  const n = file.readSync(buf);
+  if (new TextDecoder().decode(buf.subarray(0, n!)) !== "world") {
+    throw new Error("read");
+  }
+  if (file.readSync(buf) !== null) throw new Error("expected EOF");
+  if (file.seekSync(-5, "end") !== 6) throw new Error("seek from end");
+  file.truncateSync(5);
+  if (file.statSync().size !== 5) throw new Error("truncate");
+  file.close();
+
+  let error;
+  try {
+    file.readSync(buf);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.BadResource)) throw new Error("closed");
+  Deno.removeSync(path);
+});
+
+Deno.test("FsFile reads, writes and truncates asynchronously", async () => {
+  const path = await Deno.makeTempFile();
+  const file = await Deno.open(path, { read: true, write: true });
+  if (await file.write(new TextEncoder().encode("hello world")) !== 11) {
+    throw new Error("write");
+  }
+  await file.seek(6, Deno.SeekMode.Start);
+  const buf = new Uint8Array(16);
+  const n = await file.read(buf);
+  if (new TextDecoder().decode(buf.subarray(0, n!)) !== "world") {
+    throw new Error("read");
+  }
+  if (await file.read(buf) !== null) throw new Error("expected EOF");
+  await file.truncate(5);
+  if ((await file.stat()).size !== 5) throw new Error("truncate");
+  file.close();
+
+  let error;
+  try {
+    await file.read(buf);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.BadResource)) throw new Error("closed");
+  await Deno.remove(path);
+});
+
+Deno.test("FsFile is async iterable", async () => {
+  const path = await Deno.makeTempFile();
+  // Larger than one read chunk
+  const data = new Uint8Array(100_000).map((_, i) => i % 251);
+  await Deno.writeFile(path, data);
+  const chunks: Uint8Array[] = [];
+  const file = await Deno.open(path);
+  for await (const chunk of file) chunks.push(chunk);
+  file.close();
+  if (chunks.length < 2) throw new Error(`chunks: ${chunks.length}`);
+  let offset = 0;
+  for (const chunk of chunks) {
+    for (const byte of chunk) {
+      if (byte !== data[offset++]) throw new Error(`byte ${offset - 1}`);
+    }
+  }
+  if (offset !== data.length) throw new Error(`length: ${offset}`);
+  await Deno.remove(path);
+});
+
+Deno.test("Deno.open with create and mode", async () => {
+  const dir = Deno.makeTempDirSync();
+  const path = `${dir}/new.txt`;
+  const file = await Deno.open(path, { write: true, create: true, mode: 0o600 });
+  file.writeSync(new TextEncoder().encode("data"));
+  file.close();
+  if (Deno.readTextFileSync(path) !== "data") throw new Error("content");
+  if (Deno.build.os !== "windows" && (Deno.statSync(path).mode! & 0o777) !== 0o600) {
+    throw new Error("mode");
+  }
+  Deno.removeSync(dir, { recursive: true });
+});
+
+Deno.test("Deno.seek and Deno.seekSync by rid", async () => {
+  const path = Deno.makeTempFileSync();
+  Deno.writeTextFileSync(path, "0123456789");
+  const file = Deno.openSync(path);
+  if (Deno.seekSync(file.rid, 4, Deno.SeekMode.Start) !== 4) {
+    throw new Error("seekSync");
+  }
+  if ((await Deno.seek(file.rid, 2, Deno.SeekMode.Current)) !== 6) {
+    throw new Error("seek");
+  }
+  if ((await file.seek(-1, Deno.SeekMode.End)) !== 9) {
+    throw new Error("FsFile.seek");
+  }
+  file.close();
+
+  let error;
+  try {
+    await Deno.seek(file.rid, 0, Deno.SeekMode.Start);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.BadResource)) throw new Error("closed");
+  Deno.removeSync(path);
+});
+
+Deno.test("Deno.fstat and Deno.fsync by rid", async () => {
+  const dir = Deno.makeTempDirSync();
+  const file = Deno.openSync(`${dir}/new.txt`, { write: true, createNew: true });
+  file.writeSync(new TextEncoder().encode("abc"));
+  Deno.fsyncSync(file.rid);
+  await Deno.fdatasync(file.rid);
+  if (!Deno.fstatSync(file.rid).isFile) throw new Error("fstatSync");
+  if ((await Deno.fstat(file.rid)).size !== 3) throw new Error("fstat");
+  file.close();
+
+  let error;
+  try {
+    await Deno.fstat(file.rid);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.BadResource)) throw new Error("closed");
+  Deno.removeSync(dir, { recursive: true });
+});
+
+Deno.test("Deno.utime and Deno.futime set timestamps", async () => {
+  const path = Deno.makeTempFileSync();
+  Deno.utimeSync(path, 1_000_000, 2_000_000);
+  if (Deno.statSync(path).mtime?.getTime() !== 2_000_000_000) {
+    throw new Error("utimeSync");
+  }
+
+  const file = Deno.openSync(path, { write: true });
+  Deno.futimeSync(file.rid, null, new Date(3_000_000_000));
+  await Deno.futime(file.rid, 4_000_000.5, null);
+  const info = Deno.fstatSync(file.rid);
+  if (info.mtime?.getTime() !== 3_000_000_000) throw new Error("futimeSync");
+  if (info.atime?.getTime() !== 4_000_000_500) throw new Error("futime");
+  Deno.close(file.rid);
+
+  let error;
+  try {
+    Deno.futimeSync(file.rid, 0, 0);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.BadResource)) throw new Error("closed");
+  Deno.removeSync(path);
+});
+
+Deno.test("Deno.readDirSync and Deno.readDir iterate entries", async () => {
+  const dir = Deno.makeTempDirSync();
+  Deno.writeTextFileSync(`${dir}/a.txt`, "a");
+  Deno.mkdirSync(`${dir}/sub`);
+
+  const names: string[] = [];
+  for (const entry of Deno.readDirSync(dir)) {
+    names.push(`${entry.name}:${entry.isFile}:${entry.isDirectory}`);
+  }
+  if (names.sort().join(",") !== "a.txt:true:false,sub:false:true") {
+    throw new Error(`readDirSync ${names}`);
+  }
+
+  const asyncNames: string[] = [];
+  for await (const entry of Deno.readDir(dir)) {
+    if (entry.isSymlink) throw new Error("isSymlink");
+    asyncNames.push(entry.name);
+  }
+  if (asyncNames.sort().join(",") !== "a.txt,sub") {
+    throw new Error(`readDir ${asyncNames}`);
+  }
+  Deno.removeSync(dir, { recursive: true });
+});
+
+Deno.test("Deno.readFile and Deno.writeFile round trip bytes", async () => {
+  const path = await Deno.makeTempFile({ suffix: ".bin" });
+  if (!path.endsWith(".bin")) throw new Error(path);
+  await Deno.writeFile(path, new Uint8Array([1, 2, 3]));
+  await Deno.writeFile(path, new Uint8Array([4]), { append: true });
+  const data = await Deno.readFile(path);
+  if (data.join(",") !== "1,2,3,4") throw new Error(`data: ${data}`);
+  await Deno.remove(path);
+});
+
+Deno.test("Deno.readTextFile and Deno.writeTextFile", async () => {
+  const path = await Deno.makeTempFile();
+  await Deno.writeTextFile(path, "hello");
+  if (await Deno.readTextFile(path) !== "hello") throw new Error("text");
+  await Deno.remove(path);
+});
+
+Deno.test("Deno.mkdir, Deno.stat, Deno.rename and Deno.remove", async () => {
+  const dir = await Deno.makeTempDir();
+  await Deno.mkdir(`${dir}/a/b`, { recursive: true });
+  if (!(await Deno.stat(`${dir}/a/b`)).isDirectory) {
+    throw new Error("isDirectory");
+  }
+  await Deno.writeTextFile(`${dir}/a/file.txt`, "x");
+  await Deno.copyFile(`${dir}/a/file.txt`, `${dir}/a/copy.txt`);
+  await Deno.rename(`${dir}/a/copy.txt`, `${dir}/a/moved.txt`);
+  if ((await Deno.lstat(`${dir}/a/moved.txt`)).size !== 1) {
+    throw new Error("size");
+  }
+  await Deno.remove(dir, { recursive: true });
+
+  let error;
+  try {
+    await Deno.stat(dir);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.NotFound)) throw new Error("NotFound");
+});
+
+Deno.test("Deno.symlink, Deno.readLink and Deno.link", async () => {
+  const dir = Deno.makeTempDirSync();
+  Deno.writeTextFileSync(`${dir}/file.txt`, "x");
+
+  Deno.symlinkSync(`${dir}/file.txt`, `${dir}/sync-link`);
+  if (Deno.readLinkSync(`${dir}/sync-link`) !== `${dir}/file.txt`) {
+    throw new Error("readLinkSync");
+  }
+  await Deno.symlink(`${dir}/file.txt`, `${dir}/async-link`, { type: "file" });
+  if (!(await Deno.lstat(`${dir}/async-link`)).isSymlink) throw new Error("isSymlink");
+  if ((await Deno.readLink(`${dir}/async-link`)) !== `${dir}/file.txt`) {
+    throw new Error("readLink");
+  }
+
+  await Deno.link(`${dir}/file.txt`, `${dir}/hard-link`);
+  if (Deno.readTextFileSync(`${dir}/hard-link`) !== "x") throw new Error("link");
+
+  let error;
+  try {
+    Deno.symlinkSync(`${dir}/file.txt`, `${dir}/sync-link`);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.AlreadyExists)) throw new Error("AlreadyExists");
+  error = undefined;
+  try {
+    await Deno.readLink(`${dir}/missing`);
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.NotFound)) throw new Error("NotFound");
+  Deno.removeSync(dir, { recursive: true });
+});
+
+Deno.test("file system errors map to Deno.errors classes", () => {
+  const dir = Deno.makeTempDirSync();
+  const file = `${dir}/file.txt`;
+  Deno.writeTextFileSync(file, "");
+
+  const thrown = (fn: () => unknown) => {
+    try {
+      fn();
+    } catch (e) {
+      return e;
+    }
+    throw new Error("expected an error");
+  };
+  if (!(thrown(() => Deno.readDirSync(file)) instanceof Deno.errors.NotADirectory)) {
+    throw new Error("NotADirectory");
+  }
+  if (
+    Deno.build.os !== "windows" &&
+    !(thrown(() => Deno.readTextFileSync(dir)) instanceof Deno.errors.IsADirectory)
+  ) {
+    throw new Error("IsADirectory");
+  }
+  if (!(thrown(() => Deno.statSync(`${dir}/missing`)) instanceof Deno.errors.NotFound)) {
+    throw new Error("NotFound");
+  }
+  Deno.removeSync(dir, { recursive: true });
+});
+
+Deno.test("async file system errors reject with Deno.errors classes", async () => {
+  const error = await Deno.readTextFile("/no-such-path").then(
+    () => undefined,
+    (e) => e,
+  );
+  if (!(error instanceof Deno.errors.NotFound)) throw new Error(`rejected with ${error}`);
+});
+
+Deno.test("Deno.chdir changes the working directory", () => {
+  const original = Deno.cwd();
+  const dir = Deno.makeTempDirSync();
+  Deno.mkdirSync(`${dir}/sub`);
+  Deno.writeTextFileSync(`${dir}/sub/file.txt`, "hello");
+  try {
+    // realPathSync returns a file URL
+    Deno.chdir(new URL(`${Deno.realPathSync(dir)}/sub`));
+    if (!Deno.cwd().endsWith("sub")) throw new Error(`cwd: ${Deno.cwd()}`);
+    if (Deno.readTextFileSync("file.txt") !== "hello") throw new Error("relative read");
+    try {
+      Deno.chdir("missing");
+      throw new Error("expected NotFound");
+    } catch (e) {
+      if (!(e instanceof Deno.errors.NotFound)) throw e;
+    }
+  } finally {
+    Deno.chdir(original);
+    Deno.removeSync(dir, { recursive: true });
+  }
+});
+
+Deno.test("Deno.stdin exposes rid 0 and line readers", () => {
+  if (Deno.stdin.rid !== 0) throw new Error("rid");
+  if (typeof Deno.stdin.isTerminal() !== "boolean") throw new Error("isTerminal");
+  if (typeof Deno.stdin.readLines !== "function") throw new Error("readLines");
+  if (typeof Deno.stdin.lines !== "function") throw new Error("lines");
+});
+
+Deno.test("Deno.stdin.setRaw toggles raw mode on a terminal", () => {
+  if (Deno.stdin.isTerminal()) {
+    Deno.stdin.setRaw(true, { cbreak: true });
+    Deno.stdin.setRaw(false);
+    return;
+  }
+  try {
+    Deno.stdin.setRaw(true);
+  } catch (e) {
+    if (!(e instanceof Deno.errors.NotSupported)) throw e;
+    return;
+  }
+  throw new Error("setRaw should throw when stdin is not a terminal");
+});
@@ -1,9 +1,14 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
-use rquickjs::function::Constructor;
-use rquickjs::{Ctx, Module, Result as QuickResult};
+mod file;
+mod watch;
+
+use filetime::FileTime;
+use mdeno_path_util::{strip_unc_prefix, to_file_url};
+use rquickjs::function::{Async, Constructor};
+use rquickjs::{Ctx, Module, Result as QuickResult, TypedArray};
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
 use utils::{DenoError, DenoResult, JsResult, add_internal_function};
 use utils_macros::include_ts;
@@ -162,227 +167,524 @@ fn fs_cwd() -> JsResult<String> {
     result.into()
 }
 
-fn fs_read_file_sync(path: String) -> JsResult<Vec<u8>> {
-    let result: DenoResult<Vec<u8>> = fs::read(&path).map_err(std::convert::Into::into);
+fn fs_chdir(directory: String) -> JsResult<()> {
+    let result: DenoResult<()> = (|| {
+        check_read(&directory)?;
+        env::set_current_dir(&directory)?;
+        Ok(())
+    })();
     result.into()
 }
 
+fn read_file(path: &str) -> DenoResult<Vec<u8>> {
+    check_read(path)?;
+    Ok(fs::read(path)?)
+}
+
+fn fs_read_file_sync(path: String) -> JsResult<Vec<u8>> {
+    read_file(&path).into()
+}
+
+async fn fs_read_file(path: String) -> JsResult<Vec<u8>> {
+    run_blocking(move || read_file(&path)).await.into()
+}
+
+fn read_text_file(path: &str) -> DenoResult<String> {
+    check_read(path)?;
+    Ok(fs::read_to_string(path)?)
+}
+
 fn fs_read_text_file_sync(path: String) -> JsResult<String> {
-    let result: DenoResult<String> = fs::read_to_string(&path).map_err(std::convert::Into::into);
-    result.into()
+    read_text_file(&path).into()
+}
+
+async fn fs_read_text_file(path: String) -> JsResult<String> {
+    run_blocking(move || read_text_file(&path)).await.into()
+}
+
+fn write_file(path: &str, data: &[u8], options: &WriteFileOptions) -> DenoResult<()> {
+    use std::io::Write;
+    check_write(path)?;
+
+    if options.create_new && Path::new(path).exists() {
+        return Err(DenoError::Other("File already exists".to_string()));
+    }
+
+    if options.append {
+        let mut file = fs::OpenOptions::new()
+            .create(options.create)
+            .append(true)
+            .open(path)?;
+        file.write_all(data)?;
+    } else {
+        fs::write(path, data)?;
+    }
+    Ok(())
 }
 
 fn fs_write_file_sync(
     path: String,
-    data: Vec<u8>,
+    data: TypedArray<'_, u8>,
     options: Option<WriteFileOptions>,
 ) -> JsResult<()> {
-    use std::io::Write;
-    let result: DenoResult<()> = (|| {
-        let opts = options.unwrap_or_default();
-
-        if opts.create_new && Path::new(&path).exists() {
-            return Err(DenoError::Other("File already exists".to_string()));
-        }
-
-        if opts.append {
-            let mut file = fs::OpenOptions::new()
-                .create(opts.create)
-                .append(true)
-                .open(&path)?;
-            file.write_all(&data)?;
-        } else {
-            fs::write(&path, &data)?;
-        }
-        Ok(())
-    })();
+    let result: DenoResult<()> = match data.as_bytes() {
+        Some(bytes) => write_file(&path, bytes, &options.unwrap_or_default()),
+        None => Err(DenoError::Other("Buffer is detached".to_string())),
+    };
     result.into()
 }
 
+async fn fs_write_file(
+    path: String,
+    data: TypedArray<'_, u8>,
+    options: Option<WriteFileOptions>,
+) -> JsResult<()> {
+    let Some(data) = data.as_bytes().map(<[u8]>::to_vec) else {
+        return JsResult::from(Err::<(), _>(DenoError::Other(
+            "Buffer is detached".to_string(),
+        )));
+    };
+    let options = options.unwrap_or_default();
+    run_blocking(move || write_file(&path, &data, &options))
+        .await
+        .into()
+}
+
 fn fs_write_text_file_sync(
     path: String,
     text: String,
     options: Option<WriteFileOptions>,
 ) -> JsResult<()> {
-    use std::io::Write;
-    let result: DenoResult<()> = (|| {
-        let opts = options.unwrap_or_default();
+    write_file(&path, text.as_bytes(), &options.unwrap_or_default()).into()
+}
 
-        if opts.create_new && Path::new(&path).exists() {
-            return Err(DenoError::Other("File already exists".to_string()));
-        }
+async fn fs_write_text_file(
+    path: String,
+    text: String,
+    options: Option<WriteFileOptions>,
+) -> JsResult<()> {
+    let options = options.unwrap_or_default();
+    run_blocking(move || write_file(&path, text.as_bytes(), &options))
+        .await
+        .into()
+}
 
-        if opts.append {
-            let mut file = fs::OpenOptions::new()
-                .create(opts.create)
-                .append(true)
-                .open(&path)?;
-            file.write_all(text.as_bytes())?;
-        } else {
-            fs::write(&path, &text)?;
-        }
-        Ok(())
-    })();
-    result.into()
+fn stat(path: &str) -> DenoResult<FileInfo> {
+    check_read(path)?;
+    let metadata = fs::metadata(path)?;
+    Ok(build_file_info(&metadata))
 }
 
 fn fs_stat_sync(path: String) -> JsResult<FileInfo> {
-    let result: DenoResult<FileInfo> = (|| {
-        let metadata = fs::metadata(&path)?;
-        Ok(build_file_info(&metadata))
-    })();
-    result.into()
+    stat(&path).into()
+}
+
+async fn fs_stat(path: String) -> JsResult<FileInfo> {
+    run_blocking(move || stat(&path)).await.into()
+}
+
+fn mkdir(path: &str, options: &MkdirOptions) -> DenoResult<()> {
+    check_write(path)?;
+    if options.recursive {
+        fs::create_dir_all(path)?;
+    } else {
+        fs::create_dir(path)?;
+    }
+    Ok(())
 }
 
 fn fs_mkdir_sync(path: String, options: Option<MkdirOptions>) -> JsResult<()> {
-    let result: DenoResult<()> = (|| {
-        let opts = options.unwrap_or_default();
+    mkdir(&path, &options.unwrap_or_default()).into()
+}
+
+async fn fs_mkdir(path: String, options: Option<MkdirOptions>) -> JsResult<()> {
+    let options = options.unwrap_or_default();
+    run_blocking(move || mkdir(&path, &options)).await.into()
+}
 
-        if opts.recursive {
-            fs::create_dir_all(&path)?;
+fn remove(path: &str, options: &RemoveOptions) -> DenoResult<()> {
+    check_write(path)?;
+
+    let path_obj = Path::new(path);
+    if !path_obj.exists() {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Path not found").into());
+    }
+
+    if path_obj.is_dir() {
+        if options.recursive {
+            fs::remove_dir_all(path)?;
         } else {
-            fs::create_dir(&path)?;
+            fs::remove_dir(path)?;
         }
-        Ok(())
-    })();
-    result.into()
+    } else {
+        fs::remove_file(path)?;
+    }
+    Ok(())
 }
 
 fn fs_remove_sync(path: String, options: Option<RemoveOptions>) -> JsResult<()> {
-    let result: DenoResult<()> = (|| {
-        let opts = options.unwrap_or_default();
+    remove(&path, &options.unwrap_or_default()).into()
+}
 
-        let path_obj = Path::new(&path);
-        if !path_obj.exists() {
-            return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Path not found").into());
-        }
+async fn fs_remove(path: String, options: Option<RemoveOptions>) -> JsResult<()> {
+    let options = options.unwrap_or_default();
+    run_blocking(move || remove(&path, &options)).await.into()
+}
 
-        if path_obj.is_dir() {
-            if opts.recursive {
-                fs::remove_dir_all(&path)?;
-            } else {
-                fs::remove_dir(&path)?;
-            }
-        } else {
-            fs::remove_file(&path)?;
-        }
-        Ok(())
-    })();
-    result.into()
+fn copy_file(from: &str, to: &str) -> DenoResult<()> {
+    check_read(from)?;
+    check_write(to)?;
+    fs::copy(from, to)?;
+    Ok(())
 }
 
 fn fs_copy_file_sync(from: String, to: String) -> JsResult<()> {
-    let result: DenoResult<()> = (|| {
-        fs::copy(&from, &to)?;
-        Ok(())
-    })();
-    result.into()
+    copy_file(&from, &to).into()
+}
+
+async fn fs_copy_file(from: String, to: String) -> JsResult<()> {
+    run_blocking(move || copy_file(&from, &to)).await.into()
+}
+
+fn lstat(path: &str) -> DenoResult<FileInfo> {
+    check_read(path)?;
+    let metadata = fs::symlink_metadata(path)?;
+    Ok(build_file_info(&metadata))
 }
 
 fn fs_lstat_sync(path: String) -> JsResult<FileInfo> {
-    let result: DenoResult<FileInfo> = (|| {
-        let metadata = fs::symlink_metadata(&path)?;
-        Ok(build_file_info(&metadata))
-    })();
-    result.into()
+    lstat(&path).into()
+}
+
+async fn fs_lstat(path: String) -> JsResult<FileInfo> {
+    run_blocking(move || lstat(&path)).await.into()
+}
+
+fn read_dir(path: &str) -> DenoResult<Vec<DirEntry>> {
+    check_read(path)?;
+    let entries = fs::read_dir(path)?;
+    let mut dir_entries = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let name = entry
+            .file_name()
+            .into_string()
+            .map_err(|_| DenoError::Other("Invalid filename".to_string()))?;
+        dir_entries.push(DirEntry {
+            name,
+            is_file: file_type.is_file(),
+            is_directory: file_type.is_dir(),
+            is_symlink: file_type.is_symlink(),
+        });
+    }
+    Ok(dir_entries)
 }
 
 fn fs_read_dir_sync(path: String) -> JsResult<Vec<DirEntry>> {
-    let result: DenoResult<Vec<DirEntry>> = (|| {
-        let entries = fs::read_dir(&path)?;
-        let mut dir_entries = Vec::new();
-        for entry in entries {
-            let entry = entry?;
-            let file_type = entry.file_type()?;
-            let name = entry
-                .file_name()
-                .into_string()
-                .map_err(|_| DenoError::Other("Invalid filename".to_string()))?;
-            dir_entries.push(DirEntry {
-                name,
-                is_file: file_type.is_file(),
-                is_directory: file_type.is_dir(),
-                is_symlink: file_type.is_symlink(),
-            });
-        }
-        Ok(dir_entries)
-    })();
-    result.into()
+    read_dir(&path).into()
+}
+
+async fn fs_read_dir(path: String) -> JsResult<Vec<DirEntry>> {
+    run_blocking(move || read_dir(&path)).await.into()
+}
+
+fn rename(oldpath: &str, newpath: &str) -> DenoResult<()> {
+    check_read(oldpath)?;
+    check_write(oldpath)?;
+    check_write(newpath)?;
+    fs::rename(oldpath, newpath)?;
+    Ok(())
 }
 
 fn fs_rename_sync(oldpath: String, newpath: String) -> JsResult<()> {
+    rename(&oldpath, &newpath).into()
+}
+
+async fn fs_rename(oldpath: String, newpath: String) -> JsResult<()> {
+    run_blocking(move || rename(&oldpath, &newpath))
+        .await
+        .into()
+}
+
+fn fs_link_sync(oldpath: String, newpath: String) -> JsResult<()> {
     let result: DenoResult<()> = (|| {
-        fs::rename(&oldpath, &newpath)?;
+        link_permissions(&oldpath, &newpath)?;
+        fs::hard_link(&oldpath, &newpath)?;
         Ok(())
     })();
     result.into()
 }
 
+async fn fs_link(oldpath: String, newpath: String) -> JsResult<()> {
+    let result: DenoResult<()> = match link_permissions(&oldpath, &newpath) {
+        Ok(()) => compio::fs::hard_link(&oldpath, &newpath)
+            .await
+            .map_err(DenoError::from),
+        Err(error) => Err(error),
+    };
+    result.into()
+}
+
+fn symlink(oldpath: &str, newpath: &str, kind: Option<&str>) -> DenoResult<()> {
+    link_permissions(oldpath, newpath)?;
+    #[cfg(unix)]
+    {
+        // The type only matters on Windows
+        let _ = kind;
+        std::os::unix::fs::symlink(oldpath, newpath)?;
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::{symlink_dir, symlink_file};
+        // Without a type, follow the target like Deno does
+        let is_dir = match kind {
+            Some("dir" | "junction") => true,
+            Some(_) => false,
+            None => Path::new(newpath)
+                .parent()
+                .map_or_else(|| PathBuf::from(oldpath), |parent| parent.join(oldpath))
+                .is_dir(),
+        };
+        if is_dir {
+            symlink_dir(oldpath, newpath)?;
+        } else {
+            symlink_file(oldpath, newpath)?;
+        }
+    }
+    Ok(())
+}
+
+fn fs_symlink_sync(oldpath: String, newpath: String, kind: Option<String>) -> JsResult<()> {
+    symlink(&oldpath, &newpath, kind.as_deref()).into()
+}
+
+async fn fs_symlink(oldpath: String, newpath: String, kind: Option<String>) -> JsResult<()> {
+    run_blocking(move || symlink(&oldpath, &newpath, kind.as_deref()))
+        .await
+        .into()
+}
+
+fn read_link(path: &str) -> DenoResult<String> {
+    check_read(path)?;
+    Ok(fs::read_link(path)?.to_string_lossy().into_owned())
+}
+
+fn fs_read_link_sync(path: String) -> JsResult<String> {
+    read_link(&path).into()
+}
+
+async fn fs_read_link(path: String) -> JsResult<String> {
+    run_blocking(move || read_link(&path)).await.into()
+}
+
 fn fs_real_path_sync(path: String) -> JsResult<String> {
     let result: DenoResult<String> = (|| {
-        let canonical_path = fs::canonicalize(&path)?;
-        Ok(canonical_path.to_string_lossy().to_string())
+        check_read(&path)?;
+        Ok(to_file_url(&fs::canonicalize(&path)?))
     })();
     result.into()
 }
 
-fn fs_truncate_sync(path: String, len: Option<u64>) -> JsResult<()> {
-    let result: DenoResult<()> = (|| {
-        let file = fs::OpenOptions::new().write(true).open(&path)?;
-        let new_len = len.unwrap_or(0);
-        file.set_len(new_len)?;
-        Ok(())
+async fn fs_real_path(path: String) -> JsResult<String> {
+    run_blocking(move || {
+        check_read(&path)?;
+        Ok(to_file_url(&fs::canonicalize(&path)?))
+    })
+    .await
+    .into()
+}
+
+fn fs_canonicalize_sync(path: String) -> JsResult<String> {
+    let result: DenoResult<String> = (|| {
+        check_read(&path)?;
+        let canonical_path = strip_unc_prefix(fs::canonicalize(&path)?);
+        Ok(canonical_path.to_string_lossy().to_string())
     })();
     result.into()
 }
 
-fn fs_make_temp_dir_sync(options: Option<MakeTempOptions>) -> JsResult<String> {
-    let result: DenoResult<String> = (|| {
-        let opts = options.unwrap_or_default();
+fn truncate(path: &str, len: Option<u64>) -> DenoResult<()> {
+    check_write(path)?;
+    let file = fs::OpenOptions::new().write(true).open(path)?;
+    file.set_len(len.unwrap_or(0))?;
+    Ok(())
+}
 
-        let prefix = opts.prefix.as_deref().unwrap_or("tmp");
+fn fs_truncate_sync(path: String, len: Option<u64>) -> JsResult<()> {
+    truncate(&path, len).into()
+}
 
-        let temp_dir = if let Some(base_dir) = opts.dir.as_deref() {
-            tempfile::Builder::new()
-                .prefix(prefix)
-                .tempdir_in(base_dir)?
-        } else {
-            tempfile::Builder::new().prefix(prefix).tempdir()?
-        };
+async fn fs_truncate(path: String, len: Option<u64>) -> JsResult<()> {
+    run_blocking(move || truncate(&path, len)).await.into()
+}
 
-        let path = temp_dir.path().to_string_lossy().to_string();
-        // Leak the TempDir to keep it alive (it won't be deleted)
-        std::mem::forget(temp_dir);
-        Ok(path)
-    })();
-    result.into()
+fn chmod(path: &str, mode: u32) -> DenoResult<()> {
+    check_write(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o7777))?;
+    }
+    #[cfg(not(unix))]
+    {
+        // Only the read-only bit exists here; it is set when no one may write
+        let mut permissions = fs::metadata(path)?.permissions();
+        permissions.set_readonly(mode & 0o222 == 0);
+        fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
 }
 
-fn fs_make_temp_file_sync(options: Option<MakeTempOptions>) -> JsResult<String> {
-    let result: DenoResult<String> = (|| {
-        let opts = options.unwrap_or_default();
+fn fs_chmod_sync(path: String, mode: u32) -> JsResult<()> {
+    chmod(&path, mode).into()
+}
 
-        let prefix = opts.prefix.as_deref().unwrap_or("tmp");
-        let suffix = opts.suffix.as_deref().unwrap_or("");
+async fn fs_chmod(path: String, mode: u32) -> JsResult<()> {
+    run_blocking(move || chmod(&path, mode)).await.into()
+}
 
-        let temp_file = if let Some(base_dir) = opts.dir.as_deref() {
-            tempfile::Builder::new()
-                .prefix(prefix)
-                .suffix(suffix)
-                .tempfile_in(base_dir)?
-        } else {
-            tempfile::Builder::new()
-                .prefix(prefix)
-                .suffix(suffix)
-                .tempfile()?
-        };
+fn chown(path: &str, uid: Option<u32>, gid: Option<u32>) -> DenoResult<()> {
+    check_write(path)?;
+    #[cfg(unix)]
+    std::os::unix::fs::chown(path, uid, gid)?;
+    // Windows has no numeric owners, so there is nothing to change
+    #[cfg(not(unix))]
+    let _ = (uid, gid);
+    Ok(())
+}
 
-        let path = temp_file.path().to_string_lossy().to_string();
-        // Leak the NamedTempFile to keep it alive (it won't be deleted)
-        std::mem::forget(temp_file);
-        Ok(path)
-    })();
-    result.into()
+fn fs_chown_sync(path: String, uid: Option<u32>, gid: Option<u32>) -> JsResult<()> {
+    chown(&path, uid, gid).into()
+}
+
+async fn fs_chown(path: String, uid: Option<u32>, gid: Option<u32>) -> JsResult<()> {
+    run_blocking(move || chown(&path, uid, gid)).await.into()
+}
+
+/// Convert a Unix timestamp in seconds to a `FileTime`
+pub(crate) fn file_time(seconds: f64) -> FileTime {
+    let whole = seconds.floor();
+    FileTime::from_unix_time(whole as i64, ((seconds - whole) * 1e9) as u32)
+}
+
+/// Set the access and modification times of `path`, leaving `None` unchanged
+fn utime(path: &str, atime: Option<f64>, mtime: Option<f64>) -> DenoResult<()> {
+    check_write(path)?;
+    match (atime.map(file_time), mtime.map(file_time)) {
+        (Some(atime), Some(mtime)) => filetime::set_file_times(path, atime, mtime)?,
+        (Some(atime), None) => filetime::set_file_atime(path, atime)?,
+        (None, Some(mtime)) => filetime::set_file_mtime(path, mtime)?,
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+fn fs_utime_sync(path: String, atime: Option<f64>, mtime: Option<f64>) -> JsResult<()> {
+    utime(&path, atime, mtime).into()
+}
+
+async fn fs_utime(path: String, atime: Option<f64>, mtime: Option<f64>) -> JsResult<()> {
+    run_blocking(move || utime(&path, atime, mtime))
+        .await
+        .into()
+}
+
+/// Fail with `PermissionDenied` unless `path` may be read
+pub(crate) fn check_read(path: &str) -> DenoResult<()> {
+    deno_permissions::check("read", path)
+}
+
+/// Fail with `PermissionDenied` unless `path` may be written
+pub(crate) fn check_write(path: &str) -> DenoResult<()> {
+    deno_permissions::check("write", path)
+}
+
+/// A link, hard or symbolic, exposes `oldpath` through `newpath`, so it needs both
+fn link_permissions(oldpath: &str, newpath: &str) -> DenoResult<()> {
+    check_read(oldpath)?;
+    check_write(oldpath)?;
+    check_read(newpath)?;
+    check_write(newpath)
+}
+
+/// Run a blocking file system operation on compio's thread pool. Permission
+/// scopes are per thread, so the caller's scope is carried over for the
+/// permission checks made there.
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> DenoResult<T> + Send + 'static,
+) -> DenoResult<T> {
+    let scope = deno_permissions::current_scope();
+    compio::runtime::spawn_blocking(move || deno_permissions::with_scope(scope, f))
+        .await
+        .unwrap_or_else(|_| Err(DenoError::Other("File system operation panicked".into())))
+}
+
+fn make_temp_dir(options: &MakeTempOptions) -> DenoResult<String> {
+    let prefix = options.prefix.as_deref().unwrap_or("tmp");
+
+    let base_dir = options
+        .dir
+        .as_deref()
+        .map_or_else(env::temp_dir, PathBuf::from);
+    check_write(&base_dir.to_string_lossy())?;
+    let temp_dir = if let Some(base_dir) = options.dir.as_deref() {
+        tempfile::Builder::new()
+            .prefix(prefix)
+            .tempdir_in(base_dir)?
+    } else {
+        tempfile::Builder::new().prefix(prefix).tempdir()?
+    };
+
+    let path = temp_dir.path().to_string_lossy().to_string();
+    // Leak the TempDir to keep it alive (it won't be deleted)
+    std::mem::forget(temp_dir);
+    Ok(path)
+}
+
+fn fs_make_temp_dir_sync(options: Option<MakeTempOptions>) -> JsResult<String> {
+    make_temp_dir(&options.unwrap_or_default()).into()
+}
+
+async fn fs_make_temp_dir(options: Option<MakeTempOptions>) -> JsResult<String> {
+    let options = options.unwrap_or_default();
+    run_blocking(move || make_temp_dir(&options)).await.into()
+}
+
+fn make_temp_file(options: &MakeTempOptions) -> DenoResult<String> {
+    let prefix = options.prefix.as_deref().unwrap_or("tmp");
+    let suffix = options.suffix.as_deref().unwrap_or("");
+
+    let base_dir = options
+        .dir
+        .as_deref()
+        .map_or_else(env::temp_dir, PathBuf::from);
+    check_write(&base_dir.to_string_lossy())?;
+    let temp_file = if let Some(base_dir) = options.dir.as_deref() {
+        tempfile::Builder::new()
+            .prefix(prefix)
+            .suffix(suffix)
+            .tempfile_in(base_dir)?
+    } else {
+        tempfile::Builder::new()
+            .prefix(prefix)
+            .suffix(suffix)
+            .tempfile()?
+    };
+
+    let path = temp_file.path().to_string_lossy().to_string();
+    // Leak the NamedTempFile to keep it alive (it won't be deleted)
+    std::mem::forget(temp_file);
+    Ok(path)
+}
+
+fn fs_make_temp_file_sync(options: Option<MakeTempOptions>) -> JsResult<String> {
+    make_temp_file(&options.unwrap_or_default()).into()
+}
+
+async fn fs_make_temp_file(options: Option<MakeTempOptions>) -> JsResult<String> {
+    let options = options.unwrap_or_default();
+    run_blocking(move || make_temp_file(&options)).await.into()
 }
 
 fn setup_internal(ctx: &Ctx) -> Result<(), Box<dyn std::error::Error>> {
@@ -392,51 +694,207 @@ fn setup_internal(ctx: &Ctx) -> Result<(), Box<dyn std::error::Error>> {
     // cwd(): string - Get current working directory
     add_internal_function!(ctx, "fs.cwd", fs_cwd);
 
+    // chdir(directory: string): void
+    add_internal_function!(ctx, "fs.chdir", fs_chdir);
+
     // readFileSync(path: string | URL): Uint8Array
     add_internal_function!(ctx, "fs.readFileSync", fs_read_file_sync);
 
+    // readFile(path: string | URL): Promise<Uint8Array>
+    add_internal_function!(ctx, "fs.readFile", Async(fs_read_file));
+
     // readTextFileSync(path: string | URL): string
     add_internal_function!(ctx, "fs.readTextFileSync", fs_read_text_file_sync);
 
+    // readTextFile(path: string | URL): Promise<string>
+    add_internal_function!(ctx, "fs.readTextFile", Async(fs_read_text_file));
+
     // writeFileSync(path: string | URL, data: Uint8Array, options?: WriteFileOptions): void
     add_internal_function!(ctx, "fs.writeFileSync", fs_write_file_sync);
 
+    // writeFile(path: string | URL, data: Uint8Array, options?: WriteFileOptions): Promise<void>
+    add_internal_function!(ctx, "fs.writeFile", Async(fs_write_file));
+
     // writeTextFileSync(path: string | URL, text: string, options?: WriteFileOptions): void
     add_internal_function!(ctx, "fs.writeTextFileSync", fs_write_text_file_sync);
 
+    // writeTextFile(path: string | URL, text: string, options?: WriteFileOptions): Promise<void>
+    add_internal_function!(ctx, "fs.writeTextFile", Async(fs_write_text_file));
+
     // statSync(path: string | URL): FileInfo
     add_internal_function!(ctx, "fs.statSync", fs_stat_sync);
 
+    // stat(path: string | URL): Promise<FileInfo>
+    add_internal_function!(ctx, "fs.stat", Async(fs_stat));
+
     // mkdirSync(path: string | URL, options?: MkdirOptions): void
     add_internal_function!(ctx, "fs.mkdirSync", fs_mkdir_sync);
 
+    // mkdir(path: string | URL, options?: MkdirOptions): Promise<void>
+    add_internal_function!(ctx, "fs.mkdir", Async(fs_mkdir));
+
     // removeSync(path: string | URL, options?: RemoveOptions): void
     add_internal_function!(ctx, "fs.removeSync", fs_remove_sync);
 
+    // remove(path: string | URL, options?: RemoveOptions): Promise<void>
+    add_internal_function!(ctx, "fs.remove", Async(fs_remove));
+
     // copyFileSync(fromPath: string | URL, toPath: string | URL): void
     add_internal_function!(ctx, "fs.copyFileSync", fs_copy_file_sync);
 
+    // copyFile(fromPath: string | URL, toPath: string | URL): Promise<void>
+    add_internal_function!(ctx, "fs.copyFile", Async(fs_copy_file));
+
     // lstatSync(path: string | URL): FileInfo
     add_internal_function!(ctx, "fs.lstatSync", fs_lstat_sync);
 
-    // readDirSync(path: string | URL): Iterable<DirEntry>
+    // lstat(path: string | URL): Promise<FileInfo>
+    add_internal_function!(ctx, "fs.lstat", Async(fs_lstat));
+
+    // readDirSync(path: string): DirEntry[]
     add_internal_function!(ctx, "fs.readDirSync", fs_read_dir_sync);
 
+    // readDir(path: string): Promise<DirEntry[]>
+    add_internal_function!(ctx, "fs.readDir", Async(fs_read_dir));
+
     // renameSync(oldpath: string | URL, newpath: string | URL): void
     add_internal_function!(ctx, "fs.renameSync", fs_rename_sync);
 
-    // realPathSync(path: string): string
+    // rename(oldpath: string | URL, newpath: string | URL): Promise<void>
+    add_internal_function!(ctx, "fs.rename", Async(fs_rename));
+
+    // linkSync(oldpath: string, newpath: string): void
+    add_internal_function!(ctx, "fs.linkSync", fs_link_sync);
+
+    // link(oldpath: string, newpath: string): Promise<void>
+    add_internal_function!(ctx, "fs.link", Async(fs_link));
+
+    // symlinkSync(oldpath: string, newpath: string, type?: "file" | "dir" | "junction"): void
+    add_internal_function!(ctx, "fs.symlinkSync", fs_symlink_sync);
+
+    // symlink(oldpath: string, newpath: string, type?: "file" | "dir" | "junction"): Promise<void>
+    add_internal_function!(ctx, "fs.symlink", Async(fs_symlink));
+
+    // readLinkSync(path: string): string
+    add_internal_function!(ctx, "fs.readLinkSync", fs_read_link_sync);
+
+    // readLink(path: string): Promise<string>
+    add_internal_function!(ctx, "fs.readLink", Async(fs_read_link));
+
+    // realPathSync(path: string): string (file:// URL)
     add_internal_function!(ctx, "fs.realPathSync", fs_real_path_sync);
 
+    // realPath(path: string): Promise<string> (file:// URL)
+    add_internal_function!(ctx, "fs.realPath", Async(fs_real_path));
+
+    // canonicalizeSync(path: string): string (native path)
+    add_internal_function!(ctx, "fs.canonicalizeSync", fs_canonicalize_sync);
+
     // truncateSync(path: string, len?: number): void
     add_internal_function!(ctx, "fs.truncateSync", fs_truncate_sync);
 
+    // truncate(path: string, len?: number): Promise<void>
+    add_internal_function!(ctx, "fs.truncate", Async(fs_truncate));
+
+    // chmodSync(path: string, mode: number): void
+    add_internal_function!(ctx, "fs.chmodSync", fs_chmod_sync);
+
+    // chmod(path: string, mode: number): Promise<void>
+    add_internal_function!(ctx, "fs.chmod", Async(fs_chmod));
+
+    // chownSync(path: string, uid: number | null, gid: number | null): void
+    add_internal_function!(ctx, "fs.chownSync", fs_chown_sync);
+
+    // chown(path: string, uid: number | null, gid: number | null): Promise<void>
+    add_internal_function!(ctx, "fs.chown", Async(fs_chown));
+
+    // utimeSync(path: string, atime: number | null, mtime: number | null): void
+    add_internal_function!(ctx, "fs.utimeSync", fs_utime_sync);
+
+    // utime(path: string, atime: number | null, mtime: number | null): Promise<void>
+    add_internal_function!(ctx, "fs.utime", Async(fs_utime));
+
+    // openSync(path: string, options?: OpenOptions): number (rid)
+    add_internal_function!(ctx, "fs.openSync", file::fs_open_sync);
+
+    // open(path: string, options?: OpenOptions): Promise<number>
+    add_internal_function!(ctx, "fs.open", Async(file::fs_open));
+
+    // fileReadSync(rid: number, len: number): Uint8Array | null
+    add_internal_function!(ctx, "fs.fileReadSync", file::fs_file_read_sync);
+
+    // fileRead(rid: number, len: number): Promise<Uint8Array | null>
+    add_internal_function!(ctx, "fs.fileRead", Async(file::fs_file_read));
+
+    // fileWriteSync(rid: number, data: Uint8Array): number
+    add_internal_function!(ctx, "fs.fileWriteSync", file::fs_file_write_sync);
+
+    // fileWrite(rid: number, data: Uint8Array): Promise<number>
+    add_internal_function!(ctx, "fs.fileWrite", Async(file::fs_file_write));
+
+    // fileSeekSync(rid: number, offset: number, whence: number): number
+    add_internal_function!(ctx, "fs.fileSeekSync", file::fs_file_seek_sync);
+
+    // fileSeek(rid: number, offset: number, whence: number): Promise<number>
+    add_internal_function!(ctx, "fs.fileSeek", Async(file::fs_file_seek));
+
+    // fileTruncateSync(rid: number, len?: number): void
+    add_internal_function!(ctx, "fs.fileTruncateSync", file::fs_file_truncate_sync);
+
+    // fileTruncate(rid: number, len?: number): Promise<void>
+    add_internal_function!(ctx, "fs.fileTruncate", Async(file::fs_file_truncate));
+
+    // fileSyncSync(rid: number): void
+    add_internal_function!(ctx, "fs.fileSyncSync", file::fs_file_sync_sync);
+
+    // fileSync(rid: number): Promise<void>
+    add_internal_function!(ctx, "fs.fileSync", Async(file::fs_file_sync));
+
+    // fileSyncDataSync(rid: number): void
+    add_internal_function!(ctx, "fs.fileSyncDataSync", file::fs_file_sync_data_sync);
+
+    // fileSyncData(rid: number): Promise<void>
+    add_internal_function!(ctx, "fs.fileSyncData", Async(file::fs_file_sync_data));
+
+    // fileFlushSync(rid: number): void
+    add_internal_function!(ctx, "fs.fileFlushSync", file::fs_file_flush_sync);
+
+    // fileStatSync(rid: number): FileInfo
+    add_internal_function!(ctx, "fs.fileStatSync", file::fs_file_stat_sync);
+
+    // fileStat(rid: number): Promise<FileInfo>
+    add_internal_function!(ctx, "fs.fileStat", Async(file::fs_file_stat));
+
+    // fileUtimeSync(rid: number, atime: number | null, mtime: number | null): void
+    add_internal_function!(ctx, "fs.fileUtimeSync", file::fs_file_utime_sync);
+
+    // fileUtime(rid: number, atime: number | null, mtime: number | null): Promise<void>
+    add_internal_function!(ctx, "fs.fileUtime", Async(file::fs_file_utime));
+
+    // fileClose(rid: number): void
+    add_internal_function!(ctx, "fs.fileClose", file::fs_file_close);
+
+    // watch(paths: string[], recursive: boolean): number
+    add_internal_function!(ctx, "fs.watch", watch::fs_watch);
+
+    // watchNext(rid: number): Promise<FsEvent | null>
+    add_internal_function!(ctx, "fs.watchNext", Async(watch::fs_watch_next));
+
+    // watchClose(rid: number): void
+    add_internal_function!(ctx, "fs.watchClose", watch::fs_watch_close);
+
     // makeTempDirSync(options?: MakeTempOptions): string
     add_internal_function!(ctx, "fs.makeTempDirSync", fs_make_temp_dir_sync);
 
+    // makeTempDir(options?: MakeTempOptions): Promise<string>
+    add_internal_function!(ctx, "fs.makeTempDir", Async(fs_make_temp_dir));
+
     // makeTempFileSync(options?: MakeTempOptions): string
     add_internal_function!(ctx, "fs.makeTempFileSync", fs_make_temp_file_sync);
 
+    // makeTempFile(options?: MakeTempOptions): Promise<string>
+    add_internal_function!(ctx, "fs.makeTempFile", Async(fs_make_temp_file));
+
     Ok(())
 }
 
@@ -0,0 +1,129 @@
+// File system watchers for Deno.watchFs, addressed by resource ID
+use crate::check_read;
+use futures_channel::mpsc::{UnboundedReceiver, unbounded};
+use futures_util::StreamExt;
+use futures_util::lock::Mutex;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rquickjs::{IntoJs, Object};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+use utils::{DenoError, DenoResult, JsResult};
+
+/// A change reported to `Deno.FsWatcher`
+pub(crate) struct FsEvent {
+    kind: &'static str,
+    paths: Vec<String>,
+}
+
+impl From<Event> for FsEvent {
+    fn from(event: Event) -> Self {
+        let kind = match event.kind {
+            EventKind::Access(_) => "access",
+            EventKind::Create(_) => "create",
+            EventKind::Modify(_) => "modify",
+            EventKind::Remove(_) => "remove",
+            EventKind::Any | EventKind::Other => "any",
+        };
+        Self {
+            kind,
+            paths: event
+                .paths
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+        }
+    }
+}
+
+impl<'js> IntoJs<'js> for FsEvent {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let obj = Object::new(ctx.clone())?;
+        obj.set("kind", self.kind)?;
+        obj.set("paths", self.paths)?;
+        Ok(obj.into_value())
+    }
+}
+
+struct WatchResource {
+    /// Dropping the watcher stops it, which ends `events`
+    watcher: RefCell<Option<RecommendedWatcher>>,
+    /// Events from notify's thread, buffered until JS asks for them
+    events: Mutex<UnboundedReceiver<notify::Result<Event>>>,
+}
+
+thread_local! {
+    // Like child processes, watchers belong to the thread whose runtime
+    // awaits their events
+    static RESOURCES: RefCell<HashMap<u32, Rc<WatchResource>>> = RefCell::new(HashMap::new());
+    static NEXT_RID: Cell<u32> = const { Cell::new(0) };
+}
+
+fn watch_error(error: notify::Error) -> DenoError {
+    match error.kind {
+        notify::ErrorKind::Io(error) => DenoError::Io(error),
+        notify::ErrorKind::PathNotFound => {
+            DenoError::Io(std::io::Error::from(std::io::ErrorKind::NotFound))
+        }
+        _ => DenoError::Other(error.to_string()),
+    }
+}
+
+fn watch(paths: &[String], recursive: bool) -> DenoResult<u32> {
+    for path in paths {
+        check_read(path)?;
+    }
+    let (sender, receiver) = unbounded();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        // The receiver is gone once the watcher is closed
+        let _ = sender.unbounded_send(event);
+    })
+    .map_err(watch_error)?;
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    for path in paths {
+        // Absolute, so events report absolute paths as in Deno
+        let path = std::path::absolute(Path::new(path))?;
+        watcher.watch(&path, mode).map_err(watch_error)?;
+    }
+
+    let rid = NEXT_RID.replace(NEXT_RID.get() + 1);
+    let resource = WatchResource {
+        watcher: RefCell::new(Some(watcher)),
+        events: Mutex::new(receiver),
+    };
+    RESOURCES.with_borrow_mut(|resources| resources.insert(rid, Rc::new(resource)));
+    Ok(rid)
+}
+
+pub(crate) fn fs_watch(paths: Vec<String>, recursive: bool) -> JsResult<u32> {
+    watch(&paths, recursive).into()
+}
+
+/// The next event, or `None` once the watcher is closed
+async fn watch_next(rid: u32) -> DenoResult<Option<FsEvent>> {
+    let Some(resource) = RESOURCES.with_borrow(|resources| resources.get(&rid).cloned()) else {
+        return Ok(None);
+    };
+    let mut events = resource.events.lock().await;
+    match events.next().await {
+        Some(Ok(event)) => Ok(Some(event.into())),
+        Some(Err(error)) => Err(watch_error(error)),
+        None => Ok(None),
+    }
+}
+
+pub(crate) async fn fs_watch_next(rid: u32) -> JsResult<Option<FsEvent>> {
+    watch_next(rid).await.into()
+}
+
+pub(crate) fn fs_watch_close(rid: u32) {
+    let resource = RESOURCES.with_borrow_mut(|resources| resources.remove(&rid));
+    if let Some(resource) = resource {
+        resource.watcher.borrow_mut().take();
+    }
+}
@@ -0,0 +1,49 @@
+// Deno.watchFs E2E tests
+
+Deno.test("watchFs reports a created file", async () => {
+  const dir = Deno.makeTempDirSync();
+  try {
+    const watcher = Deno.watchFs(dir);
+    const path = `${dir}/created.txt`;
+    setTimeout(() => Deno.writeTextFileSync(path, "hello"), 50);
+    let found = false;
+    for await (const event of watcher) {
+      if (event.kind === "create" && event.paths.includes(path)) {
+        found = true;
+        break;
+      }
+    }
+    if (!found) throw new Error("No create event");
+  } finally {
+    Deno.removeSync(dir, { recursive: true });
+  }
+});
+
+Deno.test("watchFs close ends a pending iteration", async () => {
+  const dir = Deno.makeTempDirSync();
+  try {
+    const watcher = Deno.watchFs([dir], { recursive: false });
+    const pending = watcher.next();
+    watcher.close();
+    const result = await pending;
+    if (!result.done) throw new Error("Expected done after close");
+    if (!(await watcher.next()).done) throw new Error("Expected done");
+  } finally {
+    Deno.removeSync(dir, { recursive: true });
+  }
+});
+
+Deno.test("watchFs rejects empty and missing paths", () => {
+  try {
+    Deno.watchFs([]);
+    throw new Error("should throw");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+  try {
+    Deno.watchFs("/mdeno-no-such-directory");
+    throw new Error("should throw");
+  } catch (error) {
+    if (!(error instanceof Deno.errors.NotFound)) throw error;
+  }
+});
@@ -0,0 +1,279 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+// Register Deno KV APIs under __mdeno__.kv
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+type KvKeyPart = Uint8Array | string | number | boolean;
+type KvKey = readonly KvKeyPart[];
+
+interface KvEntry {
+  key: KvKey;
+  value: unknown;
+  versionstamp: string;
+}
+
+interface KvEntryMaybe {
+  key: KvKey;
+  value: unknown;
+  versionstamp: string | null;
+}
+
+interface KvListSelector {
+  prefix?: KvKey;
+  start?: KvKey;
+  end?: KvKey;
+}
+
+interface KvListOptions {
+  limit?: number;
+  cursor?: string;
+  reverse?: boolean;
+  batchSize?: number;
+}
+
+interface AtomicCheck {
+  key: KvKey;
+  versionstamp: string | null;
+}
+
+type KvMutation =
+  | { type: "set"; key: KvKey; value: unknown }
+  | { type: "delete"; key: KvKey };
+
+// Entries fetched at once while iterating a list without a smaller limit
+const DEFAULT_BATCH_SIZE = 100;
+
+function validateKey(key: unknown): KvKey {
+  if (!Array.isArray(key)) {
+    throw new TypeError("Key must be an array");
+  }
+  for (const part of key) {
+    if (
+      typeof part !== "string" && typeof part !== "number" &&
+      typeof part !== "boolean" && !(part instanceof Uint8Array)
+    ) {
+      throw new TypeError(
+        `Key parts must be strings, numbers, booleans or Uint8Arrays, got ${typeof part}`,
+      );
+    }
+  }
+  return key;
+}
+
+// Values are stored as JSON
+function serialize(value: unknown): string {
+  const json = JSON.stringify(value);
+  if (json === undefined) {
+    throw new TypeError("Value must be JSON-serializable");
+  }
+  return json;
+}
+
+function toEntry(
+  entry: { key: KvKey; value: string; versionstamp: string },
+): KvEntry {
+  return {
+    key: entry.key,
+    value: JSON.parse(entry.value),
+    versionstamp: entry.versionstamp,
+  };
+}
+
+function toMutation(mutation: KvMutation) {
+  validateKey(mutation.key);
+  switch (mutation.type) {
+    case "set":
+      return {
+        type: "set",
+        key: mutation.key,
+        value: serialize(mutation.value),
+      };
+    case "delete":
+      return { type: "delete", key: mutation.key };
+    default:
+      throw new TypeError(
+        `Unsupported mutation type: ${(mutation as { type: string }).type}`,
+      );
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.AtomicOperation
+class AtomicOperation {
+  #rid: number;
+  #checks: AtomicCheck[] = [];
+  #mutations: ReturnType<typeof toMutation>[] = [];
+
+  constructor(rid: number) {
+    this.#rid = rid;
+  }
+
+  check(...checks: AtomicCheck[]): this {
+    for (const { key, versionstamp } of checks) {
+      this.#checks.push({ key: validateKey(key), versionstamp });
+    }
+    return this;
+  }
+
+  mutate(...mutations: KvMutation[]): this {
+    for (const mutation of mutations) {
+      this.#mutations.push(toMutation(mutation));
+    }
+    return this;
+  }
+
+  set(key: KvKey, value: unknown): this {
+    return this.mutate({ type: "set", key, value });
+  }
+
+  delete(key: KvKey): this {
+    return this.mutate({ type: "delete", key });
+  }
+
+  commit(): Promise<{ ok: true; versionstamp: string } | { ok: false }> {
+    return new Promise((resolve) => {
+      const versionstamp = __internal.kv.commit(
+        this.#rid,
+        this.#checks,
+        this.#mutations,
+      );
+      resolve(
+        versionstamp === undefined ? { ok: false } : { ok: true, versionstamp },
+      );
+    });
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.KvListIterator
+class KvListIterator implements AsyncIterableIterator<KvEntry> {
+  #rid: number;
+  #selector: KvListSelector;
+  #cursor: string | undefined;
+  #remaining: number;
+  #reverse: boolean;
+  #batchSize: number;
+  #batch: { key: KvKey; value: string; versionstamp: string; cursor: string }[] =
+    [];
+  #exhausted = false;
+
+  constructor(rid: number, selector: KvListSelector, options: KvListOptions) {
+    this.#rid = rid;
+    this.#selector = selector;
+    this.#cursor = options.cursor;
+    this.#remaining = options.limit ?? Infinity;
+    this.#reverse = options.reverse ?? false;
+    this.#batchSize = options.batchSize ?? DEFAULT_BATCH_SIZE;
+  }
+
+  // Pass this to list() to continue after the last entry returned
+  get cursor(): string {
+    return this.#cursor ?? "";
+  }
+
+  next(): Promise<IteratorResult<KvEntry, undefined>> {
+    return new Promise((resolve) => {
+      if (this.#batch.length === 0 && !this.#exhausted && this.#remaining > 0) {
+        const limit = Math.min(this.#batchSize, this.#remaining);
+        this.#batch = __internal.kv.list(
+          this.#rid,
+          this.#selector,
+          this.#cursor,
+          limit,
+          this.#reverse,
+        );
+        this.#exhausted = this.#batch.length < limit;
+      }
+      const entry = this.#batch.shift();
+      if (entry === undefined || this.#remaining <= 0) {
+        resolve({ done: true, value: undefined });
+        return;
+      }
+      this.#remaining--;
+      this.#cursor = entry.cursor;
+      resolve({ done: false, value: toEntry(entry) });
+    });
+  }
+
+  [Symbol.asyncIterator](): this {
+    return this;
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.Kv
+class Kv {
+  #rid: number;
+
+  constructor(rid: number) {
+    this.#rid = rid;
+  }
+
+  get(key: KvKey): Promise<KvEntryMaybe> {
+    return new Promise((resolve) => {
+      const entry = __internal.kv.get(this.#rid, validateKey(key));
+      resolve(
+        entry === undefined
+          ? { key, value: null, versionstamp: null }
+          : toEntry(entry),
+      );
+    });
+  }
+
+  async getMany(keys: readonly KvKey[]): Promise<KvEntryMaybe[]> {
+    const entries = [];
+    for (const key of keys) {
+      entries.push(await this.get(key));
+    }
+    return entries;
+  }
+
+  set(
+    key: KvKey,
+    value: unknown,
+  ): Promise<{ ok: true; versionstamp: string }> {
+    return this.atomic().set(key, value).commit() as Promise<
+      { ok: true; versionstamp: string }
+    >;
+  }
+
+  async delete(key: KvKey): Promise<void> {
+    await this.atomic().delete(key).commit();
+  }
+
+  list(selector: KvListSelector, options: KvListOptions = {}): KvListIterator {
+    const { prefix, start, end } = selector;
+    if (prefix === undefined && (start === undefined || end === undefined)) {
+      throw new TypeError(
+        "Selector must specify either 'prefix' or both 'start' and 'end'",
+      );
+    }
+    for (const key of [prefix, start, end]) {
+      if (key !== undefined) validateKey(key);
+    }
+    return new KvListIterator(this.#rid, { prefix, start, end }, options);
+  }
+
+  atomic(): AtomicOperation {
+    return new AtomicOperation(this.#rid);
+  }
+
+  close(): void {
+    __internal.kv.close(this.#rid);
+  }
+
+  [Symbol.dispose](): void {
+    this.close();
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.openKv
+// Without a path (or with ":memory:") the database only lives in memory
+function openKv(path?: string): Promise<Kv> {
+  return new Promise((resolve) => resolve(new Kv(__internal.kv.open(path))));
+}
+
+// @ts-ignore: mdeno internal API
+Object.assign(globalThis.__mdeno__.kv, {
+  openKv,
+  Kv,
+  AtomicOperation,
+  KvListIterator,
+});
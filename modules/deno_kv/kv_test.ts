@@ -0,0 +1,164 @@
+Deno.test("Deno.Kv gets, sets and deletes entries", async () => {
+  const kv = await Deno.openKv();
+  const missing = await kv.get(["users", "alice"]);
+  if (missing.value !== null || missing.versionstamp !== null) {
+    throw new Error("missing entry");
+  }
+
+  const result = await kv.set(["users", "alice"], { age: 30, tags: ["a"] });
+  if (!result.ok || typeof result.versionstamp !== "string") {
+    throw new Error("set result");
+  }
+  const entry = await kv.get(["users", "alice"]);
+  if (JSON.stringify(entry.value) !== '{"age":30,"tags":["a"]}') {
+    throw new Error(`value: ${JSON.stringify(entry.value)}`);
+  }
+  if (entry.versionstamp !== result.versionstamp) {
+    throw new Error("versionstamp");
+  }
+
+  const next = await kv.set(["users", "alice"], 31);
+  if (next.versionstamp <= result.versionstamp) {
+    throw new Error("versionstamps increase");
+  }
+
+  await kv.delete(["users", "alice"]);
+  if ((await kv.get(["users", "alice"])).value !== null) {
+    throw new Error("deleted");
+  }
+  kv.close();
+});
+
+Deno.test("Deno.Kv lists entries in key order", async () => {
+  const kv = await Deno.openKv(":memory:");
+  const bytes = new Uint8Array([1, 2]);
+  for (
+    const key of [
+      ["a", 10],
+      ["a", 2],
+      ["a", "x"],
+      ["a", true],
+      ["a", bytes],
+      ["a"],
+      ["b", 1],
+    ]
+  ) {
+    await kv.set(key, key.length);
+  }
+
+  const keys = [];
+  for await (const entry of kv.list({ prefix: ["a"] })) {
+    keys.push(entry.key[1]);
+  }
+  if (!(keys[0] instanceof Uint8Array) || keys[0][1] !== 2) {
+    throw new Error(`bytes first: ${keys[0]}`);
+  }
+  if (keys.slice(1).join() !== "x,2,10,true") {
+    throw new Error(`keys: ${keys.slice(1)}`);
+  }
+
+  const reversed = [];
+  for await (const entry of kv.list({ prefix: ["a"] }, { reverse: true })) {
+    reversed.push(entry.key[1]);
+  }
+  if (reversed.slice(0, 2).join() !== "true,10") {
+    throw new Error(`reversed: ${reversed}`);
+  }
+
+  const range = [];
+  for await (const entry of kv.list({ start: ["a", 2], end: ["b", 1] })) {
+    range.push(entry.key.join("/"));
+  }
+  if (range.join() !== "a/2,a/10,a/true") {
+    throw new Error(`range: ${range}`);
+  }
+
+  try {
+    kv.list({ start: ["a"] });
+    throw new Error("should require an end");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+  kv.close();
+});
+
+Deno.test("Deno.Kv list continues from a cursor", async () => {
+  const kv = await Deno.openKv();
+  for (let i = 0; i < 5; i++) {
+    await kv.set(["n", i], i);
+  }
+
+  const first = kv.list({ prefix: ["n"] }, { limit: 2 });
+  const values = [];
+  for await (const entry of first) {
+    values.push(entry.value);
+  }
+  const rest = kv.list({ prefix: ["n"] }, {
+    cursor: first.cursor,
+    batchSize: 1,
+  });
+  for await (const entry of rest) {
+    values.push(entry.value);
+  }
+  if (values.join() !== "0,1,2,3,4") throw new Error(`values: ${values}`);
+  kv.close();
+});
+
+Deno.test("Deno.Kv atomic operations check versionstamps", async () => {
+  const kv = await Deno.openKv();
+  const { versionstamp } = await kv.set(["balance"], 100);
+
+  const transfer = await kv.atomic()
+    .check({ key: ["balance"], versionstamp })
+    .check({ key: ["log"], versionstamp: null })
+    .set(["balance"], 50)
+    .set(["log"], "transferred 50")
+    .commit();
+  if (!transfer.ok) throw new Error("first commit");
+
+  // The balance changed since the versionstamp was read
+  const stale = await kv.atomic()
+    .check({ key: ["balance"], versionstamp })
+    .delete(["balance"])
+    .commit();
+  if (stale.ok) throw new Error("stale commit should fail");
+  if ((await kv.get(["balance"])).value !== 50) {
+    throw new Error("failed commits change nothing");
+  }
+  kv.close();
+});
+
+Deno.test("Deno.openKv persists to a file", async () => {
+  const dir = Deno.makeTempDirSync();
+  const path = `${dir}/kv.sqlite3`;
+  const kv = await Deno.openKv(path);
+  await kv.set(["greeting"], "hello");
+  kv.close();
+
+  const reopened = await Deno.openKv(path);
+  if ((await reopened.get(["greeting"])).value !== "hello") {
+    throw new Error("persisted value");
+  }
+  reopened.close();
+  Deno.removeSync(dir, { recursive: true });
+});
+
+Deno.test("Deno.Kv rejects invalid keys and values", async () => {
+  const kv = await Deno.openKv();
+  for (const key of ["a", [{}], [null]]) {
+    try {
+      // @ts-ignore: testing invalid keys
+      await kv.set(key, 1);
+      throw new Error(`should reject ${JSON.stringify(key)}`);
+    } catch (error) {
+      if (!(error instanceof TypeError)) throw error;
+    }
+  }
+  try {
+    await kv.set(["fn"], () => {});
+    throw new Error("should reject functions");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+  kv.close();
+});
@@ -0,0 +1,703 @@
+// Deno KV: a key-value store persisted in SQLite, addressed by resource ID
+use rquickjs::{Ctx, FromJs, IntoJs, Module, Object, TypedArray, Value};
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params, params_from_iter};
+use std::cmp::Ordering;
+use std::fmt::Write;
+use std::ops::Bound;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use utils::{DenoError, DenoResult, JsResult, add_internal_function};
+use utils_macros::include_ts;
+
+/// Open databases indexed by resource ID; closed slots are reused
+static DATABASES: Mutex<Vec<Option<Connection>>> = Mutex::new(Vec::new());
+
+/// How long a write waits for another connection to release the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS kv (
+        key BLOB PRIMARY KEY,
+        value TEXT NOT NULL,
+        version INTEGER NOT NULL
+    ) WITHOUT ROWID;
+    CREATE TABLE IF NOT EXISTS kv_version (version INTEGER NOT NULL);
+    INSERT INTO kv_version SELECT 0 WHERE NOT EXISTS (SELECT 1 FROM kv_version);
+";
+
+/// One part of a key. Parts of different types order by type, in the order
+/// of the variants.
+#[derive(Debug, Clone)]
+pub enum KeyPart {
+    Bytes(Vec<u8>),
+    String(String),
+    Number(f64),
+    Boolean(bool),
+}
+
+impl<'js> FromJs<'js> for KeyPart {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        if let Some(string) = value.as_string() {
+            return Ok(Self::String(string.to_string()?));
+        }
+        if let Some(number) = value.as_number() {
+            return Ok(Self::Number(number));
+        }
+        if let Some(boolean) = value.as_bool() {
+            return Ok(Self::Boolean(boolean));
+        }
+        let type_name = value.type_name();
+        TypedArray::<u8>::from_js(ctx, value)
+            .ok()
+            .and_then(|bytes| bytes.as_bytes().map(<[u8]>::to_vec))
+            .map(Self::Bytes)
+            .ok_or_else(|| rquickjs::Error::new_from_js(type_name, "KvKeyPart"))
+    }
+}
+
+impl<'js> IntoJs<'js> for KeyPart {
+    fn into_js(self, ctx: &Ctx<'js>) -> rquickjs::Result<Value<'js>> {
+        match self {
+            Self::Bytes(bytes) => TypedArray::<u8>::new(ctx.clone(), bytes)?.into_js(ctx),
+            Self::String(string) => string.into_js(ctx),
+            Self::Number(number) => number.into_js(ctx),
+            Self::Boolean(boolean) => boolean.into_js(ctx),
+        }
+    }
+}
+
+// Type bytes that start each encoded key part, as in the FoundationDB tuple
+// layer. None is 0xff, so appending 0xff to a key gives a bound past every
+// key under it.
+const BYTES: u8 = 0x01;
+const STRING: u8 = 0x02;
+const NUMBER: u8 = 0x21;
+const FALSE: u8 = 0x26;
+const TRUE: u8 = 0x27;
+
+/// Serialize a key so that encoded keys compare bytewise in key order, which
+/// lets the database select ranges of keys. A key sorts right after its prefixes.
+fn encode_key(key: &[KeyPart]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for part in key {
+        match part {
+            KeyPart::Bytes(value) => {
+                bytes.push(BYTES);
+                escape_into(&mut bytes, value);
+            }
+            KeyPart::String(value) => {
+                bytes.push(STRING);
+                escape_into(&mut bytes, value.as_bytes());
+            }
+            KeyPart::Number(number) => {
+                // Flipping the sign bit of positive numbers and every bit of
+                // negative ones orders them like f64::total_cmp
+                let bits = number.to_bits();
+                let bits = if bits >> 63 == 0 {
+                    bits | 1 << 63
+                } else {
+                    !bits
+                };
+                bytes.push(NUMBER);
+                bytes.extend_from_slice(&bits.to_be_bytes());
+            }
+            KeyPart::Boolean(false) => bytes.push(FALSE),
+            KeyPart::Boolean(true) => bytes.push(TRUE),
+        }
+    }
+    bytes
+}
+
+/// Append `value` and a 0x00 terminator, escaping its own 0x00 bytes as
+/// 0x00 0xff so that shorter values still sort first
+fn escape_into(bytes: &mut Vec<u8>, value: &[u8]) {
+    for &byte in value {
+        bytes.push(byte);
+        if byte == 0 {
+            bytes.push(0xff);
+        }
+    }
+    bytes.push(0);
+}
+
+fn decode_key(mut bytes: &[u8]) -> DenoResult<Vec<KeyPart>> {
+    let invalid = || invalid_data("Invalid key in KV database");
+    let mut key = Vec::new();
+    while let Some((&tag, rest)) = bytes.split_first() {
+        bytes = rest;
+        let part = match tag {
+            BYTES | STRING => {
+                let mut value = Vec::new();
+                loop {
+                    let (&byte, rest) = bytes.split_first().ok_or_else(invalid)?;
+                    bytes = rest;
+                    if byte != 0 {
+                        value.push(byte);
+                    } else if let Some((0xff, rest)) = bytes.split_first() {
+                        bytes = rest;
+                        value.push(0);
+                    } else {
+                        break;
+                    }
+                }
+                if tag == BYTES {
+                    KeyPart::Bytes(value)
+                } else {
+                    KeyPart::String(String::from_utf8(value).map_err(|_| invalid())?)
+                }
+            }
+            NUMBER => {
+                let (number, rest) = bytes.split_first_chunk::<8>().ok_or_else(invalid)?;
+                bytes = rest;
+                let bits = u64::from_be_bytes(*number);
+                let bits = if bits >> 63 == 1 {
+                    bits & !(1 << 63)
+                } else {
+                    !bits
+                };
+                KeyPart::Number(f64::from_bits(bits))
+            }
+            FALSE => KeyPart::Boolean(false),
+            TRUE => KeyPart::Boolean(true),
+            _ => return Err(invalid()),
+        };
+        key.push(part);
+    }
+    Ok(key)
+}
+
+/// Versionstamps are the database version of the commit that wrote an entry
+fn versionstamp(version: i64) -> String {
+    format!("{version:020x}")
+}
+
+/// Cursors are the hex encoded key of the last entry returned
+fn encode_cursor(key: &[u8]) -> String {
+    key.iter().fold(String::new(), |mut cursor, byte| {
+        let _ = write!(cursor, "{byte:02x}");
+        cursor
+    })
+}
+
+/// The encoded key a cursor continues after
+fn decode_cursor(cursor: &str) -> DenoResult<Vec<u8>> {
+    let invalid = || invalid_data("Invalid cursor");
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| {
+            cursor
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect::<DenoResult<Vec<u8>>>()?;
+    decode_key(&bytes).map_err(|_| invalid())?;
+    Ok(bytes)
+}
+
+fn invalid_data(message: &str) -> DenoError {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message).into()
+}
+
+fn sqlite_error(error: rusqlite::Error) -> DenoError {
+    DenoError::Other(error.to_string())
+}
+
+/// An entry as handed to JS; the value is JSON, parsed on the JS side.
+/// Listed entries come with the cursor that continues after them.
+pub struct Entry {
+    key: Vec<KeyPart>,
+    value: String,
+    versionstamp: String,
+    cursor: Option<String>,
+}
+
+impl<'js> IntoJs<'js> for Entry {
+    fn into_js(self, ctx: &Ctx<'js>) -> rquickjs::Result<Value<'js>> {
+        let object = Object::new(ctx.clone())?;
+        object.set("key", self.key)?;
+        object.set("value", self.value)?;
+        object.set("versionstamp", self.versionstamp)?;
+        if let Some(cursor) = self.cursor {
+            object.set("cursor", cursor)?;
+        }
+        Ok(object.into_value())
+    }
+}
+
+/// Keys to list: those under `prefix` (but not `prefix` itself), from
+/// `start` (inclusive) and before `end`
+#[derive(Default)]
+pub struct Selector {
+    prefix: Option<Vec<KeyPart>>,
+    start: Option<Vec<KeyPart>>,
+    end: Option<Vec<KeyPart>>,
+}
+
+impl Selector {
+    /// The encoded keys to list, as a lower bound and an excluded upper
+    /// bound, narrowed to the keys past `cursor` in the listing order
+    fn range(&self, cursor: Option<Vec<u8>>, reverse: bool) -> (Bound<Vec<u8>>, Option<Vec<u8>>) {
+        let mut lower = Bound::Unbounded;
+        let mut upper = None;
+        if let Some(prefix) = &self.prefix {
+            let prefix = encode_key(prefix);
+            let mut end = prefix.clone();
+            end.push(0xff);
+            lower = raise(lower, Bound::Excluded(prefix));
+            upper = Some(lower_to(upper, end));
+        }
+        if let Some(start) = &self.start {
+            lower = raise(lower, Bound::Included(encode_key(start)));
+        }
+        if let Some(end) = &self.end {
+            upper = Some(lower_to(upper, encode_key(end)));
+        }
+        match cursor {
+            Some(cursor) if reverse => upper = Some(lower_to(upper, cursor)),
+            Some(cursor) => lower = raise(lower, Bound::Excluded(cursor)),
+            None => {}
+        }
+        (lower, upper)
+    }
+}
+
+/// The higher of two lower bounds
+fn raise(lower: Bound<Vec<u8>>, bound: Bound<Vec<u8>>) -> Bound<Vec<u8>> {
+    let (Bound::Included(current) | Bound::Excluded(current)) = &lower else {
+        return bound;
+    };
+    let (Bound::Included(new) | Bound::Excluded(new)) = &bound else {
+        return lower;
+    };
+    match new.cmp(current) {
+        Ordering::Greater => bound,
+        Ordering::Equal if matches!(bound, Bound::Excluded(_)) => bound,
+        _ => lower,
+    }
+}
+
+/// The lower of two excluded upper bounds
+fn lower_to(upper: Option<Vec<u8>>, bound: Vec<u8>) -> Vec<u8> {
+    match upper {
+        Some(upper) if upper <= bound => upper,
+        _ => bound,
+    }
+}
+
+impl<'js> FromJs<'js> for Selector {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        let object = Object::from_js(ctx, value)?;
+        Ok(Self {
+            prefix: object.get("prefix")?,
+            start: object.get("start")?,
+            end: object.get("end")?,
+        })
+    }
+}
+
+/// `check()` of an atomic operation; a `None` versionstamp expects no entry
+pub struct Check {
+    key: Vec<KeyPart>,
+    versionstamp: Option<String>,
+}
+
+impl<'js> FromJs<'js> for Check {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        let object = Object::from_js(ctx, value)?;
+        Ok(Self {
+            key: object.get("key")?,
+            versionstamp: object.get("versionstamp")?,
+        })
+    }
+}
+
+pub enum Mutation {
+    Set { key: Vec<KeyPart>, value: String },
+    Delete { key: Vec<KeyPart> },
+}
+
+impl<'js> FromJs<'js> for Mutation {
+    fn from_js(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Self> {
+        let object = Object::from_js(ctx, value)?;
+        let key = object.get("key")?;
+        match object.get::<_, String>("type")?.as_str() {
+            "set" => Ok(Self::Set {
+                key,
+                value: object.get("value")?,
+            }),
+            "delete" => Ok(Self::Delete { key }),
+            _ => Err(rquickjs::Error::new_from_js("object", "KvMutation")),
+        }
+    }
+}
+
+fn databases() -> MutexGuard<'static, Vec<Option<Connection>>> {
+    DATABASES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Run `f` on the open database behind `rid`
+fn with_database<T>(rid: u32, f: impl FnOnce(&mut Connection) -> DenoResult<T>) -> DenoResult<T> {
+    let mut databases = databases();
+    let connection = databases
+        .get_mut(rid as usize)
+        .and_then(Option::as_mut)
+        .ok_or_else(|| DenoError::BadResource("Bad resource ID".to_string()))?;
+    f(connection)
+}
+
+/// Open the database at `path`, or an in-memory one without a path
+fn open(path: Option<&str>) -> DenoResult<u32> {
+    let connection = match path {
+        None | Some(":memory:") => Connection::open_in_memory(),
+        Some(path) => {
+            deno_permissions::check("read", path)?;
+            deno_permissions::check("write", path)?;
+            Connection::open(path)
+        }
+    }
+    .map_err(sqlite_error)?;
+
+    // WAL lets other connections read while one of them writes
+    connection
+        .pragma_update_and_check(None, "journal_mode", "wal", |row| row.get::<_, String>(0))
+        .map_err(sqlite_error)?;
+    connection
+        .busy_timeout(BUSY_TIMEOUT)
+        .map_err(sqlite_error)?;
+    connection.execute_batch(SCHEMA).map_err(sqlite_error)?;
+
+    let mut databases = databases();
+    let rid = if let Some(free) = databases.iter().position(Option::is_none) {
+        databases[free] = Some(connection);
+        free
+    } else {
+        databases.push(Some(connection));
+        databases.len() - 1
+    };
+    Ok(u32::try_from(rid).unwrap_or(u32::MAX))
+}
+
+fn close(rid: u32) -> DenoResult<()> {
+    databases()
+        .get_mut(rid as usize)
+        .and_then(Option::take)
+        .map(drop)
+        .ok_or_else(|| DenoError::BadResource("Bad resource ID".to_string()))
+}
+
+fn get(rid: u32, key: Vec<KeyPart>) -> DenoResult<Option<Entry>> {
+    with_database(rid, |connection| {
+        let row = connection
+            .query_row(
+                "SELECT value, version FROM kv WHERE key = ?1",
+                [encode_key(&key)],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .optional()
+            .map_err(sqlite_error)?;
+        Ok(row.map(|(value, version)| Entry {
+            key,
+            value,
+            versionstamp: versionstamp(version),
+            cursor: None,
+        }))
+    })
+}
+
+/// Up to `limit` entries matching `selector` in key order, starting after
+/// `cursor`
+fn list(
+    rid: u32,
+    selector: &Selector,
+    cursor: Option<&str>,
+    limit: usize,
+    reverse: bool,
+) -> DenoResult<Vec<Entry>> {
+    let cursor = cursor.map(decode_cursor).transpose()?;
+    let (lower, upper) = selector.range(cursor, reverse);
+    let mut conditions = Vec::new();
+    let mut bounds = Vec::new();
+    match lower {
+        Bound::Included(lower) => {
+            conditions.push("key >= ?");
+            bounds.push(lower);
+        }
+        Bound::Excluded(lower) => {
+            conditions.push("key > ?");
+            bounds.push(lower);
+        }
+        Bound::Unbounded => {}
+    }
+    if let Some(upper) = upper {
+        conditions.push("key < ?");
+        bounds.push(upper);
+    }
+    let filter = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    let order = if reverse { "DESC" } else { "ASC" };
+    let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+    let sql =
+        format!("SELECT key, value, version FROM kv{filter} ORDER BY key {order} LIMIT {limit}");
+
+    with_database(rid, |connection| {
+        let mut statement = connection.prepare(&sql).map_err(sqlite_error)?;
+        let rows = statement
+            .query_map(params_from_iter(bounds), |row| {
+                Ok((
+                    row.get::<_, Vec<u8>>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })
+            .map_err(sqlite_error)?;
+        let mut entries = Vec::new();
+        for row in rows {
+            let (key, value, version) = row.map_err(sqlite_error)?;
+            entries.push(Entry {
+                key: decode_key(&key)?,
+                value,
+                versionstamp: versionstamp(version),
+                cursor: Some(encode_cursor(&key)),
+            });
+        }
+        Ok(entries)
+    })
+}
+
+/// Apply `mutations` if every check holds, returning the new versionstamp,
+/// or `None` if a check failed
+fn commit(rid: u32, checks: &[Check], mutations: Vec<Mutation>) -> DenoResult<Option<String>> {
+    with_database(rid, |connection| {
+        // Take the write lock before the checks. A deferred transaction
+        // would upgrade from reading to writing, which fails with
+        // SQLITE_BUSY without waiting if another connection wrote since.
+        let transaction = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(sqlite_error)?;
+        for check in checks {
+            let version = transaction
+                .query_row(
+                    "SELECT version FROM kv WHERE key = ?1",
+                    [encode_key(&check.key)],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()
+                .map_err(sqlite_error)?;
+            // Dropping the transaction rolls it back
+            if version.map(versionstamp) != check.versionstamp {
+                return Ok(None);
+            }
+        }
+
+        let version = transaction
+            .query_row(
+                "UPDATE kv_version SET version = version + 1 RETURNING version",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(sqlite_error)?;
+        for mutation in mutations {
+            match mutation {
+                Mutation::Set { key, value } => transaction.execute(
+                    "INSERT OR REPLACE INTO kv (key, value, version) VALUES (?1, ?2, ?3)",
+                    params![encode_key(&key), value, version],
+                ),
+                Mutation::Delete { key } => {
+                    transaction.execute("DELETE FROM kv WHERE key = ?1", [encode_key(&key)])
+                }
+            }
+            .map_err(sqlite_error)?;
+        }
+        transaction.commit().map_err(sqlite_error)?;
+        Ok(Some(versionstamp(version)))
+    })
+}
+
+fn kv_open(path: Option<String>) -> JsResult<u32> {
+    open(path.as_deref()).into()
+}
+
+fn kv_close(rid: u32) -> JsResult<()> {
+    close(rid).into()
+}
+
+fn kv_get(rid: u32, key: Vec<KeyPart>) -> JsResult<Option<Entry>> {
+    get(rid, key).into()
+}
+
+fn kv_list(
+    rid: u32,
+    selector: Selector,
+    cursor: Option<String>,
+    limit: usize,
+    reverse: bool,
+) -> JsResult<Vec<Entry>> {
+    list(rid, &selector, cursor.as_deref(), limit, reverse).into()
+}
+
+fn kv_commit(rid: u32, checks: Vec<Check>, mutations: Vec<Mutation>) -> JsResult<Option<String>> {
+    commit(rid, &checks, mutations).into()
+}
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    setup_internal(ctx)?;
+    let js_source = include_ts!("deno_kv.ts");
+    let module = Module::evaluate(ctx.clone(), "deno_kv", js_source)?;
+    module.finish::<()>()?;
+    Ok(())
+}
+
+fn setup_internal(ctx: &Ctx) -> rquickjs::Result<()> {
+    ctx.eval::<(), _>("globalThis[Symbol.for('mdeno.internal')].kv = {};")?;
+
+    // open(path?: string): number
+    add_internal_function!(ctx, "kv.open", kv_open);
+    // close(rid: number): void
+    add_internal_function!(ctx, "kv.close", kv_close);
+    // get(rid: number, key: KvKey): Entry | undefined
+    add_internal_function!(ctx, "kv.get", kv_get);
+    // list(rid, selector, cursor, limit, reverse): Entry[]
+    add_internal_function!(ctx, "kv.list", kv_list);
+    // commit(rid, checks, mutations): versionstamp, or undefined if a check failed
+    add_internal_function!(ctx, "kv.commit", kv_commit);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> KeyPart {
+        KeyPart::String(s.to_string())
+    }
+
+    fn set(rid: u32, key: Vec<KeyPart>) {
+        let mutation = Mutation::Set {
+            key,
+            value: "null".to_string(),
+        };
+        commit(rid, &[], vec![mutation]).unwrap().unwrap();
+    }
+
+    fn listed(rid: u32, selector: &Selector, cursor: Option<&str>, reverse: bool) -> Vec<String> {
+        list(rid, selector, cursor, 10, reverse)
+            .unwrap()
+            .iter()
+            .map(|entry| format!("{:?}", entry.key))
+            .collect()
+    }
+
+    #[test]
+    fn test_key_order() {
+        let mut keys = [
+            vec![KeyPart::Boolean(false)],
+            vec![string("b")],
+            vec![string("a"), KeyPart::Number(2.0)],
+            vec![KeyPart::Number(-1.5)],
+            vec![string("a")],
+            vec![KeyPart::Bytes(vec![1])],
+            vec![string("a"), KeyPart::Number(-3.0)],
+            vec![string("aa")],
+            vec![string("a\0")],
+            vec![KeyPart::Number(f64::NEG_INFINITY)],
+            vec![KeyPart::Number(0.5)],
+            vec![KeyPart::Boolean(true)],
+        ];
+        keys.sort_by_key(|key| encode_key(key));
+        let encoded: Vec<String> = keys.iter().map(|key| format!("{key:?}")).collect();
+        assert_eq!(
+            encoded,
+            [
+                "[Bytes([1])]",
+                "[String(\"a\")]",
+                "[String(\"a\"), Number(-3.0)]",
+                "[String(\"a\"), Number(2.0)]",
+                "[String(\"a\\0\")]",
+                "[String(\"aa\")]",
+                "[String(\"b\")]",
+                "[Number(-inf)]",
+                "[Number(-1.5)]",
+                "[Number(0.5)]",
+                "[Boolean(false)]",
+                "[Boolean(true)]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_and_cursor_round_trip() {
+        let key = vec![
+            KeyPart::Bytes(vec![0, 255, 0]),
+            string("us\0ers"),
+            KeyPart::Number(-42.5),
+            KeyPart::Number(0.0),
+            KeyPart::Boolean(true),
+        ];
+        let encoded = encode_key(&key);
+        let decoded = decode_key(&encoded).unwrap();
+        assert_eq!(format!("{decoded:?}"), format!("{key:?}"));
+        assert_eq!(decode_cursor(&encode_cursor(&encoded)).unwrap(), encoded);
+        assert!(decode_cursor("zz").is_err());
+        assert!(decode_key(&[STRING, b'a']).is_err());
+        assert!(decode_key(&[NUMBER, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_list_selects_a_range() {
+        let rid = open(None).unwrap();
+        for key in [
+            vec![string("users")],
+            vec![string("users"), string("alice")],
+            vec![string("users"), string("bob"), KeyPart::Number(1.0)],
+            vec![string("users"), KeyPart::Boolean(true)],
+            vec![string("users\0")],
+            vec![string("usersx")],
+            vec![string("posts"), string("alice")],
+        ] {
+            set(rid, key);
+        }
+
+        // A prefix selects the keys under it, but not itself
+        let selector = Selector {
+            prefix: Some(vec![string("users")]),
+            ..Selector::default()
+        };
+        let users = [
+            "[String(\"users\"), String(\"alice\")]",
+            "[String(\"users\"), String(\"bob\"), Number(1.0)]",
+            "[String(\"users\"), Boolean(true)]",
+        ];
+        assert_eq!(listed(rid, &selector, None, false), users);
+        let mut reversed = users;
+        reversed.reverse();
+        assert_eq!(listed(rid, &selector, None, true), reversed);
+
+        // Cursors continue in the listing order
+        let first = list(rid, &selector, None, 1, false).unwrap();
+        let cursor = first[0].cursor.as_deref();
+        assert_eq!(listed(rid, &selector, cursor, false), users[1..]);
+        let last = list(rid, &selector, None, 1, true).unwrap();
+        let cursor = last[0].cursor.as_deref();
+        assert_eq!(listed(rid, &selector, cursor, true), reversed[1..]);
+
+        // Start is included and end excluded
+        let selector = Selector {
+            prefix: None,
+            start: Some(vec![string("users"), string("bob")]),
+            end: Some(vec![string("usersx")]),
+        };
+        assert_eq!(
+            listed(rid, &selector, None, false),
+            [users[1], users[2], "[String(\"users\\0\")]"]
+        );
+        close(rid).unwrap();
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+// Register network APIs under __mdeno__.net
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+interface NetAddr {
+  transport: "tcp";
+  hostname: string;
+  port: number;
+}
+
+interface ConnInfo {
+  rid: number;
+  localAddr: NetAddr;
+  remoteAddr: NetAddr;
+  alpnProtocol: string | null;
+}
+
+interface ListenerInfo {
+  rid: number;
+  addr: NetAddr;
+}
+
+interface ConnectOptions {
+  hostname?: string;
+  port: number;
+  transport?: "tcp";
+}
+
+interface ListenOptions {
+  hostname?: string;
+  port?: number;
+  transport?: "tcp";
+  // Let other listeners bind the same port; only has an effect on Linux
+  reusePort?: boolean;
+}
+
+interface TlsOptions {
+  caCerts?: string[];
+  cert?: string;
+  key?: string;
+  // Older names for cert and key
+  certChain?: string;
+  privateKey?: string;
+  alpnProtocols?: string[];
+}
+
+function checkTransport(transport: string | undefined): void {
+  if (transport !== undefined && transport !== "tcp") {
+    throw new TypeError(`Unsupported transport: '${transport}'`);
+  }
+}
+
+function checkPort(port: unknown): number {
+  if (typeof port !== "number" || !Number.isInteger(port) || port < 0 || port > 65535) {
+    throw new TypeError(`Invalid port: ${port}`);
+  }
+  return port;
+}
+
+function tlsOptions(options: TlsOptions) {
+  return {
+    caCerts: options.caCerts,
+    cert: options.cert ?? options.certChain,
+    key: options.key ?? options.privateKey,
+    alpnProtocols: options.alpnProtocols,
+  };
+}
+
+// Resource ID of a connection, for Deno.startTls
+let connRid: (conn: Conn) => number;
+
+// https://docs.deno.com/api/deno/~/Deno.Conn
+class Conn {
+  #rid: number;
+  #localAddr: NetAddr;
+  #remoteAddr: NetAddr;
+
+  static {
+    connRid = (conn) => conn.#rid;
+  }
+
+  constructor(info: ConnInfo) {
+    this.#rid = info.rid;
+    this.#localAddr = info.localAddr;
+    this.#remoteAddr = info.remoteAddr;
+  }
+
+  get localAddr(): NetAddr {
+    return this.#localAddr;
+  }
+
+  get remoteAddr(): NetAddr {
+    return this.#remoteAddr;
+  }
+
+  async read(buffer: Uint8Array): Promise<number | null> {
+    const data = await __internal.net.read(this.#rid, buffer.byteLength);
+    if (data == null) return null;
+    buffer.set(data);
+    return data.length;
+  }
+
+  write(data: Uint8Array): Promise<number> {
+    return __internal.net.write(this.#rid, data);
+  }
+
+  closeWrite(): Promise<void> {
+    return __internal.net.closeWrite(this.#rid);
+  }
+
+  close(): void {
+    __internal.net.close(this.#rid);
+  }
+
+  [Symbol.dispose](): void {
+    try {
+      __internal.net.close(this.#rid);
+    } catch {
+      // Already closed
+    }
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.TcpConn
+class TcpConn extends Conn {}
+
+// https://docs.deno.com/api/deno/~/Deno.TlsConn
+class TlsConn extends Conn {
+  #alpnProtocol: string | null;
+
+  constructor(info: ConnInfo) {
+    super(info);
+    this.#alpnProtocol = info.alpnProtocol;
+  }
+
+  // The handshake already completed when the connection was made
+  handshake(): Promise<{ alpnProtocol: string | null }> {
+    return Promise.resolve({ alpnProtocol: this.#alpnProtocol });
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.Listener
+class Listener {
+  #rid: number;
+  #addr: NetAddr;
+  #Conn: typeof TcpConn | typeof TlsConn;
+
+  constructor(info: ListenerInfo, ConnClass: typeof TcpConn | typeof TlsConn) {
+    this.#rid = info.rid;
+    this.#addr = info.addr;
+    this.#Conn = ConnClass;
+  }
+
+  get addr(): NetAddr {
+    return this.#addr;
+  }
+
+  async accept(): Promise<Conn> {
+    return new this.#Conn(await __internal.net.accept(this.#rid));
+  }
+
+  close(): void {
+    __internal.net.close(this.#rid);
+  }
+
+  async *[Symbol.asyncIterator](): AsyncGenerator<Conn> {
+    while (true) {
+      try {
+        yield await this.accept();
+      } catch (error) {
+        // @ts-ignore: mdeno internal API
+        const errors = globalThis.__mdeno__.errors;
+        // Iteration ends once the listener is closed
+        if (error instanceof errors.BadResource || error instanceof errors.Interrupted) {
+          return;
+        }
+        throw error;
+      }
+    }
+  }
+
+  [Symbol.dispose](): void {
+    try {
+      __internal.net.close(this.#rid);
+    } catch {
+      // Already closed
+    }
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.TlsListener
+class TlsListener extends Listener {}
+
+// https://docs.deno.com/api/deno/~/Deno.connect
+async function connect(options: ConnectOptions): Promise<TcpConn> {
+  checkTransport(options.transport);
+  const hostname = options.hostname ?? "127.0.0.1";
+  return new TcpConn(
+    await __internal.net.connect(hostname, checkPort(options.port)),
+  );
+}
+
+// https://docs.deno.com/api/deno/~/Deno.listen
+function listen(options: ListenOptions): Listener {
+  checkTransport(options.transport);
+  const hostname = options.hostname ?? "0.0.0.0";
+  const info = __internal.net.listen(
+    hostname,
+    checkPort(options.port ?? 0),
+    options.reusePort ?? false,
+  );
+  return new Listener(info, TcpConn);
+}
+
+// https://docs.deno.com/api/deno/~/Deno.connectTls
+async function connectTls(
+  options: ConnectOptions & TlsOptions,
+): Promise<TlsConn> {
+  const hostname = options.hostname ?? "127.0.0.1";
+  const info = await __internal.net.connectTls(
+    hostname,
+    checkPort(options.port),
+    tlsOptions(options),
+  );
+  return new TlsConn(info);
+}
+
+// https://docs.deno.com/api/deno/~/Deno.listenTls
+function listenTls(options: ListenOptions & TlsOptions): TlsListener {
+  checkTransport(options.transport);
+  const hostname = options.hostname ?? "0.0.0.0";
+  const info = __internal.net.listenTls(
+    hostname,
+    checkPort(options.port ?? 0),
+    tlsOptions(options),
+    options.reusePort ?? false,
+  );
+  return new TlsListener(info, TlsConn);
+}
+
+// https://docs.deno.com/api/deno/~/Deno.startTls
+async function startTls(
+  conn: TcpConn,
+  options: { hostname?: string } & TlsOptions = {},
+): Promise<TlsConn> {
+  const hostname = options.hostname ?? "127.0.0.1";
+  return new TlsConn(
+    await __internal.net.startTls(connRid(conn), hostname, tlsOptions(options)),
+  );
+}
+
+// @ts-ignore: mdeno internal API
+Object.assign(globalThis.__mdeno__.net, {
+  connect,
+  listen,
+  connectTls,
+  listenTls,
+  startTls,
+});
@@ -0,0 +1,65 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+mod resources;
+mod tcp;
+mod tls;
+
+pub use tls::TlsOptions;
+
+use rquickjs::function::Async;
+use rquickjs::{Ctx, Module};
+use utils::add_internal_function;
+use utils_macros::include_ts;
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    setup_internal(ctx)?;
+
+    // Register network APIs under __mdeno__.net as a module
+    let js_source = include_ts!("deno_net.ts");
+    let module = Module::evaluate(ctx.clone(), "deno_net", js_source)?;
+    module.finish::<()>()?;
+
+    // Deno.serve builds on Deno.listen
+    let js_source = include_ts!("serve.ts");
+    let module = Module::evaluate(ctx.clone(), "deno_net_serve", js_source)?;
+    module.finish::<()>()?;
+
+    Ok(())
+}
+
+fn setup_internal(ctx: &Ctx) -> rquickjs::Result<()> {
+    ctx.eval::<(), _>("globalThis[Symbol.for('mdeno.internal')].net = {};")?;
+
+    // connect(hostname: string, port: number): Promise<ConnInfo>
+    add_internal_function!(ctx, "net.connect", Async(tcp::net_connect));
+
+    // listen(hostname: string, port: number, reusePort: boolean): ListenerInfo
+    add_internal_function!(ctx, "net.listen", tcp::net_listen);
+
+    // accept(rid: number): Promise<ConnInfo>
+    add_internal_function!(ctx, "net.accept", Async(tcp::net_accept));
+
+    // read(rid: number, len: number): Promise<Uint8Array | null>
+    add_internal_function!(ctx, "net.read", Async(tcp::net_read));
+
+    // write(rid: number, data: Uint8Array): Promise<number>
+    add_internal_function!(ctx, "net.write", Async(tcp::net_write));
+
+    // closeWrite(rid: number): Promise<void>
+    add_internal_function!(ctx, "net.closeWrite", Async(tcp::net_close_write));
+
+    // close(rid: number): void
+    add_internal_function!(ctx, "net.close", tcp::net_close);
+
+    // connectTls(hostname: string, port: number, options: TlsOptions): Promise<ConnInfo>
+    add_internal_function!(ctx, "net.connectTls", Async(tls::net_connect_tls));
+
+    // listenTls(hostname: string, port: number, options: TlsOptions, reusePort: boolean): ListenerInfo
+    add_internal_function!(ctx, "net.listenTls", tls::net_listen_tls);
+
+    // startTls(rid: number, hostname: string, options: TlsOptions): Promise<ConnInfo>
+    add_internal_function!(ctx, "net.startTls", Async(tls::net_start_tls));
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+import { caCert, cert, key } from "./testdata/certs.ts";
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+async function readAll(conn: Deno.Conn): Promise<string> {
+  const buffer = new Uint8Array(1024);
+  let text = "";
+  while (true) {
+    const n = await conn.read(buffer);
+    if (n === null) return text;
+    text += decoder.decode(buffer.subarray(0, n));
+  }
+}
+
+// Answer one connection by sending back what it wrote
+async function echo(listener: Deno.Listener): Promise<void> {
+  const conn = await listener.accept();
+  const text = await readAll(conn);
+  await conn.write(encoder.encode(text));
+  conn.close();
+}
+
+Deno.test("Deno.connect talks to Deno.listen over TCP", async () => {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 0 });
+  const { port } = listener.addr;
+  if (listener.addr.transport !== "tcp" || port === 0) {
+    throw new Error(`addr: ${JSON.stringify(listener.addr)}`);
+  }
+  const served = echo(listener);
+
+  const conn = await Deno.connect({ port });
+  if (conn.remoteAddr.port !== port) throw new Error("remoteAddr");
+  await conn.write(encoder.encode("hello"));
+  await conn.closeWrite();
+  const reply = await readAll(conn);
+  conn.close();
+  await served;
+  listener.close();
+  if (reply !== "hello") throw new Error(`reply: ${reply}`);
+});
+
+Deno.test("Deno.connectTls talks to Deno.listenTls", async () => {
+  const listener = Deno.listenTls({
+    hostname: "127.0.0.1",
+    port: 0,
+    cert,
+    key,
+    alpnProtocols: ["h2", "http/1.1"],
+  });
+  const served = echo(listener);
+
+  const conn = await Deno.connectTls({
+    hostname: "localhost",
+    port: listener.addr.port,
+    caCerts: [caCert],
+    alpnProtocols: ["http/1.1"],
+  });
+  const { alpnProtocol } = await conn.handshake();
+  if (alpnProtocol !== "http/1.1") throw new Error(`alpn: ${alpnProtocol}`);
+  await conn.write(encoder.encode("secret"));
+  await conn.closeWrite();
+  const reply = await readAll(conn);
+  conn.close();
+  await served;
+  listener.close();
+  if (reply !== "secret") throw new Error(`reply: ${reply}`);
+});
+
+Deno.test("Deno.connectTls rejects an untrusted certificate", async () => {
+  const listener = Deno.listenTls({ hostname: "127.0.0.1", port: 0, cert, key });
+  const accepted = listener.accept().catch(() => {});
+  let threw = false;
+  try {
+    const conn = await Deno.connectTls({
+      hostname: "localhost",
+      port: listener.addr.port,
+    });
+    conn.close();
+  } catch {
+    threw = true;
+  }
+  listener.close();
+  await accepted;
+  if (!threw) throw new Error("connectTls should fail without caCerts");
+});
+
+Deno.test("Deno.startTls upgrades a TCP connection", async () => {
+  const listener = Deno.listenTls({ hostname: "127.0.0.1", port: 0, cert, key });
+  const served = echo(listener);
+
+  const tcp = await Deno.connect({ port: listener.addr.port });
+  const conn = await Deno.startTls(tcp, {
+    hostname: "localhost",
+    caCerts: [caCert],
+  });
+  await conn.write(encoder.encode("upgraded"));
+  await conn.closeWrite();
+  const reply = await readAll(conn);
+  conn.close();
+  await served;
+  listener.close();
+  if (reply !== "upgraded") throw new Error(`reply: ${reply}`);
+
+  let threw = false;
+  try {
+    await tcp.write(encoder.encode("stale"));
+  } catch (error) {
+    threw = error instanceof Deno.errors.BadResource;
+  }
+  if (!threw) throw new Error("old TCP connection should be gone");
+});
+
+Deno.test("Listener iteration ends when the listener is closed", async () => {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 0 });
+  const conns: Deno.Conn[] = [];
+  const done = (async () => {
+    for await (const conn of listener) {
+      conns.push(conn);
+      listener.close();
+    }
+  })();
+  const client = await Deno.connect({ port: listener.addr.port });
+  await done;
+  client.close();
+  for (const conn of conns) conn.close();
+  if (conns.length !== 1) throw new Error(`accepted: ${conns.length}`);
+});
+
+Deno.test("Deno.connect rejects an invalid port", async () => {
+  let threw = false;
+  try {
+    await Deno.connect({ port: 70000 });
+  } catch (error) {
+    threw = error instanceof TypeError;
+  }
+  if (!threw) throw new Error("connect should reject port 70000");
+});
@@ -0,0 +1,168 @@
+// Open sockets for Deno.connect / Deno.listen, addressed by resource ID
+use compio::net::{TcpListener, TcpStream};
+use compio_tls::{TlsAcceptor, TlsStream};
+use futures_util::future::{AbortHandle, Abortable};
+use futures_util::lock::Mutex;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use utils::{DenoError, DenoResult};
+
+/// A connected socket
+pub(crate) enum Stream {
+    /// Plain TCP reads and writes can run at the same time
+    Tcp(TcpStream),
+    /// TLS streams need exclusive access, so reads and writes take turns
+    Tls(Box<Mutex<TlsStream<TcpStream>>>),
+}
+
+pub(crate) struct Conn {
+    pub stream: Stream,
+    pending: Pending,
+}
+
+pub(crate) struct Listener {
+    pub socket: TcpListener,
+    /// Set for `Deno.listenTls`, which handshakes every accepted connection
+    pub tls: Option<TlsAcceptor>,
+    pending: Pending,
+}
+
+enum Resource {
+    Conn(Rc<Conn>),
+    Listener(Rc<Listener>),
+}
+
+thread_local! {
+    // Sockets can't leave the runtime thread, so they live in a thread-local table
+    static RESOURCES: RefCell<HashMap<u32, Resource>> = RefCell::new(HashMap::new());
+    static NEXT_RID: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Operations in flight on a resource, cancelled when it is closed
+#[derive(Default)]
+struct Pending {
+    ops: RefCell<HashMap<u64, AbortHandle>>,
+    next_id: Cell<u64>,
+}
+
+impl Pending {
+    async fn run<F: Future>(&self, future: F) -> DenoResult<F::Output> {
+        let (handle, registration) = AbortHandle::new_pair();
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.ops.borrow_mut().insert(id, handle);
+        let result = Abortable::new(future, registration).await;
+        self.ops.borrow_mut().remove(&id);
+        result.map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::Interrupted, "Operation canceled").into()
+        })
+    }
+
+    fn cancel_all(&self) {
+        for (_, handle) in self.ops.borrow_mut().drain() {
+            handle.abort();
+        }
+    }
+}
+
+impl Conn {
+    pub fn new(stream: Stream) -> Self {
+        Self {
+            stream,
+            pending: Pending::default(),
+        }
+    }
+
+    /// Run `future` until it finishes or the connection is closed
+    pub async fn run<F: Future>(&self, future: F) -> DenoResult<F::Output> {
+        self.pending.run(future).await
+    }
+}
+
+impl Listener {
+    pub fn new(listener: TcpListener, tls: Option<TlsAcceptor>) -> Self {
+        Self {
+            socket: listener,
+            tls,
+            pending: Pending::default(),
+        }
+    }
+
+    /// Run `future` until it finishes or the listener is closed
+    pub async fn run<F: Future>(&self, future: F) -> DenoResult<F::Output> {
+        self.pending.run(future).await
+    }
+}
+
+fn insert(resource: Resource) -> u32 {
+    let rid = NEXT_RID.get();
+    NEXT_RID.set(rid + 1);
+    RESOURCES.with_borrow_mut(|resources| resources.insert(rid, resource));
+    rid
+}
+
+pub(crate) fn insert_conn(conn: Conn) -> u32 {
+    insert(Resource::Conn(Rc::new(conn)))
+}
+
+pub(crate) fn insert_listener(listener: Listener) -> u32 {
+    insert(Resource::Listener(Rc::new(listener)))
+}
+
+fn bad_resource() -> DenoError {
+    DenoError::BadResource("Bad resource ID".to_string())
+}
+
+pub(crate) fn conn(rid: u32) -> DenoResult<Rc<Conn>> {
+    RESOURCES.with_borrow(|resources| match resources.get(&rid) {
+        Some(Resource::Conn(conn)) => Ok(Rc::clone(conn)),
+        _ => Err(bad_resource()),
+    })
+}
+
+pub(crate) fn listener(rid: u32) -> DenoResult<Rc<Listener>> {
+    RESOURCES.with_borrow(|resources| match resources.get(&rid) {
+        Some(Resource::Listener(listener)) => Ok(Rc::clone(listener)),
+        _ => Err(DenoError::BadResource(
+            "Listener has been closed".to_string(),
+        )),
+    })
+}
+
+/// Take a plain TCP connection out of the table, to be wrapped in TLS
+pub(crate) fn take_tcp_conn(rid: u32) -> DenoResult<TcpStream> {
+    let conn = RESOURCES.with_borrow_mut(|resources| {
+        match resources.get(&rid) {
+            Some(Resource::Conn(conn)) if matches!(conn.stream, Stream::Tcp(_)) => {}
+            Some(Resource::Conn(_)) => {
+                return Err(DenoError::Other(
+                    "Connection is already using TLS".to_string(),
+                ));
+            }
+            _ => return Err(bad_resource()),
+        }
+        match resources.remove(&rid) {
+            Some(Resource::Conn(conn)) => Ok(conn),
+            _ => Err(bad_resource()),
+        }
+    })?;
+    let conn = Rc::try_unwrap(conn).map_err(|conn| {
+        RESOURCES.with_borrow_mut(|resources| resources.insert(rid, Resource::Conn(conn)));
+        DenoError::Busy("Connection has pending reads or writes".to_string())
+    })?;
+    match conn.stream {
+        Stream::Tcp(stream) => Ok(stream),
+        Stream::Tls(_) => Err(bad_resource()),
+    }
+}
+
+/// Close a connection or listener, cancelling what is pending on it
+pub(crate) fn close(rid: u32) -> DenoResult<()> {
+    match RESOURCES.with_borrow_mut(|resources| resources.remove(&rid)) {
+        Some(Resource::Conn(conn)) => conn.pending.cancel_all(),
+        Some(Resource::Listener(listener)) => listener.pending.cancel_all(),
+        None => return Err(bad_resource()),
+    }
+    Ok(())
+}
@@ -0,0 +1,468 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+// Deno.serve: an HTTP/1.1 server on top of Deno.listen
+// @ts-ignore: mdeno internal API
+const net = globalThis.__mdeno__.net;
+
+interface NetAddr {
+  transport: "tcp";
+  hostname: string;
+  port: number;
+}
+
+interface ServeHandlerInfo {
+  remoteAddr: NetAddr;
+}
+
+type ServeHandler = (
+  request: Request,
+  info: ServeHandlerInfo,
+) => Response | Promise<Response>;
+
+// Anything with the AbortSignal interface
+interface AbortSignalLike {
+  aborted: boolean;
+  addEventListener(type: "abort", listener: () => void): void;
+}
+
+interface ServeOptions {
+  port?: number;
+  hostname?: string;
+  reusePort?: boolean;
+  handler?: ServeHandler;
+  signal?: AbortSignalLike;
+  onListen?: (addr: { hostname: string; port: number }) => void;
+  onError?: (error: unknown) => Response | Promise<Response>;
+}
+
+interface Conn {
+  remoteAddr: NetAddr;
+  read(buffer: Uint8Array): Promise<number | null>;
+  write(data: Uint8Array): Promise<number>;
+  close(): void;
+}
+
+// Requests with a larger head are answered with 431
+const MAX_HEAD_SIZE = 64 * 1024;
+// Requests with a larger body, chunked or not, are answered with 413
+const MAX_BODY_SIZE = 16 * 1024 * 1024;
+const READ_BUFFER_SIZE = 16 * 1024;
+
+const STATUS_TEXT: Record<number, string> = {
+  200: "OK",
+  201: "Created",
+  202: "Accepted",
+  204: "No Content",
+  206: "Partial Content",
+  301: "Moved Permanently",
+  302: "Found",
+  303: "See Other",
+  304: "Not Modified",
+  307: "Temporary Redirect",
+  308: "Permanent Redirect",
+  400: "Bad Request",
+  401: "Unauthorized",
+  403: "Forbidden",
+  404: "Not Found",
+  405: "Method Not Allowed",
+  409: "Conflict",
+  413: "Content Too Large",
+  415: "Unsupported Media Type",
+  422: "Unprocessable Content",
+  429: "Too Many Requests",
+  431: "Request Header Fields Too Large",
+  500: "Internal Server Error",
+  501: "Not Implemented",
+  502: "Bad Gateway",
+  503: "Service Unavailable",
+};
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+class HttpError extends Error {
+  status: number;
+
+  constructor(status: number, message: string) {
+    super(message);
+    this.status = status;
+  }
+}
+
+// Buffered reads from a connection
+class ConnReader {
+  #conn: Conn;
+  #buffer = new Uint8Array(0);
+  #eof = false;
+  #onData: () => void;
+
+  // onData is called whenever bytes arrive
+  constructor(conn: Conn, onData: () => void) {
+    this.#conn = conn;
+    this.#onData = onData;
+  }
+
+  // Read more bytes into the buffer; false at end of stream
+  async #fill(): Promise<boolean> {
+    if (this.#eof) return false;
+    const chunk = new Uint8Array(READ_BUFFER_SIZE);
+    const n = await this.#conn.read(chunk);
+    if (n === null) {
+      this.#eof = true;
+      return false;
+    }
+    this.#onData();
+    const buffer = new Uint8Array(this.#buffer.length + n);
+    buffer.set(this.#buffer);
+    buffer.set(chunk.subarray(0, n), this.#buffer.length);
+    this.#buffer = buffer;
+    return true;
+  }
+
+  #take(length: number): Uint8Array {
+    const bytes = this.#buffer.slice(0, length);
+    this.#buffer = this.#buffer.subarray(length);
+    return bytes;
+  }
+
+  // Everything up to and including the next "\r\n\r\n", or null if the
+  // connection closed before another request started
+  async readHead(): Promise<string | null> {
+    let searched = 0;
+    while (true) {
+      for (let i = Math.max(searched - 3, 0); i + 3 < this.#buffer.length; i++) {
+        if (
+          this.#buffer[i] === 13 && this.#buffer[i + 1] === 10 &&
+          this.#buffer[i + 2] === 13 && this.#buffer[i + 3] === 10
+        ) {
+          return decoder.decode(this.#take(i + 4));
+        }
+      }
+      searched = this.#buffer.length;
+      if (searched > MAX_HEAD_SIZE) {
+        throw new HttpError(431, "Request header fields too large");
+      }
+      if (!(await this.#fill())) {
+        if (this.#buffer.length === 0) return null;
+        throw new HttpError(400, "Incomplete request");
+      }
+    }
+  }
+
+  async readLine(): Promise<string> {
+    while (true) {
+      const end = this.#buffer.indexOf(10);
+      if (end !== -1) {
+        return decoder.decode(this.#take(end + 1)).trimEnd();
+      }
+      if (!(await this.#fill())) throw new HttpError(400, "Incomplete body");
+    }
+  }
+
+  async readExact(length: number): Promise<Uint8Array> {
+    while (this.#buffer.length < length) {
+      if (!(await this.#fill())) throw new HttpError(400, "Incomplete body");
+    }
+    return this.#take(length);
+  }
+}
+
+async function readChunkedBody(reader: ConnReader): Promise<Uint8Array> {
+  const chunks: Uint8Array[] = [];
+  let total = 0;
+  while (true) {
+    const size = parseInt((await reader.readLine()).split(";")[0], 16);
+    if (Number.isNaN(size)) throw new HttpError(400, "Invalid chunk size");
+    if (size === 0) break;
+    if (total + size > MAX_BODY_SIZE) {
+      throw new HttpError(413, "Request body too large");
+    }
+    chunks.push(await reader.readExact(size));
+    total += size;
+    await reader.readLine();
+  }
+  // Skip trailers up to the empty line
+  while ((await reader.readLine()) !== "");
+  const body = new Uint8Array(total);
+  let offset = 0;
+  for (const chunk of chunks) {
+    body.set(chunk, offset);
+    offset += chunk.length;
+  }
+  return body;
+}
+
+interface ParsedRequest {
+  request: Request;
+  keepAlive: boolean;
+}
+
+async function readRequest(
+  reader: ConnReader,
+  defaultHost: string,
+): Promise<ParsedRequest | null> {
+  const head = await reader.readHead();
+  if (head === null) return null;
+  const [requestLine, ...lines] = head.slice(0, -4).split("\r\n");
+  const [method, target, version] = requestLine.split(" ");
+  if (!method || !target || !version?.startsWith("HTTP/1.")) {
+    throw new HttpError(400, "Malformed request line");
+  }
+
+  const headers: [string, string][] = [];
+  for (const line of lines) {
+    const colon = line.indexOf(":");
+    if (colon <= 0) throw new HttpError(400, "Malformed header");
+    headers.push([line.slice(0, colon).trim(), line.slice(colon + 1).trim()]);
+  }
+  const header = (name: string) =>
+    headers.find(([key]) => key.toLowerCase() === name)?.[1];
+
+  const transferEncoding = header("transfer-encoding");
+  const contentLength = header("content-length");
+  // Proxies may frame such a request differently (request smuggling)
+  if (transferEncoding !== undefined && contentLength !== undefined) {
+    throw new HttpError(400, "Both Transfer-Encoding and Content-Length");
+  }
+
+  let body: Uint8Array | undefined;
+  if (transferEncoding?.toLowerCase().includes("chunked")) {
+    body = await readChunkedBody(reader);
+  } else if (contentLength !== undefined) {
+    const length = Number(contentLength);
+    if (!Number.isInteger(length) || length < 0) {
+      throw new HttpError(400, "Invalid Content-Length");
+    }
+    if (length > MAX_BODY_SIZE) {
+      throw new HttpError(413, "Request body too large");
+    }
+    body = await reader.readExact(length);
+  }
+
+  const connection = header("connection")?.toLowerCase();
+  const keepAlive = version === "HTTP/1.1"
+    ? connection !== "close"
+    : connection === "keep-alive";
+  const url = target.startsWith("/")
+    ? `http://${header("host") ?? defaultHost}${target}`
+    : target;
+  const hasBody = body !== undefined && method !== "GET" && method !== "HEAD";
+  return {
+    request: new Request(url, {
+      method,
+      headers,
+      body: hasBody ? body : undefined,
+    }),
+    keepAlive,
+  };
+}
+
+async function writeResponse(
+  conn: Conn,
+  response: Response,
+  method: string,
+  keepAlive: boolean,
+): Promise<void> {
+  const status = response.status;
+  const statusText = response.statusText || STATUS_TEXT[status] || "";
+  const body = new Uint8Array(await response.arrayBuffer());
+  const bodyless = method === "HEAD" || status === 204 || status === 304;
+
+  let head = `HTTP/1.1 ${status} ${statusText}\r\n`;
+  for (const [name, value] of response.headers.entries()) {
+    // The framing of the response is decided here
+    if (name === "content-length" || name === "transfer-encoding" || name === "connection") {
+      continue;
+    }
+    head += `${name}: ${value}\r\n`;
+  }
+  if (!response.headers.has("date")) {
+    head += `date: ${new Date().toUTCString()}\r\n`;
+  }
+  if (status !== 204 && status !== 304) {
+    head += `content-length: ${body.length}\r\n`;
+  }
+  head += `connection: ${keepAlive ? "keep-alive" : "close"}\r\n\r\n`;
+
+  const headBytes = encoder.encode(head);
+  const message = new Uint8Array(headBytes.length + (bodyless ? 0 : body.length));
+  message.set(headBytes);
+  if (!bodyless) message.set(body, headBytes.length);
+  let written = 0;
+  while (written < message.length) {
+    written += await conn.write(message.subarray(written));
+  }
+}
+
+function errorResponse(error: unknown): Response {
+  const message = error instanceof Error ? error.message : String(error);
+  return new Response(message, {
+    status: 500,
+    headers: { "content-type": "text/plain;charset=UTF-8" },
+  });
+}
+
+// A connection being served, idle while it waits for the next request
+interface ServedConn {
+  conn: Conn;
+  idle: boolean;
+}
+
+// Answer requests on one connection until it closes, stops keeping alive or
+// the server shuts down
+async function serveConn(
+  served: ServedConn,
+  handler: ServeHandler,
+  onError: (error: unknown) => Response | Promise<Response>,
+  defaultHost: string,
+  isClosed: () => boolean,
+): Promise<void> {
+  const { conn } = served;
+  const reader = new ConnReader(conn, () => served.idle = false);
+  try {
+    while (!isClosed()) {
+      let parsed: ParsedRequest | null;
+      served.idle = true;
+      try {
+        parsed = await readRequest(reader, defaultHost);
+      } catch (error) {
+        if (error instanceof HttpError) {
+          const response = new Response(error.message, { status: error.status });
+          await writeResponse(conn, response, "GET", false);
+          return;
+        }
+        throw error;
+      }
+      if (parsed === null) return;
+
+      const { request, keepAlive } = parsed;
+      let response: Response;
+      try {
+        response = await handler(request, { remoteAddr: conn.remoteAddr });
+        if (!(response instanceof Response)) {
+          throw new TypeError("Return value from serve handler must be a response");
+        }
+      } catch (error) {
+        try {
+          response = await onError(error);
+        } catch (error) {
+          response = errorResponse(error);
+        }
+      }
+      await writeResponse(conn, response, request.method, keepAlive);
+      if (!keepAlive) return;
+    }
+  } catch {
+    // The client went away mid-request
+  } finally {
+    try {
+      conn.close();
+    } catch {
+      // Already closed
+    }
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.HttpServer
+class HttpServer {
+  #addr: NetAddr;
+  #finished: Promise<void>;
+  #shutdown: () => void;
+
+  constructor(addr: NetAddr, finished: Promise<void>, shutdown: () => void) {
+    this.#addr = addr;
+    this.#finished = finished;
+    this.#shutdown = shutdown;
+  }
+
+  get addr(): NetAddr {
+    return this.#addr;
+  }
+
+  // Resolves once the server stopped and in-flight requests were answered
+  get finished(): Promise<void> {
+    return this.#finished;
+  }
+
+  // Stop accepting connections
+  shutdown(): Promise<void> {
+    this.#shutdown();
+    return this.#finished;
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.serve
+// serve(handler), serve(options, handler) or serve({ handler, ...options })
+function serve(
+  optionsOrHandler: ServeOptions | ServeHandler,
+  maybeHandler?: ServeHandler,
+): HttpServer {
+  const options = typeof optionsOrHandler === "function"
+    ? {}
+    : optionsOrHandler;
+  const handler = typeof optionsOrHandler === "function"
+    ? optionsOrHandler
+    : maybeHandler ?? optionsOrHandler.handler;
+  if (typeof handler !== "function") {
+    throw new TypeError("A handler function must be provided");
+  }
+  const onError = options.onError ?? errorResponse;
+
+  const listener = net.listen({
+    hostname: options.hostname ?? "0.0.0.0",
+    port: options.port ?? 8000,
+    reusePort: options.reusePort,
+  });
+  const addr: NetAddr = listener.addr;
+  const defaultHost = `${addr.hostname}:${addr.port}`;
+
+  const connections = new Set<ServedConn>();
+  const pending = new Set<Promise<void>>();
+  let closed = false;
+  const shutdown = () => {
+    if (closed) return;
+    closed = true;
+    try {
+      listener.close();
+    } catch {
+      // Already closed
+    }
+    // Connections kept alive between requests would otherwise hold
+    // `finished` open; the others close once their response is written
+    for (const served of connections) {
+      if (served.idle) served.conn.close();
+    }
+  };
+  if (options.signal?.aborted) {
+    shutdown();
+  } else {
+    options.signal?.addEventListener("abort", shutdown);
+  }
+
+  const finished = (async () => {
+    for await (const conn of listener) {
+      const served = { conn, idle: true };
+      connections.add(served);
+      const done = serveConn(served, handler, onError, defaultHost, () => closed)
+        .finally(() => {
+          connections.delete(served);
+          pending.delete(done);
+        });
+      pending.add(done);
+    }
+    await Promise.all(pending);
+  })();
+
+  if (!closed) {
+    const { hostname, port } = addr;
+    if (options.onListen) {
+      options.onListen({ hostname, port });
+    } else {
+      const host = hostname === "0.0.0.0" ? "localhost" : hostname;
+      console.log(`Listening on http://${host}:${port}/`);
+    }
+  }
+
+  return new HttpServer(addr, finished, shutdown);
+}
+
+Object.assign(net, { serve, HttpServer });
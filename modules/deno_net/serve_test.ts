@@ -0,0 +1,191 @@
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+// Send raw bytes and read until the server closes the connection
+async function exchange(port: number, request: string): Promise<string> {
+  const conn = await Deno.connect({ port });
+  await conn.write(encoder.encode(request));
+  const buffer = new Uint8Array(4096);
+  let text = "";
+  while (true) {
+    const n = await conn.read(buffer);
+    if (n === null) break;
+    text += decoder.decode(buffer.subarray(0, n));
+  }
+  conn.close();
+  return text;
+}
+
+Deno.test("Deno.serve answers fetch requests", async () => {
+  let listening: { hostname: string; port: number } | undefined;
+  const server = Deno.serve({
+    hostname: "127.0.0.1",
+    port: 0,
+    onListen: (addr) => listening = addr,
+  }, async (request, info) => {
+    const url = new URL(request.url);
+    if (request.method === "POST") {
+      return new Response(`${url.pathname}: ${await request.text()}`);
+    }
+    return new Response(JSON.stringify({
+      path: url.pathname,
+      query: url.searchParams.get("q"),
+      host: request.headers.get("host") === url.host,
+      remote: info.remoteAddr.hostname,
+    }));
+  });
+  if (listening?.port !== server.addr.port || server.addr.port === 0) {
+    throw new Error(`onListen: ${JSON.stringify(listening)}`);
+  }
+  const base = `http://127.0.0.1:${server.addr.port}`;
+
+  const response = await fetch(`${base}/items?q=1`);
+  const json = await response.json();
+  if (
+    JSON.stringify(json) !==
+      '{"path":"/items","query":"1","host":true,"remote":"127.0.0.1"}'
+  ) {
+    throw new Error(`json: ${JSON.stringify(json)}`);
+  }
+
+  const posted = await fetch(`${base}/echo`, { method: "POST", body: "hi" });
+  if ((await posted.text()) !== "/echo: hi") throw new Error("post body");
+
+  await server.shutdown();
+});
+
+Deno.test("Deno.serve reports handler errors", async () => {
+  const server = Deno.serve({ hostname: "127.0.0.1", port: 0 }, () => {
+    throw new Error("kaboom");
+  });
+  const response = await fetch(`http://127.0.0.1:${server.addr.port}/`);
+  if (response.status !== 500 || (await response.text()) !== "kaboom") {
+    throw new Error(`default onError: ${response.status}`);
+  }
+  await server.shutdown();
+
+  const custom = Deno.serve({
+    hostname: "127.0.0.1",
+    port: 0,
+    handler: () => {
+      throw new Error("kaboom");
+    },
+    onError: (error) =>
+      new Response(`handled ${(error as Error).message}`, { status: 503 }),
+  });
+  const handled = await fetch(`http://127.0.0.1:${custom.addr.port}/`);
+  if (handled.status !== 503 || (await handled.text()) !== "handled kaboom") {
+    throw new Error(`custom onError: ${handled.status}`);
+  }
+  await custom.shutdown();
+});
+
+Deno.test("Deno.serve reads chunked bodies and keeps connections alive", async () => {
+  const server = Deno.serve(
+    { hostname: "127.0.0.1", port: 0, onListen: () => {} },
+    async (request) => new Response(`${request.method} ${await request.text()}`),
+  );
+  const reply = await exchange(
+    server.addr.port,
+    "POST / HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\n\r\n" +
+      "3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n" +
+      "HEAD / HTTP/1.1\r\nHost: x\r\n\r\n" +
+      "GET / HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+  );
+  const responses = reply.split("HTTP/1.1 200 OK").slice(1);
+  if (responses.length !== 3) throw new Error(`responses: ${reply}`);
+  if (!responses[0].endsWith("\r\n\r\nPOST abcde")) throw new Error(responses[0]);
+  // HEAD responses keep the length of the body they leave out
+  if (
+    !responses[1].includes("content-length: 5") ||
+    !responses[1].endsWith("keep-alive\r\n\r\n")
+  ) {
+    throw new Error(responses[1]);
+  }
+  if (!responses[2].includes("connection: close")) throw new Error(responses[2]);
+  await server.shutdown();
+});
+
+Deno.test("Deno.serve rejects malformed requests", async () => {
+  const server = Deno.serve(
+    { hostname: "127.0.0.1", port: 0, onListen: () => {} },
+    () => new Response("unreachable"),
+  );
+  const reply = await exchange(server.addr.port, "nonsense\r\n\r\n");
+  if (!reply.startsWith("HTTP/1.1 400 Bad Request")) throw new Error(reply);
+  const framed = await exchange(
+    server.addr.port,
+    "POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 3\r\n" +
+      "Transfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n0\r\n\r\n",
+  );
+  if (!framed.startsWith("HTTP/1.1 400 Bad Request")) throw new Error(framed);
+  await server.shutdown();
+});
+
+Deno.test("Deno.serve rejects bodies over the size limit", async () => {
+  const server = Deno.serve(
+    { hostname: "127.0.0.1", port: 0, onListen: () => {} },
+    () => new Response("unreachable"),
+  );
+  const sized = await exchange(
+    server.addr.port,
+    "POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 16777217\r\n\r\n",
+  );
+  if (!sized.startsWith("HTTP/1.1 413 Content Too Large")) throw new Error(sized);
+  const chunked = await exchange(
+    server.addr.port,
+    "POST / HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\n\r\n" +
+      "1000001\r\n",
+  );
+  if (!chunked.startsWith("HTTP/1.1 413 Content Too Large")) throw new Error(chunked);
+  await server.shutdown();
+});
+
+Deno.test("Deno.serve stops when its signal aborts", async () => {
+  const controller = new AbortController();
+  const server = Deno.serve(
+    {
+      hostname: "127.0.0.1",
+      port: 0,
+      signal: controller.signal,
+      onListen: () => {},
+    },
+    () => new Response("ok"),
+  );
+  const response = await fetch(`http://127.0.0.1:${server.addr.port}/`);
+  if ((await response.text()) !== "ok") throw new Error("response");
+
+  controller.abort();
+  await server.finished;
+  try {
+    await Deno.connect({ port: server.addr.port });
+    throw new Error("should not accept connections after abort");
+  } catch (error) {
+    if (!(error instanceof Deno.errors.ConnectionRefused)) throw error;
+  }
+});
+
+Deno.test({
+  name: "Deno.serve shares its port with reusePort",
+  ignore: Deno.build.os !== "linux",
+  async fn() {
+    const options = { hostname: "127.0.0.1", reusePort: true, onListen: () => {} };
+    const first = Deno.serve({ ...options, port: 0 }, () => new Response("first"));
+    const port = first.addr.port;
+    const second = Deno.serve({ ...options, port }, () => new Response("second"));
+    if (second.addr.port !== port) throw new Error(`port: ${second.addr.port}`);
+
+    const text = await (await fetch(`http://127.0.0.1:${port}/`)).text();
+    if (text !== "first" && text !== "second") throw new Error(text);
+
+    // Listeners that don't opt in still can't take the port
+    try {
+      Deno.listen({ hostname: "127.0.0.1", port }).close();
+      throw new Error("should not bind without reusePort");
+    } catch (error) {
+      if (!(error instanceof Deno.errors.AddrInUse)) throw error;
+    }
+    await first.shutdown();
+    await second.shutdown();
+  },
+});
@@ -0,0 +1,242 @@
+// TCP connections and listeners for Deno.connect / Deno.listen
+use crate::resources::{self, Conn, Listener, Stream};
+use compio::BufResult;
+use compio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use compio::net::{TcpListener, TcpStream};
+use rquickjs::{IntoJs, Object, TypedArray};
+use std::net::SocketAddr;
+use utils::{DenoError, DenoResult, JsResult};
+
+/// Resource ID and addresses of a new connection
+pub(crate) struct ConnInfo {
+    pub rid: u32,
+    pub local_addr: SocketAddr,
+    pub remote_addr: SocketAddr,
+    /// Protocol agreed on with ALPN, for TLS connections
+    pub alpn_protocol: Option<String>,
+}
+
+impl<'js> IntoJs<'js> for ConnInfo {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let obj = Object::new(ctx.clone())?;
+        obj.set("rid", self.rid)?;
+        obj.set("localAddr", NetAddr(self.local_addr))?;
+        obj.set("remoteAddr", NetAddr(self.remote_addr))?;
+        obj.set("alpnProtocol", self.alpn_protocol)?;
+        Ok(obj.into_value())
+    }
+}
+
+/// Resource ID and bound address of a new listener
+pub(crate) struct ListenerInfo {
+    pub rid: u32,
+    pub addr: SocketAddr,
+}
+
+impl<'js> IntoJs<'js> for ListenerInfo {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let obj = Object::new(ctx.clone())?;
+        obj.set("rid", self.rid)?;
+        obj.set("addr", NetAddr(self.addr))?;
+        Ok(obj.into_value())
+    }
+}
+
+/// `Deno.NetAddr`: `{ transport: "tcp", hostname, port }`
+struct NetAddr(SocketAddr);
+
+impl<'js> IntoJs<'js> for NetAddr {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let obj = Object::new(ctx.clone())?;
+        obj.set("transport", "tcp")?;
+        obj.set("hostname", self.0.ip().to_string())?;
+        obj.set("port", self.0.port())?;
+        Ok(obj.into_value())
+    }
+}
+
+pub(crate) fn conn_info(
+    stream: Stream,
+    local_addr: SocketAddr,
+    remote_addr: SocketAddr,
+) -> ConnInfo {
+    let alpn_protocol = match &stream {
+        Stream::Tcp(_) => None,
+        Stream::Tls(tls) => tls.try_lock().and_then(|tls| {
+            tls.negotiated_alpn()
+                .map(|protocol| String::from_utf8_lossy(&protocol).into_owned())
+        }),
+    };
+    ConnInfo {
+        rid: resources::insert_conn(Conn::new(stream)),
+        local_addr,
+        remote_addr,
+        alpn_protocol,
+    }
+}
+
+async fn connect(hostname: &str, port: u16) -> DenoResult<ConnInfo> {
+    let stream = TcpStream::connect((hostname, port)).await?;
+    let local_addr = stream.local_addr()?;
+    let remote_addr = stream.peer_addr()?;
+    Ok(conn_info(Stream::Tcp(stream), local_addr, remote_addr))
+}
+
+pub(crate) async fn net_connect(hostname: String, port: u16) -> JsResult<ConnInfo> {
+    connect(&hostname, port).await.into()
+}
+
+/// Bind synchronously, as `Deno.listen` returns the listener right away
+/// Bind a listener. `reuse_port` lets several listeners share the port,
+/// which only Linux balances between them; elsewhere it is ignored, as in Deno.
+pub(crate) fn bind(hostname: &str, port: u16, reuse_port: bool) -> DenoResult<TcpListener> {
+    #[cfg(target_os = "linux")]
+    if reuse_port {
+        return Ok(TcpListener::from_std(bind_reuse_port(hostname, port)?)?);
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = reuse_port;
+    let listener = std::net::TcpListener::bind((hostname, port))?;
+    Ok(TcpListener::from_std(listener)?)
+}
+
+/// `std::net::TcpListener::bind`, with `SO_REUSEPORT` set before binding
+#[cfg(target_os = "linux")]
+fn bind_reuse_port(hostname: &str, port: u16) -> DenoResult<std::net::TcpListener> {
+    use socket2::{Domain, Socket, Type};
+    use std::net::ToSocketAddrs;
+
+    let mut last_error = None;
+    for addr in (hostname, port).to_socket_addrs()? {
+        let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+        // std sets SO_REUSEADDR on Unix listeners as well
+        socket.set_reuse_address(true)?;
+        socket.set_reuse_port(true)?;
+        match socket.bind(&addr.into()) {
+            Ok(()) => {
+                socket.listen(128)?;
+                return Ok(socket.into());
+            }
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(last_error
+        .unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "could not resolve to any addresses",
+            )
+        })
+        .into())
+}
+
+fn listen(hostname: &str, port: u16, reuse_port: bool) -> DenoResult<ListenerInfo> {
+    let listener = bind(hostname, port, reuse_port)?;
+    let addr = listener.local_addr()?;
+    Ok(ListenerInfo {
+        rid: resources::insert_listener(Listener::new(listener, None)),
+        addr,
+    })
+}
+
+pub(crate) fn net_listen(hostname: String, port: u16, reuse_port: bool) -> JsResult<ListenerInfo> {
+    listen(&hostname, port, reuse_port).into()
+}
+
+async fn accept(rid: u32) -> DenoResult<ConnInfo> {
+    let listener = resources::listener(rid)?;
+    listener
+        .run(async {
+            let (stream, remote_addr) = listener.socket.accept().await?;
+            let local_addr = stream.local_addr()?;
+            let stream = match &listener.tls {
+                Some(acceptor) => Stream::Tls(Box::new(acceptor.accept(stream).await?.into())),
+                None => Stream::Tcp(stream),
+            };
+            Ok(conn_info(stream, local_addr, remote_addr))
+        })
+        .await?
+}
+
+pub(crate) async fn net_accept(rid: u32) -> JsResult<ConnInfo> {
+    accept(rid).await.into()
+}
+
+/// Read up to `len` bytes, returning `None` once the peer has closed its side
+async fn read(rid: u32, len: usize) -> DenoResult<Option<Vec<u8>>> {
+    let conn = resources::conn(rid)?;
+    let BufResult(result, buf) = conn
+        .run(async {
+            let buf = Vec::with_capacity(len);
+            match &conn.stream {
+                Stream::Tcp(stream) => {
+                    let mut stream = stream;
+                    stream.read(buf).await
+                }
+                Stream::Tls(stream) => stream.lock().await.read(buf).await,
+            }
+        })
+        .await?;
+    if result? == 0 && len > 0 {
+        return Ok(None);
+    }
+    Ok(Some(buf))
+}
+
+pub(crate) async fn net_read(rid: u32, len: usize) -> JsResult<Option<Vec<u8>>> {
+    read(rid, len).await.into()
+}
+
+async fn write(rid: u32, data: Vec<u8>) -> DenoResult<usize> {
+    let conn = resources::conn(rid)?;
+    let len = data.len();
+    conn.run(async {
+        match &conn.stream {
+            Stream::Tcp(stream) => {
+                let mut stream = stream;
+                stream.write_all(data).await.0
+            }
+            Stream::Tls(stream) => {
+                let mut stream = stream.lock().await;
+                stream.write_all(data).await.0?;
+                // TLS records are buffered until flushed
+                stream.flush().await
+            }
+        }
+    })
+    .await??;
+    Ok(len)
+}
+
+pub(crate) async fn net_write(rid: u32, data: TypedArray<'_, u8>) -> JsResult<usize> {
+    let Some(data) = data.as_bytes().map(<[u8]>::to_vec) else {
+        return JsResult::from(Err::<usize, _>(DenoError::Other(
+            "Buffer is detached".to_string(),
+        )));
+    };
+    write(rid, data).await.into()
+}
+
+/// Shut down the writing side, so the peer reads EOF
+async fn close_write(rid: u32) -> DenoResult<()> {
+    let conn = resources::conn(rid)?;
+    conn.run(async {
+        match &conn.stream {
+            Stream::Tcp(stream) => {
+                let mut stream = stream;
+                stream.shutdown().await
+            }
+            Stream::Tls(stream) => stream.lock().await.shutdown().await,
+        }
+    })
+    .await??;
+    Ok(())
+}
+
+pub(crate) async fn net_close_write(rid: u32) -> JsResult<()> {
+    close_write(rid).await.into()
+}
+
+pub(crate) fn net_close(rid: u32) -> JsResult<()> {
+    resources::close(rid).into()
+}
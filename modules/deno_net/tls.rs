@@ -0,0 +1,213 @@
+// TLS for Deno.connectTls / Deno.listenTls / Deno.startTls, using rustls
+use crate::resources::{self, Listener, Stream};
+use crate::tcp::{self, ConnInfo, ListenerInfo};
+use compio::net::TcpStream;
+use compio_tls::rustls::crypto::{CryptoProvider, ring};
+use compio_tls::rustls::pki_types::pem::PemObject;
+use compio_tls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use compio_tls::rustls::{ClientConfig, RootCertStore, ServerConfig};
+use compio_tls::{TlsAcceptor, TlsConnector};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+use utils::{DenoError, DenoResult, JsResult};
+
+/// Root certificate bundles on common systems, tried when `SSL_CERT_FILE`
+/// isn't set
+const CA_BUNDLE_PATHS: &[&str] = &[
+    "/etc/ssl/certs/ca-certificates.crt", // Debian, Ubuntu, Arch, Alpine
+    "/etc/pki/tls/certs/ca-bundle.crt",   // Fedora, RHEL
+    "/etc/ssl/ca-bundle.pem",             // openSUSE
+    "/etc/ssl/cert.pem",                  // macOS, BSD
+];
+
+/// Roots trusted by every client connection, besides `caCerts`
+static SYSTEM_ROOTS: LazyLock<Vec<CertificateDer<'static>>> = LazyLock::new(|| {
+    let path = std::env::var_os("SSL_CERT_FILE")
+        .map(PathBuf::from)
+        .or_else(|| {
+            CA_BUNDLE_PATHS
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.is_file())
+        });
+    path.and_then(|path| std::fs::read(path).ok())
+        .map(|pem| {
+            CertificateDer::pem_slice_iter(&pem)
+                .filter_map(Result::ok)
+                .collect()
+        })
+        .unwrap_or_default()
+});
+
+/// TLS options shared by `Deno.connectTls`, `Deno.listenTls` and
+/// `Deno.startTls`, with `certChain` / `privateKey` already mapped to
+/// `cert` / `key`
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// Extra PEM root certificates to trust
+    pub ca_certs: Vec<String>,
+    /// PEM certificate chain: the server's, or the client's for mutual TLS
+    pub cert: Option<String>,
+    /// PEM private key for `cert`
+    pub key: Option<String>,
+    pub alpn_protocols: Vec<String>,
+}
+
+impl<'js> rquickjs::FromJs<'js> for TlsOptions {
+    fn from_js(ctx: &rquickjs::Ctx<'js>, value: rquickjs::Value<'js>) -> rquickjs::Result<Self> {
+        if value.is_undefined() || value.is_null() {
+            return Ok(Self::default());
+        }
+        let obj = rquickjs::Object::from_js(ctx, value)?;
+        Ok(Self {
+            ca_certs: obj
+                .get::<_, Option<Vec<String>>>("caCerts")?
+                .unwrap_or_default(),
+            cert: obj.get("cert")?,
+            key: obj.get("key")?,
+            alpn_protocols: obj
+                .get::<_, Option<Vec<String>>>("alpnProtocols")?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+fn invalid_data(message: impl std::fmt::Display) -> DenoError {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string()).into()
+}
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(ring::default_provider())
+}
+
+fn parse_certs(pem: &str) -> DenoResult<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_slice_iter(pem.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| invalid_data(format!("Invalid certificate: {e}")))?;
+    if certs.is_empty() {
+        return Err(invalid_data("No certificates found"));
+    }
+    Ok(certs)
+}
+
+fn parse_key(pem: &str) -> DenoResult<PrivateKeyDer<'static>> {
+    PrivateKeyDer::from_pem_slice(pem.as_bytes())
+        .map_err(|e| invalid_data(format!("Invalid private key: {e}")))
+}
+
+fn alpn(protocols: &[String]) -> Vec<Vec<u8>> {
+    protocols
+        .iter()
+        .map(|protocol| protocol.as_bytes().to_vec())
+        .collect()
+}
+
+fn client_config(options: &TlsOptions) -> DenoResult<Arc<ClientConfig>> {
+    let mut roots = RootCertStore::empty();
+    roots.add_parsable_certificates(SYSTEM_ROOTS.iter().cloned());
+    for pem in &options.ca_certs {
+        for cert in parse_certs(pem)? {
+            roots.add(cert).map_err(invalid_data)?;
+        }
+    }
+
+    let builder = ClientConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .map_err(invalid_data)?
+        .with_root_certificates(roots);
+    let mut config = match (&options.cert, &options.key) {
+        (Some(cert), Some(key)) => builder
+            .with_client_auth_cert(parse_certs(cert)?, parse_key(key)?)
+            .map_err(invalid_data)?,
+        (None, None) => builder.with_no_client_auth(),
+        _ => {
+            return Err(DenoError::Other(
+                "A client certificate needs both cert and key".to_string(),
+            ));
+        }
+    };
+    config.alpn_protocols = alpn(&options.alpn_protocols);
+    Ok(Arc::new(config))
+}
+
+fn server_config(options: &TlsOptions) -> DenoResult<Arc<ServerConfig>> {
+    let (Some(cert), Some(key)) = (&options.cert, &options.key) else {
+        return Err(DenoError::Other(
+            "Deno.listenTls requires cert and key".to_string(),
+        ));
+    };
+    let mut config = ServerConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .map_err(invalid_data)?
+        .with_no_client_auth()
+        .with_single_cert(parse_certs(cert)?, parse_key(key)?)
+        .map_err(invalid_data)?;
+    config.alpn_protocols = alpn(&options.alpn_protocols);
+    Ok(Arc::new(config))
+}
+
+/// Handshake as a client over an established TCP connection
+async fn handshake(
+    stream: TcpStream,
+    hostname: &str,
+    options: &TlsOptions,
+) -> DenoResult<ConnInfo> {
+    let connector = TlsConnector::from(client_config(options)?);
+    let local_addr = stream.local_addr()?;
+    let remote_addr = stream.peer_addr()?;
+    let stream = connector.connect(hostname, stream).await?;
+    Ok(tcp::conn_info(
+        Stream::Tls(Box::new(stream.into())),
+        local_addr,
+        remote_addr,
+    ))
+}
+
+async fn connect_tls(hostname: &str, port: u16, options: &TlsOptions) -> DenoResult<ConnInfo> {
+    let stream = TcpStream::connect((hostname, port)).await?;
+    handshake(stream, hostname, options).await
+}
+
+pub(crate) async fn net_connect_tls(
+    hostname: String,
+    port: u16,
+    options: TlsOptions,
+) -> JsResult<ConnInfo> {
+    connect_tls(&hostname, port, &options).await.into()
+}
+
+fn listen_tls(
+    hostname: &str,
+    port: u16,
+    options: &TlsOptions,
+    reuse_port: bool,
+) -> DenoResult<ListenerInfo> {
+    let acceptor = TlsAcceptor::from(server_config(options)?);
+    let listener = tcp::bind(hostname, port, reuse_port)?;
+    let addr = listener.local_addr()?;
+    Ok(ListenerInfo {
+        rid: resources::insert_listener(Listener::new(listener, Some(acceptor))),
+        addr,
+    })
+}
+
+pub(crate) fn net_listen_tls(
+    hostname: String,
+    port: u16,
+    options: TlsOptions,
+    reuse_port: bool,
+) -> JsResult<ListenerInfo> {
+    listen_tls(&hostname, port, &options, reuse_port).into()
+}
+
+pub(crate) async fn net_start_tls(
+    rid: u32,
+    hostname: String,
+    options: TlsOptions,
+) -> JsResult<ConnInfo> {
+    let result = match resources::take_tcp_conn(rid) {
+        Ok(stream) => handshake(stream, &hostname, &options).await,
+        Err(e) => Err(e),
+    };
+    result.into()
+}
@@ -1,12 +1,165 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
 // Deno namespace binding point - individual modules define their own APIs
 
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
 // @ts-ignore: mdeno internal API
 const fs = globalThis.__mdeno__.fs;
 // @ts-ignore: mdeno internal API
 const os = globalThis.__mdeno__.os;
+// @ts-ignore: mdeno internal API
+const net = globalThis.__mdeno__.net;
+// @ts-ignore: mdeno internal API
+const permissions = globalThis.__mdeno__.permissions;
+// @ts-ignore: mdeno internal API
+const kv = globalThis.__mdeno__.kv;
+// @ts-ignore: mdeno internal API
+const command = globalThis.__mdeno__.command;
+
+// Size of each read made by readLines()
+const READ_LINES_BUFFER_SIZE = 4096;
+
+// Yield the lines of `reader` without their "\n", "\r\n" or "\r" terminators,
+// including an unterminated last line
+async function* readLines(reader: {
+  read(buffer: Uint8Array): Promise<number | null>;
+}): AsyncGenerator<string> {
+  const CR = 0x0d;
+  const LF = 0x0a;
+  const decoder = new TextDecoder();
+  const buffer = new Uint8Array(READ_LINES_BUFFER_SIZE);
+  // Bytes of the current line so far. Lines are split on bytes, since CR and
+  // LF never occur inside a multi-byte UTF-8 sequence.
+  let pending = new Uint8Array(0);
+  // A CR ending the previous read may be followed by LF in the next one
+  let skipLF = false;
+  while (true) {
+    const n = await reader.read(buffer);
+    if (n === null) break;
+    const chunk = new Uint8Array(pending.length + n);
+    chunk.set(pending);
+    chunk.set(buffer.subarray(0, n), pending.length);
+
+    let start = 0;
+    if (skipLF && pending.length === 0 && chunk[0] === LF) start = 1;
+    skipLF = false;
+    for (let i = start; i < chunk.length; i++) {
+      const byte = chunk[i];
+      if (byte !== LF && byte !== CR) continue;
+      yield decoder.decode(chunk.subarray(start, i));
+      if (byte === CR) {
+        if (i + 1 === chunk.length) {
+          skipLF = true;
+        } else if (chunk[i + 1] === LF) {
+          i++;
+        }
+      }
+      start = i + 1;
+    }
+    pending = chunk.slice(start);
+  }
+  if (pending.length > 0) yield decoder.decode(pending);
+}
+
+// Size of each read made by readAll() and iter() unless `bufSize` is given
+const DEFAULT_BUFFER_SIZE = 32 * 1024;
+
+interface Reader {
+  read(buffer: Uint8Array): Promise<number | null>;
+}
+
+interface ReaderSync {
+  readSync(buffer: Uint8Array): number | null;
+}
+
+interface Writer {
+  write(data: Uint8Array): Promise<number>;
+}
+
+interface WriterSync {
+  writeSync(data: Uint8Array): number;
+}
+
+// Join chunks into a single array
+function concat(chunks: Uint8Array[]): Uint8Array {
+  let length = 0;
+  for (const chunk of chunks) length += chunk.length;
+  const result = new Uint8Array(length);
+  let offset = 0;
+  for (const chunk of chunks) {
+    result.set(chunk, offset);
+    offset += chunk.length;
+  }
+  return result;
+}
+
+// https://docs.deno.com/api/deno/~/Deno.iter
+// Each chunk is a copy, so it stays valid after the next read
+async function* iter(
+  reader: Reader,
+  options?: { bufSize?: number },
+): AsyncGenerator<Uint8Array> {
+  const buffer = new Uint8Array(options?.bufSize ?? DEFAULT_BUFFER_SIZE);
+  while (true) {
+    const n = await reader.read(buffer);
+    if (n === null) break;
+    yield buffer.slice(0, n);
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.iterSync
+function* iterSync(
+  reader: ReaderSync,
+  options?: { bufSize?: number },
+): Generator<Uint8Array> {
+  const buffer = new Uint8Array(options?.bufSize ?? DEFAULT_BUFFER_SIZE);
+  while (true) {
+    const n = reader.readSync(buffer);
+    if (n === null) break;
+    yield buffer.slice(0, n);
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.readAll
+async function readAll(reader: Reader): Promise<Uint8Array> {
+  const chunks = [];
+  for await (const chunk of iter(reader)) chunks.push(chunk);
+  return concat(chunks);
+}
+
+// https://docs.deno.com/api/deno/~/Deno.readAllSync
+function readAllSync(reader: ReaderSync): Uint8Array {
+  return concat([...iterSync(reader)]);
+}
+
+// https://docs.deno.com/api/deno/~/Deno.writeAll
+async function writeAll(writer: Writer, data: Uint8Array): Promise<void> {
+  let written = 0;
+  while (written < data.length) {
+    written += await writer.write(data.subarray(written));
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.writeAllSync
+function writeAllSync(writer: WriterSync, data: Uint8Array): void {
+  let written = 0;
+  while (written < data.length) {
+    written += writer.writeSync(data.subarray(written));
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.stdin
+const stdin = Object.assign(os.stdin, {
+  readLines(): AsyncGenerator<string> {
+    return readLines(os.stdin);
+  },
+  lines(): AsyncGenerator<string> {
+    return readLines(os.stdin);
+  },
+});
 
-const permissionStatus = new os.PermissionStatus("granted", false);
+// Resources with fixed IDs; everything else is a file
+const stdio = [stdin, os.stdout, os.stderr];
 
 const denoNs = {
   // Command line arguments
@@ -14,37 +167,138 @@ const denoNs = {
 
   // Process APIs
   cwd: fs.cwd,
+  chdir: fs.chdir,
 
   // File System APIs
   readFileSync: fs.readFileSync,
+  readFile: fs.readFile,
   readTextFileSync: fs.readTextFileSync,
+  readTextFile: fs.readTextFile,
   writeFileSync: fs.writeFileSync,
+  writeFile: fs.writeFile,
   writeTextFileSync: fs.writeTextFileSync,
+  writeTextFile: fs.writeTextFile,
   statSync: fs.statSync,
+  stat: fs.stat,
   lstatSync: fs.lstatSync,
+  lstat: fs.lstat,
   mkdirSync: fs.mkdirSync,
+  mkdir: fs.mkdir,
   removeSync: fs.removeSync,
+  remove: fs.remove,
   copyFileSync: fs.copyFileSync,
+  copyFile: fs.copyFile,
   readDirSync: fs.readDirSync,
+  readDir: fs.readDir,
   renameSync: fs.renameSync,
+  rename: fs.rename,
+  link: fs.link,
+  linkSync: fs.linkSync,
+  symlink: fs.symlink,
+  symlinkSync: fs.symlinkSync,
+  readLink: fs.readLink,
+  readLinkSync: fs.readLinkSync,
   realPathSync: fs.realPathSync,
+  realPath: fs.realPath,
   truncateSync: fs.truncateSync,
+  truncate: fs.truncate,
+  chmodSync: fs.chmodSync,
+  chmod: fs.chmod,
+  chownSync: fs.chownSync,
+  chown: fs.chown,
+  openSync: fs.openSync,
+  open: fs.open,
+  seekSync: fs.seekSync,
+  seek: fs.seek,
+  fstatSync: fs.fstatSync,
+  fstat: fs.fstat,
+  fsyncSync: fs.fsyncSync,
+  fsync: fs.fsync,
+  fdatasyncSync: fs.fdatasyncSync,
+  fdatasync: fs.fdatasync,
+  utimeSync: fs.utimeSync,
+  utime: fs.utime,
+  futimeSync: fs.futimeSync,
+  futime: fs.futime,
+  FsFile: fs.FsFile,
+  SeekMode: fs.SeekMode,
   makeTempDirSync: fs.makeTempDirSync,
+  makeTempDir: fs.makeTempDir,
   makeTempFileSync: fs.makeTempFileSync,
+  makeTempFile: fs.makeTempFile,
+  watchFs: fs.watchFs,
+  FsWatcher: fs.FsWatcher,
+
+  // I/O APIs
+  stdin,
+  stdout: os.stdout,
+  stderr: os.stderr,
+  iter,
+  iterSync,
+  readAll,
+  readAllSync,
+  writeAll,
+  writeAllSync,
+
+  // Network APIs
+  connect: net.connect,
+  listen: net.listen,
+  connectTls: net.connectTls,
+  listenTls: net.listenTls,
+  startTls: net.startTls,
+  serve: net.serve,
+
+  // Subprocess APIs
+  Command: command.Command,
+  ChildProcess: command.ChildProcess,
+
+  // Resource APIs
+  // https://docs.deno.com/api/deno/~/Deno.close
+  close(rid: number): void {
+    // Files and stdio are the only resources so far; sockets and child
+    // processes will need their own handlers here
+    if (rid < stdio.length) {
+      stdio[rid].close();
+    } else {
+      fs.close(rid);
+    }
+  },
+
+  // Console APIs
+  inspect: __internal.inspect,
 
   // OS APIs
+  version: os.version,
+  hostname: os.hostname,
+  username: os.username,
+  osUptime: os.osUptime,
+  loadavg: os.loadavg,
+  osRelease: os.osRelease,
+  uid: os.uid,
+  gid: os.gid,
+  systemMemoryInfo: os.systemMemoryInfo,
+  networkInterfaces: os.networkInterfaces,
   exit: os.exit,
   env: os.env,
+  addSignalListener: os.addSignalListener,
+  removeSignalListener: os.removeSignalListener,
 
-  // Permission APIs - always grant
-  permissions: {
-    query: (_desc: unknown) => Promise.resolve(permissionStatus),
-    querySync: (_desc: unknown) => permissionStatus,
-    revoke: (_desc: unknown) => Promise.resolve(permissionStatus),
-    revokeSync: (_desc: unknown) => permissionStatus,
-    request: (_desc: unknown) => Promise.resolve(permissionStatus),
-    requestSync: (_desc: unknown) => permissionStatus,
-  },
+  // Permission APIs
+  permissions: permissions.permissions,
+  PermissionStatus: permissions.PermissionStatus,
+
+  // KV APIs
+  openKv: kv.openKv,
+  Kv: kv.Kv,
+  AtomicOperation: kv.AtomicOperation,
+  KvListIterator: kv.KvListIterator,
+
+  // QuickJS diagnostics (mdeno only)
+  core: Object.freeze({
+    heapStats: __internal.core.heapStats,
+    gc: __internal.core.gc,
+    eventLoopHasMoreWork: __internal.core.eventLoopHasMoreWork,
+  }),
 };
 
 // Add noColor as a getter
@@ -54,6 +308,26 @@ Object.defineProperty(denoNs, "noColor", {
   },
 });
 
+// Add mainModule as a getter, since the runtime sets it once the entry
+// module is known
+Object.defineProperty(denoNs, "mainModule", {
+  get() {
+    return __internal.mainModule;
+  },
+});
+
+// Add pid and ppid as getters, so they can't be reassigned
+Object.defineProperty(denoNs, "pid", {
+  get() {
+    return os.pid;
+  },
+});
+Object.defineProperty(denoNs, "ppid", {
+  get() {
+    return os.ppid;
+  },
+});
+
 // Add build as a getter
 Object.defineProperty(denoNs, "build", {
   get() {
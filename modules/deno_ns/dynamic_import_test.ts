@@ -0,0 +1,33 @@
+Deno.test("dynamic import() of a local module", async () => {
+  const mod = await import("./testdata/sub.js");
+  if (mod.value !== 42) throw new Error(`Expected 42, got ${mod.value}`);
+  if (mod.default("mdeno") !== "Hello, mdeno!") {
+    throw new Error("Unexpected default export");
+  }
+});
+
+Deno.test("dynamic import() of a computed specifier", async () => {
+  const name = "sub";
+  const mod = await import(`./testdata/${name}.js`);
+  if (mod.value !== 42) throw new Error(`Expected 42, got ${mod.value}`);
+});
+
+Deno.test("dynamic import() of a missing module rejects", async () => {
+  const specifier = "./testdata/missing.js";
+  let error;
+  try {
+    await import(specifier);
+  } catch (e) {
+    error = e;
+  }
+  if (!error) throw new Error("Expected the import to fail");
+});
+
+Deno.test("dynamic import() of a local TypeScript module", async () => {
+  const name = "typed";
+  const mod = await import(`./testdata/${name}.ts`);
+  if (mod.answer.value !== 42) throw new Error(`Expected 42, got ${mod.answer.value}`);
+  if (mod.default("mdeno") !== "Hello, mdeno!") {
+    throw new Error("Unexpected default export");
+  }
+});
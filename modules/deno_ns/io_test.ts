@@ -0,0 +1,106 @@
+// Deno.readAll, Deno.writeAll and Deno.iter E2E tests
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+// More than one default-sized read
+const data = encoder.encode("mdeno ".repeat(10_000));
+
+function assertBytes(actual: Uint8Array, expected: Uint8Array) {
+  if (actual.length !== expected.length) {
+    throw new Error(`Expected ${expected.length} bytes, got ${actual.length}`);
+  }
+  for (let i = 0; i < expected.length; i++) {
+    if (actual[i] !== expected[i]) throw new Error(`Byte ${i} differs`);
+  }
+}
+
+Deno.test("io - writeAllSync and readAllSync round trip a file", () => {
+  const path = Deno.makeTempFileSync();
+  try {
+    const file = Deno.openSync(path, { write: true });
+    Deno.writeAllSync(file, data);
+    file.close();
+
+    const reader = Deno.openSync(path);
+    assertBytes(Deno.readAllSync(reader), data);
+    reader.close();
+  } finally {
+    Deno.removeSync(path);
+  }
+});
+
+Deno.test("io - iterSync yields chunks of bufSize", () => {
+  const path = Deno.makeTempFileSync();
+  try {
+    Deno.writeTextFileSync(path, "abcdefg");
+    const file = Deno.openSync(path);
+    const chunks = [...Deno.iterSync(file, { bufSize: 3 })];
+    file.close();
+    const texts = chunks.map((chunk) => decoder.decode(chunk));
+    if (texts.join(",") !== "abc,def,g") {
+      throw new Error(`Unexpected chunks: ${texts}`);
+    }
+  } finally {
+    Deno.removeSync(path);
+  }
+});
+
+Deno.test("io - readAllSync of an empty reader", () => {
+  const reader = { readSync: () => null };
+  if (Deno.readAllSync(reader).length !== 0) {
+    throw new Error("Expected no bytes");
+  }
+});
+
+Deno.test("io - writeAll retries short writes", async () => {
+  const written: number[] = [];
+  const writer = {
+    write(chunk: Uint8Array): Promise<number> {
+      const n = Math.min(chunk.length, 5);
+      written.push(...chunk.subarray(0, n));
+      return Promise.resolve(n);
+    },
+  };
+  await Deno.writeAll(writer, encoder.encode("hello world"));
+  if (decoder.decode(new Uint8Array(written)) !== "hello world") {
+    throw new Error(`Unexpected bytes: ${written}`);
+  }
+});
+
+Deno.test("io - writeAll and readAll over a connection", async () => {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 0 });
+  const sent = (async () => {
+    const conn = await listener.accept();
+    await Deno.writeAll(conn, data);
+    conn.close();
+  })();
+  const conn = await Deno.connect({
+    hostname: "127.0.0.1",
+    port: listener.addr.port,
+  });
+  const received = await Deno.readAll(conn);
+  conn.close();
+  await sent;
+  listener.close();
+  assertBytes(received, data);
+});
+
+Deno.test("io - iter yields every chunk", async () => {
+  const parts = ["ab", "cd", "e"].map((part) => encoder.encode(part));
+  const reader = {
+    read(buffer: Uint8Array): Promise<number | null> {
+      const part = parts.shift();
+      if (part === undefined) return Promise.resolve(null);
+      buffer.set(part);
+      return Promise.resolve(part.length);
+    },
+  };
+  const chunks: string[] = [];
+  for await (const chunk of Deno.iter(reader)) {
+    chunks.push(decoder.decode(chunk));
+  }
+  if (chunks.join(",") !== "ab,cd,e") {
+    throw new Error(`Unexpected chunks: ${chunks}`);
+  }
+});
@@ -0,0 +1,5 @@
+// Loaded by dynamic_import_test.ts at runtime
+export const value = 42;
+export default function greet(name) {
+  return `Hello, ${name}!`;
+}
@@ -0,0 +1,11 @@
+// Loaded by dynamic_import_test.ts at runtime, which strips its types
+import greet, { value } from "./sub.js";
+
+interface Answer {
+  value: number;
+}
+
+export const answer: Answer = { value };
+export default function greetTyped(name: string): string {
+  return greet(name);
+}
@@ -5,29 +5,240 @@ const __internal = globalThis[Symbol.for("mdeno.internal")];
 
 const noColorValue = __internal.noColor ?? false;
 
-class PermissionStatus {
-  #state: string;
-  #partial: boolean;
+type Listener = ((event: unknown) => void) | {
+  handleEvent(event: unknown): void;
+};
 
-  constructor(state: string = "granted", partial: boolean = false) {
-    this.#state = state;
-    this.#partial = partial;
+// "unload" listeners are run by the exit hooks, which only accept functions,
+// so they aren't kept with the other listeners of globalThis
+const unloadHooks = new Map<Listener, () => void>();
+const eventTarget = EventTarget.prototype;
+
+function createEvent(type: string) {
+  return { type, target: globalThis, currentTarget: globalThis };
+}
+
+function callListener(listener: Listener, event: unknown): void {
+  if (typeof listener === "function") {
+    listener.call(globalThis, event);
+  } else {
+    listener.handleEvent(event);
+  }
+}
+
+function addEventListener(
+  type: string,
+  listener: Listener | null,
+  options?: boolean | AddEventListenerOptions,
+): void {
+  if (String(type) !== "unload") {
+    eventTarget.addEventListener.call(globalThis, type, listener, options);
+    return;
+  }
+  if (listener === null || listener === undefined || unloadHooks.has(listener)) {
+    return;
+  }
+  const hook = () => callListener(listener, createEvent("unload"));
+  unloadHooks.set(listener, hook);
+  __internal.exitHooks.register(hook);
+}
+
+function removeEventListener(
+  type: string,
+  listener: Listener | null,
+  options?: boolean | EventListenerOptions,
+): void {
+  if (String(type) !== "unload") {
+    eventTarget.removeEventListener.call(globalThis, type, listener, options);
+    return;
+  }
+  if (listener === null || listener === undefined) {
+    return;
+  }
+  const hook = unloadHooks.get(listener);
+  if (hook) {
+    unloadHooks.delete(listener);
+    __internal.exitHooks.unregister(hook);
+  }
+}
+
+Object.defineProperties(globalThis, {
+  addEventListener: {
+    value: addEventListener,
+    writable: true,
+    enumerable: false,
+    configurable: true,
+  },
+  removeEventListener: {
+    value: removeEventListener,
+    writable: true,
+    enumerable: false,
+    configurable: true,
+  },
+});
+
+// Called by the executor once the main module has finished evaluating
+__internal.dispatchLoad = function (): void {
+  globalThis.dispatchEvent(new Event("load"));
+};
+
+// https://docs.deno.com/api/deno/~/Deno.SystemMemoryInfo
+interface SystemMemoryInfo {
+  total: number;
+  free: number;
+  available: number;
+  buffers: number;
+  cached: number;
+  swapTotal: number;
+  swapFree: number;
+}
+
+// https://docs.deno.com/api/deno/~/Deno.NetworkInterfaceInfo
+interface NetworkInterfaceInfo {
+  family: "IPv4" | "IPv6";
+  name: string;
+  address: string;
+  netmask: string;
+  scopeid: number | null;
+  cidr: string;
+  mac: string;
+}
+
+function checkSignal(signal: string): void {
+  if (signal !== "beforeExit") {
+    throw new TypeError(`Unsupported signal: ${signal}`);
+  }
+}
+
+// Whether Deno.stdin is in raw mode, so it can be restored on exit
+let rawMode = false;
+let restoreOnUnload = false;
+
+// https://docs.deno.com/api/deno/~/Deno.stdin.setRaw
+function stdinSetRaw(mode: boolean, options?: { cbreak?: boolean }): void {
+  const cbreak = !!options?.cbreak;
+  __internal.stdinSetRaw(!!mode, cbreak);
+  rawMode = !!mode;
+  if (rawMode && !restoreOnUnload) {
+    // Leave the terminal usable even if the script never turns raw mode off
+    restoreOnUnload = true;
+    addEventListener("unload", () => {
+      if (rawMode) {
+        rawMode = false;
+        __internal.stdinSetRaw(false, false);
+      }
+    });
+  }
+}
+
+// Size of each read made by Deno.stdin.readable
+const STDIN_CHUNK_SIZE = 16 * 1024;
+
+// https://docs.deno.com/api/deno/~/Deno.stdin
+class Stdin {
+  #readable: ReadableStream<Uint8Array> | undefined;
+
+  get rid(): number {
+    return 0;
+  }
+
+  readSync(buffer: Uint8Array): number | null {
+    const data = __internal.stdio.readSync(buffer.byteLength);
+    if (data == null) return null;
+    buffer.set(data);
+    return data.length;
+  }
+
+  async read(buffer: Uint8Array): Promise<number | null> {
+    const data = await __internal.stdio.read(buffer.byteLength);
+    if (data == null) return null;
+    buffer.set(data);
+    return data.length;
+  }
+
+  close(): void {
+    __internal.stdio.close(0);
+  }
+
+  isTerminal(): boolean {
+    return __internal.stdio.isTerminal(0);
+  }
+
+  setRaw(mode: boolean, options?: { cbreak?: boolean }): void {
+    stdinSetRaw(mode, options);
+  }
+
+  // Chunks as they arrive, until EOF. Nothing is read ahead, since a pending
+  // read would keep the process waiting on stdin.
+  get readable(): ReadableStream<Uint8Array> {
+    this.#readable ??= new ReadableStream({
+      async pull(controller) {
+        const data = await __internal.stdio.read(STDIN_CHUNK_SIZE);
+        if (data == null) {
+          controller.close();
+        } else {
+          controller.enqueue(new Uint8Array(data));
+        }
+      },
+    }, { highWaterMark: 0 });
+    return this.#readable;
+  }
+}
+
+// Deno.stdout and Deno.stderr, which differ only in their resource ID
+class StdWriter {
+  #rid: number;
+  #writable: WritableStream<Uint8Array> | undefined;
+
+  constructor(rid: number) {
+    this.#rid = rid;
+  }
+
+  get rid(): number {
+    return this.#rid;
+  }
+
+  writeSync(data: Uint8Array): number {
+    return __internal.stdio.writeSync(this.#rid, data);
+  }
+
+  write(data: Uint8Array): Promise<number> {
+    return __internal.stdio.write(this.#rid, data);
   }
 
-  get state(): string {
-    return this.#state;
+  close(): void {
+    __internal.stdio.close(this.#rid);
   }
 
-  get partial(): boolean {
-    return this.#partial;
+  isTerminal(): boolean {
+    return __internal.stdio.isTerminal(this.#rid);
   }
 
-  get onchange(): null {
-    return null;
+  // Each chunk is written in full before the next
+  get writable(): WritableStream<Uint8Array> {
+    this.#writable ??= new WritableStream({
+      write: async (chunk) => {
+        let written = 0;
+        while (written < chunk.length) {
+          written += await this.write(chunk.subarray(written));
+        }
+      },
+    });
+    return this.#writable;
   }
+}
 
-  set onchange(_handler: unknown) {
-    // Ignore onchange setter
+// https://docs.deno.com/api/deno/~/Deno.stdout
+class Stdout extends StdWriter {
+  constructor() {
+    super(1);
+  }
+}
+
+// https://docs.deno.com/api/deno/~/Deno.stderr
+class Stderr extends StdWriter {
+  constructor() {
+    super(2);
   }
 }
 
@@ -40,8 +251,23 @@ Object.assign(globalThis.__mdeno__.os, {
   },
 
   env: {
-    get: function (key: string): string | undefined {
-      return __internal.env.get(key);
+    // defaultValue is an mdeno extension, returned when the variable is unset
+    get: function (key: string, defaultValue?: string): string | undefined {
+      return __internal.env.get(key) ?? defaultValue;
+    },
+    // mdeno extension: throws Deno.errors.NotFound when the variable is unset
+    getRequired: function (key: string): string {
+      const value = __internal.env.get(key);
+      if (value === undefined) {
+        // @ts-ignore: mdeno internal API
+        throw new globalThis.__mdeno__.errors.NotFound(
+          `Missing environment variable: ${key}`,
+        );
+      }
+      return value;
+    },
+    getOrThrow: function (key: string): string {
+      return this.getRequired(key);
     },
     set: function (key: string, value: string): void {
       __internal.env.set(key, value);
@@ -53,7 +279,19 @@ Object.assign(globalThis.__mdeno__.os, {
       return __internal.env.has(key);
     },
     toObject: function (): Record<string, string> {
-      return __internal.env.toObject();
+      return Object.freeze(__internal.env.toObject());
+    },
+    entries: function (): IterableIterator<[string, string]> {
+      return Object.entries(__internal.env.toObject())[Symbol.iterator]();
+    },
+    keys: function (): IterableIterator<string> {
+      return Object.keys(__internal.env.toObject())[Symbol.iterator]();
+    },
+    values: function (): IterableIterator<string> {
+      return Object.values(__internal.env.toObject())[Symbol.iterator]();
+    },
+    [Symbol.iterator]: function (): IterableIterator<[string, string]> {
+      return Object.entries(__internal.env.toObject())[Symbol.iterator]();
     },
   },
 
@@ -65,5 +303,72 @@ Object.assign(globalThis.__mdeno__.os, {
     return __internal.build;
   },
 
-  PermissionStatus: PermissionStatus,
+  version: Object.freeze(__internal.version),
+
+  get pid(): number {
+    return __internal.pid;
+  },
+
+  get ppid(): number {
+    return __internal.ppid;
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.hostname
+  hostname: function (): string {
+    return __internal.hostname();
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.username
+  username: function (): string {
+    return __internal.username();
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.osUptime
+  osUptime: function (): number {
+    return __internal.osUptime();
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.loadavg
+  loadavg: function (): number[] {
+    return __internal.loadavg();
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.osRelease
+  osRelease: function (): string {
+    return __internal.osRelease();
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.uid
+  uid: function (): number | null {
+    return __internal.uid() ?? null;
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.gid
+  gid: function (): number | null {
+    return __internal.gid() ?? null;
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.systemMemoryInfo
+  systemMemoryInfo: function (): SystemMemoryInfo {
+    return __internal.systemMemoryInfo();
+  },
+
+  // https://docs.deno.com/api/deno/~/Deno.networkInterfaces
+  networkInterfaces: function (): NetworkInterfaceInfo[] {
+    return __internal.networkInterfaces();
+  },
+
+  stdin: new Stdin(),
+  stdout: new Stdout(),
+  stderr: new Stderr(),
+
+  addSignalListener: function (signal: string, handler: () => void): void {
+    checkSignal(signal);
+    __internal.exitHooks.register(handler);
+  },
+
+  removeSignalListener: function (signal: string, handler: () => void): void {
+    checkSignal(signal);
+    __internal.exitHooks.unregister(handler);
+  },
 });
@@ -0,0 +1,135 @@
+Deno.test("Deno.env.toObject returns a frozen snapshot", () => {
+  Deno.env.set("MDENO_ENV_TEST", "1");
+  const env = Deno.env.toObject();
+  Deno.env.set("MDENO_ENV_TEST", "2");
+  if (env.MDENO_ENV_TEST !== "1") throw new Error("snapshot changed");
+  if (!Object.isFrozen(env)) throw new Error("snapshot is not frozen");
+  if (Deno.env.toObject() === env) throw new Error("snapshot was reused");
+  Deno.env.delete("MDENO_ENV_TEST");
+});
+
+Deno.test("Deno.env.has", () => {
+  Deno.env.set("MDENO_ENV_TEST", "1");
+  if (!Deno.env.has("MDENO_ENV_TEST")) throw new Error("expected variable");
+  Deno.env.delete("MDENO_ENV_TEST");
+  if (Deno.env.has("MDENO_ENV_TEST")) throw new Error("expected no variable");
+});
+
+Deno.test("Deno.env iterators", () => {
+  Deno.env.set("MDENO_ENV_TEST", "value");
+  const entries = new Map(Deno.env);
+  if (entries.get("MDENO_ENV_TEST") !== "value") throw new Error("Symbol.iterator");
+  if (!new Map(Deno.env.entries()).has("MDENO_ENV_TEST")) throw new Error("entries");
+  if (![...Deno.env.keys()].includes("MDENO_ENV_TEST")) throw new Error("keys");
+  if (![...Deno.env.values()].includes("value")) throw new Error("values");
+  Deno.env.delete("MDENO_ENV_TEST");
+});
+
+Deno.test("Deno.version reports component versions", () => {
+  const { deno, mdeno, quickjs, typescript } = Deno.version;
+  for (const version of [deno, mdeno, quickjs, typescript]) {
+    if (typeof version !== "string" || version.length === 0) {
+      throw new Error(`unexpected version: ${version}`);
+    }
+  }
+  if (!Object.isFrozen(Deno.version)) {
+    throw new Error("Deno.version should be frozen");
+  }
+});
+
+Deno.test("Deno.hostname, Deno.username and Deno.build.locale", () => {
+  for (const value of [Deno.hostname(), Deno.username(), Deno.build.locale]) {
+    if (typeof value !== "string" || value.length === 0) {
+      throw new Error(`unexpected value: ${value}`);
+    }
+  }
+});
+
+Deno.test("Deno.env.get with a default and getRequired", () => {
+  Deno.env.delete("MDENO_ENV_TEST");
+  const env = Deno.env as typeof Deno.env & {
+    get(key: string, defaultValue?: string): string | undefined;
+    getRequired(key: string): string;
+    getOrThrow(key: string): string;
+  };
+  if (env.get("MDENO_ENV_TEST", "3000") !== "3000") throw new Error("default");
+  let error;
+  try {
+    env.getRequired("MDENO_ENV_TEST");
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Deno.errors.NotFound)) throw new Error("getRequired");
+
+  Deno.env.set("MDENO_ENV_TEST", "8080");
+  if (env.get("MDENO_ENV_TEST", "3000") !== "8080") throw new Error("set value");
+  if (env.getOrThrow("MDENO_ENV_TEST") !== "8080") throw new Error("getOrThrow");
+  Deno.env.delete("MDENO_ENV_TEST");
+});
+
+Deno.test("Deno.osUptime counts up from boot", () => {
+  const first = Deno.osUptime();
+  if (typeof first !== "number" || first < 0) {
+    throw new Error(`unexpected uptime: ${first}`);
+  }
+  if (Deno.build.os === "linux" && first === 0) {
+    throw new Error("expected a non-zero uptime on Linux");
+  }
+  const second = Deno.osUptime();
+  if (second < first) throw new Error(`uptime went back: ${first} -> ${second}`);
+});
+
+Deno.test("Deno.pid and Deno.ppid", () => {
+  if (!Number.isInteger(Deno.pid) || Deno.pid <= 0) throw new Error(`pid: ${Deno.pid}`);
+  if (!Number.isInteger(Deno.ppid) || Deno.ppid <= 0) throw new Error(`ppid: ${Deno.ppid}`);
+  if (Deno.pid === Deno.ppid) throw new Error("pid and ppid are the same");
+  try {
+    // @ts-ignore: checking that the property is read-only
+    Deno.pid = 1;
+  } catch {
+    // Assigning to a getter throws in strict mode
+  }
+  if (Deno.pid === 1) throw new Error("pid was reassigned");
+});
+
+Deno.test("Deno.systemMemoryInfo reports memory in bytes", () => {
+  const info = Deno.systemMemoryInfo();
+  for (
+    const key of ["total", "free", "available", "buffers", "cached", "swapTotal", "swapFree"]
+  ) {
+    const value = info[key as keyof typeof info];
+    if (typeof value !== "number" || value < 0) throw new Error(`${key}: ${value}`);
+  }
+  if (info.total <= 0 || info.free > info.total) throw new Error(`total: ${info.total}`);
+});
+
+Deno.test("Deno.networkInterfaces lists addresses", () => {
+  const interfaces = Deno.networkInterfaces();
+  if (!Array.isArray(interfaces)) throw new Error("not an array");
+  for (const info of interfaces) {
+    if (info.family !== "IPv4" && info.family !== "IPv6") throw new Error(info.family);
+    if (!info.cidr.startsWith(`${info.address}/`)) throw new Error(info.cidr);
+    if (!/^([0-9a-f]{2}:){5}[0-9a-f]{2}$/.test(info.mac)) throw new Error(info.mac);
+    if ((info.family === "IPv4") !== (info.scopeid === null)) throw new Error("scopeid");
+  }
+  const loopback = interfaces.find((info) => info.address === "127.0.0.1");
+  if (loopback && (loopback.netmask !== "255.0.0.0" || loopback.cidr !== "127.0.0.1/8")) {
+    throw new Error(`loopback: ${JSON.stringify(loopback)}`);
+  }
+});
+
+Deno.test("Deno.loadavg, Deno.osRelease, Deno.uid and Deno.gid", () => {
+  const loads = Deno.loadavg();
+  if (loads.length !== 3 || loads.some((load) => typeof load !== "number" || load < 0)) {
+    throw new Error(`loadavg: ${loads}`);
+  }
+  const release = Deno.osRelease();
+  if (typeof release !== "string" || release === "") throw new Error("osRelease");
+  if (Deno.build.os === "windows") {
+    if (Deno.uid() !== null || Deno.gid() !== null) throw new Error("uid/gid on Windows");
+  } else {
+    if (!Number.isInteger(Deno.uid()) || !Number.isInteger(Deno.gid())) {
+      throw new Error(`uid: ${Deno.uid()}, gid: ${Deno.gid()}`);
+    }
+  }
+});
@@ -0,0 +1,77 @@
+// Process exit hooks for "unload" and "beforeExit" listeners
+
+use rquickjs::{Ctx, Function, JsLifetime, Object, Persistent, Result, Value, class::Trace};
+use std::sync::{Arc, Mutex, PoisonError};
+
+#[derive(Clone, Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct ExitHooks {
+    #[qjs(skip_trace)]
+    #[allow(clippy::arc_with_non_send_sync)] // JavaScript values aren't Send/Sync
+    inner: Arc<Mutex<Vec<Persistent<Function<'static>>>>>,
+}
+
+impl Default for ExitHooks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[rquickjs::methods]
+impl ExitHooks {
+    #[qjs(constructor)]
+    #[allow(clippy::arc_with_non_send_sync)] // JavaScript values aren't Send/Sync
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Register a handler to be called before the process exits
+    pub fn register<'js>(&self, ctx: Ctx<'js>, func: Function<'js>) {
+        let mut hooks = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        hooks.push(Persistent::save(&ctx, func));
+    }
+
+    /// Remove a previously registered handler
+    pub fn unregister<'js>(&self, ctx: Ctx<'js>, func: Function<'js>) {
+        let mut hooks = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        hooks.retain(|hook| {
+            hook.clone()
+                .restore(&ctx)
+                .is_ok_and(|hook| hook.as_value() != func.as_value())
+        });
+    }
+}
+
+impl ExitHooks {
+    /// Take all registered handlers, leaving the list empty
+    fn drain(&self) -> Vec<Persistent<Function<'static>>> {
+        let mut hooks = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        hooks.drain(..).collect()
+    }
+}
+
+/// Call all registered exit hooks synchronously, in registration order
+///
+/// The hook list is drained before any handler runs, so calling this again
+/// (e.g. `Deno.exit()` from inside an "unload" listener) is a no-op and every
+/// persistent function is released before the runtime is dropped.
+///
+/// # Errors
+/// Returns an error if a handler throws; remaining handlers are dropped
+pub fn run_exit_hooks(ctx: &Ctx<'_>) -> Result<()> {
+    let globals = ctx.globals();
+    let symbol_ctor: Function = globals.get("Symbol")?;
+    let symbol_for: Function = symbol_ctor.get("for")?;
+    let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
+    let internal: Object = globals.get(internal_symbol)?;
+    let Ok(exit_hooks) = internal.get::<_, ExitHooks>("exitHooks") else {
+        return Ok(());
+    };
+
+    for hook in exit_hooks.drain() {
+        hook.restore(ctx)?.call::<_, ()>(())?;
+    }
+    Ok(())
+}
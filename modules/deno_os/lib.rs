@@ -1,16 +1,35 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
-use rquickjs::{Ctx, Module};
+mod exit_hooks;
+mod net_interfaces;
+mod stdio;
+mod sys_info;
+mod tty;
+
+pub use exit_hooks::{ExitHooks, run_exit_hooks};
+
+use rquickjs::function::Async;
+use rquickjs::{Ctx, Function, Module, Object, Value};
 use std::collections::HashMap;
 use std::env;
 use std::sync::OnceLock;
-use utils::{SECTION_NAME, add_internal_function};
+use std::time::Instant;
+use utils::{SECTION_NAME, add_internal_function, quickjs_version};
 use utils_macros::include_ts;
 
 static SCRIPT_ARGS: OnceLock<Vec<String>> = OnceLock::new();
+static STANDALONE: OnceLock<bool> = OnceLock::new();
+static MDENO_VERSION: OnceLock<&'static str> = OnceLock::new();
+/// System uptime in seconds when it was first read, and when that was
+static BOOT_UPTIME: OnceLock<(Instant, f64)> = OnceLock::new();
 
-/// Check if this executable is a standalone binary
+/// Deno release whose APIs mdeno follows
+const DENO_VERSION: &str = "2.0.0";
+/// TypeScript version whose syntax the oxc transformer strips
+const TYPESCRIPT_VERSION: &str = "5.9.2";
+
+/// Check if this executable is a standalone binary (scanned once per process)
 fn is_standalone() -> bool {
-    libsui::find_section(SECTION_NAME).ok().flatten().is_some()
+    *STANDALONE.get_or_init(|| libsui::find_section(SECTION_NAME).ok().flatten().is_some())
 }
 
 /// Get script arguments
@@ -29,6 +48,128 @@ pub fn set_script_args(args: Vec<String>) {
     let _ = SCRIPT_ARGS.set(args);
 }
 
+/// Set the version reported as `Deno.version.mdeno` (called from the runtime)
+pub fn set_mdeno_version(version: &'static str) {
+    let _ = MDENO_VERSION.set(version);
+}
+
+/// Seconds since the system booted, or 0 where that can't be found out
+fn os_uptime() -> f64 {
+    // The boot time is read once; later calls only add the time elapsed since
+    let (start, uptime) =
+        BOOT_UPTIME.get_or_init(|| (Instant::now(), read_uptime().unwrap_or(0.0)));
+    if *uptime == 0.0 {
+        return 0.0;
+    }
+    uptime + start.elapsed().as_secs_f64()
+}
+
+#[cfg(target_os = "linux")]
+fn read_uptime() -> Option<f64> {
+    // "<uptime> <idle time>", both in seconds
+    let uptime = std::fs::read_to_string("/proc/uptime").ok()?;
+    uptime.split_whitespace().next()?.parse().ok()
+}
+
+#[cfg(target_os = "macos")]
+fn read_uptime() -> Option<f64> {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    let mut mib = [libc::CTL_KERN, libc::KERN_BOOTTIME];
+    let mut boottime = libc::timeval {
+        tv_sec: 0,
+        tv_usec: 0,
+    };
+    let mut size = std::mem::size_of::<libc::timeval>();
+    // SAFETY: kern.boottime fills a timeval, and size matches the buffer
+    let result = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            2,
+            (&raw mut boottime).cast(),
+            &raw mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result != 0 {
+        return None;
+    }
+    let boot = UNIX_EPOCH
+        + Duration::from_secs(u64::try_from(boottime.tv_sec).ok()?)
+        + Duration::from_micros(u64::try_from(boottime.tv_usec).ok()?);
+    Some(SystemTime::now().duration_since(boot).ok()?.as_secs_f64())
+}
+
+#[cfg(windows)]
+#[allow(clippy::unnecessary_wraps)] // Matches the other platforms
+fn read_uptime() -> Option<f64> {
+    // SAFETY: GetTickCount64 has no preconditions
+    let millis = unsafe { windows_sys::Win32::System::SystemInformation::GetTickCount64() };
+    #[allow(clippy::cast_precision_loss)] // Exact up to 2^53 ms, ~285,000 years
+    Some(millis as f64 / 1000.0)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn read_uptime() -> Option<f64> {
+    None
+}
+
+/// The parent's process ID
+#[cfg(unix)]
+fn parent_pid() -> u32 {
+    // SAFETY: getppid has no preconditions and always succeeds
+    let ppid = unsafe { libc::getppid() };
+    u32::try_from(ppid).unwrap_or(0)
+}
+
+/// The parent's process ID, found in a snapshot of all processes
+#[cfg(windows)]
+fn parent_pid() -> u32 {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, PROCESSENTRY32, Process32First, Process32Next, TH32CS_SNAPPROCESS,
+    };
+
+    let pid = std::process::id();
+    // SAFETY: the snapshot handle is checked before use and closed after,
+    // and entry.dwSize is set as Process32First requires
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return 0;
+        }
+        let mut entry: PROCESSENTRY32 = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32>() as u32;
+        let mut parent = 0;
+        let mut found = Process32First(snapshot, &raw mut entry) != 0;
+        while found {
+            if entry.th32ProcessID == pid {
+                parent = entry.th32ParentProcessID;
+                break;
+            }
+            found = Process32Next(snapshot, &raw mut entry) != 0;
+        }
+        CloseHandle(snapshot);
+        parent
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn parent_pid() -> u32 {
+    0
+}
+
+/// Run exit hooks, then terminate the process
+fn exit(ctx: Ctx<'_>, code: Option<i32>) {
+    // A throwing handler must not prevent the process from exiting
+    let _ = run_exit_hooks(&ctx);
+    #[allow(clippy::exit)] // Intentional: implements Deno.exit()
+    {
+        std::process::exit(code.unwrap_or(0));
+    }
+}
+
 /// # Errors
 /// Returns an error if module initialization fails
 pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
@@ -46,14 +187,58 @@ fn setup_internal(ctx: &Ctx) -> Result<(), Box<dyn std::error::Error>> {
     let script = format!("globalThis[Symbol.for('mdeno.internal')].args = {args_json};");
     ctx.eval::<(), _>(script)?;
 
+    // Exit hooks - "unload" and "beforeExit" listeners
+    let symbol_ctor: Function = ctx.globals().get("Symbol")?;
+    let symbol_for: Function = symbol_ctor.get("for")?;
+    let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
+    let internal: Object = ctx.globals().get(internal_symbol)?;
+    internal.set("exitHooks", ExitHooks::new())?;
+
     // Deno.exit
-    add_internal_function!(ctx, "exit", |code: Option<i32>| -> i32 {
-        let exit_code = code.unwrap_or(0);
-        #[allow(clippy::exit)] // Intentional: implements Deno.exit()
-        {
-            std::process::exit(exit_code);
-        }
+    add_internal_function!(ctx, "exit", exit);
+
+    // Deno.hostname / Deno.username
+    add_internal_function!(ctx, "hostname", || -> String {
+        hostname::get()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
     });
+    add_internal_function!(ctx, "username", whoami::username);
+
+    // Deno.osUptime
+    add_internal_function!(ctx, "osUptime", os_uptime);
+
+    // Deno.loadavg / Deno.osRelease / Deno.uid / Deno.gid
+    add_internal_function!(ctx, "loadavg", sys_info::loadavg);
+    add_internal_function!(ctx, "osRelease", sys_info::os_release);
+    add_internal_function!(ctx, "uid", sys_info::uid);
+    add_internal_function!(ctx, "gid", sys_info::gid);
+
+    // Deno.systemMemoryInfo / Deno.networkInterfaces
+    add_internal_function!(ctx, "systemMemoryInfo", sys_info::system_memory_info);
+    add_internal_function!(
+        ctx,
+        "networkInterfaces",
+        net_interfaces::os_network_interfaces
+    );
+
+    // Deno.stdin.setRaw
+    add_internal_function!(ctx, "stdinSetRaw", tty::stdin_set_raw);
+
+    // Deno.stdin / Deno.stdout / Deno.stderr
+    {
+        ctx.eval::<(), _>("globalThis[Symbol.for('mdeno.internal')].stdio = {};")?;
+        // readSync(len: number): Uint8Array | null
+        add_internal_function!(ctx, "stdio.readSync", stdio::stdio_read_sync);
+        // read(len: number): Promise<Uint8Array | null>
+        add_internal_function!(ctx, "stdio.read", Async(stdio::stdio_read));
+        // writeSync(rid: number, data: Uint8Array): number
+        add_internal_function!(ctx, "stdio.writeSync", stdio::stdio_write_sync);
+        // write(rid: number, data: Uint8Array): Promise<number>
+        add_internal_function!(ctx, "stdio.write", Async(stdio::stdio_write));
+        add_internal_function!(ctx, "stdio.isTerminal", stdio::stdio_is_terminal);
+        add_internal_function!(ctx, "stdio.close", stdio::stdio_close);
+    }
 
     // Deno.env
     {
@@ -61,6 +246,10 @@ fn setup_internal(ctx: &Ctx) -> Result<(), Box<dyn std::error::Error>> {
         add_internal_function!(ctx, "env.get", |key: String| -> Option<String> {
             env::var(&key).ok()
         });
+        // SAFETY: set_var and remove_var became unsafe in Rust 2024, as they race
+        // with any other thread reading the environment (e.g. fs operations
+        // on the blocking pool). JS runs on one thread, but this should move
+        // behind a lock shared with every reader once there is one.
         add_internal_function!(ctx, "env.set", |key: String, value: String| {
             unsafe {
                 env::set_var(&key, value);
@@ -74,11 +263,25 @@ fn setup_internal(ctx: &Ctx) -> Result<(), Box<dyn std::error::Error>> {
         add_internal_function!(ctx, "env.has", |key: String| -> bool {
             env::var(&key).is_ok()
         });
+        // Variables whose name or value isn't valid UTF-8 are skipped
         add_internal_function!(ctx, "env.toObject", || -> HashMap<String, String> {
-            env::vars().collect()
+            env::vars_os()
+                .filter_map(|(key, value)| {
+                    Some((key.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect()
         });
     }
 
+    // Deno.pid / Deno.ppid
+    let script = format!(
+        "globalThis[Symbol.for('mdeno.internal')].pid = {};\n\
+         globalThis[Symbol.for('mdeno.internal')].ppid = {};",
+        std::process::id(),
+        parent_pid()
+    );
+    ctx.eval::<(), _>(script)?;
+
     // Deno.noColor - store in internal namespace
     let no_color = env::var("NO_COLOR").is_ok();
     let script = format!("globalThis[Symbol.for('mdeno.internal')].noColor = {no_color};");
@@ -145,16 +348,32 @@ fn setup_internal(ctx: &Ctx) -> Result<(), Box<dyn std::error::Error>> {
     // Determine if this is a standalone build
     let standalone = is_standalone();
 
+    // System locale in BCP 47 form (e.g. "ja_JP" -> "ja-JP"), as in navigator.language
+    let locale = sys_locale::get_locale()
+        .map_or_else(|| "en-US".to_string(), |locale| locale.replace('_', "-"));
+    let locale = serde_json::to_string(&locale)?;
+
     let build_info = format!(
         r#"globalThis[Symbol.for('mdeno.internal')].build = {{
   os: "{os}",
   arch: "{arch}",
   target: "{target}",
   vendor: "{vendor}",
-  standalone: {standalone}
+  standalone: {standalone},
+  locale: {locale}
 }};"#
     );
     ctx.eval::<(), _>(build_info)?;
 
+    // Deno.version
+    let version = serde_json::json!({
+        "deno": DENO_VERSION,
+        "mdeno": MDENO_VERSION.get().copied().unwrap_or(env!("CARGO_PKG_VERSION")),
+        "quickjs": quickjs_version(),
+        "typescript": TYPESCRIPT_VERSION,
+    });
+    let script = format!("globalThis[Symbol.for('mdeno.internal')].version = {version};");
+    ctx.eval::<(), _>(script)?;
+
     Ok(())
 }
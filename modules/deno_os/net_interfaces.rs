@@ -0,0 +1,289 @@
+// Network interface addresses for Deno.networkInterfaces
+use rquickjs::{IntoJs, Object};
+use std::net::IpAddr;
+use utils::{DenoError, DenoResult, JsResult};
+
+/// One address of a network interface, as reported by `Deno.networkInterfaces()`
+pub(crate) struct NetworkInterface {
+    name: String,
+    address: IpAddr,
+    /// Length of the network prefix, in bits
+    prefix_len: u32,
+    /// Only set for IPv6 addresses
+    scope_id: Option<u32>,
+    mac: [u8; 6],
+}
+
+impl NetworkInterface {
+    fn netmask(&self) -> IpAddr {
+        match self.address {
+            IpAddr::V4(_) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len).unwrap_or(0);
+                IpAddr::from(mask.to_be_bytes())
+            }
+            IpAddr::V6(_) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len).unwrap_or(0);
+                IpAddr::from(mask.to_be_bytes())
+            }
+        }
+    }
+}
+
+impl<'js> IntoJs<'js> for NetworkInterface {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let family = if self.address.is_ipv4() {
+            "IPv4"
+        } else {
+            "IPv6"
+        };
+        let mac = self
+            .mac
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(":");
+        let obj = Object::new(ctx.clone())?;
+        obj.set("family", family)?;
+        obj.set("name", self.name.as_str())?;
+        obj.set("address", self.address.to_string())?;
+        obj.set("netmask", self.netmask().to_string())?;
+        match self.scope_id {
+            Some(scope_id) => obj.set("scopeid", scope_id)?,
+            None => obj.set("scopeid", rquickjs::Value::new_null(ctx.clone()))?,
+        }
+        obj.set("cidr", format!("{}/{}", self.address, self.prefix_len))?;
+        obj.set("mac", mac)?;
+        Ok(obj.into_value())
+    }
+}
+
+/// The IP address in a socket address, if it is one
+///
+/// # Safety
+/// `addr` must be null or point to a socket address of its family's size
+#[cfg(unix)]
+unsafe fn ip_of(addr: *const libc::sockaddr) -> Option<(IpAddr, Option<u32>)> {
+    // SAFETY: guaranteed by the caller
+    let family = i32::from(unsafe { addr.as_ref() }?.sa_family);
+    match family {
+        libc::AF_INET => {
+            // SAFETY: AF_INET addresses are sockaddr_in
+            let addr = unsafe { addr.cast::<libc::sockaddr_in>().read_unaligned() };
+            Some((IpAddr::from(addr.sin_addr.s_addr.to_ne_bytes()), None))
+        }
+        libc::AF_INET6 => {
+            // SAFETY: AF_INET6 addresses are sockaddr_in6
+            let addr = unsafe { addr.cast::<libc::sockaddr_in6>().read_unaligned() };
+            Some((
+                IpAddr::from(addr.sin6_addr.s6_addr),
+                Some(addr.sin6_scope_id),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// The hardware address in a link-layer socket address, if it is one
+///
+/// # Safety
+/// `addr` must point to a valid socket address
+#[cfg(unix)]
+unsafe fn mac_of(addr: *const libc::sockaddr) -> Option<[u8; 6]> {
+    // SAFETY: guaranteed by the caller
+    let family = i32::from(unsafe { &*addr }.sa_family);
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if family == libc::AF_PACKET {
+        // SAFETY: AF_PACKET addresses are sockaddr_ll
+        let addr = unsafe { addr.cast::<libc::sockaddr_ll>().read_unaligned() };
+        return addr.sll_addr.get(..6)?.try_into().ok();
+    }
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    if family == libc::AF_LINK {
+        // SAFETY: AF_LINK addresses are sockaddr_dl
+        let link = unsafe { addr.cast::<libc::sockaddr_dl>().read_unaligned() };
+        // The data holds the interface name, then the hardware address, and
+        // may run past the end of the declared sdl_data array
+        let offset = std::mem::offset_of!(libc::sockaddr_dl, sdl_data) + usize::from(link.sdl_nlen);
+        if link.sdl_alen != 6 || offset + 6 > usize::from(link.sdl_len) {
+            return None;
+        }
+        // SAFETY: the six bytes are within the sdl_len bytes of the address
+        let mac = unsafe {
+            addr.cast::<u8>()
+                .add(offset)
+                .cast::<[u8; 6]>()
+                .read_unaligned()
+        };
+        return Some(mac);
+    }
+    let _ = family;
+    None
+}
+
+#[cfg(unix)]
+fn network_interfaces() -> DenoResult<Vec<NetworkInterface>> {
+    use std::collections::HashMap;
+    use std::ffi::CStr;
+
+    let mut first: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: getifaddrs fills `first` with a list freed below
+    if unsafe { libc::getifaddrs(&raw mut first) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    let mut interfaces = Vec::new();
+    // Hardware addresses are listed as separate entries of the same name
+    let mut macs = HashMap::new();
+    let mut cursor = first;
+    // SAFETY: each entry of the list is valid until freeifaddrs, and its
+    // addresses are null or sized for their family
+    while let Some(entry) = unsafe { cursor.as_ref() } {
+        cursor = entry.ifa_next;
+        if entry.ifa_addr.is_null() {
+            continue;
+        }
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }
+            .to_string_lossy()
+            .into_owned();
+        if let Some(mac) = unsafe { mac_of(entry.ifa_addr) } {
+            macs.insert(name, mac);
+            continue;
+        }
+        let Some((address, scope_id)) = (unsafe { ip_of(entry.ifa_addr) }) else {
+            continue;
+        };
+        let prefix_len = match unsafe { ip_of(entry.ifa_netmask) } {
+            Some((IpAddr::V4(mask), _)) => mask.to_bits().count_ones(),
+            Some((IpAddr::V6(mask), _)) => mask.to_bits().count_ones(),
+            None => 0,
+        };
+        interfaces.push(NetworkInterface {
+            name,
+            address,
+            prefix_len,
+            scope_id,
+            mac: [0; 6],
+        });
+    }
+    // SAFETY: `first` came from getifaddrs and is not used after this
+    unsafe { libc::freeifaddrs(first) };
+
+    for interface in &mut interfaces {
+        if let Some(mac) = macs.get(&interface.name) {
+            interface.mac = *mac;
+        }
+    }
+    Ok(interfaces)
+}
+
+#[cfg(windows)]
+fn network_interfaces() -> DenoResult<Vec<NetworkInterface>> {
+    use windows_sys::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS};
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER, GAA_FLAG_SKIP_MULTICAST,
+        GetAdaptersAddresses, IP_ADAPTER_ADDRESSES_LH,
+    };
+    use windows_sys::Win32::Networking::WinSock::{
+        AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_IN, SOCKADDR_IN6,
+    };
+
+    let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+    // Grow the buffer until the adapter list fits; u64 keeps it aligned
+    let mut size: u32 = 16 * 1024;
+    let mut buffer: Vec<u64>;
+    loop {
+        buffer = vec![0; (size as usize).div_ceil(8)];
+        // SAFETY: buffer holds `size` bytes, aligned for the adapter structs
+        let result = unsafe {
+            GetAdaptersAddresses(
+                u32::from(AF_UNSPEC),
+                flags,
+                std::ptr::null(),
+                buffer.as_mut_ptr().cast(),
+                &raw mut size,
+            )
+        };
+        match result {
+            ERROR_SUCCESS => break,
+            ERROR_BUFFER_OVERFLOW => {}
+            #[allow(clippy::cast_possible_wrap)] // Win32 error codes fit in i32
+            code => return Err(std::io::Error::from_raw_os_error(code as i32).into()),
+        }
+    }
+
+    let mut interfaces = Vec::new();
+    let mut adapter = buffer.as_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>();
+    // SAFETY: the adapter and address lists live in `buffer`, and each
+    // socket address is sized for its family
+    while let Some(current) = unsafe { adapter.as_ref() } {
+        adapter = current.Next;
+        let name = unsafe { wide_to_string(current.FriendlyName) };
+        let mut mac = [0; 6];
+        if current.PhysicalAddressLength == 6 {
+            mac.copy_from_slice(&current.PhysicalAddress[..6]);
+        }
+        let mut unicast = current.FirstUnicastAddress;
+        while let Some(address) = unsafe { unicast.as_ref() } {
+            unicast = address.Next;
+            let sockaddr = address.Address.lpSockaddr;
+            let Some(family) = (unsafe { sockaddr.as_ref() }).map(|addr| addr.sa_family) else {
+                continue;
+            };
+            let (ip, scope_id) = if family == AF_INET {
+                let addr = unsafe { sockaddr.cast::<SOCKADDR_IN>().read_unaligned() };
+                let octets = unsafe { addr.sin_addr.S_un.S_addr }.to_ne_bytes();
+                (IpAddr::from(octets), None)
+            } else if family == AF_INET6 {
+                let addr = unsafe { sockaddr.cast::<SOCKADDR_IN6>().read_unaligned() };
+                let octets = unsafe { addr.sin6_addr.u.Byte };
+                (
+                    IpAddr::from(octets),
+                    Some(unsafe { addr.Anonymous.sin6_scope_id }),
+                )
+            } else {
+                continue;
+            };
+            interfaces.push(NetworkInterface {
+                name: name.clone(),
+                address: ip,
+                prefix_len: u32::from(address.OnLinkPrefixLength),
+                scope_id,
+                mac,
+            });
+        }
+    }
+    Ok(interfaces)
+}
+
+/// # Safety
+/// `ptr` must be null or point to a null-terminated UTF-16 string
+#[cfg(windows)]
+unsafe fn wide_to_string(ptr: *const u16) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let mut len = 0;
+    // SAFETY: guaranteed by the caller
+    while unsafe { *ptr.add(len) } != 0 {
+        len += 1;
+    }
+    String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(ptr, len) })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn network_interfaces() -> DenoResult<Vec<NetworkInterface>> {
+    Err(DenoError::NotSupported(
+        "networkInterfaces is not supported on this platform".to_string(),
+    ))
+}
+
+pub(crate) fn os_network_interfaces() -> JsResult<Vec<NetworkInterface>> {
+    network_interfaces()
+        .map_err(|error| match error {
+            DenoError::Io(error) => {
+                DenoError::Other(format!("Failed to list network interfaces: {error}"))
+            }
+            error => error,
+        })
+        .into()
+}
@@ -0,0 +1,124 @@
+// Deno.stdin / Deno.stdout / Deno.stderr, addressed by their resource IDs 0-2
+use rquickjs::TypedArray;
+use std::io::{IsTerminal, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use utils::{DenoError, DenoResult, JsResult};
+
+const STDIN: u32 = 0;
+const STDOUT: u32 = 1;
+const STDERR: u32 = 2;
+
+/// Whether each of stdin, stdout and stderr has been closed from JS. The
+/// descriptors themselves stay open, since console output and panics still
+/// go through them.
+static CLOSED: [AtomicBool; 3] = [const { AtomicBool::new(false) }; 3];
+
+fn check_open(rid: u32) -> DenoResult<()> {
+    let closed = CLOSED
+        .get(rid as usize)
+        .is_none_or(|closed| closed.load(Ordering::Relaxed));
+    if closed {
+        return Err(DenoError::BadResource(format!("Bad resource ID: {rid}")));
+    }
+    Ok(())
+}
+
+/// Read from the stdin descriptor directly, since `std::io::stdin()` would
+/// keep bytes in its buffer where asynchronous reads can't see them
+#[cfg(unix)]
+fn read_stdin(buf: &mut [u8]) -> std::io::Result<usize> {
+    use std::fs::File;
+    use std::mem::ManuallyDrop;
+    use std::os::fd::FromRawFd;
+
+    // SAFETY: stdin is open for the life of the process, and ManuallyDrop
+    // keeps it that way
+    let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(libc::STDIN_FILENO) });
+    file.read(buf)
+}
+
+/// Consoles have to be read through `std::io::stdin()`
+#[cfg(not(unix))]
+fn read_stdin(buf: &mut [u8]) -> std::io::Result<usize> {
+    std::io::stdin().lock().read(buf)
+}
+
+fn read(len: usize) -> DenoResult<Option<Vec<u8>>> {
+    check_open(STDIN)?;
+    let mut buf = vec![0; len];
+    let read = read_stdin(&mut buf)?;
+    if read == 0 && len > 0 {
+        return Ok(None);
+    }
+    buf.truncate(read);
+    Ok(Some(buf))
+}
+
+fn write(rid: u32, data: &[u8]) -> DenoResult<usize> {
+    check_open(rid)?;
+    // Flushing keeps bytes in order with console output, which is buffered
+    let written = match rid {
+        STDOUT => {
+            let mut stdout = std::io::stdout().lock();
+            let written = stdout.write(data)?;
+            stdout.flush()?;
+            written
+        }
+        STDERR => std::io::stderr().write(data)?,
+        _ => return Err(DenoError::BadResource(format!("Bad resource ID: {rid}"))),
+    };
+    Ok(written)
+}
+
+/// Run `f` on compio's thread pool. compio's own stdio handles read and
+/// write at offset 0, which goes wrong when a stream is redirected to a file.
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> DenoResult<T> + Send + 'static,
+) -> DenoResult<T> {
+    compio::runtime::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|_| Err(DenoError::Other("Stdio operation panicked".into())))
+}
+
+pub(crate) fn stdio_read_sync(len: usize) -> JsResult<Option<Vec<u8>>> {
+    read(len).into()
+}
+
+pub(crate) async fn stdio_read(len: usize) -> JsResult<Option<Vec<u8>>> {
+    run_blocking(move || read(len)).await.into()
+}
+
+pub(crate) fn stdio_write_sync(rid: u32, data: TypedArray<'_, u8>) -> JsResult<usize> {
+    let result: DenoResult<usize> = match data.as_bytes() {
+        Some(bytes) => write(rid, bytes),
+        None => Err(DenoError::Other("Buffer is detached".to_string())),
+    };
+    result.into()
+}
+
+pub(crate) async fn stdio_write(rid: u32, data: TypedArray<'_, u8>) -> JsResult<usize> {
+    let Some(data) = data.as_bytes().map(<[u8]>::to_vec) else {
+        return JsResult::from(Err::<usize, _>(DenoError::Other(
+            "Buffer is detached".to_string(),
+        )));
+    };
+    run_blocking(move || write(rid, &data)).await.into()
+}
+
+pub(crate) fn stdio_is_terminal(rid: u32) -> bool {
+    match rid {
+        STDIN => std::io::stdin().is_terminal(),
+        STDOUT => std::io::stdout().is_terminal(),
+        STDERR => std::io::stderr().is_terminal(),
+        _ => false,
+    }
+}
+
+pub(crate) fn stdio_close(rid: u32) -> JsResult<()> {
+    let result = check_open(rid).map(|()| {
+        if let Some(closed) = CLOSED.get(rid as usize) {
+            closed.store(true, Ordering::Relaxed);
+        }
+    });
+    result.into()
+}
@@ -0,0 +1,24 @@
+Deno.test("Deno.stdin, stdout and stderr have rids 0-2", () => {
+  if (Deno.stdin.rid !== 0) throw new Error("stdin rid");
+  if (Deno.stdout.rid !== 1) throw new Error("stdout rid");
+  if (Deno.stderr.rid !== 2) throw new Error("stderr rid");
+  for (const stream of [Deno.stdin, Deno.stdout, Deno.stderr]) {
+    if (typeof stream.isTerminal() !== "boolean") throw new Error("isTerminal");
+  }
+});
+
+Deno.test("Deno.stdout and stderr report bytes written", async () => {
+  const empty = new Uint8Array(0);
+  if (Deno.stdout.writeSync(empty) !== 0) throw new Error("stdout writeSync");
+  if (await Deno.stderr.write(empty) !== 0) throw new Error("stderr write");
+});
+
+Deno.test("Deno.stdin.readable and Deno.stdout.writable are created once", () => {
+  const readable = Deno.stdin.readable;
+  if (!(readable instanceof ReadableStream)) throw new Error("readable");
+  if (Deno.stdin.readable !== readable) throw new Error("readable changed");
+  const writable = Deno.stdout.writable;
+  if (!(writable instanceof WritableStream)) throw new Error("writable");
+  if (Deno.stdout.writable !== writable) throw new Error("writable changed");
+  if (Deno.stderr.writable === writable) throw new Error("shared writable");
+});
@@ -0,0 +1,234 @@
+// System information for Deno.systemMemoryInfo, Deno.loadavg, Deno.osRelease,
+// Deno.uid and Deno.gid
+use rquickjs::{IntoJs, Object};
+use utils::{DenoError, DenoResult, JsResult};
+
+/// Memory figures in bytes, as reported by `Deno.systemMemoryInfo()`
+#[derive(Default)]
+pub(crate) struct MemoryInfo {
+    total: u64,
+    free: u64,
+    available: u64,
+    buffers: u64,
+    cached: u64,
+    swap_total: u64,
+    swap_free: u64,
+}
+
+impl<'js> IntoJs<'js> for MemoryInfo {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        let obj = Object::new(ctx.clone())?;
+        obj.set("total", self.total)?;
+        obj.set("free", self.free)?;
+        obj.set("available", self.available)?;
+        obj.set("buffers", self.buffers)?;
+        obj.set("cached", self.cached)?;
+        obj.set("swapTotal", self.swap_total)?;
+        obj.set("swapFree", self.swap_free)?;
+        Ok(obj.into_value())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn memory_info() -> DenoResult<MemoryInfo> {
+    // Lines like "MemTotal:       16318480 kB"
+    let meminfo = std::fs::read_to_string("/proc/meminfo")?;
+    let mut info = MemoryInfo::default();
+    for line in meminfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(kib) = value
+            .split_whitespace()
+            .next()
+            .and_then(|kib| kib.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let field = match key {
+            "MemTotal" => &mut info.total,
+            "MemFree" => &mut info.free,
+            "MemAvailable" => &mut info.available,
+            "Buffers" => &mut info.buffers,
+            "Cached" => &mut info.cached,
+            "SwapTotal" => &mut info.swap_total,
+            "SwapFree" => &mut info.swap_free,
+            _ => continue,
+        };
+        *field = kib * 1024;
+    }
+    Ok(info)
+}
+
+/// Read a fixed-size value with `sysctlbyname`
+#[cfg(target_os = "macos")]
+fn sysctl<T: Default>(name: &std::ffi::CStr) -> DenoResult<T> {
+    let mut value = T::default();
+    let mut size = std::mem::size_of::<T>();
+    // SAFETY: value is a valid buffer of `size` bytes
+    let result = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            (&raw mut value).cast(),
+            &raw mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(value)
+}
+
+#[cfg(target_os = "macos")]
+fn memory_info() -> DenoResult<MemoryInfo> {
+    // SAFETY: sysconf has no preconditions
+    let page_size = u64::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).unwrap_or(0);
+    let free = u64::from(sysctl::<u32>(c"vm.page_free_count")?) * page_size;
+    let swap = sysctl::<libc::xsw_usage>(c"vm.swapusage").unwrap_or(libc::xsw_usage {
+        xsu_total: 0,
+        xsu_avail: 0,
+        xsu_used: 0,
+        xsu_pagesize: 0,
+        xsu_encrypted: 0,
+    });
+    Ok(MemoryInfo {
+        total: sysctl::<u64>(c"hw.memsize")?,
+        free,
+        // Pages that could be reclaimed aren't counted, as there is no
+        // sysctl for them
+        available: free,
+        swap_total: swap.xsu_total,
+        swap_free: swap.xsu_avail,
+        ..MemoryInfo::default()
+    })
+}
+
+#[cfg(windows)]
+fn memory_info() -> DenoResult<MemoryInfo> {
+    use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    // SAFETY: MEMORYSTATUSEX is plain data, and dwLength is set as
+    // GlobalMemoryStatusEx requires
+    let status = unsafe {
+        let mut status: MEMORYSTATUSEX = std::mem::zeroed();
+        status.dwLength = std::mem::size_of::<MEMORYSTATUSEX>() as u32;
+        if GlobalMemoryStatusEx(&raw mut status) == 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        status
+    };
+    // The page file includes physical memory, so swap is what's beyond it
+    Ok(MemoryInfo {
+        total: status.ullTotalPhys,
+        free: status.ullAvailPhys,
+        available: status.ullAvailPhys,
+        swap_total: status.ullTotalPageFile.saturating_sub(status.ullTotalPhys),
+        swap_free: status.ullAvailPageFile.saturating_sub(status.ullAvailPhys),
+        ..MemoryInfo::default()
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn memory_info() -> DenoResult<MemoryInfo> {
+    Err(DenoError::NotSupported(
+        "systemMemoryInfo is not supported on this platform".to_string(),
+    ))
+}
+
+pub(crate) fn system_memory_info() -> JsResult<MemoryInfo> {
+    memory_info()
+        .map_err(|error| match error {
+            DenoError::Io(error) => {
+                DenoError::Other(format!("Failed to read memory info: {error}"))
+            }
+            error => error,
+        })
+        .into()
+}
+
+/// Load averages over the last 1, 5 and 15 minutes
+#[cfg(unix)]
+pub(crate) fn loadavg() -> Vec<f64> {
+    let mut loads = vec![0.0; 3];
+    // SAFETY: loads has room for the three samples asked for
+    if unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) } != 3 {
+        return vec![0.0; 3];
+    }
+    loads
+}
+
+/// Windows has no load average
+#[cfg(not(unix))]
+pub(crate) fn loadavg() -> Vec<f64> {
+    vec![0.0; 3]
+}
+
+/// The kernel release, as printed by `uname -r`
+#[cfg(unix)]
+pub(crate) fn os_release() -> String {
+    // SAFETY: utsname is plain data, filled in by uname
+    let mut name: libc::utsname = unsafe { std::mem::zeroed() };
+    // SAFETY: name is a valid utsname
+    if unsafe { libc::uname(&raw mut name) } != 0 {
+        return String::new();
+    }
+    // SAFETY: uname null-terminates each field
+    unsafe { std::ffi::CStr::from_ptr(name.release.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The Windows version as "major.minor.build", which `GetVersionEx` would
+/// misreport to processes without a compatibility manifest
+#[cfg(windows)]
+pub(crate) fn os_release() -> String {
+    use windows_sys::Wdk::System::SystemServices::RtlGetVersion;
+    use windows_sys::Win32::System::SystemInformation::OSVERSIONINFOW;
+
+    // SAFETY: OSVERSIONINFOW is plain data, and dwOSVersionInfoSize is set
+    // as RtlGetVersion requires
+    let info = unsafe {
+        let mut info: OSVERSIONINFOW = std::mem::zeroed();
+        info.dwOSVersionInfoSize = std::mem::size_of::<OSVERSIONINFOW>() as u32;
+        if RtlGetVersion(&raw mut info) != 0 {
+            return String::new();
+        }
+        info
+    };
+    format!(
+        "{}.{}.{}",
+        info.dwMajorVersion, info.dwMinorVersion, info.dwBuildNumber
+    )
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn os_release() -> String {
+    String::new()
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)] // None on Windows
+pub(crate) fn uid() -> Option<u32> {
+    // SAFETY: getuid has no preconditions and always succeeds
+    Some(unsafe { libc::getuid() })
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)] // None on Windows
+pub(crate) fn gid() -> Option<u32> {
+    // SAFETY: getgid has no preconditions and always succeeds
+    Some(unsafe { libc::getgid() })
+}
+
+/// Windows has no numeric user or group IDs
+#[cfg(not(unix))]
+pub(crate) fn uid() -> Option<u32> {
+    None
+}
+
+#[cfg(not(unix))]
+pub(crate) fn gid() -> Option<u32> {
+    None
+}
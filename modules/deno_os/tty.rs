@@ -0,0 +1,113 @@
+// Terminal raw mode for Deno.stdin.setRaw
+
+use std::sync::{Mutex, PoisonError};
+use utils::{DenoError, DenoResult, JsResult};
+
+#[cfg(unix)]
+type TerminalMode = libc::termios;
+#[cfg(windows)]
+type TerminalMode = windows_sys::Win32::System::Console::CONSOLE_MODE;
+
+/// The mode stdin had before raw mode was first enabled, restored when it
+/// is disabled again
+static ORIGINAL_MODE: Mutex<Option<TerminalMode>> = Mutex::new(None);
+
+pub(crate) fn stdin_set_raw(raw: bool, cbreak: bool) -> JsResult<()> {
+    set_raw(raw, cbreak).into()
+}
+
+/// Enable or disable raw mode on stdin. In raw mode input is passed through
+/// byte by byte without echo, and Ctrl+C arrives as input unless `cbreak`
+/// keeps signals working.
+fn set_raw(raw: bool, cbreak: bool) -> DenoResult<()> {
+    let mut original = ORIGINAL_MODE.lock().unwrap_or_else(PoisonError::into_inner);
+    if raw {
+        let current = get_mode()?;
+        let saved = *original.get_or_insert(current);
+        set_mode(&raw_mode(saved, cbreak))
+    } else if let Some(saved) = original.take() {
+        set_mode(&saved)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn get_mode() -> DenoResult<TerminalMode> {
+    // SAFETY: isatty only inspects the descriptor
+    if unsafe { libc::isatty(libc::STDIN_FILENO) } != 1 {
+        return Err(not_a_terminal());
+    }
+    // SAFETY: termios is plain data, filled in by tcgetattr
+    let mut mode: libc::termios = unsafe { std::mem::zeroed() };
+    // SAFETY: `mode` is a valid termios to write to
+    if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &raw mut mode) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(mode)
+}
+
+#[cfg(unix)]
+fn set_mode(mode: &TerminalMode) -> DenoResult<()> {
+    // SAFETY: `mode` is a valid termios read by tcgetattr
+    if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSADRAIN, mode) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn raw_mode(mut mode: TerminalMode, cbreak: bool) -> TerminalMode {
+    // Output processing is left alone, so "\n" still moves to a new line
+    mode.c_iflag &= !(libc::BRKINT | libc::ICRNL | libc::INPCK | libc::ISTRIP | libc::IXON);
+    mode.c_cflag |= libc::CS8;
+    mode.c_lflag &= !(libc::ECHO | libc::ICANON | libc::IEXTEN);
+    if !cbreak {
+        mode.c_lflag &= !libc::ISIG;
+    }
+    // Return from read() as soon as a single byte is available
+    mode.c_cc[libc::VMIN] = 1;
+    mode.c_cc[libc::VTIME] = 0;
+    mode
+}
+
+#[cfg(windows)]
+fn get_mode() -> DenoResult<TerminalMode> {
+    use windows_sys::Win32::System::Console::{GetConsoleMode, GetStdHandle, STD_INPUT_HANDLE};
+
+    let mut mode = 0;
+    // SAFETY: GetConsoleMode fails (returns 0) for handles that aren't a console
+    if unsafe { GetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), &raw mut mode) } == 0 {
+        return Err(not_a_terminal());
+    }
+    Ok(mode)
+}
+
+#[cfg(windows)]
+fn set_mode(mode: &TerminalMode) -> DenoResult<()> {
+    use windows_sys::Win32::System::Console::{GetStdHandle, STD_INPUT_HANDLE, SetConsoleMode};
+
+    // SAFETY: SetConsoleMode fails (returns 0) for handles that aren't a console
+    if unsafe { SetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), *mode) } == 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn raw_mode(mode: TerminalMode, cbreak: bool) -> TerminalMode {
+    use windows_sys::Win32::System::Console::{
+        ENABLE_ECHO_INPUT, ENABLE_LINE_INPUT, ENABLE_PROCESSED_INPUT,
+    };
+
+    let mut mode = mode & !(ENABLE_ECHO_INPUT | ENABLE_LINE_INPUT);
+    if !cbreak {
+        // Let Ctrl+C through as input instead of a signal
+        mode &= !ENABLE_PROCESSED_INPUT;
+    }
+    mode
+}
+
+fn not_a_terminal() -> DenoError {
+    DenoError::NotSupported("Deno.stdin is not a terminal".to_string())
+}
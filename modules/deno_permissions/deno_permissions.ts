@@ -0,0 +1,196 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+// Register permission APIs under __mdeno__.permissions
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+type PermissionState = "granted" | "denied" | "prompt";
+
+interface PermissionDescriptor {
+  name: string;
+  path?: string | URL;
+  host?: string;
+  variable?: string;
+  kind?: string;
+  command?: string | URL;
+}
+
+type ChangeListener = ((event: unknown) => void) | {
+  handleEvent(event: unknown): void;
+};
+
+const PERMISSION_NAMES = [
+  "read",
+  "write",
+  "net",
+  "env",
+  "sys",
+  "run",
+  "ffi",
+  "import",
+];
+
+// Statuses handed out to scripts, so onchange can fire when state changes
+const liveStatuses = new Set<WeakRef<PermissionStatus>>();
+
+// Re-read a status from the store and fire "change" if it differs
+let refreshStatus: (status: PermissionStatus) => void;
+
+class PermissionStatus {
+  #state: PermissionState;
+  #partial: boolean;
+  #descriptor: PermissionDescriptor | undefined;
+  #onchange: ((event: unknown) => void) | null = null;
+  #listeners: ChangeListener[] = [];
+
+  constructor(
+    state: PermissionState = "granted",
+    partial: boolean = false,
+    descriptor?: PermissionDescriptor,
+  ) {
+    this.#state = state;
+    this.#partial = partial;
+    this.#descriptor = descriptor;
+    if (descriptor) {
+      liveStatuses.add(new WeakRef(this));
+    }
+  }
+
+  get state(): PermissionState {
+    return this.#state;
+  }
+
+  get partial(): boolean {
+    return this.#partial;
+  }
+
+  get onchange(): ((event: unknown) => void) | null {
+    return this.#onchange;
+  }
+
+  set onchange(handler: ((event: unknown) => void) | null) {
+    this.#onchange = typeof handler === "function" ? handler : null;
+  }
+
+  addEventListener(type: string, listener: ChangeListener | null): void {
+    if (type === "change" && listener && !this.#listeners.includes(listener)) {
+      this.#listeners.push(listener);
+    }
+  }
+
+  removeEventListener(type: string, listener: ChangeListener | null): void {
+    const index = listener ? this.#listeners.indexOf(listener) : -1;
+    if (type === "change" && index !== -1) {
+      this.#listeners.splice(index, 1);
+    }
+  }
+
+  static {
+    refreshStatus = (status) => {
+      if (!status.#descriptor) {
+        return;
+      }
+      const state = queryState(status.#descriptor);
+      if (state === status.#state) {
+        return;
+      }
+      status.#state = state;
+      const event = { type: "change", target: status, currentTarget: status };
+      status.#onchange?.call(status, event);
+      for (const listener of [...status.#listeners]) {
+        if (typeof listener === "function") {
+          listener.call(status, event);
+        } else {
+          listener.handleEvent(event);
+        }
+      }
+    };
+  }
+}
+
+function refreshStatuses(): void {
+  for (const ref of liveStatuses) {
+    const status = ref.deref();
+    if (status) {
+      refreshStatus(status);
+    } else {
+      liveStatuses.delete(ref);
+    }
+  }
+}
+
+function toPath(path: string | URL): string {
+  if (path instanceof URL) {
+    if (path.protocol !== "file:") {
+      throw new TypeError("Must be a file URL");
+    }
+    return decodeURIComponent(path.pathname);
+  }
+  return String(path);
+}
+
+// Validate a descriptor and extract the resource it is scoped to
+function resourceOf(desc: PermissionDescriptor): string | undefined {
+  if (typeof desc !== "object" || desc === null) {
+    throw new TypeError("Permission descriptor must be an object");
+  }
+  if (!PERMISSION_NAMES.includes(desc.name)) {
+    throw new TypeError(
+      `The provided value "${desc.name}" is not a valid permission name.`,
+    );
+  }
+  switch (desc.name) {
+    case "read":
+    case "write":
+    case "ffi":
+      return desc.path === undefined ? undefined : toPath(desc.path);
+    case "net":
+    case "import":
+      return desc.host;
+    case "env":
+      return desc.variable;
+    case "sys":
+      return desc.kind;
+    case "run":
+      return desc.command === undefined ? undefined : toPath(desc.command);
+  }
+  return undefined;
+}
+
+function queryState(desc: PermissionDescriptor): PermissionState {
+  return __internal.permissions.query(desc.name, resourceOf(desc));
+}
+
+function querySync(desc: PermissionDescriptor): PermissionStatus {
+  return new PermissionStatus(queryState(desc), false, desc);
+}
+
+function revokeSync(desc: PermissionDescriptor): PermissionStatus {
+  const state = __internal.permissions.revoke(desc.name, resourceOf(desc));
+  refreshStatuses();
+  return new PermissionStatus(state, false, desc);
+}
+
+function requestSync(desc: PermissionDescriptor): PermissionStatus {
+  const state = __internal.permissions.request(desc.name, resourceOf(desc));
+  refreshStatuses();
+  return new PermissionStatus(state, false, desc);
+}
+
+// https://docs.deno.com/api/deno/~/Deno.Permissions
+const permissions = {
+  query: (desc: PermissionDescriptor) =>
+    new Promise((resolve) => resolve(querySync(desc))),
+  querySync,
+  revoke: (desc: PermissionDescriptor) =>
+    new Promise((resolve) => resolve(revokeSync(desc))),
+  revokeSync,
+  request: (desc: PermissionDescriptor) =>
+    new Promise((resolve) => resolve(requestSync(desc))),
+  requestSync,
+};
+
+// @ts-ignore: mdeno internal API
+Object.assign(globalThis.__mdeno__.permissions, {
+  permissions,
+  PermissionStatus,
+});
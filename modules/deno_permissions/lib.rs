@@ -0,0 +1,196 @@
+// Copyright 2018-2025 the Deno authors. MIT license.
+mod permission_store;
+
+pub use permission_store::{PERMISSION_NAMES, PermissionState, PermissionStore};
+
+use permission_store::normalize_resource;
+use rquickjs::{Ctx, Module};
+use std::cell::RefCell;
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::{LazyLock, Mutex, MutexGuard, PoisonError};
+use utils::{DenoResult, add_internal_function};
+use utils_macros::include_ts;
+
+static PERMISSIONS: LazyLock<Mutex<PermissionStore>> =
+    LazyLock::new(|| Mutex::new(PermissionStore::default()));
+
+thread_local! {
+    /// Stores pushed by scoped tests, innermost last. They shadow the shared
+    /// store on this thread only, so test files running on other threads keep
+    /// their own permissions.
+    static SCOPES: RefCell<Vec<PermissionStore>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Access the permission store shared by the runtime
+pub fn permissions() -> MutexGuard<'static, PermissionStore> {
+    PERMISSIONS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Run `f` on the store in effect on this thread: the innermost scope, or the
+/// shared store when no scope is active
+fn with_permissions<R>(f: impl FnOnce(&mut PermissionStore) -> R) -> R {
+    SCOPES.with_borrow_mut(|scopes| match scopes.last_mut() {
+        Some(store) => f(store),
+        None => f(&mut permissions()),
+    })
+}
+
+/// A copy of the store in effect on this thread
+pub fn current_permissions() -> PermissionStore {
+    with_permissions(|store| store.clone())
+}
+
+/// A copy of this thread's innermost scope, to carry it onto another thread
+pub fn current_scope() -> Option<PermissionStore> {
+    SCOPES.with_borrow(|scopes| scopes.last().cloned())
+}
+
+/// Make `store` the permissions of this thread until the matching
+/// [`pop_scope`]
+pub fn push_scope(store: PermissionStore) {
+    SCOPES.with_borrow_mut(|scopes| scopes.push(store));
+}
+
+/// Drop the innermost scope pushed by [`push_scope`]
+pub fn pop_scope() {
+    SCOPES.with_borrow_mut(Vec::pop);
+}
+
+/// Run `f` with `scope` pushed, if any, popping it again even if `f` panics
+pub fn with_scope<R>(scope: Option<PermissionStore>, f: impl FnOnce() -> R) -> R {
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            pop_scope();
+        }
+    }
+
+    let _guard = scope.map(|scope| {
+        push_scope(scope);
+        Guard
+    });
+    f()
+}
+
+/// Replace the permission store (called before the runtime starts)
+pub fn set_permissions(store: PermissionStore) {
+    *permissions() = store;
+}
+
+/// Check that `name` is granted for `resource` before touching it
+///
+/// # Errors
+/// Returns a `PermissionDenied` error if the resource is denied
+pub fn check(name: &str, resource: &str) -> DenoResult<()> {
+    let resource = normalize_resource(name, resource);
+    if with_permissions(|store| store.query(name, Some(&resource))) == PermissionState::Granted {
+        return Ok(());
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::PermissionDenied,
+        format!("Requires {name} access to \"{resource}\""),
+    )
+    .into())
+}
+
+/// Deny `name` for each resource (--deny-read, --deny-write)
+pub fn deny(name: &str, resources: &[String]) {
+    let mut store = permissions();
+    for resource in resources {
+        store.revoke(name, Some(&normalize_resource(name, resource)));
+    }
+}
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    setup_internal(ctx)?;
+    let js_source = include_ts!("deno_permissions.ts");
+    let module = Module::evaluate(ctx.clone(), "deno_permissions", js_source)?;
+    module.finish::<()>()?;
+    Ok(())
+}
+
+fn setup_internal(ctx: &Ctx) -> rquickjs::Result<()> {
+    ctx.eval::<(), _>("globalThis[Symbol.for('mdeno.internal')].permissions = {};")?;
+
+    add_internal_function!(ctx, "permissions.query", query_permission);
+    add_internal_function!(ctx, "permissions.revoke", revoke_permission);
+    add_internal_function!(ctx, "permissions.request", request_permission);
+
+    Ok(())
+}
+
+fn query_permission(name: String, resource: Option<String>) -> String {
+    let resource = resource.map(|r| normalize_resource(&name, &r));
+    with_permissions(|store| store.query(&name, resource.as_deref()))
+        .as_str()
+        .to_string()
+}
+
+fn revoke_permission(name: String, resource: Option<String>) -> String {
+    let resource = resource.map(|r| normalize_resource(&name, &r));
+    with_permissions(|store| {
+        store.revoke(&name, resource.as_deref());
+        store.query(&name, resource.as_deref()).as_str().to_string()
+    })
+}
+
+fn request_permission(name: String, resource: Option<String>) -> String {
+    let resource = resource.map(|r| normalize_resource(&name, &r));
+    request(&name, resource.as_deref())
+}
+
+/// Prompt the user on stderr for a permission that isn't granted yet.
+/// A revoked or refused resource isn't asked about again, since granting it
+/// couldn't lift the denial.
+fn request(name: &str, resource: Option<&str>) -> String {
+    let (state, denied) = with_permissions(|store| {
+        let denied = resource.is_some_and(|resource| store.is_denied(name, resource));
+        (store.query(name, resource), denied)
+    });
+    if state == PermissionState::Granted || denied {
+        return state.as_str().to_string();
+    }
+
+    // Without a terminal there is nobody to ask
+    if !std::io::stdin().is_terminal() {
+        return state.as_str().to_string();
+    }
+
+    let target = match resource {
+        Some(resource) => format!("{name} access to {resource}"),
+        None => format!("{name} access"),
+    };
+    let mut stderr = std::io::stderr();
+    let _ = write!(stderr, "⚠️ Deno requests {target}. Allow? [y/n] ");
+    let _ = stderr.flush();
+
+    let mut answer = String::new();
+    let _ = std::io::stdin().lock().read_line(&mut answer);
+
+    let allow = matches!(answer.trim(), "y" | "Y" | "yes");
+    with_permissions(|store| {
+        if allow {
+            store.grant(name, resource);
+        } else {
+            store.revoke(name, resource);
+        }
+        store.query(name, resource).as_str().to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_only_applies_to_its_thread() {
+        push_scope(PermissionStore::new(PermissionState::Denied));
+        assert!(check("read", "/tmp").is_err());
+        let other = std::thread::spawn(|| check("read", "/tmp").is_ok());
+        assert!(other.join().unwrap_or(false));
+        pop_scope();
+        assert!(check("read", "/tmp").is_ok());
+    }
+}
@@ -0,0 +1,273 @@
+// Permission state tracking for Deno.permissions
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    Prompt,
+}
+
+impl PermissionState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Granted => "granted",
+            Self::Denied => "denied",
+            Self::Prompt => "prompt",
+        }
+    }
+}
+
+/// Permission names accepted by `Deno.permissions`
+pub const PERMISSION_NAMES: &[&str] =
+    &["read", "write", "net", "env", "sys", "run", "ffi", "import"];
+
+/// Whether the resources of a permission are file system paths
+fn is_path_permission(name: &str) -> bool {
+    matches!(name, "read" | "write" | "ffi")
+}
+
+/// State of a single permission name (e.g. "read")
+#[derive(Debug, Clone)]
+struct UnaryPermission {
+    global: PermissionState,
+    granted: HashSet<String>,
+    denied: HashSet<String>,
+}
+
+impl UnaryPermission {
+    fn new(global: PermissionState) -> Self {
+        Self {
+            global,
+            granted: HashSet::new(),
+            denied: HashSet::new(),
+        }
+    }
+}
+
+/// Tracks the granted/denied state of every permission
+///
+/// mdeno runs with all permissions granted by default, so a fresh store
+/// grants everything until a permission is revoked.
+#[derive(Debug, Clone)]
+pub struct PermissionStore {
+    permissions: HashMap<&'static str, UnaryPermission>,
+}
+
+impl Default for PermissionStore {
+    fn default() -> Self {
+        Self::new(PermissionState::Granted)
+    }
+}
+
+impl PermissionStore {
+    pub fn new(state: PermissionState) -> Self {
+        Self {
+            permissions: PERMISSION_NAMES
+                .iter()
+                .map(|name| (*name, UnaryPermission::new(state)))
+                .collect(),
+        }
+    }
+
+    /// Query the state of `name`, optionally scoped to a resource
+    /// (a path, host, variable name, ...)
+    pub fn query(&self, name: &str, resource: Option<&str>) -> PermissionState {
+        let Some(permission) = self.permissions.get(name) else {
+            return PermissionState::Denied;
+        };
+
+        let Some(resource) = resource else {
+            return permission.global;
+        };
+
+        if permission
+            .denied
+            .iter()
+            .any(|entry| resource_matches(name, entry, resource))
+        {
+            return PermissionState::Denied;
+        }
+        if permission
+            .granted
+            .iter()
+            .any(|entry| resource_matches(name, entry, resource))
+        {
+            return PermissionState::Granted;
+        }
+        permission.global
+    }
+
+    /// Whether a denial stored for `resource`, or for a path above it,
+    /// decides its state. A grant for `resource` can't override one.
+    pub fn is_denied(&self, name: &str, resource: &str) -> bool {
+        self.permissions.get(name).is_some_and(|permission| {
+            permission
+                .denied
+                .iter()
+                .any(|entry| resource_matches(name, entry, resource))
+        })
+    }
+
+    /// Grant `name`, optionally scoped to a resource
+    pub fn grant(&mut self, name: &str, resource: Option<&str>) {
+        let Some(permission) = self.permissions.get_mut(name) else {
+            return;
+        };
+        if let Some(resource) = resource {
+            permission.denied.retain(|entry| entry != resource);
+            permission.granted.insert(resource.to_string());
+        } else {
+            permission.global = PermissionState::Granted;
+            permission.denied.clear();
+        }
+    }
+
+    /// Downgrade `name` to denied, optionally scoped to a resource
+    pub fn revoke(&mut self, name: &str, resource: Option<&str>) {
+        let Some(permission) = self.permissions.get_mut(name) else {
+            return;
+        };
+        if let Some(resource) = resource {
+            permission.granted.retain(|entry| entry != resource);
+            permission.denied.insert(resource.to_string());
+        } else {
+            permission.global = PermissionState::Denied;
+            permission.granted.clear();
+        }
+    }
+
+    /// Narrow `name` to only the given resources, or deny it entirely when
+    /// there are none
+    ///
+    /// # Errors
+    /// Returns an error if a resource isn't granted already, since a narrower
+    /// scope can't escalate
+    pub fn restrict(&mut self, name: &str, resources: &[String]) -> Result<(), String> {
+        let resources: Vec<String> = resources
+            .iter()
+            .map(|resource| normalize_resource(name, resource))
+            .collect();
+        if let Some(resource) = resources
+            .iter()
+            .find(|resource| self.query(name, Some(resource)) != PermissionState::Granted)
+        {
+            return Err(format!(
+                "Can't escalate parent permissions: {name} access to {resource}"
+            ));
+        }
+        let Some(permission) = self.permissions.get_mut(name) else {
+            return Ok(());
+        };
+        permission.global = PermissionState::Denied;
+        permission.denied.clear();
+        permission.granted = resources.into_iter().collect();
+        Ok(())
+    }
+}
+
+/// Check whether a stored entry covers the queried resource.
+/// Path entries also cover everything below them.
+fn resource_matches(name: &str, entry: &str, resource: &str) -> bool {
+    if is_path_permission(name) {
+        Path::new(resource).starts_with(Path::new(entry))
+    } else {
+        entry == resource
+    }
+}
+
+/// Normalize a resource for storage and lookup.
+/// Paths are made absolute so "./foo" and "/cwd/foo" are the same entry.
+pub fn normalize_resource(name: &str, resource: &str) -> String {
+    if is_path_permission(name) {
+        std::path::absolute(resource)
+            .unwrap_or_else(|_| PathBuf::from(resource))
+            .display()
+            .to_string()
+    } else {
+        resource.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_granted_by_default() {
+        let store = PermissionStore::default();
+        assert_eq!(store.query("read", None), PermissionState::Granted);
+        assert_eq!(
+            store.query("net", Some("deno.land")),
+            PermissionState::Granted
+        );
+    }
+
+    #[test]
+    fn test_revoke_path_covers_children() {
+        let mut store = PermissionStore::default();
+        store.revoke("read", Some("/tmp/secret"));
+        assert_eq!(
+            store.query("read", Some("/tmp/secret/key")),
+            PermissionState::Denied
+        );
+        assert_eq!(
+            store.query("read", Some("/tmp/other")),
+            PermissionState::Granted
+        );
+        assert_eq!(store.query("read", None), PermissionState::Granted);
+    }
+
+    #[test]
+    fn test_deny_wins_over_grant() {
+        let mut store = PermissionStore::new(PermissionState::Denied);
+        store.grant("read", Some("/tmp"));
+        store.revoke("read", Some("/tmp/secret"));
+        assert_eq!(
+            store.query("read", Some("/tmp/other")),
+            PermissionState::Granted
+        );
+        assert_eq!(
+            store.query("read", Some("/tmp/secret/key")),
+            PermissionState::Denied
+        );
+
+        // Granting the parent again doesn't lift the narrower denial
+        store.grant("read", Some("/tmp"));
+        assert_eq!(
+            store.query("read", Some("/tmp/secret")),
+            PermissionState::Denied
+        );
+        assert!(store.is_denied("read", "/tmp/secret/key"));
+        assert!(!store.is_denied("read", "/tmp/other"));
+    }
+
+    #[test]
+    fn test_grant_after_revoke() {
+        let mut store = PermissionStore::default();
+        store.revoke("env", None);
+        assert_eq!(store.query("env", Some("HOME")), PermissionState::Denied);
+        store.grant("env", Some("HOME"));
+        assert_eq!(store.query("env", Some("HOME")), PermissionState::Granted);
+        assert_eq!(store.query("env", Some("PATH")), PermissionState::Denied);
+    }
+
+    #[test]
+    fn test_restrict() {
+        let mut store = PermissionStore::default();
+        assert!(store.restrict("read", &["/tmp".to_string()]).is_ok());
+        assert_eq!(
+            store.query("read", Some("/tmp/file")),
+            PermissionState::Granted
+        );
+        assert_eq!(store.query("read", Some("/etc")), PermissionState::Denied);
+        assert_eq!(store.query("read", None), PermissionState::Denied);
+
+        // A narrower scope can't grant what was already taken away
+        assert!(store.restrict("read", &["/etc".to_string()]).is_err());
+        assert!(store.restrict("write", &[]).is_ok());
+        assert_eq!(store.query("write", Some("/tmp")), PermissionState::Denied);
+    }
+}
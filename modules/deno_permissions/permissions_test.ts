@@ -0,0 +1,79 @@
+// Deno.permissions.revoke enforcement tests
+
+const dir = Deno.makeTempDirSync({ prefix: "mdeno_permissions_" });
+Deno.mkdirSync(`${dir}/secret`);
+Deno.writeTextFileSync(`${dir}/secret/key`, "secret");
+Deno.writeTextFileSync(`${dir}/other`, "other");
+
+Deno.test({
+  name: "permissions - revoking a path denies it but not its siblings",
+  permissions: { read: [dir], write: [dir] },
+  fn() {
+    const status = Deno.permissions.querySync({
+      name: "read",
+      path: `${dir}/secret`,
+    });
+    const changes: string[] = [];
+    status.onchange = () => changes.push(status.state);
+
+    Deno.permissions.revokeSync({ name: "read", path: `${dir}/secret` });
+    if (changes.join() !== "denied") {
+      throw new Error(`onchange: ${changes}`);
+    }
+
+    if (Deno.readTextFileSync(`${dir}/other`) !== "other") {
+      throw new Error("sibling should still be readable");
+    }
+    try {
+      Deno.readTextFileSync(`${dir}/secret/key`);
+      throw new Error("should have been denied");
+    } catch (error) {
+      if (!(error instanceof Deno.errors.PermissionDenied)) throw error;
+    }
+    // Writing is a separate permission
+    Deno.writeTextFileSync(`${dir}/secret/key`, "rotated");
+  },
+});
+
+Deno.test({
+  name: "permissions - requesting a path below a revoked one stays denied",
+  permissions: { read: [dir] },
+  fn() {
+    Deno.permissions.revokeSync({ name: "read", path: `${dir}/secret` });
+    const status = Deno.permissions.requestSync({
+      name: "read",
+      path: `${dir}/secret/key`,
+    });
+    if (status.state !== "denied") throw new Error(`state: ${status.state}`);
+    const other = Deno.permissions.requestSync({ name: "read", path: `${dir}/other` });
+    if (other.state !== "granted") throw new Error(`other: ${other.state}`);
+  },
+});
+
+Deno.test({
+  name: "permissions - fs operations check the scoped permissions",
+  permissions: { read: [`${dir}/other`] },
+  fn() {
+    Deno.statSync(`${dir}/other`);
+    for (const op of [
+      () => Deno.readDirSync(dir),
+      () => Deno.writeTextFileSync(`${dir}/other`, "x"),
+      () => Deno.removeSync(`${dir}/other`),
+      () => Deno.openSync(`${dir}/other`, { write: true }),
+    ]) {
+      try {
+        op();
+        throw new Error(`should have been denied: ${op}`);
+      } catch (error) {
+        if (!(error instanceof Deno.errors.PermissionDenied)) throw error;
+      }
+    }
+  },
+});
+
+Deno.test("permissions - revocations end with the scoped test", () => {
+  if (Deno.readTextFileSync(`${dir}/secret/key`) !== "rotated") {
+    throw new Error("read should be granted again");
+  }
+  Deno.removeSync(dir, { recursive: true });
+});
@@ -0,0 +1,183 @@
+// Assertion helpers, imported from "mdeno:test"
+
+// deno-lint-ignore no-explicit-any
+type ErrorClass = new (...args: any[]) => Error;
+
+export class AssertionError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "AssertionError";
+  }
+}
+
+function format(value: unknown): string {
+  // @ts-ignore: Deno.inspect is registered by deno_ns
+  const inspect = globalThis.Deno?.inspect;
+  return typeof inspect === "function" ? inspect(value) : String(value);
+}
+
+function suffix(msg?: string): string {
+  return msg ? `: ${msg}` : "";
+}
+
+// Deep equality: same primitives (by Object.is), or objects of the same
+// prototype whose entries are equal
+export function equal(a: unknown, b: unknown): boolean {
+  return compare(a, b, new Map());
+}
+
+function compare(a: unknown, b: unknown, seen: Map<object, object>): boolean {
+  if (Object.is(a, b)) return true;
+  if (
+    typeof a !== "object" || typeof b !== "object" || a === null ||
+    b === null
+  ) {
+    return false;
+  }
+  if (Object.getPrototypeOf(a) !== Object.getPrototypeOf(b)) return false;
+  // Cyclic structures are equal where they cycle back the same way
+  if (seen.get(a) === b) return true;
+  seen.set(a, b);
+
+  if (a instanceof Date) {
+    return Object.is(a.getTime(), (b as Date).getTime());
+  }
+  if (a instanceof RegExp) {
+    return String(a) === String(b);
+  }
+  if (a instanceof Map) {
+    const other = b as Map<unknown, unknown>;
+    if (a.size !== other.size) return false;
+    for (const [key, value] of a) {
+      if (!other.has(key) || !compare(value, other.get(key), seen)) {
+        return false;
+      }
+    }
+    return true;
+  }
+  if (a instanceof Set) {
+    const other = b as Set<unknown>;
+    if (a.size !== other.size) return false;
+    for (const value of a) {
+      if (
+        !other.has(value) &&
+        ![...other].some((candidate) => compare(value, candidate, seen))
+      ) {
+        return false;
+      }
+    }
+    return true;
+  }
+  if (ArrayBuffer.isView(a)) {
+    const left = new Uint8Array(a.buffer, a.byteOffset, a.byteLength);
+    const view = b as ArrayBufferView;
+    const right = new Uint8Array(view.buffer, view.byteOffset, view.byteLength);
+    return left.length === right.length &&
+      left.every((byte, i) => byte === right[i]);
+  }
+
+  const keysA = Reflect.ownKeys(a);
+  const keysB = Reflect.ownKeys(b);
+  if (keysA.length !== keysB.length) return false;
+  const record = b as Record<PropertyKey, unknown>;
+  return keysA.every((key) =>
+    Object.prototype.hasOwnProperty.call(b, key) &&
+    compare((a as Record<PropertyKey, unknown>)[key], record[key], seen)
+  );
+}
+
+export function assert(expr: unknown, msg = ""): asserts expr {
+  if (!expr) {
+    throw new AssertionError(msg || "Expected expression to be truthy");
+  }
+}
+
+export function assertEquals<T>(actual: T, expected: T, msg?: string): void {
+  if (equal(actual, expected)) return;
+  throw new AssertionError(
+    `Values are not equal${suffix(msg)}\n\n` +
+      `    actual: ${format(actual)}\n  expected: ${format(expected)}`,
+  );
+}
+
+export function assertNotEquals<T>(actual: T, expected: T, msg?: string): void {
+  if (!equal(actual, expected)) return;
+  throw new AssertionError(
+    `Expected actual: ${format(actual)} not to be: ${format(expected)}${
+      suffix(msg)
+    }`,
+  );
+}
+
+export function assertExists<T>(
+  actual: T,
+  msg?: string,
+): asserts actual is NonNullable<T> {
+  if (actual === null || actual === undefined) {
+    throw new AssertionError(
+      `Expected actual: "${actual}" to not be null or undefined${suffix(msg)}`,
+    );
+  }
+}
+
+// Check a thrown value against the expected class and message
+function checkError(
+  error: unknown,
+  ErrorClass?: ErrorClass,
+  msgIncludes?: string,
+): void {
+  if (ErrorClass === undefined) return;
+  if (!(error instanceof ErrorClass)) {
+    const actual = error instanceof Error ? error.constructor.name : format(error);
+    throw new AssertionError(
+      `Expected error to be instance of "${ErrorClass.name}", but was "${actual}"`,
+    );
+  }
+  if (msgIncludes !== undefined && !error.message.includes(msgIncludes)) {
+    throw new AssertionError(
+      `Expected error message to include "${msgIncludes}", but got "${error.message}"`,
+    );
+  }
+}
+
+export function assertThrows(
+  fn: () => unknown,
+  ErrorClass?: ErrorClass,
+  msgIncludes?: string,
+): unknown {
+  try {
+    fn();
+  } catch (error) {
+    checkError(error, ErrorClass, msgIncludes);
+    return error;
+  }
+  throw new AssertionError("Expected function to throw");
+}
+
+export async function assertRejects(
+  fn: () => PromiseLike<unknown>,
+  ErrorClass?: ErrorClass,
+  msgIncludes?: string,
+): Promise<unknown> {
+  let result;
+  try {
+    result = fn();
+  } catch (error) {
+    // Throwing before returning a promise isn't a rejection
+    throw new AssertionError(
+      `Function throws when expected to reject: ${format(error)}`,
+    );
+  }
+  if (typeof result?.then !== "function") {
+    throw new AssertionError(
+      "Function returned a non-promise when expected to reject",
+    );
+  }
+  try {
+    await result;
+  } catch (error) {
+    checkError(error, ErrorClass, msgIncludes);
+    return error;
+  }
+  throw new AssertionError("Expected promise to reject");
+}
@@ -0,0 +1,75 @@
+// mdeno:test assertion E2E tests
+import {
+  AssertionError,
+  assertEquals,
+  assertExists,
+  assertNotEquals,
+  assertRejects,
+  assertThrows,
+} from "mdeno:test";
+
+Deno.test("assertions - assertEquals compares deeply", () => {
+  assertEquals({ a: [1, { b: 2 }], c: new Map([["d", new Set([3])]]) }, {
+    a: [1, { b: 2 }],
+    c: new Map([["d", new Set([3])]]),
+  });
+  assertEquals(new Date(0), new Date(0));
+  assertEquals(new Uint8Array([1, 2]), new Uint8Array([1, 2]));
+  assertEquals(NaN, NaN);
+  assertNotEquals({ a: 1 }, { a: 2 });
+  assertNotEquals([1], { 0: 1 });
+
+  const cyclic: Record<string, unknown> = { name: "a" };
+  cyclic.self = cyclic;
+  const other: Record<string, unknown> = { name: "a" };
+  other.self = other;
+  assertEquals(cyclic, other);
+});
+
+Deno.test("assertions - assertEquals reports both values", () => {
+  const error = assertThrows(
+    () => assertEquals({ a: 1 }, { a: 2 }, "mismatch"),
+    AssertionError,
+    "Values are not equal: mismatch",
+  ) as Error;
+  assertEquals(error.message.includes("expected:"), true);
+});
+
+Deno.test("assertions - assertThrows checks the error class and message", () => {
+  assertThrows(() => {
+    throw new TypeError("bad input");
+  }, TypeError, "bad");
+  assertThrows(() => assertThrows(() => {}), AssertionError, "to throw");
+  assertThrows(
+    () =>
+      assertThrows(() => {
+        throw new Error("x");
+      }, TypeError),
+    AssertionError,
+    'instance of "TypeError"',
+  );
+});
+
+Deno.test("assertions - assertRejects awaits the function", async () => {
+  const error = await assertRejects(
+    async () => {
+      await Promise.resolve();
+      throw new RangeError("out of range");
+    },
+    RangeError,
+    "range",
+  );
+  assertEquals((error as Error).message, "out of range");
+  await assertRejects(
+    () => assertRejects(async () => {}),
+    AssertionError,
+    "to reject",
+  );
+});
+
+Deno.test("assertions - assertExists rejects null and undefined", () => {
+  assertExists(0);
+  assertExists("");
+  assertThrows(() => assertExists(null), AssertionError);
+  assertThrows(() => assertExists(undefined), AssertionError);
+});
@@ -0,0 +1,124 @@
+// Deno.bench() for `mdeno bench`; outside of it benchmarks are ignored
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+// Time spent running a benchmark before measuring it
+const WARMUP_MS = 100;
+// Time spent measuring, as long as MIN_ITERATIONS have run
+const MEASURE_MS = 500;
+const MIN_ITERATIONS = 10;
+const MAX_ITERATIONS = 100_000;
+
+type BenchFn = () => void | Promise<void>;
+
+interface BenchDef {
+  name: string;
+  fn: BenchFn;
+  ignore: boolean;
+  only: boolean;
+}
+
+const benches: BenchDef[] = [];
+let scheduled = false;
+
+function bench(
+  nameOrOptions: string | Partial<BenchDef> | BenchFn,
+  fn?: BenchFn | Partial<BenchDef>,
+): void {
+  let options: Partial<BenchDef>;
+  if (typeof nameOrOptions === "string") {
+    options = typeof fn === "function"
+      ? { name: nameOrOptions, fn }
+      : { ...fn, name: nameOrOptions };
+  } else if (typeof nameOrOptions === "function") {
+    options = { name: nameOrOptions.name, fn: nameOrOptions };
+  } else {
+    // Deno.bench({ name, fn }) or Deno.bench(options, fn)
+    options = typeof fn === "function"
+      ? { ...nameOrOptions, fn }
+      : nameOrOptions;
+  }
+  const name = options.name || options.fn?.name;
+  if (typeof options.fn !== "function") {
+    throw new TypeError("Missing bench function");
+  }
+  if (!name) {
+    throw new TypeError("The bench name can't be empty");
+  }
+  if (!__internal.bench.enabled()) return;
+
+  benches.push({
+    name,
+    fn: options.fn,
+    ignore: Boolean(options.ignore),
+    only: Boolean(options.only),
+  });
+  if (!scheduled) {
+    scheduled = true;
+    // Run once every benchmark of the module has been registered
+    globalThis.addEventListener("load", () => {
+      runBenches();
+    });
+  }
+}
+
+async function runOnce(fn: BenchFn): Promise<number> {
+  const start = performance.now();
+  const result = fn();
+  if (result instanceof Promise) await result;
+  return performance.now() - start;
+}
+
+function percentile(sorted: number[], p: number): number {
+  return sorted[Math.max(0, Math.ceil(sorted.length * p) - 1)];
+}
+
+async function measure(def: BenchDef) {
+  let warmupIterations = 0;
+  const warmupEnd = performance.now() + WARMUP_MS;
+  while (performance.now() < warmupEnd) {
+    await runOnce(def.fn);
+    warmupIterations++;
+  }
+
+  const samples: number[] = [];
+  const measureEnd = performance.now() + MEASURE_MS;
+  while (
+    samples.length < MIN_ITERATIONS ||
+    (samples.length < MAX_ITERATIONS && performance.now() < measureEnd)
+  ) {
+    samples.push((await runOnce(def.fn)) * 1e6);
+  }
+
+  samples.sort((a, b) => a - b);
+  const avgNs = samples.reduce((sum, ns) => sum + ns, 0) / samples.length;
+  return {
+    name: def.name,
+    warmupIterations,
+    totalIterations: samples.length,
+    avgNs,
+    minNs: samples[0],
+    maxNs: samples[samples.length - 1],
+    p75Ns: percentile(samples, 0.75),
+    p99Ns: percentile(samples, 0.99),
+    throughput: avgNs > 0 ? 1e9 / avgNs : 0,
+  };
+}
+
+async function runBenches(): Promise<void> {
+  const hasOnly = benches.some((def) => def.only);
+  for (const def of benches) {
+    if (def.ignore || (hasOnly && !def.only)) continue;
+    let result;
+    try {
+      result = await measure(def);
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      result = { name: def.name, error: message };
+    }
+    __internal.bench.report(JSON.stringify(result));
+  }
+}
+
+// @ts-ignore: Deno.bench is not part of mdeno's Deno types
+globalThis.Deno.bench = bench;
@@ -0,0 +1,25 @@
+// Deno.bench() outside of `mdeno bench`
+
+// Deno.bench is not part of mdeno's Deno types yet
+const bench = (Deno as unknown as {
+  bench(name: unknown, fn?: unknown): void;
+}).bench;
+
+Deno.test("Deno.bench - benchmarks only run under mdeno bench", () => {
+  let ran = false;
+  bench("not run", () => {
+    ran = true;
+  });
+  bench({ name: "not run either", fn: () => (ran = true) });
+  if (ran) throw new Error("Benchmark ran outside of mdeno bench");
+});
+
+Deno.test("Deno.bench - a function is required", () => {
+  let error;
+  try {
+    bench("missing function");
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof TypeError)) throw new Error("Expected a TypeError");
+});
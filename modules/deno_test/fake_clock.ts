@@ -0,0 +1,142 @@
+// Fake timers for Deno.test({ fakeClock: true })
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+// Give up on runAll() after this many timers, as an interval never drains
+const MAX_TIMERS = 1000;
+
+interface Timer {
+  id: number;
+  time: number;
+  callback: (...args: unknown[]) => void;
+  args: unknown[];
+  interval: number | null;
+}
+
+const TIMER_GLOBALS = [
+  "setTimeout",
+  "setInterval",
+  "clearTimeout",
+  "clearInterval",
+  "performance",
+] as const;
+
+class FakeClock {
+  #now = Date.now();
+  // Whole milliseconds, so differences of fake times come out exact
+  #start = Math.floor(performance.now());
+  #nextId = 1;
+  // Pending timers sorted by fire time, then by creation order
+  #timers: Timer[] = [];
+  #originals: Map<string, PropertyDescriptor | undefined> | null = null;
+  #originalDateNow: (() => number) | null = null;
+
+  get now(): number {
+    return this.#now;
+  }
+
+  install(): void {
+    if (this.#originals) return;
+    const global = globalThis as Record<string, unknown>;
+    this.#originals = new Map(
+      TIMER_GLOBALS.map((name) => [
+        name,
+        Object.getOwnPropertyDescriptor(globalThis, name),
+      ]),
+    );
+    this.#originalDateNow = Date.now;
+
+    const schedule = (interval: boolean) =>
+    (
+      callback: (...args: unknown[]) => void,
+      delay = 0,
+      ...args: unknown[]
+    ): number => {
+      if (typeof callback !== "function") {
+        throw new TypeError("Callback must be a function");
+      }
+      const ms = Math.max(0, Number(delay) || 0);
+      const id = this.#nextId++;
+      this.#insert({
+        id,
+        time: this.#now + ms,
+        callback,
+        args,
+        // An interval of 0 would never let time advance
+        interval: interval ? Math.max(1, ms) : null,
+      });
+      return id;
+    };
+    const clear = (id?: number) => {
+      this.#timers = this.#timers.filter((timer) => timer.id !== id);
+    };
+
+    global.setTimeout = schedule(false);
+    global.setInterval = schedule(true);
+    global.clearTimeout = clear;
+    global.clearInterval = clear;
+    Date.now = () => this.#now;
+    // performance.now is read-only, so stand in for the whole object
+    const start = this.#start;
+    const origin = this.#now;
+    global.performance = Object.create(performance, {
+      now: { value: () => start + (this.#now - origin) },
+    });
+  }
+
+  uninstall(): void {
+    if (!this.#originals) return;
+    const global = globalThis as Record<string, unknown>;
+    for (const [name, descriptor] of this.#originals) {
+      if (descriptor) {
+        Object.defineProperty(globalThis, name, descriptor);
+      } else {
+        delete global[name];
+      }
+    }
+    Date.now = this.#originalDateNow!;
+    this.#originals = null;
+    this.#originalDateNow = null;
+    this.#timers = [];
+  }
+
+  /** Advance time by `ms`, firing every timer due within that window */
+  tick(ms: number): void {
+    const target = this.#now + Math.max(0, Number(ms) || 0);
+    while (this.#timers.length > 0 && this.#timers[0].time <= target) {
+      this.#fire(this.#timers.shift()!);
+    }
+    this.#now = target;
+  }
+
+  /** Fire pending timers in order until none are left */
+  runAll(): void {
+    for (let fired = 0; this.#timers.length > 0; fired++) {
+      if (fired >= MAX_TIMERS) {
+        throw new Error(
+          `Aborting after running ${MAX_TIMERS} timers, assuming an infinite loop`,
+        );
+      }
+      this.#fire(this.#timers.shift()!);
+    }
+  }
+
+  #fire(timer: Timer): void {
+    this.#now = Math.max(this.#now, timer.time);
+    if (timer.interval !== null) {
+      this.#insert({ ...timer, time: this.#now + timer.interval });
+    }
+    timer.callback(...timer.args);
+  }
+
+  #insert(timer: Timer): void {
+    const index = this.#timers.findIndex((other) => other.time > timer.time);
+    if (index === -1) {
+      this.#timers.push(timer);
+    } else {
+      this.#timers.splice(index, 0, timer);
+    }
+  }
+}
+
+__internal.test.FakeClock = FakeClock;
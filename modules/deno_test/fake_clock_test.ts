@@ -0,0 +1,77 @@
+// Deno.test({ fakeClock: true }) E2E tests
+
+// t.clock is an mdeno extension to Deno.TestContext
+interface FakeClock {
+  readonly now: number;
+  tick(ms: number): void;
+  runAll(): void;
+}
+type ClockContext = { clock: FakeClock };
+
+const originalSetTimeout = globalThis.setTimeout;
+const originalPerformance = globalThis.performance;
+
+Deno.test({ name: "fakeClock - tick fires due timers", fakeClock: true }, (t) => {
+  const { clock } = t as unknown as ClockContext;
+  const fired: string[] = [];
+  setTimeout(() => fired.push("b"), 200);
+  setTimeout(() => fired.push("a"), 100);
+  const cancelled = setTimeout(() => fired.push("cancelled"), 150);
+  clearTimeout(cancelled);
+
+  clock.tick(99);
+  if (fired.length !== 0) throw new Error(`Fired too early: ${fired}`);
+  clock.tick(1);
+  if (fired.join(",") !== "a") throw new Error(`Expected a, got ${fired}`);
+  clock.tick(100);
+  if (fired.join(",") !== "a,b") throw new Error(`Expected a,b, got ${fired}`);
+});
+
+Deno.test({ fakeClock: true }, async function fakeClockIntervalAndNow(t) {
+  const { clock } = t as unknown as ClockContext;
+  const dateStart = Date.now();
+  const perfStart = performance.now();
+  let count = 0;
+  const id = setInterval(() => {
+    count++;
+    if (count === 3) clearInterval(id);
+  }, 50);
+
+  clock.runAll();
+  if (count !== 3) throw new Error(`Expected 3 ticks, got ${count}`);
+  if (Date.now() - dateStart !== 150) {
+    throw new Error(`Date.now advanced ${Date.now() - dateStart}ms`);
+  }
+  if (performance.now() - perfStart !== 150) {
+    throw new Error(`performance.now advanced ${performance.now() - perfStart}ms`);
+  }
+
+  // Timers resolve promises without waiting for real time
+  const waited = new Promise((resolve) => setTimeout(resolve, 60_000));
+  clock.tick(60_000);
+  await waited;
+});
+
+Deno.test({
+  name: "fakeClock - stays installed while an async test waits on I/O",
+  fakeClock: true,
+}, async () => {
+  const path = Deno.makeTempFileSync();
+  await Deno.readFile(path);
+  Deno.removeSync(path);
+  if (globalThis.setTimeout === originalSetTimeout) {
+    throw new Error("The fake clock was uninstalled before the test settled");
+  }
+});
+
+Deno.test("fakeClock - timers are restored after the test", () => {
+  if (globalThis.setTimeout !== originalSetTimeout) {
+    throw new Error("Fake setTimeout leaked out of its test");
+  }
+  if (globalThis.performance !== originalPerformance) {
+    throw new Error("Fake performance leaked out of its test");
+  }
+  if (Math.abs(Date.now() - new Date().getTime()) > 1000) {
+    throw new Error("Date.now is still faked");
+  }
+});
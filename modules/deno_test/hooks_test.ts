@@ -0,0 +1,119 @@
+// Test hooks and describe() E2E tests
+
+// Hooks and describe() are mdeno extensions to the Deno namespace
+const { beforeAll, afterAll, beforeEach, afterEach, describe } =
+  Deno as unknown as {
+    beforeAll(fn: () => void | Promise<void>): void;
+    afterAll(fn: () => void | Promise<void>): void;
+    beforeEach(fn: () => void | Promise<void>): void;
+    afterEach(fn: () => void | Promise<void>): void;
+    describe(name: string, fn: () => void): void;
+  };
+
+const calls: string[] = [];
+
+beforeAll(() => {
+  calls.push("beforeAll");
+});
+
+beforeEach(() => {
+  calls.push("beforeEach");
+});
+
+afterEach(() => {
+  calls.push("afterEach");
+});
+
+Deno.test("hooks - beforeAll and beforeEach run first", () => {
+  const expected = ["beforeAll", "beforeEach"];
+  if (calls.join(",") !== expected.join(",")) {
+    throw new Error(`Expected ${expected}, got ${calls}`);
+  }
+});
+
+Deno.test("hooks - afterEach runs after each test", () => {
+  const expected = ["beforeAll", "beforeEach", "afterEach", "beforeEach"];
+  if (calls.join(",") !== expected.join(",")) {
+    throw new Error(`Expected ${expected}, got ${calls}`);
+  }
+});
+
+describe("suite", () => {
+  let value = 0;
+
+  beforeEach(() => {
+    value++;
+  });
+
+  Deno.test("describe - inner beforeEach runs after outer", () => {
+    if (value !== 1) {
+      throw new Error(`Expected value to be 1, got ${value}`);
+    }
+    if (calls.at(-1) !== "beforeEach") {
+      throw new Error(`Expected outer beforeEach to run, got ${calls}`);
+    }
+  });
+
+  Deno.test("describe - hooks are scoped to the suite", () => {
+    if (value !== 2) {
+      throw new Error(`Expected value to be 2, got ${value}`);
+    }
+  });
+});
+
+Deno.test("describe - hooks do not leak out of the suite", () => {
+  const beforeEachCount = calls.filter((c) => c === "beforeEach").length;
+  if (beforeEachCount !== 5) {
+    throw new Error(`Expected 5 beforeEach calls, got ${beforeEachCount}`);
+  }
+});
+
+// Set by the "async hooks" suite's afterAll
+let asyncSuiteEvents: string[] = [];
+
+describe("async hooks", () => {
+  const events: string[] = [];
+  const tick = () => new Promise((resolve) => setTimeout(resolve, 5));
+
+  beforeEach(async () => {
+    await tick();
+    events.push("beforeEach");
+  });
+
+  afterEach(async () => {
+    await tick();
+    events.push("afterEach");
+  });
+
+  afterAll(() => {
+    asyncSuiteEvents = [...events];
+  });
+
+  Deno.test("async hooks - beforeEach settles before the test", async () => {
+    if (events.join(",") !== "beforeEach") {
+      throw new Error(`Expected beforeEach, got ${events}`);
+    }
+    await tick();
+    events.push("test");
+  });
+
+  Deno.test("async hooks - afterEach settles before the next test", () => {
+    const expected = ["beforeEach", "test", "afterEach", "beforeEach"];
+    if (events.join(",") !== expected.join(",")) {
+      throw new Error(`Expected ${expected}, got ${events}`);
+    }
+  });
+});
+
+Deno.test("async hooks - afterAll waits for the suite's tests", () => {
+  const expected = [
+    "beforeEach",
+    "test",
+    "afterEach",
+    "beforeEach",
+    "afterEach",
+  ];
+  if (asyncSuiteEvents.join(",") !== expected.join(",")) {
+    throw new Error(`Expected ${expected}, got ${asyncSuiteEvents}`);
+  }
+});
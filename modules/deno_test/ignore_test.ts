@@ -0,0 +1,15 @@
+Deno.test({
+  name: "ignored with a boolean expression",
+  ignore: Deno.build.os !== "unknown",
+  fn() {
+    throw new Error("ignored tests must not run");
+  },
+});
+
+const ignored = Deno.test({ name: "ignored probe", ignore: true, fn() {} });
+const kept = Deno.test("kept probe", () => {});
+
+Deno.test("Deno.test returns whether the test is ignored", () => {
+  if (!ignored.ignore) throw new Error("expected ignore to be true");
+  if (kept.ignore) throw new Error("expected ignore to be false");
+});
@@ -5,9 +5,34 @@ mod test_context;
 mod test_runner;
 
 pub use test_context::TestContext;
-use test_runner::{deno_test, resolve_pending, run_tests, set_test_filename};
+use test_runner::{
+    after_all, after_each, before_all, before_each, bench_enabled, bench_report, deno_test,
+    describe, resolve_pending, run_tests, set_test_filename,
+};
+pub use test_runner::{
+    deadline_passed, set_bench_mode, set_only_mode, set_retries, set_timeout, take_bench_results,
+};
 
-use rquickjs::{Ctx, Function, Object, Result, Value};
+use rquickjs::{Ctx, Function, Module, Object, Result, Value};
+use utils::ModuleDef;
+use utils_macros::include_ts;
+
+/// `mdeno:test`: assertEquals, assertThrows, assertRejects and friends
+pub struct AssertionsModule;
+
+impl ModuleDef for AssertionsModule {
+    fn init(_ctx: &Ctx<'_>) -> Result<()> {
+        Ok(())
+    }
+
+    fn name() -> &'static str {
+        "mdeno:test"
+    }
+
+    fn source() -> &'static str {
+        include_ts!("assertions.ts")
+    }
+}
 
 /// # Errors
 /// Returns an error if module initialization fails
@@ -18,6 +43,13 @@ pub fn init(ctx: &Ctx<'_>) -> Result<()> {
     let deno: Object = globals.get("Deno")?;
     deno.set("test", Function::new(ctx.clone(), deno_test)?)?;
 
+    // Register setup/teardown hooks and describe() grouping
+    deno.set("beforeAll", Function::new(ctx.clone(), before_all)?)?;
+    deno.set("afterAll", Function::new(ctx.clone(), after_all)?)?;
+    deno.set("beforeEach", Function::new(ctx.clone(), before_each)?)?;
+    deno.set("afterEach", Function::new(ctx.clone(), after_each)?)?;
+    deno.set("describe", Function::new(ctx.clone(), describe)?)?;
+
     // Create globalThis[Symbol.for('mdeno.internal')] namespace
     let symbol_ctor: Function = globals.get("Symbol")?;
     let symbol_for: Function = symbol_ctor.get("for")?;
@@ -49,5 +81,20 @@ pub fn init(ctx: &Ctx<'_>) -> Result<()> {
     )?;
     internal.set("test", test_obj)?;
 
+    // FakeClock for Deno.test({ fakeClock: true })
+    let js_source = include_ts!("fake_clock.ts");
+    let module = Module::evaluate(ctx.clone(), "deno_test", js_source)?;
+    module.finish::<()>()?;
+
+    // Deno.bench, which reports results back through bench.report
+    let bench_obj = Object::new(ctx.clone())?;
+    bench_obj.set("enabled", Function::new(ctx.clone(), bench_enabled)?)?;
+    bench_obj.set("report", Function::new(ctx.clone(), bench_report)?)?;
+    internal.set("bench", bench_obj)?;
+
+    let js_source = include_ts!("bench.ts");
+    let module = Module::evaluate(ctx.clone(), "deno_test_bench", js_source)?;
+    module.finish::<()>()?;
+
     Ok(())
 }
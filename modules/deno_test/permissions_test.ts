@@ -0,0 +1,124 @@
+// Deno.test({ permissions }) E2E tests
+
+// Hooks are mdeno extensions to the Deno namespace
+const { afterEach } = Deno as unknown as {
+  afterEach(fn: () => void): void;
+};
+
+function state(desc: Deno.PermissionDescriptor): Deno.PermissionState {
+  return Deno.permissions.querySync(desc).state;
+}
+
+// The read permission seen by afterEach after every test
+const afterEachRead: Deno.PermissionState[] = [];
+
+afterEach(() => {
+  afterEachRead.push(state({ name: "read" }));
+});
+
+Deno.test({
+  name: "permissions - none denies everything during the test",
+  permissions: "none",
+  fn() {
+    if (state({ name: "read" }) !== "denied") {
+      throw new Error("read should be denied");
+    }
+    if (state({ name: "env", variable: "HOME" }) !== "denied") {
+      throw new Error("env should be denied");
+    }
+  },
+});
+
+Deno.test({
+  name: "permissions - none makes file access throw PermissionDenied",
+  permissions: "none",
+  fn() {
+    try {
+      Deno.readFileSync("/tmp/test");
+      throw new Error("readFileSync should be denied");
+    } catch (error) {
+      if (!(error instanceof Deno.errors.PermissionDenied)) throw error;
+    }
+  },
+});
+
+Deno.test({
+  name: "permissions - an object narrows each permission",
+  permissions: { read: ["/tmp"], write: false, env: "inherit" },
+  fn() {
+    if (state({ name: "read", path: "/tmp/file" }) !== "granted") {
+      throw new Error("read of /tmp should be granted");
+    }
+    if (state({ name: "read", path: "/etc" }) !== "denied") {
+      throw new Error("read of /etc should be denied");
+    }
+    if (state({ name: "write" }) !== "denied") {
+      throw new Error("write should be denied");
+    }
+    if (state({ name: "env" }) !== "granted") {
+      throw new Error("env should be inherited");
+    }
+    if (state({ name: "net" }) !== "denied") {
+      throw new Error("net should be denied when left out");
+    }
+  },
+});
+
+Deno.test({
+  name: "permissions - async tests keep their scope until they settle",
+  permissions: "none",
+  async fn() {
+    await Promise.resolve();
+    if (state({ name: "read" }) !== "denied") {
+      throw new Error("read should be denied after await");
+    }
+  },
+});
+
+Deno.test("permissions - restored after a scoped test", () => {
+  if (state({ name: "read" }) !== "granted") {
+    throw new Error("read should be granted again");
+  }
+  if (state({ name: "net" }) !== "granted") {
+    throw new Error("net should be granted again");
+  }
+});
+
+Deno.test("permissions - afterEach runs with the runner's permissions", () => {
+  if (afterEachRead.length === 0 || afterEachRead.some((s) => s !== "granted")) {
+    throw new Error(`afterEach saw read as ${afterEachRead}`);
+  }
+});
+
+Deno.test({
+  name: "permissions - none applies to async file access",
+  permissions: "none",
+  async fn() {
+    try {
+      await Deno.readFile("/tmp/test");
+      throw new Error("readFile should be denied");
+    } catch (error) {
+      if (!(error instanceof Deno.errors.PermissionDenied)) throw error;
+    }
+  },
+});
+
+Deno.test({
+  name: "permissions - async tests keep their scope across the event loop",
+  permissions: "none",
+  async fn() {
+    await new Promise((resolve) => setTimeout(resolve, 10));
+    if (state({ name: "read" }) !== "denied") {
+      throw new Error("read should still be denied");
+    }
+  },
+});
+
+Deno.test("permissions - the next test starts with the runner's permissions", () => {
+  const path = Deno.makeTempFileSync();
+  Deno.writeTextFileSync(path, "inherited");
+  if (Deno.readTextFileSync(path) !== "inherited") {
+    throw new Error("read the wrong contents");
+  }
+  Deno.removeSync(path);
+});
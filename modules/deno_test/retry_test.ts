@@ -0,0 +1,67 @@
+// Deno.test({ retry, retryDelay }) E2E tests
+
+// retry and retryDelay are mdeno extensions to Deno.TestDefinition
+type RetryDefinition = Deno.TestDefinition & {
+  retry?: number;
+  retryDelay?: number;
+};
+
+let syncAttempts = 0;
+Deno.test({
+  name: "retry - a sync test passes on its third attempt",
+  retry: 2,
+  fn() {
+    syncAttempts++;
+    if (syncAttempts < 3) throw new Error(`Attempt ${syncAttempts} fails`);
+  },
+} as RetryDefinition);
+
+let asyncAttempts = 0;
+Deno.test({
+  name: "retry - an async test passes on its second attempt",
+  retry: 1,
+  async fn() {
+    await Promise.resolve();
+    asyncAttempts++;
+    if (asyncAttempts < 2) throw new Error("First attempt fails");
+  },
+} as RetryDefinition);
+
+let delayedAttempts: number[] = [];
+Deno.test({
+  name: "retry - retryDelay waits between attempts",
+  retry: 1,
+  retryDelay: 20,
+  fn() {
+    delayedAttempts.push(Date.now());
+    if (delayedAttempts.length < 2) throw new Error("First attempt fails");
+  },
+} as RetryDefinition);
+
+let timerAttempts = 0;
+let timerFired = false;
+Deno.test({
+  name: "retry - timers keep running during retryDelay",
+  retry: 1,
+  retryDelay: 50,
+  fn() {
+    timerAttempts++;
+    if (timerAttempts === 1) {
+      setTimeout(() => {
+        timerFired = true;
+      }, 5);
+      throw new Error("First attempt fails");
+    }
+    if (!timerFired) throw new Error("The timer didn't fire during the delay");
+  },
+} as RetryDefinition);
+
+Deno.test("retry - each test ran as expected", () => {
+  if (syncAttempts !== 3) throw new Error(`Sync attempts: ${syncAttempts}`);
+  if (asyncAttempts !== 2) throw new Error(`Async attempts: ${asyncAttempts}`);
+  const [first, second] = delayedAttempts;
+  if (second - first < 20) {
+    throw new Error(`Retried after ${second - first}ms`);
+  }
+  delayedAttempts = [];
+});
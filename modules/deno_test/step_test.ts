@@ -0,0 +1,70 @@
+// t.step() E2E tests
+
+Deno.test("step - runs sync and async steps in order", async (t) => {
+  const order: string[] = [];
+  const first = await t.step("first", () => {
+    order.push("first");
+  });
+  const second = await t.step("second", async () => {
+    await Promise.resolve();
+    order.push("second");
+  });
+  if (!first || !second) throw new Error("expected both steps to pass");
+  if (order.join(",") !== "first,second") {
+    throw new Error(`unexpected order: ${order}`);
+  }
+});
+
+Deno.test("step - passes its own context to nested steps", async (t) => {
+  if (t.name !== "step - passes its own context to nested steps") {
+    throw new Error(`unexpected test name: ${t.name}`);
+  }
+  const names: string[] = [];
+  await t.step("outer", async (outer) => {
+    names.push(outer.name);
+    await outer.step("inner", (inner) => {
+      names.push(inner.name);
+    });
+  });
+  if (names.join(",") !== "outer,inner") {
+    throw new Error(`unexpected step names: ${names}`);
+  }
+});
+
+Deno.test("step - accepts an options object", async (t) => {
+  let ran = false;
+  const passed = await t.step({
+    name: "with options",
+    sanitizeOps: false,
+    sanitizeResources: false,
+    fn: () => {
+      ran = true;
+    },
+  });
+  if (!passed || !ran) throw new Error("expected the step to run and pass");
+});
+
+Deno.test("step - ignored steps don't run", async (t) => {
+  let ran = false;
+  const passed = await t.step({
+    name: "skipped",
+    ignore: true,
+    fn: () => {
+      ran = true;
+    },
+  });
+  if (passed || ran) throw new Error("expected the step to be skipped");
+});
+
+Deno.test("step - requires a name and function", async (t) => {
+  for (const args of [[""], ["no function"], [{ name: "no function" }]]) {
+    let threw = false;
+    try {
+      // @ts-ignore: testing invalid arguments
+      await t.step(...args, ...(args[0] === "" ? [() => {}] : []));
+    } catch (error) {
+      threw = error instanceof TypeError;
+    }
+    if (!threw) throw new Error(`expected a TypeError for ${JSON.stringify(args)}`);
+  }
+});
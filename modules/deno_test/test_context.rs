@@ -1,10 +1,20 @@
 // TestContext structure and implementation
-#![allow(clippy::print_stdout)] // Test output intentionally uses println!
 #![allow(clippy::unwrap_used)] // Test infrastructure: mutex poisoning should panic
 #![allow(clippy::unwrap_in_result)] // Test infrastructure: mutex poisoning should panic
 
-use rquickjs::{Ctx, Error, Function, JsLifetime, Object, Result, Value, class::Trace};
+use crate::test_runner::{global_retries, global_timeout, only_mode, set_deadline};
+use deno_permissions::{PERMISSION_NAMES, PermissionState, PermissionStore};
+use rquickjs::{
+    Class, Ctx, Error, Exception, Function, JsLifetime, Object, Promise, Result, Value,
+    class::Trace,
+    function::Constructor,
+    prelude::{Coerced, Opt, This},
+    promise::PromiseState,
+};
+use std::cell::{Cell, RefCell};
 use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Trace, JsLifetime)]
 #[rquickjs::class]
@@ -14,23 +24,176 @@ pub struct TestContext {
     pub(crate) inner: Arc<Mutex<TestContextInner>>,
 }
 
+type PersistentFunction = rquickjs::Persistent<Function<'static>>;
+
 pub(crate) struct TestContextInner {
     pub(crate) tests: Vec<TestDef>,
     pub(crate) filename: String,
-    pub(crate) pending_promises: Vec<PendingPromise>,
-}
-
-pub(crate) struct PendingPromise {
-    pub(crate) test_name: String,
-    pub(crate) promise: rquickjs::Persistent<rquickjs::Promise<'static>>,
-    pub(crate) start_time: std::time::Instant,
+    /// Suite tree; index 0 is the file-level suite
+    pub(crate) suites: Vec<SuiteDef>,
+    /// Suite that `Deno.test` and hooks register into
+    pub(crate) current_suite: usize,
+    /// Polls the tests started by `runAll`, until they have all settled
+    pub(crate) driver: Option<PersistentFunction>,
+    /// Whether the driver is waiting for a promise to settle
+    pub(crate) waiting: bool,
+    /// The test the driver is running, if any
+    pub(crate) running: Option<String>,
+    /// Results of the tests that have finished so far
+    pub(crate) results: Vec<TestResult>,
 }
 
 pub(crate) struct TestDef {
     pub(crate) name: String,
-    pub(crate) func: rquickjs::Persistent<Function<'static>>,
+    pub(crate) func: PersistentFunction,
     pub(crate) ignore: bool,
     pub(crate) only: bool,
+    pub(crate) fake_clock: bool,
+    /// Extra attempts after a failure, overriding `--retries`
+    pub(crate) retry: Option<u32>,
+    pub(crate) retry_delay: Duration,
+    /// How long each attempt may run, overriding `--timeout`
+    pub(crate) timeout: Option<Duration>,
+    /// Permissions the test runs with; `None` inherits the runner's
+    pub(crate) permissions: Option<PermissionStore>,
+    /// Inherited by the test's steps
+    pub(crate) sanitizers: Sanitizers,
+    pub(crate) suite: usize,
+}
+
+/// The `sanitizeOps` and `sanitizeResources` options. mdeno has no op or
+/// resource sanitizers yet; the settings are only passed down so nested
+/// steps see what their parent asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Sanitizers {
+    pub(crate) ops: bool,
+    pub(crate) resources: bool,
+}
+
+impl Default for Sanitizers {
+    fn default() -> Self {
+        Self {
+            ops: true,
+            resources: true,
+        }
+    }
+}
+
+impl Sanitizers {
+    /// Read the options from `obj`, keeping `self` for the ones left out
+    fn read(self, obj: &Object<'_>) -> Self {
+        Self {
+            ops: flag_or(obj, "sanitizeOps", self.ops),
+            resources: flag_or(obj, "sanitizeResources", self.resources),
+        }
+    }
+}
+
+/// A `t.step` registration, the step counterpart of `TestDef`. Options left
+/// out are inherited from the enclosing test or step.
+#[derive(Clone)]
+pub(crate) struct StepDef {
+    pub(crate) name: String,
+    pub(crate) ignore: bool,
+    pub(crate) sanitizers: Sanitizers,
+}
+
+/// How a step ended, kept by the enclosing `StepContext`
+pub(crate) struct StepResult {
+    pub(crate) name: String,
+    pub(crate) passed: bool,
+    pub(crate) ignored: bool,
+    pub(crate) error: Option<TestError>,
+}
+
+/// Everything needed to run a test, and to run it again on retries
+pub(crate) struct TestRun {
+    pub(crate) name: String,
+    pub(crate) func: PersistentFunction,
+    pub(crate) fake_clock: bool,
+    /// beforeEach hooks, outermost first
+    pub(crate) before_each: Vec<PersistentFunction>,
+    /// afterEach hooks, innermost first
+    pub(crate) after_each: Vec<PersistentFunction>,
+    pub(crate) retries: u32,
+    pub(crate) retry_delay: Duration,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) permissions: Option<PermissionStore>,
+    pub(crate) sanitizers: Sanitizers,
+    /// Starts at 1
+    pub(crate) attempt: u32,
+}
+
+type TestError = (String, Option<String>);
+
+/// How a single run of a test ended
+enum Attempt {
+    Settled(Option<TestError>),
+    /// A beforeEach or afterEach hook threw, which fails the remaining tests
+    HookFailed(HookKind, TestError),
+}
+
+/// A `Deno.describe` block (or the file itself) with its hooks
+#[derive(Default)]
+pub(crate) struct SuiteDef {
+    pub(crate) name: String,
+    pub(crate) parent: Option<usize>,
+    pub(crate) before_all: Vec<PersistentFunction>,
+    pub(crate) after_all: Vec<PersistentFunction>,
+    pub(crate) before_each: Vec<PersistentFunction>,
+    pub(crate) after_each: Vec<PersistentFunction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HookKind {
+    BeforeAll,
+    AfterAll,
+    BeforeEach,
+    AfterEach,
+}
+
+impl HookKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::BeforeAll => "beforeAll",
+            Self::AfterAll => "afterAll",
+            Self::BeforeEach => "beforeEach",
+            Self::AfterEach => "afterEach",
+        }
+    }
+}
+
+impl TestContextInner {
+    /// Suites from the file-level suite down to `suite`
+    fn suite_chain(&self, suite: usize) -> Vec<usize> {
+        let mut chain = vec![suite];
+        let mut current = suite;
+        while let Some(parent) = self.suites[current].parent {
+            chain.push(parent);
+            current = parent;
+        }
+        chain.reverse();
+        chain
+    }
+
+    fn hooks(&self, suite: usize, kind: HookKind) -> &[PersistentFunction] {
+        let suite = &self.suites[suite];
+        match kind {
+            HookKind::BeforeAll => &suite.before_all,
+            HookKind::AfterAll => &suite.after_all,
+            HookKind::BeforeEach => &suite.before_each,
+            HookKind::AfterEach => &suite.after_each,
+        }
+    }
+
+    /// Hooks of `kind` in each of `suites`, in that order
+    fn chain_hooks(&self, suites: &[usize], kind: HookKind) -> Vec<PersistentFunction> {
+        suites
+            .iter()
+            .flat_map(|&suite| self.hooks(suite, kind))
+            .cloned()
+            .collect()
+    }
 }
 
 impl Default for TestContext {
@@ -48,7 +211,12 @@ impl TestContext {
             inner: Arc::new(Mutex::new(TestContextInner {
                 tests: Vec::new(),
                 filename: "unknown".to_string(),
-                pending_promises: Vec::new(),
+                suites: vec![SuiteDef::default()],
+                current_suite: 0,
+                driver: None,
+                waiting: false,
+                running: None,
+                results: Vec::new(),
             })),
         }
     }
@@ -73,12 +241,64 @@ impl TestContext {
             drop(test.func);
         }
 
-        for promise in inner.pending_promises.drain(..) {
-            drop(promise.promise);
+        inner.suites.clear();
+        inner.suites.push(SuiteDef::default());
+        inner.current_suite = 0;
+        inner.driver = None;
+        inner.waiting = false;
+        inner.running = None;
+        inner.results.clear();
+    }
+
+    /// Register a beforeAll/afterAll/beforeEach/afterEach hook in the current suite
+    ///
+    /// # Panics
+    /// Panics if the mutex is poisoned
+    #[qjs(skip)]
+    pub(crate) fn register_hook<'js>(&self, ctx: &Ctx<'js>, kind: HookKind, func: Function<'js>) {
+        let mut inner = self.inner.lock().unwrap();
+        let suite = inner.current_suite;
+        let func = rquickjs::Persistent::save(ctx, func);
+        let suite = &mut inner.suites[suite];
+        match kind {
+            HookKind::BeforeAll => suite.before_all.push(func),
+            HookKind::AfterAll => suite.after_all.push(func),
+            HookKind::BeforeEach => suite.before_each.push(func),
+            HookKind::AfterEach => suite.after_each.push(func),
         }
     }
 
+    /// Run `func` with tests and hooks registering into a new suite named `name`
+    ///
+    /// # Errors
+    /// Returns an error if `func` throws
+    ///
+    /// # Panics
+    /// Panics if the mutex is poisoned
+    pub fn describe(&self, name: String, func: Function<'_>) -> Result<()> {
+        let parent = {
+            let mut inner = self.inner.lock().unwrap();
+            let parent = inner.current_suite;
+            inner.suites.push(SuiteDef {
+                name,
+                parent: Some(parent),
+                ..SuiteDef::default()
+            });
+            inner.current_suite = inner.suites.len() - 1;
+            parent
+        };
+
+        // The lock must be released while the callback registers tests
+        let result = func.call::<_, ()>(());
+
+        self.inner.lock().unwrap().current_suite = parent;
+        result
+    }
+
     #[qjs(rename = "registerTest")]
+    /// Register a test, returning `{ name, ignore }` where `ignore` tells
+    /// whether the test will be skipped
+    ///
     /// # Errors
     /// Returns an error if test registration fails
     ///
@@ -89,8 +309,13 @@ impl TestContext {
         ctx: Ctx<'js>,
         name_or_options: Value<'js>,
         fn_val: Option<Value<'js>>,
-    ) -> Result<()> {
-        let (name, func, ignore, only) = if name_or_options.is_string() {
+    ) -> Result<Object<'js>> {
+        let mut retry = None;
+        let mut retry_delay = Duration::ZERO;
+        let mut timeout = None;
+        let mut permissions = None;
+        let mut sanitizers = Sanitizers::default();
+        let (name, func, ignore, only, fake_clock) = if name_or_options.is_string() {
             // Simple form: Deno.test(name, fn)
             let name: String = name_or_options.get()?;
             let func = fn_val
@@ -99,15 +324,43 @@ impl TestContext {
                 .ok_or_else(|| {
                     Error::new_from_js("registerTest", "Second argument must be a function")
                 })?;
-            (name, func, false, false)
+            (name, func, false, false, false)
         } else if name_or_options.is_object() {
-            // Object form: Deno.test({ name, fn, ignore?, only? })
+            // Object form: Deno.test({ name, fn, ignore?, only?, fakeClock?, retry?,
+            // retryDelay?, timeout?, permissions? })
+            // or Deno.test(options, fn), where the flags may be any truthy
+            // expression such as `ignore: Deno.build.os === "windows"`
             let obj: Object = name_or_options.get()?;
-            let name: String = obj.get("name")?;
-            let func: Function = obj.get("fn")?;
-            let ignore: bool = obj.get("ignore").unwrap_or(false);
-            let only: bool = obj.get("only").unwrap_or(false);
-            (name, func, ignore, only)
+            let func: Function = match fn_val.and_then(Value::into_function) {
+                Some(func) => func,
+                None => obj.get("fn")?,
+            };
+            // Without a name option, the function's own name is used
+            let name = match obj.get::<_, Option<String>>("name")? {
+                Some(name) => name,
+                None => func.get::<_, Option<String>>("name")?.unwrap_or_default(),
+            };
+            if name.is_empty() {
+                return Err(Error::new_from_js(
+                    "registerTest",
+                    "The test name can't be empty",
+                ));
+            }
+            let count = |key: &str| -> Result<Option<u64>> {
+                Ok(obj
+                    .get::<_, Option<f64>>(key)?
+                    .filter(|n| n.is_finite() && *n >= 0.0)
+                    .map(|n| n as u64))
+            };
+            retry = count("retry")?.map(|n| u32::try_from(n).unwrap_or(u32::MAX));
+            retry_delay = Duration::from_millis(count("retryDelay")?.unwrap_or(0));
+            timeout = count("timeout")?
+                .filter(|&ms| ms > 0)
+                .map(Duration::from_millis);
+            permissions = scoped_permissions(&ctx, obj.get("permissions")?)?;
+            sanitizers = sanitizers.read(&obj);
+            let flag = |key: &str| flag_or(&obj, key, false);
+            (name, func, flag("ignore"), flag("only"), flag("fakeClock"))
         } else {
             return Err(Error::new_from_js(
                 "registerTest",
@@ -117,216 +370,963 @@ impl TestContext {
 
         let mut inner = self.inner.lock().unwrap();
         let func_persistent = rquickjs::Persistent::save(&ctx, func);
+        let suite = inner.current_suite;
+
+        // Tests inside describe blocks are reported as "suite > test"
+        let mut full_name: Vec<&str> = inner
+            .suite_chain(suite)
+            .into_iter()
+            .skip(1)
+            .map(|index| inner.suites[index].name.as_str())
+            .collect();
+        full_name.push(&name);
+        let name = full_name.join(" > ");
+
+        let result = Object::new(ctx)?;
+        result.set("name", name.clone())?;
+        result.set("ignore", ignore || (only_mode() && !only))?;
+
         inner.tests.push(TestDef {
             name,
             func: func_persistent,
             ignore,
             only,
+            fake_clock,
+            retry,
+            retry_delay,
+            timeout,
+            permissions,
+            sanitizers,
+            suite,
         });
 
-        Ok(())
+        Ok(result)
     }
 
     #[qjs(rename = "runAll")]
+    /// Start the registered tests, returning `{ passed, failed, skipped }`
+    /// with only the skipped tests counted so far; `resolvePending` drives
+    /// the rest. Tests run one at a time: each one, with its hooks, settles
+    /// before the next starts, so its permissions and fake clock never leak
+    /// into other tests.
+    ///
     /// # Errors
-    /// Returns an error if test execution fails
+    /// Returns an error if the result object can't be created
     ///
     /// # Panics
     /// Panics if the mutex is poisoned
     pub fn run_all<'js>(&self, ctx: Ctx<'js>) -> Result<Value<'js>> {
-        use deno_terminal::colors;
-        use rquickjs::CatchResultExt;
-        use std::time::Instant;
+        let (tests, has_only) = {
+            let mut inner = self.inner.lock().unwrap();
+            let tests = std::mem::take(&mut inner.tests);
+            // `only` in any file of the run filters every file
+            let has_only = only_mode() || tests.iter().any(|t| t.only);
+            (tests, has_only)
+        };
+        let skipped = tests
+            .iter()
+            .filter(|t| if has_only { !t.only } else { t.ignore })
+            .count();
 
-        let mut inner = self.inner.lock().unwrap();
+        let context = self.clone();
+        let ctx_clone = ctx.clone();
+        let run = RefCell::new(Box::pin(async move {
+            context.run_tests(ctx_clone, tests, has_only).await
+        }));
+        // Poll the tests until they wait for a promise that needs the event
+        // loop, resolving to their counts once the last one has settled
+        let driver = Function::new(ctx.clone(), move || -> Result<Option<Object<'js>>> {
+            let mut run = run.borrow_mut();
+            match run.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+                Poll::Ready(counts) => counts.map(Some),
+                Poll::Pending => Ok(None),
+            }
+        })?;
+        self.inner.lock().unwrap().driver = Some(rquickjs::Persistent::save(&ctx, driver));
 
-        let has_only = inner.tests.iter().any(|t| t.only);
+        let result = Object::new(ctx)?;
+        result.set("passed", 0)?;
+        result.set("failed", 0)?;
+        result.set("skipped", skipped)?;
+        Ok(result.into_value())
+    }
+
+    #[qjs(rename = "resolvePending")]
+    /// Drive the tests started by `runAll` once the event loop is idle.
+    /// Returns `{ passed, failed, pending }`: `pending` is 1 until the last
+    /// test has settled, and then the counts cover the whole run.
+    ///
+    /// # Errors
+    /// Returns an error if the tests can't be driven
+    ///
+    /// # Panics
+    /// Panics if the mutex is poisoned
+    pub fn resolve_pending<'js>(&self, ctx: Ctx<'js>) -> Result<Value<'js>> {
+        let (driver, waiting) = {
+            let inner = self.inner.lock().unwrap();
+            (inner.driver.clone(), inner.waiting)
+        };
+        let settled = SETTLED.get();
+        let done = match driver {
+            Some(driver) => driver.restore(&ctx)?.call::<_, Option<Object>>(())?,
+            None => Some(Object::new(ctx.clone())?),
+        };
+
+        let result = Object::new(ctx)?;
+        let (passed, failed, pending) = if let Some(counts) = done {
+            self.inner.lock().unwrap().driver = None;
+            let passed: usize = counts.get::<_, Option<usize>>("passed")?.unwrap_or(0);
+            let failed: usize = counts.get::<_, Option<usize>>("failed")?.unwrap_or(0);
+            (passed, failed, 0)
+        } else if waiting && SETTLED.get() == settled {
+            // The event loop ran out of work without settling the promise
+            // the tests wait for, so nothing ever will
+            let (passed, failed) = self.report_stalled();
+            (passed, failed, 0)
+        } else {
+            self.inner.lock().unwrap().waiting = true;
+            (0, 0, 1)
+        };
+        result.set("passed", passed)?;
+        result.set("failed", failed)?;
+        result.set("pending", pending)?;
+        Ok(result.into_value())
+    }
+}
+
+impl TestContext {
+    async fn run_tests<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        tests: Vec<TestDef>,
+        has_only: bool,
+    ) -> Result<Object<'js>> {
+        use deno_terminal::colors;
+
+        let filename = self.inner.lock().unwrap().filename.clone();
 
         // Print header
         let tests_to_run_count = if has_only {
-            inner.tests.iter().filter(|t| t.only).count()
+            tests.iter().filter(|t| t.only).count()
         } else {
-            inner.tests.iter().filter(|t| !t.ignore).count()
+            tests.iter().filter(|t| !t.ignore).count()
         };
 
-        println!(
+        utils::print_line!(
             "{}",
             colors::gray(&format!(
-                "running {} tests from {}",
-                tests_to_run_count, inner.filename
+                "running {tests_to_run_count} tests from {filename}"
             ))
         );
 
-        let mut results = Vec::new();
-        let mut pending_promises_temp = Vec::new();
+        // Suites whose beforeAll hooks have run, outermost first
+        let mut entered: Vec<usize> = Vec::new();
+        // Once a hook throws, the remaining tests are failed without running
+        let mut hook_failure: Option<&str> = None;
 
-        // Restore functions and run tests
-        for test in &inner.tests {
+        for test in tests {
             // Skip if not in tests to run
             if has_only && !test.only {
                 continue;
             }
             if !has_only && test.ignore {
+                utils::print_line!(
+                    "{} ... {} {}",
+                    test.name,
+                    colors::yellow("ignored"),
+                    colors::gray("(0ms)")
+                );
                 continue;
             }
 
-            let start = Instant::now();
-            let Ok(func) = test.func.clone().restore(&ctx) else {
+            if let Some(hook) = &hook_failure {
+                let error = format!("Skipped because the {hook} hook failed");
+                self.record(report(&test.name, 0, Some((error, None))));
                 continue;
-            };
+            }
+
+            let start = Instant::now();
+            let chain = self.inner.lock().unwrap().suite_chain(test.suite);
 
-            let (passed, error, error_stack) = match func.call::<_, Value>(()).catch(&ctx) {
-                Ok(ret_val) => {
-                    // Check if it's a promise
-                    if ret_val.is_promise() {
-                        // Get promise and store it for later resolution (don't block with finish())
-                        // This allows compio to drive the I/O
-                        let promise = ret_val.as_promise().unwrap().clone();
-                        let promise_persistent = rquickjs::Persistent::save(&ctx, promise);
-                        pending_promises_temp.push(PendingPromise {
-                            test_name: test.name.clone(),
-                            promise: promise_persistent,
-                            start_time: start,
-                        });
-                        // Mark as pending - will be resolved later
-                        continue;
+            // Leave suites this test is not part of, then enter its own
+            let mut failure = None;
+            while let Some(&suite) = entered.last() {
+                if chain.contains(&suite) {
+                    break;
+                }
+                entered.pop();
+                if let Err(error) = self
+                    .call_suite_hooks(&ctx, &[suite], HookKind::AfterAll)
+                    .await
+                {
+                    failure = Some((HookKind::AfterAll, error));
+                    break;
+                }
+            }
+            if failure.is_none() {
+                for &suite in &chain[entered.len()..] {
+                    entered.push(suite);
+                    if let Err(error) = self
+                        .call_suite_hooks(&ctx, &[suite], HookKind::BeforeAll)
+                        .await
+                    {
+                        failure = Some((HookKind::BeforeAll, error));
+                        break;
                     }
-                    (true, None, None)
                 }
-                Err(caught) => {
-                    // Extract error message and stack trace
-                    let (error_msg, stack_trace) = match caught {
-                        rquickjs::CaughtError::Exception(ex) => {
-                            let msg = ex.message().unwrap_or("Unknown error".to_string());
-                            let stack = ex.stack();
-                            (msg, stack)
-                        }
-                        rquickjs::CaughtError::Error(e) => (format!("{e}"), None),
-                        rquickjs::CaughtError::Value(v) => (format!("{v:?}"), None),
-                    };
-                    (false, Some(error_msg), stack_trace)
+            }
+            if let Some((kind, (message, stack))) = failure {
+                hook_failure = Some(kind.as_str());
+                let error = format!("{} hook failed: {message}", kind.as_str());
+                let duration_ms = start.elapsed().as_millis();
+                self.record(report(&test.name, duration_ms, Some((error, stack))));
+                continue;
+            }
+
+            let run = {
+                let mut inner = self.inner.lock().unwrap();
+                inner.running = Some(test.name.clone());
+                // afterEach hooks run innermost first
+                let inner_first: Vec<usize> = chain.iter().rev().copied().collect();
+                TestRun {
+                    before_each: inner.chain_hooks(&chain, HookKind::BeforeEach),
+                    after_each: inner.chain_hooks(&inner_first, HookKind::AfterEach),
+                    name: test.name,
+                    func: test.func,
+                    fake_clock: test.fake_clock,
+                    retries: test.retry.unwrap_or_else(global_retries),
+                    retry_delay: test.retry_delay,
+                    timeout: test.timeout.or_else(global_timeout),
+                    permissions: test.permissions,
+                    sanitizers: test.sanitizers,
+                    attempt: 1,
                 }
             };
+            let (result, failed_hook) = run_test(&ctx, run, start).await?;
+            self.inner.lock().unwrap().running = None;
+            if let Some(kind) = failed_hook {
+                hook_failure = Some(kind.as_str());
+            }
+            self.record(result);
+        }
 
-            let duration_ms = start.elapsed().as_millis();
+        // Leave the remaining suites, innermost first
+        entered.reverse();
+        if let Err(error) = self
+            .call_suite_hooks(&ctx, &entered, HookKind::AfterAll)
+            .await
+        {
+            self.record(report("afterAll hook", 0, Some(error)));
+        }
 
-            // Print result immediately
-            let status = if passed {
-                colors::green("ok")
-            } else {
-                colors::red("FAILED")
-            };
-            let time_str = format!("({duration_ms}ms)");
-            println!("{} ... {} {}", test.name, status, colors::gray(&time_str));
-
-            results.push(TestResult {
-                name: test.name.clone(),
-                passed,
-                error,
-                error_stack,
-            });
+        let (passed, failed) = self.finish();
+
+        // Return results as an object
+        let result = Object::new(ctx)?;
+        result.set("passed", passed)?;
+        result.set("failed", failed)?;
+        Ok(result)
+    }
+
+    fn record(&self, result: TestResult) {
+        self.inner.lock().unwrap().results.push(result);
+    }
+
+    /// Print the errors of the finished tests and count the passed and
+    /// failed ones
+    fn finish(&self) -> (usize, usize) {
+        let inner = self.inner.lock().unwrap();
+        print_results(&inner.results, &inner.filename);
+        let passed = inner.results.iter().filter(|r| r.passed).count();
+        (passed, inner.results.len() - passed)
+    }
+
+    /// Fail the test that is waiting for a promise nothing will settle and
+    /// stop the run there, dropping the driver so the test's permission
+    /// scope is popped
+    fn report_stalled(&self) -> (usize, usize) {
+        let (driver, running) = {
+            let mut inner = self.inner.lock().unwrap();
+            (inner.driver.take(), inner.running.take())
+        };
+        drop(driver);
+        let name = running.unwrap_or_else(|| "hook".to_string());
+        let error = "Promise resolution is still pending but the event loop has already resolved";
+        self.record(report(&name, 0, Some((error.to_string(), None))));
+        self.finish()
+    }
+
+    /// Call the `kind` hooks of `suites`, in that order
+    async fn call_suite_hooks(
+        &self,
+        ctx: &Ctx<'_>,
+        suites: &[usize],
+        kind: HookKind,
+    ) -> std::result::Result<(), TestError> {
+        let hooks = self.inner.lock().unwrap().chain_hooks(suites, kind);
+        call_hooks(ctx, &hooks).await
+    }
+}
+
+/// The `t` passed to a test or step, holding the results of its steps
+#[derive(Clone, Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct StepContext {
+    #[qjs(skip_trace)]
+    #[allow(clippy::arc_with_non_send_sync)] // JavaScript values aren't Send/Sync
+    inner: Arc<Mutex<StepContextInner>>,
+}
+
+struct StepContextInner {
+    def: StepDef,
+    /// `test_name/step_name/...`, as printed in step results
+    path: String,
+    /// 0 for the test itself
+    depth: usize,
+    results: Vec<StepResult>,
+}
+
+impl StepContext {
+    #[allow(clippy::arc_with_non_send_sync)] // JavaScript values aren't Send/Sync
+    fn root(def: StepDef) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(StepContextInner {
+                path: def.name.clone(),
+                def,
+                depth: 0,
+                results: Vec::new(),
+            })),
         }
+    }
 
-        // Store pending promises
-        inner.pending_promises.extend(pending_promises_temp);
+    #[allow(clippy::arc_with_non_send_sync)] // JavaScript values aren't Send/Sync
+    fn child(&self, def: StepDef) -> Self {
+        let inner = self.inner.lock().unwrap();
+        Self {
+            inner: Arc::new(Mutex::new(StepContextInner {
+                path: format!("{}/{}", inner.path, def.name),
+                def,
+                depth: inner.depth + 1,
+                results: Vec::new(),
+            })),
+        }
+    }
 
-        // Print results summary
-        print_results(&results, &inner.filename);
+    /// Parse `t.step(name, fn)`, `t.step({ name, fn, ...options })` or
+    /// `t.step(options, fn)`, inheriting options left out
+    fn step_def<'js>(
+        &self,
+        ctx: &Ctx<'js>,
+        name_or_options: Value<'js>,
+        fn_val: Option<Value<'js>>,
+    ) -> Result<(StepDef, Function<'js>)> {
+        let mut def = StepDef {
+            name: String::new(),
+            ignore: false,
+            sanitizers: self.inner.lock().unwrap().def.sanitizers,
+        };
+        let func = if let Some(name) = name_or_options.as_string() {
+            def.name = name.to_string()?;
+            fn_val.and_then(Value::into_function)
+        } else if let Some(obj) = name_or_options.as_object() {
+            let func = match fn_val.and_then(Value::into_function) {
+                Some(func) => Some(func),
+                None => obj.get("fn")?,
+            };
+            def.name = match obj.get::<_, Option<String>>("name")? {
+                Some(name) => name,
+                None => match &func {
+                    Some(func) => func.get::<_, Option<String>>("name")?.unwrap_or_default(),
+                    None => String::new(),
+                },
+            };
+            def.ignore = flag_or(obj, "ignore", false);
+            def.sanitizers = def.sanitizers.read(obj);
+            func
+        } else {
+            return Err(Exception::throw_type(
+                ctx,
+                "Expected a step name or options object",
+            ));
+        };
+        let Some(func) = func else {
+            return Err(Exception::throw_type(ctx, "Expected a step function"));
+        };
+        if def.name.is_empty() {
+            return Err(Exception::throw_type(ctx, "The step name can't be empty"));
+        }
+        Ok((def, func))
+    }
+
+    /// Print the result line of the finished step `child` and record it
+    fn finish_step(&self, child: &Self, start: Instant, error: Option<TestError>) -> bool {
+        use deno_terminal::colors;
 
-        // Calculate results
-        let passed = results.iter().filter(|r| r.passed).count();
-        let failed = results.iter().filter(|r| !r.passed).count();
+        // A step that didn't throw still fails if any of its own steps did
+        let error = error.or_else(|| child.failure());
+        let child = child.inner.lock().unwrap();
+        let passed = error.is_none();
+        let status = if passed {
+            colors::green("ok")
+        } else {
+            colors::red("FAILED")
+        };
+        utils::print_line!(
+            "{}{} ... {} {}",
+            "  ".repeat(child.depth),
+            child.path,
+            status,
+            colors::gray(&format!("({}ms)", start.elapsed().as_millis()))
+        );
+        self.inner.lock().unwrap().results.push(StepResult {
+            name: child.def.name.clone(),
+            passed,
+            ignored: false,
+            error,
+        });
+        passed
+    }
 
-        // Clear for next file
-        inner.tests.clear();
+    /// The error of the failed steps, if any
+    fn failure(&self) -> Option<TestError> {
+        let inner = self.inner.lock().unwrap();
+        let failed: Vec<&StepResult> = inner
+            .results
+            .iter()
+            .filter(|result| !result.passed && !result.ignored)
+            .collect();
+        let first = failed.first()?;
+        let message = failed
+            .iter()
+            .map(|result| {
+                let message = result.error.as_ref().map_or("", |(message, _)| message);
+                format!("Step \"{}\" failed: {message}", result.name)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let stack = first.error.as_ref().and_then(|(_, stack)| stack.clone());
+        Some((message, stack))
+    }
+}
 
-        // Return results as an object
-        let result = Object::new(ctx.clone())?;
-        result.set("passed", passed)?;
-        result.set("failed", failed)?;
-        Ok(result.into_value())
+#[rquickjs::methods]
+impl StepContext {
+    /// Name of the test or step this context belongs to
+    #[qjs(get)]
+    pub fn name(&self) -> String {
+        self.inner.lock().unwrap().def.name.clone()
     }
 
-    #[qjs(rename = "resolvePending")]
+    /// Run a step, resolving to whether it passed. A failed step fails its
+    /// parent, but doesn't stop the parent's later steps from running.
+    ///
     /// # Errors
-    /// Returns an error if promise resolution fails
+    /// Returns an error if the step name or function is missing
     ///
     /// # Panics
     /// Panics if the mutex is poisoned
-    pub fn resolve_pending<'js>(&self, ctx: Ctx<'js>) -> Result<Value<'js>> {
+    pub fn step<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        name_or_options: Value<'js>,
+        fn_val: Opt<Value<'js>>,
+    ) -> Result<Promise<'js>> {
         use deno_terminal::colors;
         use rquickjs::CatchResultExt;
 
-        let mut inner = self.inner.lock().unwrap();
-        let pending = std::mem::take(&mut inner.pending_promises);
-        drop(inner); // Release lock
-
-        let mut results = Vec::new();
+        let (def, func) = self.step_def(&ctx, name_or_options, fn_val.0)?;
+        let child = self.child(def.clone());
 
-        for pending_promise in pending {
-            let Ok(promise) = pending_promise.promise.restore(&ctx) else {
-                continue;
-            };
+        if def.ignore {
+            let inner = child.inner.lock().unwrap();
+            utils::print_line!(
+                "{}{} ... {} {}",
+                "  ".repeat(inner.depth),
+                inner.path,
+                colors::yellow("ignored"),
+                colors::gray("(0ms)")
+            );
+            self.inner.lock().unwrap().results.push(StepResult {
+                name: def.name,
+                passed: false,
+                ignored: true,
+                error: None,
+            });
+            return resolved(&ctx, false);
+        }
 
-            // Check promise state - finish() should be safe here since runtime.idle() was already called
-            let (passed, error, error_stack) = match promise.finish::<Value>().catch(&ctx) {
-                Ok(_) => (true, None, None),
-                Err(caught) => {
-                    let (error_msg, stack_trace) = match caught {
-                        rquickjs::CaughtError::Exception(ex) => {
-                            let msg = ex.message().unwrap_or("Unknown error".to_string());
-                            let stack = ex.stack();
-                            (msg, stack)
-                        }
-                        rquickjs::CaughtError::Error(e) => (format!("{e}"), None),
-                        rquickjs::CaughtError::Value(v) => (format!("{v:?}"), None),
-                    };
-                    (false, Some(error_msg), stack_trace)
+        let start = Instant::now();
+        let t = Class::instance(ctx.clone(), child.clone())?;
+        let error = match func.call::<_, Value>((t,)).catch(&ctx) {
+            Ok(value) => match value.into_promise() {
+                Some(promise) => {
+                    // Record the step once its promise settles
+                    let (parent, step) = (self.clone(), child.clone());
+                    let on_fulfilled =
+                        Function::new(ctx.clone(), move || parent.finish_step(&step, start, None))?;
+                    let (parent, step) = (self.clone(), child);
+                    let on_rejected = Function::new(ctx.clone(), move |reason: Value<'js>| {
+                        let caught = match reason.clone().into_exception() {
+                            Some(exception) => rquickjs::CaughtError::Exception(exception),
+                            None => rquickjs::CaughtError::Value(reason),
+                        };
+                        parent.finish_step(&step, start, Some(caught_error(caught)))
+                    })?;
+                    let then: Function = promise.get("then")?;
+                    return then.call((This(promise), on_fulfilled, on_rejected));
                 }
-            };
+                None => None,
+            },
+            Err(caught) => Some(caught_error(caught)),
+        };
+        let passed = self.finish_step(&child, start, error);
+        resolved(&ctx, passed)
+    }
+}
 
-            // Explicitly drop promise to help GC
-            drop(promise);
+/// Attach a handler that does nothing, so a failing async test isn't also
+/// reported as an unhandled rejection; its result is read from Rust
+fn mark_handled<'js>(ctx: &Ctx<'js>, promise: &Promise<'js>) -> Result<()> {
+    let ignore = Function::new(ctx.clone(), || {})?;
+    promise.catch()?.call((This(promise.clone()), ignore))
+}
 
-            let duration_ms = pending_promise.start_time.elapsed().as_millis();
+thread_local! {
+    /// How many promises `settle` has seen settle, so `resolvePending` can
+    /// tell whether the tests made progress
+    static SETTLED: Cell<u64> = const { Cell::new(0) };
+}
 
-            // Print result
-            let status = if passed {
-                colors::green("ok")
-            } else {
-                colors::red("FAILED")
-            };
-            let time_str = format!("({duration_ms}ms)");
-            println!(
-                "{} ... {} {}",
-                pending_promise.test_name,
-                status,
-                colors::gray(&time_str)
-            );
+/// Wait for `promise` to settle. The driver is only polled again once the
+/// event loop is idle, so this registers no waker; a rejection is read from
+/// Rust rather than reported as unhandled.
+async fn settle<'js>(ctx: &Ctx<'js>, promise: Promise<'js>) -> Result<Value<'js>> {
+    mark_handled(ctx, &promise)?;
+    // Promises that only wait on other jobs settle without a trip through
+    // the event loop
+    while promise.state() == PromiseState::Pending && ctx.execute_pending_job() {}
+    std::future::poll_fn(|_| match promise.result() {
+        Some(result) => {
+            SETTLED.set(SETTLED.get() + 1);
+            Poll::Ready(result)
+        }
+        None => Poll::Pending,
+    })
+    .await
+}
 
-            results.push(TestResult {
-                name: pending_promise.test_name,
-                passed,
-                error,
-                error_stack,
-            });
+/// A test's permissions, in effect until dropped
+struct PermissionScope;
+
+impl PermissionScope {
+    fn push(store: PermissionStore) -> Self {
+        deno_permissions::push_scope(store);
+        Self
+    }
+}
+
+impl Drop for PermissionScope {
+    fn drop(&mut self) {
+        deno_permissions::pop_scope();
+    }
+}
+
+/// A promise already fulfilled with `value`
+fn resolved<'js>(ctx: &Ctx<'js>, value: bool) -> Result<Promise<'js>> {
+    let (promise, resolve, _) = ctx.promise()?;
+    resolve.call::<_, ()>((value,))?;
+    Ok(promise)
+}
+
+fn timeout_message(timeout: Duration) -> String {
+    format!("Test exceeded timeout of {}ms", timeout.as_millis())
+}
+
+/// Race an async test against its deadline. The returned promise settles
+/// like the test's, or rejects once the deadline passes; the test's own
+/// promise is then abandoned.
+fn with_timeout<'js>(
+    ctx: &Ctx<'js>,
+    promise: Promise<'js>,
+    deadline: Instant,
+    timeout: Duration,
+) -> Result<Promise<'js>> {
+    let ctx_clone = ctx.clone();
+    Promise::wrap_future(ctx, async move {
+        match compio::time::timeout_at(deadline, promise.into_future::<Value>()).await {
+            Ok(result) => result,
+            Err(_) => Err(Exception::throw_message(
+                &ctx_clone,
+                &timeout_message(timeout),
+            )),
         }
+    })
+}
 
-        // Print results summary if there were any
-        if !results.is_empty() {
-            let inner = self.inner.lock().unwrap();
-            print_results(&results, &inner.filename);
+/// Read a boolean option, where any truthy expression counts and a missing
+/// option takes `default`
+fn flag_or(obj: &Object<'_>, key: &str, default: bool) -> bool {
+    match obj.get::<_, Value>(key) {
+        Ok(value) if !value.is_undefined() => value.get::<Coerced<bool>>().is_ok_and(|c| c.0),
+        _ => default,
+    }
+}
+
+/// Run one attempt of a test: its beforeEach hooks, the test itself and its
+/// afterEach hooks, each awaited before the next starts
+async fn run_attempt(ctx: &Ctx<'_>, run: &TestRun) -> Result<Attempt> {
+    use rquickjs::CatchResultExt;
+
+    if let Err((message, stack)) = call_hooks(ctx, &run.before_each).await {
+        let error = (format!("beforeEach hook failed: {message}"), stack);
+        return Ok(Attempt::HookFailed(HookKind::BeforeEach, error));
+    }
+
+    let func = match run.func.clone().restore(ctx) {
+        Ok(func) => func,
+        Err(e) => return Ok(Attempt::Settled(Some((e.to_string(), None)))),
+    };
+
+    // The test's `t` argument, with `t.clock` for fakeClock tests
+    let steps = StepContext::root(StepDef {
+        name: run.name.clone(),
+        ignore: false,
+        sanitizers: run.sanitizers,
+    });
+    let t = Class::instance(ctx.clone(), steps.clone())?;
+    let clock = if run.fake_clock {
+        match install_fake_clock(ctx) {
+            Ok(clock) => {
+                t.set("clock", clock.clone())?;
+                Some(clock)
+            }
+            Err(error) => return Ok(Attempt::Settled(Some(error))),
         }
+    } else {
+        None
+    };
+    // Push the test's own permissions; finish_attempt pops them again once
+    // the test settles
+    let scope = run.permissions.clone().map(PermissionScope::push);
 
-        // Calculate results
-        let passed = results.iter().filter(|r| r.passed).count();
-        let failed = results.iter().filter(|r| !r.passed).count();
+    // Past the deadline the runtime interrupts the test, which throws an
+    // uncatchable error that is reported as the timeout
+    let deadline = run.timeout.map(|timeout| Instant::now() + timeout);
+    set_deadline(deadline);
+    let called = func.call::<_, Value>((t,)).catch(ctx);
+    set_deadline(None);
+    let error = match called {
+        Ok(ret_val) => match ret_val.into_promise() {
+            Some(promise) => {
+                // A test abandoned by its timeout may still reject later
+                mark_handled(ctx, &promise)?;
+                let promise = match (deadline, run.timeout) {
+                    (Some(deadline), Some(timeout)) => {
+                        with_timeout(ctx, promise, deadline, timeout)?
+                    }
+                    _ => promise,
+                };
+                settle(ctx, promise)
+                    .await
+                    .catch(ctx)
+                    .err()
+                    .map(caught_error)
+            }
+            None => None,
+        },
+        Err(caught) => Some(caught_error(caught)),
+    };
+    let error = match (error, run.timeout) {
+        (Some(_), Some(timeout)) if deadline.is_some_and(|at| Instant::now() >= at) => {
+            Some((timeout_message(timeout), None))
+        }
+        (error, _) => error,
+    };
 
-        // Return results as an object
-        let result = Object::new(ctx.clone())?;
-        result.set("passed", passed)?;
-        result.set("failed", failed)?;
-        Ok(result.into_value())
+    Ok(finish_attempt(ctx, run, clock.as_ref(), scope, &steps, error).await)
+}
+
+/// Uninstall the fake clock, restore the runner's permissions and run
+/// afterEach hooks, which run even when the test failed. A test that didn't
+/// throw still fails if any of its steps did.
+async fn finish_attempt(
+    ctx: &Ctx<'_>,
+    run: &TestRun,
+    clock: Option<&Object<'_>>,
+    scope: Option<PermissionScope>,
+    steps: &StepContext,
+    mut error: Option<TestError>,
+) -> Attempt {
+    if error.is_none() {
+        error = steps.failure();
+    }
+    drop(scope);
+    if let Some(clock) = clock
+        && let Err(clock_error) = uninstall_fake_clock(clock)
+    {
+        error.get_or_insert(clock_error);
+    }
+
+    let cleanup_start = Instant::now();
+    for hook in &run.after_each {
+        if let Err((message, stack)) = call_hooks(ctx, std::slice::from_ref(hook)).await {
+            let error = error.unwrap_or((format!("afterEach hook failed: {message}"), stack));
+            return Attempt::HookFailed(HookKind::AfterEach, error);
+        }
+    }
+    // Slow cleanup eats into the time of the next test
+    if let Some(timeout) = run.timeout
+        && cleanup_start.elapsed() > timeout / 10
+    {
+        use deno_terminal::colors;
+        utils::print_line!(
+            "{} afterEach hooks of \"{}\" took {}ms, over 10% of its {}ms timeout",
+            colors::yellow("warning:"),
+            run.name,
+            cleanup_start.elapsed().as_millis(),
+            timeout.as_millis()
+        );
+    }
+    Attempt::Settled(error)
+}
+
+/// Run a test, and again while it fails and has retries left, then report
+/// it along with the hook that failed, if any
+async fn run_test(
+    ctx: &Ctx<'_>,
+    mut run: TestRun,
+    start: Instant,
+) -> Result<(TestResult, Option<HookKind>)> {
+    use deno_terminal::colors;
+
+    loop {
+        let error = match run_attempt(ctx, &run).await? {
+            Attempt::HookFailed(kind, error) => {
+                let duration_ms = start.elapsed().as_millis();
+                return Ok((report_run(&run, duration_ms, Some(error)), Some(kind)));
+            }
+            Attempt::Settled(error) => error,
+        };
+        if error.is_none() || run.attempt > run.retries {
+            let duration_ms = start.elapsed().as_millis();
+            return Ok((report_run(&run, duration_ms, error), None));
+        }
+
+        utils::print_line!(
+            "{} ... {} (attempt {}/{})",
+            run.name,
+            colors::red("FAILED"),
+            run.attempt,
+            run.retries + 1
+        );
+        // A timer rather than a blocking sleep, so the runtime keeps
+        // driving pending I/O meanwhile
+        if !run.retry_delay.is_zero() {
+            let delay = run.retry_delay;
+            let timer = Promise::wrap_future(ctx, async move {
+                compio::time::sleep(delay).await;
+            })?;
+            settle(ctx, timer).await?;
+        }
+        run.attempt += 1;
+    }
+}
+
+/// Parse the `permissions` test option into the store the test runs with:
+/// `"inherit"` (the default) keeps the runner's permissions, `"none"` denies
+/// everything, and `{ read: ["/tmp"], write: false, ... }` narrows each
+/// permission, denying the ones left out
+fn scoped_permissions<'js>(ctx: &Ctx<'js>, option: Value<'js>) -> Result<Option<PermissionStore>> {
+    if option.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(mode) = option.as_string() {
+        return match mode.to_string()?.as_str() {
+            "inherit" => Ok(None),
+            "none" => Ok(Some(PermissionStore::new(PermissionState::Denied))),
+            mode => Err(Exception::throw_type(
+                ctx,
+                &format!("Invalid permissions option \"{mode}\""),
+            )),
+        };
+    }
+    let Some(options) = option.as_object() else {
+        return Err(Exception::throw_type(
+            ctx,
+            "The permissions option must be \"inherit\", \"none\" or an object",
+        ));
+    };
+
+    let mut store = deno_permissions::current_permissions();
+    for &name in PERMISSION_NAMES {
+        let value: Value = options.get(name)?;
+        let mode = value
+            .as_string()
+            .map(rquickjs::String::to_string)
+            .transpose()?;
+        if mode.as_deref() == Some("inherit") {
+            continue;
+        }
+        let resources: Vec<String> = if value.as_bool() == Some(true) {
+            if store.query(name, None) != PermissionState::Granted {
+                return Err(Exception::throw_type(
+                    ctx,
+                    &format!("Can't escalate parent permissions: {name} access"),
+                ));
+            }
+            continue;
+        } else if value.is_array() {
+            value.get()?
+        } else if value.is_undefined() || value.as_bool() == Some(false) {
+            Vec::new()
+        } else {
+            return Err(Exception::throw_type(
+                ctx,
+                &format!("Invalid value for the {name} permission"),
+            ));
+        };
+        store
+            .restrict(name, &resources)
+            .map_err(|message| Exception::throw_type(ctx, &message))?;
+    }
+    Ok(Some(store))
+}
+
+/// Extract the message and stack trace of a thrown value
+fn caught_error(caught: rquickjs::CaughtError<'_>) -> (String, Option<String>) {
+    match caught {
+        rquickjs::CaughtError::Exception(ex) => describe_thrown(ex.as_object()),
+        rquickjs::CaughtError::Error(e) => (format!("Error: {e}"), None),
+        rquickjs::CaughtError::Value(v) => match v.as_object() {
+            Some(obj) => describe_thrown(obj),
+            None => (
+                v.get::<Coerced<String>>()
+                    .map_or_else(|_| "Unknown error".to_string(), |s| s.0),
+                None,
+            ),
+        },
+    }
+}
+
+/// Read `name`, `message` and `stack` off a thrown object, whether or not it is an `Error`
+fn describe_thrown(obj: &Object<'_>) -> (String, Option<String>) {
+    let field = |key: &str| {
+        obj.get::<_, Option<Coerced<String>>>(key)
+            .ok()
+            .flatten()
+            .map(|s| s.0)
+    };
+    let name = field("name").unwrap_or_else(|| "Error".to_string());
+    let message = field("message").unwrap_or_default();
+    let stack = obj
+        .get::<_, Option<String>>("stack")
+        .ok()
+        .flatten()
+        .filter(|s| !s.is_empty());
+    (format!("{name}: {message}"), stack)
+}
+
+/// Create a `FakeClock` (see `fake_clock.ts`) and swap it in for the timer globals
+fn install_fake_clock<'js>(
+    ctx: &Ctx<'js>,
+) -> std::result::Result<Object<'js>, (String, Option<String>)> {
+    use rquickjs::CatchResultExt;
+
+    (|| {
+        let globals = ctx.globals();
+        let symbol_ctor: Function = globals.get("Symbol")?;
+        let symbol_for: Function = symbol_ctor.get("for")?;
+        let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
+        let internal: Object = globals.get(internal_symbol)?;
+        let test: Object = internal.get("test")?;
+        let constructor: Constructor = test.get("FakeClock")?;
+        let clock: Object = constructor.construct(())?;
+        clock
+            .get::<_, Function>("install")?
+            .call::<_, ()>((This(clock.clone()),))?;
+        Ok(clock)
+    })()
+    .catch(ctx)
+    .map_err(caught_error)
+}
+
+/// Restore the timer globals replaced by `install_fake_clock`
+fn uninstall_fake_clock(clock: &Object<'_>) -> std::result::Result<(), (String, Option<String>)> {
+    use rquickjs::CatchResultExt;
+
+    clock
+        .get::<_, Function>("uninstall")
+        .and_then(|uninstall| uninstall.call::<_, ()>((This(clock.clone()),)))
+        .catch(clock.ctx())
+        .map_err(caught_error)
+}
+
+/// Call hooks in order, awaiting the ones that return a promise, and stop
+/// at the first one that throws or rejects
+async fn call_hooks(
+    ctx: &Ctx<'_>,
+    hooks: &[PersistentFunction],
+) -> std::result::Result<(), (String, Option<String>)> {
+    use rquickjs::CatchResultExt;
+
+    for hook in hooks {
+        let func = hook
+            .clone()
+            .restore(ctx)
+            .map_err(|e| (e.to_string(), None))?;
+        let value = func.call::<_, Value>(()).catch(ctx).map_err(caught_error)?;
+        if let Some(promise) = value.into_promise() {
+            settle(ctx, promise)
+                .await
+                .catch(ctx)
+                .map_err(caught_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Print the result line for a test and build its `TestResult`
+fn report(name: &str, duration_ms: u128, error: Option<TestError>) -> TestResult {
+    report_with_note(name, duration_ms, error, None)
+}
+
+/// Like `report`, noting how many retries the test needed
+fn report_run(run: &TestRun, duration_ms: u128, error: Option<TestError>) -> TestResult {
+    let note = match run.attempt - 1 {
+        0 => None,
+        1 => Some("(1 retry)".to_string()),
+        retries => Some(format!("({retries} retries)")),
+    };
+    report_with_note(&run.name, duration_ms, error, note)
+}
+
+fn report_with_note(
+    name: &str,
+    duration_ms: u128,
+    error: Option<TestError>,
+    note: Option<String>,
+) -> TestResult {
+    use deno_terminal::colors;
+
+    let passed = error.is_none();
+    let status = if passed {
+        colors::green("ok")
+    } else {
+        colors::red("FAILED")
+    };
+    let status = match note {
+        Some(note) => format!("{status} {note}"),
+        None => status.to_string(),
+    };
+    let time_str = format!("({duration_ms}ms)");
+    utils::print_line!("{} ... {} {}", name, status, colors::gray(&time_str));
+
+    let (error, error_stack) = error.map_or((None, None), |(error, stack)| (Some(error), stack));
+    TestResult {
+        name: name.to_string(),
+        passed,
+        error,
+        error_stack,
     }
 }
 
@@ -340,37 +1340,37 @@ pub(crate) struct TestResult {
 fn print_results(results: &[TestResult], filename: &str) {
     use deno_terminal::colors;
 
-    println!();
+    utils::print_line!();
 
     // Print errors if any
     let failures: Vec<&TestResult> = results.iter().filter(|r| !r.passed).collect();
     if !failures.is_empty() {
-        println!("{}\n", colors::white_on_red(&colors::bold(" ERRORS ")));
+        utils::print_line!("{}\n", colors::white_on_red(&colors::bold(" ERRORS ")));
 
         for failure in &failures {
-            println!(
+            utils::print_line!(
                 "{} {}",
                 failure.name,
                 colors::gray(&format!("=> {filename}"))
             );
             if let Some(error) = &failure.error {
-                println!("{}: Error: {}", colors::red(&colors::bold("error")), error);
+                utils::print_line!("{}: {}", colors::red(&colors::bold("error")), error);
             }
             if let Some(stack) = &failure.error_stack {
-                println!("{stack}");
+                utils::print_line!("{stack}");
             }
-            println!();
+            utils::print_line!();
         }
 
-        println!("{}\n", colors::white_on_red(&colors::bold(" FAILURES ")));
+        utils::print_line!("{}\n", colors::white_on_red(&colors::bold(" FAILURES ")));
         for failure in &failures {
-            println!(
+            utils::print_line!(
                 "{} {}",
                 failure.name,
                 colors::gray(&format!("=> {filename}"))
             );
         }
-        println!();
+        utils::print_line!();
     }
 
     // Don't print summary here - it will be printed at the end by test.rs
@@ -1,7 +1,101 @@
 // Global wrapper functions for test runner
 
-use crate::test_context::TestContext;
-use rquickjs::{Ctx, Function, Object, Result, Value};
+use crate::test_context::{HookKind, TestContext};
+use rquickjs::{Ctx, Function, Object, Result, Value, prelude::Opt};
+use std::cell::Cell;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Set when any test file of the run uses `only: true`, so the other files
+/// skip their non-only tests too
+pub(crate) static ONLY_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable cross-file `only` filtering for subsequent test runs
+pub fn set_only_mode(enabled: bool) {
+    ONLY_MODE.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn only_mode() -> bool {
+    ONLY_MODE.load(Ordering::Relaxed)
+}
+
+/// Retries for tests without their own `retry` option (--retries)
+static RETRIES: AtomicU32 = AtomicU32::new(0);
+
+/// Retry failed tests up to `retries` more times, unless they set `retry`
+pub fn set_retries(retries: u32) {
+    RETRIES.store(retries, Ordering::Relaxed);
+}
+
+pub(crate) fn global_retries() -> u32 {
+    RETRIES.load(Ordering::Relaxed)
+}
+
+/// Timeout in milliseconds for tests without their own `timeout` option
+/// (--timeout); 0 means no timeout
+static TIMEOUT_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Fail tests that run longer than `ms`, unless they set `timeout`
+pub fn set_timeout(ms: u64) {
+    TIMEOUT_MS.store(ms, Ordering::Relaxed);
+}
+
+pub(crate) fn global_timeout() -> Option<Duration> {
+    match TIMEOUT_MS.load(Ordering::Relaxed) {
+        0 => None,
+        ms => Some(Duration::from_millis(ms)),
+    }
+}
+
+thread_local! {
+    /// When the synchronous part of the running test must stop. Test files
+    /// run on their own threads, so each has its own deadline.
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+pub(crate) fn set_deadline(deadline: Option<Instant>) {
+    DEADLINE.set(deadline);
+}
+
+/// Whether the running test is past its timeout; the test runner installs
+/// this as the runtime's interrupt handler to stop runaway loops
+pub fn deadline_passed() -> bool {
+    DEADLINE
+        .get()
+        .is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Set by `mdeno bench`; otherwise `Deno.bench` registrations are ignored
+static BENCH_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Results reported by `Deno.bench` since the last `take_bench_results`, as JSON
+static BENCH_RESULTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Run benchmarks once a module has loaded, collecting their results
+pub fn set_bench_mode(enabled: bool) {
+    BENCH_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// Take the JSON results reported by benchmarks so far
+pub fn take_bench_results() -> Vec<String> {
+    BENCH_RESULTS
+        .lock()
+        .map(|mut results| std::mem::take(&mut *results))
+        .unwrap_or_default()
+}
+
+#[rquickjs::function]
+pub fn bench_enabled() -> bool {
+    BENCH_MODE.load(Ordering::Relaxed)
+}
+
+#[rquickjs::function]
+pub fn bench_report(result: String) {
+    if let Ok(mut results) = BENCH_RESULTS.lock() {
+        results.push(result);
+    }
+}
 
 fn get_test_context(ctx: &Ctx<'_>) -> Result<TestContext> {
     let globals = ctx.globals();
@@ -16,10 +110,10 @@ fn get_test_context(ctx: &Ctx<'_>) -> Result<TestContext> {
 pub fn deno_test<'js>(
     ctx: Ctx<'js>,
     name_or_options: Value<'js>,
-    fn_val: Option<Value<'js>>,
-) -> Result<()> {
+    fn_val: Opt<Value<'js>>,
+) -> Result<Object<'js>> {
     let test_context = get_test_context(&ctx)?;
-    test_context.register_test(ctx, name_or_options, fn_val)
+    test_context.register_test(ctx, name_or_options, fn_val.0)
 }
 
 #[rquickjs::function]
@@ -40,3 +134,32 @@ pub fn resolve_pending(ctx: Ctx<'_>) -> Result<Value<'_>> {
     let test_context = get_test_context(&ctx)?;
     test_context.resolve_pending(ctx)
 }
+
+#[rquickjs::function]
+pub fn before_all<'js>(ctx: Ctx<'js>, func: Function<'js>) -> Result<()> {
+    get_test_context(&ctx)?.register_hook(&ctx, HookKind::BeforeAll, func);
+    Ok(())
+}
+
+#[rquickjs::function]
+pub fn after_all<'js>(ctx: Ctx<'js>, func: Function<'js>) -> Result<()> {
+    get_test_context(&ctx)?.register_hook(&ctx, HookKind::AfterAll, func);
+    Ok(())
+}
+
+#[rquickjs::function]
+pub fn before_each<'js>(ctx: Ctx<'js>, func: Function<'js>) -> Result<()> {
+    get_test_context(&ctx)?.register_hook(&ctx, HookKind::BeforeEach, func);
+    Ok(())
+}
+
+#[rquickjs::function]
+pub fn after_each<'js>(ctx: Ctx<'js>, func: Function<'js>) -> Result<()> {
+    get_test_context(&ctx)?.register_hook(&ctx, HookKind::AfterEach, func);
+    Ok(())
+}
+
+#[rquickjs::function]
+pub fn describe<'js>(ctx: Ctx<'js>, name: String, func: Function<'js>) -> Result<()> {
+    get_test_context(&ctx)?.describe(name, func)
+}
@@ -0,0 +1,49 @@
+// Deno.test({ timeout }) E2E tests
+// Each test hangs on its first attempt; the timeout fails that attempt and
+// the retry, which gets a fresh timeout, passes
+
+// timeout and retry are mdeno extensions to Deno.TestDefinition
+type TimeoutDefinition = Deno.TestDefinition & {
+  timeout?: number;
+  retry?: number;
+};
+
+let syncAttempts = 0;
+Deno.test({
+  name: "timeout - interrupts a sync test stuck in a loop",
+  timeout: 50,
+  retry: 1,
+  fn() {
+    syncAttempts++;
+    if (syncAttempts === 1) {
+      while (true) {
+        // spin until interrupted
+      }
+    }
+  },
+} as TimeoutDefinition);
+
+let asyncAttempts = 0;
+Deno.test({
+  name: "timeout - fails an async test whose promise never settles",
+  timeout: 50,
+  retry: 1,
+  async fn() {
+    asyncAttempts++;
+    if (asyncAttempts === 1) {
+      await new Promise(() => {});
+    }
+  },
+} as TimeoutDefinition);
+
+Deno.test("timeout - both tests needed their retry", () => {
+  if (syncAttempts !== 2) throw new Error(`sync attempts: ${syncAttempts}`);
+});
+
+Deno.test({
+  name: "timeout - a test that finishes in time isn't affected",
+  timeout: 1000,
+  async fn() {
+    await Promise.resolve();
+  },
+} as TimeoutDefinition);
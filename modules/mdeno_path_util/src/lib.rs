@@ -5,7 +5,11 @@
 #![deny(clippy::unused_async)]
 #![deny(clippy::unnecessary_wraps)]
 
+mod source_map;
+
+pub use source_map::{MappingEntry, SourceMapConsumer};
 use std::path::{Path, PathBuf};
+use url::Url;
 
 /// Convert a file path to a file:// URL string.
 ///
@@ -53,6 +57,64 @@ pub fn to_file_url(path: &Path) -> String {
     }
 }
 
+/// Convert a file:// URL back to a file path.
+///
+/// Percent-encoded characters are decoded. On Windows, `file:///C:/path`
+/// becomes `C:\path` and `file://server/share/path` becomes
+/// `\\server\share\path`.
+///
+/// # Examples
+///
+/// ```
+/// # use std::path::PathBuf;
+/// # use mdeno_path_util::from_file_url;
+/// # #[cfg(unix)]
+/// # {
+/// let path = from_file_url("file:///home/user/my%20file.js").unwrap();
+/// assert_eq!(path, PathBuf::from("/home/user/my file.js"));
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an error if `url` isn't a valid file:// URL or has no path
+/// equivalent on this platform
+pub fn from_file_url(url: &str) -> Result<PathBuf, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL \"{url}\": {e}"))?;
+    if parsed.scheme() != "file" {
+        return Err(format!("Not a file URL: {url}"));
+    }
+    parsed
+        .to_file_path()
+        .map_err(|()| format!("Invalid file URL: {url}"))
+}
+
+/// Resolve a relative URL reference against a base URL.
+///
+/// # Examples
+///
+/// ```
+/// # use mdeno_path_util::join_url;
+/// let url = join_url("file:///src/main.ts", "../lib/mod.ts").unwrap();
+/// assert_eq!(url, "file:///lib/mod.ts");
+/// ```
+///
+/// # Errors
+/// Returns an error if `base` isn't a valid URL or `relative` can't be
+/// resolved against it
+pub fn join_url(base: &str, relative: &str) -> Result<String, String> {
+    let base_url = Url::parse(base).map_err(|e| format!("Invalid URL \"{base}\": {e}"))?;
+    base_url
+        .join(relative)
+        .map(String::from)
+        .map_err(|e| format!("Failed to resolve \"{relative}\" against \"{base}\": {e}"))
+}
+
+/// Check whether a string is a file:// URL.
+pub fn is_file_url(s: &str) -> bool {
+    s.get(..7)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("file://"))
+}
+
 /// Strips the UNC prefix from a Windows path.
 ///
 /// This is useful when working with canonicalized paths on Windows,
@@ -128,6 +190,57 @@ mod tests {
         assert_eq!(url, "file:///home/user/file.js");
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_from_file_url_unix() {
+        assert_eq!(
+            from_file_url("file:///home/user/file.js"),
+            Ok(PathBuf::from("/home/user/file.js"))
+        );
+        assert_eq!(
+            from_file_url("file:///tmp/a%20b/%E3%81%82.ts"),
+            Ok(PathBuf::from("/tmp/a b/あ.ts"))
+        );
+        let path = PathBuf::from("/home/user/file.js");
+        assert_eq!(from_file_url(&to_file_url(&path)), Ok(path));
+        assert!(from_file_url("https://example.com/file.js").is_err());
+        assert!(from_file_url("not a url").is_err());
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_from_file_url_windows() {
+        assert_eq!(
+            from_file_url("file:///C:/Users/test/file.js"),
+            Ok(PathBuf::from(r"C:\Users\test\file.js"))
+        );
+        assert_eq!(
+            from_file_url("file://server/share/file.js"),
+            Ok(PathBuf::from(r"\\server\share\file.js"))
+        );
+    }
+
+    #[test]
+    fn test_join_url() {
+        assert_eq!(
+            join_url("file:///src/main.ts", "./util.ts").as_deref(),
+            Ok("file:///src/util.ts")
+        );
+        assert_eq!(
+            join_url("https://jsr.io/@std/assert/1.0.0/mod.ts", "../meta.json").as_deref(),
+            Ok("https://jsr.io/@std/assert/meta.json")
+        );
+        assert!(join_url("./relative", "x.ts").is_err());
+    }
+
+    #[test]
+    fn test_is_file_url() {
+        assert!(is_file_url("file:///home/user/file.js"));
+        assert!(is_file_url("FILE:///C:/file.js"));
+        assert!(!is_file_url("https://example.com"));
+        assert!(!is_file_url("/home/user/file.js"));
+    }
+
     #[cfg(windows)]
     #[test]
     fn test_strip_unc_prefix() {
@@ -0,0 +1,229 @@
+// Reading of source maps, to report positions in the original TypeScript
+// instead of the JavaScript it was transpiled to
+
+use base64::alphabet;
+use serde::Deserialize;
+
+/// The fields of a version 3 source map needed to look up positions
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSourceMap {
+    #[serde(default)]
+    source_root: Option<String>,
+    sources: Vec<Option<String>>,
+    mappings: String,
+}
+
+/// One decoded segment of `mappings`. Lines and columns are zero-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MappingEntry {
+    pub generated_line: u32,
+    pub generated_column: u32,
+    pub source: u32,
+    pub original_line: u32,
+    pub original_column: u32,
+}
+
+/// A parsed source map that maps generated positions back to the original
+/// source
+///
+/// # Examples
+///
+/// ```
+/// # use mdeno_path_util::SourceMapConsumer;
+/// let map = r#"{"version":3,"sources":["a.ts"],"mappings":"AAAA,MAAC;AACA"}"#;
+/// let consumer = SourceMapConsumer::new(map).unwrap();
+/// assert_eq!(
+///     consumer.original_position_for(0, 8),
+///     Some(("a.ts".to_string(), 0, 1))
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct SourceMapConsumer {
+    sources: Vec<String>,
+    /// Sorted by generated position for binary search
+    mappings: Vec<MappingEntry>,
+}
+
+impl SourceMapConsumer {
+    /// Parse a source map from its JSON text
+    ///
+    /// # Errors
+    /// Returns an error if `json` isn't a source map or its mappings aren't
+    /// valid base64 VLQ
+    pub fn new(json: &str) -> Result<Self, String> {
+        let raw: RawSourceMap =
+            serde_json::from_str(json).map_err(|e| format!("Invalid source map: {e}"))?;
+        let root = raw.source_root.unwrap_or_default();
+        let sources = raw
+            .sources
+            .into_iter()
+            .map(|source| {
+                let source = source.unwrap_or_default();
+                if root.is_empty() {
+                    source
+                } else {
+                    format!("{}/{source}", root.trim_end_matches('/'))
+                }
+            })
+            .collect();
+        let mut mappings = decode_mappings(&raw.mappings)?;
+        mappings.sort_by_key(|entry| (entry.generated_line, entry.generated_column));
+        Ok(Self { sources, mappings })
+    }
+
+    /// The decoded mappings, ordered by generated position
+    pub fn mappings(&self) -> &[MappingEntry] {
+        &self.mappings
+    }
+
+    /// The original file, line and column of a generated position, using the
+    /// closest mapping at or before it on the same line. Lines and columns
+    /// are zero-based.
+    pub fn original_position_for(
+        &self,
+        generated_line: u32,
+        generated_col: u32,
+    ) -> Option<(String, u32, u32)> {
+        let index = self.mappings.partition_point(|entry| {
+            (entry.generated_line, entry.generated_column) <= (generated_line, generated_col)
+        });
+        let entry = self.mappings.get(index.checked_sub(1)?)?;
+        if entry.generated_line != generated_line {
+            return None;
+        }
+        let source = self.sources.get(entry.source as usize)?;
+        Some((source.clone(), entry.original_line, entry.original_column))
+    }
+}
+
+/// Decode the `mappings` field: lines are separated by `;`, segments by `,`,
+/// and every field is a base64 VLQ relative to the previous segment
+fn decode_mappings(mappings: &str) -> Result<Vec<MappingEntry>, String> {
+    let digits = vlq_digits();
+    let mut entries = Vec::new();
+    // Source, original line and original column carry across lines
+    let (mut source, mut original_line, mut original_column) = (0i64, 0i64, 0i64);
+    for (generated_line, line) in mappings.split(';').enumerate() {
+        let mut generated_column = 0i64;
+        for segment in line.split(',').filter(|segment| !segment.is_empty()) {
+            let fields = decode_vlq(segment, &digits)?;
+            let [column, rest @ ..] = fields.as_slice() else {
+                continue;
+            };
+            generated_column += column;
+            // Segments with one field map to nothing in the original source
+            let [source_delta, line_delta, column_delta, ..] = rest else {
+                continue;
+            };
+            source += source_delta;
+            original_line += line_delta;
+            original_column += column_delta;
+            let field = |value: i64| {
+                u32::try_from(value).map_err(|_| format!("Invalid source map segment: {segment}"))
+            };
+            entries.push(MappingEntry {
+                generated_line: field(generated_line as i64)?,
+                generated_column: field(generated_column)?,
+                source: field(source)?,
+                original_line: field(original_line)?,
+                original_column: field(original_column)?,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// The value of each base64 digit, indexed by its ASCII code
+fn vlq_digits() -> [Option<u8>; 128] {
+    let mut digits = [None; 128];
+    for (value, byte) in alphabet::STANDARD.as_str().bytes().enumerate() {
+        digits[usize::from(byte)] = Some(value as u8);
+    }
+    digits
+}
+
+/// Decode the signed base64 VLQ values of one segment. Each digit holds five
+/// bits, least significant first, with the sixth bit marking a continuation;
+/// the lowest bit of a decoded value is its sign.
+fn decode_vlq(segment: &str, digits: &[Option<u8>; 128]) -> Result<Vec<i64>, String> {
+    let mut values = Vec::new();
+    let mut value = 0i64;
+    let mut shift = 0;
+    for byte in segment.bytes() {
+        let digit = digits
+            .get(usize::from(byte))
+            .copied()
+            .flatten()
+            .ok_or_else(|| format!("Invalid base64 VLQ digit: {}", char::from(byte)))?;
+        if shift > 60 {
+            return Err(format!("Base64 VLQ value is too large: {segment}"));
+        }
+        value += i64::from(digit & 0b1_1111) << shift;
+        if digit & 0b10_0000 == 0 {
+            let magnitude = value >> 1;
+            values.push(if value & 1 == 1 {
+                -magnitude
+            } else {
+                magnitude
+            });
+            value = 0;
+            shift = 0;
+        } else {
+            shift += 5;
+        }
+    }
+    if shift != 0 {
+        return Err(format!("Unterminated base64 VLQ value: {segment}"));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_vlq() {
+        let digits = vlq_digits();
+        assert_eq!(decode_vlq("AAgBC", &digits).unwrap(), [0, 0, 16, 1]);
+        assert_eq!(decode_vlq("D", &digits).unwrap(), [-1]);
+        assert_eq!(decode_vlq("2H", &digits).unwrap(), [123]);
+        assert!(decode_vlq("g", &digits).is_err());
+        assert!(decode_vlq("A!", &digits).is_err());
+    }
+
+    #[test]
+    fn test_original_position_for() {
+        let consumer = SourceMapConsumer::new(
+            r#"{"version":3,"sources":["a.ts","b.ts"],"mappings":"AAAA,IAAI;;ACCJ,EAAE"}"#,
+        )
+        .unwrap();
+        assert_eq!(consumer.mappings().len(), 4);
+        assert_eq!(
+            consumer.original_position_for(0, 6),
+            Some(("a.ts".to_string(), 0, 4))
+        );
+        assert_eq!(
+            consumer.original_position_for(2, 9),
+            Some(("b.ts".to_string(), 1, 2))
+        );
+        // Line 1 has no mappings
+        assert_eq!(consumer.original_position_for(1, 0), None);
+        assert_eq!(consumer.original_position_for(5, 0), None);
+    }
+
+    #[test]
+    fn test_source_root() {
+        let consumer = SourceMapConsumer::new(
+            r#"{"version":3,"sourceRoot":"src/","sources":["a.ts"],"mappings":"AAAA"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            consumer.original_position_for(0, 3),
+            Some(("src/a.ts".to_string(), 0, 0))
+        );
+        assert!(SourceMapConsumer::new("{}").is_err());
+    }
+}
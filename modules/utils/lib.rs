@@ -4,11 +4,85 @@ use oxc_parser::Parser;
 use oxc_semantic::SemanticBuilder;
 use oxc_span::SourceType;
 use oxc_transformer::{TransformOptions, Transformer};
-use rquickjs::{Ctx, Result};
+use rquickjs::{ArrayBuffer, Class, Ctx, Result, Value, class::JsClass};
+
+pub mod output;
 
 /// Magic section name for embedded bytecode in standalone binaries
 pub const SECTION_NAME: &str = "md3n04cl1";
 
+/// Version of the `QuickJS` engine, whose bytecode format may change between releases
+pub fn quickjs_version() -> String {
+    // SAFETY: JS_GetVersion returns a pointer to a static NUL-terminated string
+    unsafe { std::ffi::CStr::from_ptr(rquickjs::qjs::JS_GetVersion()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The bytes of an `ArrayBuffer`, typed array or `DataView`, or `None` for
+/// anything else
+pub fn buffer_source_bytes(value: &Value<'_>) -> Option<Vec<u8>> {
+    let object = value.as_object()?;
+    if let Some(array) = object.as_typed_array::<u8>() {
+        return array.as_bytes().map(<[u8]>::to_vec);
+    }
+    // Other views are read through the buffer they view
+    let (buffer, range) = match object.get::<_, Option<Value>>("buffer").ok()? {
+        Some(buffer) => {
+            let offset: usize = object.get("byteOffset").ok()?;
+            let length: usize = object.get("byteLength").ok()?;
+            (buffer, Some(offset..offset + length))
+        }
+        None => (value.clone(), None),
+    };
+    let ctx = value.ctx();
+    let Some(buffer) = ArrayBuffer::from_value(buffer) else {
+        // Checking for an ArrayBuffer leaves a TypeError pending when it isn't one
+        ctx.catch();
+        return None;
+    };
+    let bytes = buffer.as_bytes()?;
+    match range {
+        Some(range) => bytes.get(range).map(<[u8]>::to_vec),
+        None => Some(bytes.to_vec()),
+    }
+}
+
+/// `value` as an instance of the Rust class `C`
+///
+/// `Class::from_value` leaves a pending `TypeError` behind when given an
+/// object that isn't a Rust class, so the prototype chain is checked first.
+pub fn class_of<'js, C: JsClass<'js>>(value: &Value<'js>) -> Option<Class<'js, C>> {
+    let object = value.as_object()?;
+    let prototype = Class::<C>::prototype(value.ctx()).ok()??;
+    let mut current = object.get_prototype();
+    while let Some(candidate) = current {
+        if candidate == prototype {
+            return Class::from_object(object);
+        }
+        current = candidate.get_prototype();
+    }
+    None
+}
+
+/// Pass an error thrown by a callback, such as an event listener, to the
+/// runtime's uncaught error reporter, or rethrow it where there is none
+///
+/// # Errors
+/// Returns `error` if it isn't an exception, or the reporter's error
+pub fn report_uncaught(ctx: &Ctx<'_>, error: rquickjs::Error) -> Result<()> {
+    if !error.is_exception() {
+        return Err(error);
+    }
+    let thrown = ctx.catch();
+    let reporter: Option<rquickjs::Function> =
+        ctx.eval("globalThis[Symbol.for('mdeno.internal')].reportUncaught")?;
+    match reporter {
+        Some(reporter) => reporter.call((thrown,)),
+        None => Err(ctx.throw(thrown)),
+    }
+}
+
 /// Transpile TypeScript to JavaScript using oxc
 ///
 /// # Errors
@@ -146,6 +220,11 @@ impl DenoError {
                 std::io::ErrorKind::NotConnected => "NotConnected",
                 std::io::ErrorKind::AddrInUse => "AddrInUse",
                 std::io::ErrorKind::AddrNotAvailable => "AddrNotAvailable",
+                std::io::ErrorKind::IsADirectory => "IsADirectory",
+                std::io::ErrorKind::NotADirectory => "NotADirectory",
+                std::io::ErrorKind::NetworkUnreachable => "NetworkUnreachable",
+                std::io::ErrorKind::ResourceBusy => "Busy",
+                std::io::ErrorKind::Unsupported => "NotSupported",
                 _ => "Other",
             },
             DenoError::BadResource(_) => "BadResource",
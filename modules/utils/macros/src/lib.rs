@@ -1,16 +1,124 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{LitStr, parse_macro_input};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Ident, LitStr, Token, braced, parse_macro_input};
 
 use oxc_allocator::Allocator;
 use oxc_codegen::{Codegen, CodegenOptions};
 use oxc_parser::Parser;
 use oxc_semantic::SemanticBuilder;
 use oxc_span::SourceType;
-use oxc_transformer::{TransformOptions, Transformer};
+use oxc_transformer::{JsxOptions, JsxRuntime, TransformOptions, Transformer};
+
+/// JSX settings for `include_tsx!`, written as
+/// `{ runtime: "classic", pragma: "h", pragma_frag: "Fragment", import_source: "preact" }`
+///
+/// Every field is optional; the defaults match the classic transform with
+/// `React.createElement`.
+struct JsxConfig {
+    runtime: JsxRuntime,
+    pragma: Option<String>,
+    pragma_frag: Option<String>,
+    import_source: Option<String>,
+}
+
+impl Default for JsxConfig {
+    fn default() -> Self {
+        Self {
+            runtime: JsxRuntime::Classic,
+            pragma: None,
+            pragma_frag: None,
+            import_source: None,
+        }
+    }
+}
+
+impl JsxConfig {
+    fn to_options(&self) -> JsxOptions {
+        JsxOptions {
+            runtime: self.runtime,
+            pragma: self.pragma.clone(),
+            pragma_frag: self.pragma_frag.clone(),
+            import_source: self.import_source.clone(),
+            ..JsxOptions::enable()
+        }
+    }
+}
+
+struct ConfigField {
+    key: Ident,
+    value: LitStr,
+}
+
+impl Parse for ConfigField {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let value = input.parse()?;
+        Ok(Self { key, value })
+    }
+}
+
+impl Parse for JsxConfig {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        braced!(content in input);
+        let fields = Punctuated::<ConfigField, Token![,]>::parse_terminated(&content)?;
+
+        let mut config = Self::default();
+        for ConfigField { key, value } in fields {
+            match key.to_string().as_str() {
+                "runtime" => {
+                    config.runtime = match value.value().as_str() {
+                        "classic" => JsxRuntime::Classic,
+                        "automatic" => JsxRuntime::Automatic,
+                        _ => {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                "runtime must be \"classic\" or \"automatic\"",
+                            ));
+                        }
+                    };
+                }
+                "pragma" => config.pragma = Some(value.value()),
+                "pragma_frag" => config.pragma_frag = Some(value.value()),
+                "import_source" => config.import_source = Some(value.value()),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected one of: runtime, pragma, pragma_frag, import_source",
+                    ));
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// `include_tsx!` arguments: a file path and an optional JSX config
+struct TsxInput {
+    path: LitStr,
+    config: JsxConfig,
+}
+
+impl Parse for TsxInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let config = if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            input.parse()?
+        } else {
+            JsxConfig::default()
+        };
+        Ok(Self { path, config })
+    }
+}
 
 /// Transpile TypeScript to JavaScript at compile time and include as a string
 ///
+/// `.tsx` and `.jsx` files are parsed with JSX enabled, which compiles to
+/// `React.createElement` calls. Use `include_tsx!` to configure the transform.
+///
 /// # Example
 /// ```ignore
 /// let js_code = include_ts!("console.ts");
@@ -20,23 +128,44 @@ use oxc_transformer::{TransformOptions, Transformer};
 /// Panics if the TypeScript file cannot be read or transpiled.
 /// This is intentional for procedural macros to report compile-time errors.
 #[proc_macro]
-#[allow(clippy::expect_used)] // Procedural macros use expect/panic to report compile-time errors
-#[allow(clippy::panic)] // Procedural macros use panic to report compile-time errors
 pub fn include_ts(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as LitStr);
-    let ts_file_path = input.value();
+    include_transpiled(&input.value(), &JsxConfig::default())
+}
 
+/// Like `include_ts!`, with JSX settings for the transform
+///
+/// # Example
+/// ```ignore
+/// // Preact with the classic runtime
+/// let js_code = include_tsx!("app.tsx", { pragma: "h", pragma_frag: "Fragment" });
+/// // Automatic runtime, importing from "preact/jsx-runtime"
+/// let js_code = include_tsx!("app.tsx", { runtime: "automatic", import_source: "preact" });
+/// ```
+///
+/// # Panics
+/// Panics if the file cannot be read or transpiled.
+/// This is intentional for procedural macros to report compile-time errors.
+#[proc_macro]
+pub fn include_tsx(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as TsxInput);
+    include_transpiled(&input.path.value(), &input.config)
+}
+
+#[allow(clippy::expect_used)] // Procedural macros use expect/panic to report compile-time errors
+#[allow(clippy::panic)] // Procedural macros use panic to report compile-time errors
+fn include_transpiled(ts_file_path: &str, jsx: &JsxConfig) -> TokenStream {
     // Get the directory of the file that's calling this macro
     let cargo_manifest_dir =
         std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
-    let full_path = std::path::Path::new(&cargo_manifest_dir).join(&ts_file_path);
+    let full_path = std::path::Path::new(&cargo_manifest_dir).join(ts_file_path);
 
     // Read the TypeScript file
     let ts_source = std::fs::read_to_string(&full_path)
         .unwrap_or_else(|e| panic!("Failed to read {}: {e}", full_path.display()));
 
     // Transpile TypeScript to JavaScript
-    let js_source = transpile_ts(&ts_source, &ts_file_path)
+    let js_source = transpile_ts(&ts_source, ts_file_path, jsx)
         .unwrap_or_else(|e| panic!("Failed to transpile {ts_file_path}: {e}"));
 
     // Return the JavaScript source as a string literal
@@ -47,11 +176,20 @@ pub fn include_ts(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
-fn transpile_ts(source: &str, filename: &str) -> Result<String, Box<dyn std::error::Error>> {
+fn transpile_ts(
+    source: &str,
+    filename: &str,
+    jsx: &JsxConfig,
+) -> Result<String, Box<dyn std::error::Error>> {
     let allocator = Allocator::default();
-    let source_type = SourceType::from_path(std::path::Path::new(filename))
+    let path = std::path::Path::new(filename);
+    let is_jsx = path
+        .extension()
+        .is_some_and(|ext| ext == "tsx" || ext == "jsx");
+    let source_type = SourceType::from_path(path)
         .unwrap_or_default()
-        .with_typescript(true);
+        .with_typescript(true)
+        .with_jsx(is_jsx);
 
     // Parse the source code
     let parser_ret = Parser::new(&allocator, source, source_type).parse();
@@ -67,7 +205,10 @@ fn transpile_ts(source: &str, filename: &str) -> Result<String, Box<dyn std::err
         .into_scoping();
 
     // Configure and run the transformer
-    let transform_options = TransformOptions::default();
+    let transform_options = TransformOptions {
+        jsx: jsx.to_options(),
+        ..TransformOptions::default()
+    };
     let transformer_ret = Transformer::new(
         &allocator,
         std::path::Path::new(filename),
@@ -87,3 +228,37 @@ fn transpile_ts(source: &str, filename: &str) -> Result<String, Box<dyn std::err
 
     Ok(code)
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "const el: unknown = <div class=\"a\"><>x</></div>;";
+
+    #[test]
+    fn test_transpile_tsx_classic() {
+        let code = transpile_ts(SOURCE, "app.tsx", &JsxConfig::default()).unwrap();
+        assert!(code.contains("React.createElement(\"div\""));
+        assert!(code.contains("React.Fragment"));
+    }
+
+    #[test]
+    fn test_transpile_tsx_config() {
+        let config: JsxConfig =
+            syn::parse_str(r#"{ pragma: "h", pragma_frag: "Fragment" }"#).unwrap();
+        let code = transpile_ts(SOURCE, "app.tsx", &config).unwrap();
+        assert!(code.contains("h(\"div\""));
+        assert!(code.contains("h(Fragment"));
+
+        let config: JsxConfig =
+            syn::parse_str(r#"{ runtime: "automatic", import_source: "preact" }"#).unwrap();
+        let code = transpile_ts(SOURCE, "app.tsx", &config).unwrap();
+        assert!(code.contains("\"preact/jsx-runtime\""));
+    }
+
+    #[test]
+    fn test_transpile_ts_rejects_jsx() {
+        assert!(transpile_ts(SOURCE, "app.ts", &JsxConfig::default()).is_err());
+    }
+}
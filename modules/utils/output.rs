@@ -0,0 +1,106 @@
+// Line output that can be captured per thread, so parallel test files don't interleave
+
+use std::cell::RefCell;
+use std::fmt::Arguments;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Lines written while capturing, in the order they were written
+#[derive(Debug, Default)]
+pub struct CapturedOutput {
+    lines: Vec<(Stream, String)>,
+}
+
+impl CapturedOutput {
+    /// Write all captured lines to their streams in one go
+    pub fn print(&self) {
+        let mut stdout = std::io::stdout().lock();
+        let mut stderr = std::io::stderr().lock();
+        for (stream, line) in &self.lines {
+            let _ = match stream {
+                Stream::Stdout => writeln!(stdout, "{line}"),
+                Stream::Stderr => {
+                    let _ = stdout.flush();
+                    writeln!(stderr, "{line}")
+                }
+            };
+        }
+        let _ = stdout.flush();
+    }
+}
+
+thread_local! {
+    static CAPTURE: RefCell<Option<CapturedOutput>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, collecting everything written through this module on the current
+/// thread instead of printing it
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, CapturedOutput) {
+    let previous = CAPTURE.with(|capture| capture.replace(Some(CapturedOutput::default())));
+    let result = f();
+    let captured = CAPTURE.with(|capture| capture.replace(previous));
+    (result, captured.unwrap_or_default())
+}
+
+/// Write a line to `stream`, or to the capture buffer if one is active
+pub fn write_line(stream: Stream, args: Arguments<'_>) {
+    let captured = CAPTURE.with(|capture| {
+        capture.borrow_mut().as_mut().map(|output| {
+            output.lines.push((stream, args.to_string()));
+        })
+    });
+    if captured.is_some() {
+        return;
+    }
+    let _ = match stream {
+        Stream::Stdout => writeln!(std::io::stdout(), "{args}"),
+        Stream::Stderr => writeln!(std::io::stderr(), "{args}"),
+    };
+}
+
+/// Like `println!`, but captured by [`capture`]
+#[macro_export]
+macro_rules! print_line {
+    () => {
+        $crate::output::write_line($crate::output::Stream::Stdout, format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write_line($crate::output::Stream::Stdout, format_args!($($arg)*))
+    };
+}
+
+/// Like `eprintln!`, but captured by [`capture`]
+#[macro_export]
+macro_rules! eprint_line {
+    () => {
+        $crate::output::write_line($crate::output::Stream::Stderr, format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write_line($crate::output::Stream::Stderr, format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_collects_lines() {
+        let ((), output) = capture(|| {
+            print_line!("one {}", 1);
+            eprint_line!("two");
+        });
+        assert_eq!(
+            output.lines,
+            vec![
+                (Stream::Stdout, "one 1".to_string()),
+                (Stream::Stderr, "two".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use futures_util::future::{AbortHandle, AbortRegistration, Abortable};
+use rquickjs::{
+    Class, Ctx, Exception, Function, JsLifetime, Object, Result, Value, class::Trace, prelude::*,
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use utils::{class_of, report_uncaught};
+
+// AbortSignal class
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct AbortSignal<'js> {
+    /// Set once, by the controller or a timeout, and never cleared
+    #[qjs(skip_trace)]
+    aborted: Arc<AtomicBool>,
+    reason: Option<Value<'js>>,
+    listeners: Vec<Function<'js>>,
+    onabort: Option<Function<'js>>,
+    /// Signals from `AbortSignal.any()` that abort along with this one
+    dependents: Vec<Class<'js, AbortSignal<'js>>>,
+    /// When a signal from `AbortSignal.timeout()` aborts
+    #[qjs(skip_trace)]
+    deadline: Option<Instant>,
+    #[qjs(skip_trace)]
+    timer_started: bool,
+    /// Pending operations, such as `fetch()`, cancelled on abort
+    #[qjs(skip_trace)]
+    handles: Vec<AbortHandle>,
+}
+
+#[rquickjs::methods]
+impl<'js> AbortSignal<'js> {
+    /// Signals only come from a controller or the static factories
+    ///
+    /// # Errors
+    /// Always throws a `TypeError`
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>) -> Result<Self> {
+        Err(Exception::throw_type(&ctx, "Illegal constructor"))
+    }
+
+    /// # Errors
+    /// Returns an error if a timeout aborts the signal and that fails
+    #[qjs(get)]
+    pub fn aborted(this: This<Class<'js, Self>>, ctx: Ctx<'js>) -> Result<bool> {
+        check_timeout(&ctx, &this)?;
+        Ok(this.borrow().is_aborted())
+    }
+
+    /// The abort reason, or `undefined` while not aborted
+    ///
+    /// # Errors
+    /// Returns an error if a timeout aborts the signal and that fails
+    #[qjs(get)]
+    pub fn reason(this: This<Class<'js, Self>>, ctx: Ctx<'js>) -> Result<Value<'js>> {
+        check_timeout(&ctx, &this)?;
+        let reason = this.borrow().abort_reason();
+        Ok(reason.unwrap_or_else(|| Value::new_undefined(ctx)))
+    }
+
+    /// # Errors
+    /// Throws the abort reason if the signal has aborted
+    #[qjs(rename = "throwIfAborted")]
+    pub fn throw_if_aborted(this: This<Class<'js, Self>>, ctx: Ctx<'js>) -> Result<()> {
+        check_timeout(&ctx, &this)?;
+        let reason = this.borrow().abort_reason();
+        match reason {
+            Some(reason) => Err(ctx.throw(reason)),
+            None => Ok(()),
+        }
+    }
+
+    #[qjs(get, rename = "onabort")]
+    pub fn get_onabort(&self, ctx: Ctx<'js>) -> Value<'js> {
+        match &self.onabort {
+            Some(handler) => handler.clone().into_value(),
+            None => Value::new_null(ctx),
+        }
+    }
+
+    #[qjs(set, rename = "onabort")]
+    pub fn set_onabort(this: This<Class<'js, Self>>, ctx: Ctx<'js>, handler: Value<'js>) {
+        this.borrow_mut().onabort = handler.into_function();
+        start_timer(&ctx, &this);
+    }
+
+    /// Only `abort` events are ever dispatched, so other types are ignored
+    #[qjs(rename = "addEventListener")]
+    pub fn add_event_listener(
+        this: This<Class<'js, Self>>,
+        ctx: Ctx<'js>,
+        event_type: String,
+        listener: Value<'js>,
+    ) {
+        let Some(listener) = listener.into_function() else {
+            return;
+        };
+        if event_type != "abort" {
+            return;
+        }
+        {
+            let mut signal = this.borrow_mut();
+            if !signal.listeners.contains(&listener) {
+                signal.listeners.push(listener);
+            }
+        }
+        start_timer(&ctx, &this);
+    }
+
+    #[qjs(rename = "removeEventListener")]
+    pub fn remove_event_listener(&mut self, event_type: String, listener: Value<'js>) {
+        if let Some(listener) = listener.into_function()
+            && event_type == "abort"
+        {
+            self.listeners.retain(|other| *other != listener);
+        }
+    }
+
+    /// A signal that is already aborted
+    ///
+    /// # Errors
+    /// Returns an error if the signal can't be created
+    #[qjs(static)]
+    pub fn abort(ctx: Ctx<'js>, reason: Opt<Value<'js>>) -> Result<Class<'js, Self>> {
+        let signal = Class::instance(ctx.clone(), Self::pending(None))?;
+        abort_signal(&ctx, &signal, reason.0)?;
+        Ok(signal)
+    }
+
+    /// A signal that aborts with a `TimeoutError` after `ms` milliseconds
+    ///
+    /// # Errors
+    /// Throws a `RangeError` if `ms` is negative or `NaN`
+    #[qjs(static)]
+    pub fn timeout(ctx: Ctx<'js>, ms: f64) -> Result<Class<'js, Self>> {
+        if ms.is_nan() || ms < 0.0 {
+            return Err(Exception::throw_range(
+                &ctx,
+                &format!("Timeout must be a non-negative number: {ms}"),
+            ));
+        }
+        // None when too far in the future to ever fire
+        let deadline = Duration::try_from_secs_f64(ms / 1000.0)
+            .ok()
+            .and_then(|duration| Instant::now().checked_add(duration));
+        Class::instance(ctx, Self::pending(deadline))
+    }
+
+    /// A signal that aborts as soon as any of `signals` does, with its reason
+    ///
+    /// # Errors
+    /// Throws a `TypeError` if an element isn't an `AbortSignal`
+    #[qjs(static)]
+    pub fn any(ctx: Ctx<'js>, signals: Vec<Value<'js>>) -> Result<Class<'js, Self>> {
+        let mut sources = Vec::with_capacity(signals.len());
+        for value in &signals {
+            let Some(source) = class_of::<Self>(value) else {
+                return Err(Exception::throw_type(
+                    &ctx,
+                    "AbortSignal.any() expects an array of AbortSignals",
+                ));
+            };
+            check_timeout(&ctx, &source)?;
+            let reason = source.borrow().abort_reason();
+            if let Some(reason) = reason {
+                return Self::abort(ctx, Opt(Some(reason)));
+            }
+            sources.push(source);
+        }
+
+        let deadline = sources
+            .iter()
+            .filter_map(|source| source.borrow().deadline)
+            .min();
+        let signal = Class::instance(ctx, Self::pending(deadline))?;
+        for source in &sources {
+            source.borrow_mut().dependents.push(signal.clone());
+        }
+        Ok(signal)
+    }
+}
+
+impl<'js> AbortSignal<'js> {
+    fn pending(deadline: Option<Instant>) -> Self {
+        AbortSignal {
+            aborted: Arc::new(AtomicBool::new(false)),
+            reason: None,
+            listeners: Vec::new(),
+            onabort: None,
+            dependents: Vec::new(),
+            deadline,
+            timer_started: false,
+            handles: Vec::new(),
+        }
+    }
+
+    /// Whether the signal has aborted, not counting a timeout nobody has
+    /// checked yet; see [`check_timeout`]
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::SeqCst)
+    }
+
+    pub fn abort_reason(&self) -> Option<Value<'js>> {
+        self.reason.clone()
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Register a pending operation to cancel on abort. The operation should
+    /// abort the returned handle itself when done, so it can be forgotten.
+    pub fn register(&mut self) -> (AbortHandle, AbortRegistration) {
+        self.handles.retain(|handle| !handle.is_aborted());
+        let (handle, registration) = AbortHandle::new_pair();
+        if self.is_aborted() {
+            handle.abort();
+        } else {
+            self.handles.push(handle.clone());
+        }
+        (handle, registration)
+    }
+}
+
+// AbortController class
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct AbortController<'js> {
+    signal: Class<'js, AbortSignal<'js>>,
+}
+
+#[rquickjs::methods]
+impl<'js> AbortController<'js> {
+    /// # Errors
+    /// Returns an error if the signal can't be created
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>) -> Result<Self> {
+        Ok(AbortController {
+            signal: Class::instance(ctx, AbortSignal::pending(None))?,
+        })
+    }
+
+    #[qjs(get)]
+    pub fn signal(&self) -> Class<'js, AbortSignal<'js>> {
+        self.signal.clone()
+    }
+
+    /// # Errors
+    /// Returns an error if the abort fails; see [`abort_signal`]
+    pub fn abort(&self, ctx: Ctx<'js>, reason: Opt<Value<'js>>) -> Result<()> {
+        abort_signal(&ctx, &self.signal, reason.0)
+    }
+}
+
+/// Abort `signal` with `reason`, or an `AbortError` if it is undefined, and
+/// dispatch its `abort` event synchronously. Does nothing if it has already
+/// aborted. Errors thrown by listeners are reported as uncaught, as Deno does.
+///
+/// # Errors
+/// Returns an error if the event or the default reason can't be created
+pub fn abort_signal<'js>(
+    ctx: &Ctx<'js>,
+    signal: &Class<'js, AbortSignal<'js>>,
+    reason: Option<Value<'js>>,
+) -> Result<()> {
+    let reason = match reason {
+        Some(reason) if !reason.is_undefined() => reason,
+        _ => named_error(ctx, "AbortError", "The signal has been aborted")?,
+    };
+    let (listeners, dependents) = {
+        let mut signal = signal.borrow_mut();
+        if signal.is_aborted() {
+            return Ok(());
+        }
+        signal.aborted.store(true, Ordering::SeqCst);
+        signal.reason = Some(reason.clone());
+        for handle in signal.handles.drain(..) {
+            handle.abort();
+        }
+        let mut listeners: Vec<_> = signal.onabort.iter().cloned().collect();
+        listeners.append(&mut signal.listeners);
+        (listeners, std::mem::take(&mut signal.dependents))
+    };
+
+    let event = Object::new(ctx.clone())?;
+    event.set("type", "abort")?;
+    event.set("target", signal.clone())?;
+    for listener in listeners {
+        if let Err(error) = listener.call::<_, ()>((This(signal.clone()), event.clone())) {
+            report_uncaught(ctx, error)?;
+        }
+    }
+    for dependent in dependents {
+        abort_signal(ctx, &dependent, Some(reason.clone()))?;
+    }
+    Ok(())
+}
+
+/// Abort `signal` with a `TimeoutError` if its timeout has passed. Timeouts
+/// are checked whenever the signal is read, so only signals with listeners
+/// need a timer, and a pending timeout doesn't keep the runtime alive.
+///
+/// # Errors
+/// Returns an error if the abort fails; see [`abort_signal`]
+pub fn check_timeout<'js>(ctx: &Ctx<'js>, signal: &Class<'js, AbortSignal<'js>>) -> Result<()> {
+    let expired = {
+        let signal = signal.borrow();
+        !signal.is_aborted()
+            && signal
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    };
+    if expired {
+        time_out(ctx, signal)?;
+    }
+    Ok(())
+}
+
+/// Abort `signal` with a `TimeoutError`, for operations that waited out its
+/// deadline themselves
+///
+/// # Errors
+/// Returns an error if the abort fails; see [`abort_signal`]
+pub fn time_out<'js>(ctx: &Ctx<'js>, signal: &Class<'js, AbortSignal<'js>>) -> Result<()> {
+    let reason = named_error(ctx, "TimeoutError", "Signal timed out.")?;
+    abort_signal(ctx, signal, Some(reason))
+}
+
+/// Fire the `abort` event of a timeout signal on time, once something
+/// listens. The timer is cancelled if the signal aborts some other way first.
+fn start_timer<'js>(ctx: &Ctx<'js>, signal: &Class<'js, AbortSignal<'js>>) {
+    let (deadline, registration) = {
+        let mut signal = signal.borrow_mut();
+        match signal.deadline {
+            Some(deadline) if !signal.timer_started && !signal.is_aborted() => {
+                signal.timer_started = true;
+                (deadline, signal.register().1)
+            }
+            _ => return,
+        }
+    };
+    let signal = signal.clone();
+    let ctx_clone = ctx.clone();
+    ctx.spawn(async move {
+        let sleep = compio::time::sleep(deadline.saturating_duration_since(Instant::now()));
+        if Abortable::new(sleep, registration).await.is_ok() {
+            // Listener errors have been reported already
+            let _ = check_timeout(&ctx_clone, &signal);
+        }
+    });
+}
+
+/// An `Error` with the given name, standing in for a `DOMException`
+fn named_error<'js>(ctx: &Ctx<'js>, name: &str, message: &str) -> Result<Value<'js>> {
+    let error = Exception::from_message(ctx.clone(), message)?;
+    error.as_object().set("name", name)?;
+    Ok(error.into_value())
+}
@@ -0,0 +1,69 @@
+Deno.test("AbortController fires abort listeners once", () => {
+  const controller = new AbortController();
+  const { signal } = controller;
+  const seen: string[] = [];
+  const listener = () => seen.push("removed");
+  signal.addEventListener("abort", listener);
+  signal.removeEventListener("abort", listener);
+  signal.addEventListener("abort", (event) => {
+    seen.push(`${event.type} ${event.target === signal} ${signal.aborted}`);
+  });
+  signal.onabort = () => seen.push("onabort");
+  if (signal.aborted || signal.reason !== undefined) {
+    throw new Error("should start out pending");
+  }
+
+  controller.abort();
+  controller.abort("ignored");
+  if (seen.join() !== "onabort,abort true true") throw new Error(`${seen}`);
+  if (!(signal.reason instanceof Error) || signal.reason.name !== "AbortError") {
+    throw new Error(`reason: ${signal.reason}`);
+  }
+});
+
+Deno.test("AbortSignal.throwIfAborted throws the reason", () => {
+  const signal = AbortSignal.abort("stop");
+  if (!signal.aborted) throw new Error("should be aborted");
+  try {
+    signal.throwIfAborted();
+    throw new Error("should throw");
+  } catch (error) {
+    if (error !== "stop") throw error;
+  }
+  new AbortController().signal.throwIfAborted();
+});
+
+Deno.test("AbortSignal cannot be constructed directly", () => {
+  try {
+    new AbortSignal();
+    throw new Error("should throw");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+});
+
+Deno.test("AbortSignal.timeout aborts with a TimeoutError", async () => {
+  const signal = AbortSignal.timeout(10);
+  const fired = new Promise((resolve) => signal.onabort = resolve);
+  if (signal.aborted) throw new Error("should not abort right away");
+  await fired;
+  if (signal.reason.name !== "TimeoutError") {
+    throw new Error(`reason: ${signal.reason}`);
+  }
+});
+
+Deno.test("AbortSignal.any follows the first signal to abort", () => {
+  const first = new AbortController();
+  const second = new AbortController();
+  const signal = AbortSignal.any([
+    first.signal,
+    second.signal,
+    AbortSignal.timeout(60_000),
+  ]);
+  second.abort("second");
+  first.abort("first");
+  if (signal.reason !== "second") throw new Error(`${signal.reason}`);
+
+  const already = AbortSignal.any([AbortSignal.abort("done")]);
+  if (already.reason !== "done") throw new Error("already aborted");
+});
@@ -0,0 +1,15 @@
+mod abort;
+
+pub use abort::{AbortController, AbortSignal, abort_signal, check_timeout, time_out};
+
+use rquickjs::{Class, Ctx};
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    // Register AbortSignal and AbortController classes
+    Class::<AbortSignal>::define(&ctx.globals())?;
+    Class::<AbortController>::define(&ctx.globals())?;
+
+    Ok(())
+}
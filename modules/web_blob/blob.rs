@@ -0,0 +1,170 @@
+use rquickjs::{
+    ArrayBuffer, Coerced, Ctx, Function, JsLifetime, Object, Promise, Result, TypedArray, Value,
+    class::Trace, prelude::*,
+};
+use std::sync::Arc;
+
+// Blob class
+#[derive(Clone, Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct Blob {
+    /// Shared with slices of the whole blob and with the object URL store
+    #[qjs(skip_trace)]
+    data: Arc<Vec<u8>>,
+    #[qjs(skip_trace)]
+    content_type: String,
+}
+
+#[rquickjs::methods]
+impl Blob {
+    /// # Errors
+    /// Returns an error if `parts` isn't an array or a part can't be read
+    #[qjs(constructor)]
+    pub fn new(parts: Opt<Value<'_>>, options: Opt<Object<'_>>) -> Result<Self> {
+        let mut data = Vec::new();
+        if let Some(parts) = parts.0.filter(|parts| !parts.is_undefined()) {
+            let Some(parts) = parts.as_array() else {
+                return Err(rquickjs::Error::new_from_js(
+                    parts.type_name(),
+                    "sequence of BlobParts",
+                ));
+            };
+            for part in parts.iter::<Value>() {
+                append_part(&mut data, &part?)?;
+            }
+        }
+        let content_type = match options.0 {
+            Some(options) => options
+                .get::<_, Option<Coerced<String>>>("type")?
+                .map(|content_type| normalize_type(&content_type.0))
+                .unwrap_or_default(),
+            None => String::new(),
+        };
+        Ok(Self::from_bytes(data, content_type))
+    }
+
+    #[qjs(get)]
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    #[qjs(get, rename = "type")]
+    pub fn content_type(&self) -> String {
+        self.content_type.clone()
+    }
+
+    /// # Errors
+    /// Returns an error if the promise can't be created
+    pub fn text<'js>(&self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        let text = String::from_utf8_lossy(&self.data).into_owned();
+        resolved(&ctx, text)
+    }
+
+    /// # Errors
+    /// Returns an error if the buffer or promise can't be created
+    #[qjs(rename = "arrayBuffer")]
+    pub fn array_buffer<'js>(&self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        let buffer = ArrayBuffer::new_copy(ctx.clone(), self.data.as_slice())?;
+        resolved(&ctx, buffer)
+    }
+
+    /// # Errors
+    /// Returns an error if the array or promise can't be created
+    pub fn bytes<'js>(&self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        let bytes = TypedArray::<u8>::new_copy(ctx.clone(), self.data.as_slice())?;
+        resolved(&ctx, bytes)
+    }
+
+    /// The contents as a `ReadableStream` of byte chunks
+    ///
+    /// # Errors
+    /// Returns an error if the stream can't be created
+    pub fn stream<'js>(&self, ctx: Ctx<'js>) -> Result<Value<'js>> {
+        let from_bytes: Function =
+            ctx.eval("globalThis[Symbol.for('mdeno.internal')].streams.fromBytes")?;
+        let bytes = TypedArray::<u8>::new_copy(ctx.clone(), self.data.as_slice())?;
+        from_bytes.call((bytes,))
+    }
+
+    /// Bytes `start..end` as a new blob; negative offsets count from the end
+    #[must_use]
+    pub fn slice(&self, start: Opt<f64>, end: Opt<f64>, content_type: Opt<String>) -> Self {
+        let len = self.data.len();
+        let start = relative_index(start.0.unwrap_or(0.0), len);
+        let end = relative_index(end.0.unwrap_or(len as f64), len).max(start);
+        let content_type = content_type
+            .0
+            .map(|content_type| normalize_type(&content_type))
+            .unwrap_or_default();
+        if start == 0 && end == len {
+            return Self {
+                data: Arc::clone(&self.data),
+                content_type,
+            };
+        }
+        Self::from_bytes(self.data[start..end].to_vec(), content_type)
+    }
+}
+
+impl Blob {
+    pub fn from_bytes(data: Vec<u8>, content_type: String) -> Self {
+        Self {
+            data: Arc::new(data),
+            content_type,
+        }
+    }
+
+    /// The contents of the blob
+    pub fn data(&self) -> Arc<Vec<u8>> {
+        Arc::clone(&self.data)
+    }
+
+    /// The MIME type given when the blob was created, or "" if none was
+    pub fn mime_type(&self) -> &str {
+        &self.content_type
+    }
+}
+
+/// Append a string, `ArrayBuffer`, typed array, `DataView` or `Blob` part
+fn append_part(data: &mut Vec<u8>, part: &Value<'_>) -> Result<()> {
+    if let Some(blob) = utils::class_of::<Blob>(part) {
+        data.extend_from_slice(&blob.borrow().data);
+        return Ok(());
+    }
+    if let Some(bytes) = utils::buffer_source_bytes(part) {
+        data.extend_from_slice(&bytes);
+        return Ok(());
+    }
+    let text: Coerced<String> = part.get()?;
+    data.extend_from_slice(text.0.as_bytes());
+    Ok(())
+}
+
+/// MIME types are lowercased, and dropped if they contain characters
+/// outside printable ASCII
+fn normalize_type(content_type: &str) -> String {
+    if content_type
+        .bytes()
+        .all(|byte| (0x20..=0x7e).contains(&byte))
+    {
+        content_type.to_ascii_lowercase()
+    } else {
+        String::new()
+    }
+}
+
+fn relative_index(index: f64, len: usize) -> usize {
+    let len_f = len as f64;
+    let index = if index < 0.0 {
+        (len_f + index).max(0.0)
+    } else {
+        index.min(len_f)
+    };
+    index as usize
+}
+
+fn resolved<'js, T: rquickjs::IntoJs<'js>>(ctx: &Ctx<'js>, value: T) -> Result<Promise<'js>> {
+    let (promise, resolve, _) = ctx.promise()?;
+    resolve.call::<_, ()>((value,))?;
+    Ok(promise)
+}
@@ -0,0 +1,47 @@
+Deno.test("Blob joins string, buffer and blob parts", async () => {
+  const inner = new Blob(["c"]);
+  const blob = new Blob(["a", new Uint8Array([98]), inner], {
+    type: "Text/Plain",
+  });
+  if (blob.size !== 3) throw new Error(`size: ${blob.size}`);
+  if (blob.type !== "text/plain") throw new Error(`type: ${blob.type}`);
+  const text = await blob.text();
+  if (text !== "abc") throw new Error(`text: ${text}`);
+  const bytes = new Uint8Array(await blob.arrayBuffer());
+  if (bytes.length !== 3 || bytes[0] !== 97) throw new Error("arrayBuffer");
+});
+
+Deno.test("Blob.slice takes relative offsets", async () => {
+  const blob = new Blob(["hello world"]);
+  const tail = await blob.slice(-5).text();
+  if (tail !== "world") throw new Error(`slice(-5): ${tail}`);
+  const middle = await blob.slice(2, 4, "text/plain").text();
+  if (middle !== "ll") throw new Error(`slice(2, 4): ${middle}`);
+});
+
+Deno.test("URL.createObjectURL serves blobs to fetch and import", async () => {
+  const url = URL.createObjectURL(
+    new Blob(["export const answer = 42;"], { type: "text/javascript" }),
+  );
+  if (!url.startsWith("blob:mdeno/")) throw new Error(`url: ${url}`);
+
+  const response = await fetch(url);
+  if (response.headers.get("content-type") !== "text/javascript") {
+    throw new Error("content-type");
+  }
+  if ((await response.text()) !== "export const answer = 42;") {
+    throw new Error("fetched text");
+  }
+
+  const module = await import(url);
+  if (module.answer !== 42) throw new Error(`import: ${module.answer}`);
+
+  URL.revokeObjectURL(url);
+  let rejected = false;
+  try {
+    await fetch(url);
+  } catch (e) {
+    rejected = e instanceof TypeError;
+  }
+  if (!rejected) throw new Error("fetch of a revoked URL should fail");
+});
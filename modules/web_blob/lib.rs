@@ -0,0 +1,72 @@
+mod blob;
+
+pub use blob::Blob;
+use rquickjs::{Class, Ctx};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+
+/// Prefix of the URLs `URL.createObjectURL()` hands out
+const BLOB_URL_PREFIX: &str = "blob:mdeno/";
+
+/// Blobs registered with `URL.createObjectURL()`, keyed by UUID
+pub static BLOB_URLS: LazyLock<Arc<Mutex<HashMap<String, Blob>>>> =
+    LazyLock::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Register `blob` and return a `blob:mdeno/<uuid>` URL for it
+pub fn create_object_url(blob: &Blob) -> String {
+    let uuid = web_crypto::random_uuid();
+    BLOB_URLS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(uuid.clone(), blob.clone());
+    format!("{BLOB_URL_PREFIX}{uuid}")
+}
+
+/// Forget the blob behind `url`; unknown URLs are ignored
+pub fn revoke_object_url(url: &str) {
+    if let Some(uuid) = url.strip_prefix(BLOB_URL_PREFIX) {
+        BLOB_URLS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(uuid);
+    }
+}
+
+/// The blob behind a URL from `create_object_url`, unless it was revoked
+pub fn resolve_object_url(url: &str) -> Option<Blob> {
+    let uuid = url.strip_prefix(BLOB_URL_PREFIX)?;
+    BLOB_URLS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(uuid)
+        .cloned()
+}
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    // Register Blob class
+    Class::<Blob>::define(&ctx.globals())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_url_lifecycle() {
+        let blob = Blob::from_bytes(b"export default 1;".to_vec(), String::new());
+        let url = create_object_url(&blob);
+        assert!(url.starts_with("blob:mdeno/"));
+        assert_eq!(
+            resolve_object_url(&url).map(|blob| blob.data()),
+            Some(blob.data())
+        );
+
+        revoke_object_url(&url);
+        assert!(resolve_object_url(&url).is_none());
+        assert!(resolve_object_url("blob:null/unknown").is_none());
+    }
+}
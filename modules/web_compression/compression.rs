@@ -0,0 +1,193 @@
+// https://compression.spec.whatwg.org/
+use flate2::Compression;
+use flate2::write::{
+    DeflateDecoder, DeflateEncoder, GzDecoder, GzEncoder, ZlibDecoder, ZlibEncoder,
+};
+use rquickjs::{
+    Coerced, Ctx, Exception, Function, JsLifetime, Object, Result, TypedArray, Value, class::Trace,
+    function::Constructor, prelude::This,
+};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use utils::buffer_source_bytes;
+
+/// A streaming encoder or decoder, writing its output to a `Vec<u8>`
+trait Codec {
+    /// Feed `input`, returning the output produced so far
+    fn write(&mut self, input: &[u8]) -> io::Result<Vec<u8>>;
+    /// End the input, returning the rest of the output
+    fn finish(&mut self) -> io::Result<Vec<u8>>;
+}
+
+macro_rules! impl_codec {
+    ($($codec:ident),*) => {
+        $(impl Codec for $codec<Vec<u8>> {
+            fn write(&mut self, input: &[u8]) -> io::Result<Vec<u8>> {
+                self.write_all(input)?;
+                Ok(std::mem::take(self.get_mut()))
+            }
+
+            fn finish(&mut self) -> io::Result<Vec<u8>> {
+                self.try_finish()?;
+                Ok(std::mem::take(self.get_mut()))
+            }
+        })*
+    };
+}
+
+impl_codec!(
+    GzEncoder,
+    GzDecoder,
+    ZlibEncoder,
+    ZlibDecoder,
+    DeflateEncoder,
+    DeflateDecoder
+);
+
+/// The codec for `format`, or `None` if it isn't a supported format.
+/// As in the spec, "deflate" is the zlib format and "deflate-raw" is bare
+/// DEFLATE.
+fn codec(format: &str, compress: bool) -> Option<Box<dyn Codec>> {
+    let level = Compression::default();
+    Some(match (format, compress) {
+        ("gzip", true) => Box::new(GzEncoder::new(Vec::new(), level)),
+        ("gzip", false) => Box::new(GzDecoder::new(Vec::new())),
+        ("deflate", true) => Box::new(ZlibEncoder::new(Vec::new(), level)),
+        ("deflate", false) => Box::new(ZlibDecoder::new(Vec::new())),
+        ("deflate-raw", true) => Box::new(DeflateEncoder::new(Vec::new(), level)),
+        ("deflate-raw", false) => Box::new(DeflateDecoder::new(Vec::new())),
+        _ => return None,
+    })
+}
+
+/// `CompressionStream`: a `TransformStream` from bytes to compressed bytes
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct CompressionStream<'js> {
+    readable: Value<'js>,
+    writable: Value<'js>,
+}
+
+#[rquickjs::methods]
+impl<'js> CompressionStream<'js> {
+    /// # Errors
+    /// Throws a `TypeError` if `format` isn't "gzip", "deflate" or "deflate-raw"
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>, format: Coerced<String>) -> Result<Self> {
+        let Some(codec) = codec(&format, true) else {
+            return Err(unsupported_format(&ctx, &format));
+        };
+        let (readable, writable) = transform_stream(&ctx, codec, "compress")?;
+        Ok(Self { readable, writable })
+    }
+
+    #[qjs(get)]
+    pub fn readable(&self) -> Value<'js> {
+        self.readable.clone()
+    }
+
+    #[qjs(get)]
+    pub fn writable(&self) -> Value<'js> {
+        self.writable.clone()
+    }
+}
+
+/// `DecompressionStream`: a `TransformStream` from compressed bytes to bytes
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct DecompressionStream<'js> {
+    readable: Value<'js>,
+    writable: Value<'js>,
+}
+
+#[rquickjs::methods]
+impl<'js> DecompressionStream<'js> {
+    /// # Errors
+    /// Throws a `TypeError` if `format` isn't "gzip", "deflate" or "deflate-raw"
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>, format: Coerced<String>) -> Result<Self> {
+        let Some(codec) = codec(&format, false) else {
+            return Err(unsupported_format(&ctx, &format));
+        };
+        let (readable, writable) = transform_stream(&ctx, codec, "decompress")?;
+        Ok(Self { readable, writable })
+    }
+
+    #[qjs(get)]
+    pub fn readable(&self) -> Value<'js> {
+        self.readable.clone()
+    }
+
+    #[qjs(get)]
+    pub fn writable(&self) -> Value<'js> {
+        self.writable.clone()
+    }
+}
+
+fn unsupported_format(ctx: &Ctx<'_>, format: &str) -> rquickjs::Error {
+    Exception::throw_type(ctx, &format!("Unsupported compression format: '{format}'"))
+}
+
+/// The readable and writable sides of a `TransformStream` that runs each
+/// chunk through `codec`
+fn transform_stream<'js>(
+    ctx: &Ctx<'js>,
+    codec: Box<dyn Codec>,
+    action: &'static str,
+) -> Result<(Value<'js>, Value<'js>)> {
+    let codec = Rc::new(RefCell::new(codec));
+    let transformer = Object::new(ctx.clone())?;
+
+    let chunk_codec = Rc::clone(&codec);
+    transformer.set(
+        "transform",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'js>, chunk: Value<'js>, controller: Object<'js>| {
+                let Some(input) = buffer_source_bytes(&chunk) else {
+                    return Err(Exception::throw_type(
+                        &ctx,
+                        "Chunk must be an ArrayBuffer or ArrayBufferView",
+                    ));
+                };
+                let output = chunk_codec.borrow_mut().write(&input);
+                enqueue(&ctx, &controller, output, action)
+            },
+        )?,
+    )?;
+    transformer.set(
+        "flush",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'js>, controller: Object<'js>| {
+                let output = codec.borrow_mut().finish();
+                enqueue(&ctx, &controller, output, action)
+            },
+        )?,
+    )?;
+
+    let constructor: Constructor = ctx.globals().get("TransformStream")?;
+    let stream: Object = constructor.construct((transformer,))?;
+    Ok((stream.get("readable")?, stream.get("writable")?))
+}
+
+/// Pass codec output on to the readable side, or throw a `TypeError` for
+/// invalid input
+fn enqueue<'js>(
+    ctx: &Ctx<'js>,
+    controller: &Object<'js>,
+    output: io::Result<Vec<u8>>,
+    action: &str,
+) -> Result<()> {
+    let output = output
+        .map_err(|error| Exception::throw_type(ctx, &format!("Failed to {action}: {error}")))?;
+    if output.is_empty() {
+        return Ok(());
+    }
+    let enqueue: Function = controller.get("enqueue")?;
+    enqueue.call((
+        This(controller.clone()),
+        TypedArray::<u8>::new(ctx.clone(), output)?,
+    ))
+}
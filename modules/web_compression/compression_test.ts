@@ -0,0 +1,68 @@
+async function concat(stream: ReadableStream<Uint8Array>): Promise<Uint8Array> {
+  const chunks: Uint8Array[] = [];
+  for await (const chunk of stream) chunks.push(chunk);
+  const bytes = new Uint8Array(chunks.reduce((sum, c) => sum + c.byteLength, 0));
+  let offset = 0;
+  for (const chunk of chunks) {
+    bytes.set(chunk, offset);
+    offset += chunk.byteLength;
+  }
+  return bytes;
+}
+
+Deno.test("CompressionStream round-trips 1MB through each format", async () => {
+  const input = new Uint8Array(1024 * 1024);
+  for (let i = 0; i < input.length; i++) input[i] = (i * 31) % 251;
+
+  for (const format of ["gzip", "deflate", "deflate-raw"] as const) {
+    const compressed = await concat(
+      new Blob([input]).stream().pipeThrough(new CompressionStream(format)),
+    );
+    if (compressed.byteLength >= input.byteLength) {
+      throw new Error(`${format}: ${compressed.byteLength} bytes`);
+    }
+    const output = await concat(
+      new Blob([compressed]).stream().pipeThrough(new DecompressionStream(format)),
+    );
+    if (output.byteLength !== input.byteLength) {
+      throw new Error(`${format}: got ${output.byteLength} bytes`);
+    }
+    for (let i = 0; i < input.length; i++) {
+      if (output[i] !== input[i]) throw new Error(`${format}: byte ${i} differs`);
+    }
+  }
+});
+
+Deno.test("CompressionStream writes the format's header", async () => {
+  const gzip = await concat(
+    new Blob(["hello"]).stream().pipeThrough(new CompressionStream("gzip")),
+  );
+  if (gzip[0] !== 0x1f || gzip[1] !== 0x8b) throw new Error("gzip magic");
+  const zlib = await concat(
+    new Blob(["hello"]).stream().pipeThrough(new CompressionStream("deflate")),
+  );
+  if (zlib[0] !== 0x78) throw new Error("zlib header");
+});
+
+Deno.test("Compression streams reject unsupported formats", () => {
+  for (const Stream of [CompressionStream, DecompressionStream]) {
+    try {
+      new Stream("brotli" as CompressionFormat);
+      throw new Error("should throw");
+    } catch (error) {
+      if (!(error instanceof TypeError)) throw error;
+    }
+  }
+});
+
+Deno.test("DecompressionStream errors on corrupt input", async () => {
+  const stream = new Blob([new Uint8Array([1, 2, 3, 4, 5, 6, 7, 8, 9, 10])])
+    .stream()
+    .pipeThrough(new DecompressionStream("gzip"));
+  try {
+    await concat(stream);
+    throw new Error("should reject");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+});
@@ -0,0 +1,15 @@
+mod compression;
+
+pub use compression::{CompressionStream, DecompressionStream};
+
+use rquickjs::{Class, Ctx};
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    // Register CompressionStream and DecompressionStream classes
+    Class::<CompressionStream>::define(&ctx.globals())?;
+    Class::<DecompressionStream>::define(&ctx.globals())?;
+
+    Ok(())
+}
@@ -1,103 +1,653 @@
 // @ts-ignore: mdeno internal API
 const __internal = globalThis[Symbol.for("mdeno.internal")];
 
-function formatValue(arg: unknown): string {
-  if (typeof arg === "string") return arg;
-  if (arg === null) return "null";
-  if (arg === undefined) return "undefined";
-  if (typeof arg === "number" || typeof arg === "boolean") {
-    return String(arg);
-  }
-  if (typeof arg === "function") {
-    // Check if it's a class (constructor function)
-    const fnStr = arg.toString();
-    if (fnStr.startsWith("class ")) {
-      const className = arg.name || "anonymous";
-      // Check for parent class
-      const parent = Object.getPrototypeOf(arg);
-      if (parent && parent !== Function.prototype && parent.name) {
-        return `[class ${className} extends ${parent.name}]`;
-      }
-      return `[class ${className}]`;
+// How deep console.log and Deno.inspect look into nested objects and
+// arrays before printing [Object] / [Array]
+const CONSOLE_DEPTH = 2;
+// Longer arrays, typed arrays, maps and sets are cut off with
+// "... N more items"
+const MAX_ARRAY_LENGTH = 100;
+// Longer strings are cut off with "... N more characters"
+const MAX_STRING_LENGTH = 10000;
+// Compact objects longer than this are broken up, one entry per line
+const LINE_WIDTH = 72;
+
+const TYPED_ARRAY_NAMES = new Set([
+  "Uint8Array",
+  "Int8Array",
+  "Uint16Array",
+  "Int16Array",
+  "Uint32Array",
+  "Int32Array",
+  "Float32Array",
+  "Float64Array",
+  "BigInt64Array",
+  "BigUint64Array",
+]);
+
+// Objects can override how they are shown through either of these methods
+const DENO_CUSTOM_INSPECT = Symbol.for("Deno.customInspect");
+const NODE_CUSTOM_INSPECT = Symbol.for("nodejs.util.inspect.custom");
+
+interface InspectOptions {
+  depth?: number;
+  // Color output with ANSI escapes, unless NO_COLOR is set
+  colors?: boolean;
+  // When false, every entry goes on its own line and Uint8Array bytes are
+  // shown in hex
+  compact?: boolean;
+  // Sort object keys alphabetically
+  sorted?: boolean;
+  // Add a comma after the last entry when entries go one per line
+  trailingComma?: boolean;
+  // Show the values of getters instead of [Getter]
+  getters?: boolean;
+  // Show non-enumerable properties too
+  showHidden?: boolean;
+  // Line width before compact entries are broken up
+  breakLength?: number;
+  // Entries shown of arrays, typed arrays, maps and sets
+  iterableLimit?: number;
+  // Older name for iterableLimit
+  maxArrayLength?: number;
+  maxStringLength?: number;
+}
+
+interface ResolvedOptions {
+  depth: number;
+  colors: boolean;
+  compact: boolean;
+  sorted: boolean;
+  trailingComma: boolean;
+  getters: boolean;
+  showHidden: boolean;
+  breakLength: number;
+  iterableLimit: number;
+  maxStringLength: number;
+}
+
+function resolveOptions(options: InspectOptions = {}): ResolvedOptions {
+  return {
+    depth: options.depth ?? CONSOLE_DEPTH,
+    colors: !!options.colors && !__internal.noColor,
+    compact: options.compact ?? true,
+    sorted: options.sorted ?? false,
+    trailingComma: options.trailingComma ?? false,
+    getters: options.getters ?? false,
+    showHidden: options.showHidden ?? false,
+    breakLength: options.breakLength ?? LINE_WIDTH,
+    iterableLimit: options.iterableLimit ?? options.maxArrayLength ??
+      MAX_ARRAY_LENGTH,
+    maxStringLength: options.maxStringLength ?? MAX_STRING_LENGTH,
+  };
+}
+
+// ANSI colors by kind of value, as [open, close] codes
+const STYLES = {
+  number: [33, 39],
+  string: [32, 39],
+  symbol: [32, 39],
+  null: [1, 22],
+  undefined: [90, 39],
+  special: [36, 39],
+  date: [35, 39],
+  regexp: [31, 39],
+} as const;
+
+function style(
+  text: string,
+  kind: keyof typeof STYLES,
+  options: ResolvedOptions,
+): string {
+  if (!options.colors) return text;
+  const [open, close] = STYLES[kind];
+  return `\x1b[${open}m${text}\x1b[${close}m`;
+}
+
+// Length as shown in a terminal, without color escapes
+function visibleLength(text: string): number {
+  // deno-lint-ignore no-control-regex
+  return text.replace(/\x1b\[\d+m/g, "").length;
+}
+
+type TypedArray = ArrayLike<number | bigint> & {
+  [Symbol.toStringTag]: string;
+};
+
+function isTypedArray(value: unknown): value is TypedArray {
+  return typeof value === "object" && value !== null &&
+    TYPED_ARRAY_NAMES.has(
+      (value as Record<symbol, unknown>)[Symbol.toStringTag] as string,
+    );
+}
+
+function tagOf(value: unknown): string {
+  return Object.prototype.toString.call(value);
+}
+
+// Uint8Array(5) [ 72, 101, 108, 108, 111 ]
+function formatTypedArray(
+  value: TypedArray,
+  hex: boolean,
+  options: ResolvedOptions,
+): string {
+  const name = value[Symbol.toStringTag];
+  if (value.length === 0) return `${name}(0) []`;
+  const shown = Math.min(value.length, options.iterableLimit);
+  const items: string[] = [];
+  for (let i = 0; i < shown; i++) {
+    const item = value[i];
+    if (hex) {
+      items.push(`0x${item.toString(16).padStart(2, "0")}`);
+    } else {
+      const text = typeof item === "bigint" ? `${item}n` : String(item);
+      items.push(style(text, "number", options));
     }
-    return `[Function: ${arg.name || "anonymous"}]`;
   }
-  if (typeof arg === "symbol") return arg.toString();
+  const rest = value.length - shown;
+  if (rest > 0) {
+    items.push(`... ${rest} more item${rest === 1 ? "" : "s"}`);
+  }
+  return `${name}(${value.length}) [ ${items.join(", ")} ]`;
+}
 
-  if (Object.prototype.toString.call(arg) === "[object Date]") {
-    return (arg as Date).toISOString();
+// ArrayBuffer { [Uint8Contents]: <48 65>, byteLength: 2 }
+function formatArrayBuffer(value: ArrayBuffer, options: ResolvedOptions): string {
+  const bytes = new Uint8Array(value);
+  const shown = Math.min(bytes.length, options.iterableLimit);
+  const hex = Array.from(
+    bytes.subarray(0, shown),
+    (byte) => byte.toString(16).padStart(2, "0"),
+  );
+  const rest = bytes.length - shown;
+  if (rest > 0) hex.push(`... ${rest} more byte${rest === 1 ? "" : "s"}`);
+  const length = style(String(value.byteLength), "number", options);
+  return `${tagOf(value).slice(8, -1)} { [Uint8Contents]: <${
+    hex.join(" ")
+  }>, byteLength: ${length} }`;
+}
+
+function moreItems(count: number): string {
+  return `... ${count} more item${count === 1 ? "" : "s"}`;
+}
+
+// "abc"... 5 more characters
+function quoteString(value: string, options: ResolvedOptions): string {
+  if (value.length <= options.maxStringLength) {
+    return style(JSON.stringify(value), "string", options);
   }
+  const rest = value.length - options.maxStringLength;
+  const shown = style(
+    JSON.stringify(value.slice(0, options.maxStringLength)),
+    "string",
+    options,
+  );
+  return `${shown}... ${rest} more character${rest === 1 ? "" : "s"}`;
+}
 
-  // Check for Promise
-  if (arg instanceof Promise) {
-    return "Promise { <pending> }";
-  }
-
-  if (Array.isArray(arg)) {
-    if (arg.length >= 2) {
-      const items = arg.map((item) => {
-        if (typeof item === "string") return `  ${JSON.stringify(item)}`;
-        if (item === null) return "  null";
-        if (item === undefined) return "  undefined";
-        return `  ${String(item)}`;
-      }).join(",\n");
-      return `[\n${items}\n]`;
-    } else {
-      const items = arg.map((item) => {
-        if (typeof item === "string") return JSON.stringify(item);
-        if (item === null) return "null";
-        if (item === undefined) return "undefined";
-        return String(item);
-      }).join(", ");
-      return `[ ${items} ]`;
-    }
-  }
-
-  if (typeof arg === "object") {
-    const constructorName = (arg as Record<string, unknown>).constructor?.name;
-    if (constructorName && constructorName !== "Object") {
-      const ownKeys = Object.getOwnPropertyNames(arg);
-      const protoKeys = (arg as Record<string, unknown>).constructor.prototype
-        ? Object.getOwnPropertyNames(
-          (arg as Record<string, unknown>).constructor.prototype,
-        ).filter(
-          (key: string) => key !== "constructor",
-        )
-        : [];
-      const allKeys = [...new Set([...ownKeys, ...protoKeys])];
-
-      const props = allKeys
-        .filter((key) => !key.startsWith("_"))
-        .map((key) => {
-          try {
-            const val = (arg as Record<string, unknown>)[key];
-            if (typeof val === "function") return null;
-            const valStr = typeof val === "string" ? `"${val}"` : String(val);
-            return `  ${key}: ${valStr}`;
-          } catch {
-            return null;
-          }
-        })
-        .filter((p) => p !== null)
-        .join(",\n");
-      return `${constructorName} {\n${props}\n}`;
+function formatFunction(fn: (...args: unknown[]) => unknown): string {
+  // Check if it's a class (constructor function)
+  const fnStr = fn.toString();
+  if (fnStr.startsWith("class ")) {
+    const className = fn.name || "anonymous";
+    // Check for parent class
+    const parent = Object.getPrototypeOf(fn);
+    if (parent && parent !== Function.prototype && parent.name) {
+      return `[class ${className} extends ${parent.name}]`;
+    }
+    return `[class ${className}]`;
+  }
+  return `[Function: ${fn.name || "anonymous"}]`;
+}
+
+// Error: message, followed by the stack trace
+function formatError(error: Error): string {
+  let header: string;
+  try {
+    header = String(error);
+  } catch {
+    header = "Error";
+  }
+  const stack = typeof error.stack === "string" ? error.stack.trimEnd() : "";
+  if (!stack) return header;
+  // QuickJS leaves the message out of the stack; other engines put it first
+  return stack.startsWith(header) ? stack : `${header}\n${stack}`;
+}
+
+// Object keys are quoted unless they're valid identifiers
+function formatKey(key: string | symbol, options: ResolvedOptions): string {
+  if (typeof key === "symbol") {
+    return `[${style(key.toString(), "symbol", options)}]`;
+  }
+  return /^[A-Za-z_$][\w$]*$/.test(key) ? key : quoteString(key, options);
+}
+
+// Join entries on one line when compact and short enough, else one per line
+function wrapEntries(
+  open: string,
+  entries: string[],
+  close: string,
+  options: ResolvedOptions,
+): string {
+  if (entries.length === 0) return `${open}${close}`;
+  const line = `${open} ${entries.join(", ")} ${close}`;
+  if (
+    options.compact && visibleLength(line) <= options.breakLength &&
+    !entries.some((entry) => entry.includes("\n"))
+  ) {
+    return line;
+  }
+  const indented = entries.map((entry) => `  ${entry.replaceAll("\n", "\n  ")}`);
+  const trailing = options.trailingComma ? "," : "";
+  return `${open}\n${indented.join(",\n")}${trailing}\n${close}`;
+}
+
+// Own keys of a plain object, symbols last. Non-enumerable ones are only
+// included with showHidden.
+function objectKeys(value: object, options: ResolvedOptions): (string | symbol)[] {
+  const keys = options.showHidden
+    ? Object.getOwnPropertyNames(value)
+    : Object.keys(value);
+  if (options.sorted) keys.sort();
+  const symbols = Object.getOwnPropertySymbols(value).filter((symbol) =>
+    options.showHidden ||
+    Object.prototype.propertyIsEnumerable.call(value, symbol)
+  );
+  return [...keys, ...symbols];
+}
+
+// Data properties of a class instance, including prototype getters, but not
+// methods or "_private" fields
+function instanceKeys(value: object, sorted: boolean): string[] {
+  const prototype = (value as Record<string, unknown>).constructor?.prototype;
+  const ownKeys = Object.getOwnPropertyNames(value);
+  const protoKeys = prototype
+    ? Object.getOwnPropertyNames(prototype).filter((key) =>
+      key !== "constructor"
+    )
+    : [];
+  const keys = [...new Set([...ownKeys, ...protoKeys])].filter((key) => {
+    if (key.startsWith("_")) return false;
+    try {
+      return typeof (value as Record<string, unknown>)[key] !== "function";
+    } catch {
+      return false;
+    }
+  });
+  return sorted ? keys.sort() : keys;
+}
+
+// The value of a plain object's property, or [Getter] / [Setter] for
+// accessors unless getters are shown
+function formatProperty(
+  value: object,
+  key: string | symbol,
+  options: ResolvedOptions,
+  level: number,
+  seen: Set<object>,
+): string {
+  const descriptor = Object.getOwnPropertyDescriptor(value, key);
+  if (descriptor && (descriptor.get || descriptor.set)) {
+    const kind = descriptor.get
+      ? descriptor.set ? "Getter/Setter" : "Getter"
+      : "Setter";
+    if (!options.getters || !descriptor.get) {
+      return style(`[${kind}]`, "special", options);
+    }
+    let shown: string;
+    try {
+      shown = formatNested(descriptor.get.call(value), options, level, seen);
+    } catch (error) {
+      shown = `<Inspection threw (${(error as Error)?.message ?? error})>`;
+    }
+    return style(`[${kind}: `, "special", options) + shown +
+      style("]", "special", options);
+  }
+  return formatNested(
+    (value as Record<string | symbol, unknown>)[key],
+    options,
+    level,
+    seen,
+  );
+}
+
+// The result of an object's custom inspect method, or undefined if it has
+// none
+function customInspect(
+  value: object,
+  options: ResolvedOptions,
+  level: number,
+): string | undefined {
+  const record = value as Record<symbol, unknown>;
+  const inspectNested = (nested: unknown, overrides?: InspectOptions) =>
+    inspect(nested, { ...options, ...overrides });
+  let result: unknown;
+  if (typeof record[DENO_CUSTOM_INSPECT] === "function") {
+    result = (record[DENO_CUSTOM_INSPECT] as (...args: unknown[]) => unknown)
+      .call(value, inspectNested, options);
+  } else if (typeof record[NODE_CUSTOM_INSPECT] === "function") {
+    // Node passes the depth left, the options and its inspect function
+    result = (record[NODE_CUSTOM_INSPECT] as (...args: unknown[]) => unknown)
+      .call(value, options.depth - level, options, inspectNested);
+  } else {
+    return undefined;
+  }
+  return typeof result === "string" ? result : inspectNested(result);
+}
+
+// Format a value nested `level` deep inside the value being inspected
+function formatNested(
+  value: unknown,
+  options: ResolvedOptions,
+  level: number,
+  seen: Set<object>,
+): string {
+  if (typeof value === "string") return quoteString(value, options);
+  if (typeof value === "number") {
+    return style(Object.is(value, -0) ? "-0" : String(value), "number", options);
+  }
+  if (typeof value === "bigint") return style(`${value}n`, "number", options);
+  if (typeof value === "boolean") return style(String(value), "number", options);
+  if (typeof value === "symbol") return style(value.toString(), "symbol", options);
+  if (value === undefined) return style("undefined", "undefined", options);
+  if (value === null) return style("null", "null", options);
+  if (typeof value === "function") {
+    return style(
+      formatFunction(value as (...args: unknown[]) => unknown),
+      "special",
+      options,
+    );
+  }
+  if (typeof value !== "object") return String(value);
+
+  if (seen.has(value)) return style("[Circular]", "special", options);
+
+  if (level <= options.depth) {
+    seen.add(value);
+    try {
+      const custom = customInspect(value, options, level);
+      if (custom !== undefined) return custom;
+    } finally {
+      seen.delete(value);
     }
   }
 
+  const tag = tagOf(value);
+  if (tag === "[object Date]") {
+    const time = (value as Date).getTime();
+    const text = Number.isNaN(time) ? "Invalid Date" : (value as Date).toISOString();
+    return style(text, "date", options);
+  }
+  if (tag === "[object RegExp]") {
+    return style(String(value), "regexp", options);
+  }
+  if (value instanceof Error) return formatError(value);
+  if (tag === "[object ArrayBuffer]" || tag === "[object SharedArrayBuffer]") {
+    return formatArrayBuffer(value as ArrayBuffer, options);
+  }
+
+  if (isTypedArray(value)) {
+    const hex = options.compact === false &&
+      value[Symbol.toStringTag] === "Uint8Array";
+    return formatTypedArray(value, hex, options);
+  }
+
+  // Check for Promise
+  if (value instanceof Promise) {
+    return `Promise { ${style("<pending>", "special", options)} }`;
+  }
+
+  const prototype = Object.getPrototypeOf(value);
+  const constructorName = (value as Record<string, unknown>).constructor
+    ?.name;
+  const isArray = Array.isArray(value);
+  const isMap = value instanceof Map;
+  const isSet = value instanceof Set;
+  if (level > options.depth) {
+    if (isArray) return style("[Array]", "special", options);
+    return style(`[${constructorName || "Object"}]`, "special", options);
+  }
+
+  seen.add(value);
   try {
-    return JSON.stringify(arg, null, 2);
-  } catch (_e) {
-    return String(arg);
+    if (isArray) {
+      const shown = Math.min(value.length, options.iterableLimit);
+      const items: string[] = [];
+      for (let i = 0; i < shown; i++) {
+        items.push(formatNested(value[i], options, level + 1, seen));
+      }
+      if (value.length > shown) items.push(moreItems(value.length - shown));
+      return wrapEntries("[", items, "]", options);
+    }
+
+    // Map(1) { "a" => 1 } and Set(2) { 1, 2 }
+    if (isMap || isSet) {
+      const items: string[] = [];
+      for (const entry of value.entries()) {
+        if (items.length >= options.iterableLimit) break;
+        const [key, item] = entry as [unknown, unknown];
+        items.push(
+          isMap
+            ? `${formatNested(key, options, level + 1, seen)} => ${
+              formatNested(item, options, level + 1, seen)
+            }`
+            : formatNested(item, options, level + 1, seen),
+        );
+      }
+      if (value.size > items.length) items.push(moreItems(value.size - items.length));
+      const name = constructorName || (isMap ? "Map" : "Set");
+      return wrapEntries(`${name}(${value.size}) {`, items, "}", options);
+    }
+
+    if (
+      prototype !== null && constructorName && constructorName !== "Object"
+    ) {
+      const entries = instanceKeys(value, options.sorted).map((key) =>
+        `${formatKey(key, options)}: ${
+          formatNested(
+            (value as Record<string, unknown>)[key],
+            options,
+            level + 1,
+            seen,
+          )
+        }`
+      );
+      return wrapEntries(`${constructorName} {`, entries, "}", options);
+    }
+
+    const entries = objectKeys(value, options).map((key) =>
+      `${formatKey(key, options)}: ${
+        formatProperty(value, key, options, level + 1, seen)
+      }`
+    );
+    const open = prototype === null ? "[Object: null prototype] {" : "{";
+    return wrapEntries(open, entries, "}", options);
+  } finally {
+    seen.delete(value);
+  }
+}
+
+// console.log formatting: top-level strings are printed as is
+function formatValue(arg: unknown, options: InspectOptions = {}): string {
+  if (typeof arg === "string") return arg;
+  return formatNested(arg, resolveOptions(options), 0, new Set());
+}
+
+// Like console.log formatting, but top-level strings are quoted
+function inspect(value: unknown, options?: InspectOptions): string {
+  const resolved = resolveOptions(options);
+  return typeof value === "string"
+    ? quoteString(value, resolved)
+    : formatNested(value, resolved, 0, new Set());
+}
+
+__internal.inspect = inspect;
+
+// Labels of console.time timers and console.count counters
+const timers = new Map<string, number>();
+const counters = new Map<string, number>();
+// Current console.group nesting, as the indentation it adds
+let groupIndent = "";
+
+function print(args: unknown[], stderr = false): void {
+  let formatted = args.map((arg) => formatValue(arg)).join(" ");
+  if (groupIndent) {
+    formatted = groupIndent + formatted.replaceAll("\n", `\n${groupIndent}`);
+  }
+  __internal.print(formatted, stderr);
+}
+
+function now(): number {
+  return performance.now();
+}
+
+// ┌───────┬─────┐
+// │ (idx) │ a   │
+// ├───────┼─────┤
+// │     0 │ "x" │
+// └───────┴─────┘
+function renderTable(header: string[], rows: string[][]): string {
+  const widths = header.map((title, i) =>
+    Math.max(title.length, ...rows.map((row) => row[i].length))
+  );
+  const line = (left: string, middle: string, right: string) =>
+    left + widths.map((width) => "─".repeat(width + 2)).join(middle) + right;
+  const cells = (row: string[]) =>
+    "│" +
+    row.map((cell, i) =>
+      // The index column is right-aligned, like numbers
+      ` ${i === 0 ? cell.padStart(widths[i]) : cell.padEnd(widths[i])} `
+    ).join("│") +
+    "│";
+  return [
+    line("┌", "┬", "┐"),
+    cells(header),
+    line("├", "┼", "┤"),
+    ...rows.map(cells),
+    line("└", "┴", "┘"),
+  ].join("\n");
+}
+
+function table(data: unknown, properties?: string[]): void {
+  if (typeof data !== "object" || data === null) {
+    print([data]);
+    return;
+  }
+  const entries: [string, unknown][] = data instanceof Map
+    ? [...data.entries()].map(([key, value]) => [formatValue(key), value])
+    : data instanceof Set
+    ? [...data.values()].map((value, i) => [String(i), value])
+    : Object.entries(data);
+
+  // Columns in the order they first appear; primitives go under "Values"
+  const columns: string[] = properties ? [...properties] : [];
+  let hasValues = false;
+  for (const [, value] of entries) {
+    if (typeof value === "object" && value !== null) {
+      if (properties) continue;
+      for (const key of Object.keys(value)) {
+        if (!columns.includes(key)) columns.push(key);
+      }
+    } else {
+      hasValues = true;
+    }
   }
+
+  const rows = entries.map(([index, value]) => {
+    const row = [index];
+    const isObject = typeof value === "object" && value !== null;
+    for (const column of columns) {
+      const has = isObject && Object.hasOwn(value, column);
+      row.push(has ? inspect((value as Record<string, unknown>)[column]) : "");
+    }
+    if (hasValues) row.push(isObject ? "" : inspect(value));
+    return row;
+  });
+  const header = ["(idx)", ...columns, ...(hasValues ? ["Values"] : [])];
+  print([renderTable(header, rows)]);
 }
 
 globalThis.console = {
   log(...args: unknown[]) {
-    const formatted = args.map(formatValue).join(" ");
-    __internal.print(formatted);
+    print(args);
+  },
+  info(...args: unknown[]) {
+    print(args);
+  },
+  debug(...args: unknown[]) {
+    print(args);
+  },
+  warn(...args: unknown[]) {
+    print(args, true);
   },
   error(...args: unknown[]) {
-    const formatted = args.map(formatValue).join(" ");
-    __internal.print(formatted);
+    print(args, true);
+  },
+  table,
+
+  assert(condition?: unknown, ...args: unknown[]) {
+    if (condition) return;
+    if (args.length === 0) {
+      print(["Assertion failed"], true);
+    } else if (typeof args[0] === "string") {
+      print([`Assertion failed: ${args[0]}`, ...args.slice(1)], true);
+    } else {
+      print(["Assertion failed:", ...args], true);
+    }
+  },
+
+  count(label = "default") {
+    label = String(label);
+    const count = (counters.get(label) ?? 0) + 1;
+    counters.set(label, count);
+    print([`${label}: ${count}`]);
+  },
+  countReset(label = "default") {
+    label = String(label);
+    if (counters.has(label)) {
+      counters.set(label, 0);
+    } else {
+      print([`Count for '${label}' does not exist`], true);
+    }
+  },
+
+  time(label = "default") {
+    label = String(label);
+    if (timers.has(label)) {
+      print([`Timer '${label}' already exists`], true);
+      return;
+    }
+    timers.set(label, now());
+  },
+  timeLog(label = "default", ...args: unknown[]) {
+    label = String(label);
+    const start = timers.get(label);
+    if (start === undefined) {
+      print([`Timer '${label}' does not exist`], true);
+      return;
+    }
+    print([`${label}: ${Math.round(now() - start)}ms`, ...args]);
+  },
+  timeEnd(label = "default") {
+    label = String(label);
+    const start = timers.get(label);
+    if (start === undefined) {
+      print([`Timer '${label}' does not exist`], true);
+      return;
+    }
+    timers.delete(label);
+    print([`${label}: ${Math.round(now() - start)}ms`]);
+  },
+
+  group(...label: unknown[]) {
+    if (label.length > 0) print(label);
+    groupIndent += "  ";
+  },
+  groupCollapsed(...label: unknown[]) {
+    if (label.length > 0) print(label);
+    groupIndent += "  ";
+  },
+  groupEnd() {
+    groupIndent = groupIndent.slice(2);
   },
 } as Console;
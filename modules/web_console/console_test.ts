@@ -0,0 +1,236 @@
+Deno.test("Deno.inspect formats typed arrays", () => {
+  const bytes = new Uint8Array([0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+  const cases: [unknown, string][] = [
+    [bytes, "Uint8Array(5) [ 72, 101, 108, 108, 111 ]"],
+    [new Int16Array([-1, 2]), "Int16Array(2) [ -1, 2 ]"],
+    [new Float64Array([1.5]), "Float64Array(1) [ 1.5 ]"],
+    [new BigInt64Array([3n]), "BigInt64Array(1) [ 3n ]"],
+    [new Uint32Array(0), "Uint32Array(0) []"],
+  ];
+  for (const [value, expected] of cases) {
+    const actual = Deno.inspect(value);
+    if (actual !== expected) {
+      throw new Error(`Expected ${expected}, got ${actual}`);
+    }
+  }
+});
+
+Deno.test("Deno.inspect truncates long typed arrays", () => {
+  const actual = Deno.inspect(new Uint8Array(102));
+  if (!actual.startsWith("Uint8Array(102) [ 0, 0,")) {
+    throw new Error(`Unexpected prefix: ${actual}`);
+  }
+  if (!actual.endsWith(", 0, ... 2 more items ]")) {
+    throw new Error(`Unexpected suffix: ${actual}`);
+  }
+});
+
+Deno.test("Deno.inspect shows Uint8Array bytes in hex when not compact", () => {
+  const bytes = new Uint8Array([0x48, 0x0a, 0xff]);
+  const actual = Deno.inspect(bytes, { compact: false });
+  if (actual !== "Uint8Array(3) [ 0x48, 0x0a, 0xff ]") {
+    throw new Error(`Unexpected output: ${actual}`);
+  }
+});
+
+function expectInspect(actual: string, expected: string): void {
+  if (actual !== expected) {
+    throw new Error(`Expected ${JSON.stringify(expected)}, got ${JSON.stringify(actual)}`);
+  }
+}
+
+Deno.test("Deno.inspect formats objects and arrays", () => {
+  expectInspect(Deno.inspect({ a: 1, "b-c": "x" }), '{ a: 1, "b-c": "x" }');
+  expectInspect(Deno.inspect([1, "two", null]), '[ 1, "two", null ]');
+  expectInspect(Deno.inspect({}), "{}");
+  const cyclic: Record<string, unknown> = { name: "loop" };
+  cyclic.self = cyclic;
+  expectInspect(Deno.inspect(cyclic), '{ name: "loop", self: [Circular] }');
+});
+
+Deno.test("Deno.inspect stops at the depth limit", () => {
+  const nested = { a: { b: { c: { d: 1 } } }, list: [[[[1]]]] };
+  expectInspect(
+    Deno.inspect(nested),
+    "{ a: { b: { c: [Object] } }, list: [ [ [Array] ] ] }",
+  );
+  expectInspect(Deno.inspect(nested, { depth: 0 }), "{ a: [Object], list: [Array] }");
+});
+
+Deno.test("Deno.inspect puts each entry on its own line when not compact", () => {
+  expectInspect(
+    Deno.inspect({ a: 1, b: [2] }, { compact: false }),
+    "{\n  a: 1,\n  b: [\n    2\n  ]\n}",
+  );
+});
+
+Deno.test("Deno.inspect sorts keys", () => {
+  expectInspect(Deno.inspect({ b: 1, a: 2 }, { sorted: true }), "{ a: 2, b: 1 }");
+});
+
+Deno.test("Deno.inspect truncates long arrays and strings", () => {
+  expectInspect(
+    Deno.inspect([1, 2, 3, 4], { maxArrayLength: 2 }),
+    "[ 1, 2, ... 2 more items ]",
+  );
+  expectInspect(
+    Deno.inspect("abcdef", { maxStringLength: 2 }),
+    '"ab"... 4 more characters',
+  );
+});
+
+Deno.test("Deno.inspect formats maps and sets", () => {
+  expectInspect(
+    Deno.inspect(new Map<unknown, unknown>([["a", 1], [2, { b: true }]])),
+    'Map(2) { "a" => 1, 2 => { b: true } }',
+  );
+  expectInspect(Deno.inspect(new Set([1, "x"])), 'Set(2) { 1, "x" }');
+  expectInspect(Deno.inspect(new Map()), "Map(0) {}");
+  expectInspect(
+    Deno.inspect(new Set([1, 2, 3]), { iterableLimit: 1 }),
+    "Set(3) { 1, ... 2 more items }",
+  );
+  expectInspect(Deno.inspect({ a: { b: new Map() } }, { depth: 0 }), "{ a: [Object] }");
+});
+
+Deno.test("Deno.inspect formats regexps, errors and array buffers", () => {
+  expectInspect(Deno.inspect(/a+b/gi), "/a+b/gi");
+  const error = new TypeError("bad value");
+  const shown = Deno.inspect(error);
+  if (!shown.startsWith("TypeError: bad value")) {
+    throw new Error(`Expected the error message first, got ${shown}`);
+  }
+  expectInspect(
+    Deno.inspect(new Uint8Array([0, 1, 255]).buffer),
+    "ArrayBuffer { [Uint8Contents]: <00 01 ff>, byteLength: 3 }",
+  );
+});
+
+Deno.test("Deno.inspect marks null-prototype objects", () => {
+  const value = Object.create(null);
+  value.a = 1;
+  expectInspect(Deno.inspect(value), "[Object: null prototype] { a: 1 }");
+});
+
+Deno.test("Deno.inspect calls custom inspect methods", () => {
+  const deno = {
+    [Symbol.for("Deno.customInspect")]() {
+      return "custom!";
+    },
+  };
+  expectInspect(Deno.inspect({ deno }), "{ deno: custom! }");
+  const node = {
+    [Symbol.for("nodejs.util.inspect.custom")](depth: number) {
+      return `depth ${depth}`;
+    },
+  };
+  expectInspect(Deno.inspect(node, { depth: 3 }), "depth 3");
+});
+
+Deno.test("Deno.inspect shows getters, hidden keys and trailing commas", () => {
+  const value = {
+    get a() {
+      return 1;
+    },
+    set b(_: number) {},
+  };
+  expectInspect(Deno.inspect(value), "{ a: [Getter], b: [Setter] }");
+  expectInspect(Deno.inspect(value, { getters: true }), "{ a: [Getter: 1], b: [Setter] }");
+  const hidden = {};
+  Object.defineProperty(hidden, "secret", { value: 1, enumerable: false });
+  expectInspect(Deno.inspect(hidden), "{}");
+  expectInspect(Deno.inspect(hidden, { showHidden: true }), "{ secret: 1 }");
+  expectInspect(
+    Deno.inspect({ a: 1 }, { compact: false, trailingComma: true }),
+    "{\n  a: 1,\n}",
+  );
+  expectInspect(
+    Deno.inspect({ long: "x".repeat(10) }, { breakLength: 10 }),
+    '{\n  long: "xxxxxxxxxx"\n}',
+  );
+});
+
+Deno.test("Deno.inspect colors values only when asked", () => {
+  expectInspect(Deno.inspect(1), "1");
+  const colored = Deno.inspect({ a: 1, b: "x" }, { colors: true });
+  const expected = Deno.noColor
+    ? '{ a: 1, b: "x" }'
+    : '{ a: \x1b[33m1\x1b[39m, b: \x1b[32m"x"\x1b[39m }';
+  expectInspect(colored, expected);
+});
+
+// Run `fn` with console output captured instead of printed
+function captureConsole(fn: () => void): { stdout: string; stderr: string } {
+  // @ts-ignore: mdeno internal API
+  const internal = globalThis[Symbol.for("mdeno.internal")];
+  const print = internal.print;
+  const captured = { stdout: "", stderr: "" };
+  internal.print = (msg: string, stderr?: boolean) => {
+    captured[stderr ? "stderr" : "stdout"] += `${msg}\n`;
+  };
+  try {
+    fn();
+  } finally {
+    internal.print = print;
+  }
+  return captured;
+}
+
+Deno.test("console.table draws a box around rows", () => {
+  const { stdout } = captureConsole(() => {
+    console.table([{ a: 1, b: "Y" }, { a: "Z", b: 2 }]);
+    console.table([1, 2], []);
+  });
+  expectInspect(
+    stdout,
+    "┌───────┬─────┬─────┐\n" +
+      "│ (idx) │ a   │ b   │\n" +
+      "├───────┼─────┼─────┤\n" +
+      '│     0 │ 1   │ "Y" │\n' +
+      '│     1 │ "Z" │ 2   │\n' +
+      "└───────┴─────┴─────┘\n" +
+      "┌───────┬────────┐\n" +
+      "│ (idx) │ Values │\n" +
+      "├───────┼────────┤\n" +
+      "│     0 │ 1      │\n" +
+      "│     1 │ 2      │\n" +
+      "└───────┴────────┘\n",
+  );
+});
+
+Deno.test("console.group indents output", () => {
+  const { stdout, stderr } = captureConsole(() => {
+    console.group("outer");
+    console.log("a\nb");
+    console.group();
+    console.error("inner");
+    console.groupEnd();
+    console.groupEnd();
+    console.log("done");
+  });
+  expectInspect(stdout, "outer\n  a\n  b\ndone\n");
+  expectInspect(stderr, "    inner\n");
+});
+
+Deno.test("console.assert, console.count and console.time", () => {
+  const { stdout, stderr } = captureConsole(() => {
+    console.assert(true, "hidden");
+    console.assert(false, "shown", 1);
+    console.assert(false);
+    console.count();
+    console.count();
+    console.count("other");
+    console.countReset();
+    console.count();
+    console.time("t");
+    console.timeEnd("t");
+    console.timeEnd("t");
+  });
+  const lines = stdout.trimEnd().split("\n");
+  expectInspect(lines.slice(0, 4).join(), "default: 1,default: 2,other: 1,default: 1");
+  if (!/^t: \d+ms$/.test(lines[4])) throw new Error(`timer: ${lines[4]}`);
+  expectInspect(
+    stderr,
+    "Assertion failed: shown 1\nAssertion failed\nTimer 't' does not exist\n",
+  );
+});
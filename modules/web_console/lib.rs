@@ -1,3 +1,4 @@
+use rquickjs::prelude::Opt;
 use rquickjs::{Ctx, Module, Result};
 use utils::add_internal_function;
 use utils_macros::include_ts;
@@ -5,10 +6,12 @@ use utils_macros::include_ts;
 /// # Errors
 /// Returns an error if module initialization fails
 pub fn init(ctx: &Ctx<'_>) -> Result<()> {
-    add_internal_function!(ctx, "print", |msg: String| {
-        #[allow(clippy::print_stdout)] // Intentional: console.log implementation
-        {
-            println!("{msg}");
+    // print(msg: string, stderr?: boolean): void
+    add_internal_function!(ctx, "print", |msg: String, stderr: Opt<bool>| {
+        if stderr.0.unwrap_or(false) {
+            utils::eprint_line!("{msg}");
+        } else {
+            utils::print_line!("{msg}");
         }
     });
 
@@ -0,0 +1,209 @@
+// https://w3c.github.io/webcrypto/#cryptokey-interface
+use rquickjs::{Array, Ctx, JsLifetime, Object, Result, class::Trace};
+use std::sync::Arc;
+
+/// Hash functions for `digest()`, HMAC and ECDSA
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Hash {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Hash {
+    /// The hash for a normalized (upper-case) algorithm name
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "SHA-1" => Some(Self::Sha1),
+            "SHA-256" => Some(Self::Sha256),
+            "SHA-384" => Some(Self::Sha384),
+            "SHA-512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha384 => "SHA-384",
+            Self::Sha512 => "SHA-512",
+        }
+    }
+
+    /// Block size in bits, the default length of HMAC keys
+    pub(crate) fn block_bits(self) -> u32 {
+        match self {
+            Self::Sha1 | Self::Sha256 => 512,
+            Self::Sha384 | Self::Sha512 => 1024,
+        }
+    }
+}
+
+/// The algorithm a key is for, as reported by `key.algorithm`
+#[derive(Clone, Copy)]
+pub(crate) enum KeyAlgorithm {
+    /// `length` is in bits
+    Hmac {
+        hash: Hash,
+        length: u32,
+    },
+    AesGcm {
+        length: u32,
+    },
+    AesCbc {
+        length: u32,
+    },
+    /// Always on the P-256 curve
+    Ecdsa,
+}
+
+impl KeyAlgorithm {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Hmac { .. } => "HMAC",
+            Self::AesGcm { .. } => "AES-GCM",
+            Self::AesCbc { .. } => "AES-CBC",
+            Self::Ecdsa => "ECDSA",
+        }
+    }
+
+    fn to_object<'js>(self, ctx: &Ctx<'js>) -> Result<Object<'js>> {
+        let object = Object::new(ctx.clone())?;
+        object.set("name", self.name())?;
+        match self {
+            Self::Hmac { hash, length } => {
+                let hash_object = Object::new(ctx.clone())?;
+                hash_object.set("name", hash.name())?;
+                object.set("hash", hash_object)?;
+                object.set("length", length)?;
+            }
+            Self::AesGcm { length } | Self::AesCbc { length } => object.set("length", length)?,
+            Self::Ecdsa => object.set("namedCurve", "P-256")?,
+        }
+        Ok(object)
+    }
+}
+
+/// Key material
+pub(crate) enum KeyData {
+    /// HMAC and AES keys
+    Secret(Vec<u8>),
+    /// An ECDSA private key as PKCS #8, with its public key
+    EcPrivate { pkcs8: Vec<u8>, public: Vec<u8> },
+    /// An ECDSA public key as an uncompressed point
+    EcPublic(Vec<u8>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyUsage {
+    Encrypt,
+    Decrypt,
+    Sign,
+    Verify,
+    DeriveKey,
+    DeriveBits,
+    WrapKey,
+    UnwrapKey,
+}
+
+impl KeyUsage {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "encrypt" => Some(Self::Encrypt),
+            "decrypt" => Some(Self::Decrypt),
+            "sign" => Some(Self::Sign),
+            "verify" => Some(Self::Verify),
+            "deriveKey" => Some(Self::DeriveKey),
+            "deriveBits" => Some(Self::DeriveBits),
+            "wrapKey" => Some(Self::WrapKey),
+            "unwrapKey" => Some(Self::UnwrapKey),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Encrypt => "encrypt",
+            Self::Decrypt => "decrypt",
+            Self::Sign => "sign",
+            Self::Verify => "verify",
+            Self::DeriveKey => "deriveKey",
+            Self::DeriveBits => "deriveBits",
+            Self::WrapKey => "wrapKey",
+            Self::UnwrapKey => "unwrapKey",
+        }
+    }
+}
+
+/// What a key is, shared with operations running on compio's thread pool
+pub(crate) struct KeyInner {
+    pub(crate) algorithm: KeyAlgorithm,
+    pub(crate) data: KeyData,
+}
+
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct CryptoKey<'js> {
+    #[qjs(skip_trace)]
+    pub(crate) inner: Arc<KeyInner>,
+    #[qjs(skip_trace)]
+    extractable: bool,
+    #[qjs(skip_trace)]
+    pub(crate) usages: Vec<KeyUsage>,
+    /// `key.algorithm` and `key.usages`, created once so repeated reads
+    /// return the same objects
+    algorithm_object: Object<'js>,
+    usages_array: Array<'js>,
+}
+
+impl<'js> CryptoKey<'js> {
+    pub(crate) fn new(
+        ctx: &Ctx<'js>,
+        algorithm: KeyAlgorithm,
+        data: KeyData,
+        extractable: bool,
+        usages: Vec<KeyUsage>,
+    ) -> Result<Self> {
+        let usages_array = Array::new(ctx.clone())?;
+        for (index, usage) in usages.iter().enumerate() {
+            usages_array.set(index, usage.name())?;
+        }
+        Ok(Self {
+            inner: Arc::new(KeyInner { algorithm, data }),
+            extractable,
+            usages,
+            algorithm_object: algorithm.to_object(ctx)?,
+            usages_array,
+        })
+    }
+}
+
+#[rquickjs::methods]
+impl<'js> CryptoKey<'js> {
+    /// "secret", "private" or "public"
+    #[qjs(get, rename = "type")]
+    pub fn key_type(&self) -> &'static str {
+        match self.inner.data {
+            KeyData::Secret(_) => "secret",
+            KeyData::EcPrivate { .. } => "private",
+            KeyData::EcPublic(_) => "public",
+        }
+    }
+
+    #[qjs(get)]
+    pub fn extractable(&self) -> bool {
+        self.extractable
+    }
+
+    #[qjs(get)]
+    pub fn algorithm(&self) -> Object<'js> {
+        self.algorithm_object.clone()
+    }
+
+    #[qjs(get)]
+    pub fn usages(&self) -> Array<'js> {
+        self.usages_array.clone()
+    }
+}
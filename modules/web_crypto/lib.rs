@@ -1,29 +1,156 @@
+mod crypto_key;
+mod random_int;
 mod random_uuid;
+mod seeded_rng;
+mod subtle;
 
+pub use crypto_key::CryptoKey;
+
+pub use random_int::random_int;
 pub use random_uuid::random_uuid;
-use rquickjs::{Ctx, JsLifetime, Result, class::Trace};
+use rquickjs::function::{Constructor, Opt, This};
+use rquickjs::{
+    Class, Ctx, Exception, Function, JsLifetime, Object, Result, TypedArray, class::Trace,
+};
+pub use seeded_rng::{fill_random, seeded_random, set_seeded_rng};
+pub use subtle::SubtleCrypto;
 
-#[derive(Clone, Trace, JsLifetime)]
-#[rquickjs::class]
-pub struct Crypto {}
+/// Largest request `getRandomValues` accepts, per the Web Crypto spec
+const MAX_RANDOM_BYTES: usize = 65536;
 
-impl Default for Crypto {
-    fn default() -> Self {
-        Self::new()
-    }
+/// `Number.MAX_SAFE_INTEGER`
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+const INTEGER_ARRAY_TYPES: &[&str] = &[
+    "Int8Array",
+    "Uint8Array",
+    "Uint8ClampedArray",
+    "Int16Array",
+    "Uint16Array",
+    "Int32Array",
+    "Uint32Array",
+    "BigInt64Array",
+    "BigUint64Array",
+];
+
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct Crypto<'js> {
+    subtle: Class<'js, SubtleCrypto>,
 }
 
 #[rquickjs::methods]
-impl Crypto {
+impl<'js> Crypto<'js> {
+    /// # Errors
+    /// Returns an error if the `SubtleCrypto` instance can't be created
     #[qjs(constructor)]
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(ctx: Ctx<'js>) -> Result<Self> {
+        Ok(Self {
+            subtle: Class::instance(ctx, SubtleCrypto {})?,
+        })
+    }
+
+    #[qjs(get)]
+    pub fn subtle(&self) -> Class<'js, SubtleCrypto> {
+        self.subtle.clone()
     }
 
     #[qjs(rename = "randomUUID")]
     pub fn random_uuid(&self) -> String {
         random_uuid()
     }
+
+    /// Fill an integer typed array with random values
+    ///
+    /// # Errors
+    /// Throws a `TypeError` for non-integer arrays and a `QuotaExceededError`
+    /// for arrays larger than 65536 bytes
+    #[qjs(rename = "getRandomValues")]
+    pub fn get_random_values(&self, ctx: Ctx<'js>, array: Object<'js>) -> Result<Object<'js>> {
+        let type_name = array
+            .get::<_, Object>("constructor")
+            .and_then(|constructor| constructor.get::<_, String>("name"))
+            .unwrap_or_default();
+        if !INTEGER_ARRAY_TYPES.contains(&type_name.as_str()) {
+            return Err(Exception::throw_type(
+                &ctx,
+                "Argument 1 is not an integer-type TypedArray",
+            ));
+        }
+
+        let byte_length: usize = array.get("byteLength")?;
+        if byte_length > MAX_RANDOM_BYTES {
+            let exception = Exception::from_message(
+                ctx.clone(),
+                &format!(
+                    "The ArrayBufferView's byte length ({byte_length}) exceeds the number of bytes of entropy available via this API ({MAX_RANDOM_BYTES})"
+                ),
+            )?;
+            exception.set("name", "QuotaExceededError")?;
+            return Err(ctx.throw(exception.into_value()));
+        }
+
+        let mut bytes = vec![0u8; byte_length];
+        fill_random(&mut bytes).map_err(|e| {
+            Exception::throw_internal(&ctx, &format!("Failed to get random bytes: {e}"))
+        })?;
+
+        // Write through a byte view so every integer array type is handled alike
+        let buffer: Object = array.get("buffer")?;
+        let byte_offset: usize = array.get("byteOffset")?;
+        let uint8_array: Constructor = ctx.globals().get("Uint8Array")?;
+        let view: Object = uint8_array.construct((buffer, byte_offset, byte_length))?;
+        let set: Function = view.get("set")?;
+        set.call::<_, ()>((
+            This(view.clone()),
+            TypedArray::<u8>::new(ctx.clone(), bytes)?,
+        ))?;
+
+        Ok(array)
+    }
+
+    /// A random integer in `[min, max)`, where `min` defaults to 0
+    ///
+    /// # Errors
+    /// Throws a `TypeError` for arguments that aren't safe integers and a
+    /// `RangeError` unless `0 < max - min <= 2^48`
+    #[qjs(rename = "randomInt")]
+    pub fn random_int(&self, ctx: Ctx<'_>, first: f64, second: Opt<f64>) -> Result<f64> {
+        let (min, max) = match second.0 {
+            Some(max) => (first, max),
+            None => (0.0, first),
+        };
+        for (name, value) in [("min", min), ("max", max)] {
+            if value.fract() != 0.0 || value.abs() > MAX_SAFE_INTEGER {
+                return Err(Exception::throw_type(
+                    &ctx,
+                    &format!("The \"{name}\" argument must be a safe integer. Received {value}"),
+                ));
+            }
+        }
+        let (min, max) = (min as i64, max as i64);
+        if max <= min {
+            return Err(Exception::throw_range(
+                &ctx,
+                &format!(
+                    "The \"max\" argument must be greater than \"min\" ({min}). Received {max}"
+                ),
+            ));
+        }
+        if max.abs_diff(min) > random_int::MAX_RANGE {
+            return Err(Exception::throw_range(
+                &ctx,
+                &format!(
+                    "The range of max - min must be at most 2^48. Received {}",
+                    max - min
+                ),
+            ));
+        }
+        let value = random_int(min, max).map_err(|e| {
+            Exception::throw_internal(&ctx, &format!("Failed to get random bytes: {e}"))
+        })?;
+        Ok(value as f64)
+    }
 }
 
 /// Initialize the `web_crypto` module
@@ -32,11 +159,13 @@ impl Crypto {
 pub fn init(ctx: &Ctx<'_>) -> Result<()> {
     let globals = ctx.globals();
 
-    // Register Crypto class
-    rquickjs::Class::<Crypto>::define(&globals)?;
+    // Register Crypto classes
+    Class::<Crypto>::define(&globals)?;
+    Class::<SubtleCrypto>::define(&globals)?;
+    Class::<CryptoKey>::define(&globals)?;
 
     // Create crypto instance
-    let crypto = rquickjs::Class::instance(ctx.clone(), Crypto::new())?;
+    let crypto = Class::instance(ctx.clone(), Crypto::new(ctx.clone())?)?;
     globals.set("crypto", crypto)?;
 
     Ok(())
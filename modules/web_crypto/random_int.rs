@@ -0,0 +1,58 @@
+use crate::seeded_rng::fill_random;
+
+/// Largest range `randomInt` accepts, as in Node.js
+pub(crate) const MAX_RANGE: u64 = 1 << 48;
+
+/// A uniformly distributed integer in `0..range`, from 48-bit values drawn
+/// by `next`. Values from the incomplete last block of `range` are
+/// rejected, so results have no modulo bias.
+fn below(
+    range: u64,
+    mut next: impl FnMut() -> Result<u64, getrandom::Error>,
+) -> Result<u64, getrandom::Error> {
+    let limit = MAX_RANGE - MAX_RANGE % range;
+    loop {
+        let value = next()?;
+        if value < limit {
+            return Ok(value % range);
+        }
+    }
+}
+
+/// 48 random bits, from the seeded RNG if one is installed
+fn random_u48() -> Result<u64, getrandom::Error> {
+    let mut bytes = [0u8; 8];
+    fill_random(&mut bytes[..6])?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// A random integer in `min..max`, where `0 < max - min <= MAX_RANGE`
+///
+/// # Errors
+/// Returns an error if the system's random number generator fails
+pub fn random_int(min: i64, max: i64) -> Result<i64, getrandom::Error> {
+    let range = max.abs_diff(min);
+    Ok(min + below(range, random_u48)? as i64)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_below_rejects_the_biased_tail() {
+        // With a range of 3, 2^48 - 1 falls in the incomplete last block
+        let mut values = [MAX_RANGE - 1, 7].into_iter();
+        assert_eq!(below(3, || Ok(values.next().unwrap())).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_random_int_stays_in_range() {
+        for _ in 0..100 {
+            let value = random_int(-5, 5).unwrap();
+            assert!((-5..5).contains(&value));
+        }
+        assert_eq!(random_int(7, 8).unwrap(), 7);
+    }
+}
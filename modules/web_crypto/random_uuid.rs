@@ -1,5 +1,7 @@
 // Copyright 2018-2025 the Deno authors. MIT license.
 
+use crate::seeded_rng::fill_random;
+
 // Hex lookup table
 const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
 
@@ -37,14 +39,14 @@ fn fast_uuid_v4(rng_bytes: &mut [u8; 16]) -> String {
     unsafe { String::from_utf8_unchecked(buf.to_vec()) }
 }
 
-/// Generate UUID v4 string
+/// Generate UUID v4 string (deterministic when a seed is active)
 ///
 /// # Panics
 /// Panics if the system's random number generator fails
 #[allow(clippy::expect_used)] // Intentional: RNG failure should panic
 pub fn random_uuid() -> String {
     let mut bytes = [0u8; 16];
-    getrandom::fill(&mut bytes).expect("Failed to get random bytes");
+    fill_random(&mut bytes).expect("Failed to get random bytes");
     fast_uuid_v4(&mut bytes)
 }
 
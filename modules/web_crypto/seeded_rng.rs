@@ -0,0 +1,64 @@
+// Deterministic random number generation for --seed
+
+use rand_chacha::ChaCha8Rng;
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, PoisonError};
+
+thread_local! {
+    /// RNG shared by `Math.random`, `crypto.getRandomValues` and `crypto.randomUUID`
+    /// while a seed is active. Per thread, since each runtime lives on one thread
+    /// and parallel test files must not draw from each other's sequence.
+    static SEEDED_RNG: RefCell<Option<Arc<Mutex<ChaCha8Rng>>>> = const { RefCell::new(None) };
+}
+
+/// Install a fresh RNG seeded with `seed` for the current thread and return it
+pub fn set_seeded_rng(seed: u64) -> Arc<Mutex<ChaCha8Rng>> {
+    let rng = Arc::new(Mutex::new(ChaCha8Rng::seed_from_u64(seed)));
+    SEEDED_RNG.with(|cell| cell.replace(Some(rng.clone())));
+    rng
+}
+
+fn seeded_rng() -> Option<Arc<Mutex<ChaCha8Rng>>> {
+    SEEDED_RNG.with(|cell| cell.borrow().clone())
+}
+
+/// Next value in [0, 1) with 53 bits of precision, as `Math.random` returns
+pub fn seeded_random(rng: &Mutex<ChaCha8Rng>) -> f64 {
+    let value = rng
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .next_u64()
+        >> 11;
+    value as f64 / (1u64 << 53) as f64
+}
+
+/// Fill `bytes` from the seeded RNG if one is installed, otherwise from the OS
+///
+/// # Errors
+/// Returns an error if the system's random number generator fails
+pub fn fill_random(bytes: &mut [u8]) -> Result<(), getrandom::Error> {
+    if let Some(rng) = seeded_rng() {
+        rng.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .fill_bytes(bytes);
+        return Ok(());
+    }
+    getrandom::fill(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_random_is_deterministic() {
+        let a = Mutex::new(ChaCha8Rng::seed_from_u64(42));
+        let b = Mutex::new(ChaCha8Rng::seed_from_u64(42));
+        for _ in 0..8 {
+            let value = seeded_random(&a);
+            assert!((0.0..1.0).contains(&value));
+            assert!((value - seeded_random(&b)).abs() < f64::EPSILON);
+        }
+    }
+}
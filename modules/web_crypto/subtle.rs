@@ -0,0 +1,957 @@
+// https://w3c.github.io/webcrypto/#subtlecrypto-interface
+use crate::crypto_key::{CryptoKey, Hash, KeyAlgorithm, KeyData, KeyUsage};
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{BlockCipher, BlockDecryptMut, BlockEncryptMut, KeyInit, KeyIvInit};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ring::signature::{self, EcdsaKeyPair, KeyPair, UnparsedPublicKey};
+use ring::{aead, digest, hmac, rand::SystemRandom};
+use rquickjs::{
+    ArrayBuffer, Class, Coerced, Ctx, Exception, JsLifetime, Object, Promise, Result, Value,
+    class::Trace,
+};
+use utils::{buffer_source_bytes, class_of};
+
+/// A P-256 private key as PKCS #8, split where the private scalar and the
+/// public point go. This is the layout ring itself generates.
+const P256_PKCS8_PREFIX: &[u8] = &[
+    0x30, 0x81, 0x87, 0x02, 0x01, 0x00, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02,
+    0x01, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x04, 0x6d, 0x30, 0x6b, 0x02,
+    0x01, 0x01, 0x04, 0x20,
+];
+const P256_PKCS8_MIDDLE: &[u8] = &[0xa1, 0x44, 0x03, 0x42, 0x00];
+
+/// An error from an operation on compio's thread pool, thrown once back on
+/// the JS thread
+struct CryptoError {
+    name: &'static str,
+    message: String,
+}
+
+impl CryptoError {
+    fn operation(message: &str) -> Self {
+        Self {
+            name: "OperationError",
+            message: message.to_string(),
+        }
+    }
+}
+
+/// An `Error` named after the `DOMException` the spec calls for
+fn throw(ctx: &Ctx<'_>, name: &str, message: &str) -> rquickjs::Error {
+    let exception = match Exception::from_message(ctx.clone(), message) {
+        Ok(exception) => exception,
+        Err(error) => return error,
+    };
+    if let Err(error) = exception.set("name", name) {
+        return error;
+    }
+    ctx.throw(exception.into_value())
+}
+
+fn not_supported(ctx: &Ctx<'_>, message: &str) -> rquickjs::Error {
+    throw(ctx, "NotSupportedError", message)
+}
+
+/// Run `op` on compio's thread pool, so large inputs don't block the event loop
+async fn blocking<T: Send + 'static>(
+    ctx: &Ctx<'_>,
+    op: impl FnOnce() -> std::result::Result<T, CryptoError> + Send + 'static,
+) -> Result<T> {
+    compio::runtime::spawn_blocking(op)
+        .await
+        .unwrap_or_else(|_| Err(CryptoError::operation("The operation panicked")))
+        .map_err(|error| throw(ctx, error.name, &error.message))
+}
+
+/// An algorithm identifier: a name, or an object with a `name`. The name is
+/// returned upper-cased, since names match case-insensitively.
+fn normalize_algorithm<'js>(
+    ctx: &Ctx<'js>,
+    algorithm: &Value<'js>,
+) -> Result<(String, Option<Object<'js>>)> {
+    if let Some(name) = algorithm.as_string() {
+        return Ok((name.to_string()?.to_ascii_uppercase(), None));
+    }
+    let Some(object) = algorithm.as_object() else {
+        return Err(Exception::throw_type(
+            ctx,
+            "Algorithm must be a string or an object",
+        ));
+    };
+    let Some(name) = object.get::<_, Option<Coerced<String>>>("name")? else {
+        return Err(Exception::throw_type(ctx, "Algorithm is missing a name"));
+    };
+    Ok((name.0.to_ascii_uppercase(), Some(object.clone())))
+}
+
+fn hash_algorithm<'js>(ctx: &Ctx<'js>, algorithm: &Value<'js>) -> Result<Hash> {
+    let (name, _) = normalize_algorithm(ctx, algorithm)?;
+    Hash::from_name(&name).ok_or_else(|| not_supported(ctx, &format!("Unsupported hash: {name}")))
+}
+
+/// The `hash` member of an HMAC or ECDSA algorithm
+fn hash_member<'js>(ctx: &Ctx<'js>, params: Option<&Object<'js>>) -> Result<Hash> {
+    match params
+        .map(|params| params.get::<_, Value>("hash"))
+        .transpose()?
+    {
+        Some(hash) if !hash.is_undefined() => hash_algorithm(ctx, &hash),
+        _ => Err(Exception::throw_type(ctx, "Algorithm is missing a hash")),
+    }
+}
+
+/// A required member holding a `BufferSource`
+fn bytes_member<'js>(ctx: &Ctx<'js>, params: Option<&Object<'js>>, name: &str) -> Result<Vec<u8>> {
+    let value = match params {
+        Some(params) => params.get::<_, Value>(name)?,
+        None => Value::new_undefined(ctx.clone()),
+    };
+    bytes(ctx, &value, name)
+}
+
+fn bytes(ctx: &Ctx<'_>, value: &Value<'_>, name: &str) -> Result<Vec<u8>> {
+    buffer_source_bytes(value).ok_or_else(|| {
+        Exception::throw_type(
+            ctx,
+            &format!("{name} must be an ArrayBuffer or ArrayBufferView"),
+        )
+    })
+}
+
+fn parse_usages(ctx: &Ctx<'_>, usages: &Value<'_>) -> Result<Vec<KeyUsage>> {
+    let Some(array) = usages.as_array() else {
+        return Err(Exception::throw_type(ctx, "keyUsages must be an array"));
+    };
+    let mut result = Vec::new();
+    for usage in array.iter::<Coerced<String>>() {
+        let usage = usage?.0;
+        let Some(usage) = KeyUsage::from_name(&usage) else {
+            return Err(Exception::throw_type(
+                ctx,
+                &format!("Invalid key usage: '{usage}'"),
+            ));
+        };
+        if !result.contains(&usage) {
+            result.push(usage);
+        }
+    }
+    Ok(result)
+}
+
+/// Throw a `SyntaxError` unless every usage is one of `allowed`
+fn check_usages(ctx: &Ctx<'_>, usages: &[KeyUsage], allowed: &[KeyUsage]) -> Result<()> {
+    match usages.iter().find(|usage| !allowed.contains(usage)) {
+        Some(usage) => Err(throw(
+            ctx,
+            "SyntaxError",
+            &format!("Unsupported key usage: '{}'", usage.name()),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Secret and private keys are useless without a usage
+fn check_not_empty(ctx: &Ctx<'_>, usages: &[KeyUsage]) -> Result<()> {
+    if usages.is_empty() {
+        return Err(throw(ctx, "SyntaxError", "Key usages must not be empty"));
+    }
+    Ok(())
+}
+
+const HMAC_USAGES: &[KeyUsage] = &[KeyUsage::Sign, KeyUsage::Verify];
+const AES_USAGES: &[KeyUsage] = &[
+    KeyUsage::Encrypt,
+    KeyUsage::Decrypt,
+    KeyUsage::WrapKey,
+    KeyUsage::UnwrapKey,
+];
+
+/// The key `key` refers to, if it is for `name` and allows `usage`
+fn usable_key<'js>(
+    ctx: &Ctx<'js>,
+    key: &Value<'js>,
+    name: &str,
+    usage: KeyUsage,
+) -> Result<Class<'js, CryptoKey<'js>>> {
+    let Some(key) = class_of::<CryptoKey>(key) else {
+        return Err(Exception::throw_type(ctx, "key must be a CryptoKey"));
+    };
+    {
+        let borrowed = key.borrow();
+        if borrowed.inner.algorithm.name() != name {
+            return Err(throw(
+                ctx,
+                "InvalidAccessError",
+                &format!("The key is not a {name} key"),
+            ));
+        }
+        if !borrowed.usages.contains(&usage) {
+            return Err(throw(
+                ctx,
+                "InvalidAccessError",
+                &format!("The key does not support the '{}' operation", usage.name()),
+            ));
+        }
+    }
+    Ok(key)
+}
+
+fn hmac_algorithm(hash: Hash) -> hmac::Algorithm {
+    match hash {
+        Hash::Sha1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        Hash::Sha256 => hmac::HMAC_SHA256,
+        Hash::Sha384 => hmac::HMAC_SHA384,
+        Hash::Sha512 => hmac::HMAC_SHA512,
+    }
+}
+
+fn digest_algorithm(hash: Hash) -> &'static digest::Algorithm {
+    match hash {
+        Hash::Sha1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        Hash::Sha256 => &digest::SHA256,
+        Hash::Sha384 => &digest::SHA384,
+        Hash::Sha512 => &digest::SHA512,
+    }
+}
+
+/// ECDSA on P-256 with `hash`. ring only pairs P-256 with SHA-256 for
+/// fixed-length signatures, the format Web Crypto uses.
+fn ecdsa_algorithms(
+    ctx: &Ctx<'_>,
+    hash: Hash,
+) -> Result<(
+    &'static signature::EcdsaSigningAlgorithm,
+    &'static signature::EcdsaVerificationAlgorithm,
+)> {
+    if hash != Hash::Sha256 {
+        return Err(not_supported(
+            ctx,
+            &format!("ECDSA does not support {}", hash.name()),
+        ));
+    }
+    Ok((
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        &signature::ECDSA_P256_SHA256_FIXED,
+    ))
+}
+
+fn check_named_curve(ctx: &Ctx<'_>, params: Option<&Object<'_>>) -> Result<()> {
+    let curve = params
+        .map(|params| params.get::<_, Option<Coerced<String>>>("namedCurve"))
+        .transpose()?
+        .flatten();
+    match curve {
+        Some(curve) if curve.0 == "P-256" => Ok(()),
+        Some(curve) => Err(not_supported(
+            ctx,
+            &format!("Unsupported named curve: {}", curve.0),
+        )),
+        None => Err(Exception::throw_type(
+            ctx,
+            "Algorithm is missing a namedCurve",
+        )),
+    }
+}
+
+/// The AES algorithm `name` with a key of `length` bits
+fn aes_algorithm(ctx: &Ctx<'_>, name: &str, length: u32) -> Result<KeyAlgorithm> {
+    if !matches!(length, 128 | 192 | 256) {
+        return Err(throw(
+            ctx,
+            "OperationError",
+            "AES key length must be 128, 192 or 256 bits",
+        ));
+    }
+    match name {
+        // ring only implements AES-128-GCM and AES-256-GCM
+        "AES-GCM" if length == 192 => {
+            Err(not_supported(ctx, "192-bit AES-GCM keys are not supported"))
+        }
+        "AES-GCM" => Ok(KeyAlgorithm::AesGcm { length }),
+        _ => Ok(KeyAlgorithm::AesCbc { length }),
+    }
+}
+
+fn cbc_encrypt<C>(key: &[u8], iv: &[u8], data: &[u8]) -> std::result::Result<Vec<u8>, CryptoError>
+where
+    C: BlockCipher + BlockEncryptMut + KeyInit,
+{
+    let encryptor = cbc::Encryptor::<C>::new_from_slices(key, iv)
+        .map_err(|_| CryptoError::operation("Invalid AES-CBC key or iv"))?;
+    Ok(encryptor.encrypt_padded_vec_mut::<Pkcs7>(data))
+}
+
+fn cbc_decrypt<C>(key: &[u8], iv: &[u8], data: &[u8]) -> std::result::Result<Vec<u8>, CryptoError>
+where
+    C: BlockCipher + BlockDecryptMut + KeyInit,
+{
+    let decryptor = cbc::Decryptor::<C>::new_from_slices(key, iv)
+        .map_err(|_| CryptoError::operation("Invalid AES-CBC key or iv"))?;
+    decryptor
+        .decrypt_padded_vec_mut::<Pkcs7>(data)
+        .map_err(|_| CryptoError::operation("Decryption failed"))
+}
+
+fn gcm_key(key: &[u8]) -> std::result::Result<aead::LessSafeKey, CryptoError> {
+    let algorithm = if key.len() == 16 {
+        &aead::AES_128_GCM
+    } else {
+        &aead::AES_256_GCM
+    };
+    let key = aead::UnboundKey::new(algorithm, key)
+        .map_err(|_| CryptoError::operation("Invalid AES-GCM key"))?;
+    Ok(aead::LessSafeKey::new(key))
+}
+
+/// Parameters of an AES encryption or decryption
+struct AesParams {
+    iv: Vec<u8>,
+    additional_data: Vec<u8>,
+}
+
+fn aes_params<'js>(ctx: &Ctx<'js>, name: &str, params: Option<&Object<'js>>) -> Result<AesParams> {
+    let iv = bytes_member(ctx, params, "iv")?;
+    let mut additional_data = Vec::new();
+    if name == "AES-GCM" {
+        // ring only implements 96-bit nonces and 128-bit tags
+        if iv.len() != aead::NONCE_LEN {
+            return Err(not_supported(ctx, "AES-GCM iv must be 12 bytes"));
+        }
+        if let Some(params) = params {
+            if let Some(tag_length) = params.get::<_, Option<u32>>("tagLength")?
+                && tag_length != 128
+            {
+                return Err(not_supported(ctx, "AES-GCM tagLength must be 128"));
+            }
+            let data: Value = params.get("additionalData")?;
+            if !data.is_undefined() {
+                additional_data = bytes(ctx, &data, "additionalData")?;
+            }
+        }
+    } else if iv.len() != 16 {
+        return Err(throw(ctx, "OperationError", "AES-CBC iv must be 16 bytes"));
+    }
+    Ok(AesParams {
+        iv,
+        additional_data,
+    })
+}
+
+fn aes_crypt(
+    algorithm: KeyAlgorithm,
+    key: &[u8],
+    params: &AesParams,
+    data: Vec<u8>,
+    encrypt: bool,
+) -> std::result::Result<Vec<u8>, CryptoError> {
+    if let KeyAlgorithm::AesGcm { .. } = algorithm {
+        let key = gcm_key(key)?;
+        let nonce = aead::Nonce::try_assume_unique_for_key(&params.iv)
+            .map_err(|_| CryptoError::operation("Invalid AES-GCM iv"))?;
+        let aad = aead::Aad::from(params.additional_data.as_slice());
+        let mut in_out = data;
+        if encrypt {
+            key.seal_in_place_append_tag(nonce, aad, &mut in_out)
+                .map_err(|_| CryptoError::operation("Encryption failed"))?;
+        } else {
+            let length = key
+                .open_in_place(nonce, aad, &mut in_out)
+                .map_err(|_| CryptoError::operation("Decryption failed"))?
+                .len();
+            in_out.truncate(length);
+        }
+        return Ok(in_out);
+    }
+    let iv = &params.iv;
+    match (key.len(), encrypt) {
+        (16, true) => cbc_encrypt::<aes::Aes128>(key, iv, &data),
+        (24, true) => cbc_encrypt::<aes::Aes192>(key, iv, &data),
+        (_, true) => cbc_encrypt::<aes::Aes256>(key, iv, &data),
+        (16, false) => cbc_decrypt::<aes::Aes128>(key, iv, &data),
+        (24, false) => cbc_decrypt::<aes::Aes192>(key, iv, &data),
+        (_, false) => cbc_decrypt::<aes::Aes256>(key, iv, &data),
+    }
+}
+
+/// The `CryptoKey` for new key material
+fn crypto_key<'js>(
+    ctx: &Ctx<'js>,
+    algorithm: KeyAlgorithm,
+    data: KeyData,
+    extractable: bool,
+    usages: Vec<KeyUsage>,
+) -> Result<Value<'js>> {
+    let key = CryptoKey::new(ctx, algorithm, data, extractable, usages)?;
+    Ok(Class::instance(ctx.clone(), key)?.into_value())
+}
+
+/// A string member of a JSON Web Key
+fn jwk_string(jwk: &Object<'_>, name: &str) -> Result<Option<String>> {
+    Ok(jwk
+        .get::<_, Option<Coerced<String>>>(name)?
+        .map(|value| value.0))
+}
+
+/// A base64url-encoded member of a JSON Web Key
+fn jwk_bytes(ctx: &Ctx<'_>, jwk: &Object<'_>, name: &str) -> Result<Vec<u8>> {
+    let Some(value) = jwk_string(jwk, name)? else {
+        return Err(throw(ctx, "DataError", &format!("JWK is missing '{name}'")));
+    };
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| throw(ctx, "DataError", &format!("JWK '{name}' is not base64url")))
+}
+
+/// Check the members of a JSON Web Key that apply to every key type
+fn check_jwk(
+    ctx: &Ctx<'_>,
+    jwk: &Object<'_>,
+    kty: &str,
+    alg: Option<&str>,
+    extractable: bool,
+    usages: &[KeyUsage],
+) -> Result<()> {
+    if jwk_string(jwk, "kty")?.as_deref() != Some(kty) {
+        return Err(throw(
+            ctx,
+            "DataError",
+            &format!("JWK 'kty' must be '{kty}'"),
+        ));
+    }
+    if let (Some(expected), Some(actual)) = (alg, jwk_string(jwk, "alg")?)
+        && actual != expected
+    {
+        return Err(throw(
+            ctx,
+            "DataError",
+            &format!("JWK 'alg' must be '{expected}'"),
+        ));
+    }
+    if extractable && jwk.get::<_, Option<bool>>("ext")? == Some(false) {
+        return Err(throw(ctx, "DataError", "JWK is not extractable"));
+    }
+    if let Some(key_ops) = jwk.get::<_, Option<Vec<String>>>("key_ops")?
+        && let Some(usage) = usages
+            .iter()
+            .find(|usage| !key_ops.iter().any(|op| op == usage.name()))
+    {
+        return Err(throw(
+            ctx,
+            "DataError",
+            &format!("JWK 'key_ops' does not allow '{}'", usage.name()),
+        ));
+    }
+    Ok(())
+}
+
+/// The key material of a P-256 JSON Web Key, private if it has `d`
+fn ec_jwk_data(ctx: &Ctx<'_>, jwk: &Object<'_>) -> Result<KeyData> {
+    if jwk_string(jwk, "crv")?.as_deref() != Some("P-256") {
+        return Err(throw(ctx, "DataError", "JWK 'crv' must be 'P-256'"));
+    }
+    let x = jwk_bytes(ctx, jwk, "x")?;
+    let y = jwk_bytes(ctx, jwk, "y")?;
+    if x.len() != 32 || y.len() != 32 {
+        return Err(throw(ctx, "DataError", "Invalid P-256 point"));
+    }
+    let public = [&[0x04][..], &x, &y].concat();
+    if jwk_string(jwk, "d")?.is_none() {
+        return Ok(KeyData::EcPublic(public));
+    }
+    let private = jwk_bytes(ctx, jwk, "d")?;
+    let pkcs8 = [P256_PKCS8_PREFIX, &private, P256_PKCS8_MIDDLE, &public].concat();
+    // Parsing checks that the private key matches the public key
+    EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        &pkcs8,
+        &SystemRandom::new(),
+    )
+    .map_err(|_| throw(ctx, "DataError", "Invalid P-256 private key"))?;
+    Ok(KeyData::EcPrivate { pkcs8, public })
+}
+
+/// The JWK `alg` for a key algorithm
+fn jwk_alg(algorithm: KeyAlgorithm) -> &'static str {
+    match algorithm {
+        KeyAlgorithm::Hmac { hash, .. } => match hash {
+            Hash::Sha1 => "HS1",
+            Hash::Sha256 => "HS256",
+            Hash::Sha384 => "HS384",
+            Hash::Sha512 => "HS512",
+        },
+        KeyAlgorithm::AesGcm { length } => match length {
+            128 => "A128GCM",
+            192 => "A192GCM",
+            _ => "A256GCM",
+        },
+        KeyAlgorithm::AesCbc { length } => match length {
+            128 => "A128CBC",
+            192 => "A192CBC",
+            _ => "A256CBC",
+        },
+        KeyAlgorithm::Ecdsa => "ES256",
+    }
+}
+
+/// `crypto.subtle`
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct SubtleCrypto {}
+
+#[rquickjs::methods]
+impl SubtleCrypto {
+    /// Resolves to the digest of `data` as an `ArrayBuffer`
+    ///
+    /// # Errors
+    /// Returns an error if the promise can't be created
+    pub fn digest<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        algorithm: Value<'js>,
+        data: Value<'js>,
+    ) -> Result<Promise<'js>> {
+        let ctx_clone = ctx.clone();
+        Promise::wrap_future(&ctx, async move {
+            let ctx = ctx_clone;
+            let hash = hash_algorithm(&ctx, &algorithm)?;
+            let data = bytes(&ctx, &data, "data")?;
+            let digest = blocking(&ctx, move || {
+                Ok(digest::digest(digest_algorithm(hash), &data)
+                    .as_ref()
+                    .to_vec())
+            })
+            .await?;
+            ArrayBuffer::new(ctx, digest)
+        })
+    }
+
+    /// Resolves to a new `CryptoKey`, or a `{ privateKey, publicKey }` pair
+    /// for ECDSA
+    ///
+    /// # Errors
+    /// Returns an error if the promise can't be created
+    #[qjs(rename = "generateKey")]
+    pub fn generate_key<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        algorithm: Value<'js>,
+        extractable: bool,
+        key_usages: Value<'js>,
+    ) -> Result<Promise<'js>> {
+        let ctx_clone = ctx.clone();
+        Promise::wrap_future(&ctx, async move {
+            let ctx = ctx_clone;
+            let (name, params) = normalize_algorithm(&ctx, &algorithm)?;
+            let params = params.as_ref();
+            let usages = parse_usages(&ctx, &key_usages)?;
+            let algorithm = match name.as_str() {
+                "HMAC" => {
+                    check_usages(&ctx, &usages, HMAC_USAGES)?;
+                    let hash = hash_member(&ctx, params)?;
+                    let length = match params
+                        .map(|params| params.get::<_, Option<u32>>("length"))
+                        .transpose()?
+                        .flatten()
+                    {
+                        Some(0) => {
+                            return Err(throw(
+                                &ctx,
+                                "OperationError",
+                                "HMAC key length must not be 0",
+                            ));
+                        }
+                        Some(length) if length % 8 != 0 => {
+                            return Err(not_supported(
+                                &ctx,
+                                "HMAC key length must be a multiple of 8",
+                            ));
+                        }
+                        Some(length) => length,
+                        None => hash.block_bits(),
+                    };
+                    KeyAlgorithm::Hmac { hash, length }
+                }
+                "AES-GCM" | "AES-CBC" => {
+                    check_usages(&ctx, &usages, AES_USAGES)?;
+                    let length = params
+                        .map(|params| params.get::<_, Option<u32>>("length"))
+                        .transpose()?
+                        .flatten()
+                        .ok_or_else(|| {
+                            Exception::throw_type(&ctx, "Algorithm is missing a length")
+                        })?;
+                    aes_algorithm(&ctx, &name, length)?
+                }
+                "ECDSA" => {
+                    check_usages(&ctx, &usages, HMAC_USAGES)?;
+                    check_named_curve(&ctx, params)?;
+                    let private_usages: Vec<_> = usages
+                        .iter()
+                        .copied()
+                        .filter(|usage| *usage == KeyUsage::Sign)
+                        .collect();
+                    check_not_empty(&ctx, &private_usages)?;
+                    let public_usages: Vec<_> = usages
+                        .iter()
+                        .copied()
+                        .filter(|usage| *usage == KeyUsage::Verify)
+                        .collect();
+                    let (pkcs8, public) = blocking(&ctx, || {
+                        let rng = SystemRandom::new();
+                        let alg = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+                        let pkcs8 = EcdsaKeyPair::generate_pkcs8(alg, &rng)
+                            .map_err(|_| CryptoError::operation("Key generation failed"))?;
+                        let pair = EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng)
+                            .map_err(|_| CryptoError::operation("Key generation failed"))?;
+                        Ok((pkcs8.as_ref().to_vec(), pair.public_key().as_ref().to_vec()))
+                    })
+                    .await?;
+                    let pair = Object::new(ctx.clone())?;
+                    pair.set(
+                        "privateKey",
+                        crypto_key(
+                            &ctx,
+                            KeyAlgorithm::Ecdsa,
+                            KeyData::EcPrivate {
+                                pkcs8,
+                                public: public.clone(),
+                            },
+                            extractable,
+                            private_usages,
+                        )?,
+                    )?;
+                    // Public keys are always extractable
+                    pair.set(
+                        "publicKey",
+                        crypto_key(
+                            &ctx,
+                            KeyAlgorithm::Ecdsa,
+                            KeyData::EcPublic(public),
+                            true,
+                            public_usages,
+                        )?,
+                    )?;
+                    return Ok(pair.into_value());
+                }
+                _ => {
+                    return Err(not_supported(
+                        &ctx,
+                        &format!("Unsupported algorithm: {name}"),
+                    ));
+                }
+            };
+            check_not_empty(&ctx, &usages)?;
+            let length = match algorithm {
+                KeyAlgorithm::Hmac { length, .. }
+                | KeyAlgorithm::AesGcm { length }
+                | KeyAlgorithm::AesCbc { length } => length,
+                KeyAlgorithm::Ecdsa => 0,
+            };
+            let secret = blocking(&ctx, move || {
+                // Key material always comes from the OS, never the --seed RNG
+                let mut secret = vec![0u8; length as usize / 8];
+                getrandom::fill(&mut secret).map_err(|e| {
+                    CryptoError::operation(&format!("Failed to get random bytes: {e}"))
+                })?;
+                Ok(secret)
+            })
+            .await?;
+            crypto_key(
+                &ctx,
+                algorithm,
+                KeyData::Secret(secret),
+                extractable,
+                usages,
+            )
+        })
+    }
+
+    /// Resolves to a `CryptoKey` for `keyData` in the "raw" or "jwk" format
+    ///
+    /// # Errors
+    /// Returns an error if the promise can't be created
+    #[qjs(rename = "importKey")]
+    pub fn import_key<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        format: Coerced<String>,
+        key_data: Value<'js>,
+        algorithm: Value<'js>,
+        extractable: bool,
+        key_usages: Value<'js>,
+    ) -> Result<Promise<'js>> {
+        let ctx_clone = ctx.clone();
+        Promise::wrap_future(&ctx, async move {
+            let ctx = ctx_clone;
+            let (name, params) = normalize_algorithm(&ctx, &algorithm)?;
+            let params = params.as_ref();
+            let usages = parse_usages(&ctx, &key_usages)?;
+            let jwk = match format.0.as_str() {
+                "raw" => None,
+                "jwk" => match key_data.as_object() {
+                    Some(jwk) => Some(jwk.clone()),
+                    None => {
+                        return Err(Exception::throw_type(
+                            &ctx,
+                            "keyData must be a JSON Web Key",
+                        ));
+                    }
+                },
+                other => {
+                    return Err(not_supported(
+                        &ctx,
+                        &format!("Unsupported key format: {other}"),
+                    ));
+                }
+            };
+
+            let (algorithm, data) = match name.as_str() {
+                "HMAC" => {
+                    check_usages(&ctx, &usages, HMAC_USAGES)?;
+                    let hash = hash_member(&ctx, params)?;
+                    let secret = match &jwk {
+                        Some(jwk) => {
+                            let alg = jwk_alg(KeyAlgorithm::Hmac { hash, length: 0 });
+                            check_jwk(&ctx, jwk, "oct", Some(alg), extractable, &usages)?;
+                            jwk_bytes(&ctx, jwk, "k")?
+                        }
+                        None => bytes(&ctx, &key_data, "keyData")?,
+                    };
+                    if secret.is_empty() {
+                        return Err(throw(&ctx, "DataError", "HMAC key must not be empty"));
+                    }
+                    let length = secret.len() as u32 * 8;
+                    if let Some(params) = params
+                        && let Some(requested) = params.get::<_, Option<u32>>("length")?
+                        && requested != length
+                    {
+                        return Err(throw(
+                            &ctx,
+                            "DataError",
+                            "HMAC key length does not match the key",
+                        ));
+                    }
+                    (KeyAlgorithm::Hmac { hash, length }, KeyData::Secret(secret))
+                }
+                "AES-GCM" | "AES-CBC" => {
+                    check_usages(&ctx, &usages, AES_USAGES)?;
+                    let secret = match &jwk {
+                        Some(jwk) => {
+                            check_jwk(&ctx, jwk, "oct", None, extractable, &usages)?;
+                            jwk_bytes(&ctx, jwk, "k")?
+                        }
+                        None => bytes(&ctx, &key_data, "keyData")?,
+                    };
+                    if !matches!(secret.len(), 16 | 24 | 32) {
+                        return Err(throw(
+                            &ctx,
+                            "DataError",
+                            "AES key must be 16, 24 or 32 bytes",
+                        ));
+                    }
+                    let algorithm = aes_algorithm(&ctx, &name, secret.len() as u32 * 8)?;
+                    if let Some(jwk) = &jwk
+                        && let Some(alg) = jwk_string(jwk, "alg")?
+                        && alg != jwk_alg(algorithm)
+                    {
+                        return Err(throw(
+                            &ctx,
+                            "DataError",
+                            &format!("JWK 'alg' must be '{}'", jwk_alg(algorithm)),
+                        ));
+                    }
+                    (algorithm, KeyData::Secret(secret))
+                }
+                "ECDSA" => {
+                    check_usages(&ctx, &usages, HMAC_USAGES)?;
+                    check_named_curve(&ctx, params)?;
+                    let data = if let Some(jwk) = &jwk {
+                        check_jwk(&ctx, jwk, "EC", None, extractable, &usages)?;
+                        ec_jwk_data(&ctx, jwk)?
+                    } else {
+                        let public = bytes(&ctx, &key_data, "keyData")?;
+                        if public.len() != 65 || public[0] != 0x04 {
+                            return Err(throw(
+                                &ctx,
+                                "DataError",
+                                "Raw ECDSA keys must be uncompressed P-256 points",
+                            ));
+                        }
+                        KeyData::EcPublic(public)
+                    };
+                    let allowed = if matches!(data, KeyData::EcPrivate { .. }) {
+                        KeyUsage::Sign
+                    } else {
+                        KeyUsage::Verify
+                    };
+                    check_usages(&ctx, &usages, &[allowed])?;
+                    (KeyAlgorithm::Ecdsa, data)
+                }
+                _ => {
+                    return Err(not_supported(
+                        &ctx,
+                        &format!("Unsupported algorithm: {name}"),
+                    ));
+                }
+            };
+            if !matches!(data, KeyData::EcPublic(_)) {
+                check_not_empty(&ctx, &usages)?;
+            }
+            crypto_key(&ctx, algorithm, data, extractable, usages)
+        })
+    }
+
+    /// Resolves to the signature of `data` as an `ArrayBuffer`
+    ///
+    /// # Errors
+    /// Returns an error if the promise can't be created
+    pub fn sign<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        algorithm: Value<'js>,
+        key: Value<'js>,
+        data: Value<'js>,
+    ) -> Result<Promise<'js>> {
+        let ctx_clone = ctx.clone();
+        Promise::wrap_future(&ctx, async move {
+            let ctx = ctx_clone;
+            let (name, params) = normalize_algorithm(&ctx, &algorithm)?;
+            let key = usable_key(&ctx, &key, &name, KeyUsage::Sign)?;
+            let inner = key.borrow().inner.clone();
+            let data = bytes(&ctx, &data, "data")?;
+            let signing = match inner.algorithm {
+                KeyAlgorithm::Ecdsa => {
+                    Some(ecdsa_algorithms(&ctx, hash_member(&ctx, params.as_ref())?)?.0)
+                }
+                _ => None,
+            };
+            let signature = blocking(&ctx, move || match (&inner.data, inner.algorithm) {
+                (KeyData::Secret(secret), KeyAlgorithm::Hmac { hash, .. }) => {
+                    let key = hmac::Key::new(hmac_algorithm(hash), secret);
+                    Ok(hmac::sign(&key, &data).as_ref().to_vec())
+                }
+                (KeyData::EcPrivate { pkcs8, .. }, _) => {
+                    let rng = SystemRandom::new();
+                    let alg = signing.ok_or_else(|| CryptoError::operation("Signing failed"))?;
+                    let pair = EcdsaKeyPair::from_pkcs8(alg, pkcs8, &rng)
+                        .map_err(|_| CryptoError::operation("Invalid private key"))?;
+                    let signature = pair
+                        .sign(&rng, &data)
+                        .map_err(|_| CryptoError::operation("Signing failed"))?;
+                    Ok(signature.as_ref().to_vec())
+                }
+                _ => Err(CryptoError::operation("The key cannot sign")),
+            })
+            .await?;
+            ArrayBuffer::new(ctx, signature)
+        })
+    }
+
+    /// Resolves to whether `signature` is a valid signature of `data`
+    ///
+    /// # Errors
+    /// Returns an error if the promise can't be created
+    pub fn verify<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        algorithm: Value<'js>,
+        key: Value<'js>,
+        signature: Value<'js>,
+        data: Value<'js>,
+    ) -> Result<Promise<'js>> {
+        let ctx_clone = ctx.clone();
+        Promise::wrap_future(&ctx, async move {
+            let ctx = ctx_clone;
+            let (name, params) = normalize_algorithm(&ctx, &algorithm)?;
+            let key = usable_key(&ctx, &key, &name, KeyUsage::Verify)?;
+            let inner = key.borrow().inner.clone();
+            let signature = bytes(&ctx, &signature, "signature")?;
+            let data = bytes(&ctx, &data, "data")?;
+            let verification = match inner.algorithm {
+                KeyAlgorithm::Ecdsa => {
+                    Some(ecdsa_algorithms(&ctx, hash_member(&ctx, params.as_ref())?)?.1)
+                }
+                _ => None,
+            };
+            blocking(&ctx, move || match (&inner.data, inner.algorithm) {
+                (KeyData::Secret(secret), KeyAlgorithm::Hmac { hash, .. }) => {
+                    let key = hmac::Key::new(hmac_algorithm(hash), secret);
+                    Ok(hmac::verify(&key, &data, &signature).is_ok())
+                }
+                (KeyData::EcPublic(public) | KeyData::EcPrivate { public, .. }, _) => {
+                    let alg = verification
+                        .ok_or_else(|| CryptoError::operation("Verification failed"))?;
+                    Ok(UnparsedPublicKey::new(alg, public)
+                        .verify(&data, &signature)
+                        .is_ok())
+                }
+                _ => Err(CryptoError::operation("The key cannot verify")),
+            })
+            .await
+        })
+    }
+
+    /// Resolves to `data` encrypted with AES-GCM or AES-CBC, as an `ArrayBuffer`
+    ///
+    /// # Errors
+    /// Returns an error if the promise can't be created
+    pub fn encrypt<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        algorithm: Value<'js>,
+        key: Value<'js>,
+        data: Value<'js>,
+    ) -> Result<Promise<'js>> {
+        crypt(ctx, algorithm, key, data, true)
+    }
+
+    /// Resolves to `data` decrypted with AES-GCM or AES-CBC, as an `ArrayBuffer`
+    ///
+    /// # Errors
+    /// Returns an error if the promise can't be created
+    pub fn decrypt<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        algorithm: Value<'js>,
+        key: Value<'js>,
+        data: Value<'js>,
+    ) -> Result<Promise<'js>> {
+        crypt(ctx, algorithm, key, data, false)
+    }
+}
+
+fn crypt<'js>(
+    ctx: Ctx<'js>,
+    algorithm: Value<'js>,
+    key: Value<'js>,
+    data: Value<'js>,
+    encrypt: bool,
+) -> Result<Promise<'js>> {
+    let ctx_clone = ctx.clone();
+    Promise::wrap_future(&ctx, async move {
+        let ctx = ctx_clone;
+        let (name, params) = normalize_algorithm(&ctx, &algorithm)?;
+        if name != "AES-GCM" && name != "AES-CBC" {
+            return Err(not_supported(
+                &ctx,
+                &format!("Unsupported algorithm: {name}"),
+            ));
+        }
+        let usage = if encrypt {
+            KeyUsage::Encrypt
+        } else {
+            KeyUsage::Decrypt
+        };
+        let key = usable_key(&ctx, &key, &name, usage)?;
+        let inner = key.borrow().inner.clone();
+        let params = aes_params(&ctx, &name, params.as_ref())?;
+        let data = bytes(&ctx, &data, "data")?;
+        let output = blocking(&ctx, move || match &inner.data {
+            KeyData::Secret(secret) => aes_crypt(inner.algorithm, secret, &params, data, encrypt),
+            _ => Err(CryptoError::operation("The key is not an AES key")),
+        })
+        .await?;
+        ArrayBuffer::new(ctx, output)
+    })
+}
@@ -0,0 +1,178 @@
+const encoder = new TextEncoder();
+
+function hex(buffer: ArrayBuffer): string {
+  return Array.from(new Uint8Array(buffer), (b) => b.toString(16).padStart(2, "0"))
+    .join("");
+}
+
+async function rejectsWith(promise: Promise<unknown>, name: string) {
+  try {
+    await promise;
+  } catch (error) {
+    if ((error as Error).name !== name) throw error;
+    return;
+  }
+  throw new Error(`should reject with ${name}`);
+}
+
+Deno.test("crypto.subtle.digest() hashes with SHA-256", async () => {
+  const digest = await crypto.subtle.digest("SHA-256", encoder.encode("abc"));
+  if (
+    hex(digest) !==
+      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+  ) {
+    throw new Error(hex(digest));
+  }
+  const sha1 = await crypto.subtle.digest({ name: "sha-1" }, new ArrayBuffer(0));
+  if (hex(sha1) !== "da39a3ee5e6b4b0d3255bfef95601890afd80709") {
+    throw new Error(hex(sha1));
+  }
+});
+
+Deno.test("crypto.subtle signs and verifies with HMAC", async () => {
+  const key = await crypto.subtle.importKey(
+    "raw",
+    encoder.encode("Jefe"),
+    { name: "HMAC", hash: "SHA-256" },
+    false,
+    ["sign", "verify"],
+  );
+  if (key.type !== "secret" || key.algorithm.name !== "HMAC") {
+    throw new Error("key properties");
+  }
+  const data = encoder.encode("what do ya want for nothing?");
+  const signature = await crypto.subtle.sign("HMAC", key, data);
+  if (
+    hex(signature) !==
+      "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+  ) {
+    throw new Error(hex(signature));
+  }
+  if (!await crypto.subtle.verify("HMAC", key, signature, data)) {
+    throw new Error("should verify");
+  }
+  if (await crypto.subtle.verify("HMAC", key, signature, encoder.encode("x"))) {
+    throw new Error("should not verify");
+  }
+
+  const generated = await crypto.subtle.generateKey(
+    { name: "HMAC", hash: "SHA-512" },
+    true,
+    ["sign"],
+  );
+  if ((generated.algorithm as HmacKeyAlgorithm).length !== 1024) {
+    throw new Error("default HMAC length");
+  }
+});
+
+Deno.test("crypto.subtle encrypts and decrypts with AES-GCM", async () => {
+  const key = await crypto.subtle.generateKey(
+    { name: "AES-GCM", length: 256 },
+    false,
+    ["encrypt", "decrypt"],
+  );
+  const iv = crypto.getRandomValues(new Uint8Array(12));
+  const plaintext = encoder.encode("attack at dawn");
+  const ciphertext = await crypto.subtle.encrypt({ name: "AES-GCM", iv }, key, plaintext);
+  if (ciphertext.byteLength !== plaintext.byteLength + 16) {
+    throw new Error(`ciphertext is ${ciphertext.byteLength} bytes`);
+  }
+  const decrypted = await crypto.subtle.decrypt({ name: "AES-GCM", iv }, key, ciphertext);
+  if (new TextDecoder().decode(decrypted) !== "attack at dawn") {
+    throw new Error("round trip");
+  }
+
+  const tampered = new Uint8Array(ciphertext);
+  tampered[0] ^= 1;
+  await rejectsWith(
+    crypto.subtle.decrypt({ name: "AES-GCM", iv }, key, tampered),
+    "OperationError",
+  );
+});
+
+Deno.test("crypto.subtle encrypts and decrypts with AES-CBC", async () => {
+  const key = await crypto.subtle.importKey(
+    "jwk",
+    { kty: "oct", k: "AAECAwQFBgcICQoLDA0ODw", alg: "A128CBC" },
+    "AES-CBC",
+    true,
+    ["encrypt", "decrypt"],
+  );
+  const iv = new Uint8Array(16);
+  const ciphertext = await crypto.subtle.encrypt(
+    { name: "AES-CBC", iv },
+    key,
+    encoder.encode("sixteen byte msg"),
+  );
+  if (ciphertext.byteLength !== 32) throw new Error("PKCS #7 padding");
+  const decrypted = await crypto.subtle.decrypt({ name: "AES-CBC", iv }, key, ciphertext);
+  if (new TextDecoder().decode(decrypted) !== "sixteen byte msg") {
+    throw new Error("round trip");
+  }
+});
+
+Deno.test("crypto.subtle signs and verifies with ECDSA", async () => {
+  const { privateKey, publicKey } = await crypto.subtle.generateKey(
+    { name: "ECDSA", namedCurve: "P-256" },
+    false,
+    ["sign", "verify"],
+  ) as CryptoKeyPair;
+  if (privateKey.type !== "private" || publicKey.type !== "public") {
+    throw new Error("key types");
+  }
+  if (!publicKey.extractable || publicKey.usages.join() !== "verify") {
+    throw new Error("public key properties");
+  }
+  const algorithm = { name: "ECDSA", hash: "SHA-256" };
+  const data = encoder.encode("hello");
+  const signature = await crypto.subtle.sign(algorithm, privateKey, data);
+  if (signature.byteLength !== 64) throw new Error("signature length");
+  if (!await crypto.subtle.verify(algorithm, publicKey, signature, data)) {
+    throw new Error("should verify");
+  }
+});
+
+Deno.test("crypto.subtle imports ECDSA keys as JWK", async () => {
+  const jwk = {
+    kty: "EC",
+    crv: "P-256",
+    x: "UVw9brnjlrkE0_7Kf1T9zQzB6Ze_N13KUVrQpsO0A18",
+    y: "RTa-OlDzGPv5pUdZAqIhUCvvDVfgjFOyzApW8X2fk1Q",
+    d: "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA",
+  };
+  const algorithm = { name: "ECDSA", namedCurve: "P-256" };
+  const privateKey = await crypto.subtle.importKey("jwk", jwk, algorithm, false, ["sign"]);
+  const { d: _, ...publicJwk } = jwk;
+  const publicKey = await crypto.subtle.importKey("jwk", publicJwk, algorithm, true, [
+    "verify",
+  ]);
+  const data = encoder.encode("payload");
+  const signature = await crypto.subtle.sign({ name: "ECDSA", hash: "SHA-256" }, privateKey, data);
+  if (!await crypto.subtle.verify({ name: "ECDSA", hash: "SHA-256" }, publicKey, signature, data)) {
+    throw new Error("should verify");
+  }
+
+  // A private key that doesn't match the public key
+  await rejectsWith(
+    crypto.subtle.importKey("jwk", { ...jwk, d: jwk.x }, algorithm, false, ["sign"]),
+    "DataError",
+  );
+});
+
+Deno.test("crypto.subtle rejects bad algorithms and usages", async () => {
+  await rejectsWith(crypto.subtle.digest("MD5", new Uint8Array()), "NotSupportedError");
+  await rejectsWith(
+    crypto.subtle.generateKey({ name: "AES-GCM", length: 128 }, false, ["sign"]),
+    "SyntaxError",
+  );
+  const key = await crypto.subtle.generateKey(
+    { name: "HMAC", hash: "SHA-256" },
+    false,
+    ["verify"],
+  );
+  await rejectsWith(crypto.subtle.sign("HMAC", key, new Uint8Array()), "InvalidAccessError");
+  await rejectsWith(
+    crypto.subtle.encrypt({ name: "AES-GCM", iv: new Uint8Array(12) }, key, new Uint8Array()),
+    "InvalidAccessError",
+  );
+});
@@ -104,11 +104,14 @@ fn normalize_encoding_label(label: &str) -> String {
 
 /// Extract bytes from `ArrayBuffer`, `TypedArray`, or `DataView`
 fn extract_bytes<'js>(ctx: Ctx<'js>, obj: Object<'js>) -> Result<Vec<u8>> {
-    // Try as ArrayBuffer
-    if let Some(buffer) = ArrayBuffer::from_object(obj.clone())
-        && let Some(bytes) = buffer.as_bytes()
-    {
-        return Ok(bytes.to_vec());
+    // Try as ArrayBuffer. QuickJS throws for any other object, so clear that
+    // exception or it surfaces later as an uncaught error.
+    if let Some(buffer) = ArrayBuffer::from_object(obj.clone()) {
+        if let Some(bytes) = buffer.as_bytes() {
+            return Ok(bytes.to_vec());
+        }
+    } else {
+        ctx.catch();
     }
 
     // Try as Uint8Array
@@ -0,0 +1,216 @@
+// https://dom.spec.whatwg.org/#interface-event
+use rquickjs::{Ctx, Exception, JsLifetime, Result, Value, class::Trace, prelude::*};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Not being dispatched
+const NONE: u8 = 0;
+/// Being dispatched to listeners of its target
+const AT_TARGET: u8 = 2;
+
+/// An event, dispatched with `EventTarget.dispatchEvent()`. `CustomEvent`,
+/// `ErrorEvent` and `PromiseRejectionEvent` extend it from JavaScript.
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct Event<'js> {
+    #[qjs(skip_trace)]
+    event_type: String,
+    #[qjs(skip_trace)]
+    bubbles: bool,
+    #[qjs(skip_trace)]
+    cancelable: bool,
+    #[qjs(skip_trace)]
+    composed: bool,
+    #[qjs(skip_trace)]
+    time_stamp: f64,
+    target: Option<Value<'js>>,
+    current_target: Option<Value<'js>>,
+    #[qjs(skip_trace)]
+    pub(crate) phase: u8,
+    #[qjs(skip_trace)]
+    pub(crate) default_prevented: bool,
+    #[qjs(skip_trace)]
+    pub(crate) stop_propagation: bool,
+    #[qjs(skip_trace)]
+    pub(crate) stop_immediate_propagation: bool,
+    /// Set while a passive listener runs, so `preventDefault()` is ignored
+    #[qjs(skip_trace)]
+    pub(crate) in_passive_listener: bool,
+}
+
+#[rquickjs::methods]
+impl<'js> Event<'js> {
+    /// # Errors
+    /// Throws a `TypeError` if `type` is missing
+    #[qjs(constructor)]
+    pub fn new(
+        ctx: Ctx<'js>,
+        event_type: Opt<Coerced<String>>,
+        init: Opt<Value<'js>>,
+    ) -> Result<Self> {
+        let Some(Coerced(event_type)) = event_type.0 else {
+            return Err(Exception::throw_type(
+                &ctx,
+                "Failed to construct 'Event': 1 argument required, but only 0 present",
+            ));
+        };
+        let init = init.0.and_then(Value::into_object);
+        let flag = |name: &str| -> Result<bool> {
+            match &init {
+                Some(init) => Ok(init.get::<_, Coerced<bool>>(name)?.0),
+                None => Ok(false),
+            }
+        };
+        let time_stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0);
+        Ok(Event {
+            event_type,
+            bubbles: flag("bubbles")?,
+            cancelable: flag("cancelable")?,
+            composed: flag("composed")?,
+            time_stamp,
+            target: None,
+            current_target: None,
+            phase: NONE,
+            default_prevented: false,
+            stop_propagation: false,
+            stop_immediate_propagation: false,
+            in_passive_listener: false,
+        })
+    }
+
+    #[qjs(get, rename = "type")]
+    pub fn event_type(&self) -> String {
+        self.event_type.clone()
+    }
+
+    /// The target the event was dispatched to, or `null` before dispatch
+    #[qjs(get)]
+    pub fn target(&self, ctx: Ctx<'js>) -> Value<'js> {
+        self.target.clone().unwrap_or_else(|| Value::new_null(ctx))
+    }
+
+    /// Same as `target`, since events don't propagate through a tree
+    #[qjs(get, rename = "srcElement")]
+    pub fn src_element(&self, ctx: Ctx<'js>) -> Value<'js> {
+        self.target(ctx)
+    }
+
+    /// The target whose listeners are running, or `null` outside dispatch
+    #[qjs(get, rename = "currentTarget")]
+    pub fn current_target(&self, ctx: Ctx<'js>) -> Value<'js> {
+        self.current_target
+            .clone()
+            .unwrap_or_else(|| Value::new_null(ctx))
+    }
+
+    /// The targets the event passes through: just the current one
+    #[qjs(rename = "composedPath")]
+    pub fn composed_path(&self) -> Vec<Value<'js>> {
+        self.current_target.iter().cloned().collect()
+    }
+
+    #[qjs(get, rename = "eventPhase")]
+    pub fn event_phase(&self) -> u8 {
+        self.phase
+    }
+
+    #[qjs(get)]
+    pub fn bubbles(&self) -> bool {
+        self.bubbles
+    }
+
+    #[qjs(get)]
+    pub fn cancelable(&self) -> bool {
+        self.cancelable
+    }
+
+    #[qjs(get)]
+    pub fn composed(&self) -> bool {
+        self.composed
+    }
+
+    #[qjs(get, rename = "defaultPrevented")]
+    pub fn default_prevented(&self) -> bool {
+        self.default_prevented
+    }
+
+    /// Events are only ever dispatched by scripts
+    #[qjs(get, rename = "isTrusted")]
+    pub fn is_trusted(&self) -> bool {
+        false
+    }
+
+    /// Milliseconds since the Unix epoch when the event was created
+    #[qjs(get, rename = "timeStamp")]
+    pub fn time_stamp(&self) -> f64 {
+        self.time_stamp
+    }
+
+    /// The inverse of `defaultPrevented`, kept for older code
+    #[qjs(get, rename = "returnValue")]
+    pub fn return_value(&self) -> bool {
+        !self.default_prevented
+    }
+
+    #[qjs(set, rename = "returnValue")]
+    pub fn set_return_value(&mut self, value: Coerced<bool>) {
+        if !value.0 {
+            self.prevent_default();
+        }
+    }
+
+    #[qjs(get, rename = "cancelBubble")]
+    pub fn cancel_bubble(&self) -> bool {
+        self.stop_propagation
+    }
+
+    #[qjs(set, rename = "cancelBubble")]
+    pub fn set_cancel_bubble(&mut self, value: Coerced<bool>) {
+        if value.0 {
+            self.stop_propagation = true;
+        }
+    }
+
+    #[qjs(rename = "stopPropagation")]
+    pub fn stop_propagation(&mut self) {
+        self.stop_propagation = true;
+    }
+
+    /// Skip the listeners after the current one as well
+    #[qjs(rename = "stopImmediatePropagation")]
+    pub fn stop_immediate_propagation(&mut self) {
+        self.stop_propagation = true;
+        self.stop_immediate_propagation = true;
+    }
+
+    /// Cancel the event, if it is cancelable and not in a passive listener
+    #[qjs(rename = "preventDefault")]
+    pub fn prevent_default(&mut self) {
+        if self.cancelable && !self.in_passive_listener {
+            self.default_prevented = true;
+        }
+    }
+}
+
+impl<'js> Event<'js> {
+    pub fn is_dispatching(&self) -> bool {
+        self.phase != NONE
+    }
+
+    /// Start dispatching to `target`, clearing the flags of any earlier
+    /// dispatch
+    pub(crate) fn begin_dispatch(&mut self, target: Value<'js>) {
+        self.target = Some(target.clone());
+        self.current_target = Some(target);
+        self.phase = AT_TARGET;
+        self.stop_propagation = false;
+        self.stop_immediate_propagation = false;
+    }
+
+    pub(crate) fn end_dispatch(&mut self) {
+        self.current_target = None;
+        self.phase = NONE;
+        self.in_passive_listener = false;
+    }
+}
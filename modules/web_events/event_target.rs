@@ -0,0 +1,289 @@
+// https://dom.spec.whatwg.org/#interface-eventtarget
+use crate::event::Event;
+use rquickjs::{
+    Class, Ctx, Exception, Function, JsLifetime, Object, Result, Value, class::Trace, prelude::*,
+};
+use std::cell::Cell;
+use std::rc::Rc;
+use utils::{class_of, report_uncaught};
+
+#[derive(Clone, Trace, JsLifetime)]
+struct Listener<'js> {
+    #[qjs(skip_trace)]
+    event_type: String,
+    /// A function, or an object with a `handleEvent` method
+    callback: Value<'js>,
+    #[qjs(skip_trace)]
+    capture: bool,
+    #[qjs(skip_trace)]
+    once: bool,
+    #[qjs(skip_trace)]
+    passive: bool,
+    /// Set on removal, so a dispatch already under way skips the listener
+    #[qjs(skip_trace)]
+    removed: Rc<Cell<bool>>,
+}
+
+impl<'js> Listener<'js> {
+    fn matches(&self, event_type: &str, callback: &Value<'js>, capture: bool) -> bool {
+        self.event_type == event_type && self.callback == *callback && self.capture == capture
+    }
+}
+
+#[derive(Default, Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct EventTarget<'js> {
+    listeners: Vec<Listener<'js>>,
+}
+
+#[rquickjs::methods]
+impl<'js> EventTarget<'js> {
+    #[qjs(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `options` is either the `capture` flag or an object with `capture`,
+    /// `once`, `passive` and `signal`. Adding the same listener twice does
+    /// nothing.
+    ///
+    /// # Errors
+    /// Throws a `TypeError` if `this` isn't an `EventTarget` or the listener
+    /// isn't callable
+    #[qjs(rename = "addEventListener")]
+    pub fn add_event_listener(
+        this: This<Value<'js>>,
+        ctx: Ctx<'js>,
+        event_type: Coerced<String>,
+        callback: Value<'js>,
+        options: Opt<Value<'js>>,
+    ) -> Result<()> {
+        let (target, _) = target_of(&ctx, &this.0)?;
+        if callback.is_null() || callback.is_undefined() {
+            return Ok(());
+        }
+        if !callback.is_object() {
+            return Err(Exception::throw_type(
+                &ctx,
+                "The listener must be a function or an object with a handleEvent method",
+            ));
+        }
+
+        let mut capture = false;
+        let mut once = false;
+        let mut passive = false;
+        let mut signal = None;
+        match options.0 {
+            Some(options) if options.is_object() => {
+                let options = Object::from_value(options)?;
+                capture = options.get::<_, Coerced<bool>>("capture")?.0;
+                once = options.get::<_, Coerced<bool>>("once")?.0;
+                passive = options.get::<_, Coerced<bool>>("passive")?.0;
+                signal = options
+                    .get::<_, Option<Object>>("signal")?
+                    .filter(|signal| !signal.is_null());
+            }
+            Some(options) => capture = options.as_bool().unwrap_or(false),
+            None => {}
+        }
+        if let Some(signal) = &signal
+            && signal.get::<_, bool>("aborted")?
+        {
+            return Ok(());
+        }
+
+        let event_type = event_type.0;
+        {
+            let mut target = target.borrow_mut();
+            if target
+                .listeners
+                .iter()
+                .any(|listener| listener.matches(&event_type, &callback, capture))
+            {
+                return Ok(());
+            }
+            target.listeners.push(Listener {
+                event_type: event_type.clone(),
+                callback: callback.clone(),
+                capture,
+                once,
+                passive,
+                removed: Rc::new(Cell::new(false)),
+            });
+        }
+
+        // Remove the listener again once the signal aborts
+        if let Some(signal) = signal {
+            let on_abort = Function::new(ctx.clone(), move || {
+                target.borrow_mut().remove(&event_type, &callback, capture);
+            })?;
+            let add: Function = signal.get("addEventListener")?;
+            add.call::<_, ()>((This(signal), "abort", on_abort))?;
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    /// Throws a `TypeError` if `this` isn't an `EventTarget`
+    #[qjs(rename = "removeEventListener")]
+    pub fn remove_event_listener(
+        this: This<Value<'js>>,
+        ctx: Ctx<'js>,
+        event_type: Coerced<String>,
+        callback: Value<'js>,
+        options: Opt<Value<'js>>,
+    ) -> Result<()> {
+        let (target, _) = target_of(&ctx, &this.0)?;
+        let capture = match options.0 {
+            Some(options) if options.is_object() => {
+                Object::from_value(options)?
+                    .get::<_, Coerced<bool>>("capture")?
+                    .0
+            }
+            Some(options) => options.as_bool().unwrap_or(false),
+            None => false,
+        };
+        target
+            .borrow_mut()
+            .remove(&event_type.0, &callback, capture);
+        Ok(())
+    }
+
+    /// Call the listeners for `event.type` synchronously, in the order they
+    /// were added, capturing ones first. Errors thrown by listeners are
+    /// reported as uncaught instead of stopping the dispatch.
+    ///
+    /// Returns false if a listener called `preventDefault()`.
+    ///
+    /// # Errors
+    /// Throws a `TypeError` if `event` isn't an `Event`, and an
+    /// `InvalidStateError` if it is already being dispatched
+    #[qjs(rename = "dispatchEvent")]
+    pub fn dispatch_event(
+        this: This<Value<'js>>,
+        ctx: Ctx<'js>,
+        event: Value<'js>,
+    ) -> Result<bool> {
+        let (target, target_value) = target_of(&ctx, &this.0)?;
+        let Some(event_class) = class_of::<Event>(&event) else {
+            return Err(Exception::throw_type(
+                &ctx,
+                "Failed to execute 'dispatchEvent': parameter 1 is not of type 'Event'",
+            ));
+        };
+        if event_class.borrow().is_dispatching() {
+            return Err(named_error(
+                &ctx,
+                "InvalidStateError",
+                "The event is already being dispatched",
+            ));
+        }
+        let event_type = event_class.borrow().event_type();
+        event_class
+            .borrow_mut()
+            .begin_dispatch(target_value.clone());
+
+        let mut listeners: Vec<_> = target
+            .borrow()
+            .listeners
+            .iter()
+            .filter(|listener| listener.event_type == event_type)
+            .cloned()
+            .collect();
+        listeners.sort_by_key(|listener| !listener.capture);
+
+        let mut result = Ok(());
+        for listener in listeners {
+            if event_class.borrow().stop_immediate_propagation {
+                break;
+            }
+            if listener.removed.get() {
+                continue;
+            }
+            if listener.once {
+                target
+                    .borrow_mut()
+                    .remove(&event_type, &listener.callback, listener.capture);
+            }
+            event_class.borrow_mut().in_passive_listener = listener.passive;
+            let called = call_listener(&ctx, &listener.callback, &target_value, &event);
+            event_class.borrow_mut().in_passive_listener = false;
+            if let Err(error) = called {
+                result = report_uncaught(&ctx, error);
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+
+        let mut event = event_class.borrow_mut();
+        event.end_dispatch();
+        result.map(|()| !event.default_prevented)
+    }
+}
+
+impl<'js> EventTarget<'js> {
+    fn remove(&mut self, event_type: &str, callback: &Value<'js>, capture: bool) {
+        self.listeners.retain(|listener| {
+            let matches = listener.matches(event_type, callback, capture);
+            if matches {
+                listener.removed.set(true);
+            }
+            !matches
+        });
+    }
+}
+
+/// The listeners of `this`, and the value events see as their target.
+/// `globalThis` inherits from `EventTarget` but isn't one, so its listeners
+/// are kept by a hidden instance; a missing `this`, as in a bare
+/// `addEventListener()` call, means `globalThis` too.
+fn target_of<'js>(
+    ctx: &Ctx<'js>,
+    this: &Value<'js>,
+) -> Result<(Class<'js, EventTarget<'js>>, Value<'js>)> {
+    let globals = ctx.globals();
+    if this.is_undefined() || this.is_null() || this.as_object() == Some(&globals) {
+        let target: Option<Class<EventTarget>> =
+            ctx.eval("globalThis[Symbol.for('mdeno.internal')].globalEventTarget")?;
+        if let Some(target) = target {
+            return Ok((target, globals.into_value()));
+        }
+    } else if let Some(target) = class_of::<EventTarget>(this) {
+        return Ok((target, this.clone()));
+    }
+    Err(Exception::throw_type(ctx, "Illegal invocation"))
+}
+
+fn call_listener<'js>(
+    ctx: &Ctx<'js>,
+    callback: &Value<'js>,
+    target: &Value<'js>,
+    event: &Value<'js>,
+) -> Result<()> {
+    if let Some(function) = callback.as_function() {
+        return function.call((This(target.clone()), event.clone()));
+    }
+    let Some(object) = callback.as_object() else {
+        return Ok(());
+    };
+    match object.get::<_, Value>("handleEvent")?.into_function() {
+        Some(handle_event) => handle_event.call((This(object.clone()), event.clone())),
+        None => Err(Exception::throw_type(
+            ctx,
+            "The listener has no handleEvent method",
+        )),
+    }
+}
+
+/// An `Error` with the given name, standing in for a `DOMException`
+fn named_error(ctx: &Ctx<'_>, name: &str, message: &str) -> rquickjs::Error {
+    let exception = match Exception::from_message(ctx.clone(), message) {
+        Ok(exception) => exception,
+        Err(error) => return error,
+    };
+    if let Err(error) = exception.set("name", name) {
+        return error;
+    }
+    ctx.throw(exception.into_value())
+}
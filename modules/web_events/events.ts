@@ -0,0 +1,179 @@
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+// Event.NONE and the other phase constants, on the class and its instances
+for (const [name, value] of Object.entries({
+  NONE: 0,
+  CAPTURING_PHASE: 1,
+  AT_TARGET: 2,
+  BUBBLING_PHASE: 3,
+})) {
+  const descriptor = { value, enumerable: true };
+  Object.defineProperty(Event, name, descriptor);
+  Object.defineProperty(Event.prototype, name, descriptor);
+}
+
+interface CustomEventInit<T> extends EventInit {
+  detail?: T;
+}
+
+// https://dom.spec.whatwg.org/#interface-customevent
+class CustomEvent<T = unknown> extends Event {
+  #detail: T | null;
+
+  constructor(type: string, eventInitDict: CustomEventInit<T> = {}) {
+    super(type, eventInitDict);
+    this.#detail = eventInitDict?.detail ?? null;
+  }
+
+  get detail(): T | null {
+    return this.#detail;
+  }
+}
+
+interface ErrorEventInit extends EventInit {
+  message?: string;
+  filename?: string;
+  lineno?: number;
+  colno?: number;
+  error?: unknown;
+}
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#errorevent
+class ErrorEvent extends Event {
+  #message: string;
+  #filename: string;
+  #lineno: number;
+  #colno: number;
+  #error: unknown;
+
+  constructor(type: string, eventInitDict: ErrorEventInit = {}) {
+    super(type, eventInitDict);
+    this.#message = String(eventInitDict?.message ?? "");
+    this.#filename = String(eventInitDict?.filename ?? "");
+    this.#lineno = Number(eventInitDict?.lineno ?? 0);
+    this.#colno = Number(eventInitDict?.colno ?? 0);
+    this.#error = eventInitDict?.error;
+  }
+
+  get message(): string {
+    return this.#message;
+  }
+
+  get filename(): string {
+    return this.#filename;
+  }
+
+  get lineno(): number {
+    return this.#lineno;
+  }
+
+  get colno(): number {
+    return this.#colno;
+  }
+
+  get error(): unknown {
+    return this.#error;
+  }
+}
+
+interface PromiseRejectionEventInit extends EventInit {
+  promise: Promise<unknown>;
+  reason?: unknown;
+}
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#promiserejectionevent
+class PromiseRejectionEvent extends Event {
+  #promise: Promise<unknown>;
+  #reason: unknown;
+
+  constructor(type: string, eventInitDict: PromiseRejectionEventInit) {
+    super(type, eventInitDict);
+    if (!(eventInitDict?.promise instanceof Promise)) {
+      throw new TypeError("PromiseRejectionEvent requires a promise");
+    }
+    this.#promise = eventInitDict.promise;
+    this.#reason = eventInitDict.reason;
+  }
+
+  get promise(): Promise<unknown> {
+    return this.#promise;
+  }
+
+  get reason(): unknown {
+    return this.#reason;
+  }
+}
+
+for (const [name, value] of Object.entries({
+  CustomEvent,
+  ErrorEvent,
+  PromiseRejectionEvent,
+})) {
+  Object.defineProperty(globalThis, name, {
+    value,
+    writable: true,
+    enumerable: false,
+    configurable: true,
+  });
+}
+
+// globalThis is an EventTarget, whose listeners are kept by this instance
+__internal.globalEventTarget = new EventTarget();
+Object.setPrototypeOf(globalThis, EventTarget.prototype);
+
+// Rejected promises without a handler, by promise. The runtime's rejection
+// tracker adds and removes them, and processRejections() runs once pending
+// jobs are done, so handlers attached in the meantime still count.
+const pendingRejections = new Map<Promise<unknown>, unknown>();
+
+__internal.trackRejection = function (
+  promise: Promise<unknown>,
+  reason: unknown,
+  handled: boolean,
+): void {
+  if (handled) {
+    pendingRejections.delete(promise);
+  } else {
+    pendingRejections.set(promise, reason);
+  }
+};
+
+// Dispatch "unhandledrejection" for each rejection still unhandled, and
+// report the ones no listener prevented
+__internal.processRejections = function (): void {
+  for (const [promise, reason] of [...pendingRejections]) {
+    pendingRejections.delete(promise);
+    const event = new PromiseRejectionEvent("unhandledrejection", {
+      cancelable: true,
+      promise,
+      reason,
+    });
+    if (globalThis.dispatchEvent(event)) {
+      __internal.reportUncaught(reason, true);
+    }
+  }
+};
+
+// Whether an "error" event is being dispatched, so an error thrown by one
+// of its listeners is reported rather than dispatched again
+let dispatchingError = false;
+
+// Dispatch "error" for an uncaught error, returning true if a listener
+// called preventDefault()
+__internal.dispatchUncaught = function (error: unknown): boolean {
+  if (dispatchingError) {
+    return false;
+  }
+  const event = new ErrorEvent("error", {
+    cancelable: true,
+    message: error instanceof Error ? error.message : String(error),
+    error,
+  });
+  dispatchingError = true;
+  try {
+    return !globalThis.dispatchEvent(event);
+  } finally {
+    dispatchingError = false;
+  }
+};
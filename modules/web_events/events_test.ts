@@ -0,0 +1,134 @@
+Deno.test("EventTarget calls listeners in order with the event", () => {
+  const target = new EventTarget();
+  const seen: string[] = [];
+  const first = (event: Event) => {
+    seen.push(`first ${event.type}`);
+    if (event.target !== target || event.currentTarget !== target) {
+      throw new Error("wrong target");
+    }
+  };
+  target.addEventListener("ping", first);
+  target.addEventListener("ping", first);
+  target.addEventListener("ping", { handleEvent: () => seen.push("object") });
+  target.addEventListener("pong", () => seen.push("pong"));
+  const event = new Event("ping");
+  if (!target.dispatchEvent(event)) throw new Error("should not be canceled");
+  if (seen.join() !== "first ping,object") throw new Error(`${seen}`);
+  if (event.currentTarget !== null || event.eventPhase !== Event.NONE) {
+    throw new Error("dispatch state should be cleared");
+  }
+
+  target.removeEventListener("ping", first);
+  seen.length = 0;
+  target.dispatchEvent(new Event("ping"));
+  if (seen.join() !== "object") throw new Error(`${seen}`);
+});
+
+Deno.test("EventTarget supports once, capture and signal options", () => {
+  const target = new EventTarget();
+  let count = 0;
+  target.addEventListener("tick", () => count++, { once: true });
+  target.dispatchEvent(new Event("tick"));
+  target.dispatchEvent(new Event("tick"));
+  if (count !== 1) throw new Error(`once listener ran ${count} times`);
+
+  const order: string[] = [];
+  target.addEventListener("go", () => order.push("bubble"));
+  target.addEventListener("go", () => order.push("capture"), true);
+  target.dispatchEvent(new Event("go"));
+  if (order.join() !== "capture,bubble") throw new Error(`${order}`);
+
+  const controller = new AbortController();
+  let signaled = 0;
+  target.addEventListener("sig", () => signaled++, { signal: controller.signal });
+  target.dispatchEvent(new Event("sig"));
+  controller.abort();
+  target.dispatchEvent(new Event("sig"));
+  if (signaled !== 1) throw new Error(`signal listener ran ${signaled} times`);
+});
+
+Deno.test("Event.preventDefault only cancels cancelable events", () => {
+  const target = new EventTarget();
+  target.addEventListener("x", (event) => event.preventDefault());
+  if (!target.dispatchEvent(new Event("x"))) {
+    throw new Error("a non-cancelable event can't be canceled");
+  }
+  const event = new Event("x", { cancelable: true });
+  if (target.dispatchEvent(event) || !event.defaultPrevented) {
+    throw new Error("the event should be canceled");
+  }
+
+  const passive = new EventTarget();
+  passive.addEventListener("x", (event) => event.preventDefault(), { passive: true });
+  if (!passive.dispatchEvent(new Event("x", { cancelable: true }))) {
+    throw new Error("passive listeners can't cancel");
+  }
+});
+
+Deno.test("Event.stopImmediatePropagation skips later listeners", () => {
+  const target = new EventTarget();
+  const seen: number[] = [];
+  target.addEventListener("x", (event) => {
+    seen.push(1);
+    event.stopImmediatePropagation();
+  });
+  target.addEventListener("x", () => seen.push(2));
+  target.dispatchEvent(new Event("x"));
+  if (seen.join() !== "1") throw new Error(`${seen}`);
+});
+
+Deno.test("Event exposes its init flags", () => {
+  const event = new Event("x", { bubbles: true, cancelable: true, composed: true });
+  if (event.type !== "x" || !event.bubbles || !event.cancelable || !event.composed) {
+    throw new Error("init flags not set");
+  }
+  if (event.isTrusted || event.target !== null || typeof event.timeStamp !== "number") {
+    throw new Error("unexpected defaults");
+  }
+  try {
+    // @ts-expect-error: the type is required
+    new Event();
+    throw new Error("should throw");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+});
+
+Deno.test("CustomEvent carries its detail", () => {
+  const event = new CustomEvent("data", { detail: { id: 1 } });
+  if (!(event instanceof Event) || event.detail?.id !== 1) {
+    throw new Error("detail not set");
+  }
+  if (new CustomEvent("empty").detail !== null) throw new Error("detail defaults to null");
+  let received: unknown;
+  const target = new EventTarget();
+  target.addEventListener("data", (e) => received = (e as CustomEvent).detail);
+  target.dispatchEvent(event);
+  if (received !== event.detail) throw new Error("listener didn't see detail");
+});
+
+Deno.test("EventTarget and Event can be subclassed", () => {
+  class Emitter extends EventTarget {
+    emit(value: number) {
+      return this.dispatchEvent(new CustomEvent("value", { detail: value }));
+    }
+  }
+  const emitter = new Emitter();
+  let value = 0;
+  emitter.addEventListener("value", function (this: Emitter, event) {
+    if (this !== emitter) throw new Error("listener this should be the target");
+    value = (event as CustomEvent<number>).detail;
+  });
+  emitter.emit(42);
+  if (value !== 42) throw new Error(`got ${value}`);
+});
+
+Deno.test("globalThis is an EventTarget", () => {
+  if (!(globalThis instanceof EventTarget)) throw new Error("not an EventTarget");
+  let seen: unknown;
+  const listener = (event: Event) => seen = event.target;
+  addEventListener("custom", listener);
+  dispatchEvent(new Event("custom"));
+  removeEventListener("custom", listener);
+  if (seen !== globalThis) throw new Error("target should be globalThis");
+});
@@ -0,0 +1,41 @@
+mod event;
+mod event_target;
+
+pub use event::Event;
+pub use event_target::EventTarget;
+
+use rquickjs::{Class, Ctx, Function, Module};
+use utils::{add_internal_function, report_uncaught};
+use utils_macros::include_ts;
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    Class::<Event>::define(&ctx.globals())?;
+    Class::<EventTarget>::define(&ctx.globals())?;
+
+    // queueTask(callback): call `callback` once pending jobs have run, as a
+    // task of its own
+    add_internal_function!(ctx, "queueTask", |callback: Function<'_>| {
+        let ctx = callback.ctx().clone();
+        ctx.clone().spawn(async move {
+            if let Err(error) = callback.call::<_, ()>(()) {
+                // Reported errors end the process; others have nowhere to go
+                let _ = report_uncaught(&ctx, error);
+            }
+        });
+    });
+
+    // CustomEvent and the other Event subclasses, EventTarget on globalThis
+    // and unhandled rejection tracking
+    let js_source = include_ts!("events.ts");
+    let module = Module::evaluate(ctx.clone(), "web_events", js_source)?;
+    module.finish::<()>()?;
+
+    // MessageChannel, MessagePort and MessageEvent
+    let js_source = include_ts!("messages.ts");
+    let module = Module::evaluate(ctx.clone(), "web_messages", js_source)?;
+    module.finish::<()>()?;
+
+    Ok(())
+}
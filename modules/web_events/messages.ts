@@ -0,0 +1,205 @@
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+interface MessageEventInit<T> extends EventInit {
+  data?: T;
+  origin?: string;
+  lastEventId?: string;
+  source?: MessagePort | null;
+  ports?: MessagePort[];
+}
+
+// https://html.spec.whatwg.org/multipage/comms.html#messageevent
+class MessageEvent<T = unknown> extends Event {
+  #data: T | null;
+  #origin: string;
+  #lastEventId: string;
+  #source: MessagePort | null;
+  #ports: readonly MessagePort[];
+
+  constructor(type: string, eventInitDict: MessageEventInit<T> = {}) {
+    super(type, eventInitDict);
+    this.#data = eventInitDict?.data ?? null;
+    this.#origin = String(eventInitDict?.origin ?? "");
+    this.#lastEventId = String(eventInitDict?.lastEventId ?? "");
+    this.#source = eventInitDict?.source ?? null;
+    this.#ports = Object.freeze([...(eventInitDict?.ports ?? [])]);
+  }
+
+  get data(): T | null {
+    return this.#data;
+  }
+
+  get origin(): string {
+    return this.#origin;
+  }
+
+  get lastEventId(): string {
+    return this.#lastEventId;
+  }
+
+  get source(): MessagePort | null {
+    return this.#source;
+  }
+
+  get ports(): readonly MessagePort[] {
+    return this.#ports;
+  }
+}
+
+// Copy a message for the receiving port
+function cloneMessage(data: unknown): unknown {
+  return __internal.structuredClone(data);
+}
+
+// Only MessageChannel creates ports
+let constructing = false;
+// Lets MessageChannel entangle the ports it creates
+let entangle: (port1: MessagePort, port2: MessagePort) => void;
+
+type MessageHandler = ((event: MessageEvent) => void) | null;
+
+// https://html.spec.whatwg.org/multipage/web-messaging.html#message-ports
+//
+// Messages are delivered as tasks, after pending promise jobs, and only once
+// the port is started by start() or by setting onmessage. Ports don't keep
+// the runtime alive while waiting for messages.
+class MessagePort extends EventTarget {
+  #other: MessagePort | null = null;
+  // Messages received but not dispatched yet
+  #queue: unknown[] = [];
+  #started = false;
+  #closed = false;
+  #onmessage: MessageHandler = null;
+  #onmessageerror: MessageHandler = null;
+
+  static {
+    entangle = (port1, port2) => {
+      port1.#other = port2;
+      port2.#other = port1;
+    };
+  }
+
+  constructor() {
+    if (!constructing) {
+      throw new TypeError("Illegal constructor");
+    }
+    super();
+  }
+
+  // Objects listed in `transfer` are copied like the rest of the message
+  // rather than moved
+  postMessage(
+    message: unknown,
+    _transfer?: Transferable[] | StructuredSerializeOptions,
+  ): void {
+    const data = cloneMessage(message);
+    const other = this.#other;
+    if (this.#closed || other === null || other.#closed) {
+      return;
+    }
+    other.#queue.push(data);
+    if (other.#started) {
+      other.#scheduleDelivery();
+    }
+  }
+
+  start(): void {
+    if (this.#started || this.#closed) {
+      return;
+    }
+    this.#started = true;
+    for (let i = 0; i < this.#queue.length; i++) {
+      this.#scheduleDelivery();
+    }
+  }
+
+  // Drop pending messages and disentangle both ports
+  close(): void {
+    this.#closed = true;
+    this.#queue.length = 0;
+    const other = this.#other;
+    this.#other = null;
+    if (other !== null) {
+      other.#other = null;
+    }
+  }
+
+  get onmessage(): MessageHandler {
+    return this.#onmessage;
+  }
+
+  set onmessage(handler: MessageHandler) {
+    if (this.#onmessage !== null) {
+      this.removeEventListener("message", this.#onmessage);
+    }
+    this.#onmessage = typeof handler === "function" ? handler : null;
+    if (this.#onmessage !== null) {
+      this.addEventListener("message", this.#onmessage);
+      this.start();
+    }
+  }
+
+  get onmessageerror(): MessageHandler {
+    return this.#onmessageerror;
+  }
+
+  set onmessageerror(handler: MessageHandler) {
+    if (this.#onmessageerror !== null) {
+      this.removeEventListener("messageerror", this.#onmessageerror);
+    }
+    this.#onmessageerror = typeof handler === "function" ? handler : null;
+    if (this.#onmessageerror !== null) {
+      this.addEventListener("messageerror", this.#onmessageerror);
+    }
+  }
+
+  // Dispatch the oldest queued message in a task of its own
+  #scheduleDelivery(): void {
+    __internal.queueTask(() => {
+      if (this.#closed || this.#queue.length === 0) {
+        return;
+      }
+      const data = this.#queue.shift();
+      this.dispatchEvent(new MessageEvent("message", { data }));
+    });
+  }
+}
+
+// https://html.spec.whatwg.org/multipage/web-messaging.html#message-channels
+class MessageChannel {
+  #port1: MessagePort;
+  #port2: MessagePort;
+
+  constructor() {
+    constructing = true;
+    try {
+      this.#port1 = new MessagePort();
+      this.#port2 = new MessagePort();
+    } finally {
+      constructing = false;
+    }
+    entangle(this.#port1, this.#port2);
+  }
+
+  get port1(): MessagePort {
+    return this.#port1;
+  }
+
+  get port2(): MessagePort {
+    return this.#port2;
+  }
+}
+
+for (const [name, value] of Object.entries({
+  MessageEvent,
+  MessagePort,
+  MessageChannel,
+})) {
+  Object.defineProperty(globalThis, name, {
+    value,
+    writable: true,
+    enumerable: false,
+    configurable: true,
+  });
+}
@@ -0,0 +1,101 @@
+function nextMessage(port: MessagePort): Promise<MessageEvent> {
+  return new Promise((resolve) => {
+    port.addEventListener("message", (event) => resolve(event as MessageEvent), {
+      once: true,
+    });
+    port.start();
+  });
+}
+
+Deno.test("MessageChannel delivers messages to the other port", async () => {
+  const { port1, port2 } = new MessageChannel();
+  const received = nextMessage(port2);
+  port1.postMessage({ text: "hello", list: [1, 2] });
+  const event = await received;
+  if (!(event instanceof MessageEvent) || event.target !== port2) {
+    throw new Error("expected a MessageEvent on port2");
+  }
+  if (event.data.text !== "hello" || event.data.list.join() !== "1,2") {
+    throw new Error(`unexpected data: ${JSON.stringify(event.data)}`);
+  }
+
+  const reply = nextMessage(port1);
+  port2.postMessage("back");
+  if ((await reply).data !== "back") throw new Error("reply not received");
+  port1.close();
+  port2.close();
+});
+
+Deno.test("MessagePort copies messages and delivers them after microtasks", async () => {
+  const { port1, port2 } = new MessageChannel();
+  const order: string[] = [];
+  const message = { count: 1 };
+  const received = new Promise<void>((resolve) => {
+    port2.onmessage = (event) => {
+      order.push(`message ${event.data.count}`);
+      if (event.data === message) throw new Error("message should be copied");
+      resolve();
+    };
+  });
+  port1.postMessage(message);
+  message.count = 2;
+  queueMicrotask(() => order.push("microtask"));
+  await received;
+  if (order.join() !== "microtask,message 1") throw new Error(`${order}`);
+  port1.close();
+});
+
+Deno.test("MessagePort queues messages until started", async () => {
+  const { port1, port2 } = new MessageChannel();
+  const seen: unknown[] = [];
+  port2.addEventListener("message", (event) => seen.push((event as MessageEvent).data));
+  port1.postMessage(1);
+  port1.postMessage(2);
+  await new Promise((resolve) => setTimeout(resolve, 0));
+  if (seen.length !== 0) throw new Error("delivered before start()");
+  const last = nextMessage(port2);
+  port2.start();
+  await last;
+  await new Promise((resolve) => setTimeout(resolve, 0));
+  if (seen.join() !== "1,2") throw new Error(`${seen}`);
+  port1.close();
+});
+
+Deno.test("MessagePort.close drops pending and later messages", async () => {
+  const { port1, port2 } = new MessageChannel();
+  let count = 0;
+  port2.onmessage = () => count++;
+  port1.postMessage("pending");
+  port2.close();
+  port1.postMessage("after close");
+  await new Promise((resolve) => setTimeout(resolve, 0));
+  if (count !== 0) throw new Error(`got ${count} messages`);
+});
+
+Deno.test("MessagePort rejects messages that can't be cloned", () => {
+  const { port1 } = new MessageChannel();
+  const cyclic: Record<string, unknown> = {};
+  cyclic.self = cyclic;
+  try {
+    port1.postMessage(cyclic);
+    throw new Error("should throw");
+  } catch (error) {
+    if (!(error instanceof DOMException) || error.name !== "DataCloneError") {
+      throw error;
+    }
+  }
+  port1.close();
+});
+
+Deno.test("MessagePort can't be constructed directly", () => {
+  try {
+    // @ts-expect-error: the constructor is illegal
+    new MessagePort();
+    throw new Error("should throw");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+  if (!(new MessageChannel().port1 instanceof EventTarget)) {
+    throw new Error("ports should be EventTargets");
+  }
+});
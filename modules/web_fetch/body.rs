@@ -0,0 +1,248 @@
+// Response bodies: buffered bytes, or chunks still arriving from the network
+use futures_util::lock::Mutex;
+use futures_util::{Stream, StreamExt};
+use rquickjs::{
+    Class, Ctx, Exception, Function, IntoJs, JsLifetime, Object, Promise, Result, TypedArray,
+    Value, class::Trace, prelude::This,
+};
+use std::cell::{Cell, RefCell};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Chunks of a body as they are read
+pub(crate) type ChunkStream = Pin<Box<dyn Stream<Item = std::result::Result<Vec<u8>, String>>>>;
+
+#[derive(Clone)]
+pub(crate) enum Body {
+    Buffered(Arc<Vec<u8>>),
+    /// Read from the network on demand
+    Streaming(Rc<BodyBranch>),
+}
+
+impl Body {
+    /// Read the whole body
+    pub(crate) async fn collect(self) -> std::result::Result<Arc<Vec<u8>>, String> {
+        match self {
+            Self::Buffered(bytes) => Ok(bytes),
+            Self::Streaming(branch) => {
+                let mut bytes = Vec::new();
+                while let Some(chunk) = branch.next().await? {
+                    bytes.extend_from_slice(&chunk);
+                }
+                Ok(Arc::new(bytes))
+            }
+        }
+    }
+
+    /// A second, independent reader of the same body, for `Response.clone()`
+    pub(crate) fn tee(&self) -> Self {
+        match self {
+            Self::Buffered(bytes) => Self::Buffered(Arc::clone(bytes)),
+            Self::Streaming(branch) => Self::Streaming(branch.tee()),
+        }
+    }
+
+    fn into_branch(self) -> Rc<BodyBranch> {
+        match self {
+            Self::Buffered(bytes) => {
+                let chunk = bytes.as_ref().clone();
+                BodyBranch::new(Box::pin(futures_util::stream::iter([Ok(chunk)])))
+            }
+            Self::Streaming(branch) => branch,
+        }
+    }
+}
+
+/// The network side of a streaming body, shared by every branch
+struct BodySource {
+    /// `None` once the stream has ended or failed
+    stream: Mutex<Option<ChunkStream>>,
+    /// Chunks pulled so far, kept for the other branches once the body has
+    /// been cloned
+    history: RefCell<Vec<Vec<u8>>>,
+    retain: Cell<bool>,
+}
+
+/// One reader's position in a streaming body
+pub(crate) struct BodyBranch {
+    source: Rc<BodySource>,
+    position: Cell<usize>,
+}
+
+impl BodyBranch {
+    pub(crate) fn new(stream: ChunkStream) -> Rc<Self> {
+        Rc::new(Self {
+            source: Rc::new(BodySource {
+                stream: Mutex::new(Some(stream)),
+                history: RefCell::new(Vec::new()),
+                retain: Cell::new(false),
+            }),
+            position: Cell::new(0),
+        })
+    }
+
+    fn tee(&self) -> Rc<Self> {
+        // Bodies can only be cloned before they are read, so both branches
+        // start at the first chunk
+        self.source.retain.set(true);
+        Rc::new(Self {
+            source: Rc::clone(&self.source),
+            position: Cell::new(self.position.get()),
+        })
+    }
+
+    /// The next chunk, or `None` at the end of the body
+    async fn next(&self) -> std::result::Result<Option<Vec<u8>>, String> {
+        if let Some(chunk) = self.next_from_history() {
+            return Ok(Some(chunk));
+        }
+        let mut stream = self.source.stream.lock().await;
+        // Another branch may have pulled the chunk while this one waited
+        if let Some(chunk) = self.next_from_history() {
+            return Ok(Some(chunk));
+        }
+        let Some(chunks) = stream.as_mut() else {
+            return Ok(None);
+        };
+        match chunks.next().await {
+            Some(Ok(chunk)) => {
+                if self.source.retain.get() {
+                    self.source.history.borrow_mut().push(chunk.clone());
+                    self.position.set(self.position.get() + 1);
+                }
+                Ok(Some(chunk))
+            }
+            Some(Err(error)) => {
+                *stream = None;
+                Err(error)
+            }
+            None => {
+                *stream = None;
+                Ok(None)
+            }
+        }
+    }
+
+    fn next_from_history(&self) -> Option<Vec<u8>> {
+        let position = self.position.get();
+        let chunk = self.source.history.borrow().get(position).cloned()?;
+        self.position.set(position + 1);
+        Some(chunk)
+    }
+
+    /// Stop reading, closing the connection unless another branch still needs it
+    fn cancel(&self) {
+        if !self.source.retain.get()
+            && let Some(mut stream) = self.source.stream.try_lock()
+        {
+            *stream = None;
+        }
+    }
+}
+
+/// `{ value, done }`, as resolved by `reader.read()`
+struct ReadResult(Option<Vec<u8>>);
+
+impl<'js> IntoJs<'js> for ReadResult {
+    fn into_js(self, ctx: &Ctx<'js>) -> Result<Value<'js>> {
+        let result = Object::new(ctx.clone())?;
+        result.set("done", self.0.is_none())?;
+        if let Some(chunk) = self.0 {
+            result.set("value", TypedArray::<u8>::new(ctx.clone(), chunk)?)?;
+        }
+        Ok(result.into_value())
+    }
+}
+
+/// Resolve to the next `{ value, done }` of `branch`
+fn read_chunk<'js>(ctx: &Ctx<'js>, branch: Rc<BodyBranch>) -> Result<Promise<'js>> {
+    let ctx_clone = ctx.clone();
+    Promise::wrap_future(ctx, async move {
+        match branch.next().await {
+            Ok(chunk) => Ok(ReadResult(chunk)),
+            Err(error) => Err(Exception::throw_type(&ctx_clone, &error)),
+        }
+    })
+}
+
+/// The native side of `response.body`: chunks of a body, read by the
+/// `ReadableStream` that `web_streams` builds around it
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct BodyStream {
+    #[qjs(skip_trace)]
+    branch: Rc<BodyBranch>,
+    #[qjs(skip_trace)]
+    locked: bool,
+}
+
+impl BodyStream {
+    pub(crate) fn new(body: Body) -> Self {
+        Self {
+            branch: body.into_branch(),
+            locked: false,
+        }
+    }
+}
+
+#[rquickjs::methods]
+impl BodyStream {
+    #[qjs(rename = "getReader")]
+    pub fn get_reader<'js>(
+        ctx: Ctx<'js>,
+        this: This<Class<'js, Self>>,
+    ) -> Result<Class<'js, BodyReader>> {
+        let mut stream = this.0.borrow_mut();
+        if stream.locked {
+            return Err(Exception::throw_type(&ctx, "Body is already being read"));
+        }
+        stream.locked = true;
+        let reader = BodyReader {
+            branch: Rc::clone(&stream.branch),
+        };
+        Class::instance(ctx, reader)
+    }
+
+    pub fn cancel<'js>(&self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        self.branch.cancel();
+        Promise::wrap_future(&ctx, async {})
+    }
+}
+
+/// Reads a [`BodyStream`] chunk by chunk
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct BodyReader {
+    #[qjs(skip_trace)]
+    branch: Rc<BodyBranch>,
+}
+
+#[rquickjs::methods]
+impl BodyReader {
+    pub fn read<'js>(&self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        read_chunk(&ctx, Rc::clone(&self.branch))
+    }
+
+    pub fn cancel<'js>(&self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        self.branch.cancel();
+        Promise::wrap_future(&ctx, async {})
+    }
+}
+
+/// A `ReadableStream` over `body`, for `response.body` and `request.body`
+pub(crate) fn readable_stream<'js>(ctx: &Ctx<'js>, body: Body) -> Result<Value<'js>> {
+    let source = Class::instance(ctx.clone(), BodyStream::new(body))?;
+    let from_body: Function =
+        ctx.eval("globalThis[Symbol.for('mdeno.internal')].streams.fromBody")?;
+    from_body.call((source,))
+}
+
+/// Whether `stream` is locked to a reader, or has been read from or cancelled
+pub(crate) fn is_locked_or_disturbed(stream: &Value<'_>) -> bool {
+    stream
+        .ctx()
+        .eval::<Function, _>("globalThis[Symbol.for('mdeno.internal')].streams.isLockedOrDisturbed")
+        .and_then(|check| check.call((stream.clone(),)))
+        .unwrap_or(false)
+}
@@ -1,59 +1,351 @@
-use crate::response::Response;
+use crate::body::{Body, BodyBranch};
+use crate::headers::Headers;
+use crate::request::{Request, RequestParts};
+use crate::response::{Response, body_bytes};
+use futures_util::StreamExt;
+use futures_util::future::{Abortable, Aborted};
 use rquickjs::{Class, Ctx, prelude::*};
-use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use utils::class_of;
+use web_abort::AbortSignal;
+
+/// Bodies with a known length up to this size are read before `fetch()`
+/// resolves; anything larger or of unknown length is streamed
+static EAGER_BUFFER_LIMIT: AtomicU64 = AtomicU64::new(1024 * 1024);
+
+/// Set the largest response body, in bytes, that `fetch()` buffers eagerly
+pub fn set_eager_buffer_limit(bytes: u64) {
+    EAGER_BUFFER_LIMIT.store(bytes, Ordering::Relaxed);
+}
+
+/// Request body, as given in `fetch()` options
+#[derive(Debug, Clone)]
+pub enum FetchBody {
+    /// A string, sent as UTF-8 text
+    Text(String),
+    /// An `ArrayBuffer` or `Uint8Array`, sent as is
+    Bytes(Arc<Vec<u8>>),
+    /// `URLSearchParams`, already serialized with `toString()`
+    UrlSearchParams(String),
+    /// `FormData` entries as name/value pairs
+    FormData(Vec<(String, String)>),
+}
+
+static BOUNDARY_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+impl FetchBody {
+    fn from_js<'js>(ctx: &Ctx<'js>, value: &rquickjs::Value<'js>) -> rquickjs::Result<Self> {
+        if let Some(text) = value.as_string() {
+            return Ok(Self::Text(text.to_string()?));
+        }
+        if let Some(obj) = value.as_object() {
+            match type_name(obj).as_deref() {
+                Some("URLSearchParams") => {
+                    let text: Coerced<String> = value.get()?;
+                    return Ok(Self::UrlSearchParams(text.0));
+                }
+                Some("FormData") => {
+                    let from: rquickjs::Function = ctx
+                        .globals()
+                        .get::<_, rquickjs::Object>("Array")?
+                        .get("from")?;
+                    let entries: Vec<Vec<Coerced<String>>> = from.call((obj.clone(),))?;
+                    let fields = entries
+                        .into_iter()
+                        .filter_map(|entry| {
+                            let mut entry = entry.into_iter();
+                            Some((entry.next()?.0, entry.next()?.0))
+                        })
+                        .collect();
+                    return Ok(Self::FormData(fields));
+                }
+                _ => {}
+            }
+        }
+        Ok(Self::Bytes(Arc::new(body_bytes(value)?)))
+    }
+
+    /// The bytes to send and the `Content-Type` they imply
+    fn encode(self) -> (Arc<Vec<u8>>, Option<String>) {
+        match self {
+            Self::Text(text) => (
+                Arc::new(text.into_bytes()),
+                Some("text/plain;charset=UTF-8".to_string()),
+            ),
+            Self::Bytes(bytes) => (bytes, None),
+            Self::UrlSearchParams(query) => (
+                Arc::new(query.into_bytes()),
+                Some("application/x-www-form-urlencoded;charset=UTF-8".to_string()),
+            ),
+            Self::FormData(fields) => {
+                let nanos = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.subsec_nanos());
+                let count = BOUNDARY_COUNTER.fetch_add(1, Ordering::Relaxed);
+                let boundary = format!("----mdenoFormBoundary{nanos:08x}{count:08x}");
+                let body = encode_multipart(&fields, &boundary);
+                (
+                    Arc::new(body),
+                    Some(format!("multipart/form-data; boundary={boundary}")),
+                )
+            }
+        }
+    }
+}
+
+/// `Symbol.toStringTag`, falling back to the constructor name for classes
+/// that don't define one
+fn type_name(obj: &rquickjs::Object<'_>) -> Option<String> {
+    if let Ok(Some(tag)) =
+        obj.get::<_, Option<String>>(rquickjs::atom::PredefinedAtom::SymbolToStringTag)
+    {
+        return Some(tag);
+    }
+    obj.get::<_, rquickjs::Object>("constructor")
+        .and_then(|constructor| constructor.get::<_, String>("name"))
+        .ok()
+}
+
+/// Serialize `FormData` fields as a `multipart/form-data` body
+fn encode_multipart(fields: &[(String, String)], boundary: &str) -> Vec<u8> {
+    let mut body = String::new();
+    for (name, value) in fields {
+        // Escaped the way browsers do for field names
+        let name = name
+            .replace('"', "%22")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A");
+        let _ = write!(
+            body,
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+        );
+    }
+    let _ = write!(body, "--{boundary}--\r\n");
+    body.into_bytes()
+}
 
 // Fetch options structure
-#[derive(Debug, Clone, Default)]
-pub struct FetchOptions {
+#[derive(Clone, Default)]
+pub struct FetchOptions<'js> {
     pub method: Option<String>,
+    /// Lowercased request headers, in the order given
+    pub headers: Option<Vec<(String, String)>>,
+    pub body: Option<FetchBody>,
+    /// Decode gzip/deflate/br response bodies (default: true)
+    pub decompress: Option<bool>,
+    /// Require HTTP/2 instead of negotiating it (default: negotiate)
+    pub http2: Option<bool>,
+    pub signal: Option<Class<'js, AbortSignal<'js>>>,
 }
 
-impl<'js> rquickjs::FromJs<'js> for FetchOptions {
-    fn from_js(_ctx: &rquickjs::Ctx<'js>, value: rquickjs::Value<'js>) -> rquickjs::Result<Self> {
+impl<'js> rquickjs::FromJs<'js> for FetchOptions<'js> {
+    fn from_js(ctx: &rquickjs::Ctx<'js>, value: rquickjs::Value<'js>) -> rquickjs::Result<Self> {
         if let Some(obj) = value.as_object() {
             let method = obj.get::<_, Option<String>>("method").ok().flatten();
-            Ok(FetchOptions { method })
+            let headers = obj
+                .get::<_, rquickjs::Object>("headers")
+                .ok()
+                .map(|init| Headers::new(Opt(Some(init))).headers);
+            let body = match obj.get::<_, rquickjs::Value>("body") {
+                Ok(body) if !body.is_undefined() && !body.is_null() => {
+                    Some(FetchBody::from_js(ctx, &body)?)
+                }
+                _ => None,
+            };
+            let decompress = obj.get::<_, Option<bool>>("decompress").ok().flatten();
+            let http2 = obj.get::<_, Option<bool>>("http2").ok().flatten();
+            let signal = match obj.get::<_, rquickjs::Value>("signal") {
+                Ok(signal) if !signal.is_undefined() && !signal.is_null() => {
+                    let Some(signal) = class_of::<AbortSignal>(&signal) else {
+                        return Err(rquickjs::Exception::throw_type(
+                            ctx,
+                            "signal must be an AbortSignal",
+                        ));
+                    };
+                    Some(signal)
+                }
+                _ => None,
+            };
+            Ok(FetchOptions {
+                method,
+                headers,
+                body,
+                decompress,
+                http2,
+                signal,
+            })
         } else {
             Ok(FetchOptions::default())
         }
     }
 }
 
-pub async fn fetch(
-    ctx: Ctx<'_>,
-    url: String,
-    options: Opt<FetchOptions>,
-) -> rquickjs::Result<Class<'_, Response<'_>>> {
-    // Extract method from options, default to GET
-    let method = options
-        .0
-        .and_then(|opts| opts.method)
-        .unwrap_or_else(|| "GET".to_string());
-
-    // Perform the request
-    let (status, headers_map, body) = fetch_request(&url, &method)
-        .await
-        .map_err(|_e| rquickjs::Error::Unknown)?;
+pub async fn fetch<'js>(
+    ctx: Ctx<'js>,
+    input: rquickjs::Value<'js>,
+    options: Opt<FetchOptions<'js>>,
+) -> rquickjs::Result<Class<'js, Response<'js>>> {
+    let options = options.0.unwrap_or_default();
+    if let Some(signal) = &options.signal {
+        web_abort::check_timeout(&ctx, signal)?;
+        if signal.borrow().is_aborted() {
+            return Err(throw_reason(&ctx, signal));
+        }
+    }
+    // A request supplies the defaults that the options don't override
+    let RequestParts {
+        url,
+        method,
+        headers,
+        body: request_body,
+    } = if let Some(request) = class_of::<Request>(&input) {
+        request.borrow_mut().take_for_fetch(&ctx)?
+    } else {
+        let Coerced(url) = Coerced::<String>::from_js(&ctx, input)?;
+        RequestParts {
+            url,
+            method: "GET".to_string(),
+            headers: Vec::new(),
+            body: None,
+        }
+    };
+    if url.starts_with("blob:") {
+        return fetch_blob(ctx, &url);
+    }
+
+    let method = options.method.unwrap_or(method);
+    let decompress = options.decompress.unwrap_or(true);
+    // HTTP/2 is negotiated through TLS ALPN; cleartext would need prior
+    // knowledge, which the client doesn't offer
+    let version = if options.http2 == Some(true) {
+        if !url.starts_with("https://") {
+            return Err(rquickjs::Exception::throw_type(
+                &ctx,
+                &format!("HTTP/2 can only be required for https:// URLs: {url}"),
+            ));
+        }
+        Some(http::Version::HTTP_2)
+    } else {
+        None
+    };
+    let (body, content_type) = match options.body {
+        Some(body) => {
+            let (bytes, content_type) = body.encode();
+            (Some(bytes), content_type)
+        }
+        None => match request_body {
+            Some(body) => (
+                Some(
+                    body.collect()
+                        .await
+                        .map_err(|error: String| rquickjs::Exception::throw_type(&ctx, &error))?,
+                ),
+                None,
+            ),
+            None => (None, None),
+        },
+    };
+
+    let mut headers = options.headers.unwrap_or(headers);
+    // The body's own type applies unless the caller chose one
+    if let Some(content_type) = content_type
+        && !headers.iter().any(|(name, _)| name == "content-type")
+    {
+        headers.push(("content-type".to_string(), content_type));
+    }
+
+    // Perform the request, dropping it if the signal aborts
+    let request = fetch_request(&url, method, headers, body, decompress, version);
+    let result = match &options.signal {
+        Some(signal) => {
+            let (handle, registration) = signal.borrow_mut().register();
+            let deadline = signal.borrow().deadline();
+            let request = Abortable::new(request, registration);
+            let outcome = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    compio::time::timeout(remaining, request).await.ok()
+                }
+                None => Some(request.await),
+            };
+            handle.abort();
+            match outcome {
+                Some(Ok(result)) => result,
+                Some(Err(Aborted)) => return Err(throw_reason(&ctx, signal)),
+                None => {
+                    web_abort::time_out(&ctx, signal)?;
+                    return Err(throw_reason(&ctx, signal));
+                }
+            }
+        }
+        None => request.await,
+    };
+    let (status, headers, body) = result.map_err(|_e| rquickjs::Error::Unknown)?;
 
     // Return Response instance directly
-    let response = Response::from_fetch(ctx, status, headers_map, body)?;
+    let response = Response::from_fetch(ctx, status, headers, body)?;
     Ok(response)
 }
 
-// Global HTTP client
+/// Throw the reason an aborted signal gave
+fn throw_reason<'js>(ctx: &Ctx<'js>, signal: &Class<'js, AbortSignal<'js>>) -> rquickjs::Error {
+    let reason = signal.borrow().abort_reason();
+    match reason {
+        Some(reason) => ctx.throw(reason),
+        None => rquickjs::Error::Unknown,
+    }
+}
+
+/// Respond with the contents of a blob registered by `URL.createObjectURL()`
+fn fetch_blob<'js>(ctx: Ctx<'js>, url: &str) -> rquickjs::Result<Class<'js, Response<'js>>> {
+    let Some(blob) = web_blob::resolve_object_url(url) else {
+        return Err(rquickjs::Exception::throw_type(
+            &ctx,
+            &format!("Blob URL not found: {url}"),
+        ));
+    };
+    let data = blob.data();
+    let headers = vec![
+        ("content-length".to_string(), data.len().to_string()),
+        ("content-type".to_string(), blob.mime_type().to_string()),
+    ];
+    Response::from_fetch(ctx, 200, headers, Body::Buffered(data))
+}
+
+// Global HTTP client. Built with cyper's `http2` feature, it offers "h2"
+// through ALPN and uses HTTP/2 whenever an https:// server accepts it.
 static HTTP_CLIENT: std::sync::LazyLock<cyper::Client> =
     std::sync::LazyLock::new(|| cyper::ClientBuilder::new().build());
 
+/// Headers that describe the request body, removed when a redirect drops it
+const BODY_HEADERS: [&str; 4] = [
+    "content-encoding",
+    "content-language",
+    "content-location",
+    "content-type",
+];
+
 async fn fetch_request(
     url: &str,
-    method: &str,
-) -> Result<(u16, HashMap<String, String>, String), String> {
+    method: String,
+    mut request_headers: Vec<(String, String)>,
+    mut request_body: Option<Arc<Vec<u8>>>,
+    decompress: bool,
+    version: Option<http::Version>,
+) -> Result<(u16, Vec<(String, String)>, Body), String> {
     const MAX_REDIRECTS: usize = 20; // Same as fetch spec
     let mut current_url = url.to_string();
+    let mut method = method.to_uppercase();
+    let has_header =
+        |headers: &[(String, String)], name: &str| headers.iter().any(|(key, _)| key == name);
 
     for redirect_count in 0..=MAX_REDIRECTS {
         // Call cyper directly - the patched waker should maintain the runtime context
-        let response = match method.to_uppercase().as_str() {
+        let mut request = match method.as_str() {
             "GET" => HTTP_CLIENT.get(&current_url),
             "POST" => HTTP_CLIENT.post(&current_url),
             "PUT" => HTTP_CLIENT.put(&current_url),
@@ -62,12 +354,37 @@ async fn fetch_request(
             "HEAD" => HTTP_CLIENT.head(&current_url),
             _ => return Err(format!("Unsupported HTTP method: {method}")),
         }
-        .map_err(|e| format!("Failed to create request: {e}"))?
-        .header("User-Agent", "mdeno/0.1.0")
-        .map_err(|e| format!("Failed to set header: {e}"))?
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {e:?}"))?;
+        .map_err(|e| format!("Failed to create request: {e}"))?;
+        for (name, value) in &request_headers {
+            request = request
+                .header(name.as_str(), value.as_str())
+                .map_err(|e| format!("Failed to set header: {e}"))?;
+        }
+        if !has_header(&request_headers, "user-agent") {
+            request = request
+                .header("User-Agent", "mdeno/0.1.0")
+                .map_err(|e| format!("Failed to set header: {e}"))?;
+        }
+        let request = match &request_body {
+            // Copied per attempt, as a 307/308 redirect sends the body again
+            Some(body) => request.body(body.as_ref().clone()),
+            None => request,
+        };
+        let request = match version {
+            Some(version) => request.version(version),
+            None => request,
+        };
+        let request = if decompress && !has_header(&request_headers, "accept-encoding") {
+            request
+                .header("Accept-Encoding", "gzip, deflate, br")
+                .map_err(|e| format!("Failed to set header: {e}"))?
+        } else {
+            request
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {e:?}"))?;
 
         let status = response.status().as_u16();
 
@@ -89,28 +406,124 @@ async fn fetch_request(
                     current_url = absolute.href().to_string();
                 }
 
-                // POST/PUT/PATCH redirects should change to GET (except 307/308)
-                // TODO: Handle this properly if needed
+                // 301, 302 and 303 drop the body; 307 and 308 resend the
+                // request as it was
+                if matches!(status, 301..=303) {
+                    if status == 303 && method != "HEAD" || method == "POST" {
+                        method = "GET".to_string();
+                    }
+                    request_body = None;
+                    request_headers.retain(|(name, _)| !BODY_HEADERS.contains(&name.as_str()));
+                }
 
                 continue; // Follow redirect
             }
         }
 
-        // Not a redirect or no Location header - return this response
-        let mut headers_map = HashMap::new();
+        // Not a redirect or no Location header - return this response.
+        // Repeated headers (e.g. Set-Cookie) are kept as separate entries,
+        // including those of every HTTP/2 HEADERS and CONTINUATION frame.
+        let mut headers = Vec::new();
         for (key, value) in response.headers() {
             if let Ok(value_str) = value.to_str() {
-                headers_map.insert(key.as_str().to_lowercase(), value_str.to_string());
+                headers.push((key.as_str().to_lowercase(), value_str.to_string()));
             }
         }
 
-        let body = response
-            .text()
+        let encoding = headers
+            .iter()
+            .find(|(key, _)| key == "content-encoding")
+            .map(|(_, value)| value.trim().to_lowercase())
+            .filter(|encoding| decompress && encoding != "identity");
+        // Encoded bodies are decoded as a whole, so they are always buffered
+        let buffer = encoding.is_some()
+            || response
+                .content_length()
+                .is_some_and(|len| len <= EAGER_BUFFER_LIMIT.load(Ordering::Relaxed));
+        if !buffer {
+            let chunks = response.bytes_stream().map(|chunk| {
+                chunk
+                    .map(|bytes| bytes.to_vec())
+                    .map_err(|e| format!("Failed to read body: {e}"))
+            });
+            let body = Body::Streaming(BodyBranch::new(Box::pin(chunks)));
+            return Ok((status, headers, body));
+        }
+
+        let bytes = response
+            .bytes()
             .await
             .map_err(|e| format!("Failed to read body: {e}"))?;
+        let body = match encoding {
+            Some(encoding) => {
+                let decoded = decode_body(&bytes, &encoding)?;
+                // The body no longer matches these, as with other fetch implementations
+                headers.retain(|(key, _)| key != "content-encoding" && key != "content-length");
+                decoded
+            }
+            None => bytes.to_vec(),
+        };
 
-        return Ok((status, headers_map, body));
+        return Ok((status, headers, Body::Buffered(Arc::new(body))));
     }
 
     Err(format!("Too many redirects (exceeded {MAX_REDIRECTS})"))
 }
+
+/// Undo a `Content-Encoding`, applying a list like "gzip, br" in reverse order
+fn decode_body(bytes: &[u8], encoding: &str) -> Result<Vec<u8>, String> {
+    let mut body = bytes.to_vec();
+    for coding in encoding.rsplit(',').map(str::trim) {
+        let mut decoded = Vec::new();
+        let result = match coding {
+            "gzip" | "x-gzip" => {
+                flate2::read::GzDecoder::new(body.as_slice()).read_to_end(&mut decoded)
+            }
+            "deflate" => flate2::read::ZlibDecoder::new(body.as_slice()).read_to_end(&mut decoded),
+            "br" => brotli::Decompressor::new(body.as_slice(), 4096).read_to_end(&mut decoded),
+            "identity" | "" => continue,
+            _ => return Err(format!("Unsupported Content-Encoding: {coding}")),
+        };
+        result.map_err(|e| format!("Failed to decode {coding} body: {e}"))?;
+        body = decoded;
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test code: unwrap is acceptable
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_decode_body() {
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(b"{\"ok\":true}").unwrap();
+        let gzip = gzip.finish().unwrap();
+        assert_eq!(decode_body(&gzip, "gzip").unwrap(), b"{\"ok\":true}");
+
+        let mut br = Vec::new();
+        brotli::CompressorWriter::new(&mut br, 4096, 5, 22)
+            .write_all(b"hello")
+            .unwrap();
+        assert_eq!(decode_body(&br, "br").unwrap(), b"hello");
+
+        assert!(decode_body(b"data", "zstd").is_err());
+    }
+
+    #[test]
+    fn test_encode_multipart() {
+        let fields = vec![
+            ("name".to_string(), "mdeno".to_string()),
+            ("a\"b".to_string(), "1".to_string()),
+        ];
+        let body = encode_multipart(&fields, "XYZ");
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "--XYZ\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nmdeno\r\n\
+             --XYZ\r\nContent-Disposition: form-data; name=\"a%22b\"\r\n\r\n1\r\n\
+             --XYZ--\r\n"
+        );
+    }
+}
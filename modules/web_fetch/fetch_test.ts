@@ -0,0 +1,139 @@
+const encoder = new TextEncoder();
+
+// Answer one request with a raw HTTP/1.1 response
+async function serveOnce(listener: Deno.Listener, response: string) {
+  const conn = await listener.accept();
+  await conn.read(new Uint8Array(4096));
+  await conn.write(encoder.encode(response));
+  conn.close();
+}
+
+Deno.test("fetch keeps every value of a repeated header", async () => {
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 0 });
+  const served = serveOnce(
+    listener,
+    "HTTP/1.1 200 OK\r\nx-frame: 1\r\nx-frame: 2\r\ncontent-length: 2\r\n" +
+      "connection: close\r\n\r\nok",
+  );
+  const response = await fetch(`http://127.0.0.1:${listener.addr.port}/`);
+  if (response.headers.get("x-frame") !== "1, 2") {
+    throw new Error(`x-frame: ${response.headers.get("x-frame")}`);
+  }
+  if ((await response.text()) !== "ok") throw new Error("body");
+  await served;
+  listener.close();
+});
+
+Deno.test("fetch requires https:// to force HTTP/2", async () => {
+  try {
+    await fetch("http://127.0.0.1:1/", { http2: true } as RequestInit);
+    throw new Error("should reject cleartext HTTP/2");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+});
+
+// Echo the method, Content-Type and body of each request; /303 and /307
+// redirect to /echo with that status
+function serveEcho(): Deno.HttpServer<Deno.NetAddr> {
+  return Deno.serve({ hostname: "127.0.0.1", port: 0 }, async (request) => {
+    const { pathname } = new URL(request.url);
+    if (pathname !== "/echo") {
+      return new Response(null, {
+        status: Number(pathname.slice(1)),
+        headers: { location: "/echo" },
+      });
+    }
+    const type = request.headers.get("content-type") ?? "none";
+    return new Response(`${request.method} ${type} ${await request.text()}`);
+  });
+}
+
+Deno.test("fetch sends string, Uint8Array and URLSearchParams bodies", async () => {
+  const server = serveEcho();
+  const url = `http://127.0.0.1:${server.addr.port}/echo`;
+  const cases: [RequestInit, string][] = [
+    [{ method: "POST", body: "hi" }, "POST text/plain;charset=UTF-8 hi"],
+    [
+      {
+        method: "PUT",
+        body: "{}",
+        headers: { "Content-Type": "application/json" },
+      },
+      "PUT application/json {}",
+    ],
+    [
+      { method: "PATCH", body: new URLSearchParams({ a: "1", b: "x y" }) },
+      "PATCH application/x-www-form-urlencoded;charset=UTF-8 a=1&b=x+y",
+    ],
+    [
+      { method: "POST", body: new TextEncoder().encode("raw") },
+      "POST none raw",
+    ],
+  ];
+  for (const [init, expected] of cases) {
+    const text = await (await fetch(url, init)).text();
+    if (text !== expected) throw new Error(`${init.method}: ${text}`);
+  }
+  await server.shutdown();
+});
+
+Deno.test("fetch drops the body on 303 but keeps it on 307", async () => {
+  const server = serveEcho();
+  const base = `http://127.0.0.1:${server.addr.port}`;
+  const seeOther = await fetch(`${base}/303`, { method: "POST", body: "hi" });
+  if ((await seeOther.text()) !== "GET none ") throw new Error("303");
+  const temporary = await fetch(`${base}/307`, { method: "POST", body: "hi" });
+  if ((await temporary.text()) !== "POST text/plain;charset=UTF-8 hi") {
+    throw new Error("307");
+  }
+  await server.shutdown();
+});
+
+Deno.test("fetch sends a Request, letting init override it", async () => {
+  const server = serveEcho();
+  const url = `http://127.0.0.1:${server.addr.port}/echo`;
+  const request = new Request(url, {
+    method: "PUT",
+    headers: { "content-type": "application/json" },
+    body: "{}",
+  });
+  const sent = await (await fetch(request)).text();
+  if (sent !== "PUT application/json {}") throw new Error(`sent: ${sent}`);
+  if (!request.bodyUsed) throw new Error("bodyUsed");
+  try {
+    await fetch(request);
+    throw new Error("should reject a used body");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+
+  const overridden = await fetch(new Request(url), { method: "DELETE" });
+  if ((await overridden.text()) !== "DELETE none ") throw new Error("init");
+  await server.shutdown();
+});
+
+Deno.test("fetch rejects with the reason of an aborted signal", async () => {
+  const server = Deno.serve({ hostname: "127.0.0.1", port: 0 }, async () => {
+    await new Promise((resolve) => setTimeout(resolve, 200));
+    return new Response("late");
+  });
+  const url = `http://127.0.0.1:${server.addr.port}/`;
+  const controller = new AbortController();
+  setTimeout(() => controller.abort(), 10);
+  for (
+    const [signal, name] of [
+      [controller.signal, "AbortError"],
+      [AbortSignal.timeout(10), "TimeoutError"],
+      [AbortSignal.abort(), "AbortError"],
+    ] as const
+  ) {
+    try {
+      await fetch(url, { signal });
+      throw new Error("should reject");
+    } catch (error) {
+      if ((error as Error).name !== name) throw error;
+    }
+  }
+  await server.shutdown();
+});
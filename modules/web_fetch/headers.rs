@@ -1,43 +1,81 @@
 use rquickjs::{Array, Ctx, JsLifetime, Object, Result, class::Trace, prelude::*};
-use std::collections::HashMap;
+use utils::class_of;
 
 // Headers class
-#[derive(Clone, Trace, JsLifetime)]
+#[derive(Clone, Default, Trace, JsLifetime)]
 #[rquickjs::class]
 pub struct Headers {
+    /// Lowercased name-value pairs in insertion order. A name may repeat,
+    /// e.g. one entry per `Set-Cookie` header.
     #[qjs(skip_trace)]
-    pub(crate) headers: HashMap<String, String>,
+    pub(crate) headers: Vec<(String, String)>,
 }
 
 #[rquickjs::methods]
 impl Headers {
     #[qjs(constructor)]
     pub fn new(init: Opt<Object<'_>>) -> Self {
-        let mut headers = HashMap::new();
+        let mut headers = Vec::new();
 
         if let Some(obj) = init.0 {
-            for (key, value) in obj.props::<String, String>().flatten() {
-                headers.insert(key.to_lowercase(), value);
+            if let Some(other) = class_of::<Headers>(&obj.clone().into_value()) {
+                return other.borrow().clone();
+            }
+            if let Some(pairs) = obj.as_array() {
+                // [[name, value], ...]
+                for pair in pairs.iter::<Vec<String>>().flatten() {
+                    if let [name, value] = pair.as_slice() {
+                        headers.push((name.to_lowercase(), value.clone()));
+                    }
+                }
+            } else {
+                for (key, value) in obj.props::<String, String>().flatten() {
+                    headers.push((key.to_lowercase(), value));
+                }
             }
         }
 
         Headers { headers }
     }
 
+    /// All values for `name` joined with ", ", as the Fetch spec combines them
     pub fn get(&self, name: String) -> Option<String> {
-        self.headers.get(&name.to_lowercase()).cloned()
+        let values = self.values_of(&name);
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.join(", "))
+        }
+    }
+
+    #[qjs(rename = "getAll")]
+    pub fn get_all(&self, name: String) -> Vec<String> {
+        self.values_of(&name)
+    }
+
+    #[qjs(rename = "getSetCookie")]
+    pub fn get_set_cookie(&self) -> Vec<String> {
+        self.values_of("set-cookie")
+    }
+
+    pub fn append(&mut self, name: String, value: String) {
+        self.headers.push((name.to_lowercase(), value));
     }
 
     pub fn set(&mut self, name: String, value: String) {
-        self.headers.insert(name.to_lowercase(), value);
+        let name = name.to_lowercase();
+        self.headers.retain(|(key, _)| *key != name);
+        self.headers.push((name, value));
     }
 
     pub fn has(&self, name: String) -> bool {
-        self.headers.contains_key(&name.to_lowercase())
+        let name = name.to_lowercase();
+        self.headers.iter().any(|(key, _)| *key == name)
     }
 
     pub fn delete(&mut self, name: String) {
-        self.headers.remove(&name.to_lowercase());
+        let name = name.to_lowercase();
+        self.headers.retain(|(key, _)| *key != name);
     }
 
     pub fn entries<'js>(&self, ctx: Ctx<'js>) -> Result<Array<'js>> {
@@ -53,7 +91,7 @@ impl Headers {
 
     pub fn keys<'js>(&self, ctx: Ctx<'js>) -> Result<Array<'js>> {
         let array = Array::new(ctx)?;
-        for (i, key) in self.headers.keys().enumerate() {
+        for (i, (key, _)) in self.headers.iter().enumerate() {
             array.set(i, key.clone())?;
         }
         Ok(array)
@@ -61,9 +99,20 @@ impl Headers {
 
     pub fn values<'js>(&self, ctx: Ctx<'js>) -> Result<Array<'js>> {
         let array = Array::new(ctx)?;
-        for (i, value) in self.headers.values().enumerate() {
+        for (i, (_, value)) in self.headers.iter().enumerate() {
             array.set(i, value.clone())?;
         }
         Ok(array)
     }
 }
+
+impl Headers {
+    fn values_of(&self, name: &str) -> Vec<String> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .filter(|(key, _)| *key == name)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,28 @@
+Deno.test("Headers.append keeps every value", () => {
+  const headers = new Headers({ "Set-Cookie": "a=1" });
+  headers.append("set-cookie", "b=2");
+  if (headers.get("Set-Cookie") !== "a=1, b=2") throw new Error("get");
+  const cookies = headers.getSetCookie();
+  if (cookies.length !== 2 || cookies[1] !== "b=2") throw new Error("getSetCookie");
+});
+
+Deno.test("Headers.set and delete replace every value", () => {
+  const headers = new Headers([["Accept", "a"], ["accept", "b"]]);
+  headers.set("ACCEPT", "c");
+  if (headers.keys().length !== 1 || headers.get("accept") !== "c") {
+    throw new Error("set");
+  }
+  headers.delete("Accept");
+  if (headers.has("accept") || headers.get("accept") != null) {
+    throw new Error("delete");
+  }
+});
+
+Deno.test("Headers copies another Headers instance", () => {
+  const original = new Headers({ "X-Frame": "1" });
+  const copy = new Headers(original);
+  copy.set("x-frame", "2");
+  if (original.get("x-frame") !== "1" || copy.get("x-frame") !== "2") {
+    throw new Error("copy should be independent");
+  }
+});
@@ -1,8 +1,12 @@
+mod body;
 mod fetch;
 mod headers;
+mod request;
 mod response;
 
+pub use fetch::set_eager_buffer_limit;
 use headers::Headers;
+use request::Request;
 use response::Response;
 
 use rquickjs::{
@@ -16,6 +20,9 @@ pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
     // Register Headers class
     Class::<Headers>::define(&ctx.globals())?;
 
+    // Register Request class
+    Class::<Request>::define(&ctx.globals())?;
+
     // Register Response class
     Class::<Response>::define(&ctx.globals())?;
 
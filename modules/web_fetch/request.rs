@@ -0,0 +1,308 @@
+use crate::body::{self, Body};
+use crate::headers::Headers;
+use crate::response::body_bytes;
+use rquickjs::{
+    ArrayBuffer, Class, Ctx, Exception, IntoJs, JsLifetime, Object, Promise, Result, Value,
+    class::Trace, prelude::*,
+};
+use std::sync::Arc;
+use utils::class_of;
+use web_blob::Blob;
+
+// Request class
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct Request<'js> {
+    #[qjs(skip_trace)]
+    url: String,
+    #[qjs(skip_trace)]
+    method: String,
+    headers: Class<'js, Headers>,
+    /// `None` for a null body
+    #[qjs(skip_trace)]
+    body: Option<Body>,
+    /// `request.body`, created on first access
+    body_stream: Option<Value<'js>>,
+    #[qjs(skip_trace)]
+    body_used: bool,
+    /// `redirect`, `credentials`, `mode`, `cache` and `referrer`, kept as given
+    #[qjs(skip_trace)]
+    options: RequestOptions,
+}
+
+/// The parts of a request that `fetch()` sends
+pub(crate) struct RequestParts {
+    pub(crate) url: String,
+    pub(crate) method: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Option<Body>,
+}
+
+/// The init options that only describe the request
+#[derive(Clone)]
+struct RequestOptions {
+    redirect: String,
+    credentials: String,
+    mode: String,
+    cache: String,
+    referrer: String,
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        RequestOptions {
+            redirect: "follow".to_string(),
+            credentials: "same-origin".to_string(),
+            mode: "cors".to_string(),
+            cache: "default".to_string(),
+            referrer: "about:client".to_string(),
+        }
+    }
+}
+
+#[rquickjs::methods]
+impl<'js> Request<'js> {
+    /// `new Request(input, init?)`, where `input` is a URL or another request
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>, input: Value<'js>, init: Opt<Object<'js>>) -> Result<Self> {
+        let (url, mut method, mut headers, mut body, mut options) =
+            if let Some(other) = class_of::<Request>(&input) {
+                let other = other.borrow();
+                if other.body_used() {
+                    return Err(Exception::throw_type(
+                        &ctx,
+                        "Cannot construct a Request from one whose body has been consumed",
+                    ));
+                }
+                (
+                    other.url.clone(),
+                    other.method.clone(),
+                    other.headers.borrow().clone(),
+                    other.body.as_ref().map(Body::tee),
+                    other.options.clone(),
+                )
+            } else {
+                let Coerced(url) = Coerced::<String>::from_js(&ctx, input)?;
+                (
+                    url,
+                    "GET".to_string(),
+                    Headers::default(),
+                    None,
+                    RequestOptions::default(),
+                )
+            };
+
+        if let Some(init) = init.0 {
+            if let Some(value) = init.get::<_, Option<String>>("method")? {
+                method = normalize_method(value);
+            }
+            if let Ok(value) = init.get::<_, Object>("headers") {
+                headers = Headers::new(Opt(Some(value)));
+            }
+            match init.get::<_, Value>("body")? {
+                value if value.is_undefined() => {}
+                value if value.is_null() => body = None,
+                value => body = Some(Body::Buffered(Arc::new(body_bytes(&value)?))),
+            }
+            for (name, field) in [
+                ("redirect", &mut options.redirect),
+                ("credentials", &mut options.credentials),
+                ("mode", &mut options.mode),
+                ("cache", &mut options.cache),
+                ("referrer", &mut options.referrer),
+            ] {
+                if let Some(value) = init.get::<_, Option<String>>(name)? {
+                    *field = value;
+                }
+            }
+        }
+        if body.is_some() && matches!(method.as_str(), "GET" | "HEAD") {
+            return Err(Exception::throw_type(
+                &ctx,
+                "Request with GET/HEAD method cannot have body",
+            ));
+        }
+
+        Ok(Request {
+            url,
+            method,
+            headers: Class::instance(ctx, headers)?,
+            body,
+            body_stream: None,
+            body_used: false,
+            options,
+        })
+    }
+
+    #[qjs(get)]
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    #[qjs(get)]
+    pub fn method(&self) -> String {
+        self.method.clone()
+    }
+
+    #[qjs(get)]
+    pub fn headers(&self) -> Class<'js, Headers> {
+        self.headers.clone()
+    }
+
+    #[qjs(get)]
+    pub fn redirect(&self) -> String {
+        self.options.redirect.clone()
+    }
+
+    #[qjs(get)]
+    pub fn credentials(&self) -> String {
+        self.options.credentials.clone()
+    }
+
+    #[qjs(get)]
+    pub fn mode(&self) -> String {
+        self.options.mode.clone()
+    }
+
+    #[qjs(get)]
+    pub fn cache(&self) -> String {
+        self.options.cache.clone()
+    }
+
+    #[qjs(get)]
+    pub fn referrer(&self) -> String {
+        self.options.referrer.clone()
+    }
+
+    /// The body as a stream, or `null` when there is none
+    #[qjs(get)]
+    pub fn body(&mut self, ctx: Ctx<'js>) -> Result<Value<'js>> {
+        let Some(body) = &self.body else {
+            return Ok(Value::new_null(ctx));
+        };
+        let stream = if let Some(stream) = &self.body_stream {
+            stream.clone()
+        } else {
+            let stream = body::readable_stream(&ctx, body.clone())?;
+            self.body_stream = Some(stream.clone());
+            stream
+        };
+        Ok(stream)
+    }
+
+    /// True once the body has been read, or a reader has been attached to its stream
+    #[qjs(get, rename = "bodyUsed")]
+    pub fn body_used(&self) -> bool {
+        self.body_used
+            || self
+                .body_stream
+                .as_ref()
+                .is_some_and(body::is_locked_or_disturbed)
+    }
+
+    pub fn text(&mut self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        self.read_body(
+            ctx,
+            |_, body| Ok(String::from_utf8_lossy(body).into_owned()),
+        )
+    }
+
+    #[qjs(rename = "arrayBuffer")]
+    pub fn array_buffer(&mut self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        self.read_body(ctx, |ctx, body| ArrayBuffer::new_copy(ctx.clone(), body))
+    }
+
+    pub fn json(&mut self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        self.read_body(ctx, |ctx, body| {
+            ctx.json_parse(String::from_utf8_lossy(body).into_owned())
+        })
+    }
+
+    /// The body as a `Blob` typed by the `Content-Type` header
+    pub fn blob(&mut self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        let content_type = self.headers.borrow().get("content-type".to_string());
+        self.read_body(ctx, move |ctx, body| {
+            Class::instance(
+                ctx.clone(),
+                Blob::from_bytes(body.to_vec(), content_type.unwrap_or_default()),
+            )
+        })
+    }
+
+    #[qjs(rename = "clone")]
+    pub fn clone_request(&self, ctx: Ctx<'js>) -> Result<Class<'js, Request<'js>>> {
+        if self.body_used() {
+            return Err(Exception::throw_type(
+                &ctx,
+                "Cannot clone a request that has been consumed",
+            ));
+        }
+
+        let cloned = Request {
+            url: self.url.clone(),
+            method: self.method.clone(),
+            headers: Class::instance(ctx.clone(), self.headers.borrow().clone())?,
+            body: self.body.as_ref().map(Body::tee),
+            body_stream: None,
+            body_used: false,
+            options: self.options.clone(),
+        };
+
+        Class::instance(ctx, cloned)
+    }
+}
+
+impl<'js> Request<'js> {
+    /// What `fetch()` sends for this request, marking the body as used
+    pub(crate) fn take_for_fetch(&mut self, ctx: &Ctx<'js>) -> Result<RequestParts> {
+        if self.body.is_some() {
+            if self.body_used() {
+                return Err(Exception::throw_type(ctx, "Body has already been consumed"));
+            }
+            self.body_used = true;
+        }
+        Ok(RequestParts {
+            url: self.url.clone(),
+            method: self.method.clone(),
+            headers: self.headers.borrow().headers.clone(),
+            body: self.body.clone(),
+        })
+    }
+
+    /// Mark the body as used and read all of it, resolving to what `f` makes of
+    /// the bytes
+    fn read_body<R, F>(&mut self, ctx: Ctx<'js>, f: F) -> Result<Promise<'js>>
+    where
+        R: IntoJs<'js>,
+        F: FnOnce(&Ctx<'js>, &[u8]) -> Result<R> + 'js,
+    {
+        if self.body_used() {
+            return Err(Exception::throw_type(
+                &ctx,
+                "Body has already been consumed",
+            ));
+        }
+        self.body_used = true;
+        let body = self.body.clone();
+        let ctx_clone = ctx.clone();
+        Promise::wrap_future(&ctx, async move {
+            let bytes = match body {
+                Some(body) => body
+                    .collect()
+                    .await
+                    .map_err(|error| Exception::throw_type(&ctx_clone, &error))?,
+                None => Arc::default(),
+            };
+            f(&ctx_clone, &bytes)
+        })
+    }
+}
+
+/// Uppercase the methods the Fetch spec normalizes, keeping others as given
+fn normalize_method(method: String) -> String {
+    const NORMALIZED: [&str; 6] = ["DELETE", "GET", "HEAD", "OPTIONS", "POST", "PUT"];
+    NORMALIZED
+        .into_iter()
+        .find(|normalized| normalized.eq_ignore_ascii_case(&method))
+        .map_or(method, str::to_string)
+}
@@ -0,0 +1,59 @@
+Deno.test("Request carries method, headers and body", async () => {
+  const req = new Request("http://example.com/path", {
+    method: "post",
+    headers: { "Content-Type": "text/plain" },
+    body: "hello",
+  });
+  if (req.url !== "http://example.com/path") throw new Error("url");
+  if (req.method !== "POST") throw new Error(`method ${req.method}`);
+  if (req.headers.get("content-type") !== "text/plain") {
+    throw new Error("headers");
+  }
+  const copy = req.clone();
+  if ((await req.text()) !== "hello") throw new Error("text");
+  if (!req.bodyUsed) throw new Error("bodyUsed");
+  if ((await copy.arrayBuffer()).byteLength !== 5) throw new Error("clone");
+});
+
+Deno.test("Request defaults to GET without a body", () => {
+  const req = new Request("http://example.com/");
+  if (req.method !== "GET" || req.body !== null) throw new Error("defaults");
+  const derived = new Request(req, { method: "PATCH" });
+  if (derived.url !== req.url || derived.method !== "PATCH") {
+    throw new Error("derived");
+  }
+  try {
+    new Request("http://example.com/", { body: "x" });
+    throw new Error("GET with a body should throw");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+});
+
+Deno.test("Request exposes its init options", () => {
+  const req = new Request("http://example.com/");
+  const defaults = [req.redirect, req.credentials, req.mode, req.cache];
+  if (defaults.join() !== "follow,same-origin,cors,default") {
+    throw new Error(`defaults: ${defaults}`);
+  }
+  if (req.referrer !== "about:client") throw new Error("referrer");
+  const manual = new Request(
+    new Request(req, { redirect: "manual", cache: "no-store" }),
+  );
+  if (manual.redirect !== "manual" || manual.cache !== "no-store") {
+    throw new Error("options should carry over");
+  }
+});
+
+Deno.test("Request blob uses the Content-Type header", async () => {
+  const req = new Request("http://example.com/", {
+    method: "POST",
+    headers: { "content-type": "application/json" },
+    body: "{}",
+  });
+  const blob = await req.blob();
+  if (blob.type !== "application/json" || (await blob.text()) !== "{}") {
+    throw new Error(`blob: ${blob.type}`);
+  }
+  if (!req.bodyUsed) throw new Error("bodyUsed");
+});
@@ -1,6 +1,10 @@
+use crate::body::{self, Body};
 use crate::headers::Headers;
-use rquickjs::{Class, Ctx, JsLifetime, Object, Result, class::Trace, prelude::*};
-use std::collections::HashMap;
+use rquickjs::{
+    ArrayBuffer, Class, Ctx, Exception, IntoJs, JsLifetime, Object, Promise, Result, Value,
+    class::Trace, prelude::*,
+};
+use std::sync::Arc;
 
 // Response class
 #[derive(Trace, JsLifetime)]
@@ -11,8 +15,12 @@ pub struct Response<'js> {
     #[qjs(skip_trace)]
     status_text: String,
     headers: Class<'js, Headers>,
+    /// `None` for a null body. Buffered bodies are shared with clones, so
+    /// `clone()` doesn't copy them.
     #[qjs(skip_trace)]
-    body: String,
+    body: Option<Body>,
+    /// `response.body`, created on first access
+    body_stream: Option<Value<'js>>,
     #[qjs(skip_trace)]
     body_used: bool,
 }
@@ -20,12 +28,17 @@ pub struct Response<'js> {
 #[rquickjs::methods]
 impl<'js> Response<'js> {
     #[qjs(constructor)]
-    pub fn new(ctx: Ctx<'js>, body: Opt<String>, init: Opt<Object<'_>>) -> Result<Self> {
-        let body = body.0.unwrap_or_default();
+    pub fn new(ctx: Ctx<'js>, body: Opt<Value<'js>>, init: Opt<Object<'_>>) -> Result<Self> {
+        let body = match body.0 {
+            Some(body) if !body.is_undefined() && !body.is_null() => {
+                Some(Body::Buffered(Arc::new(body_bytes(&body)?)))
+            }
+            _ => None,
+        };
         let mut status = 200;
         let mut status_text = String::new();
         let mut headers = Headers {
-            headers: HashMap::new(),
+            headers: Vec::new(),
         };
 
         if let Some(obj) = init.0 {
@@ -45,6 +58,7 @@ impl<'js> Response<'js> {
             status_text,
             headers: Class::instance(ctx, headers)?,
             body,
+            body_stream: None,
             body_used: false,
         })
     }
@@ -69,30 +83,53 @@ impl<'js> Response<'js> {
         self.headers.clone()
     }
 
+    /// The body as a stream, or `null` when there is none
+    #[qjs(get)]
+    pub fn body(&mut self, ctx: Ctx<'js>) -> Result<Value<'js>> {
+        let Some(body) = &self.body else {
+            return Ok(Value::new_null(ctx));
+        };
+        let stream = if let Some(stream) = &self.body_stream {
+            stream.clone()
+        } else {
+            let stream = body::readable_stream(&ctx, body.clone())?;
+            self.body_stream = Some(stream.clone());
+            stream
+        };
+        Ok(stream)
+    }
+
+    /// True once the body has been read, or a reader has been attached to its stream
     #[qjs(get, rename = "bodyUsed")]
     pub fn body_used(&self) -> bool {
         self.body_used
+            || self
+                .body_stream
+                .as_ref()
+                .is_some_and(body::is_locked_or_disturbed)
     }
 
-    pub fn text(&mut self, ctx: Ctx<'js>) -> Result<String> {
-        if self.body_used {
-            return Err(rquickjs::Exception::throw_message(
-                &ctx,
-                "Body has already been consumed",
-            ));
-        }
-        self.body_used = true;
-        Ok(self.body.clone())
+    pub fn text(&mut self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        self.read_body(
+            ctx,
+            |_, body| Ok(String::from_utf8_lossy(body).into_owned()),
+        )
+    }
+
+    #[qjs(rename = "arrayBuffer")]
+    pub fn array_buffer(&mut self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        self.read_body(ctx, |ctx, body| ArrayBuffer::new_copy(ctx.clone(), body))
     }
 
-    pub fn json(&mut self, ctx: Ctx<'js>) -> Result<rquickjs::Value<'js>> {
-        let text = self.text(ctx.clone())?;
-        ctx.json_parse(text)
+    pub fn json(&mut self, ctx: Ctx<'js>) -> Result<Promise<'js>> {
+        self.read_body(ctx, |ctx, body| {
+            ctx.json_parse(String::from_utf8_lossy(body).into_owned())
+        })
     }
 
     #[qjs(rename = "clone")]
     pub fn clone_response(&self, ctx: Ctx<'js>) -> Result<Class<'js, Response<'js>>> {
-        if self.body_used {
+        if self.body_used() {
             return Err(rquickjs::Exception::throw_message(
                 &ctx,
                 "Cannot clone a response that has been consumed",
@@ -103,7 +140,8 @@ impl<'js> Response<'js> {
             status: self.status,
             status_text: self.status_text.clone(),
             headers: self.headers.clone(),
-            body: self.body.clone(),
+            body: self.body.as_ref().map(Body::tee),
+            body_stream: None,
             body_used: false,
         };
 
@@ -112,24 +150,61 @@ impl<'js> Response<'js> {
 }
 
 impl<'js> Response<'js> {
-    pub fn from_fetch(
+    /// Mark the body as used and read all of it, resolving to what `f` makes of
+    /// the bytes
+    fn read_body<R, F>(&mut self, ctx: Ctx<'js>, f: F) -> Result<Promise<'js>>
+    where
+        R: IntoJs<'js>,
+        F: FnOnce(&Ctx<'js>, &[u8]) -> Result<R> + 'js,
+    {
+        if self.body_used() {
+            return Err(rquickjs::Exception::throw_message(
+                &ctx,
+                "Body has already been consumed",
+            ));
+        }
+        self.body_used = true;
+        let body = self.body.clone();
+        let ctx_clone = ctx.clone();
+        Promise::wrap_future(&ctx, async move {
+            let bytes = match body {
+                Some(body) => body
+                    .collect()
+                    .await
+                    .map_err(|error| Exception::throw_type(&ctx_clone, &error))?,
+                None => Arc::default(),
+            };
+            f(&ctx_clone, &bytes)
+        })
+    }
+
+    pub(crate) fn from_fetch(
         ctx: Ctx<'js>,
         status: u16,
-        headers_map: HashMap<String, String>,
-        body: String,
+        headers: Vec<(String, String)>,
+        body: Body,
     ) -> Result<Class<'js, Response<'js>>> {
-        let headers = Headers {
-            headers: headers_map,
-        };
+        let headers = Headers { headers };
 
         let response = Response {
             status,
             status_text: String::new(),
             headers: Class::instance(ctx.clone(), headers)?,
-            body,
+            body: Some(body),
+            body_stream: None,
             body_used: false,
         };
 
         Class::instance(ctx, response)
     }
 }
+
+/// Bytes of a body given as a string, `ArrayBuffer`, typed array or
+/// `DataView`
+pub(crate) fn body_bytes(value: &Value<'_>) -> Result<Vec<u8>> {
+    if let Some(bytes) = utils::buffer_source_bytes(value) {
+        return Ok(bytes);
+    }
+    let text: Coerced<String> = value.get()?;
+    Ok(text.0.into_bytes())
+}
@@ -0,0 +1,50 @@
+Deno.test("Response clone shares the body", async () => {
+  const res = new Response("héllo");
+  const copy = res.clone();
+  if ((await res.text()) !== "héllo") throw new Error("text");
+  const buf = await copy.arrayBuffer();
+  if (buf.byteLength !== 6) throw new Error(`arrayBuffer ${buf.byteLength}`);
+  let error;
+  try {
+    await res.text();
+  } catch (e) {
+    error = e;
+  }
+  if (!error) throw new Error("second read should throw");
+});
+
+Deno.test("Response from bytes", async () => {
+  const res = new Response(new TextEncoder().encode("{\"a\":1}"));
+  if ((await res.json()).a !== 1) throw new Error("json");
+});
+
+Deno.test("Response body can be read as a stream", async () => {
+  const res = new Response("chunked");
+  if (new Response().body !== null) throw new Error("null body");
+  const reader = res.body!.getReader();
+  if (!res.bodyUsed) throw new Error("a locked body is used");
+  const chunks: Uint8Array[] = [];
+  while (true) {
+    const { value, done } = await reader.read();
+    if (done) break;
+    chunks.push(value);
+  }
+  const text = chunks.map((chunk) => new TextDecoder().decode(chunk)).join("");
+  if (text !== "chunked") throw new Error(`stream ${text}`);
+  let error;
+  try {
+    await res.text();
+  } catch (e) {
+    error = e;
+  }
+  if (!error) throw new Error("text() after getReader() should throw");
+});
+
+Deno.test("Response body supports for await", async () => {
+  const res = new Response(new Uint8Array([1, 2, 3]));
+  let length = 0;
+  for await (const chunk of res.body!) {
+    length += chunk.length;
+  }
+  if (length !== 3) throw new Error(`length ${length}`);
+});
@@ -0,0 +1,19 @@
+mod performance;
+
+pub use performance::{Performance, PerformanceEntry};
+
+use rquickjs::{Class, Ctx};
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    // Fix the time origin now, so performance.now() counts from startup
+    performance::time_origin();
+
+    Class::<PerformanceEntry>::define(&ctx.globals())?;
+    Class::<Performance>::define(&ctx.globals())?;
+    let performance = Class::instance(ctx.clone(), Performance::default())?;
+    ctx.globals().set("performance", performance)?;
+
+    Ok(())
+}
@@ -0,0 +1,345 @@
+// https://w3c.github.io/hr-time/ and https://w3c.github.io/user-timing/
+use rquickjs::{
+    Class, Ctx, Exception, JsLifetime, Object, Result, Value, class::Trace, prelude::*,
+};
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// When the runtime started, as an `Instant` and in milliseconds since the
+/// Unix epoch
+static TIME_ORIGIN: OnceLock<(Instant, f64)> = OnceLock::new();
+
+pub(crate) fn time_origin() -> &'static (Instant, f64) {
+    TIME_ORIGIN.get_or_init(|| {
+        let epoch_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0);
+        (Instant::now(), epoch_ms)
+    })
+}
+
+/// Milliseconds since the time origin, in whole microseconds like the engine's
+/// built-in `performance.now()` gave them
+fn now() -> f64 {
+    let micros = time_origin().0.elapsed().as_micros();
+    #[allow(clippy::cast_precision_loss)] // Exact up to 2^53 µs, ~285 years
+    let micros = micros as f64;
+    micros / 1000.0
+}
+
+/// A mark or measure. Both kinds share this class, told apart by `entryType`.
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct PerformanceEntry<'js> {
+    #[qjs(skip_trace)]
+    name: String,
+    #[qjs(skip_trace)]
+    entry_type: &'static str,
+    #[qjs(skip_trace)]
+    start_time: f64,
+    #[qjs(skip_trace)]
+    duration: f64,
+    detail: Value<'js>,
+}
+
+#[rquickjs::methods]
+impl<'js> PerformanceEntry<'js> {
+    /// Entries only come from `performance.mark()` and `performance.measure()`
+    ///
+    /// # Errors
+    /// Always throws a `TypeError`
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>) -> Result<Self> {
+        Err(Exception::throw_type(&ctx, "Illegal constructor"))
+    }
+
+    #[qjs(get)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// "mark" or "measure"
+    #[qjs(get, rename = "entryType")]
+    pub fn entry_type(&self) -> &'static str {
+        self.entry_type
+    }
+
+    #[qjs(get, rename = "startTime")]
+    pub fn start_time(&self) -> f64 {
+        self.start_time
+    }
+
+    #[qjs(get)]
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    #[qjs(get)]
+    pub fn detail(&self) -> Value<'js> {
+        self.detail.clone()
+    }
+
+    /// # Errors
+    /// Returns an error if the object can't be created
+    #[qjs(rename = "toJSON")]
+    pub fn to_json(&self, ctx: Ctx<'js>) -> Result<Object<'js>> {
+        let object = Object::new(ctx)?;
+        object.set("name", self.name.as_str())?;
+        object.set("entryType", self.entry_type)?;
+        object.set("startTime", self.start_time)?;
+        object.set("duration", self.duration)?;
+        object.set("detail", self.detail.clone())?;
+        Ok(object)
+    }
+}
+
+/// `globalThis.performance`, holding the marks and measures recorded so far
+#[derive(Default, Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct Performance<'js> {
+    entries: Vec<Class<'js, PerformanceEntry<'js>>>,
+}
+
+impl<'js> Performance<'js> {
+    fn add_entry(
+        &mut self,
+        ctx: &Ctx<'js>,
+        entry: PerformanceEntry<'js>,
+    ) -> Result<Class<'js, PerformanceEntry<'js>>> {
+        let entry = Class::instance(ctx.clone(), entry)?;
+        self.entries.push(entry.clone());
+        Ok(entry)
+    }
+
+    /// Entries matching `filter`, in the order they started
+    fn filtered(
+        &self,
+        filter: impl Fn(&PerformanceEntry<'js>) -> bool,
+    ) -> Vec<Class<'js, PerformanceEntry<'js>>> {
+        let mut entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| filter(&entry.borrow()))
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| a.borrow().start_time.total_cmp(&b.borrow().start_time));
+        entries
+    }
+
+    fn clear(&mut self, entry_type: &str, name: Option<&str>) {
+        self.entries.retain(|entry| {
+            let entry = entry.borrow();
+            entry.entry_type != entry_type || name.is_some_and(|name| entry.name != name)
+        });
+    }
+
+    /// A time given to `measure()`: a mark name, or milliseconds since the
+    /// time origin
+    fn resolve_time(&self, ctx: &Ctx<'js>, time: &Value<'js>) -> Result<f64> {
+        if let Some(name) = time.as_string() {
+            let name = name.to_string()?;
+            // The latest mark of that name wins
+            let mark = self.entries.iter().rev().find(|entry| {
+                let entry = entry.borrow();
+                entry.entry_type == "mark" && entry.name == name
+            });
+            return match mark {
+                Some(mark) => Ok(mark.borrow().start_time),
+                None => Err(syntax_error(
+                    ctx,
+                    &format!("The mark '{name}' does not exist"),
+                )),
+            };
+        }
+        match time.as_number() {
+            Some(time) if time >= 0.0 => Ok(time),
+            _ => Err(Exception::throw_type(
+                ctx,
+                "Time must be a mark name or a non-negative number",
+            )),
+        }
+    }
+}
+
+#[rquickjs::methods]
+impl<'js> Performance<'js> {
+    /// There is only the one `performance` object
+    ///
+    /// # Errors
+    /// Always throws a `TypeError`
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'js>) -> Result<Self> {
+        Err(Exception::throw_type(&ctx, "Illegal constructor"))
+    }
+
+    /// Milliseconds since the time origin
+    pub fn now(&self) -> f64 {
+        now()
+    }
+
+    /// Milliseconds since the Unix epoch when the runtime started
+    #[qjs(get, rename = "timeOrigin")]
+    pub fn time_origin(&self) -> f64 {
+        time_origin().1
+    }
+
+    /// # Errors
+    /// Throws a `TypeError` if `options.startTime` is negative
+    pub fn mark(
+        &mut self,
+        ctx: Ctx<'js>,
+        name: String,
+        options: Opt<Object<'js>>,
+    ) -> Result<Class<'js, PerformanceEntry<'js>>> {
+        let (start_time, detail) = match options.0 {
+            Some(options) => (
+                options.get::<_, Option<f64>>("startTime")?,
+                options.get::<_, Option<Value>>("detail")?,
+            ),
+            None => (None, None),
+        };
+        let start_time = start_time.unwrap_or_else(now);
+        if start_time < 0.0 {
+            return Err(Exception::throw_type(
+                &ctx,
+                "startTime must not be negative",
+            ));
+        }
+        let entry = PerformanceEntry {
+            name,
+            entry_type: "mark",
+            start_time,
+            duration: 0.0,
+            detail: detail.unwrap_or_else(|| Value::new_null(ctx.clone())),
+        };
+        self.add_entry(&ctx, entry)
+    }
+
+    /// Measure between two marks or times. The second argument is either
+    /// the start mark or an object with `start`, `end`, `duration` and
+    /// `detail`; without a start the measure begins at the time origin, and
+    /// without an end it stops now.
+    ///
+    /// # Errors
+    /// Throws a `SyntaxError` for an unknown mark, and a `TypeError` for
+    /// invalid options
+    pub fn measure(
+        &mut self,
+        ctx: Ctx<'js>,
+        name: String,
+        start_or_options: Opt<Value<'js>>,
+        end_mark: Opt<String>,
+    ) -> Result<Class<'js, PerformanceEntry<'js>>> {
+        let mut start = None;
+        let mut end = None;
+        let mut duration = None;
+        let mut detail = None;
+        match start_or_options.0 {
+            Some(value) if value.is_object() => {
+                if end_mark.0.is_some() {
+                    return Err(Exception::throw_type(
+                        &ctx,
+                        "An end mark can't be given with measure options",
+                    ));
+                }
+                let Some(options) = value.as_object() else {
+                    return Err(Exception::throw_type(&ctx, "Invalid measure options"));
+                };
+                start = options.get::<_, Option<Value>>("start")?;
+                end = options.get::<_, Option<Value>>("end")?;
+                duration = options.get::<_, Option<f64>>("duration")?;
+                detail = options.get::<_, Option<Value>>("detail")?;
+                if start.is_some() && end.is_some() && duration.is_some() {
+                    return Err(Exception::throw_type(
+                        &ctx,
+                        "start, end and duration can't all be given",
+                    ));
+                }
+            }
+            Some(value) if !value.is_undefined() && !value.is_null() => start = Some(value),
+            _ => {}
+        }
+        if let Some(end_mark) = end_mark.0 {
+            end = Some(rquickjs::String::from_str(ctx.clone(), &end_mark)?.into_value());
+        }
+
+        let start = start
+            .map(|start| self.resolve_time(&ctx, &start))
+            .transpose()?;
+        let end = end.map(|end| self.resolve_time(&ctx, &end)).transpose()?;
+        let (start_time, end_time) = match (start, end, duration) {
+            (Some(start), None, Some(duration)) => (start, start + duration),
+            (None, Some(end), Some(duration)) => (end - duration, end),
+            (start, end, _) => (start.unwrap_or(0.0), end.unwrap_or_else(now)),
+        };
+        let entry = PerformanceEntry {
+            name,
+            entry_type: "measure",
+            start_time,
+            duration: end_time - start_time,
+            detail: detail.unwrap_or_else(|| Value::new_null(ctx.clone())),
+        };
+        self.add_entry(&ctx, entry)
+    }
+
+    #[qjs(rename = "getEntries")]
+    pub fn get_entries(&self) -> Vec<Class<'js, PerformanceEntry<'js>>> {
+        self.filtered(|_| true)
+    }
+
+    #[qjs(rename = "getEntriesByName")]
+    pub fn get_entries_by_name(
+        &self,
+        name: String,
+        entry_type: Opt<String>,
+    ) -> Vec<Class<'js, PerformanceEntry<'js>>> {
+        self.filtered(|entry| {
+            entry.name == name
+                && entry_type
+                    .0
+                    .as_ref()
+                    .is_none_or(|entry_type| entry.entry_type == entry_type)
+        })
+    }
+
+    #[qjs(rename = "getEntriesByType")]
+    pub fn get_entries_by_type(
+        &self,
+        entry_type: String,
+    ) -> Vec<Class<'js, PerformanceEntry<'js>>> {
+        self.filtered(|entry| entry.entry_type == entry_type)
+    }
+
+    /// Remove the marks named `name`, or all of them
+    #[qjs(rename = "clearMarks")]
+    pub fn clear_marks(&mut self, name: Opt<String>) {
+        self.clear("mark", name.0.as_deref());
+    }
+
+    /// Remove the measures named `name`, or all of them
+    #[qjs(rename = "clearMeasures")]
+    pub fn clear_measures(&mut self, name: Opt<String>) {
+        self.clear("measure", name.0.as_deref());
+    }
+
+    /// # Errors
+    /// Returns an error if the object can't be created
+    #[qjs(rename = "toJSON")]
+    pub fn to_json(&self, ctx: Ctx<'js>) -> Result<Object<'js>> {
+        let object = Object::new(ctx)?;
+        object.set("timeOrigin", time_origin().1)?;
+        Ok(object)
+    }
+}
+
+/// A `SyntaxError`, standing in for the `DOMException` the spec calls for
+fn syntax_error(ctx: &Ctx<'_>, message: &str) -> rquickjs::Error {
+    let exception = match Exception::from_message(ctx.clone(), message) {
+        Ok(exception) => exception,
+        Err(error) => return error,
+    };
+    if let Err(error) = exception.set("name", "SyntaxError") {
+        return error;
+    }
+    ctx.throw(exception.into_value())
+}
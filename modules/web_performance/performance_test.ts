@@ -0,0 +1,60 @@
+Deno.test("performance.now counts up from timeOrigin", () => {
+  const first = performance.now();
+  if (typeof first !== "number" || first < 0) throw new Error(`now: ${first}`);
+  if (performance.now() < first) throw new Error("now went back");
+  const drift = Math.abs(performance.timeOrigin + performance.now() - Date.now());
+  if (drift > 1000) throw new Error(`timeOrigin is off by ${drift}ms`);
+  if (performance.toJSON().timeOrigin !== performance.timeOrigin) throw new Error("toJSON");
+});
+
+Deno.test("performance.mark and performance.measure record entries", () => {
+  const start = performance.mark("perf-test-start", { startTime: 10, detail: { step: 1 } });
+  if (start.entryType !== "mark" || start.duration !== 0) throw new Error("mark");
+  const now = performance.mark("perf-test-now");
+  if (now.startTime <= 0 || now.startTime > performance.now()) throw new Error("mark time");
+  if ((start.detail as { step: number }).step !== 1) throw new Error("detail");
+  if (!(start instanceof PerformanceEntry)) throw new Error("instanceof");
+  performance.mark("perf-test-end", { startTime: 15 });
+
+  const measure = performance.measure("perf-test", "perf-test-start", "perf-test-end");
+  if (measure.entryType !== "measure" || measure.duration !== 5) {
+    throw new Error(`measure: ${JSON.stringify(measure)}`);
+  }
+  const fromOptions = performance.measure("perf-test-options", {
+    start: "perf-test-start",
+    duration: 2,
+  });
+  if (fromOptions.startTime !== 10 || fromOptions.duration !== 2) {
+    throw new Error("measure options");
+  }
+
+  if (performance.getEntriesByName("perf-test-start").length !== 1) throw new Error("byName");
+  if (performance.getEntriesByName("perf-test", "mark").length !== 0) {
+    throw new Error("byName type");
+  }
+  const measures = performance.getEntriesByType("measure").map((entry) => entry.name);
+  if (!measures.includes("perf-test") || !measures.includes("perf-test-options")) {
+    throw new Error("byType");
+  }
+  const times = performance.getEntries().map((entry) => entry.startTime);
+  if (times.some((time, i) => i > 0 && time < times[i - 1])) throw new Error("order");
+
+  performance.clearMarks("perf-test-start");
+  if (performance.getEntriesByName("perf-test-start").length !== 0) throw new Error("clearMarks");
+  if (performance.getEntriesByName("perf-test-end").length !== 1) {
+    throw new Error("cleared too much");
+  }
+  performance.clearMarks();
+  performance.clearMeasures();
+  if (performance.getEntries().length !== 0) throw new Error("clear all");
+});
+
+Deno.test("performance.measure rejects unknown marks", () => {
+  let error;
+  try {
+    performance.measure("perf-test-missing", "no-such-mark");
+  } catch (e) {
+    error = e;
+  }
+  if (!(error instanceof Error) || error.name !== "SyntaxError") throw new Error("SyntaxError");
+});
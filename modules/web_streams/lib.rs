@@ -0,0 +1,14 @@
+use rquickjs::{Ctx, Module};
+use utils_macros::include_ts;
+
+/// Registers `ReadableStream`, `WritableStream`, `TransformStream`, their
+/// readers, writers and controllers, and the queuing strategies
+///
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    let js_source = include_ts!("streams.ts");
+    let module = Module::evaluate(ctx.clone(), "web_streams", js_source)?;
+    module.finish::<()>()?;
+    Ok(())
+}
@@ -0,0 +1,2142 @@
+// https://streams.spec.whatwg.org/
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+// Chunk size of streams over bytes, as in Deno
+const CHUNK_SIZE = 64 * 1024;
+
+// Internal slots, kept out of reach of user code
+const _state = Symbol("[[state]]");
+const _storedError = Symbol("[[storedError]]");
+const _controller = Symbol("[[controller]]");
+const _reader = Symbol("[[reader]]");
+const _writer = Symbol("[[writer]]");
+const _disturbed = Symbol("[[disturbed]]");
+const _stream = Symbol("[[stream]]");
+const _closedPromise = Symbol("[[closedPromise]]");
+const _readyPromise = Symbol("[[readyPromise]]");
+const _readRequests = Symbol("[[readRequests]]");
+const _queue = Symbol("[[queue]]");
+const _queueTotalSize = Symbol("[[queueTotalSize]]");
+const _started = Symbol("[[started]]");
+const _closeRequested = Symbol("[[closeRequested]]");
+const _pulling = Symbol("[[pulling]]");
+const _pullAgain = Symbol("[[pullAgain]]");
+const _highWaterMark = Symbol("[[highWaterMark]]");
+const _size = Symbol("[[size]]");
+const _isBytes = Symbol("[[isBytes]]");
+const _pullAlgorithm = Symbol("[[pullAlgorithm]]");
+const _cancelAlgorithm = Symbol("[[cancelAlgorithm]]");
+const _writeAlgorithm = Symbol("[[writeAlgorithm]]");
+const _closeAlgorithm = Symbol("[[closeAlgorithm]]");
+const _abortAlgorithm = Symbol("[[abortAlgorithm]]");
+const _abortController = Symbol("[[abortController]]");
+const _writeRequests = Symbol("[[writeRequests]]");
+const _inFlightWriteRequest = Symbol("[[inFlightWriteRequest]]");
+const _closeRequest = Symbol("[[closeRequest]]");
+const _inFlightCloseRequest = Symbol("[[inFlightCloseRequest]]");
+const _pendingAbortRequest = Symbol("[[pendingAbortRequest]]");
+const _backpressure = Symbol("[[backpressure]]");
+const _backpressureChangePromise = Symbol("[[backpressureChangePromise]]");
+const _readable = Symbol("[[readable]]");
+const _writable = Symbol("[[writable]]");
+const _transformAlgorithm = Symbol("[[transformAlgorithm]]");
+const _flushAlgorithm = Symbol("[[flushAlgorithm]]");
+const _finishPromise = Symbol("[[finishPromise]]");
+
+// Passed by this module to constructors that user code may not call
+const illegalConstructor = Symbol("illegalConstructor");
+
+// Queued in a writable stream's controller to mark where close() was called
+const closeSentinel = Symbol("close");
+
+type Algorithm<T = unknown> = (arg?: T) => Promise<void>;
+
+interface Deferred<T> {
+  promise: Promise<T>;
+  resolve(value: T): void;
+  reject(reason: unknown): void;
+  settled: boolean;
+}
+
+function deferred<T = void>(): Deferred<T> {
+  const result = { settled: false } as Deferred<T>;
+  result.promise = new Promise<T>((resolve, reject) => {
+    result.resolve = (value) => {
+      result.settled = true;
+      resolve(value);
+    };
+    result.reject = (reason) => {
+      result.settled = true;
+      reject(reason);
+    };
+  });
+  return result;
+}
+
+// A promise that is already rejected, without an unhandled rejection
+function rejectedHandled<T = void>(reason: unknown): Deferred<T> {
+  const result = deferred<T>();
+  result.promise.catch(() => {});
+  result.reject(reason);
+  return result;
+}
+
+function resolved<T = void>(value: T): Deferred<T> {
+  const result = deferred<T>();
+  result.resolve(value);
+  return result;
+}
+
+// Call a user callback, turning a synchronous throw into a rejection
+function promiseCall(
+  fn: ((...args: any[]) => unknown) | undefined,
+  thisArg: unknown,
+  ...args: unknown[]
+): Promise<void> {
+  if (fn === undefined) return Promise.resolve();
+  try {
+    return Promise.resolve(fn.apply(thisArg, args)).then(() => undefined);
+  } catch (error) {
+    return Promise.reject(error);
+  }
+}
+
+function getCallback(object: any, name: string) {
+  const value = object?.[name];
+  if (value !== undefined && typeof value !== "function") {
+    throw new TypeError(`${name} must be a function`);
+  }
+  return value as ((...args: any[]) => unknown) | undefined;
+}
+
+function extractHighWaterMark(strategy: any, defaultHWM: number): number {
+  if (strategy?.highWaterMark === undefined) return defaultHWM;
+  const highWaterMark = Number(strategy.highWaterMark);
+  if (Number.isNaN(highWaterMark) || highWaterMark < 0) {
+    throw new RangeError("highWaterMark must be a non-negative number");
+  }
+  return highWaterMark;
+}
+
+function extractSizeAlgorithm(strategy: any): (chunk: unknown) => number {
+  const size = strategy?.size;
+  if (size === undefined) return () => 1;
+  if (typeof size !== "function") {
+    throw new TypeError("size must be a function");
+  }
+  return (chunk) => size(chunk);
+}
+
+// Queue-with-sizes, shared by the readable and writable controllers
+interface QueueContainer {
+  [_queue]: { value: unknown; size: number }[];
+  [_queueTotalSize]: number;
+}
+
+function enqueueValueWithSize(
+  container: QueueContainer,
+  value: unknown,
+  size: number,
+): void {
+  if (!Number.isFinite(size) || size < 0) {
+    throw new RangeError("Chunk size must be a finite, non-negative number");
+  }
+  container[_queue].push({ value, size });
+  container[_queueTotalSize] += size;
+}
+
+function dequeueValue(container: QueueContainer): unknown {
+  const pair = container[_queue].shift()!;
+  container[_queueTotalSize] = Math.max(
+    0,
+    container[_queueTotalSize] - pair.size,
+  );
+  return pair.value;
+}
+
+function resetQueue(container: QueueContainer): void {
+  container[_queue] = [];
+  container[_queueTotalSize] = 0;
+}
+
+function isArrayBufferView(value: unknown): value is ArrayBufferView {
+  return ArrayBuffer.isView(value);
+}
+
+// Bytes of any ArrayBufferView, copied so later changes by the caller don't
+// show up in the stream
+function copyBytes(view: ArrayBufferView): Uint8Array {
+  return new Uint8Array(view.buffer, view.byteOffset, view.byteLength).slice();
+}
+
+// https://streams.spec.whatwg.org/#cqs-class
+class CountQueuingStrategy {
+  #highWaterMark: number;
+
+  constructor(init: { highWaterMark: number }) {
+    if (init?.highWaterMark === undefined) {
+      throw new TypeError("highWaterMark is required");
+    }
+    this.#highWaterMark = Number(init.highWaterMark);
+  }
+
+  get highWaterMark(): number {
+    return this.#highWaterMark;
+  }
+
+  get size(): (chunk: unknown) => number {
+    return countSize;
+  }
+}
+
+const countSize = function size(): number {
+  return 1;
+};
+
+// https://streams.spec.whatwg.org/#blqs-class
+class ByteLengthQueuingStrategy {
+  #highWaterMark: number;
+
+  constructor(init: { highWaterMark: number }) {
+    if (init?.highWaterMark === undefined) {
+      throw new TypeError("highWaterMark is required");
+    }
+    this.#highWaterMark = Number(init.highWaterMark);
+  }
+
+  get highWaterMark(): number {
+    return this.#highWaterMark;
+  }
+
+  get size(): (chunk: ArrayBufferView) => number {
+    return byteLengthSize;
+  }
+}
+
+const byteLengthSize = function size(chunk: ArrayBufferView): number {
+  return chunk.byteLength;
+};
+
+// ---------------------------------------------------------------------------
+// Readable streams
+
+type ReadableState = "readable" | "closed" | "errored";
+
+interface ReadRequest {
+  chunk(chunk: unknown): void;
+  close(): void;
+  error(error: unknown): void;
+}
+
+interface ReadIntoRequest {
+  view: ArrayBufferView;
+  chunk(view: ArrayBufferView): void;
+  close(view: ArrayBufferView): void;
+  error(error: unknown): void;
+}
+
+type ReadResult<T> = { value: T; done: false } | {
+  value: T | undefined;
+  done: true;
+};
+
+// https://streams.spec.whatwg.org/#rs-class
+class ReadableStream<R = unknown> {
+  [_state]: ReadableState = "readable";
+  [_reader]: ReadableStreamDefaultReader<R> | ReadableStreamBYOBReader | undefined =
+    undefined;
+  [_storedError]: unknown = undefined;
+  [_disturbed] = false;
+  [_controller]!: ReadableStreamDefaultController<R>;
+
+  constructor(underlyingSource: any = undefined, strategy: any = {}) {
+    if (underlyingSource === null) {
+      throw new TypeError("underlyingSource cannot be null");
+    }
+    const source = underlyingSource ?? {};
+    const start = getCallback(source, "start");
+    const pull = getCallback(source, "pull");
+    const cancel = getCallback(source, "cancel");
+    const type = source.type === undefined ? undefined : String(source.type);
+    if (type !== undefined && type !== "bytes") {
+      throw new TypeError(`Invalid ReadableStream type: '${type}'`);
+    }
+    if (type === "bytes" && strategy?.size !== undefined) {
+      throw new RangeError("A byte stream can't have a size function");
+    }
+    const isBytes = type === "bytes";
+    const controller = isBytes
+      ? new ReadableByteStreamController(illegalConstructor)
+      : new ReadableStreamDefaultController<R>(illegalConstructor);
+    setUpReadableStreamController(
+      this,
+      controller as ReadableStreamDefaultController<R>,
+      () => start?.call(source, controller),
+      () => promiseCall(pull, source, controller),
+      (reason) => promiseCall(cancel, source, reason),
+      extractHighWaterMark(strategy, isBytes ? 0 : 1),
+      isBytes ? byteLengthSize as any : extractSizeAlgorithm(strategy),
+      isBytes,
+    );
+  }
+
+  get locked(): boolean {
+    return this[_reader] !== undefined;
+  }
+
+  cancel(reason?: unknown): Promise<void> {
+    if (this.locked) {
+      return Promise.reject(
+        new TypeError("Cannot cancel a locked ReadableStream"),
+      );
+    }
+    return readableStreamCancel(this, reason);
+  }
+
+  getReader(options?: { mode?: "byob" }): any {
+    const mode = options?.mode;
+    if (mode === undefined) return new ReadableStreamDefaultReader(this);
+    if (String(mode) === "byob") return new ReadableStreamBYOBReader(this);
+    throw new TypeError(`Invalid reader mode: '${mode}'`);
+  }
+
+  pipeThrough<T>(
+    transform: { writable: WritableStream<R>; readable: ReadableStream<T> },
+    options: PipeOptions = {},
+  ): ReadableStream<T> {
+    if (this.locked) {
+      throw new TypeError("Cannot pipe a locked ReadableStream");
+    }
+    if (transform.writable.locked) {
+      throw new TypeError("Cannot pipe to a locked WritableStream");
+    }
+    readableStreamPipeTo(this, transform.writable, options).catch(() => {});
+    return transform.readable;
+  }
+
+  pipeTo(destination: WritableStream<R>, options: PipeOptions = {}): Promise<void> {
+    if (!(destination instanceof WritableStream)) {
+      return Promise.reject(
+        new TypeError("pipeTo() expects a WritableStream"),
+      );
+    }
+    if (this.locked) {
+      return Promise.reject(
+        new TypeError("Cannot pipe a locked ReadableStream"),
+      );
+    }
+    if (destination.locked) {
+      return Promise.reject(
+        new TypeError("Cannot pipe to a locked WritableStream"),
+      );
+    }
+    return readableStreamPipeTo(this, destination, options);
+  }
+
+  tee(): [ReadableStream<R>, ReadableStream<R>] {
+    return readableStreamTee(this);
+  }
+
+  values(options?: { preventCancel?: boolean }): AsyncIterableIterator<R> {
+    return readableStreamIterator(this, Boolean(options?.preventCancel));
+  }
+
+  [Symbol.asyncIterator](
+    options?: { preventCancel?: boolean },
+  ): AsyncIterableIterator<R> {
+    return this.values(options);
+  }
+
+  static from<T>(asyncIterable: AsyncIterable<T> | Iterable<T>): ReadableStream<T> {
+    const iterable = asyncIterable as any;
+    const method = iterable?.[Symbol.asyncIterator] ?? iterable?.[Symbol.iterator];
+    if (typeof method !== "function") {
+      throw new TypeError("ReadableStream.from() expects an iterable");
+    }
+    const iterator = method.call(iterable);
+    return new ReadableStream<T>({
+      async pull(controller: ReadableStreamDefaultController<T>) {
+        const result = await iterator.next();
+        if (typeof result !== "object" || result === null) {
+          throw new TypeError("The iterator result must be an object");
+        }
+        if (result.done) {
+          controller.close();
+        } else {
+          controller.enqueue(await result.value);
+        }
+      },
+      async cancel(reason: unknown) {
+        if (typeof iterator.return === "function") {
+          const result = await iterator.return(reason);
+          if (typeof result !== "object" || result === null) {
+            throw new TypeError("The iterator result must be an object");
+          }
+        }
+      },
+    }, { highWaterMark: 0 });
+  }
+}
+
+// A readable stream driven by internal algorithms rather than a source object
+function createReadableStream<R>(
+  start: () => unknown,
+  pull: Algorithm,
+  cancel: Algorithm,
+  highWaterMark = 1,
+  size: (chunk: unknown) => number = () => 1,
+): ReadableStream<R> {
+  const stream: ReadableStream<R> = Object.create(ReadableStream.prototype);
+  stream[_state] = "readable";
+  stream[_reader] = undefined;
+  stream[_storedError] = undefined;
+  stream[_disturbed] = false;
+  setUpReadableStreamController(
+    stream,
+    new ReadableStreamDefaultController<R>(illegalConstructor),
+    start,
+    pull,
+    cancel,
+    highWaterMark,
+    size,
+    false,
+  );
+  return stream;
+}
+
+function setUpReadableStreamController<R>(
+  stream: ReadableStream<R>,
+  controller: ReadableStreamDefaultController<R>,
+  start: () => unknown,
+  pull: Algorithm,
+  cancel: Algorithm,
+  highWaterMark: number,
+  size: (chunk: unknown) => number,
+  isBytes: boolean,
+): void {
+  controller[_stream] = stream;
+  resetQueue(controller);
+  controller[_started] = false;
+  controller[_closeRequested] = false;
+  controller[_pulling] = false;
+  controller[_pullAgain] = false;
+  controller[_highWaterMark] = highWaterMark;
+  controller[_size] = size;
+  controller[_isBytes] = isBytes;
+  controller[_pullAlgorithm] = pull;
+  controller[_cancelAlgorithm] = cancel;
+  stream[_controller] = controller;
+
+  Promise.resolve(start()).then(() => {
+    controller[_started] = true;
+    readableControllerCallPullIfNeeded(controller);
+  }, (error) => readableControllerError(controller, error));
+}
+
+// https://streams.spec.whatwg.org/#rs-default-controller-class
+class ReadableStreamDefaultController<R = unknown> {
+  [_stream]!: ReadableStream<R>;
+  [_queue]: { value: unknown; size: number }[] = [];
+  [_queueTotalSize] = 0;
+  [_started] = false;
+  [_closeRequested] = false;
+  [_pulling] = false;
+  [_pullAgain] = false;
+  [_highWaterMark] = 1;
+  [_size]: (chunk: unknown) => number = () => 1;
+  [_isBytes] = false;
+  [_pullAlgorithm]: Algorithm | undefined;
+  [_cancelAlgorithm]: Algorithm | undefined;
+
+  constructor(token?: symbol) {
+    if (token !== illegalConstructor) {
+      throw new TypeError("Illegal constructor");
+    }
+  }
+
+  get desiredSize(): number | null {
+    return readableControllerGetDesiredSize(this);
+  }
+
+  close(): void {
+    if (!readableControllerCanCloseOrEnqueue(this)) {
+      throw new TypeError("The stream is not in a state that permits close");
+    }
+    readableControllerClose(this);
+  }
+
+  enqueue(chunk: R): void {
+    if (!readableControllerCanCloseOrEnqueue(this)) {
+      throw new TypeError("The stream is not in a state that permits enqueue");
+    }
+    readableControllerEnqueue(this, chunk);
+  }
+
+  error(error?: unknown): void {
+    readableControllerError(this, error);
+  }
+}
+
+// https://streams.spec.whatwg.org/#rbs-controller-class
+//
+// The queue holds copies of the enqueued bytes, and BYOB reads are served from
+// it, so byobRequest is always null.
+class ReadableByteStreamController extends ReadableStreamDefaultController<Uint8Array> {
+  get byobRequest(): null {
+    return null;
+  }
+
+  override enqueue(chunk: ArrayBufferView): void {
+    if (!isArrayBufferView(chunk)) {
+      throw new TypeError("chunk must be an ArrayBufferView");
+    }
+    if (chunk.byteLength === 0) {
+      throw new TypeError("chunk must not be empty");
+    }
+    super.enqueue(copyBytes(chunk));
+  }
+}
+
+function readableControllerGetDesiredSize(
+  controller: ReadableStreamDefaultController<any>,
+): number | null {
+  const state = controller[_stream][_state];
+  if (state === "errored") return null;
+  if (state === "closed") return 0;
+  return controller[_highWaterMark] - controller[_queueTotalSize];
+}
+
+function readableControllerCanCloseOrEnqueue(
+  controller: ReadableStreamDefaultController<any>,
+): boolean {
+  return !controller[_closeRequested] &&
+    controller[_stream][_state] === "readable";
+}
+
+function readableControllerClose(
+  controller: ReadableStreamDefaultController<any>,
+): void {
+  if (!readableControllerCanCloseOrEnqueue(controller)) return;
+  controller[_closeRequested] = true;
+  if (controller[_queue].length === 0) {
+    readableControllerClearAlgorithms(controller);
+    readableStreamClose(controller[_stream]);
+  }
+}
+
+function readableControllerEnqueue(
+  controller: ReadableStreamDefaultController<any>,
+  chunk: unknown,
+): void {
+  if (!readableControllerCanCloseOrEnqueue(controller)) return;
+  const stream = controller[_stream];
+  const reader = stream[_reader];
+  if (
+    reader instanceof ReadableStreamDefaultReader &&
+    reader[_readRequests].length > 0
+  ) {
+    reader[_readRequests].shift()!.chunk(chunk);
+  } else {
+    let size;
+    try {
+      size = controller[_size](chunk);
+      enqueueValueWithSize(controller, chunk, size);
+    } catch (error) {
+      readableControllerError(controller, error);
+      throw error;
+    }
+    if (reader instanceof ReadableStreamBYOBReader) {
+      readableControllerFillReadIntoRequests(controller, reader);
+    }
+  }
+  readableControllerCallPullIfNeeded(controller);
+}
+
+function readableControllerError(
+  controller: ReadableStreamDefaultController<any>,
+  error: unknown,
+): void {
+  const stream = controller[_stream];
+  if (stream[_state] !== "readable") return;
+  resetQueue(controller);
+  readableControllerClearAlgorithms(controller);
+  readableStreamError(stream, error);
+}
+
+function readableControllerClearAlgorithms(
+  controller: ReadableStreamDefaultController<any>,
+): void {
+  controller[_pullAlgorithm] = undefined;
+  controller[_cancelAlgorithm] = undefined;
+}
+
+function readableControllerShouldCallPull(
+  controller: ReadableStreamDefaultController<any>,
+): boolean {
+  if (!readableControllerCanCloseOrEnqueue(controller)) return false;
+  if (!controller[_started]) return false;
+  const reader = controller[_stream][_reader];
+  if (reader !== undefined && reader[_readRequests].length > 0) return true;
+  return readableControllerGetDesiredSize(controller)! > 0;
+}
+
+function readableControllerCallPullIfNeeded(
+  controller: ReadableStreamDefaultController<any>,
+): void {
+  if (!readableControllerShouldCallPull(controller)) return;
+  if (controller[_pulling]) {
+    controller[_pullAgain] = true;
+    return;
+  }
+  controller[_pulling] = true;
+  controller[_pullAlgorithm]!().then(() => {
+    controller[_pulling] = false;
+    if (controller[_pullAgain]) {
+      controller[_pullAgain] = false;
+      readableControllerCallPullIfNeeded(controller);
+    }
+  }, (error) => readableControllerError(controller, error));
+}
+
+// Close the stream once close() was called and the queue has drained
+function readableControllerCloseIfDrained(
+  controller: ReadableStreamDefaultController<any>,
+): void {
+  if (controller[_closeRequested] && controller[_queue].length === 0) {
+    readableControllerClearAlgorithms(controller);
+    readableStreamClose(controller[_stream]);
+  } else {
+    readableControllerCallPullIfNeeded(controller);
+  }
+}
+
+// Serve a read from a default reader
+function readableControllerPullSteps(
+  controller: ReadableStreamDefaultController<any>,
+  readRequest: ReadRequest,
+): void {
+  if (controller[_queue].length > 0) {
+    const chunk = dequeueValue(controller);
+    readableControllerCloseIfDrained(controller);
+    readRequest.chunk(chunk);
+    return;
+  }
+  const reader = controller[_stream][_reader] as ReadableStreamDefaultReader;
+  reader[_readRequests].push(readRequest);
+  readableControllerCallPullIfNeeded(controller);
+}
+
+// Copy queued bytes into `view`, returning how many were copied. Only whole
+// elements are copied, so the view can be resized to what was filled.
+function readableControllerFillView(
+  controller: ReadableStreamDefaultController<any>,
+  view: ArrayBufferView,
+): number {
+  const elementSize = (view as any).BYTES_PER_ELEMENT ?? 1;
+  const wanted = Math.min(view.byteLength, controller[_queueTotalSize]);
+  const total = wanted - (wanted % elementSize);
+  const target = new Uint8Array(view.buffer, view.byteOffset, total);
+  let filled = 0;
+  while (filled < total) {
+    const head = controller[_queue][0];
+    const bytes = head.value as Uint8Array;
+    const count = Math.min(bytes.byteLength, total - filled);
+    target.set(bytes.subarray(0, count), filled);
+    filled += count;
+    if (count === bytes.byteLength) {
+      controller[_queue].shift();
+    } else {
+      head.value = bytes.subarray(count);
+      head.size -= count;
+    }
+    controller[_queueTotalSize] -= count;
+  }
+  return filled;
+}
+
+// A view over the same memory as `view`, holding its first `bytes` bytes
+function filledView(view: ArrayBufferView, bytes: number): ArrayBufferView {
+  const ctor = view.constructor as any;
+  const elementSize = ctor.BYTES_PER_ELEMENT ?? 1;
+  return new ctor(view.buffer, view.byteOffset, bytes / elementSize);
+}
+
+// Serve a read from a BYOB reader
+function readableControllerPullIntoSteps(
+  controller: ReadableStreamDefaultController<any>,
+  readIntoRequest: ReadIntoRequest,
+): void {
+  const stream = controller[_stream];
+  const reader = stream[_reader] as ReadableStreamBYOBReader;
+  reader[_readRequests].push(readIntoRequest);
+  readableControllerFillReadIntoRequests(controller, reader);
+  if (stream[_state] === "closed" && reader[_readRequests].length > 0) {
+    const request = reader[_readRequests].shift()!;
+    request.close(filledView(request.view, 0));
+    return;
+  }
+  readableControllerCallPullIfNeeded(controller);
+}
+
+function readableControllerFillReadIntoRequests(
+  controller: ReadableStreamDefaultController<any>,
+  reader: ReadableStreamBYOBReader,
+): void {
+  const requests = reader[_readRequests];
+  while (requests.length > 0 && controller[_queueTotalSize] > 0) {
+    const request = requests[0];
+    const filled = readableControllerFillView(controller, request.view);
+    // A view wider than the queued bytes waits for more
+    if (filled === 0) return;
+    requests.shift();
+    request.chunk(filledView(request.view, filled));
+  }
+  if (controller[_closeRequested] && controller[_queue].length === 0) {
+    readableControllerClearAlgorithms(controller);
+    readableStreamClose(controller[_stream]);
+  }
+}
+
+function readableStreamClose(stream: ReadableStream<any>): void {
+  if (stream[_state] !== "readable") return;
+  stream[_state] = "closed";
+  const reader = stream[_reader];
+  if (reader === undefined) return;
+  reader[_closedPromise].resolve(undefined);
+  const requests = reader[_readRequests];
+  reader[_readRequests] = [];
+  if (reader instanceof ReadableStreamBYOBReader) {
+    for (const request of requests as ReadIntoRequest[]) {
+      request.close(filledView(request.view, 0));
+    }
+  } else {
+    for (const request of requests as ReadRequest[]) request.close();
+  }
+}
+
+function readableStreamError(stream: ReadableStream<any>, error: unknown): void {
+  stream[_state] = "errored";
+  stream[_storedError] = error;
+  const reader = stream[_reader];
+  if (reader === undefined) return;
+  reader[_closedPromise].reject(error);
+  reader[_closedPromise].promise.catch(() => {});
+  const requests = reader[_readRequests];
+  reader[_readRequests] = [];
+  for (const request of requests) request.error(error);
+}
+
+function readableStreamCancel(
+  stream: ReadableStream<any>,
+  reason: unknown,
+): Promise<void> {
+  stream[_disturbed] = true;
+  if (stream[_state] === "closed") return Promise.resolve();
+  if (stream[_state] === "errored") {
+    return Promise.reject(stream[_storedError]);
+  }
+  readableStreamClose(stream);
+  const controller = stream[_controller];
+  resetQueue(controller);
+  const cancel = controller[_cancelAlgorithm];
+  readableControllerClearAlgorithms(controller);
+  return cancel === undefined ? Promise.resolve() : cancel(reason);
+}
+
+// https://streams.spec.whatwg.org/#generic-reader-mixin
+function readerGenericInitialize(
+  reader: ReadableStreamDefaultReader<any> | ReadableStreamBYOBReader,
+  stream: ReadableStream<any>,
+): void {
+  if (!(stream instanceof ReadableStream)) {
+    throw new TypeError("Expected a ReadableStream");
+  }
+  if (stream.locked) {
+    throw new TypeError("ReadableStream is locked");
+  }
+  reader[_stream] = stream;
+  stream[_reader] = reader;
+  if (stream[_state] === "readable") {
+    reader[_closedPromise] = deferred();
+  } else if (stream[_state] === "closed") {
+    reader[_closedPromise] = resolved(undefined);
+  } else {
+    reader[_closedPromise] = rejectedHandled(stream[_storedError]);
+  }
+}
+
+function readerGenericRelease(
+  reader: ReadableStreamDefaultReader<any> | ReadableStreamBYOBReader,
+): void {
+  const stream = reader[_stream]!;
+  const error = new TypeError("Reader was released");
+  if (reader[_closedPromise].settled) {
+    reader[_closedPromise] = rejectedHandled(error);
+  } else {
+    reader[_closedPromise].reject(error);
+    reader[_closedPromise].promise.catch(() => {});
+  }
+  const requests = reader[_readRequests];
+  reader[_readRequests] = [];
+  for (const request of requests) request.error(error);
+  stream[_reader] = undefined;
+  reader[_stream] = undefined;
+}
+
+// https://streams.spec.whatwg.org/#default-reader-class
+class ReadableStreamDefaultReader<R = unknown> {
+  [_stream]: ReadableStream<R> | undefined;
+  [_closedPromise]!: Deferred<undefined>;
+  [_readRequests]: ReadRequest[] = [];
+
+  constructor(stream: ReadableStream<R>) {
+    readerGenericInitialize(this, stream);
+  }
+
+  get closed(): Promise<undefined> {
+    return this[_closedPromise].promise;
+  }
+
+  read(): Promise<ReadResult<R>> {
+    const stream = this[_stream];
+    if (stream === undefined) {
+      return Promise.reject(new TypeError("Reader has no associated stream"));
+    }
+    const result = deferred<ReadResult<R>>();
+    readableStreamDefaultReaderRead(this, {
+      chunk: (value) => result.resolve({ value: value as R, done: false }),
+      close: () => result.resolve({ value: undefined, done: true }),
+      error: (error) => result.reject(error),
+    });
+    return result.promise;
+  }
+
+  releaseLock(): void {
+    if (this[_stream] === undefined) return;
+    readerGenericRelease(this);
+  }
+
+  cancel(reason?: unknown): Promise<void> {
+    const stream = this[_stream];
+    if (stream === undefined) {
+      return Promise.reject(new TypeError("Reader has no associated stream"));
+    }
+    return readableStreamCancel(stream, reason);
+  }
+}
+
+function readableStreamDefaultReaderRead(
+  reader: ReadableStreamDefaultReader<any>,
+  readRequest: ReadRequest,
+): void {
+  const stream = reader[_stream]!;
+  stream[_disturbed] = true;
+  if (stream[_state] === "closed") {
+    readRequest.close();
+  } else if (stream[_state] === "errored") {
+    readRequest.error(stream[_storedError]);
+  } else {
+    readableControllerPullSteps(stream[_controller], readRequest);
+  }
+}
+
+// https://streams.spec.whatwg.org/#byob-reader-class
+class ReadableStreamBYOBReader {
+  [_stream]: ReadableStream<any> | undefined;
+  [_closedPromise]!: Deferred<undefined>;
+  [_readRequests]: ReadIntoRequest[] = [];
+
+  constructor(stream: ReadableStream<any>) {
+    if (!(stream instanceof ReadableStream)) {
+      throw new TypeError("Expected a ReadableStream");
+    }
+    if (!stream[_controller][_isBytes]) {
+      throw new TypeError("A BYOB reader needs a byte stream");
+    }
+    readerGenericInitialize(this, stream);
+  }
+
+  get closed(): Promise<undefined> {
+    return this[_closedPromise].promise;
+  }
+
+  read<T extends ArrayBufferView>(view: T): Promise<ReadResult<T>> {
+    if (!isArrayBufferView(view)) {
+      return Promise.reject(new TypeError("view must be an ArrayBufferView"));
+    }
+    if (view.byteLength === 0) {
+      return Promise.reject(new TypeError("view must not be empty"));
+    }
+    const stream = this[_stream];
+    if (stream === undefined) {
+      return Promise.reject(new TypeError("Reader has no associated stream"));
+    }
+    stream[_disturbed] = true;
+    if (stream[_state] === "errored") {
+      return Promise.reject(stream[_storedError]);
+    }
+    const result = deferred<ReadResult<T>>();
+    readableControllerPullIntoSteps(stream[_controller], {
+      view,
+      chunk: (value) => result.resolve({ value: value as T, done: false }),
+      close: (value) => result.resolve({ value: value as T, done: true }),
+      error: (error) => result.reject(error),
+    });
+    return result.promise;
+  }
+
+  releaseLock(): void {
+    if (this[_stream] === undefined) return;
+    readerGenericRelease(this);
+  }
+
+  cancel(reason?: unknown): Promise<void> {
+    const stream = this[_stream];
+    if (stream === undefined) {
+      return Promise.reject(new TypeError("Reader has no associated stream"));
+    }
+    return readableStreamCancel(stream, reason);
+  }
+}
+
+// %AsyncIteratorPrototype%, so stream iterators are async iterables too
+const asyncIteratorPrototype = Object.getPrototypeOf(
+  Object.getPrototypeOf(async function* () {}).prototype,
+);
+
+function readableStreamIterator<R>(
+  stream: ReadableStream<R>,
+  preventCancel: boolean,
+): AsyncIterableIterator<R> {
+  const reader = new ReadableStreamDefaultReader<R>(stream);
+  let finished = false;
+  const iterator = Object.create(asyncIteratorPrototype);
+  iterator.next = async (): Promise<IteratorResult<R>> => {
+    if (finished) return { value: undefined, done: true };
+    try {
+      const result = await reader.read();
+      if (result.done) {
+        finished = true;
+        reader.releaseLock();
+      }
+      return result as IteratorResult<R>;
+    } catch (error) {
+      finished = true;
+      reader.releaseLock();
+      throw error;
+    }
+  };
+  iterator.return = async (value?: unknown): Promise<IteratorResult<R>> => {
+    if (!finished) {
+      finished = true;
+      if (!preventCancel) {
+        const cancelled = reader.cancel(value);
+        reader.releaseLock();
+        await cancelled;
+      } else {
+        reader.releaseLock();
+      }
+    }
+    return { value: value as R, done: true };
+  };
+  return iterator;
+}
+
+function readableStreamTee<R>(
+  stream: ReadableStream<R>,
+): [ReadableStream<R>, ReadableStream<R>] {
+  const reader = new ReadableStreamDefaultReader<R>(stream);
+  let reading = false;
+  let readAgain = false;
+  let canceled1 = false;
+  let canceled2 = false;
+  let reason1: unknown;
+  let reason2: unknown;
+  const cancelPromise = deferred<void>();
+
+  const pull = (): Promise<void> => {
+    if (reading) {
+      readAgain = true;
+      return Promise.resolve();
+    }
+    reading = true;
+    readableStreamDefaultReaderRead(reader, {
+      // Deferred, so a branch that errors while enqueuing can't affect this read
+      chunk: (chunk) =>
+        Promise.resolve().then(() => {
+          readAgain = false;
+          if (!canceled1) readableControllerEnqueue(branch1[_controller], chunk);
+          if (!canceled2) readableControllerEnqueue(branch2[_controller], chunk);
+          reading = false;
+          if (readAgain) pull();
+        }),
+      close: () => {
+        reading = false;
+        if (!canceled1) readableControllerClose(branch1[_controller]);
+        if (!canceled2) readableControllerClose(branch2[_controller]);
+        if (!canceled1 || !canceled2) cancelPromise.resolve();
+      },
+      error: () => {
+        reading = false;
+      },
+    });
+    return Promise.resolve();
+  };
+
+  const cancelBoth = () => {
+    readableStreamCancel(stream, [reason1, reason2]).then(
+      () => cancelPromise.resolve(),
+      (error) => cancelPromise.reject(error),
+    );
+  };
+  const branch1 = createReadableStream<R>(() => {}, pull, (reason) => {
+    canceled1 = true;
+    reason1 = reason;
+    if (canceled2) cancelBoth();
+    return cancelPromise.promise;
+  });
+  const branch2 = createReadableStream<R>(() => {}, pull, (reason) => {
+    canceled2 = true;
+    reason2 = reason;
+    if (canceled1) cancelBoth();
+    return cancelPromise.promise;
+  });
+
+  reader[_closedPromise].promise.catch((error) => {
+    readableControllerError(branch1[_controller], error);
+    readableControllerError(branch2[_controller], error);
+    if (!canceled1 || !canceled2) cancelPromise.resolve();
+  });
+  return [branch1, branch2];
+}
+
+interface PipeOptions {
+  preventClose?: boolean;
+  preventAbort?: boolean;
+  preventCancel?: boolean;
+  signal?: AbortSignal;
+}
+
+// https://streams.spec.whatwg.org/#readable-stream-pipe-to
+function readableStreamPipeTo<R>(
+  source: ReadableStream<R>,
+  dest: WritableStream<R>,
+  options: PipeOptions,
+): Promise<void> {
+  const preventClose = Boolean(options?.preventClose);
+  const preventAbort = Boolean(options?.preventAbort);
+  const preventCancel = Boolean(options?.preventCancel);
+  const signal = options?.signal;
+  if (signal !== undefined && !(signal instanceof AbortSignal)) {
+    return Promise.reject(new TypeError("signal must be an AbortSignal"));
+  }
+
+  const reader = new ReadableStreamDefaultReader<R>(source);
+  const writer = new WritableStreamDefaultWriter<R>(dest);
+  source[_disturbed] = true;
+  const result = deferred<void>();
+  let shuttingDown = false;
+  let currentWrite: Promise<void> = Promise.resolve();
+
+  const waitForWrites = (): Promise<void> => {
+    const write = currentWrite;
+    return write.then(() => write === currentWrite ? undefined : waitForWrites());
+  };
+  const finalize = (isError: boolean, error?: unknown) => {
+    writer.releaseLock();
+    reader.releaseLock();
+    signal?.removeEventListener("abort", abort);
+    if (isError) result.reject(error);
+    else result.resolve();
+  };
+  // Wait for pending writes, unless the destination can't take them anyway
+  const afterWrites = (then: () => void) => {
+    if (dest[_state] === "writable" && !writableStreamCloseQueuedOrInFlight(dest)) {
+      waitForWrites().then(then);
+    } else {
+      then();
+    }
+  };
+  const shutdownWithAction = (
+    action: () => Promise<unknown>,
+    isError = false,
+    error?: unknown,
+  ) => {
+    if (shuttingDown) return;
+    shuttingDown = true;
+    afterWrites(() => {
+      action().then(
+        () => finalize(isError, error),
+        (actionError) => finalize(true, actionError),
+      );
+    });
+  };
+  const shutdown = (isError = false, error?: unknown) => {
+    if (shuttingDown) return;
+    shuttingDown = true;
+    afterWrites(() => finalize(isError, error));
+  };
+
+  function abort() {
+    const error = signal!.reason;
+    const actions: (() => Promise<void>)[] = [];
+    if (!preventAbort) {
+      actions.push(() =>
+        dest[_state] === "writable"
+          ? writableStreamAbort(dest, error)
+          : Promise.resolve()
+      );
+    }
+    if (!preventCancel) {
+      actions.push(() =>
+        source[_state] === "readable"
+          ? readableStreamCancel(source, error)
+          : Promise.resolve()
+      );
+    }
+    shutdownWithAction(
+      () => Promise.all(actions.map((action) => action())),
+      true,
+      error,
+    );
+  }
+
+  const onSourceErrored = (error: unknown) => {
+    if (!preventAbort) {
+      shutdownWithAction(() => writableStreamAbort(dest, error), true, error);
+    } else {
+      shutdown(true, error);
+    }
+  };
+  const onDestErrored = (error: unknown) => {
+    if (!preventCancel) {
+      shutdownWithAction(() => readableStreamCancel(source, error), true, error);
+    } else {
+      shutdown(true, error);
+    }
+  };
+  const onSourceClosed = () => {
+    if (!preventClose) {
+      shutdownWithAction(() => writableStreamDefaultWriterCloseWithErrorPropagation(writer));
+    } else {
+      shutdown();
+    }
+  };
+  const onDestClosed = () => {
+    const error = new TypeError("The destination stream is closed");
+    if (!preventCancel) {
+      shutdownWithAction(() => readableStreamCancel(source, error), true, error);
+    } else {
+      shutdown(true, error);
+    }
+  };
+
+  if (signal?.aborted) {
+    abort();
+    return result.promise;
+  }
+  signal?.addEventListener("abort", abort);
+
+  // Check the current states first, in the order the spec gives
+  if (source[_state] === "errored") {
+    onSourceErrored(source[_storedError]);
+  } else if (dest[_state] === "errored" || dest[_state] === "erroring") {
+    onDestErrored(dest[_storedError]);
+  } else if (source[_state] === "closed") {
+    onSourceClosed();
+  } else if (writableStreamCloseQueuedOrInFlight(dest) || dest[_state] === "closed") {
+    onDestClosed();
+  }
+  reader[_closedPromise].promise.then(
+    () => onSourceClosed(),
+    (error) => {
+      if (source[_state] === "errored") onSourceErrored(error);
+    },
+  );
+  writer[_closedPromise].promise.catch((error) => {
+    if (dest[_state] === "errored") onDestErrored(error);
+  });
+
+  const pipeLoop = () => {
+    if (shuttingDown) return;
+    writer[_readyPromise].promise.then(() => {
+      if (shuttingDown) return;
+      readableStreamDefaultReaderRead(reader, {
+        chunk: (chunk) => {
+          currentWrite = writableStreamDefaultWriterWrite(writer, chunk as R)
+            .catch(() => {});
+          pipeLoop();
+        },
+        close: () => {},
+        error: () => {},
+      });
+    }, () => {});
+  };
+  pipeLoop();
+  return result.promise;
+}
+
+// ---------------------------------------------------------------------------
+// Writable streams
+
+type WritableState = "writable" | "closed" | "erroring" | "errored";
+
+interface PendingAbortRequest {
+  promise: Deferred<void>;
+  reason: unknown;
+  wasAlreadyErroring: boolean;
+}
+
+// https://streams.spec.whatwg.org/#ws-class
+class WritableStream<W = unknown> {
+  [_state]: WritableState = "writable";
+  [_storedError]: unknown = undefined;
+  [_writer]: WritableStreamDefaultWriter<W> | undefined = undefined;
+  [_controller]!: WritableStreamDefaultController;
+  [_writeRequests]: Deferred<void>[] = [];
+  [_inFlightWriteRequest]: Deferred<void> | undefined = undefined;
+  [_closeRequest]: Deferred<void> | undefined = undefined;
+  [_inFlightCloseRequest]: Deferred<void> | undefined = undefined;
+  [_pendingAbortRequest]: PendingAbortRequest | undefined = undefined;
+  [_backpressure] = false;
+
+  constructor(underlyingSink: any = undefined, strategy: any = {}) {
+    if (underlyingSink === null) {
+      throw new TypeError("underlyingSink cannot be null");
+    }
+    const sink = underlyingSink ?? {};
+    if (sink.type !== undefined) {
+      throw new RangeError("Invalid WritableStream type");
+    }
+    const start = getCallback(sink, "start");
+    const write = getCallback(sink, "write");
+    const close = getCallback(sink, "close");
+    const abort = getCallback(sink, "abort");
+    const controller = new WritableStreamDefaultController(illegalConstructor);
+    setUpWritableStreamController(
+      this,
+      controller,
+      () => start?.call(sink, controller),
+      (chunk) => promiseCall(write, sink, chunk, controller),
+      () => promiseCall(close, sink),
+      (reason) => promiseCall(abort, sink, reason),
+      extractHighWaterMark(strategy, 1),
+      extractSizeAlgorithm(strategy),
+    );
+  }
+
+  get locked(): boolean {
+    return this[_writer] !== undefined;
+  }
+
+  abort(reason?: unknown): Promise<void> {
+    if (this.locked) {
+      return Promise.reject(
+        new TypeError("Cannot abort a locked WritableStream"),
+      );
+    }
+    return writableStreamAbort(this, reason);
+  }
+
+  close(): Promise<void> {
+    if (this.locked) {
+      return Promise.reject(
+        new TypeError("Cannot close a locked WritableStream"),
+      );
+    }
+    if (writableStreamCloseQueuedOrInFlight(this)) {
+      return Promise.reject(new TypeError("The stream is already closing"));
+    }
+    return writableStreamClose(this);
+  }
+
+  getWriter(): WritableStreamDefaultWriter<W> {
+    return new WritableStreamDefaultWriter(this);
+  }
+}
+
+// A writable stream driven by internal algorithms rather than a sink object
+function createWritableStream<W>(
+  start: () => unknown,
+  write: Algorithm<W>,
+  close: Algorithm,
+  abort: Algorithm,
+  highWaterMark: number,
+  size: (chunk: unknown) => number,
+): WritableStream<W> {
+  const stream: WritableStream<W> = Object.create(WritableStream.prototype);
+  stream[_state] = "writable";
+  stream[_storedError] = undefined;
+  stream[_writer] = undefined;
+  stream[_writeRequests] = [];
+  stream[_inFlightWriteRequest] = undefined;
+  stream[_closeRequest] = undefined;
+  stream[_inFlightCloseRequest] = undefined;
+  stream[_pendingAbortRequest] = undefined;
+  stream[_backpressure] = false;
+  setUpWritableStreamController(
+    stream,
+    new WritableStreamDefaultController(illegalConstructor),
+    start,
+    write as Algorithm,
+    close,
+    abort,
+    highWaterMark,
+    size,
+  );
+  return stream;
+}
+
+function setUpWritableStreamController(
+  stream: WritableStream<any>,
+  controller: WritableStreamDefaultController,
+  start: () => unknown,
+  write: Algorithm,
+  close: Algorithm,
+  abort: Algorithm,
+  highWaterMark: number,
+  size: (chunk: unknown) => number,
+): void {
+  controller[_stream] = stream;
+  stream[_controller] = controller;
+  resetQueue(controller);
+  controller[_abortController] = new AbortController();
+  controller[_started] = false;
+  controller[_size] = size;
+  controller[_highWaterMark] = highWaterMark;
+  controller[_writeAlgorithm] = write;
+  controller[_closeAlgorithm] = close;
+  controller[_abortAlgorithm] = abort;
+  writableStreamUpdateBackpressure(
+    stream,
+    writableControllerGetBackpressure(controller),
+  );
+
+  Promise.resolve(start()).then(() => {
+    controller[_started] = true;
+    writableControllerAdvanceQueueIfNeeded(controller);
+  }, (error) => {
+    controller[_started] = true;
+    writableStreamDealWithRejection(stream, error);
+  });
+}
+
+function writableStreamAbort(
+  stream: WritableStream<any>,
+  reason: unknown,
+): Promise<void> {
+  if (stream[_state] === "closed" || stream[_state] === "errored") {
+    return Promise.resolve();
+  }
+  stream[_controller][_abortController].abort(reason);
+  // Abort listeners may have closed or errored the stream
+  const state = stream[_state] as WritableState;
+  if (state === "closed" || state === "errored") return Promise.resolve();
+  if (stream[_pendingAbortRequest] !== undefined) {
+    return stream[_pendingAbortRequest].promise.promise;
+  }
+  const wasAlreadyErroring = state === "erroring";
+  const promise = deferred<void>();
+  stream[_pendingAbortRequest] = {
+    promise,
+    reason: wasAlreadyErroring ? undefined : reason,
+    wasAlreadyErroring,
+  };
+  if (!wasAlreadyErroring) writableStreamStartErroring(stream, reason);
+  return promise.promise;
+}
+
+function writableStreamClose(stream: WritableStream<any>): Promise<void> {
+  const state = stream[_state];
+  if (state === "closed" || state === "errored") {
+    return Promise.reject(new TypeError("The stream is closed or errored"));
+  }
+  const promise = deferred<void>();
+  stream[_closeRequest] = promise;
+  const writer = stream[_writer];
+  if (writer !== undefined && stream[_backpressure] && state === "writable") {
+    writer[_readyPromise].resolve(undefined);
+  }
+  const controller = stream[_controller];
+  enqueueValueWithSize(controller, closeSentinel, 0);
+  writableControllerAdvanceQueueIfNeeded(controller);
+  return promise.promise;
+}
+
+function writableStreamCloseQueuedOrInFlight(stream: WritableStream<any>): boolean {
+  return stream[_closeRequest] !== undefined ||
+    stream[_inFlightCloseRequest] !== undefined;
+}
+
+function writableStreamDealWithRejection(
+  stream: WritableStream<any>,
+  error: unknown,
+): void {
+  if (stream[_state] === "writable") {
+    writableStreamStartErroring(stream, error);
+  } else {
+    writableStreamFinishErroring(stream);
+  }
+}
+
+function writableStreamStartErroring(
+  stream: WritableStream<any>,
+  reason: unknown,
+): void {
+  const controller = stream[_controller];
+  stream[_state] = "erroring";
+  stream[_storedError] = reason;
+  const writer = stream[_writer];
+  if (writer !== undefined) writerEnsureReadyPromiseRejected(writer, reason);
+  const inFlight = stream[_inFlightWriteRequest] !== undefined ||
+    stream[_inFlightCloseRequest] !== undefined;
+  if (!inFlight && controller[_started]) writableStreamFinishErroring(stream);
+}
+
+function writableStreamFinishErroring(stream: WritableStream<any>): void {
+  stream[_state] = "errored";
+  resetQueue(stream[_controller]);
+  const storedError = stream[_storedError];
+  for (const request of stream[_writeRequests]) request.reject(storedError);
+  stream[_writeRequests] = [];
+  const abortRequest = stream[_pendingAbortRequest];
+  if (abortRequest === undefined) {
+    writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+    return;
+  }
+  stream[_pendingAbortRequest] = undefined;
+  if (abortRequest.wasAlreadyErroring) {
+    abortRequest.promise.reject(storedError);
+    writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+    return;
+  }
+  const controller = stream[_controller];
+  const abort = controller[_abortAlgorithm];
+  writableControllerClearAlgorithms(controller);
+  const aborted = abort === undefined
+    ? Promise.resolve()
+    : abort(abortRequest.reason);
+  aborted.then(() => {
+    abortRequest.promise.resolve();
+    writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+  }, (error) => {
+    abortRequest.promise.reject(error);
+    writableStreamRejectCloseAndClosedPromiseIfNeeded(stream);
+  });
+}
+
+function writableStreamRejectCloseAndClosedPromiseIfNeeded(
+  stream: WritableStream<any>,
+): void {
+  const storedError = stream[_storedError];
+  if (stream[_closeRequest] !== undefined) {
+    stream[_closeRequest].reject(storedError);
+    stream[_closeRequest] = undefined;
+  }
+  const writer = stream[_writer];
+  if (writer !== undefined) {
+    writer[_closedPromise].reject(storedError);
+    writer[_closedPromise].promise.catch(() => {});
+  }
+}
+
+function writableStreamUpdateBackpressure(
+  stream: WritableStream<any>,
+  backpressure: boolean,
+): void {
+  const writer = stream[_writer];
+  if (writer !== undefined && backpressure !== stream[_backpressure]) {
+    if (backpressure) {
+      writer[_readyPromise] = deferred();
+    } else {
+      writer[_readyPromise].resolve(undefined);
+    }
+  }
+  stream[_backpressure] = backpressure;
+}
+
+// https://streams.spec.whatwg.org/#ws-default-controller-class
+class WritableStreamDefaultController {
+  [_stream]!: WritableStream<any>;
+  [_queue]: { value: unknown; size: number }[] = [];
+  [_queueTotalSize] = 0;
+  [_abortController]!: AbortController;
+  [_started] = false;
+  [_highWaterMark] = 1;
+  [_size]: ((chunk: unknown) => number) | undefined;
+  [_writeAlgorithm]: Algorithm | undefined;
+  [_closeAlgorithm]: Algorithm | undefined;
+  [_abortAlgorithm]: Algorithm | undefined;
+
+  constructor(token?: symbol) {
+    if (token !== illegalConstructor) {
+      throw new TypeError("Illegal constructor");
+    }
+  }
+
+  get signal(): AbortSignal {
+    return this[_abortController].signal;
+  }
+
+  error(error?: unknown): void {
+    if (this[_stream][_state] !== "writable") return;
+    writableControllerError(this, error);
+  }
+}
+
+function writableControllerClearAlgorithms(
+  controller: WritableStreamDefaultController,
+): void {
+  controller[_writeAlgorithm] = undefined;
+  controller[_closeAlgorithm] = undefined;
+  controller[_abortAlgorithm] = undefined;
+  controller[_size] = undefined;
+}
+
+function writableControllerGetDesiredSize(
+  controller: WritableStreamDefaultController,
+): number {
+  return controller[_highWaterMark] - controller[_queueTotalSize];
+}
+
+function writableControllerGetBackpressure(
+  controller: WritableStreamDefaultController,
+): boolean {
+  return writableControllerGetDesiredSize(controller) <= 0;
+}
+
+function writableControllerGetChunkSize(
+  controller: WritableStreamDefaultController,
+  chunk: unknown,
+): number {
+  const size = controller[_size];
+  if (size === undefined) return 1;
+  try {
+    return size(chunk);
+  } catch (error) {
+    writableControllerErrorIfNeeded(controller, error);
+    return 1;
+  }
+}
+
+function writableControllerWrite(
+  controller: WritableStreamDefaultController,
+  chunk: unknown,
+  chunkSize: number,
+): void {
+  try {
+    enqueueValueWithSize(controller, chunk, chunkSize);
+  } catch (error) {
+    writableControllerErrorIfNeeded(controller, error);
+    return;
+  }
+  const stream = controller[_stream];
+  if (!writableStreamCloseQueuedOrInFlight(stream) && stream[_state] === "writable") {
+    writableStreamUpdateBackpressure(
+      stream,
+      writableControllerGetBackpressure(controller),
+    );
+  }
+  writableControllerAdvanceQueueIfNeeded(controller);
+}
+
+function writableControllerAdvanceQueueIfNeeded(
+  controller: WritableStreamDefaultController,
+): void {
+  const stream = controller[_stream];
+  if (!controller[_started]) return;
+  if (stream[_inFlightWriteRequest] !== undefined) return;
+  if (stream[_state] === "erroring") {
+    writableStreamFinishErroring(stream);
+    return;
+  }
+  if (controller[_queue].length === 0) return;
+  const value = controller[_queue][0].value;
+  if (value === closeSentinel) {
+    writableControllerProcessClose(controller);
+  } else {
+    writableControllerProcessWrite(controller, value);
+  }
+}
+
+function writableControllerErrorIfNeeded(
+  controller: WritableStreamDefaultController,
+  error: unknown,
+): void {
+  if (controller[_stream][_state] === "writable") {
+    writableControllerError(controller, error);
+  }
+}
+
+function writableControllerError(
+  controller: WritableStreamDefaultController,
+  error: unknown,
+): void {
+  writableControllerClearAlgorithms(controller);
+  writableStreamStartErroring(controller[_stream], error);
+}
+
+function writableControllerProcessClose(
+  controller: WritableStreamDefaultController,
+): void {
+  const stream = controller[_stream];
+  stream[_inFlightCloseRequest] = stream[_closeRequest];
+  stream[_closeRequest] = undefined;
+  dequeueValue(controller);
+  const close = controller[_closeAlgorithm]!;
+  writableControllerClearAlgorithms(controller);
+  close().then(() => {
+    stream[_inFlightCloseRequest]!.resolve();
+    stream[_inFlightCloseRequest] = undefined;
+    if (stream[_state] === "erroring") {
+      stream[_storedError] = undefined;
+      if (stream[_pendingAbortRequest] !== undefined) {
+        stream[_pendingAbortRequest].promise.resolve();
+        stream[_pendingAbortRequest] = undefined;
+      }
+    }
+    stream[_state] = "closed";
+    stream[_writer]?.[_closedPromise].resolve(undefined);
+  }, (error) => {
+    stream[_inFlightCloseRequest]!.reject(error);
+    stream[_inFlightCloseRequest] = undefined;
+    if (stream[_pendingAbortRequest] !== undefined) {
+      stream[_pendingAbortRequest].promise.reject(error);
+      stream[_pendingAbortRequest] = undefined;
+    }
+    writableStreamDealWithRejection(stream, error);
+  });
+}
+
+function writableControllerProcessWrite(
+  controller: WritableStreamDefaultController,
+  chunk: unknown,
+): void {
+  const stream = controller[_stream];
+  stream[_inFlightWriteRequest] = stream[_writeRequests].shift();
+  controller[_writeAlgorithm]!(chunk).then(() => {
+    stream[_inFlightWriteRequest]!.resolve();
+    stream[_inFlightWriteRequest] = undefined;
+    dequeueValue(controller);
+    if (!writableStreamCloseQueuedOrInFlight(stream) && stream[_state] === "writable") {
+      writableStreamUpdateBackpressure(
+        stream,
+        writableControllerGetBackpressure(controller),
+      );
+    }
+    writableControllerAdvanceQueueIfNeeded(controller);
+  }, (error) => {
+    if (stream[_state] === "writable") {
+      writableControllerClearAlgorithms(controller);
+    }
+    stream[_inFlightWriteRequest]!.reject(error);
+    stream[_inFlightWriteRequest] = undefined;
+    writableStreamDealWithRejection(stream, error);
+  });
+}
+
+// https://streams.spec.whatwg.org/#default-writer-class
+class WritableStreamDefaultWriter<W = unknown> {
+  [_stream]: WritableStream<W> | undefined;
+  [_readyPromise]!: Deferred<undefined>;
+  [_closedPromise]!: Deferred<undefined>;
+
+  constructor(stream: WritableStream<W>) {
+    if (!(stream instanceof WritableStream)) {
+      throw new TypeError("Expected a WritableStream");
+    }
+    if (stream.locked) {
+      throw new TypeError("WritableStream is locked");
+    }
+    this[_stream] = stream;
+    stream[_writer] = this;
+    const state = stream[_state];
+    if (state === "writable") {
+      this[_readyPromise] =
+        !writableStreamCloseQueuedOrInFlight(stream) && stream[_backpressure]
+          ? deferred()
+          : resolved(undefined);
+      this[_closedPromise] = deferred();
+    } else if (state === "erroring") {
+      this[_readyPromise] = rejectedHandled(stream[_storedError]);
+      this[_closedPromise] = deferred();
+    } else if (state === "closed") {
+      this[_readyPromise] = resolved(undefined);
+      this[_closedPromise] = resolved(undefined);
+    } else {
+      this[_readyPromise] = rejectedHandled(stream[_storedError]);
+      this[_closedPromise] = rejectedHandled(stream[_storedError]);
+    }
+  }
+
+  get closed(): Promise<undefined> {
+    return this[_closedPromise].promise;
+  }
+
+  get ready(): Promise<undefined> {
+    return this[_readyPromise].promise;
+  }
+
+  get desiredSize(): number | null {
+    const stream = this[_stream];
+    if (stream === undefined) {
+      throw new TypeError("Writer has no associated stream");
+    }
+    const state = stream[_state];
+    if (state === "errored" || state === "erroring") return null;
+    if (state === "closed") return 0;
+    return writableControllerGetDesiredSize(stream[_controller]);
+  }
+
+  abort(reason?: unknown): Promise<void> {
+    const stream = this[_stream];
+    if (stream === undefined) {
+      return Promise.reject(new TypeError("Writer has no associated stream"));
+    }
+    return writableStreamAbort(stream, reason);
+  }
+
+  close(): Promise<void> {
+    const stream = this[_stream];
+    if (stream === undefined) {
+      return Promise.reject(new TypeError("Writer has no associated stream"));
+    }
+    if (writableStreamCloseQueuedOrInFlight(stream)) {
+      return Promise.reject(new TypeError("The stream is already closing"));
+    }
+    return writableStreamClose(stream);
+  }
+
+  releaseLock(): void {
+    const stream = this[_stream];
+    if (stream === undefined) return;
+    const error = new TypeError("Writer was released");
+    writerEnsureReadyPromiseRejected(this, error);
+    if (this[_closedPromise].settled) {
+      this[_closedPromise] = rejectedHandled(error);
+    } else {
+      this[_closedPromise].reject(error);
+      this[_closedPromise].promise.catch(() => {});
+    }
+    stream[_writer] = undefined;
+    this[_stream] = undefined;
+  }
+
+  write(chunk: W): Promise<void> {
+    if (this[_stream] === undefined) {
+      return Promise.reject(new TypeError("Writer has no associated stream"));
+    }
+    return writableStreamDefaultWriterWrite(this, chunk);
+  }
+}
+
+function writerEnsureReadyPromiseRejected(
+  writer: WritableStreamDefaultWriter<any>,
+  error: unknown,
+): void {
+  if (writer[_readyPromise].settled) {
+    writer[_readyPromise] = rejectedHandled(error);
+  } else {
+    writer[_readyPromise].reject(error);
+    writer[_readyPromise].promise.catch(() => {});
+  }
+}
+
+function writableStreamDefaultWriterWrite<W>(
+  writer: WritableStreamDefaultWriter<W>,
+  chunk: W,
+): Promise<void> {
+  const stream = writer[_stream]!;
+  const controller = stream[_controller];
+  const chunkSize = writableControllerGetChunkSize(controller, chunk);
+  if (stream !== writer[_stream]) {
+    return Promise.reject(new TypeError("Writer was released"));
+  }
+  const state = stream[_state];
+  if (state === "errored" || state === "erroring") {
+    return Promise.reject(stream[_storedError]);
+  }
+  if (writableStreamCloseQueuedOrInFlight(stream) || state === "closed") {
+    return Promise.reject(new TypeError("The stream is closing or closed"));
+  }
+  const promise = deferred<void>();
+  stream[_writeRequests].push(promise);
+  writableControllerWrite(controller, chunk, chunkSize);
+  return promise.promise;
+}
+
+function writableStreamDefaultWriterCloseWithErrorPropagation(
+  writer: WritableStreamDefaultWriter<any>,
+): Promise<void> {
+  const stream = writer[_stream]!;
+  const state = stream[_state];
+  if (writableStreamCloseQueuedOrInFlight(stream) || state === "closed") {
+    return Promise.resolve();
+  }
+  if (state === "errored") return Promise.reject(stream[_storedError]);
+  return writableStreamClose(stream);
+}
+
+// ---------------------------------------------------------------------------
+// Transform streams
+
+// https://streams.spec.whatwg.org/#ts-class
+class TransformStream<I = unknown, O = unknown> {
+  [_readable]!: ReadableStream<O>;
+  [_writable]!: WritableStream<I>;
+  [_backpressure]: boolean | undefined;
+  [_backpressureChangePromise]: Deferred<void> | undefined;
+  [_controller]!: TransformStreamDefaultController<O>;
+
+  constructor(
+    transformer: any = undefined,
+    writableStrategy: any = {},
+    readableStrategy: any = {},
+  ) {
+    if (transformer === null) {
+      throw new TypeError("transformer cannot be null");
+    }
+    const source = transformer ?? {};
+    if (source.readableType !== undefined || source.writableType !== undefined) {
+      throw new RangeError("Invalid TransformStream type");
+    }
+    const start = getCallback(source, "start");
+    const transform = getCallback(source, "transform");
+    const flush = getCallback(source, "flush");
+    const cancel = getCallback(source, "cancel");
+    const startPromise = deferred<void>();
+    initializeTransformStream(
+      this,
+      startPromise.promise,
+      extractHighWaterMark(writableStrategy, 1),
+      extractSizeAlgorithm(writableStrategy),
+      extractHighWaterMark(readableStrategy, 0),
+      extractSizeAlgorithm(readableStrategy),
+    );
+
+    const controller = new TransformStreamDefaultController<O>(illegalConstructor);
+    controller[_stream] = this;
+    this[_controller] = controller;
+    controller[_transformAlgorithm] = transform === undefined
+      ? (chunk) => {
+        try {
+          controller.enqueue(chunk as O);
+          return Promise.resolve();
+        } catch (error) {
+          return Promise.reject(error);
+        }
+      }
+      : (chunk) => promiseCall(transform, source, chunk, controller);
+    controller[_flushAlgorithm] = () => promiseCall(flush, source, controller);
+    controller[_cancelAlgorithm] = (reason) => promiseCall(cancel, source, reason);
+
+    try {
+      startPromise.resolve(start?.call(source, controller) as undefined);
+    } catch (error) {
+      startPromise.reject(error);
+      throw error;
+    }
+  }
+
+  get readable(): ReadableStream<O> {
+    return this[_readable];
+  }
+
+  get writable(): WritableStream<I> {
+    return this[_writable];
+  }
+}
+
+function initializeTransformStream(
+  stream: TransformStream<any, any>,
+  startPromise: Promise<void>,
+  writableHighWaterMark: number,
+  writableSize: (chunk: unknown) => number,
+  readableHighWaterMark: number,
+  readableSize: (chunk: unknown) => number,
+): void {
+  const start = () => startPromise;
+  stream[_writable] = createWritableStream(
+    start,
+    (chunk) => transformStreamSinkWrite(stream, chunk),
+    () => transformStreamSinkClose(stream),
+    (reason) => transformStreamSinkAbort(stream, reason),
+    writableHighWaterMark,
+    writableSize,
+  );
+  stream[_readable] = createReadableStream(
+    start,
+    () => transformStreamSourcePull(stream),
+    (reason) => transformStreamSourceCancel(stream, reason),
+    readableHighWaterMark,
+    readableSize,
+  );
+  stream[_backpressure] = undefined;
+  stream[_backpressureChangePromise] = undefined;
+  transformStreamSetBackpressure(stream, true);
+}
+
+// https://streams.spec.whatwg.org/#ts-default-controller-class
+class TransformStreamDefaultController<O = unknown> {
+  [_stream]!: TransformStream<any, O>;
+  [_transformAlgorithm]: Algorithm | undefined;
+  [_flushAlgorithm]: Algorithm | undefined;
+  [_cancelAlgorithm]: Algorithm | undefined;
+  [_finishPromise]: Deferred<void> | undefined;
+
+  constructor(token?: symbol) {
+    if (token !== illegalConstructor) {
+      throw new TypeError("Illegal constructor");
+    }
+  }
+
+  get desiredSize(): number | null {
+    return readableControllerGetDesiredSize(
+      this[_stream][_readable][_controller],
+    );
+  }
+
+  enqueue(chunk?: O): void {
+    const stream = this[_stream];
+    const readableController = stream[_readable][_controller];
+    if (!readableControllerCanCloseOrEnqueue(readableController)) {
+      throw new TypeError("The readable side is not in a state that permits enqueue");
+    }
+    try {
+      readableControllerEnqueue(readableController, chunk);
+    } catch (error) {
+      transformStreamErrorWritableAndUnblockWrite(stream, error);
+      throw stream[_readable][_storedError];
+    }
+    const backpressure = !readableControllerShouldCallPull(readableController);
+    if (backpressure !== stream[_backpressure]) {
+      transformStreamSetBackpressure(stream, true);
+    }
+  }
+
+  error(reason?: unknown): void {
+    transformStreamError(this[_stream], reason);
+  }
+
+  terminate(): void {
+    const stream = this[_stream];
+    readableControllerClose(stream[_readable][_controller]);
+    transformStreamErrorWritableAndUnblockWrite(
+      stream,
+      new TypeError("The transform stream has been terminated"),
+    );
+  }
+}
+
+function transformControllerClearAlgorithms(
+  controller: TransformStreamDefaultController<any>,
+): void {
+  controller[_transformAlgorithm] = undefined;
+  controller[_flushAlgorithm] = undefined;
+  controller[_cancelAlgorithm] = undefined;
+}
+
+function transformStreamError(
+  stream: TransformStream<any, any>,
+  error: unknown,
+): void {
+  readableControllerError(stream[_readable][_controller], error);
+  transformStreamErrorWritableAndUnblockWrite(stream, error);
+}
+
+function transformStreamErrorWritableAndUnblockWrite(
+  stream: TransformStream<any, any>,
+  error: unknown,
+): void {
+  transformControllerClearAlgorithms(stream[_controller]);
+  writableControllerErrorIfNeeded(stream[_writable][_controller], error);
+  transformStreamUnblockWrite(stream);
+}
+
+function transformStreamUnblockWrite(stream: TransformStream<any, any>): void {
+  if (stream[_backpressure]) transformStreamSetBackpressure(stream, false);
+}
+
+function transformStreamSetBackpressure(
+  stream: TransformStream<any, any>,
+  backpressure: boolean,
+): void {
+  stream[_backpressureChangePromise]?.resolve();
+  stream[_backpressureChangePromise] = deferred();
+  stream[_backpressure] = backpressure;
+}
+
+function transformControllerPerformTransform(
+  controller: TransformStreamDefaultController<any>,
+  chunk: unknown,
+): Promise<void> {
+  return controller[_transformAlgorithm]!(chunk).catch((error) => {
+    transformStreamError(controller[_stream], error);
+    throw error;
+  });
+}
+
+function transformStreamSinkWrite(
+  stream: TransformStream<any, any>,
+  chunk: unknown,
+): Promise<void> {
+  const controller = stream[_controller];
+  if (stream[_backpressure]) {
+    return stream[_backpressureChangePromise]!.promise.then(() => {
+      const writable = stream[_writable];
+      if (writable[_state] === "erroring") throw writable[_storedError];
+      return transformControllerPerformTransform(controller, chunk);
+    });
+  }
+  return transformControllerPerformTransform(controller, chunk);
+}
+
+// Settle a finish promise once the algorithm for the other side is done
+function transformStreamFinish(
+  controller: TransformStreamDefaultController<any>,
+  algorithm: Promise<void>,
+  onFulfilled: (finish: Deferred<void>) => void,
+  onRejected: (error: unknown) => void,
+): Promise<void> {
+  const finish = deferred<void>();
+  controller[_finishPromise] = finish;
+  transformControllerClearAlgorithms(controller);
+  algorithm.then(() => onFulfilled(finish), (error) => {
+    onRejected(error);
+    finish.reject(error);
+  });
+  return finish.promise;
+}
+
+function transformStreamSinkAbort(
+  stream: TransformStream<any, any>,
+  reason: unknown,
+): Promise<void> {
+  const controller = stream[_controller];
+  if (controller[_finishPromise] !== undefined) {
+    return controller[_finishPromise].promise;
+  }
+  const readable = stream[_readable];
+  const cancel = controller[_cancelAlgorithm]!;
+  return transformStreamFinish(controller, cancel(reason), (finish) => {
+    if (readable[_state] === "errored") {
+      finish.reject(readable[_storedError]);
+    } else {
+      readableControllerError(readable[_controller], reason);
+      finish.resolve();
+    }
+  }, (error) => readableControllerError(readable[_controller], error));
+}
+
+function transformStreamSinkClose(stream: TransformStream<any, any>): Promise<void> {
+  const controller = stream[_controller];
+  if (controller[_finishPromise] !== undefined) {
+    return controller[_finishPromise].promise;
+  }
+  const readable = stream[_readable];
+  const flush = controller[_flushAlgorithm]!;
+  return transformStreamFinish(controller, flush(), (finish) => {
+    if (readable[_state] === "errored") {
+      finish.reject(readable[_storedError]);
+    } else {
+      readableControllerClose(readable[_controller]);
+      finish.resolve();
+    }
+  }, (error) => readableControllerError(readable[_controller], error));
+}
+
+function transformStreamSourcePull(stream: TransformStream<any, any>): Promise<void> {
+  transformStreamSetBackpressure(stream, false);
+  return stream[_backpressureChangePromise]!.promise;
+}
+
+function transformStreamSourceCancel(
+  stream: TransformStream<any, any>,
+  reason: unknown,
+): Promise<void> {
+  const controller = stream[_controller];
+  if (controller[_finishPromise] !== undefined) {
+    return controller[_finishPromise].promise;
+  }
+  const writable = stream[_writable];
+  const cancel = controller[_cancelAlgorithm]!;
+  return transformStreamFinish(controller, cancel(reason), (finish) => {
+    if (writable[_state] === "errored") {
+      finish.reject(writable[_storedError]);
+    } else {
+      writableControllerErrorIfNeeded(writable[_controller], reason);
+      transformStreamUnblockWrite(stream);
+      finish.resolve();
+    }
+  }, (error) => {
+    writableControllerErrorIfNeeded(writable[_controller], error);
+    transformStreamUnblockWrite(stream);
+  });
+}
+
+// ---------------------------------------------------------------------------
+// Streams for other modules
+
+// A byte stream over a fetch body, read in chunks as they arrive. `source` is
+// the body's native reader source, only opened by the first read.
+function fromBody(source: {
+  getReader(): { read(): Promise<ReadResult<Uint8Array>>; cancel(): Promise<void> };
+  cancel(): Promise<void>;
+}): ReadableStream<Uint8Array> {
+  let reader: ReturnType<typeof source.getReader> | undefined;
+  return new ReadableStream({
+    type: "bytes",
+    async pull(controller: ReadableByteStreamController) {
+      reader ??= source.getReader();
+      const { value, done } = await reader.read();
+      if (done) {
+        controller.close();
+      } else if (value!.byteLength > 0) {
+        controller.enqueue(value!);
+      }
+    },
+    cancel() {
+      return reader === undefined ? source.cancel() : reader.cancel();
+    },
+  });
+}
+
+// A byte stream over `bytes`, in chunks of at most CHUNK_SIZE
+function fromBytes(bytes: Uint8Array): ReadableStream<Uint8Array> {
+  let offset = 0;
+  return new ReadableStream({
+    type: "bytes",
+    pull(controller: ReadableByteStreamController) {
+      if (offset >= bytes.byteLength) {
+        controller.close();
+        return;
+      }
+      const end = Math.min(offset + CHUNK_SIZE, bytes.byteLength);
+      controller.enqueue(bytes.subarray(offset, end));
+      offset = end;
+    },
+  });
+}
+
+// Whether a body stream counts as used, which like Deno includes being locked
+function isLockedOrDisturbed(stream: ReadableStream<unknown>): boolean {
+  return stream.locked || stream[_disturbed];
+}
+
+__internal.streams = { fromBody, fromBytes, isLockedOrDisturbed };
+
+Object.assign(globalThis, {
+  ReadableStream,
+  ReadableStreamDefaultReader,
+  ReadableStreamBYOBReader,
+  ReadableStreamDefaultController,
+  ReadableByteStreamController,
+  WritableStream,
+  WritableStreamDefaultWriter,
+  WritableStreamDefaultController,
+  TransformStream,
+  TransformStreamDefaultController,
+  ByteLengthQueuingStrategy,
+  CountQueuingStrategy,
+});
@@ -0,0 +1,211 @@
+const decoder = new TextDecoder();
+
+async function collect<T>(stream: ReadableStream<T>): Promise<T[]> {
+  const chunks: T[] = [];
+  for await (const chunk of stream) chunks.push(chunk);
+  return chunks;
+}
+
+Deno.test("ReadableStream queues chunks until they are read", async () => {
+  const stream = new ReadableStream<number>({
+    start(controller) {
+      controller.enqueue(1);
+      controller.enqueue(2);
+      controller.close();
+    },
+  });
+  const reader = stream.getReader();
+  if (!stream.locked) throw new Error("should be locked");
+  try {
+    stream.getReader();
+    throw new Error("second reader should throw");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+  const results = [await reader.read(), await reader.read(), await reader.read()];
+  const seen = results.map(({ value, done }) => `${value}:${done}`).join();
+  if (seen !== "1:false,2:false,undefined:true") throw new Error(seen);
+  await reader.closed;
+  reader.releaseLock();
+  if (stream.locked) throw new Error("should be released");
+});
+
+Deno.test("ReadableStream pulls on demand and can be cancelled", async () => {
+  let pulls = 0;
+  let cancelled: unknown;
+  const stream = new ReadableStream<number>({
+    pull(controller) {
+      controller.enqueue(++pulls);
+    },
+    cancel(reason) {
+      cancelled = reason;
+    },
+  }, new CountQueuingStrategy({ highWaterMark: 0 }));
+  const reader = stream.getReader();
+  await reader.read();
+  await reader.read();
+  if (pulls !== 2) throw new Error(`pulls: ${pulls}`);
+  await reader.cancel("done");
+  if (cancelled !== "done") throw new Error(`cancelled: ${cancelled}`);
+  const { done } = await reader.read();
+  if (!done) throw new Error("should be closed after cancel");
+});
+
+Deno.test("ReadableStream errors reject reads", async () => {
+  const stream = new ReadableStream({
+    start(controller) {
+      controller.error(new RangeError("broken"));
+    },
+  });
+  try {
+    await stream.getReader().read();
+    throw new Error("should reject");
+  } catch (error) {
+    if (!(error instanceof RangeError)) throw error;
+  }
+});
+
+Deno.test("ReadableStream.from and tee", async () => {
+  const [a, b] = ReadableStream.from(["x", "y"]).tee();
+  const chunks = await Promise.all([collect(a), collect(b)]);
+  if (chunks.map((c) => c.join("")).join() !== "xy,xy") {
+    throw new Error(`${chunks}`);
+  }
+});
+
+Deno.test("pipeThrough transforms chunks and flushes", async () => {
+  const upper = new TransformStream<string, string>({
+    transform(chunk, controller) {
+      controller.enqueue(chunk.toUpperCase());
+    },
+    flush(controller) {
+      controller.enqueue("!");
+    },
+  });
+  const chunks = await collect(ReadableStream.from(["a", "b"]).pipeThrough(upper));
+  if (chunks.join("") !== "AB!") throw new Error(chunks.join());
+});
+
+Deno.test("pipeTo writes every chunk and closes the destination", async () => {
+  const written: string[] = [];
+  let closed = false;
+  const destination = new WritableStream<string>({
+    write(chunk) {
+      written.push(chunk);
+    },
+    close() {
+      closed = true;
+    },
+  });
+  await ReadableStream.from(["a", "b", "c"]).pipeTo(destination);
+  if (written.join("") !== "abc" || !closed) {
+    throw new Error(`${written} ${closed}`);
+  }
+
+  // A failing sink cancels the source and rejects the pipe
+  let cancelled: unknown;
+  const source = new ReadableStream({
+    pull(controller) {
+      controller.enqueue("chunk");
+    },
+    cancel(reason) {
+      cancelled = reason;
+    },
+  });
+  const failing = new WritableStream({
+    write() {
+      throw new Error("sink failed");
+    },
+  });
+  try {
+    await source.pipeTo(failing);
+    throw new Error("should reject");
+  } catch (error) {
+    if ((error as Error).message !== "sink failed") throw error;
+  }
+  if ((cancelled as Error)?.message !== "sink failed") {
+    throw new Error(`cancelled: ${cancelled}`);
+  }
+});
+
+Deno.test("WritableStream signals backpressure through ready", async () => {
+  const written: number[] = [];
+  const stream = new WritableStream<number>({
+    async write(chunk) {
+      await new Promise((resolve) => setTimeout(resolve, 1));
+      written.push(chunk);
+    },
+  }, new CountQueuingStrategy({ highWaterMark: 2 }));
+  const writer = stream.getWriter();
+  if (writer.desiredSize !== 2) throw new Error(`${writer.desiredSize}`);
+
+  let maxQueued = 0;
+  for (let i = 0; i < 20; i++) {
+    await writer.ready;
+    writer.write(i);
+    maxQueued = Math.max(maxQueued, 2 - writer.desiredSize!);
+  }
+  await writer.close();
+  if (maxQueued > 2) throw new Error(`queued ${maxQueued} chunks`);
+  if (written.length !== 20) throw new Error(`wrote ${written.length}`);
+  if (writer.desiredSize !== 0) throw new Error(`${writer.desiredSize}`);
+});
+
+Deno.test("WritableStream abort rejects pending writes", async () => {
+  let aborted: unknown;
+  const stream = new WritableStream({
+    write: () => new Promise(() => {}),
+    abort(reason) {
+      aborted = reason;
+    },
+  });
+  const writer = stream.getWriter();
+  writer.write("stuck").catch(() => {});
+  const pending = writer.write("queued");
+  await writer.abort("stop");
+  try {
+    await pending;
+    throw new Error("should reject");
+  } catch (error) {
+    if (error !== "stop") throw error;
+  }
+  if (aborted !== "stop") throw new Error(`aborted: ${aborted}`);
+});
+
+Deno.test("Byte streams support BYOB readers", async () => {
+  const stream = new Blob(["hello world"]).stream();
+  const reader = stream.getReader({ mode: "byob" });
+  const first = await reader.read(new Uint8Array(5));
+  if (decoder.decode(first.value) !== "hello") throw new Error("first read");
+  const rest = await reader.read(new Uint8Array(64));
+  if (decoder.decode(rest.value) !== " world") throw new Error("second read");
+  const end = await reader.read(new Uint8Array(64));
+  if (!end.done || end.value!.byteLength !== 0) throw new Error("end");
+
+  try {
+    new ReadableStream().getReader({ mode: "byob" });
+    throw new Error("a default stream has no BYOB reader");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+});
+
+Deno.test("Response.body is a ReadableStream", async () => {
+  const response = new Response("streamed");
+  const body = response.body!;
+  if (!(body instanceof ReadableStream) || response.body !== body) {
+    throw new Error("body should be a cached ReadableStream");
+  }
+  const chunks = await collect(body);
+  if (!response.bodyUsed) throw new Error("bodyUsed");
+  if (decoder.decode(chunks[0]) !== "streamed") throw new Error("contents");
+});
+
+Deno.test("ByteLengthQueuingStrategy measures chunks by size", () => {
+  const strategy = new ByteLengthQueuingStrategy({ highWaterMark: 16 });
+  if (strategy.highWaterMark !== 16) throw new Error("highWaterMark");
+  if (strategy.size(new Uint8Array(5)) !== 5) throw new Error("size");
+  if (new CountQueuingStrategy({ highWaterMark: 1 }).size() !== 1) {
+    throw new Error("count size");
+  }
+});
@@ -0,0 +1,61 @@
+use futures_util::future::{AbortHandle, Abortable};
+use rquickjs::promise::Promised;
+use rquickjs::{Ctx, Module};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+use utils::add_internal_function;
+use utils_macros::include_ts;
+
+thread_local! {
+    // Sleeps of pending timers by ID, aborted by clearTimeout/clearInterval
+    static SLEEPS: RefCell<HashMap<u32, AbortHandle>> = RefCell::new(HashMap::new());
+}
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    setup_internal(ctx)?;
+    let js_source = include_ts!("timers.ts");
+    let module = Module::evaluate(ctx.clone(), "web_timers", js_source)?;
+    module.finish::<()>()?;
+    Ok(())
+}
+
+fn setup_internal(ctx: &Ctx) -> rquickjs::Result<()> {
+    ctx.eval::<(), _>("globalThis[Symbol.for('mdeno.internal')].timers = {};")?;
+
+    // sleep(id: number, ms: number): Promise<boolean>
+    add_internal_function!(ctx, "timers.sleep", sleep);
+
+    // cancel(id: number): void
+    add_internal_function!(ctx, "timers.cancel", cancel);
+
+    Ok(())
+}
+
+/// Wait `ms` milliseconds for timer `id`, resolving to `false` if the timer
+/// was cleared first. The pending sleep keeps the runtime busy, so
+/// `runtime.idle()` returns only once every timer has fired or been cleared.
+/// The abort handle is registered right away, since a timer may be cleared
+/// before its sleep is first polled.
+fn sleep(id: u32, ms: f64) -> Promised<impl Future<Output = bool>> {
+    let (handle, registration) = AbortHandle::new_pair();
+    SLEEPS.with_borrow_mut(|sleeps| sleeps.insert(id, handle));
+    let duration = Duration::try_from_secs_f64(ms / 1000.0).unwrap_or_default();
+    Promised(async move {
+        let fired = Abortable::new(compio::time::sleep(duration), registration)
+            .await
+            .is_ok();
+        SLEEPS.with_borrow_mut(|sleeps| sleeps.remove(&id));
+        fired
+    })
+}
+
+fn cancel(id: u32) {
+    SLEEPS.with_borrow(|sleeps| {
+        if let Some(handle) = sleeps.get(&id) {
+            handle.abort();
+        }
+    });
+}
@@ -0,0 +1,100 @@
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#timers
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+
+// Longer delays overflow a signed 32-bit integer; like Deno, they become 1ms
+const TIMEOUT_MAX = 2 ** 31 - 1;
+
+// IDs of timers that haven't fired (or, for intervals, been cleared) yet
+const active = new Set<number>();
+let nextId = 1;
+
+// NaN and negative delays are treated as zero
+function toDelay(delay: unknown): number {
+  const ms = Number(delay);
+  if (!(ms > 0)) return 0;
+  return ms > TIMEOUT_MAX ? 1 : ms;
+}
+
+function schedule(
+  id: number,
+  callback: (...args: unknown[]) => void,
+  delay: number,
+  args: unknown[],
+  repeat: boolean,
+): void {
+  __internal.timers.sleep(id, delay).then((fired: boolean) => {
+    if (!fired || !active.has(id)) return;
+    if (!repeat) active.delete(id);
+    try {
+      callback(...args);
+    } catch (error) {
+      active.delete(id);
+      __internal.reportUncaught(error);
+      return;
+    }
+    // The callback may have cleared its own interval
+    if (repeat && active.has(id)) schedule(id, callback, delay, args, true);
+  });
+}
+
+function start(
+  callback: unknown,
+  delay: unknown,
+  args: unknown[],
+  repeat: boolean,
+): number {
+  if (typeof callback !== "function") {
+    throw new TypeError("Callback must be a function");
+  }
+  const id = nextId++;
+  active.add(id);
+  schedule(
+    id,
+    callback as (...args: unknown[]) => void,
+    toDelay(delay),
+    args,
+    repeat,
+  );
+  return id;
+}
+
+function clear(id: unknown): void {
+  const timerId = Number(id);
+  if (active.delete(timerId)) __internal.timers.cancel(timerId);
+}
+
+// https://developer.mozilla.org/en-US/docs/Web/API/Window/setTimeout
+function setTimeout(
+  callback: (...args: unknown[]) => void,
+  delay = 0,
+  ...args: unknown[]
+): number {
+  return start(callback, delay, args, false);
+}
+
+// https://developer.mozilla.org/en-US/docs/Web/API/Window/setInterval
+function setInterval(
+  callback: (...args: unknown[]) => void,
+  delay = 0,
+  ...args: unknown[]
+): number {
+  return start(callback, delay, args, true);
+}
+
+// https://developer.mozilla.org/en-US/docs/Web/API/Window/clearTimeout
+function clearTimeout(id?: number): void {
+  clear(id);
+}
+
+// https://developer.mozilla.org/en-US/docs/Web/API/Window/clearInterval
+function clearInterval(id?: number): void {
+  clear(id);
+}
+
+Object.assign(globalThis, {
+  setTimeout,
+  setInterval,
+  clearTimeout,
+  clearInterval,
+});
@@ -0,0 +1,64 @@
+// setTimeout / setInterval E2E tests
+
+function delay(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+Deno.test("setTimeout passes arguments and returns a positive ID", async () => {
+  const args = await new Promise<unknown[]>((resolve) => {
+    const id = setTimeout((...args: unknown[]) => resolve(args), 1, "a", 2);
+    if (!Number.isInteger(id) || id <= 0) throw new Error(`id: ${id}`);
+  });
+  if (args.join(",") !== "a,2") throw new Error(`args: ${args}`);
+});
+
+Deno.test("setTimeout fires in delay order", async () => {
+  const order: string[] = [];
+  setTimeout(() => order.push("late"), 20);
+  setTimeout(() => order.push("zero"), 0);
+  setTimeout(() => order.push("negative"), -10);
+  setTimeout(() => order.push("NaN"), NaN);
+  await delay(40);
+  if (order.join(",") !== "zero,negative,NaN,late") {
+    throw new Error(`order: ${order}`);
+  }
+});
+
+Deno.test("clearTimeout cancels a pending timer", async () => {
+  let fired = false;
+  const id = setTimeout(() => {
+    fired = true;
+  }, 5);
+  clearTimeout(id);
+  clearTimeout(undefined);
+  clearTimeout(id);
+  await delay(20);
+  if (fired) throw new Error("cleared timer fired");
+});
+
+Deno.test("setInterval repeats until cleared", async () => {
+  let count = 0;
+  await new Promise<void>((resolve) => {
+    const id = setInterval(() => {
+      count++;
+      if (count === 3) {
+        clearInterval(id);
+        resolve();
+      }
+    }, 1);
+  });
+  await delay(10);
+  if (count !== 3) throw new Error(`count: ${count}`);
+});
+
+Deno.test("setTimeout rejects a non-function callback", () => {
+  for (const callback of ["code", 1, null]) {
+    try {
+      // @ts-ignore: testing invalid input
+      setTimeout(callback);
+      throw new Error(`accepted ${callback}`);
+    } catch (error) {
+      if (!(error instanceof TypeError)) throw error;
+    }
+  }
+});
@@ -31,5 +31,12 @@ pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
     // Register URL class
     Class::<Url>::define(&ctx.globals())?;
 
+    // URL.prototype[Symbol.toPrimitive] returns href for every hint
+    let url_ctor: rquickjs::Function = globals.get("URL")?;
+    let url_prototype: rquickjs::Object = url_ctor.get("prototype")?;
+    let to_primitive_symbol: rquickjs::Symbol = symbol_obj.get("toPrimitive")?;
+    let to_string_fn: rquickjs::Function = url_prototype.get("toString")?;
+    url_prototype.set(to_primitive_symbol, to_string_fn)?;
+
     Ok(())
 }
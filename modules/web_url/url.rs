@@ -179,4 +179,96 @@ impl<'js> Url<'js> {
         let base_ref = base.0.as_deref();
         ars::Url::parse(&url, base_ref).is_ok()
     }
+
+    /// Register a blob and return a `blob:` URL for it, usable as a module
+    /// specifier or with `fetch()` until revoked
+    #[qjs(static, rename = "createObjectURL")]
+    pub fn create_object_url(blob: Class<'js, web_blob::Blob>) -> String {
+        web_blob::create_object_url(&blob.borrow())
+    }
+
+    #[qjs(static, rename = "revokeObjectURL")]
+    pub fn revoke_object_url(url: String) {
+        web_blob::revoke_object_url(&url);
+    }
+
+    /// Build a URL string from components, like Node.js `url.format(urlObject)`
+    #[qjs(static)]
+    pub fn format(url_object: rquickjs::Value<'js>) -> rquickjs::Result<String> {
+        if let Some(url) = url_object.as_string() {
+            return url.to_string();
+        }
+        if let Some(url) = utils::class_of::<Url>(&url_object) {
+            return Ok(url.borrow().get_href());
+        }
+        let Some(object) = url_object.as_object() else {
+            return Err(rquickjs::Error::new_from_js(
+                url_object.type_name(),
+                "URL components object",
+            ));
+        };
+        let component = |name: &str| -> rquickjs::Result<String> {
+            Ok(object
+                .get::<_, Option<rquickjs::Coerced<String>>>(name)?
+                .map(|value| value.0)
+                .unwrap_or_default())
+        };
+
+        let mut protocol = component("protocol")?;
+        if !protocol.is_empty() && !protocol.ends_with(':') {
+            protocol.push(':');
+        }
+
+        let mut host = component("host")?;
+        if host.is_empty() {
+            host = component("hostname")?;
+            if host.contains(':') && !host.starts_with('[') {
+                host = format!("[{host}]");
+            }
+            let port = component("port")?;
+            if !host.is_empty() && !port.is_empty() {
+                host = format!("{host}:{port}");
+            }
+        }
+        let auth = component("auth")?;
+        if !host.is_empty() && !auth.is_empty() {
+            host = format!("{auth}@{host}");
+        }
+
+        let slashes = object.get::<_, Option<bool>>("slashes")?.unwrap_or(false)
+            || SLASHED_PROTOCOLS.contains(&protocol.as_str());
+        let mut pathname = component("pathname")?;
+        let prefix = if slashes && (!host.is_empty() || protocol == "file:") {
+            if !pathname.is_empty() && !pathname.starts_with('/') {
+                pathname.insert(0, '/');
+            }
+            "//"
+        } else {
+            ""
+        };
+
+        let mut search = component("search")?;
+        if search.is_empty()
+            && let Some(query) = object.get::<_, Option<rquickjs::Object>>("query")?
+        {
+            let mut params = ars::UrlSearchParams::parse("");
+            for (key, value) in query.props::<String, rquickjs::Coerced<String>>().flatten() {
+                params.append(&key, &value.0);
+            }
+            search = params.to_string();
+        }
+        if !search.is_empty() && !search.starts_with('?') {
+            search.insert(0, '?');
+        }
+
+        let mut hash = component("hash")?;
+        if !hash.is_empty() && !hash.starts_with('#') {
+            hash.insert(0, '#');
+        }
+
+        Ok(format!("{protocol}{prefix}{host}{pathname}{search}{hash}"))
+    }
 }
+
+/// Protocols that Node.js always formats with "//" after the scheme
+const SLASHED_PROTOCOLS: [&str; 7] = ["http:", "https:", "ftp:", "gopher:", "file:", "ws:", "wss:"];
@@ -0,0 +1,808 @@
+// Interpreter for decoded WebAssembly functions
+
+use crate::module::{Func, FuncType, Instr, Module, PAGE_SIZE};
+use std::fmt;
+
+/// Nested wasm calls allowed before `Trap::StackOverflow`
+const MAX_CALL_DEPTH: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Trap {
+    Unreachable,
+    IntegerDivideByZero,
+    IntegerOverflow,
+    InvalidConversion,
+    MemoryOutOfBounds,
+    TableOutOfBounds,
+    NullFunction,
+    SignatureMismatch,
+    StackOverflow,
+    /// Operand stack underflow, which validation rules out; kept so a bug
+    /// here traps instead of panicking
+    InvalidStack,
+    /// An import threw; the exception is still pending in the context
+    Exception,
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unreachable => "unreachable executed",
+            Self::IntegerDivideByZero => "integer divide by zero",
+            Self::IntegerOverflow => "integer overflow",
+            Self::InvalidConversion => "invalid conversion to integer",
+            Self::MemoryOutOfBounds => "memory access out of bounds",
+            Self::TableOutOfBounds => "table index is out of bounds",
+            Self::NullFunction => "null function reference",
+            Self::SignatureMismatch => "function signature mismatch",
+            Self::StackOverflow => "call stack exhausted",
+            Self::InvalidStack => "invalid operand stack",
+            Self::Exception => "exception in imported function",
+        })
+    }
+}
+
+type Result<T> = std::result::Result<T, Trap>;
+
+/// What the interpreter needs from the instance it runs in
+pub(crate) trait Host {
+    /// Call the imported function `func` with raw argument values
+    fn call_import(&mut self, func: u32, args: Vec<u64>) -> Result<Vec<u64>>;
+    /// Current memory contents, empty if the module has no memory
+    fn memory(&mut self) -> &mut [u8];
+    /// Grow memory by `delta` pages, returning the previous size in pages
+    fn grow_memory(&mut self, delta: u32) -> Option<u32>;
+    fn global(&mut self, index: u32) -> u64;
+    fn set_global(&mut self, index: u32, value: u64);
+    /// Function at `index` in the table
+    fn table_get(&mut self, index: u32) -> Result<u32>;
+}
+
+/// Call function `func` of `module` with raw argument values
+pub(crate) fn invoke<H: Host>(
+    module: &Module,
+    host: &mut H,
+    func: u32,
+    args: Vec<u64>,
+) -> Result<Vec<u64>> {
+    Machine {
+        module,
+        host,
+        depth: 0,
+    }
+    .call(func, args)
+}
+
+struct Machine<'a, H> {
+    module: &'a Module,
+    host: &'a mut H,
+    depth: usize,
+}
+
+/// An open block, loop or if
+struct Label {
+    /// Operand stack height below the block's parameters
+    height: usize,
+    /// Values a branch to this label carries
+    arity: usize,
+    /// Where a branch to this label continues
+    target: usize,
+    is_loop: bool,
+}
+
+/// Values stored on the operand stack, as raw bits
+trait Raw: Sized {
+    fn from_raw(value: u64) -> Self;
+    fn raw(self) -> u64;
+}
+
+impl Raw for u32 {
+    fn from_raw(value: u64) -> Self {
+        value as u32
+    }
+    fn raw(self) -> u64 {
+        u64::from(self)
+    }
+}
+
+impl Raw for i32 {
+    fn from_raw(value: u64) -> Self {
+        value as i32
+    }
+    fn raw(self) -> u64 {
+        u64::from(self as u32)
+    }
+}
+
+impl Raw for u64 {
+    fn from_raw(value: u64) -> Self {
+        value
+    }
+    fn raw(self) -> u64 {
+        self
+    }
+}
+
+impl Raw for i64 {
+    fn from_raw(value: u64) -> Self {
+        value as i64
+    }
+    fn raw(self) -> u64 {
+        self as u64
+    }
+}
+
+impl Raw for f32 {
+    fn from_raw(value: u64) -> Self {
+        f32::from_bits(value as u32)
+    }
+    fn raw(self) -> u64 {
+        u64::from(self.to_bits())
+    }
+}
+
+impl Raw for f64 {
+    fn from_raw(value: u64) -> Self {
+        f64::from_bits(value)
+    }
+    fn raw(self) -> u64 {
+        self.to_bits()
+    }
+}
+
+impl Raw for bool {
+    fn from_raw(value: u64) -> Self {
+        value != 0
+    }
+    fn raw(self) -> u64 {
+        u64::from(self)
+    }
+}
+
+fn pop(stack: &mut Vec<u64>) -> Result<u64> {
+    stack.pop().ok_or(Trap::InvalidStack)
+}
+
+/// Split the top `count` values off the stack
+fn pop_n(stack: &mut Vec<u64>, count: usize) -> Result<Vec<u64>> {
+    let at = stack.len().checked_sub(count).ok_or(Trap::InvalidStack)?;
+    Ok(stack.split_off(at))
+}
+
+impl<H: Host> Machine<'_, H> {
+    fn call(&mut self, func: u32, args: Vec<u64>) -> Result<Vec<u64>> {
+        if func < self.module.imported_funcs {
+            return self.host.call_import(func, args);
+        }
+        let module = self.module;
+        let Some(def) = module.funcs.get((func - module.imported_funcs) as usize) else {
+            return Err(Trap::NullFunction);
+        };
+        let ty = &module.types[def.type_index as usize];
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(Trap::StackOverflow);
+        }
+        self.depth += 1;
+        let result = self.execute(def, ty, args);
+        self.depth -= 1;
+        result
+    }
+
+    fn execute(&mut self, func: &Func, ty: &FuncType, mut locals: Vec<u64>) -> Result<Vec<u64>> {
+        locals.resize(ty.params.len() + func.locals.len(), 0);
+        let code = &func.body;
+        let mut stack: Vec<u64> = Vec::new();
+        let mut labels: Vec<Label> = Vec::new();
+        let mut pc = 0;
+
+        while let Some(instr) = code.get(pc) {
+            pc += 1;
+            match instr {
+                Instr::Unreachable => return Err(Trap::Unreachable),
+                Instr::Nop => {}
+                Instr::Block {
+                    params,
+                    results,
+                    end,
+                } => {
+                    labels.push(Label {
+                        height: stack.len().saturating_sub(*params as usize),
+                        arity: *results as usize,
+                        target: *end as usize + 1,
+                        is_loop: false,
+                    });
+                }
+                Instr::Loop { params } => {
+                    labels.push(Label {
+                        height: stack.len().saturating_sub(*params as usize),
+                        arity: *params as usize,
+                        target: pc,
+                        is_loop: true,
+                    });
+                }
+                Instr::If {
+                    params,
+                    results,
+                    else_,
+                    end,
+                } => {
+                    let condition = pop(&mut stack)?;
+                    labels.push(Label {
+                        height: stack.len().saturating_sub(*params as usize),
+                        arity: *results as usize,
+                        target: *end as usize + 1,
+                        is_loop: false,
+                    });
+                    if condition == 0 {
+                        // Without an else arm, land on the End that closes the label
+                        pc = else_.map_or(*end as usize, |else_| else_ as usize + 1);
+                    }
+                }
+                Instr::Else { end } => pc = *end as usize,
+                Instr::End => {
+                    if labels.pop().is_none() {
+                        break;
+                    }
+                }
+                Instr::Br(depth) => match branch(&mut stack, &mut labels, *depth)? {
+                    Some(target) => pc = target,
+                    None => break,
+                },
+                Instr::BrIf(depth) => {
+                    if pop(&mut stack)? != 0 {
+                        match branch(&mut stack, &mut labels, *depth)? {
+                            Some(target) => pc = target,
+                            None => break,
+                        }
+                    }
+                }
+                Instr::BrTable(depths, default) => {
+                    let index = pop(&mut stack)? as u32;
+                    let depth = depths.get(index as usize).unwrap_or(default);
+                    match branch(&mut stack, &mut labels, *depth)? {
+                        Some(target) => pc = target,
+                        None => break,
+                    }
+                }
+                Instr::Return => break,
+                Instr::Call(callee) => {
+                    let callee_ty = self.module.func_type(*callee).ok_or(Trap::NullFunction)?;
+                    let args = pop_n(&mut stack, callee_ty.params.len())?;
+                    let results = self.call(*callee, args)?;
+                    stack.extend(results);
+                }
+                Instr::CallIndirect { type_index, .. } => {
+                    let index = pop(&mut stack)? as u32;
+                    let callee = self.host.table_get(index)?;
+                    let expected = &self.module.types[*type_index as usize];
+                    if self.module.func_type(callee) != Some(expected) {
+                        return Err(Trap::SignatureMismatch);
+                    }
+                    let args = pop_n(&mut stack, expected.params.len())?;
+                    let results = self.call(callee, args)?;
+                    stack.extend(results);
+                }
+                Instr::Drop => {
+                    pop(&mut stack)?;
+                }
+                Instr::Select => {
+                    let condition = pop(&mut stack)?;
+                    let second = pop(&mut stack)?;
+                    let first = pop(&mut stack)?;
+                    stack.push(if condition == 0 { second } else { first });
+                }
+                Instr::LocalGet(index) => stack.push(locals[*index as usize]),
+                Instr::LocalSet(index) => locals[*index as usize] = pop(&mut stack)?,
+                Instr::LocalTee(index) => {
+                    locals[*index as usize] = *stack.last().ok_or(Trap::InvalidStack)?;
+                }
+                Instr::GlobalGet(index) => stack.push(self.host.global(*index)),
+                Instr::GlobalSet(index) => {
+                    let value = pop(&mut stack)?;
+                    self.host.set_global(*index, value);
+                }
+                Instr::Load(opcode, offset) => {
+                    let addr = pop(&mut stack)? as u32;
+                    stack.push(load(self.host.memory(), *opcode, addr, *offset)?);
+                }
+                Instr::Store(opcode, offset) => {
+                    let value = pop(&mut stack)?;
+                    let addr = pop(&mut stack)? as u32;
+                    store(self.host.memory(), *opcode, addr, *offset, value)?;
+                }
+                Instr::MemorySize => {
+                    stack.push((self.host.memory().len() / PAGE_SIZE) as u64);
+                }
+                Instr::MemoryGrow => {
+                    let delta = pop(&mut stack)? as u32;
+                    let previous = self.host.grow_memory(delta).unwrap_or(u32::MAX);
+                    stack.push(u64::from(previous));
+                }
+                Instr::MemoryCopy => {
+                    let len = pop(&mut stack)? as u32 as usize;
+                    let src = pop(&mut stack)? as u32 as usize;
+                    let dst = pop(&mut stack)? as u32 as usize;
+                    let memory = self.host.memory();
+                    if src + len > memory.len() || dst + len > memory.len() {
+                        return Err(Trap::MemoryOutOfBounds);
+                    }
+                    memory.copy_within(src..src + len, dst);
+                }
+                Instr::MemoryFill => {
+                    let len = pop(&mut stack)? as u32 as usize;
+                    let value = pop(&mut stack)? as u8;
+                    let dst = pop(&mut stack)? as u32 as usize;
+                    let memory = self.host.memory();
+                    if dst + len > memory.len() {
+                        return Err(Trap::MemoryOutOfBounds);
+                    }
+                    memory[dst..dst + len].fill(value);
+                }
+                Instr::Const(value) => stack.push(*value),
+                Instr::Numeric(opcode) => numeric(*opcode, &mut stack)?,
+                Instr::TruncSat(sub) => trunc_sat(*sub, &mut stack)?,
+            }
+        }
+
+        pop_n(&mut stack, ty.results.len())
+    }
+}
+
+/// Branch to the label `depth` levels out, returning where execution
+/// continues, or `None` for the function body itself
+fn branch(stack: &mut Vec<u64>, labels: &mut Vec<Label>, depth: u32) -> Result<Option<usize>> {
+    let Some(index) = labels.len().checked_sub(depth as usize + 1) else {
+        return Ok(None);
+    };
+    let label = labels.swap_remove(index);
+    let carried = pop_n(stack, label.arity)?;
+    if stack.len() < label.height {
+        return Err(Trap::InvalidStack);
+    }
+    stack.truncate(label.height);
+    stack.extend(carried);
+    labels.truncate(index);
+    let target = label.target;
+    if label.is_loop {
+        labels.push(label);
+    }
+    Ok(Some(target))
+}
+
+/// Bounds-check an access of `size` bytes
+fn address(memory: &[u8], addr: u32, offset: u32, size: usize) -> Result<usize> {
+    let start = u64::from(addr) + u64::from(offset);
+    if start + size as u64 > memory.len() as u64 {
+        return Err(Trap::MemoryOutOfBounds);
+    }
+    Ok(start as usize)
+}
+
+fn load(memory: &[u8], opcode: u8, addr: u32, offset: u32) -> Result<u64> {
+    let size = match opcode {
+        0x29 | 0x2B => 8,
+        0x28 | 0x2A | 0x34 | 0x35 => 4,
+        0x2E | 0x2F | 0x32 | 0x33 => 2,
+        _ => 1,
+    };
+    let start = address(memory, addr, offset, size)?;
+    let mut bytes = [0; 8];
+    bytes[..size].copy_from_slice(&memory[start..start + size]);
+    let raw = u64::from_le_bytes(bytes);
+    Ok(match opcode {
+        0x2C => i32::from(raw as i8).raw(),
+        0x2E => i32::from(raw as i16).raw(),
+        0x30 => i64::from(raw as i8).raw(),
+        0x32 => i64::from(raw as i16).raw(),
+        0x34 => i64::from(raw as i32).raw(),
+        _ => raw,
+    })
+}
+
+fn store(memory: &mut [u8], opcode: u8, addr: u32, offset: u32, value: u64) -> Result<()> {
+    let size = match opcode {
+        0x37 | 0x39 => 8,
+        0x36 | 0x38 | 0x3E => 4,
+        0x3B | 0x3D => 2,
+        _ => 1,
+    };
+    let start = address(memory, addr, offset, size)?;
+    memory[start..start + size].copy_from_slice(&value.to_le_bytes()[..size]);
+    Ok(())
+}
+
+/// Truncate toward zero, trapping unless the result lies in `min..max`
+fn trunc(value: f64, min: f64, max: f64) -> Result<f64> {
+    if value.is_nan() {
+        return Err(Trap::InvalidConversion);
+    }
+    let value = value.trunc();
+    if value < min || value >= max {
+        return Err(Trap::IntegerOverflow);
+    }
+    Ok(value)
+}
+
+const I32_MIN: f64 = -2_147_483_648.0;
+const I32_END: f64 = 2_147_483_648.0;
+const U32_END: f64 = 4_294_967_296.0;
+const I64_MIN: f64 = -9_223_372_036_854_775_808.0;
+const I64_END: f64 = 9_223_372_036_854_775_808.0;
+const U64_END: f64 = 18_446_744_073_709_551_616.0;
+
+macro_rules! float_ops {
+    ($min:ident, $max:ident, $t:ty) => {
+        /// `min` that propagates NaN and orders -0 below +0
+        #[allow(clippy::float_cmp)]
+        fn $min(a: $t, b: $t) -> $t {
+            if a.is_nan() || b.is_nan() {
+                <$t>::NAN
+            } else if a == b {
+                <$t>::from_bits(a.to_bits() | b.to_bits())
+            } else {
+                a.min(b)
+            }
+        }
+
+        /// `max` that propagates NaN and orders -0 below +0
+        #[allow(clippy::float_cmp)]
+        fn $max(a: $t, b: $t) -> $t {
+            if a.is_nan() || b.is_nan() {
+                <$t>::NAN
+            } else if a == b {
+                <$t>::from_bits(a.to_bits() & b.to_bits())
+            } else {
+                a.max(b)
+            }
+        }
+    };
+}
+
+float_ops!(f32_min, f32_max, f32);
+float_ops!(f64_min, f64_max, f64);
+
+/// Run a numeric instruction (0x45..=0xC4) against the operand stack
+#[allow(clippy::float_cmp)] // Float comparisons are the instructions' semantics
+fn numeric(opcode: u8, stack: &mut Vec<u64>) -> Result<()> {
+    macro_rules! unop {
+        ($t:ty, |$a:ident| $e:expr) => {{
+            let $a = <$t as Raw>::from_raw(pop(stack)?);
+            stack.push(Raw::raw($e));
+        }};
+    }
+    macro_rules! binop {
+        ($t:ty, |$a:ident, $b:ident| $e:expr) => {{
+            let $b = <$t as Raw>::from_raw(pop(stack)?);
+            let $a = <$t as Raw>::from_raw(pop(stack)?);
+            stack.push(Raw::raw($e));
+        }};
+    }
+
+    match opcode {
+        0x45 => unop!(u32, |a| a == 0),
+        0x46 => binop!(u32, |a, b| a == b),
+        0x47 => binop!(u32, |a, b| a != b),
+        0x48 => binop!(i32, |a, b| a < b),
+        0x49 => binop!(u32, |a, b| a < b),
+        0x4A => binop!(i32, |a, b| a > b),
+        0x4B => binop!(u32, |a, b| a > b),
+        0x4C => binop!(i32, |a, b| a <= b),
+        0x4D => binop!(u32, |a, b| a <= b),
+        0x4E => binop!(i32, |a, b| a >= b),
+        0x4F => binop!(u32, |a, b| a >= b),
+
+        0x50 => unop!(u64, |a| a == 0),
+        0x51 => binop!(u64, |a, b| a == b),
+        0x52 => binop!(u64, |a, b| a != b),
+        0x53 => binop!(i64, |a, b| a < b),
+        0x54 => binop!(u64, |a, b| a < b),
+        0x55 => binop!(i64, |a, b| a > b),
+        0x56 => binop!(u64, |a, b| a > b),
+        0x57 => binop!(i64, |a, b| a <= b),
+        0x58 => binop!(u64, |a, b| a <= b),
+        0x59 => binop!(i64, |a, b| a >= b),
+        0x5A => binop!(u64, |a, b| a >= b),
+
+        0x5B => binop!(f32, |a, b| a == b),
+        0x5C => binop!(f32, |a, b| a != b),
+        0x5D => binop!(f32, |a, b| a < b),
+        0x5E => binop!(f32, |a, b| a > b),
+        0x5F => binop!(f32, |a, b| a <= b),
+        0x60 => binop!(f32, |a, b| a >= b),
+
+        0x61 => binop!(f64, |a, b| a == b),
+        0x62 => binop!(f64, |a, b| a != b),
+        0x63 => binop!(f64, |a, b| a < b),
+        0x64 => binop!(f64, |a, b| a > b),
+        0x65 => binop!(f64, |a, b| a <= b),
+        0x66 => binop!(f64, |a, b| a >= b),
+
+        0x67 => unop!(u32, |a| a.leading_zeros()),
+        0x68 => unop!(u32, |a| a.trailing_zeros()),
+        0x69 => unop!(u32, |a| a.count_ones()),
+        0x6A => binop!(u32, |a, b| a.wrapping_add(b)),
+        0x6B => binop!(u32, |a, b| a.wrapping_sub(b)),
+        0x6C => binop!(u32, |a, b| a.wrapping_mul(b)),
+        0x6D => binop!(i32, |a, b| {
+            if b == 0 {
+                return Err(Trap::IntegerDivideByZero);
+            }
+            a.checked_div(b).ok_or(Trap::IntegerOverflow)?
+        }),
+        0x6E => binop!(u32, |a, b| a
+            .checked_div(b)
+            .ok_or(Trap::IntegerDivideByZero)?),
+        0x6F => binop!(i32, |a, b| {
+            if b == 0 {
+                return Err(Trap::IntegerDivideByZero);
+            }
+            a.wrapping_rem(b)
+        }),
+        0x70 => binop!(u32, |a, b| a
+            .checked_rem(b)
+            .ok_or(Trap::IntegerDivideByZero)?),
+        0x71 => binop!(u32, |a, b| a & b),
+        0x72 => binop!(u32, |a, b| a | b),
+        0x73 => binop!(u32, |a, b| a ^ b),
+        0x74 => binop!(u32, |a, b| a.wrapping_shl(b)),
+        0x75 => binop!(i32, |a, b| a.wrapping_shr(b as u32)),
+        0x76 => binop!(u32, |a, b| a.wrapping_shr(b)),
+        0x77 => binop!(u32, |a, b| a.rotate_left(b)),
+        0x78 => binop!(u32, |a, b| a.rotate_right(b)),
+
+        0x79 => unop!(u64, |a| u64::from(a.leading_zeros())),
+        0x7A => unop!(u64, |a| u64::from(a.trailing_zeros())),
+        0x7B => unop!(u64, |a| u64::from(a.count_ones())),
+        0x7C => binop!(u64, |a, b| a.wrapping_add(b)),
+        0x7D => binop!(u64, |a, b| a.wrapping_sub(b)),
+        0x7E => binop!(u64, |a, b| a.wrapping_mul(b)),
+        0x7F => binop!(i64, |a, b| {
+            if b == 0 {
+                return Err(Trap::IntegerDivideByZero);
+            }
+            a.checked_div(b).ok_or(Trap::IntegerOverflow)?
+        }),
+        0x80 => binop!(u64, |a, b| a
+            .checked_div(b)
+            .ok_or(Trap::IntegerDivideByZero)?),
+        0x81 => binop!(i64, |a, b| {
+            if b == 0 {
+                return Err(Trap::IntegerDivideByZero);
+            }
+            a.wrapping_rem(b)
+        }),
+        0x82 => binop!(u64, |a, b| a
+            .checked_rem(b)
+            .ok_or(Trap::IntegerDivideByZero)?),
+        0x83 => binop!(u64, |a, b| a & b),
+        0x84 => binop!(u64, |a, b| a | b),
+        0x85 => binop!(u64, |a, b| a ^ b),
+        0x86 => binop!(u64, |a, b| a.wrapping_shl(b as u32)),
+        0x87 => binop!(i64, |a, b| a.wrapping_shr(b as u32)),
+        0x88 => binop!(u64, |a, b| a.wrapping_shr(b as u32)),
+        0x89 => binop!(u64, |a, b| a.rotate_left((b % 64) as u32)),
+        0x8A => binop!(u64, |a, b| a.rotate_right((b % 64) as u32)),
+
+        0x8B => unop!(f32, |a| a.abs()),
+        0x8C => unop!(f32, |a| -a),
+        0x8D => unop!(f32, |a| a.ceil()),
+        0x8E => unop!(f32, |a| a.floor()),
+        0x8F => unop!(f32, |a| a.trunc()),
+        0x90 => unop!(f32, |a| a.round_ties_even()),
+        0x91 => unop!(f32, |a| a.sqrt()),
+        0x92 => binop!(f32, |a, b| a + b),
+        0x93 => binop!(f32, |a, b| a - b),
+        0x94 => binop!(f32, |a, b| a * b),
+        0x95 => binop!(f32, |a, b| a / b),
+        0x96 => binop!(f32, |a, b| f32_min(a, b)),
+        0x97 => binop!(f32, |a, b| f32_max(a, b)),
+        0x98 => binop!(f32, |a, b| a.copysign(b)),
+
+        0x99 => unop!(f64, |a| a.abs()),
+        0x9A => unop!(f64, |a| -a),
+        0x9B => unop!(f64, |a| a.ceil()),
+        0x9C => unop!(f64, |a| a.floor()),
+        0x9D => unop!(f64, |a| a.trunc()),
+        0x9E => unop!(f64, |a| a.round_ties_even()),
+        0x9F => unop!(f64, |a| a.sqrt()),
+        0xA0 => binop!(f64, |a, b| a + b),
+        0xA1 => binop!(f64, |a, b| a - b),
+        0xA2 => binop!(f64, |a, b| a * b),
+        0xA3 => binop!(f64, |a, b| a / b),
+        0xA4 => binop!(f64, |a, b| f64_min(a, b)),
+        0xA5 => binop!(f64, |a, b| f64_max(a, b)),
+        0xA6 => binop!(f64, |a, b| a.copysign(b)),
+
+        0xA7 => unop!(u64, |a| a as u32),
+        0xA8 => unop!(f32, |a| trunc(f64::from(a), I32_MIN, I32_END)? as i32),
+        0xA9 => unop!(f32, |a| trunc(f64::from(a), 0.0, U32_END)? as u32),
+        0xAA => unop!(f64, |a| trunc(a, I32_MIN, I32_END)? as i32),
+        0xAB => unop!(f64, |a| trunc(a, 0.0, U32_END)? as u32),
+        0xAC => unop!(i32, |a| i64::from(a)),
+        0xAD => unop!(u32, |a| u64::from(a)),
+        0xAE => unop!(f32, |a| trunc(f64::from(a), I64_MIN, I64_END)? as i64),
+        0xAF => unop!(f32, |a| trunc(f64::from(a), 0.0, U64_END)? as u64),
+        0xB0 => unop!(f64, |a| trunc(a, I64_MIN, I64_END)? as i64),
+        0xB1 => unop!(f64, |a| trunc(a, 0.0, U64_END)? as u64),
+        0xB2 => unop!(i32, |a| a as f32),
+        0xB3 => unop!(u32, |a| a as f32),
+        0xB4 => unop!(i64, |a| a as f32),
+        0xB5 => unop!(u64, |a| a as f32),
+        0xB6 => unop!(f64, |a| a as f32),
+        0xB7 => unop!(i32, |a| f64::from(a)),
+        0xB8 => unop!(u32, |a| f64::from(a)),
+        0xB9 => unop!(i64, |a| a as f64),
+        0xBA => unop!(u64, |a| a as f64),
+        0xBB => unop!(f32, |a| f64::from(a)),
+        // Reinterpretations keep the raw bits
+        0xBC..=0xBF => {}
+        0xC0 => unop!(i32, |a| i32::from(a as i8)),
+        0xC1 => unop!(i32, |a| i32::from(a as i16)),
+        0xC2 => unop!(i64, |a| i64::from(a as i8)),
+        0xC3 => unop!(i64, |a| i64::from(a as i16)),
+        0xC4 => unop!(i64, |a| i64::from(a as i32)),
+        _ => return Err(Trap::InvalidStack),
+    }
+    Ok(())
+}
+
+/// Run a saturating truncation (0xFC 0..=7); `as` casts already saturate
+fn trunc_sat(sub: u8, stack: &mut Vec<u64>) -> Result<()> {
+    let value = pop(stack)?;
+    let value = if sub & 2 == 0 {
+        f64::from(f32::from_raw(value))
+    } else {
+        f64::from_raw(value)
+    };
+    stack.push(match sub {
+        0 | 2 => (value as i32).raw(),
+        1 | 3 => (value as u32).raw(),
+        4 | 6 => (value as i64).raw(),
+        _ => (value as u64).raw(),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::module::decode;
+
+    #[derive(Default)]
+    struct TestHost {
+        memory: Vec<u8>,
+    }
+
+    impl Host for TestHost {
+        fn call_import(&mut self, _func: u32, args: Vec<u64>) -> Result<Vec<u64>> {
+            Ok(vec![args.iter().sum()])
+        }
+        fn memory(&mut self) -> &mut [u8] {
+            &mut self.memory
+        }
+        fn grow_memory(&mut self, delta: u32) -> Option<u32> {
+            let previous = self.memory.len() / PAGE_SIZE;
+            self.memory
+                .resize((previous + delta as usize) * PAGE_SIZE, 0);
+            Some(previous as u32)
+        }
+        fn global(&mut self, _index: u32) -> u64 {
+            0
+        }
+        fn set_global(&mut self, _index: u32, _value: u64) {}
+        fn table_get(&mut self, _index: u32) -> Result<u32> {
+            Err(Trap::TableOutOfBounds)
+        }
+    }
+
+    /// A module with one function of type `ty` and body `code`
+    fn single_func(ty: &[u8], code: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        bytes.extend([1, ty.len() as u8 + 1, 1]);
+        bytes.extend(ty);
+        bytes.extend([3, 2, 1, 0]);
+        bytes.extend([10, code.len() as u8 + 3, 1, code.len() as u8 + 1, 0]);
+        bytes.extend(code);
+        bytes
+    }
+
+    fn run(ty: &[u8], code: &[u8], args: Vec<u64>) -> Result<Vec<u64>> {
+        let Ok(module) = decode(&single_func(ty, code)) else {
+            return Err(Trap::InvalidStack);
+        };
+        invoke(&module, &mut TestHost::default(), 0, args)
+    }
+
+    #[test]
+    fn test_add() {
+        // (i32, i32) -> i32: local.get 0, local.get 1, i32.add
+        let ty = [0x60, 2, 0x7F, 0x7F, 1, 0x7F];
+        let result = run(&ty, &[0x20, 0, 0x20, 1, 0x6A, 0x0B], vec![2, 40]);
+        assert_eq!(result, Ok(vec![42]));
+    }
+
+    #[test]
+    fn test_loop_sum() {
+        // (i32) -> i32: sum 1..=n with a loop and br_if
+        let ty = [0x60, 1, 0x7F, 1, 0x7F];
+        #[rustfmt::skip]
+        let code = [
+            0x01, 0x01, 0x7F, // one local i32 (accumulator)
+            0x03, 0x40,       // loop
+            0x20, 1, 0x20, 0, 0x6A, 0x21, 1, // acc += n
+            0x20, 0, 0x41, 1, 0x6B, 0x22, 0, // n -= 1 (tee)
+            0x0D, 0,          // br_if 0
+            0x0B,             // end
+            0x20, 1, 0x0B,
+        ];
+        let module = decode(&single_func_with_locals(&ty, &code)).unwrap();
+        let result = invoke(&module, &mut TestHost::default(), 0, vec![100]);
+        assert_eq!(result, Ok(vec![5050]));
+    }
+
+    /// Like `single_func`, but `code` starts with its own locals declaration
+    fn single_func_with_locals(ty: &[u8], code: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        bytes.extend([1, ty.len() as u8 + 1, 1]);
+        bytes.extend(ty);
+        bytes.extend([3, 2, 1, 0]);
+        bytes.extend([10, code.len() as u8 + 2, 1, code.len() as u8]);
+        bytes.extend(code);
+        bytes
+    }
+
+    #[test]
+    fn test_type_errors_are_rejected() {
+        let binary = [0x60, 2, 0x7F, 0x7F, 1, 0x7F];
+        // i32.add on an i64 operand
+        assert!(decode(&single_func(&binary, &[0x20, 0, 0x42, 1, 0x6A, 0x0B])).is_err());
+        // i32.add with nothing on the stack
+        assert!(decode(&single_func(&binary, &[0x6A, 0x0B])).is_err());
+        // No result where an i32 is expected
+        assert!(decode(&single_func(&binary, &[0x0B])).is_err());
+        // A value left over in a function that returns nothing
+        assert!(decode(&single_func(&[0x60, 0, 0], &[0x41, 1, 0x0B])).is_err());
+        // br_if without a condition
+        assert!(decode(&single_func(&[0x60, 0, 0], &[0x0D, 0, 0x0B])).is_err());
+    }
+
+    #[test]
+    fn test_traps() {
+        let ty = [0x60, 2, 0x7F, 0x7F, 1, 0x7F];
+        let div = [0x20, 0, 0x20, 1, 0x6D, 0x0B];
+        assert_eq!(run(&ty, &div, vec![1, 0]), Err(Trap::IntegerDivideByZero));
+        assert_eq!(
+            run(
+                &ty,
+                &div,
+                vec![u64::from(i32::MIN as u32), u64::from(u32::MAX)]
+            ),
+            Err(Trap::IntegerOverflow)
+        );
+        assert_eq!(
+            run(&[0x60, 0, 0], &[0x00, 0x0B], vec![]),
+            Err(Trap::Unreachable)
+        );
+    }
+
+    #[test]
+    fn test_trunc() {
+        assert_eq!(
+            trunc(f64::NAN, I32_MIN, I32_END),
+            Err(Trap::InvalidConversion)
+        );
+        assert_eq!(trunc(I32_END, I32_MIN, I32_END), Err(Trap::IntegerOverflow));
+        assert_eq!(trunc(-0.9, 0.0, U32_END), Ok(0.0));
+        let mut stack = vec![f64::NAN.raw()];
+        trunc_sat(2, &mut stack).unwrap();
+        assert_eq!(stack, vec![0]);
+    }
+
+    #[test]
+    fn test_float_min_max() {
+        assert!(f64_min(0.0, -0.0).is_sign_negative());
+        assert!(f64_max(-0.0, 0.0).is_sign_positive());
+        assert!(f32_min(1.0, f32::NAN).is_nan());
+    }
+}
@@ -0,0 +1,530 @@
+mod exec;
+mod module;
+
+use exec::{Host, Trap};
+use module::{ConstExpr, ExportKind, ImportKind, MAX_PAGES, Module, PAGE_SIZE, ValType};
+use rquickjs::function::{Constructor, Rest};
+use rquickjs::{
+    Array, ArrayBuffer, BigInt, Class, Coerced, Ctx, Exception, FromJs, Function, IntoJs,
+    JsLifetime, Object, Result, TypedArray, Value, class::Trace,
+};
+use std::cell::RefCell;
+use std::sync::Arc;
+use utils::add_internal_function;
+use utils_macros::include_ts;
+
+/// A compiled module, shared by every instance made from it
+#[derive(Clone, Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct WasmModule {
+    #[qjs(skip_trace)]
+    module: Arc<Module>,
+}
+
+/// Mutable state of an instance. Imported functions and the memory are kept
+/// on the JS side and passed back in on every call, so the GC can see them.
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct WasmInstance {
+    #[qjs(skip_trace)]
+    module: Arc<Module>,
+    #[qjs(skip_trace)]
+    globals: RefCell<Vec<u64>>,
+    #[qjs(skip_trace)]
+    table: RefCell<Vec<Option<u32>>>,
+}
+
+/// # Errors
+/// Returns an error if module initialization fails
+pub fn init(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    setup_internal(ctx)?;
+
+    // Register the WebAssembly namespace
+    let js_source = include_ts!("wasm.ts");
+    let module = rquickjs::Module::evaluate(ctx.clone(), "web_wasm", js_source)?;
+    module.finish::<()>()?;
+
+    Ok(())
+}
+
+fn setup_internal(ctx: &Ctx) -> Result<()> {
+    ctx.eval::<(), _>("globalThis[Symbol.for('mdeno.internal')].wasm = {};")?;
+
+    // compile(bytes: Uint8Array): WasmModule
+    add_internal_function!(ctx, "wasm.compile", wasm_compile);
+
+    // validate(bytes: Uint8Array): boolean
+    add_internal_function!(ctx, "wasm.validate", |bytes: TypedArray<u8>| {
+        bytes
+            .as_bytes()
+            .is_some_and(|bytes| module::decode(bytes).is_ok())
+    });
+
+    // imports(module: WasmModule): { module, name, kind }[]
+    add_internal_function!(ctx, "wasm.imports", wasm_imports);
+
+    // exports(module: WasmModule): { name, kind, index, type?, mutable? }[]
+    add_internal_function!(ctx, "wasm.exports", wasm_exports);
+
+    // instantiate(module, functions, globals, memory?): { instance, memory? }
+    add_internal_function!(ctx, "wasm.instantiate", wasm_instantiate);
+
+    // call(instance, functions, memory | undefined, index: number, args: any[]): any
+    add_internal_function!(ctx, "wasm.call", wasm_call);
+
+    // getGlobal(instance, index: number): any
+    add_internal_function!(ctx, "wasm.getGlobal", wasm_get_global);
+
+    // setGlobal(instance, index: number, value: any): void
+    add_internal_function!(ctx, "wasm.setGlobal", wasm_set_global);
+
+    // newMemory(initial: number, maximum?: number): { buffer, maximum }
+    add_internal_function!(ctx, "wasm.newMemory", new_memory);
+
+    // memoryGrow(memory, delta: number): number
+    add_internal_function!(ctx, "wasm.memoryGrow", memory_grow);
+
+    Ok(())
+}
+
+/// Throw one of the error classes `wasm.ts` registers on the internal object
+fn throw(ctx: &Ctx<'_>, kind: &str, message: &str) -> rquickjs::Error {
+    let error = (|| -> Result<Value> {
+        let symbol_for: Function = ctx.globals().get::<_, Object>("Symbol")?.get("for")?;
+        let internal_symbol: Value = symbol_for.call(("mdeno.internal",))?;
+        let internal: Object = ctx.globals().get(internal_symbol)?;
+        let constructor: Constructor = internal.get::<_, Object>("wasm")?.get(kind)?;
+        constructor.construct((message,))
+    })();
+    match error {
+        Ok(error) => ctx.throw(error),
+        Err(error) => error,
+    }
+}
+
+fn wasm_compile(ctx: Ctx<'_>, bytes: TypedArray<u8>) -> Result<WasmModule> {
+    let bytes = bytes
+        .as_bytes()
+        .ok_or_else(|| Exception::throw_type(&ctx, "Buffer is detached"))?;
+    let module = module::decode(bytes).map_err(|error| throw(&ctx, "CompileError", &error))?;
+    Ok(WasmModule {
+        module: Arc::new(module),
+    })
+}
+
+fn val_type_name(ty: ValType) -> &'static str {
+    match ty {
+        ValType::I32 => "i32",
+        ValType::I64 => "i64",
+        ValType::F32 => "f32",
+        ValType::F64 => "f64",
+        ValType::FuncRef => "anyfunc",
+        ValType::ExternRef => "externref",
+    }
+}
+
+fn wasm_imports<'js>(ctx: Ctx<'js>, module: Class<'js, WasmModule>) -> Result<Array<'js>> {
+    let module = module.borrow().module.clone();
+    let list = Array::new(ctx.clone())?;
+    for (i, import) in module.imports.iter().enumerate() {
+        let descriptor = Object::new(ctx.clone())?;
+        descriptor.set("module", import.module.as_str())?;
+        descriptor.set("name", import.name.as_str())?;
+        let kind = match import.kind {
+            ImportKind::Func(_) => "function",
+            ImportKind::Table => "table",
+            ImportKind::Memory(_) => "memory",
+            ImportKind::Global(ty) => {
+                descriptor.set("type", val_type_name(ty.ty))?;
+                descriptor.set("mutable", ty.mutable)?;
+                "global"
+            }
+        };
+        descriptor.set("kind", kind)?;
+        list.set(i, descriptor)?;
+    }
+    Ok(list)
+}
+
+fn wasm_exports<'js>(ctx: Ctx<'js>, module: Class<'js, WasmModule>) -> Result<Array<'js>> {
+    let module = module.borrow().module.clone();
+    let list = Array::new(ctx.clone())?;
+    for (i, export) in module.exports.iter().enumerate() {
+        let descriptor = Object::new(ctx.clone())?;
+        descriptor.set("name", export.name.as_str())?;
+        descriptor.set("kind", export.kind.as_str())?;
+        descriptor.set("index", export.index)?;
+        if export.kind == ExportKind::Global
+            && let Some(ty) = module.global_type(export.index)
+        {
+            descriptor.set("type", val_type_name(ty.ty))?;
+            descriptor.set("mutable", ty.mutable)?;
+        }
+        list.set(i, descriptor)?;
+    }
+    Ok(list)
+}
+
+/// Convert a JS value to a wasm value (`ToWebAssemblyValue`)
+fn to_raw<'js>(ctx: &Ctx<'js>, ty: ValType, value: Value<'js>) -> Result<u64> {
+    Ok(match ty {
+        ValType::I32 => u64::from(Coerced::<i32>::from_js(ctx, value)?.0 as u32),
+        ValType::I64 => match value.as_big_int() {
+            Some(big_int) => big_int.clone().to_i64()? as u64,
+            None => return Err(Exception::throw_type(ctx, "Cannot convert to BigInt")),
+        },
+        ValType::F32 => u64::from((Coerced::<f64>::from_js(ctx, value)?.0 as f32).to_bits()),
+        ValType::F64 => Coerced::<f64>::from_js(ctx, value)?.0.to_bits(),
+        ValType::FuncRef | ValType::ExternRef => u64::MAX,
+    })
+}
+
+/// Convert a wasm value to a JS value (`ToJSValue`)
+fn to_js<'js>(ctx: &Ctx<'js>, ty: ValType, raw: u64) -> Result<Value<'js>> {
+    match ty {
+        ValType::I32 => (raw as i32).into_js(ctx),
+        ValType::I64 => Ok(BigInt::from_i64(ctx.clone(), raw as i64)?.into_value()),
+        ValType::F32 => f64::from(f32::from_bits(raw as u32)).into_js(ctx),
+        ValType::F64 => f64::from_bits(raw).into_js(ctx),
+        ValType::FuncRef | ValType::ExternRef => Ok(Value::new_null(ctx.clone())),
+    }
+}
+
+/// Results as a single value, `undefined`, or an array for several
+fn results_to_js<'js>(ctx: &Ctx<'js>, types: &[ValType], raw: &[u64]) -> Result<Value<'js>> {
+    match types {
+        [] => Ok(Value::new_undefined(ctx.clone())),
+        [ty] => to_js(ctx, *ty, raw[0]),
+        _ => {
+            let list = Array::new(ctx.clone())?;
+            for (i, (ty, raw)) in types.iter().zip(raw).enumerate() {
+                list.set(i, to_js(ctx, *ty, *raw)?)?;
+            }
+            Ok(list.into_value())
+        }
+    }
+}
+
+fn eval_const(expr: ConstExpr, globals: &[u64]) -> u64 {
+    match expr {
+        ConstExpr::Value(value) => value,
+        ConstExpr::GlobalGet(index) => globals.get(index as usize).copied().unwrap_or(0),
+        ConstExpr::RefNull => u64::MAX,
+        ConstExpr::RefFunc(func) => u64::from(func),
+    }
+}
+
+/// A memory's buffer. `QuickJS` owns the bytes: buffers are detached when the
+/// memory grows, and a detached buffer made from a `Vec` would be freed twice.
+fn memory_buffer<'js>(ctx: &Ctx<'js>, bytes: &[u8]) -> Result<ArrayBuffer<'js>> {
+    ArrayBuffer::new_copy(ctx.clone(), bytes)
+}
+
+fn new_memory(ctx: Ctx<'_>, initial: u32, maximum: Option<u32>) -> Result<Object<'_>> {
+    if initial > MAX_PAGES || maximum.is_some_and(|maximum| maximum < initial) {
+        return Err(Exception::throw_range(&ctx, "Invalid memory size"));
+    }
+    let state = Object::new(ctx.clone())?;
+    let buffer = memory_buffer(&ctx, &vec![0u8; initial as usize * PAGE_SIZE])?;
+    state.set("buffer", buffer)?;
+    state.set("maximum", maximum)?;
+    Ok(state)
+}
+
+/// Grow a memory, replacing and detaching its buffer. Returns the previous
+/// size in pages, or `None` if the memory can't grow that far.
+fn grow<'js>(ctx: &Ctx<'js>, state: &Object<'js>, delta: u32) -> Result<Option<u32>> {
+    let mut buffer: ArrayBuffer = state.get("buffer")?;
+    let old = buffer.as_bytes().unwrap_or_default();
+    let pages = (old.len() / PAGE_SIZE) as u32;
+    let maximum = state
+        .get::<_, Option<u32>>("maximum")?
+        .map_or(MAX_PAGES, |maximum| maximum.min(MAX_PAGES));
+    let Some(new_pages) = pages.checked_add(delta).filter(|&pages| pages <= maximum) else {
+        return Ok(None);
+    };
+    let mut bytes = vec![0u8; new_pages as usize * PAGE_SIZE];
+    bytes[..old.len()].copy_from_slice(old);
+    state.set("buffer", memory_buffer(ctx, &bytes)?)?;
+    buffer.detach();
+    Ok(Some(pages))
+}
+
+fn memory_grow<'js>(ctx: Ctx<'js>, state: Object<'js>, delta: u32) -> Result<u32> {
+    grow(&ctx, &state, delta)?
+        .ok_or_else(|| Exception::throw_range(&ctx, "Maximum memory size exceeded"))
+}
+
+/// The `Host` an instance runs against while JS calls into it
+struct JsHost<'a, 'js> {
+    ctx: Ctx<'js>,
+    instance: &'a WasmInstance,
+    functions: Array<'js>,
+    memory: Option<Object<'js>>,
+    /// The memory's current buffer, refreshed whenever JS may have grown it
+    buffer: Option<ArrayBuffer<'js>>,
+    /// The exception behind `Trap::Exception`
+    error: Option<rquickjs::Error>,
+}
+
+impl<'a, 'js> JsHost<'a, 'js> {
+    fn new(
+        ctx: &Ctx<'js>,
+        instance: &'a WasmInstance,
+        functions: Array<'js>,
+        memory: Option<Object<'js>>,
+    ) -> Self {
+        let mut host = Self {
+            ctx: ctx.clone(),
+            instance,
+            functions,
+            memory,
+            buffer: None,
+            error: None,
+        };
+        host.refresh_buffer();
+        host
+    }
+
+    fn refresh_buffer(&mut self) {
+        self.buffer = self
+            .memory
+            .as_ref()
+            .and_then(|memory| memory.get("buffer").ok());
+    }
+
+    /// Run `func`, turning a trap into a `RuntimeError`
+    fn invoke(&mut self, func: u32, args: Vec<u64>) -> Result<Vec<u64>> {
+        let module = self.instance.module.clone();
+        exec::invoke(&module, self, func, args).map_err(|trap| match trap {
+            Trap::Exception => self.error.take().unwrap_or(rquickjs::Error::Exception),
+            trap => throw(&self.ctx, "RuntimeError", &trap.to_string()),
+        })
+    }
+
+    fn call_function(&mut self, func: u32, args: Vec<u64>) -> Result<Vec<u64>> {
+        let module = self.instance.module.clone();
+        let ty = module
+            .func_type(func)
+            .ok_or_else(|| Exception::throw_internal(&self.ctx, "Unknown function"))?;
+        let function: Function = self.functions.get(func as usize)?;
+        let args = ty
+            .params
+            .iter()
+            .zip(args)
+            .map(|(param, raw)| to_js(&self.ctx, *param, raw))
+            .collect::<Result<Vec<_>>>()?;
+        let result: Value = function.call((Rest(args),))?;
+        self.refresh_buffer();
+        match ty.results.as_slice() {
+            [] => Ok(Vec::new()),
+            [ty] => Ok(vec![to_raw(&self.ctx, *ty, result)?]),
+            types => {
+                let Some(values) = result.as_array() else {
+                    return Err(Exception::throw_type(
+                        &self.ctx,
+                        "Imported function must return an array",
+                    ));
+                };
+                if values.len() != types.len() {
+                    return Err(Exception::throw_type(
+                        &self.ctx,
+                        "Imported function returned the wrong number of values",
+                    ));
+                }
+                types
+                    .iter()
+                    .zip(values.iter::<Value>())
+                    .map(|(ty, value)| to_raw(&self.ctx, *ty, value?))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl Host for JsHost<'_, '_> {
+    fn call_import(&mut self, func: u32, args: Vec<u64>) -> std::result::Result<Vec<u64>, Trap> {
+        self.call_function(func, args).map_err(|error| {
+            self.error = Some(error);
+            Trap::Exception
+        })
+    }
+
+    fn memory(&mut self) -> &mut [u8] {
+        match self.buffer.as_ref().and_then(ArrayBuffer::as_raw) {
+            // SAFETY: `self.buffer` keeps the buffer alive, and no JS can run
+            // (and detach or resize it) while the returned borrow of `self`
+            // is held
+            Some(raw) => unsafe { std::slice::from_raw_parts_mut(raw.ptr.as_ptr(), raw.len) },
+            None => &mut [],
+        }
+    }
+
+    fn grow_memory(&mut self, delta: u32) -> Option<u32> {
+        let memory = self.memory.clone()?;
+        let previous = grow(&self.ctx, &memory, delta).unwrap_or_default();
+        self.refresh_buffer();
+        previous
+    }
+
+    fn global(&mut self, index: u32) -> u64 {
+        self.instance.globals.borrow()[index as usize]
+    }
+
+    fn set_global(&mut self, index: u32, value: u64) {
+        self.instance.globals.borrow_mut()[index as usize] = value;
+    }
+
+    fn table_get(&mut self, index: u32) -> std::result::Result<u32, Trap> {
+        match self.instance.table.borrow().get(index as usize) {
+            Some(Some(func)) => Ok(*func),
+            Some(None) => Err(Trap::NullFunction),
+            None => Err(Trap::TableOutOfBounds),
+        }
+    }
+}
+
+fn wasm_instantiate<'js>(
+    ctx: Ctx<'js>,
+    module: Class<'js, WasmModule>,
+    functions: Array<'js>,
+    imported_globals: Array<'js>,
+    memory: Option<Object<'js>>,
+) -> Result<Object<'js>> {
+    let module = module.borrow().module.clone();
+
+    // Imported globals, then the module's own
+    let mut globals = Vec::new();
+    for (import, value) in module
+        .imports
+        .iter()
+        .filter_map(|import| match import.kind {
+            ImportKind::Global(ty) => Some(ty),
+            _ => None,
+        })
+        .zip(imported_globals.iter::<Value>())
+    {
+        globals.push(to_raw(&ctx, import.ty, value?)?);
+    }
+    for global in &module.globals {
+        globals.push(eval_const(global.init, &globals));
+    }
+
+    let memory = match (memory, module.memories.first()) {
+        (Some(memory), _) => {
+            let limits = module.imports.iter().find_map(|import| match import.kind {
+                ImportKind::Memory(limits) => Some(limits),
+                _ => None,
+            });
+            let buffer: ArrayBuffer = memory.get("buffer")?;
+            if let Some(limits) = limits
+                && buffer.len() < limits.min as usize * PAGE_SIZE
+            {
+                return Err(throw(&ctx, "LinkError", "Imported memory is too small"));
+            }
+            Some(memory)
+        }
+        (None, Some(limits)) => Some(new_memory(ctx.clone(), limits.min, limits.max)?),
+        (None, None) => None,
+    };
+
+    let mut table = vec![
+        None;
+        module
+            .tables
+            .first()
+            .map_or(0, |limits| limits.min as usize)
+    ];
+    for elem in &module.elems {
+        let offset = eval_const(elem.offset, &globals) as u32 as usize;
+        let Some(slots) = table.get_mut(offset..offset + elem.funcs.len()) else {
+            return Err(throw(
+                &ctx,
+                "RuntimeError",
+                "Element segment is out of bounds",
+            ));
+        };
+        slots.copy_from_slice(&elem.funcs);
+    }
+
+    let instance = WasmInstance {
+        module: module.clone(),
+        globals: RefCell::new(globals),
+        table: RefCell::new(table),
+    };
+
+    let mut host = JsHost::new(&ctx, &instance, functions, memory.clone());
+    for data in &module.datas {
+        let offset = eval_const(data.offset, &instance.globals.borrow()) as u32 as usize;
+        let Some(bytes) = host.memory().get_mut(offset..offset + data.bytes.len()) else {
+            return Err(throw(&ctx, "RuntimeError", "Data segment is out of bounds"));
+        };
+        bytes.copy_from_slice(&data.bytes);
+    }
+    if let Some(start) = module.start {
+        host.invoke(start, Vec::new())?;
+    }
+    drop(host);
+
+    let result = Object::new(ctx.clone())?;
+    result.set("instance", Class::instance(ctx.clone(), instance)?)?;
+    result.set("memory", memory)?;
+    Ok(result)
+}
+
+fn wasm_call<'js>(
+    ctx: Ctx<'js>,
+    instance: Class<'js, WasmInstance>,
+    functions: Array<'js>,
+    memory: Option<Object<'js>>,
+    index: u32,
+    args: Array<'js>,
+) -> Result<Value<'js>> {
+    let instance = instance.borrow();
+    let module = instance.module.clone();
+    let ty = module
+        .func_type(index)
+        .ok_or_else(|| Exception::throw_internal(&ctx, "Unknown function"))?;
+    let mut raw_args = Vec::with_capacity(ty.params.len());
+    for (i, param) in ty.params.iter().enumerate() {
+        raw_args.push(to_raw(&ctx, *param, args.get(i)?)?);
+    }
+    let results = JsHost::new(&ctx, &instance, functions, memory).invoke(index, raw_args)?;
+    results_to_js(&ctx, &ty.results, &results)
+}
+
+fn wasm_get_global<'js>(
+    ctx: Ctx<'js>,
+    instance: Class<'js, WasmInstance>,
+    index: u32,
+) -> Result<Value<'js>> {
+    let instance = instance.borrow();
+    let ty = instance
+        .module
+        .global_type(index)
+        .ok_or_else(|| Exception::throw_internal(&ctx, "Unknown global"))?;
+    let raw = instance.globals.borrow()[index as usize];
+    to_js(&ctx, ty.ty, raw)
+}
+
+fn wasm_set_global<'js>(
+    ctx: Ctx<'js>,
+    instance: Class<'js, WasmInstance>,
+    index: u32,
+    value: Value<'js>,
+) -> Result<()> {
+    let instance = instance.borrow();
+    let ty = instance
+        .module
+        .global_type(index)
+        .ok_or_else(|| Exception::throw_internal(&ctx, "Unknown global"))?;
+    if !ty.mutable {
+        return Err(Exception::throw_type(
+            &ctx,
+            "Can't set the value of an immutable global",
+        ));
+    }
+    let raw = to_raw(&ctx, ty.ty, value)?;
+    instance.globals.borrow_mut()[index as usize] = raw;
+    Ok(())
+}
@@ -0,0 +1,969 @@
+// Decoder for the WebAssembly binary format (MVP, plus sign extension,
+// saturating truncation and bulk memory.copy / memory.fill)
+
+use wasmparser::{Validator, WasmFeatures};
+
+/// Pages are 64 KiB
+pub(crate) const PAGE_SIZE: usize = 65536;
+/// Memories are capped at 1 GiB, well below what a 32-bit index can address
+pub(crate) const MAX_PAGES: u32 = 16384;
+/// Locals a single function may declare
+const MAX_LOCALS: usize = 50_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ValType {
+    I32,
+    I64,
+    F32,
+    F64,
+    FuncRef,
+    ExternRef,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FuncType {
+    pub params: Vec<ValType>,
+    pub results: Vec<ValType>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    pub min: u32,
+    pub max: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GlobalType {
+    pub ty: ValType,
+    pub mutable: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ImportKind {
+    /// Index into `Module::types`
+    Func(u32),
+    /// Recognized so `Module.imports()` can list it; instantiation rejects it
+    Table,
+    Memory(Limits),
+    Global(GlobalType),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Import {
+    pub module: String,
+    pub name: String,
+    pub kind: ImportKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExportKind {
+    Func,
+    Table,
+    Memory,
+    Global,
+}
+
+impl ExportKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Func => "function",
+            Self::Table => "table",
+            Self::Memory => "memory",
+            Self::Global => "global",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Export {
+    pub name: String,
+    pub kind: ExportKind,
+    pub index: u32,
+}
+
+/// Initializer of a global, or offset of an element or data segment
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ConstExpr {
+    /// A plain value, in the same encoding as the operand stack
+    Value(u64),
+    GlobalGet(u32),
+    RefNull,
+    RefFunc(u32),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Global {
+    pub ty: GlobalType,
+    pub init: ConstExpr,
+}
+
+#[derive(Debug)]
+pub(crate) struct Func {
+    pub type_index: u32,
+    pub locals: Vec<ValType>,
+    pub body: Vec<Instr>,
+}
+
+/// An active element segment; passive and declarative ones are dropped
+#[derive(Debug)]
+pub(crate) struct Elem {
+    pub table: u32,
+    pub offset: ConstExpr,
+    /// Function indices, `None` for `ref.null`
+    pub funcs: Vec<Option<u32>>,
+}
+
+/// An active data segment; passive ones are dropped
+#[derive(Debug)]
+pub(crate) struct Data {
+    pub offset: ConstExpr,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Instr {
+    Unreachable,
+    Nop,
+    /// `end` is the index of the matching `End`
+    Block {
+        params: u32,
+        results: u32,
+        end: u32,
+    },
+    Loop {
+        params: u32,
+    },
+    /// `else_` is the index of the matching `Else`, if there is one
+    If {
+        params: u32,
+        results: u32,
+        else_: Option<u32>,
+        end: u32,
+    },
+    /// Reached when the `then` arm finishes, which skips to `end`
+    Else {
+        end: u32,
+    },
+    End,
+    Br(u32),
+    BrIf(u32),
+    BrTable(Box<[u32]>, u32),
+    Return,
+    Call(u32),
+    CallIndirect {
+        type_index: u32,
+        table: u32,
+    },
+    Drop,
+    Select,
+    LocalGet(u32),
+    LocalSet(u32),
+    LocalTee(u32),
+    GlobalGet(u32),
+    GlobalSet(u32),
+    /// Opcode (0x28..=0x35) and offset
+    Load(u8, u32),
+    /// Opcode (0x36..=0x3E) and offset
+    Store(u8, u32),
+    MemorySize,
+    MemoryGrow,
+    MemoryCopy,
+    MemoryFill,
+    Const(u64),
+    /// Numeric opcodes 0x45..=0xC4, which take no immediates
+    Numeric(u8),
+    /// Saturating truncation, 0xFC 0..=7
+    TruncSat(u8),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Module {
+    pub types: Vec<FuncType>,
+    pub imports: Vec<Import>,
+    /// Functions defined in the module, numbered after the imported ones
+    pub funcs: Vec<Func>,
+    pub tables: Vec<Limits>,
+    pub memories: Vec<Limits>,
+    pub globals: Vec<Global>,
+    pub exports: Vec<Export>,
+    pub start: Option<u32>,
+    pub elems: Vec<Elem>,
+    pub datas: Vec<Data>,
+    pub imported_funcs: u32,
+    pub imported_globals: u32,
+}
+
+impl Module {
+    /// Type of a function, imported or defined
+    pub(crate) fn func_type(&self, func: u32) -> Option<&FuncType> {
+        let type_index = if func < self.imported_funcs {
+            self.imports
+                .iter()
+                .filter_map(|import| match import.kind {
+                    ImportKind::Func(type_index) => Some(type_index),
+                    _ => None,
+                })
+                .nth(func as usize)?
+        } else {
+            self.funcs
+                .get((func - self.imported_funcs) as usize)?
+                .type_index
+        };
+        self.types.get(type_index as usize)
+    }
+
+    /// Type of a global, imported or defined
+    pub(crate) fn global_type(&self, global: u32) -> Option<GlobalType> {
+        if global < self.imported_globals {
+            self.imports
+                .iter()
+                .filter_map(|import| match import.kind {
+                    ImportKind::Global(ty) => Some(ty),
+                    _ => None,
+                })
+                .nth(global as usize)
+        } else {
+            self.globals
+                .get((global - self.imported_globals) as usize)
+                .map(|global| global.ty)
+        }
+    }
+
+    pub(crate) fn total_funcs(&self) -> u32 {
+        self.imported_funcs + self.funcs.len() as u32
+    }
+
+    pub(crate) fn total_globals(&self) -> u32 {
+        self.imported_globals + self.globals.len() as u32
+    }
+
+    pub(crate) fn has_memory(&self) -> bool {
+        !self.memories.is_empty()
+            || self
+                .imports
+                .iter()
+                .any(|import| matches!(import.kind, ImportKind::Memory(_)))
+    }
+
+    pub(crate) fn has_table(&self) -> bool {
+        !self.tables.is_empty()
+            || self
+                .imports
+                .iter()
+                .any(|import| matches!(import.kind, ImportKind::Table))
+    }
+}
+
+/// Why a binary isn't a module this decoder accepts
+pub(crate) type DecodeError = String;
+type Result<T> = std::result::Result<T, DecodeError>;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| format!("Unexpected end of input at offset {}", self.pos))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| format!("Unexpected end of input at offset {}", self.pos))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Unsigned LEB128 of at most `bits` bits
+    fn uleb(&mut self, bits: u32) -> Result<u64> {
+        let mut result = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift >= bits {
+                return Err("Integer representation too long".to_string());
+            }
+            result |= u64::from(byte & 0x7F) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        if bits < 64 && result >> bits != 0 {
+            return Err("Integer too large".to_string());
+        }
+        Ok(result)
+    }
+
+    /// Signed LEB128 of at most `bits` bits
+    fn sleb(&mut self, bits: u32) -> Result<i64> {
+        let mut result = 0i64;
+        let mut shift = 0;
+        let mut byte;
+        loop {
+            byte = self.byte()?;
+            if shift >= bits {
+                return Err("Integer representation too long".to_string());
+            }
+            result |= i64::from(byte & 0x7F) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        if shift < 64 && byte & 0x40 != 0 {
+            result |= -1 << shift;
+        }
+        Ok(result)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(self.uleb(32)? as u32)
+    }
+
+    /// A length or count, which must fit in the remaining input
+    fn len(&mut self) -> Result<usize> {
+        let len = self.u32()? as usize;
+        if len > self.bytes.len() - self.pos {
+            return Err(format!("Length {len} is out of bounds"));
+        }
+        Ok(len)
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| "Invalid UTF-8 in name".to_string())
+    }
+
+    fn val_type(&mut self) -> Result<ValType> {
+        match self.byte()? {
+            0x7F => Ok(ValType::I32),
+            0x7E => Ok(ValType::I64),
+            0x7D => Ok(ValType::F32),
+            0x7C => Ok(ValType::F64),
+            0x70 => Ok(ValType::FuncRef),
+            0x6F => Ok(ValType::ExternRef),
+            0x7B => Err("SIMD (v128) is not supported".to_string()),
+            byte => Err(format!("Invalid value type 0x{byte:02x}")),
+        }
+    }
+
+    fn limits(&mut self) -> Result<Limits> {
+        match self.byte()? {
+            0x00 => Ok(Limits {
+                min: self.u32()?,
+                max: None,
+            }),
+            0x01 => {
+                let min = self.u32()?;
+                let max = self.u32()?;
+                if max < min {
+                    return Err("Limits maximum is below the minimum".to_string());
+                }
+                Ok(Limits {
+                    min,
+                    max: Some(max),
+                })
+            }
+            _ => Err("Shared and 64-bit memories are not supported".to_string()),
+        }
+    }
+
+    fn memory_limits(&mut self) -> Result<Limits> {
+        let limits = self.limits()?;
+        if limits.min > MAX_PAGES {
+            return Err(format!("Memories are limited to {MAX_PAGES} pages"));
+        }
+        Ok(limits)
+    }
+
+    fn table_type(&mut self) -> Result<Limits> {
+        if self.val_type()? != ValType::FuncRef {
+            return Err("Only funcref tables are supported".to_string());
+        }
+        self.limits()
+    }
+
+    fn global_type(&mut self) -> Result<GlobalType> {
+        let ty = self.val_type()?;
+        let mutable = match self.byte()? {
+            0 => false,
+            1 => true,
+            _ => return Err("Invalid global mutability".to_string()),
+        };
+        Ok(GlobalType { ty, mutable })
+    }
+
+    fn const_expr(&mut self) -> Result<ConstExpr> {
+        let expr = match self.byte()? {
+            0x41 => ConstExpr::Value(u64::from(self.sleb(32)? as u32)),
+            0x42 => ConstExpr::Value(self.sleb(64)? as u64),
+            0x43 => ConstExpr::Value(u64::from(self.f32_bits()?)),
+            0x44 => ConstExpr::Value(self.f64_bits()?),
+            0x23 => ConstExpr::GlobalGet(self.u32()?),
+            0xD0 => {
+                self.byte()?;
+                ConstExpr::RefNull
+            }
+            0xD2 => ConstExpr::RefFunc(self.u32()?),
+            byte => {
+                return Err(format!(
+                    "Unsupported instruction 0x{byte:02x} in constant expression"
+                ));
+            }
+        };
+        if self.byte()? != 0x0B {
+            return Err("Constant expression must end after one instruction".to_string());
+        }
+        Ok(expr)
+    }
+
+    fn f32_bits(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f64_bits(&mut self) -> Result<u64> {
+        let mut bits = [0; 8];
+        bits.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bits))
+    }
+}
+
+/// Validate and decode a module. `wasmparser` checks it against the
+/// WebAssembly 2.0 rules, including operand types, so the interpreter only
+/// ever runs well-typed code; decoding then rejects what it doesn't support.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Module> {
+    let features = WasmFeatures::WASM2.difference(WasmFeatures::SIMD);
+    Validator::new_with_features(features)
+        .validate_all(bytes)
+        .map_err(|error| error.to_string())?;
+
+    let mut reader = Reader::new(bytes);
+    if reader.take(4).ok() != Some(b"\0asm".as_slice()) {
+        return Err("Missing the \\0asm magic number".to_string());
+    }
+    if reader.take(4).ok() != Some([1, 0, 0, 0].as_slice()) {
+        return Err("Unsupported WebAssembly version".to_string());
+    }
+
+    let mut module = Module::default();
+    let mut func_types: Vec<u32> = Vec::new();
+    let mut last_section = 0;
+    while !reader.is_empty() {
+        let id = reader.byte()?;
+        let len = reader.len()?;
+        let mut section = Reader::new(reader.take(len)?);
+        // Custom sections may appear anywhere; the data count section (12)
+        // comes between imports and code
+        let order = match id {
+            0 => continue,
+            12 => 9,
+            10 | 11 => id + 1,
+            id => id,
+        };
+        if order <= last_section {
+            return Err(format!("Section {id} is out of order"));
+        }
+        last_section = order;
+
+        match id {
+            1 => {
+                for _ in 0..section.len()? {
+                    if section.byte()? != 0x60 {
+                        return Err("Invalid function type".to_string());
+                    }
+                    let params = (0..section.len()?)
+                        .map(|_| section.val_type())
+                        .collect::<Result<_>>()?;
+                    let results = (0..section.len()?)
+                        .map(|_| section.val_type())
+                        .collect::<Result<_>>()?;
+                    module.types.push(FuncType { params, results });
+                }
+            }
+            2 => {
+                for _ in 0..section.len()? {
+                    let module_name = section.name()?;
+                    let name = section.name()?;
+                    let kind = match section.byte()? {
+                        0x00 => {
+                            let type_index = section.u32()?;
+                            if type_index as usize >= module.types.len() {
+                                return Err(format!("Unknown type {type_index}"));
+                            }
+                            module.imported_funcs += 1;
+                            ImportKind::Func(type_index)
+                        }
+                        0x01 => {
+                            section.table_type()?;
+                            ImportKind::Table
+                        }
+                        0x02 => ImportKind::Memory(section.memory_limits()?),
+                        0x03 => {
+                            module.imported_globals += 1;
+                            ImportKind::Global(section.global_type()?)
+                        }
+                        _ => return Err("Invalid import kind".to_string()),
+                    };
+                    module.imports.push(Import {
+                        module: module_name,
+                        name,
+                        kind,
+                    });
+                }
+            }
+            3 => {
+                for _ in 0..section.len()? {
+                    let type_index = section.u32()?;
+                    if type_index as usize >= module.types.len() {
+                        return Err(format!("Unknown type {type_index}"));
+                    }
+                    func_types.push(type_index);
+                }
+            }
+            4 => {
+                for _ in 0..section.len()? {
+                    module.tables.push(section.table_type()?);
+                }
+            }
+            5 => {
+                for _ in 0..section.len()? {
+                    module.memories.push(section.memory_limits()?);
+                }
+            }
+            6 => {
+                for _ in 0..section.len()? {
+                    let ty = section.global_type()?;
+                    let init = section.const_expr()?;
+                    module.globals.push(Global { ty, init });
+                }
+            }
+            7 => {
+                for _ in 0..section.len()? {
+                    let name = section.name()?;
+                    let kind = match section.byte()? {
+                        0x00 => ExportKind::Func,
+                        0x01 => ExportKind::Table,
+                        0x02 => ExportKind::Memory,
+                        0x03 => ExportKind::Global,
+                        _ => return Err("Invalid export kind".to_string()),
+                    };
+                    let index = section.u32()?;
+                    if module.exports.iter().any(|export| export.name == name) {
+                        return Err(format!("Duplicate export \"{name}\""));
+                    }
+                    module.exports.push(Export { name, kind, index });
+                }
+            }
+            8 => module.start = Some(section.u32()?),
+            9 => {
+                for _ in 0..section.len()? {
+                    if let Some(elem) = decode_elem(&mut section)? {
+                        module.elems.push(elem);
+                    }
+                }
+            }
+            10 => {
+                if section.len()? != func_types.len() {
+                    return Err("Function and code section sizes differ".to_string());
+                }
+                for &type_index in &func_types {
+                    let size = section.len()?;
+                    let mut body = Reader::new(section.take(size)?);
+                    let mut locals = Vec::new();
+                    for _ in 0..body.len()? {
+                        let count = body.u32()? as usize;
+                        if locals.len() + count > MAX_LOCALS {
+                            return Err("Too many locals".to_string());
+                        }
+                        let ty = body.val_type()?;
+                        locals.extend(std::iter::repeat_n(ty, count));
+                    }
+                    module.funcs.push(Func {
+                        type_index,
+                        locals,
+                        body: Vec::new(),
+                    });
+                    let code = decode_code(&mut body, &module, module.funcs.len() - 1)?;
+                    if let Some(func) = module.funcs.last_mut() {
+                        func.body = code;
+                    }
+                }
+            }
+            11 => {
+                for _ in 0..section.len()? {
+                    let offset = match section.u32()? {
+                        0 => Some(section.const_expr()?),
+                        1 => None,
+                        2 => {
+                            if section.u32()? != 0 {
+                                return Err("Unknown memory".to_string());
+                            }
+                            Some(section.const_expr()?)
+                        }
+                        _ => return Err("Invalid data segment".to_string()),
+                    };
+                    let len = section.len()?;
+                    let bytes = section.take(len)?.to_vec();
+                    if let Some(offset) = offset {
+                        module.datas.push(Data { offset, bytes });
+                    }
+                }
+            }
+            12 => {
+                section.u32()?;
+            }
+            id => return Err(format!("Unknown section {id}")),
+        }
+        if !section.is_empty() {
+            return Err(format!("Section {id} has trailing bytes"));
+        }
+    }
+
+    if module.funcs.len() != func_types.len() {
+        return Err("Function and code section sizes differ".to_string());
+    }
+    check_indices(&module)?;
+    Ok(module)
+}
+
+fn decode_elem(section: &mut Reader<'_>) -> Result<Option<Elem>> {
+    let flags = section.u32()?;
+    if flags > 7 {
+        return Err("Invalid element segment".to_string());
+    }
+    // Bit 0: passive or declarative; bit 1: explicit table index (when
+    // active) or element kind; bit 2: expressions instead of indices
+    let active = flags & 1 == 0;
+    let table = if active && flags & 2 != 0 {
+        section.u32()?
+    } else {
+        0
+    };
+    let offset = if active {
+        Some(section.const_expr()?)
+    } else {
+        None
+    };
+    if flags & 3 != 0 {
+        // Element kind (0x00 = funcref) or reference type
+        section.byte()?;
+    }
+    let mut funcs = Vec::new();
+    for _ in 0..section.len()? {
+        if flags & 4 == 0 {
+            funcs.push(Some(section.u32()?));
+        } else {
+            match section.const_expr()? {
+                ConstExpr::RefFunc(func) => funcs.push(Some(func)),
+                ConstExpr::RefNull => funcs.push(None),
+                _ => return Err("Invalid element expression".to_string()),
+            }
+        }
+    }
+    Ok(offset.map(|offset| Elem {
+        table,
+        offset,
+        funcs,
+    }))
+}
+
+/// Parse a block type into its parameter and result counts
+fn block_type(reader: &mut Reader<'_>, module: &Module) -> Result<(u32, u32)> {
+    match reader.sleb(33)? {
+        // 0x40: no values
+        -64 => Ok((0, 0)),
+        // A single value type, which reads as a small negative number
+        -1 | -2 | -3 | -4 | -16 | -17 => Ok((0, 1)),
+        index if index >= 0 => {
+            let ty = usize::try_from(index)
+                .ok()
+                .and_then(|index| module.types.get(index))
+                .ok_or_else(|| format!("Unknown type {index}"))?;
+            Ok((ty.params.len() as u32, ty.results.len() as u32))
+        }
+        _ => Err("Invalid block type".to_string()),
+    }
+}
+
+/// Decode a function body, resolving where each block ends
+fn decode_code(reader: &mut Reader<'_>, module: &Module, func: usize) -> Result<Vec<Instr>> {
+    let ty = &module.types[module.funcs[func].type_index as usize];
+    let num_locals = ty.params.len() + module.funcs[func].locals.len();
+    let total_globals = module.total_globals();
+    let mut code = Vec::new();
+    // Indices of the open Block / Loop / If instructions
+    let mut open: Vec<usize> = Vec::new();
+
+    loop {
+        let opcode = reader.byte()?;
+        let index = code.len();
+        let here = index as u32;
+        let instr = match opcode {
+            0x00 => Instr::Unreachable,
+            0x01 => Instr::Nop,
+            0x02 => {
+                let (params, results) = block_type(reader, module)?;
+                open.push(index);
+                Instr::Block {
+                    params,
+                    results,
+                    end: 0,
+                }
+            }
+            0x03 => {
+                let (params, _) = block_type(reader, module)?;
+                open.push(index);
+                Instr::Loop { params }
+            }
+            0x04 => {
+                let (params, results) = block_type(reader, module)?;
+                open.push(index);
+                Instr::If {
+                    params,
+                    results,
+                    else_: None,
+                    end: 0,
+                }
+            }
+            0x05 => {
+                let opener = open.last().copied();
+                match opener.and_then(|opener| code.get_mut(opener)) {
+                    Some(Instr::If { else_, .. }) if else_.is_none() => *else_ = Some(here),
+                    _ => return Err("else without a matching if".to_string()),
+                }
+                Instr::Else { end: 0 }
+            }
+            0x0B => {
+                let Some(opener) = open.pop() else {
+                    // The end of the function body
+                    code.push(Instr::End);
+                    break;
+                };
+                match &mut code[opener] {
+                    Instr::Block { end, .. } => *end = here,
+                    Instr::If { end, else_, .. } => {
+                        *end = here;
+                        if let Some(else_) = *else_
+                            && let Some(Instr::Else { end }) = code.get_mut(else_ as usize)
+                        {
+                            *end = here;
+                        }
+                    }
+                    _ => {}
+                }
+                Instr::End
+            }
+            0x0C | 0x0D => {
+                let depth = reader.u32()?;
+                if depth as usize > open.len() {
+                    return Err(format!("Unknown label {depth}"));
+                }
+                if opcode == 0x0C {
+                    Instr::Br(depth)
+                } else {
+                    Instr::BrIf(depth)
+                }
+            }
+            0x0E => {
+                let mut depths = Vec::new();
+                for _ in 0..reader.len()? {
+                    depths.push(reader.u32()?);
+                }
+                let default = reader.u32()?;
+                if depths
+                    .iter()
+                    .chain([&default])
+                    .any(|&depth| depth as usize > open.len())
+                {
+                    return Err("Unknown label in br_table".to_string());
+                }
+                Instr::BrTable(depths.into_boxed_slice(), default)
+            }
+            0x0F => Instr::Return,
+            // Callees are checked once every function is known
+            0x10 => Instr::Call(reader.u32()?),
+            0x11 => {
+                let type_index = reader.u32()?;
+                let table = reader.u32()?;
+                if type_index as usize >= module.types.len() {
+                    return Err(format!("Unknown type {type_index}"));
+                }
+                Instr::CallIndirect { type_index, table }
+            }
+            0x1A => Instr::Drop,
+            0x1B => Instr::Select,
+            0x1C => {
+                for _ in 0..reader.len()? {
+                    reader.val_type()?;
+                }
+                Instr::Select
+            }
+            0x20..=0x22 => {
+                let local = reader.u32()?;
+                if local as usize >= num_locals {
+                    return Err(format!("Unknown local {local}"));
+                }
+                match opcode {
+                    0x20 => Instr::LocalGet(local),
+                    0x21 => Instr::LocalSet(local),
+                    _ => Instr::LocalTee(local),
+                }
+            }
+            0x23 | 0x24 => {
+                let global = reader.u32()?;
+                if global >= total_globals {
+                    return Err(format!("Unknown global {global}"));
+                }
+                if opcode == 0x23 {
+                    Instr::GlobalGet(global)
+                } else {
+                    Instr::GlobalSet(global)
+                }
+            }
+            0x28..=0x3E => {
+                if !module.has_memory() {
+                    return Err("Memory instruction without a memory".to_string());
+                }
+                let _align = reader.u32()?;
+                let offset = reader.u32()?;
+                if opcode <= 0x35 {
+                    Instr::Load(opcode, offset)
+                } else {
+                    Instr::Store(opcode, offset)
+                }
+            }
+            0x3F | 0x40 => {
+                if reader.byte()? != 0 || !module.has_memory() {
+                    return Err("Unknown memory".to_string());
+                }
+                if opcode == 0x3F {
+                    Instr::MemorySize
+                } else {
+                    Instr::MemoryGrow
+                }
+            }
+            0x41 => Instr::Const(u64::from(reader.sleb(32)? as u32)),
+            0x42 => Instr::Const(reader.sleb(64)? as u64),
+            0x43 => Instr::Const(u64::from(reader.f32_bits()?)),
+            0x44 => Instr::Const(reader.f64_bits()?),
+            0x45..=0xC4 => Instr::Numeric(opcode),
+            0xFC => match reader.u32()? {
+                sub @ 0..=7 => Instr::TruncSat(sub as u8),
+                10 => {
+                    if reader.byte()? != 0 || reader.byte()? != 0 || !module.has_memory() {
+                        return Err("Unknown memory".to_string());
+                    }
+                    Instr::MemoryCopy
+                }
+                11 => {
+                    if reader.byte()? != 0 || !module.has_memory() {
+                        return Err("Unknown memory".to_string());
+                    }
+                    Instr::MemoryFill
+                }
+                sub => return Err(format!("Unsupported instruction 0xfc {sub}")),
+            },
+            opcode => return Err(format!("Unsupported instruction 0x{opcode:02x}")),
+        };
+        code.push(instr);
+    }
+
+    if !reader.is_empty() {
+        return Err("Function body has trailing bytes".to_string());
+    }
+    Ok(code)
+}
+
+/// Check indices that can only be checked once the whole module is read
+fn check_indices(module: &Module) -> Result<()> {
+    let total_funcs = module.total_funcs();
+    for func in &module.funcs {
+        for instr in &func.body {
+            match instr {
+                Instr::Call(callee) if *callee >= total_funcs => {
+                    return Err(format!("Unknown function {callee}"));
+                }
+                Instr::CallIndirect { table, .. } if *table != 0 || !module.has_table() => {
+                    return Err(format!("Unknown table {table}"));
+                }
+                _ => {}
+            }
+        }
+    }
+    for export in &module.exports {
+        let in_range = match export.kind {
+            ExportKind::Func => export.index < total_funcs,
+            ExportKind::Global => export.index < module.total_globals(),
+            ExportKind::Memory => export.index == 0 && module.has_memory(),
+            ExportKind::Table => export.index == 0 && module.has_table(),
+        };
+        if !in_range {
+            return Err(format!(
+                "Export \"{}\" refers to a missing item",
+                export.name
+            ));
+        }
+    }
+    if let Some(start) = module.start {
+        let ty = module
+            .func_type(start)
+            .ok_or_else(|| format!("Unknown start function {start}"))?;
+        if !ty.params.is_empty() || !ty.results.is_empty() {
+            return Err("The start function must take and return nothing".to_string());
+        }
+    }
+    for elem in &module.elems {
+        if elem.table != 0 || !module.has_table() {
+            return Err(format!("Unknown table {}", elem.table));
+        }
+        if elem.funcs.iter().flatten().any(|&func| func >= total_funcs) {
+            return Err("Element segment refers to a missing function".to_string());
+        }
+    }
+    if !module.datas.is_empty() && !module.has_memory() {
+        return Err("Data segment without a memory".to_string());
+    }
+    let memories = module.memories.len()
+        + module
+            .imports
+            .iter()
+            .filter(|import| matches!(import.kind, ImportKind::Memory(_)))
+            .count();
+    let tables = module.tables.len()
+        + module
+            .imports
+            .iter()
+            .filter(|import| matches!(import.kind, ImportKind::Table))
+            .count();
+    if memories > 1 || tables > 1 {
+        return Err("Multiple memories or tables are not supported".to_string());
+    }
+    for global in &module.globals {
+        if let ConstExpr::GlobalGet(index) = global.init
+            && index >= module.imported_globals
+        {
+            return Err("Global initializers may only read imported globals".to_string());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,390 @@
+// WebAssembly JavaScript interface
+// @ts-ignore: mdeno internal API
+const __internal = globalThis[Symbol.for("mdeno.internal")];
+const wasm = __internal.wasm;
+
+type ValueType = "i32" | "i64" | "f32" | "f64";
+type BufferSource = ArrayBuffer | ArrayBufferView;
+type ImportObject = Record<string, Record<string, unknown>>;
+
+interface ImportDescriptor {
+  module: string;
+  name: string;
+  kind: "function" | "table" | "memory" | "global";
+  type?: string;
+  mutable?: boolean;
+}
+
+interface ExportDescriptor {
+  name: string;
+  kind: "function" | "table" | "memory" | "global";
+  index: number;
+  type?: ValueType;
+  mutable?: boolean;
+}
+
+// Memory state shared with the interpreter
+interface MemoryState {
+  buffer: ArrayBuffer;
+  maximum: number | undefined;
+}
+
+class CompileError extends Error {
+  constructor(message?: string, options?: ErrorOptions) {
+    super(message, options);
+    this.name = "CompileError";
+  }
+}
+
+class LinkError extends Error {
+  constructor(message?: string, options?: ErrorOptions) {
+    super(message, options);
+    this.name = "LinkError";
+  }
+}
+
+class RuntimeError extends Error {
+  constructor(message?: string, options?: ErrorOptions) {
+    super(message, options);
+    this.name = "RuntimeError";
+  }
+}
+
+// Thrown from Rust for invalid modules and traps
+Object.assign(wasm, { CompileError, LinkError, RuntimeError });
+
+function toBytes(source: BufferSource): Uint8Array {
+  if (source instanceof ArrayBuffer) {
+    return new Uint8Array(source);
+  }
+  if (ArrayBuffer.isView(source)) {
+    return new Uint8Array(source.buffer, source.byteOffset, source.byteLength);
+  }
+  throw new TypeError("Argument must be an ArrayBuffer or a typed array");
+}
+
+function toPages(value: unknown, name: string): number {
+  const pages = Number(value);
+  if (!Number.isInteger(pages) || pages < 0 || pages > 0xffffffff) {
+    throw new TypeError(`${name} must be a non-negative integer`);
+  }
+  return pages;
+}
+
+function toValue(type: ValueType, value: unknown): number | bigint {
+  switch (type) {
+    case "i32":
+      return Number(value) | 0;
+    case "i64":
+      if (typeof value !== "bigint") {
+        throw new TypeError("Value of an i64 global must be a BigInt");
+      }
+      return BigInt.asIntN(64, value);
+    case "f32":
+      return Math.fround(Number(value));
+    default:
+      return Number(value);
+  }
+}
+
+let moduleHandle: (module: Module) => unknown;
+
+class Module {
+  #handle: unknown;
+
+  static {
+    moduleHandle = (module) => module.#handle;
+  }
+
+  constructor(bytes: BufferSource) {
+    this.#handle = wasm.compile(toBytes(bytes));
+  }
+
+  static imports(module: Module) {
+    return wasm.imports(moduleHandle(module)).map(
+      ({ module, name, kind }: ImportDescriptor) => ({ module, name, kind }),
+    );
+  }
+
+  static exports(module: Module) {
+    return wasm.exports(moduleHandle(module)).map(
+      ({ name, kind }: ExportDescriptor) => ({ name, kind }),
+    );
+  }
+
+  static customSections(module: Module, _sectionName: string): ArrayBuffer[] {
+    moduleHandle(module);
+    return [];
+  }
+
+  get [Symbol.toStringTag]() {
+    return "WebAssembly.Module";
+  }
+}
+
+let memoryState: (memory: Memory) => MemoryState;
+let wrapMemory: (state: MemoryState) => Memory;
+
+class Memory {
+  #state: MemoryState;
+
+  static {
+    memoryState = (memory) => memory.#state;
+    wrapMemory = (state) => {
+      const memory = new Memory({ initial: 0 });
+      memory.#state = state;
+      return memory;
+    };
+  }
+
+  constructor(descriptor: { initial: number; maximum?: number }) {
+    if (typeof descriptor !== "object" || descriptor === null) {
+      throw new TypeError("Memory descriptor must be an object");
+    }
+    const initial = toPages(descriptor.initial, "initial");
+    const maximum = descriptor.maximum === undefined
+      ? undefined
+      : toPages(descriptor.maximum, "maximum");
+    this.#state = wasm.newMemory(initial, maximum);
+  }
+
+  get buffer(): ArrayBuffer {
+    return this.#state.buffer;
+  }
+
+  grow(delta: number): number {
+    return wasm.memoryGrow(this.#state, toPages(delta, "delta"));
+  }
+
+  get [Symbol.toStringTag]() {
+    return "WebAssembly.Memory";
+  }
+}
+
+let bindGlobal: (
+  type: ValueType,
+  mutable: boolean,
+  instance: unknown,
+  index: number,
+) => Global;
+
+class Global {
+  #type: ValueType;
+  #mutable: boolean;
+  #value: number | bigint;
+  // Set for globals exported from an instance, which live in the instance
+  #instance: unknown;
+  #index = 0;
+
+  static {
+    bindGlobal = (type, mutable, instance, index) => {
+      const global = new Global({ value: type, mutable });
+      global.#instance = instance;
+      global.#index = index;
+      return global;
+    };
+  }
+
+  constructor(
+    descriptor: { value: ValueType; mutable?: boolean },
+    value?: unknown,
+  ) {
+    if (typeof descriptor !== "object" || descriptor === null) {
+      throw new TypeError("Global descriptor must be an object");
+    }
+    const type = descriptor.value;
+    if (!["i32", "i64", "f32", "f64"].includes(type)) {
+      throw new TypeError(`Unsupported global type "${type}"`);
+    }
+    this.#type = type;
+    this.#mutable = Boolean(descriptor.mutable);
+    if (value === undefined) {
+      this.#value = type === "i64" ? 0n : 0;
+    } else {
+      this.#value = toValue(type, value);
+    }
+  }
+
+  get value(): number | bigint {
+    if (this.#instance !== undefined) {
+      return wasm.getGlobal(this.#instance, this.#index);
+    }
+    return this.#value;
+  }
+
+  set value(value: unknown) {
+    if (!this.#mutable) {
+      throw new TypeError("Can't set the value of an immutable global");
+    }
+    if (this.#instance !== undefined) {
+      wasm.setGlobal(this.#instance, this.#index, value);
+    } else {
+      this.#value = toValue(this.#type, value);
+    }
+  }
+
+  valueOf(): number | bigint {
+    return this.value;
+  }
+
+  get [Symbol.toStringTag]() {
+    return "WebAssembly.Global";
+  }
+}
+
+class Instance {
+  readonly exports: Record<string, unknown>;
+
+  constructor(module: Module, importObject?: ImportObject) {
+    const handle = moduleHandle(module);
+    const descriptors: ImportDescriptor[] = wasm.imports(handle);
+    if (descriptors.length > 0 && typeof importObject !== "object") {
+      throw new TypeError("Imports argument must be present and an object");
+    }
+
+    // Functions in index order, imported global values, and the memory
+    const functions: ((...args: unknown[]) => unknown)[] = [];
+    const globals: unknown[] = [];
+    let memory: Memory | undefined;
+    for (const { module, name, kind, type, mutable } of descriptors) {
+      const namespace = importObject?.[module];
+      if (typeof namespace !== "object" || namespace === null) {
+        throw new TypeError(`Import module "${module}" must be an object`);
+      }
+      const value = namespace[name];
+      const label = `Import "${module}"."${name}"`;
+      switch (kind) {
+        case "function":
+          if (typeof value !== "function") {
+            throw new LinkError(`${label} must be a function`);
+          }
+          functions.push(value as (...args: unknown[]) => unknown);
+          break;
+        case "global":
+          if (value instanceof Global) {
+            globals.push(value.value);
+          } else if (mutable) {
+            throw new LinkError(`${label} must be a mutable WebAssembly.Global`);
+          } else if (
+            type === "i64" ? typeof value !== "bigint" : typeof value !== "number"
+          ) {
+            throw new LinkError(`${label} must be a ${type} value`);
+          } else {
+            globals.push(value);
+          }
+          break;
+        case "memory":
+          if (!(value instanceof Memory)) {
+            throw new LinkError(`${label} must be a WebAssembly.Memory`);
+          }
+          memory = value;
+          break;
+        default:
+          throw new LinkError(`${label}: importing tables is not supported`);
+      }
+    }
+
+    const { instance, memory: state } = wasm.instantiate(
+      handle,
+      functions,
+      globals,
+      memory && memoryState(memory),
+    );
+    memory ??= state && wrapMemory(state);
+
+    const exported = new Map<number, (...args: unknown[]) => unknown>();
+    const exports = Object.create(null);
+    for (const { name, kind, index, type, mutable } of wasm.exports(handle)) {
+      switch (kind) {
+        case "function":
+          if (index < functions.length) {
+            // Re-exported imports keep their identity
+            exports[name] = functions[index];
+            break;
+          }
+          if (!exported.has(index)) {
+            const func = (...args: unknown[]) =>
+              wasm.call(instance, functions, state, index, args);
+            Object.defineProperty(func, "name", { value: String(index) });
+            exported.set(index, func);
+          }
+          exports[name] = exported.get(index);
+          break;
+        case "memory":
+          exports[name] = memory;
+          break;
+        case "global":
+          exports[name] = bindGlobal(type!, mutable!, instance, index);
+          break;
+      }
+    }
+    this.exports = Object.freeze(exports);
+  }
+
+  get [Symbol.toStringTag]() {
+    return "WebAssembly.Instance";
+  }
+}
+
+function validate(bytes: BufferSource): boolean {
+  return wasm.validate(toBytes(bytes));
+}
+
+// deno-lint-ignore require-await
+async function compile(bytes: BufferSource): Promise<Module> {
+  return new Module(bytes);
+}
+
+function instantiate(
+  module: Module,
+  importObject?: ImportObject,
+): Promise<Instance>;
+function instantiate(
+  bytes: BufferSource,
+  importObject?: ImportObject,
+): Promise<{ module: Module; instance: Instance }>;
+// deno-lint-ignore require-await
+async function instantiate(
+  source: Module | BufferSource,
+  importObject?: ImportObject,
+) {
+  if (source instanceof Module) {
+    return new Instance(source, importObject);
+  }
+  const module = new Module(source);
+  return { module, instance: new Instance(module, importObject) };
+}
+
+async function compileStreaming(
+  source: Response | Promise<Response>,
+): Promise<Module> {
+  const response = await source;
+  return new Module(await response.arrayBuffer());
+}
+
+async function instantiateStreaming(
+  source: Response | Promise<Response>,
+  importObject?: ImportObject,
+): Promise<{ module: Module; instance: Instance }> {
+  const module = await compileStreaming(source);
+  return { module, instance: new Instance(module, importObject) };
+}
+
+const WebAssembly = {
+  Module,
+  Instance,
+  Memory,
+  Global,
+  CompileError,
+  LinkError,
+  RuntimeError,
+  validate,
+  compile,
+  instantiate,
+  compileStreaming,
+  instantiateStreaming,
+  [Symbol.toStringTag]: "WebAssembly",
+};
+
+// @ts-ignore: partial WebAssembly implementation
+globalThis.WebAssembly = WebAssembly;
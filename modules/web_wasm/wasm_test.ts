@@ -0,0 +1,210 @@
+// Modules are assembled by hand; every section here is under 128 bytes
+function section(id: number, ...bytes: number[]): number[] {
+  return [id, bytes.length, ...bytes];
+}
+
+function name(text: string): number[] {
+  return [text.length, ...Array.from(text, (char) => char.charCodeAt(0))];
+}
+
+// A function body without locals
+function body(...code: number[]): number[] {
+  return [code.length + 1, 0, ...code];
+}
+
+function wasmModule(...sections: number[][]): Uint8Array {
+  return new Uint8Array([0, 0x61, 0x73, 0x6d, 1, 0, 0, 0, ...sections.flat()]);
+}
+
+const I32 = 0x7f;
+const I64 = 0x7e;
+
+// (i32, i32) -> i32 exported as "add", "div" and "fib"
+const arithmetic = wasmModule(
+  section(1, 2, 0x60, 2, I32, I32, 1, I32, 0x60, 1, I32, 1, I32),
+  section(3, 3, 0, 0, 1),
+  section(7, 3, ...name("add"), 0, 0, ...name("div"), 0, 1, ...name("fib"), 0, 2),
+  section(
+    10,
+    3,
+    ...body(0x20, 0, 0x20, 1, 0x6a, 0x0b),
+    ...body(0x20, 0, 0x20, 1, 0x6d, 0x0b),
+    // if n < 2 then n else fib(n - 1) + fib(n - 2)
+    ...body(
+      0x20, 0, 0x41, 2, 0x48, 0x04, I32,
+      0x20, 0,
+      0x05,
+      0x20, 0, 0x41, 1, 0x6b, 0x10, 2,
+      0x20, 0, 0x41, 2, 0x6b, 0x10, 2,
+      0x6a,
+      0x0b,
+      0x0b,
+    ),
+  ),
+);
+
+// Imports env.log, and exports a memory, a mutable global and "bump", which
+// logs the global, increments it and returns it
+const stateful = wasmModule(
+  section(1, 2, 0x60, 1, I32, 0, 0x60, 0, 1, I32),
+  section(2, 1, ...name("env"), ...name("log"), 0, 0),
+  section(3, 1, 1),
+  section(5, 1, 0, 1),
+  section(6, 1, I32, 1, 0x41, 10, 0x0b),
+  section(
+    7,
+    3,
+    ...name("bump"), 0, 1,
+    ...name("mem"), 2, 0,
+    ...name("counter"), 3, 0,
+  ),
+  section(
+    10,
+    1,
+    ...body(
+      0x23, 0, 0x10, 0,
+      0x23, 0, 0x41, 1, 0x6a, 0x24, 0,
+      0x23, 0,
+      0x0b,
+    ),
+  ),
+  section(11, 1, 0, 0x41, 0, 0x0b, 2, 0x68, 0x69),
+);
+
+Deno.test("WebAssembly.instantiate runs exported functions", async () => {
+  const { module, instance } = await WebAssembly.instantiate(arithmetic);
+  if (!(module instanceof WebAssembly.Module)) throw new Error("module");
+  const { add, fib } = instance.exports as Record<
+    string,
+    (...args: unknown[]) => number
+  >;
+  if (add(2, 3) !== 5) throw new Error(`add: ${add(2, 3)}`);
+  if (add("4", 1) !== 5) throw new Error("arguments are coerced to i32");
+  if (add(0x7fffffff, 1) !== -0x80000000) throw new Error("i32 wraps");
+  if (fib(15) !== 610) throw new Error(`fib: ${fib(15)}`);
+
+  const again = await WebAssembly.instantiate(module);
+  if (!(again instanceof WebAssembly.Instance)) throw new Error("instance");
+});
+
+Deno.test("WebAssembly traps throw RuntimeError", async () => {
+  const { instance } = await WebAssembly.instantiate(arithmetic);
+  const div = instance.exports.div as (a: number, b: number) => number;
+  if (div(7, 2) !== 3) throw new Error("div");
+  try {
+    div(1, 0);
+    throw new Error("should have trapped");
+  } catch (error) {
+    if (!(error instanceof WebAssembly.RuntimeError)) throw error;
+    if (!(error as Error).message.includes("divide by zero")) throw error;
+  }
+});
+
+Deno.test("WebAssembly instances share imports, memory and globals", async () => {
+  const logged: number[] = [];
+  const { instance } = await WebAssembly.instantiate(stateful, {
+    env: { log: (value: number) => logged.push(value) },
+  });
+  const exports = instance.exports as {
+    bump: () => number;
+    mem: WebAssembly.Memory;
+    counter: WebAssembly.Global;
+  };
+  if (exports.bump() !== 11) throw new Error("bump");
+  if (logged.join() !== "10") throw new Error(`logged: ${logged}`);
+  if (exports.counter.value !== 11) throw new Error("counter");
+  exports.counter.value = 41;
+  if (exports.bump() !== 42) throw new Error("set counter");
+
+  const bytes = new Uint8Array(exports.mem.buffer, 0, 2);
+  if (new TextDecoder().decode(bytes) !== "hi") throw new Error("data");
+});
+
+Deno.test("WebAssembly reports bad modules and imports", async () => {
+  if (!WebAssembly.validate(arithmetic)) throw new Error("valid module");
+  if (WebAssembly.validate(new Uint8Array([0, 1, 2]))) {
+    throw new Error("invalid module");
+  }
+
+  // (i32, i32) -> i32 whose body adds an i64 to an i32
+  const mistyped = wasmModule(
+    section(1, 1, 0x60, 2, I32, I32, 1, I32),
+    section(3, 1, 0),
+    section(10, 1, ...body(0x20, 0, 0x42, 1, 0x6a, 0x0b)),
+  );
+  if (WebAssembly.validate(mistyped)) throw new Error("mistyped module");
+  try {
+    await WebAssembly.compile(mistyped);
+    throw new Error("should have failed to compile a mistyped module");
+  } catch (error) {
+    if (!(error instanceof WebAssembly.CompileError)) throw error;
+  }
+
+  try {
+    await WebAssembly.compile(new Uint8Array([0, 0x61, 0x73, 0x6d, 2, 0, 0, 0]));
+    throw new Error("should have failed to compile");
+  } catch (error) {
+    if (!(error instanceof WebAssembly.CompileError)) throw error;
+  }
+
+  try {
+    await WebAssembly.instantiate(stateful, { env: { log: 1 } });
+    throw new Error("should have failed to link");
+  } catch (error) {
+    if (!(error instanceof WebAssembly.LinkError)) throw error;
+  }
+
+  const module = new WebAssembly.Module(stateful);
+  const imports = WebAssembly.Module.imports(module);
+  if (JSON.stringify(imports) !== '[{"module":"env","name":"log","kind":"function"}]') {
+    throw new Error(`imports: ${JSON.stringify(imports)}`);
+  }
+  const exports = WebAssembly.Module.exports(module).map(({ kind }) => kind);
+  if (exports.join() !== "function,memory,global") {
+    throw new Error(`exports: ${exports}`);
+  }
+});
+
+Deno.test("WebAssembly passes i64 values as BigInt", async () => {
+  const double = wasmModule(
+    section(1, 1, 0x60, 1, I64, 1, I64),
+    section(3, 1, 0),
+    section(7, 1, ...name("double"), 0, 0),
+    section(10, 1, ...body(0x20, 0, 0x42, 2, 0x7e, 0x0b)),
+  );
+  const { instance } = await WebAssembly.instantiate(double);
+  const fn = instance.exports.double as (value: bigint) => bigint;
+  if (fn(2n ** 40n) !== 2n ** 41n) throw new Error("double");
+  if (fn(2n ** 63n - 1n) !== -2n) throw new Error("i64 wraps");
+  try {
+    fn(1 as unknown as bigint);
+    throw new Error("numbers aren't i64 values");
+  } catch (error) {
+    if (!(error instanceof TypeError)) throw error;
+  }
+});
+
+Deno.test("WebAssembly.Memory grows and detaches its old buffer", () => {
+  const memory = new WebAssembly.Memory({ initial: 1, maximum: 2 });
+  const buffer = memory.buffer;
+  new Uint8Array(buffer)[0] = 7;
+  if (memory.grow(1) !== 1) throw new Error("grow");
+  if (buffer.byteLength !== 0) throw new Error("old buffer is detached");
+  if (memory.buffer.byteLength !== 2 * 65536) throw new Error("new size");
+  if (new Uint8Array(memory.buffer)[0] !== 7) throw new Error("contents");
+  try {
+    memory.grow(1);
+    throw new Error("should not grow past the maximum");
+  } catch (error) {
+    if (!(error instanceof RangeError)) throw error;
+  }
+});
+
+Deno.test("WebAssembly.instantiateStreaming reads a Response", async () => {
+  const response = new Response(arithmetic, {
+    headers: { "content-type": "application/wasm" },
+  });
+  const { instance } = await WebAssembly.instantiateStreaming(response);
+  const add = instance.exports.add as (a: number, b: number) => number;
+  if (add(20, 22) !== 42) throw new Error("add");
+});